@@ -1,6 +1,6 @@
 //! This module defines `AssistCtx` -- the API surface that is exposed to assists.
 use hir::{db::HirDatabase, InFile, SourceAnalyzer};
-use ra_db::FileRange;
+use ra_db::{FileId, FileRange};
 use ra_fmt::{leading_indent, reindent};
 use ra_syntax::{
     algo::{self, find_covering_element, find_node_at_offset},
@@ -9,7 +9,7 @@ use ra_syntax::{
 };
 use ra_text_edit::TextEditBuilder;
 
-use crate::{AssistAction, AssistId, AssistLabel};
+use crate::{AssistAction, AssistConfig, AssistId, AssistLabel};
 
 #[derive(Clone, Debug)]
 pub(crate) enum Assist {
@@ -51,6 +51,7 @@ pub(crate) enum Assist {
 pub(crate) struct AssistCtx<'a, DB> {
     pub(crate) db: &'a DB,
     pub(crate) frange: FileRange,
+    pub(crate) config: AssistConfig,
     source_file: SourceFile,
     should_compute_edit: bool,
 }
@@ -60,6 +61,7 @@ impl<'a, DB> Clone for AssistCtx<'a, DB> {
         AssistCtx {
             db: self.db,
             frange: self.frange,
+            config: self.config,
             source_file: self.source_file.clone(),
             should_compute_edit: self.should_compute_edit,
         }
@@ -73,10 +75,20 @@ impl<'a, DB: HirDatabase> AssistCtx<'a, DB> {
     {
         let parse = db.parse(frange.file_id);
 
-        let ctx = AssistCtx { db, frange, source_file: parse.tree(), should_compute_edit };
+        let ctx = AssistCtx {
+            db,
+            frange,
+            config: AssistConfig::default(),
+            source_file: parse.tree(),
+            should_compute_edit,
+        };
         f(ctx)
     }
 
+    pub(crate) fn with_config(self, config: AssistConfig) -> AssistCtx<'a, DB> {
+        AssistCtx { config, ..self }
+    }
+
     pub(crate) fn add_assist(
         self,
         id: AssistId,
@@ -128,6 +140,7 @@ impl<'a, DB: HirDatabase> AssistCtx<'a, DB> {
 #[derive(Default)]
 pub(crate) struct AssistBuilder {
     edit: TextEditBuilder,
+    other_file_edits: Vec<(FileId, TextEditBuilder)>,
     cursor_position: Option<TextUnit>,
     target: Option<TextRange>,
 }
@@ -182,6 +195,19 @@ impl AssistBuilder {
         &mut self.edit
     }
 
+    /// Get access to the raw `TextEditBuilder` of a file other than the one
+    /// the assist was requested in.
+    pub(crate) fn other_file_edit_builder(&mut self, file_id: FileId) -> &mut TextEditBuilder {
+        let idx = match self.other_file_edits.iter().position(|(it, _)| *it == file_id) {
+            Some(it) => it,
+            None => {
+                self.other_file_edits.push((file_id, TextEditBuilder::default()));
+                self.other_file_edits.len() - 1
+            }
+        };
+        &mut self.other_file_edits[idx].1
+    }
+
     pub(crate) fn replace_ast<N: AstNode>(&mut self, old: N, new: N) {
         algo::diff(old.syntax(), new.syntax()).into_text_edit(&mut self.edit)
     }
@@ -189,6 +215,11 @@ impl AssistBuilder {
     fn build(self) -> AssistAction {
         AssistAction {
             edit: self.edit.finish(),
+            other_file_edits: self
+                .other_file_edits
+                .into_iter()
+                .map(|(file_id, edit)| (file_id, edit.finish()))
+                .collect(),
             cursor_position: self.cursor_position,
             target: self.target,
         }
@@ -4,7 +4,7 @@ use ra_syntax::{
     SmolStr,
 };
 
-use crate::{Assist, AssistCtx, AssistId};
+use crate::{Assist, AssistConfig, AssistCtx, AssistId};
 
 #[derive(PartialEq)]
 enum AddMissingImplMembersMode {
@@ -36,7 +36,7 @@ enum AddMissingImplMembersMode {
 // }
 //
 // impl T for () {
-//     fn foo(&self) { unimplemented!() }
+//     fn foo(&self) { todo!() }
 //
 // }
 // ```
@@ -99,11 +99,52 @@ fn add_missing_impl_members_inner(
     let impl_node = ctx.find_node_at_offset::<ast::ImplBlock>()?;
     let impl_item_list = impl_node.item_list()?;
 
-    let trait_def = {
-        let analyzer = ctx.source_analyzer(impl_node.syntax(), None);
+    let analyzer = ctx.source_analyzer(impl_node.syntax(), None);
+    let missing_items = missing_items(ctx.db, &analyzer, &impl_node, mode)?;
+    if missing_items.is_empty() {
+        return None;
+    }
 
-        resolve_target_trait_def(ctx.db, &analyzer, &impl_node)?
-    };
+    let db = ctx.db;
+    let config = ctx.config;
+    ctx.add_assist(AssistId(assist_id), label, |edit| {
+        let n_existing_items = impl_item_list.impl_items().count();
+        let items = missing_items.into_iter().map(|it| stub(db, &analyzer, &config, it));
+        let new_impl_item_list = impl_item_list.append_items(items);
+        let cursor_position = {
+            let first_new_item = new_impl_item_list.impl_items().nth(n_existing_items).unwrap();
+            first_new_item.syntax().text_range().start()
+        };
+
+        edit.replace_ast(impl_item_list, new_impl_item_list);
+        edit.set_cursor(cursor_position);
+    })
+}
+
+/// Renders stubs for the items of the implemented trait which are missing
+/// from `impl_block`, skipping the methods with a default body. This is the
+/// text `add_missing_impl_members` would insert, for use in completion.
+pub fn missing_impl_members_stubs(
+    db: &impl HirDatabase,
+    analyzer: &hir::SourceAnalyzer,
+    config: &AssistConfig,
+    impl_block: &ast::ImplBlock,
+) -> Vec<String> {
+    missing_items(db, analyzer, impl_block, AddMissingImplMembersMode::NoDefaultMethods)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|it| stub(db, analyzer, config, it).syntax().to_string())
+        .collect()
+}
+
+fn missing_items(
+    db: &impl HirDatabase,
+    analyzer: &hir::SourceAnalyzer,
+    impl_node: &ast::ImplBlock,
+    mode: AddMissingImplMembersMode,
+) -> Option<Vec<ast::ImplItem>> {
+    let impl_item_list = impl_node.item_list()?;
+    let trait_def = resolve_target_trait_def(db, analyzer, impl_node)?;
 
     let def_name = |item: &ast::ImplItem| -> Option<SmolStr> {
         match item {
@@ -117,7 +158,7 @@ fn add_missing_impl_members_inner(
     let trait_items = trait_def.item_list()?.impl_items();
     let impl_items = impl_item_list.impl_items().collect::<Vec<_>>();
 
-    let missing_items: Vec<_> = trait_items
+    let missing_items = trait_items
         .filter(|t| def_name(t).is_some())
         .filter(|t| match t {
             ast::ImplItem::FnDef(def) => match mode {
@@ -128,38 +169,64 @@ fn add_missing_impl_members_inner(
         })
         .filter(|t| impl_items.iter().all(|i| def_name(i) != def_name(t)))
         .collect();
-    if missing_items.is_empty() {
-        return None;
-    }
-
-    ctx.add_assist(AssistId(assist_id), label, |edit| {
-        let n_existing_items = impl_item_list.impl_items().count();
-        let items = missing_items
-            .into_iter()
-            .map(|it| match it {
-                ast::ImplItem::FnDef(def) => ast::ImplItem::FnDef(add_body(def)),
-                _ => it,
-            })
-            .map(|it| edit::strip_attrs_and_docs(&it));
-        let new_impl_item_list = impl_item_list.append_items(items);
-        let cursor_position = {
-            let first_new_item = new_impl_item_list.impl_items().nth(n_existing_items).unwrap();
-            first_new_item.syntax().text_range().start()
-        };
+    Some(missing_items)
+}
 
-        edit.replace_ast(impl_item_list, new_impl_item_list);
-        edit.set_cursor(cursor_position);
-    })
+fn stub(
+    db: &impl HirDatabase,
+    analyzer: &hir::SourceAnalyzer,
+    config: &AssistConfig,
+    item: ast::ImplItem,
+) -> ast::ImplItem {
+    let item = match item {
+        ast::ImplItem::FnDef(def) => ast::ImplItem::FnDef(add_body(db, analyzer, config, def)),
+        it => it,
+    };
+    edit::strip_attrs_and_docs(&item)
 }
 
-fn add_body(fn_def: ast::FnDef) -> ast::FnDef {
+fn add_body(
+    db: &impl HirDatabase,
+    analyzer: &hir::SourceAnalyzer,
+    config: &AssistConfig,
+    fn_def: ast::FnDef,
+) -> ast::FnDef {
     if fn_def.body().is_none() {
-        fn_def.with_body(make::block_from_expr(make::expr_unimplemented()))
+        let body = if config.fill_default_bodies {
+            expr_fill_default(db, analyzer, &fn_def)
+        } else {
+            make::expr_todo()
+        };
+        fn_def.with_body(make::block_from_expr(body))
     } else {
         fn_def
     }
 }
 
+/// Picks the stub body for a method: `()` for unit returns, `Default::default()`
+/// if the return type is known to implement `Default` and `todo!()` otherwise.
+fn expr_fill_default(
+    db: &impl HirDatabase,
+    analyzer: &hir::SourceAnalyzer,
+    fn_def: &ast::FnDef,
+) -> ast::Expr {
+    let ret_type = match fn_def.ret_type().and_then(|it| it.type_ref()) {
+        Some(it) => it,
+        None => return make::expr_unit(),
+    };
+    if let ast::TypeRef::TupleType(tuple) = &ret_type {
+        if tuple.fields().next().is_none() {
+            return make::expr_unit();
+        }
+    }
+    match analyzer.resolve_type(db, &ret_type) {
+        Some(ty) if !ty.contains_unknown() && analyzer.impls_default(db, ty) => {
+            make::expr_default()
+        }
+        _ => make::expr_todo(),
+    }
+}
+
 /// Given an `ast::ImplBlock`, resolves the target trait (the one being
 /// implemented) to a `ast::TraitDef`.
 fn resolve_target_trait_def(
@@ -182,7 +249,13 @@ fn resolve_target_trait_def(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::helpers::{check_assist, check_assist_not_applicable};
+    use crate::helpers::{check_assist, check_assist_in_fixture, check_assist_not_applicable};
+
+    fn add_missing_impl_members_filling_defaults(
+        ctx: AssistCtx<crate::test_db::TestDB>,
+    ) -> Option<Assist> {
+        add_missing_impl_members(ctx.with_config(AssistConfig { fill_default_bodies: true }))
+    }
 
     #[test]
     fn test_add_missing_impl_members() {
@@ -222,8 +295,8 @@ impl Foo for S {
     fn bar(&self) {}
     <|>type Output;
     const CONST: usize = 42;
-    fn foo(&self) { unimplemented!() }
-    fn baz(&self) { unimplemented!() }
+    fn foo(&self) { todo!() }
+    fn baz(&self) { todo!() }
 
 }",
         );
@@ -257,7 +330,7 @@ struct S;
 
 impl Foo for S {
     fn bar(&self) {}
-    <|>fn foo(&self) { unimplemented!() }
+    <|>fn foo(&self) { todo!() }
 
 }",
         );
@@ -275,7 +348,7 @@ impl Foo for S { <|> }",
 trait Foo { fn foo(&self); }
 struct S;
 impl Foo for S {
-    <|>fn foo(&self) { unimplemented!() }
+    <|>fn foo(&self) { todo!() }
 }",
         );
     }
@@ -292,7 +365,7 @@ impl Foo for S {}<|>",
 trait Foo { fn foo(&self); }
 struct S;
 impl Foo for S {
-    <|>fn foo(&self) { unimplemented!() }
+    <|>fn foo(&self) { todo!() }
 }",
         )
     }
@@ -349,7 +422,7 @@ trait Foo {
 struct S;
 impl Foo for S {
     <|>type Output;
-    fn foo(&self) { unimplemented!() }
+    fn foo(&self) { todo!() }
 }"#,
         )
     }
@@ -384,4 +457,84 @@ impl Foo for S {
 }",
         )
     }
+
+    #[test]
+    fn test_fill_default_bodies() {
+        check_assist_in_fixture(
+            add_missing_impl_members_filling_defaults,
+            r#"
+//- /main.rs crate:main deps:std
+struct Unknown;
+trait Foo {
+    fn unit(&self) -> ();
+    fn default(&self) -> u32;
+    fn not_default(&self) -> Unknown;
+}
+impl Foo for () { <|> }
+
+//- /std/lib.rs crate:std
+pub mod default {
+    pub trait Default {
+        fn default() -> Self;
+    }
+    impl Default for u32 {
+        fn default() -> u32 { 0 }
+    }
+}
+"#,
+            r#"struct Unknown;
+trait Foo {
+    fn unit(&self) -> ();
+    fn default(&self) -> u32;
+    fn not_default(&self) -> Unknown;
+}
+impl Foo for () {
+    <|>fn unit(&self) -> () { () }
+    fn default(&self) -> u32 { Default::default() }
+    fn not_default(&self) -> Unknown { todo!() }
+}
+
+"#,
+        )
+    }
+
+    #[test]
+    fn test_fill_default_bodies_is_opt_in() {
+        check_assist_in_fixture(
+            add_missing_impl_members,
+            r#"
+//- /main.rs crate:main deps:std
+struct Unknown;
+trait Foo {
+    fn unit(&self) -> ();
+    fn default(&self) -> u32;
+    fn not_default(&self) -> Unknown;
+}
+impl Foo for () { <|> }
+
+//- /std/lib.rs crate:std
+pub mod default {
+    pub trait Default {
+        fn default() -> Self;
+    }
+    impl Default for u32 {
+        fn default() -> u32 { 0 }
+    }
+}
+"#,
+            r#"struct Unknown;
+trait Foo {
+    fn unit(&self) -> ();
+    fn default(&self) -> u32;
+    fn not_default(&self) -> Unknown;
+}
+impl Foo for () {
+    <|>fn unit(&self) -> () { todo!() }
+    fn default(&self) -> u32 { todo!() }
+    fn not_default(&self) -> Unknown { todo!() }
+}
+
+"#,
+        )
+    }
 }
@@ -0,0 +1,302 @@
+use std::iter;
+
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, make, AstNode, NameOwner},
+    Direction,
+    SyntaxKind::WHITESPACE,
+    T,
+};
+
+use crate::{assists::fill_match_arms::resolve_enum_def, Assist, AssistCtx, AssistId};
+
+// Assist: add_missing_match_arms
+//
+// Adds the arms for the variants missing from a `match` expression which
+// already has some arms.
+//
+// ```
+// enum Action { Move { distance: u32 }, Jump(u32), Stop }
+//
+// fn handle(action: Action) {
+//     match action {
+//         Action::Stop => (),<|>
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Action { Move { distance: u32 }, Jump(u32), Stop }
+//
+// fn handle(action: Action) {
+//     match action {
+//         Action::Stop => (),
+//         Action::Move { .. } => todo!(),
+//         Action::Jump(_) => todo!(),
+//     }
+// }
+// ```
+pub(crate) fn add_missing_match_arms(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let match_expr = ctx.find_node_at_offset::<ast::MatchExpr>()?;
+    let match_arm_list = match_expr.match_arm_list()?;
+    let arms: Vec<ast::MatchArm> = match_arm_list.arms().collect();
+    let last_arm = arms.last()?.clone();
+
+    let expr = match_expr.expr()?;
+    let (enum_def, needs_wildcard) = {
+        let analyzer = ctx.source_analyzer(expr.syntax(), None);
+        resolve_enum_def(ctx.db, &analyzer, &expr)?
+    };
+    let enum_name = enum_def.name()?;
+    let covered: Vec<String> = arms
+        .iter()
+        .filter(|arm| arm.guard().is_none())
+        .flat_map(|arm| arm.pats())
+        .filter_map(|pat| variant_name(&pat))
+        .collect();
+    let catch_all = if is_catch_all(&last_arm) { Some(last_arm.clone()) } else { None };
+
+    let missing_pats: Vec<String> = enum_def
+        .variant_list()?
+        .variants()
+        .filter(|var| var.name().map_or(false, |it| !covered.contains(&it.text().to_string())))
+        .filter_map(|var| build_pat(&enum_name, var))
+        .chain(if needs_wildcard && catch_all.is_none() { Some("_".to_string()) } else { None })
+        .collect();
+    if missing_pats.is_empty() {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("add_missing_match_arms"), "add missing match arms", |edit| {
+        let new_arms =
+            missing_pats.iter().map(|pat| format!("{} => {},", pat, make::expr_todo().syntax()));
+        match catch_all {
+            Some(catch_all) => {
+                let indent = indent_of(&catch_all);
+                let text: String = new_arms.map(|arm| format!("{}\n{}", arm, indent)).collect();
+                edit.insert(catch_all.syntax().text_range().start(), text);
+            }
+            None => {
+                let indent = indent_of(&last_arm);
+                let comma = last_arm
+                    .syntax()
+                    .siblings_with_tokens(Direction::Next)
+                    .skip(1)
+                    .find(|it| it.kind() != WHITESPACE)
+                    .filter(|it| it.kind() == T![,]);
+                // arms with a block body don't need a comma
+                let (offset, comma) = match comma {
+                    Some(comma) => (comma.text_range().end(), ""),
+                    None => match last_arm.expr() {
+                        Some(ast::Expr::BlockExpr(_)) => (last_arm.syntax().text_range().end(), ""),
+                        _ => (last_arm.syntax().text_range().end(), ","),
+                    },
+                };
+                let text: String = iter::once(comma.to_string())
+                    .chain(new_arms.map(|arm| format!("\n{}{}", indent, arm)))
+                    .collect();
+                edit.insert(offset, text);
+            }
+        }
+        edit.target(match_expr.syntax().text_range());
+    })
+}
+
+/// The name of the variant matched by `pat`, if it only matches one variant.
+fn variant_name(pat: &ast::Pat) -> Option<String> {
+    let path = match pat {
+        ast::Pat::PathPat(it) => it.path()?,
+        ast::Pat::TupleStructPat(it) => it.path()?,
+        ast::Pat::RecordPat(it) => it.path()?,
+        // variants imported with `use Enum::*;` are matched with a bare name
+        ast::Pat::BindPat(it) if it.pat().is_none() => {
+            return it.name().map(|it| it.text().to_string())
+        }
+        _ => return None,
+    };
+    path.segment()?.name_ref().map(|it| it.text().to_string())
+}
+
+fn is_catch_all(arm: &ast::MatchArm) -> bool {
+    arm.guard().is_none()
+        && arm.pats().any(|pat| match pat {
+            ast::Pat::PlaceholderPat(..) => true,
+            _ => false,
+        })
+}
+
+fn indent_of(arm: &ast::MatchArm) -> String {
+    "    ".repeat(IndentLevel::from_node(arm.syntax()).0 as usize)
+}
+
+fn build_pat(enum_name: &ast::Name, var: ast::EnumVariant) -> Option<String> {
+    let path = format!("{}::{}", enum_name.text(), var.name()?.text());
+    let pat = match var.kind() {
+        ast::StructKind::Tuple(field_list) => {
+            let fields = vec!["_"; field_list.fields().count()].join(", ");
+            format!("{}({})", path, fields)
+        }
+        ast::StructKind::Record(_) => format!("{} {{ .. }}", path),
+        ast::StructKind::Unit => path,
+    };
+    Some(pat)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_missing_arms_at_the_end() {
+        check_assist(
+            add_missing_match_arms,
+            "
+enum E { A, B(u32, u32), C { x: u32 } }
+fn main() {
+    match E::A {
+        E::B(..) => (),<|>
+    }
+}",
+            "
+enum E { A, B(u32, u32), C { x: u32 } }
+fn main() {
+    match E::A {
+        E::B(..) => (),<|>
+        E::A => todo!(),
+        E::C { .. } => todo!(),
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn add_missing_arms_after_arm_without_comma() {
+        check_assist(
+            add_missing_match_arms,
+            "
+enum E { A, B }
+fn main() {
+    match E::A {
+        E::A => ()<|>
+    }
+}",
+            "
+enum E { A, B }
+fn main() {
+    match E::A {
+        E::A => ()<|>,
+        E::B => todo!(),
+    }
+}",
+        );
+        check_assist(
+            add_missing_match_arms,
+            "
+enum E { A, B }
+fn main() {
+    match E::A {
+        E::A => {}<|>
+    }
+}",
+            "
+enum E { A, B }
+fn main() {
+    match E::A {
+        E::A => {}<|>
+        E::B => todo!(),
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn add_missing_arms_before_catch_all() {
+        check_assist(
+            add_missing_match_arms,
+            "
+enum E { A, B, C }
+fn main() {
+    match E::A {
+        E::A | E::C => (),
+        <|>_ => (),
+    }
+}",
+            "
+enum E { A, B, C }
+fn main() {
+    match E::A {
+        E::A | E::C => (),
+        <|>E::B => todo!(),
+        _ => (),
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn add_missing_arms_for_guarded_variants() {
+        check_assist(
+            add_missing_match_arms,
+            "
+enum E { A(bool), B }
+fn main() {
+    match E::B {
+        E::A(b) if b => (),<|>
+        E::B => (),
+    }
+}",
+            "
+enum E { A(bool), B }
+fn main() {
+    match E::B {
+        E::A(b) if b => (),<|>
+        E::B => (),
+        E::A(_) => todo!(),
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn add_missing_arms_target() {
+        check_assist_target(
+            add_missing_match_arms,
+            "
+enum E { A, B }
+fn main() {
+    match E::A {
+        E::A => (),<|>
+    }
+}",
+            "match E::A {
+        E::A => (),
+    }",
+        );
+    }
+
+    #[test]
+    fn add_missing_arms_not_applicable() {
+        // all the variants are covered
+        check_assist_not_applicable(
+            add_missing_match_arms,
+            "
+enum E { A, B }
+fn main() {
+    match E::A {
+        E::A => (),<|>
+        E::B => (),
+    }
+}",
+        );
+        // empty matches are handled by `fill_match_arms`
+        check_assist_not_applicable(
+            add_missing_match_arms,
+            "
+enum E { A, B }
+fn main() {
+    match E::A {<|>}
+}",
+        );
+    }
+}
@@ -9,7 +9,7 @@ use ra_syntax::{
 };
 use std::fmt::Write;
 
-use crate::{Assist, AssistCtx, AssistId};
+use crate::{assist_ctx::AssistBuilder, Assist, AssistCtx, AssistId};
 
 // Assist: add_new
 //
@@ -34,65 +34,91 @@ use crate::{Assist, AssistCtx, AssistId};
 pub(crate) fn add_new(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
     let strukt = ctx.find_node_at_offset::<ast::StructDef>()?;
 
-    // We want to only apply this to non-union structs with named fields
-    let field_list = match strukt.kind() {
-        StructKind::Record(named) => named,
-        _ => return None,
+    // We want to only apply this to non-union structs with fields
+    let (params, body) = match strukt.kind() {
+        StructKind::Record(named) => {
+            let params = join(named.fields().filter_map(|f| {
+                Some(format!(
+                    "{}: {}",
+                    f.name()?.syntax().text(),
+                    f.ascribed_type()?.syntax().text()
+                ))
+            }))
+            .separator(", ")
+            .to_string();
+            let fields = join(named.fields().filter_map(|f| Some(f.name()?.syntax().text())))
+                .separator(", ")
+                .surround_with(" ", " ")
+                .to_string();
+            (params, format!("Self {{{}}}", fields))
+        }
+        StructKind::Tuple(tuple) => {
+            let types = tuple.fields().filter_map(|f| f.type_ref()).collect::<Vec<_>>();
+            let params =
+                join(types.iter().enumerate().map(|(i, ty)| format!("arg{}: {}", i, ty.syntax())))
+                    .separator(", ")
+                    .to_string();
+            let args = join((0..types.len()).map(|i| format!("arg{}", i)))
+                .separator(", ")
+                .surround_with("(", ")")
+                .to_string();
+            (params, format!("Self{}", args))
+        }
+        StructKind::Unit => return None,
     };
 
     // Return early if we've found an existing new fn
-    let impl_block = find_struct_impl(&ctx, &strukt)?;
+    let impl_block = find_struct_impl(&ctx, &strukt, "new")?;
 
     ctx.add_assist(AssistId("add_new"), "add new fn", |edit| {
         edit.target(strukt.syntax().text_range());
 
-        let mut buf = String::with_capacity(512);
-
-        if impl_block.is_some() {
-            buf.push('\n');
-        }
-
+        let mut method = String::with_capacity(512);
         let vis = strukt.visibility().map(|v| format!("{} ", v.syntax()));
         let vis = vis.as_ref().map(String::as_str).unwrap_or("");
-        write!(&mut buf, "    {}fn new(", vis).unwrap();
+        write!(&mut method, "{}fn new({}) -> Self {{ {} }}", vis, params, body).unwrap();
+
+        insert_method(edit, &strukt, impl_block, &method);
+    })
+}
 
-        join(field_list.fields().filter_map(|f| {
-            Some(format!("{}: {}", f.name()?.syntax().text(), f.ascribed_type()?.syntax().text()))
-        }))
-        .separator(", ")
-        .to_buf(&mut buf);
+/// Adds `method` at the start of `impl_block`, or of a new impl block placed
+/// after the struct if there's none, and puts the cursor at the end of it.
+pub(super) fn insert_method(
+    edit: &mut AssistBuilder,
+    strukt: &ast::StructDef,
+    impl_block: Option<ast::ImplBlock>,
+    method: &str,
+) {
+    let mut buf = String::with_capacity(method.len() + 8);
 
-        buf.push_str(") -> Self { Self {");
+    if impl_block.is_some() {
+        buf.push('\n');
+    }
+    buf.push_str("    ");
+    buf.push_str(method);
 
-        join(field_list.fields().filter_map(|f| Some(f.name()?.syntax().text())))
-            .separator(", ")
-            .surround_with(" ", " ")
-            .to_buf(&mut buf);
-
-        buf.push_str("} }");
-
-        let (start_offset, end_offset) = impl_block
-            .and_then(|impl_block| {
-                buf.push('\n');
-                let start = impl_block
-                    .syntax()
-                    .descendants_with_tokens()
-                    .find(|t| t.kind() == T!['{'])?
-                    .text_range()
-                    .end();
-
-                Some((start, TextUnit::from_usize(1)))
-            })
-            .unwrap_or_else(|| {
-                buf = generate_impl_text(&strukt, &buf);
-                let start = strukt.syntax().text_range().end();
-
-                (start, TextUnit::from_usize(3))
-            });
-
-        edit.set_cursor(start_offset + TextUnit::of_str(&buf) - end_offset);
-        edit.insert(start_offset, buf);
-    })
+    let (start_offset, end_offset) = impl_block
+        .and_then(|impl_block| {
+            buf.push('\n');
+            let start = impl_block
+                .syntax()
+                .descendants_with_tokens()
+                .find(|t| t.kind() == T!['{'])?
+                .text_range()
+                .end();
+
+            Some((start, TextUnit::from_usize(1)))
+        })
+        .unwrap_or_else(|| {
+            buf = generate_impl_text(strukt, &buf);
+            let start = strukt.syntax().text_range().end();
+
+            (start, TextUnit::from_usize(3))
+        });
+
+    edit.set_cursor(start_offset + TextUnit::of_str(&buf) - end_offset);
+    edit.insert(start_offset, buf);
 }
 
 // Generates the surrounding `impl Type { <code> }` including type and lifetime
@@ -124,13 +150,14 @@ fn generate_impl_text(strukt: &ast::StructDef, code: &str) -> String {
 // Uses a syntax-driven approach to find any impl blocks for the struct that
 // exist within the module/file
 //
-// Returns `None` if we've found an existing `new` fn
+// Returns `None` if we've found an existing fn named `fn_name`
 //
-// FIXME: change the new fn checking to a more semantic approach when that's more
+// FIXME: change the fn checking to a more semantic approach when that's more
 // viable (e.g. we process proc macros, etc)
-fn find_struct_impl(
+pub(super) fn find_struct_impl(
     ctx: &AssistCtx<impl HirDatabase>,
     strukt: &ast::StructDef,
+    fn_name: &str,
 ) -> Option<Option<ast::ImplBlock>> {
     let db = ctx.db;
     let module = strukt.syntax().ancestors().find(|node| {
@@ -157,7 +184,7 @@ fn find_struct_impl(
     });
 
     if let Some(ref impl_blk) = block {
-        if has_new_fn(impl_blk) {
+        if has_fn(impl_blk, fn_name) {
             return None;
         }
     }
@@ -165,12 +192,12 @@ fn find_struct_impl(
     Some(block)
 }
 
-fn has_new_fn(imp: &ast::ImplBlock) -> bool {
+fn has_fn(imp: &ast::ImplBlock, fn_name: &str) -> bool {
     if let Some(il) = imp.item_list() {
         for item in il.impl_items() {
             if let ast::ImplItem::FnDef(f) = item {
                 if let Some(name) = f.name() {
-                    if name.text().eq_ignore_ascii_case("new") {
+                    if name.text().eq_ignore_ascii_case(fn_name) {
                         return true;
                     }
                 }
@@ -333,6 +360,36 @@ impl Foo {
         );
     }
 
+    #[test]
+    #[rustfmt::skip]
+    fn test_add_new_tuple_struct() {
+        check_assist(
+            add_new,
+"struct Foo(<|>);",
+"struct Foo();
+
+impl Foo {
+    fn new() -> Self { Self() }<|>
+}
+",
+        );
+        check_assist(
+            add_new,
+"pub struct Foo(String, Vec<i32><|>);",
+"pub struct Foo(String, Vec<i32>);
+
+impl Foo {
+    pub fn new(arg0: String, arg1: Vec<i32>) -> Self { Self(arg0, arg1) }<|>
+}
+",
+        );
+    }
+
+    #[test]
+    fn add_new_not_applicable_for_unit_struct() {
+        check_assist_not_applicable(add_new, "struct Foo<|>;");
+    }
+
     #[test]
     fn add_new_not_applicable_if_fn_exists() {
         check_assist_not_applicable(
@@ -9,8 +9,9 @@ use crate::{Assist, AssistCtx, AssistId};
 // Apply [De Morgan's law](https://en.wikipedia.org/wiki/De_Morgan%27s_laws).
 // This transforms expressions of the form `!l || !r` into `!(l && r)`.
 // This also works with `&&`. This assist can only be applied with the cursor
-// on either `||` or `&&`, with both operands being a negation of some kind.
-// This means something of the form `!x` or `x != y`.
+// on either `||` or `&&`, with both operands having a known inverse.
+// This means something of the form `!x`, `x != y`, `x == y` or a call of
+// `is_some()`, `is_none()`, `is_ok()` or `is_err()`.
 //
 // ```
 // fn main() {
@@ -77,6 +78,26 @@ mod tests {
         check_assist(apply_demorgan, "fn f() { x != x ||<|> !x }", "fn f() { !(x == x &&<|> x) }")
     }
 
+    #[test]
+    fn demorgan_adds_inequality() {
+        check_assist(apply_demorgan, "fn f() { x == y &&<|> !z }", "fn f() { !(x != y ||<|> z) }")
+    }
+
+    #[test]
+    fn demorgan_swaps_inverse_methods() {
+        check_assist(
+            apply_demorgan,
+            "fn f() { a.is_some() ||<|> b.is_err() }",
+            "fn f() { !(a.is_none() &&<|> b.is_ok()) }",
+        )
+    }
+
+    #[test]
+    fn demorgan_doesnt_apply_to_methods_without_inverse() {
+        check_assist_not_applicable(apply_demorgan, "fn f() { a.is_empty() ||<|> !b }");
+        check_assist_not_applicable(apply_demorgan, "fn f() { a.is_some::<u32>() ||<|> !b }")
+    }
+
     #[test]
     fn demorgan_doesnt_apply_with_cursor_not_on_op() {
         check_assist_not_applicable(apply_demorgan, "fn f() { <|> !x || !x }")
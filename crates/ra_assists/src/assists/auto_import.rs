@@ -0,0 +1,365 @@
+use std::collections::VecDeque;
+
+use hir::{db::HirDatabase, InFile, Module, ModuleDef, ModuleSource, PathKind, ScopeDef};
+use ra_db::SourceDatabaseExt;
+use ra_syntax::{
+    ast::{self, AstNode},
+    SmolStr, SyntaxNode,
+};
+use ra_text_edit::TextEditBuilder;
+
+use crate::{assists::add_import::auto_import_text_edit, Assist, AssistCtx, AssistId};
+
+/// Finds the definitions with a given name in all the crates, for the
+/// `auto_import` assist. The index of all the symbols lives above this crate.
+pub trait ImportsLocator {
+    fn find_imports(&mut self, name_to_import: &str) -> Vec<ModuleDef>;
+}
+
+/// Offers to import the unresolved name under the cursor, once for each of the
+/// items of that name which can be imported, like `use std::collections::HashMap`
+/// for `HashMap`.
+///
+/// The items of the standard library come first, then the ones of the
+/// workspace, then the ones of the other dependencies.
+pub(crate) fn auto_import<F: ImportsLocator>(
+    ctx: AssistCtx<impl HirDatabase>,
+    imports_locator: &mut F,
+) -> Option<Vec<Assist>> {
+    let mut path = ctx.find_node_at_offset::<ast::Path>()?;
+    // only the first segment of a path can be imported
+    while let Some(qualifier) = path.qualifier() {
+        path = qualifier;
+    }
+    if path.syntax().ancestors().find_map(ast::UseItem::cast).is_some() {
+        return None;
+    }
+    let name_ref = path.segment()?.name_ref()?;
+    if hir::Path::from_ast(path.clone())?.kind() != &PathKind::Plain {
+        return None;
+    }
+    let analyzer = ctx.source_analyzer(path.syntax(), None);
+    if analyzer.resolve_path(ctx.db, &path).is_some() {
+        return None;
+    }
+
+    let file_id = ctx.frange.file_id;
+    let module_src =
+        ModuleSource::from_child_node(ctx.db, InFile::new(file_id.into(), path.syntax()));
+    let module = Module::from_definition(ctx.db, InFile::new(file_id.into(), module_src))?;
+
+    let mut imports: Vec<(u8, String, Vec<SmolStr>)> = imports_locator
+        .find_imports(&name_ref.text())
+        .into_iter()
+        .filter_map(|def| {
+            let import = find_import_path(ctx.db, module, def)?;
+            Some((rank(ctx.db, def, &import), import.join("::"), import))
+        })
+        .collect();
+    imports.sort();
+    imports.dedup_by(|a, b| a.1 == b.1);
+    if imports.is_empty() {
+        return None;
+    }
+
+    let container = path.syntax().ancestors().find_map(|it| {
+        if let Some(module) = ast::Module::cast(it.clone()) {
+            return module.item_list().map(|it| it.syntax().clone());
+        }
+        ast::SourceFile::cast(it).map(|it| it.syntax().clone())
+    })?;
+    let assists = imports
+        .into_iter()
+        .filter_map(|(_, label, import)| {
+            ctx.clone().add_assist(AssistId("auto_import"), format!("import {}", label), |edit| {
+                edit.target(path.syntax().text_range());
+                insert_use(&container, path.syntax(), &import, edit.text_edit_builder());
+            })
+        })
+        .collect();
+    Some(assists)
+}
+
+/// The shortest path to `def` visible from `from`, through the
+/// modules and the re-exports of the crate of `def`.
+fn find_import_path(db: &impl HirDatabase, from: Module, def: ModuleDef) -> Option<Vec<SmolStr>> {
+    let krate = def.module(db)?.krate();
+    let crate_name: SmolStr = if krate == from.krate() {
+        "crate".into()
+    } else {
+        let dep = from.krate().dependencies(db).into_iter().find(|it| it.krate == krate)?;
+        dep.name.to_string().into()
+    };
+
+    let mut queue = VecDeque::new();
+    queue.push_back((krate.root_module(db)?, vec![crate_name]));
+    let mut visited = Vec::new();
+    while let Some((module, path)) = queue.pop_front() {
+        if visited.contains(&module) {
+            continue;
+        }
+        visited.push(module);
+        let scope = module.scope(db, Some(from));
+        if let Some((name, _)) = scope.iter().find(|(_, it)| *it == ScopeDef::ModuleDef(def)) {
+            let mut path = path;
+            path.push(name.to_string().into());
+            return Some(path);
+        }
+        for (name, it) in scope {
+            if let ScopeDef::ModuleDef(ModuleDef::Module(child)) = it {
+                if child.krate() == krate {
+                    let mut path = path.clone();
+                    path.push(name.to_string().into());
+                    queue.push_back((child, path));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// The standard library first, then the crates of the workspace, then the
+/// other dependencies.
+fn rank(db: &impl HirDatabase, def: ModuleDef, import: &[SmolStr]) -> u8 {
+    match import[0].as_str() {
+        "std" | "core" | "alloc" => return 0,
+        "crate" => return 1,
+        _ => (),
+    }
+    let root_file = def
+        .module(db)
+        .and_then(|it| it.krate().root_module(db))
+        .map(|it| it.definition_source(db).file_id.original_file(db));
+    match root_file {
+        Some(file_id) if !db.source_root(db.file_source_root(file_id)).is_library => 1,
+        _ => 2,
+    }
+}
+
+/// Adds the `use` next to the other ones, keeping them sorted, or merges it
+/// into a `use` of the same crate.
+fn insert_use(
+    container: &SyntaxNode,
+    anchor: &SyntaxNode,
+    import: &[SmolStr],
+    edit: &mut TextEditBuilder,
+) {
+    let uses: Vec<ast::UseItem> = container.children().filter_map(ast::UseItem::cast).collect();
+    let same_crate = uses.iter().any(|it| {
+        let first_segment = it.use_tree().and_then(|it| it.path()).map(|mut path| {
+            while let Some(qualifier) = path.qualifier() {
+                path = qualifier;
+            }
+            path.syntax().text().to_string()
+        });
+        first_segment.as_ref().map(|it| it.as_str()) == Some(import[0].as_str())
+    });
+    let last = match uses.last() {
+        Some(it) if !same_crate => it,
+        _ => return auto_import_text_edit(anchor, anchor, import, edit),
+    };
+
+    let import = import.join("::");
+    let next = uses
+        .iter()
+        .find(|it| it.use_tree().map_or(false, |it| it.syntax().text().to_string() > import));
+    match next {
+        Some(next) => {
+            let indent =
+                ra_fmt::leading_indent(next.syntax()).map_or(String::new(), |it| it.to_string());
+            edit.insert(next.syntax().text_range().start(), format!("use {};\n{}", import, indent));
+        }
+        None => {
+            let indent =
+                ra_fmt::leading_indent(last.syntax()).map_or(String::new(), |it| it.to_string());
+            edit.insert(last.syntax().text_range().end(), format!("\n{}use {};", indent, import));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hir::Crate;
+    use ra_db::{fixture::WithFixture, FileRange, SourceDatabaseExt};
+    use ra_syntax::TextRange;
+    use test_utils::assert_eq_text;
+
+    use super::*;
+    use crate::test_db::TestDB;
+
+    /// Finds the definitions by walking the modules of all the crates.
+    struct TestImportsLocator<'a> {
+        db: &'a TestDB,
+    }
+
+    impl ImportsLocator for TestImportsLocator<'_> {
+        fn find_imports(&mut self, name_to_import: &str) -> Vec<ModuleDef> {
+            let mut res = Vec::new();
+            let mut stack: Vec<Module> =
+                Crate::all(self.db).into_iter().filter_map(|it| it.root_module(self.db)).collect();
+            while let Some(module) = stack.pop() {
+                for def in module.declarations(self.db) {
+                    let name = match def {
+                        ModuleDef::Module(it) => it.name(self.db),
+                        ModuleDef::Function(it) => Some(it.name(self.db)),
+                        ModuleDef::Adt(hir::Adt::Struct(it)) => Some(it.name(self.db)),
+                        ModuleDef::Trait(it) => Some(it.name(self.db)),
+                        _ => None,
+                    };
+                    if name.map_or(false, |it| it.to_string() == name_to_import) {
+                        res.push(def);
+                    }
+                }
+                stack.extend(module.children(self.db));
+            }
+            res
+        }
+    }
+
+    fn auto_imports(fixture: &str) -> Vec<(String, String)> {
+        let (db, position) = TestDB::with_position(fixture);
+        let frange = FileRange {
+            file_id: position.file_id,
+            range: TextRange::offset_len(position.offset, 0.into()),
+        };
+        let before = db.file_text(position.file_id);
+        let mut locator = TestImportsLocator { db: &db };
+        let assists = AssistCtx::with_ctx(&db, frange, true, |ctx| auto_import(ctx, &mut locator))
+            .unwrap_or_default();
+        assists
+            .into_iter()
+            .map(|it| match it {
+                Assist::Resolved { label, action } => (label.label, action.edit.apply(&before)),
+                Assist::Unresolved { .. } => unreachable!(),
+            })
+            .collect()
+    }
+
+    fn check_auto_import(fixture: &str, label: &str, after: &str) {
+        let imports = auto_imports(fixture);
+        let (_, actual) = imports
+            .iter()
+            .find(|(it, _)| it == label)
+            .unwrap_or_else(|| panic!("no `{}` assist in {:?}", label, imports));
+        assert_eq_text!(after, actual);
+    }
+
+    const STD: &str = r#"//- /std/lib.rs crate:std
+pub mod collections {
+    mod hash {
+        pub struct HashMap;
+    }
+    pub use self::hash::HashMap;
+    pub struct BTreeMap;
+}
+pub mod fmt {
+    pub trait Debug {}
+}
+"#;
+
+    #[test]
+    fn auto_import_from_std_through_reexport() {
+        check_auto_import(
+            &format!(
+                r#"
+//- /main.rs crate:main deps:std
+fn main() {{
+    let map = HashMap<|>::new();
+}}
+{}"#,
+                STD
+            ),
+            "import std::collections::HashMap",
+            r#"use std::collections::HashMap;
+
+fn main() {
+    let map = HashMap::new();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn auto_import_in_sorted_position() {
+        check_auto_import(
+            &format!(
+                r#"
+//- /main.rs crate:main deps:std
+use crate::a::A;
+use foo::Foo;
+
+mod a {{ pub struct A; }}
+fn f(map: BTreeMap<|>) {{}}
+{}"#,
+                STD
+            ),
+            "import std::collections::BTreeMap",
+            r#"use crate::a::A;
+use foo::Foo;
+use std::collections::BTreeMap;
+
+mod a { pub struct A; }
+fn f(map: BTreeMap) {}
+"#,
+        );
+        check_auto_import(
+            &format!(
+                r#"
+//- /main.rs crate:main deps:std
+use crate::a::A;
+use zzz::Z;
+
+mod a {{ pub struct A; }}
+fn f(map: BTreeMap<|>) {{}}
+{}"#,
+                STD
+            ),
+            "import std::collections::BTreeMap",
+            r#"use crate::a::A;
+use std::collections::BTreeMap;
+use zzz::Z;
+
+mod a { pub struct A; }
+fn f(map: BTreeMap) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn auto_import_offers_every_candidate_std_first() {
+        let imports = auto_imports(&format!(
+            r#"
+//- /main.rs crate:main deps:std,dep
+mod local {{
+    pub trait Debug {{}}
+}}
+impl Debug<|> for () {{}}
+//- /dep/lib.rs crate:dep
+pub trait Debug {{}}
+mod private {{
+    pub trait Debug {{}}
+}}
+{}"#,
+            STD
+        ));
+        let labels: Vec<&str> = imports.iter().map(|(label, _)| label.as_str()).collect();
+        assert_eq!(
+            labels,
+            vec!["import std::fmt::Debug", "import crate::local::Debug", "import dep::Debug"]
+        );
+    }
+
+    #[test]
+    fn auto_import_not_applicable() {
+        let fixture = |code: &str| {
+            format!("\n//- /main.rs crate:main deps:std\nstruct HashMap;\n{}\n{}", code, STD)
+        };
+        // resolved names
+        assert!(auto_imports(&fixture("fn f() { HashMap<|>; }")).is_empty());
+        // qualified names and names without candidates
+        assert!(auto_imports(&fixture("fn f() { std::collections::BTreeMap<|>; }")).is_empty());
+        assert!(auto_imports(&fixture("fn f() { Unknown<|>; }")).is_empty());
+        // use items
+        assert!(auto_imports(&fixture("use BTreeMap<|>;")).is_empty());
+    }
+}
@@ -3,8 +3,8 @@ use ra_syntax::{
     ast::{self, NameOwner, VisibilityOwner},
     AstNode,
     SyntaxKind::{
-        ATTR, COMMENT, ENUM_DEF, FN_DEF, IDENT, MODULE, STRUCT_DEF, TRAIT_DEF, VISIBILITY,
-        WHITESPACE,
+        ATTR, COMMENT, CONST_DEF, ENUM_DEF, FN_DEF, IDENT, MODULE, STATIC_DEF, STRUCT_DEF,
+        TRAIT_DEF, TYPE_ALIAS_DEF, VISIBILITY, WHITESPACE,
     },
     SyntaxNode, TextUnit, T,
 };
@@ -20,7 +20,7 @@ use crate::{Assist, AssistCtx, AssistId};
 // ```
 // ->
 // ```
-// pub(crate) fn frobnicate() {}
+// pub fn frobnicate() {}
 // ```
 pub(crate) fn change_visibility(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
     if let Some(vis) = ctx.find_node_at_offset::<ast::Visibility>() {
@@ -31,13 +31,31 @@ pub(crate) fn change_visibility(ctx: AssistCtx<impl HirDatabase>) -> Option<Assi
 
 fn add_vis(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
     let item_keyword = ctx.token_at_offset().find(|leaf| match leaf.kind() {
-        T![fn] | T![mod] | T![struct] | T![enum] | T![trait] => true,
+        T![fn]
+        | T![mod]
+        | T![struct]
+        | T![enum]
+        | T![trait]
+        | T![const]
+        | T![static]
+        | T![type]
+        | T![async]
+        | T![unsafe] => true,
         _ => false,
     });
 
     let (offset, target) = if let Some(keyword) = item_keyword {
         let parent = keyword.parent();
-        let def_kws = vec![FN_DEF, MODULE, STRUCT_DEF, ENUM_DEF, TRAIT_DEF];
+        let def_kws = vec![
+            FN_DEF,
+            MODULE,
+            STRUCT_DEF,
+            ENUM_DEF,
+            TRAIT_DEF,
+            CONST_DEF,
+            STATIC_DEF,
+            TYPE_ALIAS_DEF,
+        ];
         // Parent is not a definition, can't add visibility
         if !def_kws.iter().any(|&def_kw| def_kw == parent.kind()) {
             return None;
@@ -47,6 +65,16 @@ fn add_vis(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
             return None;
         }
         (vis_offset(&parent), keyword.text_range())
+    } else if let Some(field) = ctx.find_node_at_offset::<ast::TupleFieldDef>() {
+        if field.visibility().is_some() {
+            return None;
+        }
+        // Fields of enum variants are as visible as the enum
+        let in_variant = field.syntax().parent().and_then(|list| list.parent());
+        if in_variant.and_then(ast::EnumVariant::cast).is_some() {
+            return None;
+        }
+        (vis_offset(field.syntax()), field.syntax().text_range())
     } else {
         let ident = ctx.token_at_offset().find(|leaf| leaf.kind() == IDENT)?;
         let field = ident.parent().ancestors().find_map(ast::RecordFieldDef::cast)?;
@@ -57,9 +85,9 @@ fn add_vis(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
         (vis_offset(field.syntax()), ident.text_range())
     };
 
-    ctx.add_assist(AssistId("change_visibility"), "make pub(crate)", |edit| {
+    ctx.add_assist(AssistId("change_visibility"), "make pub", |edit| {
         edit.target(target);
-        edit.insert(offset, "pub(crate) ");
+        edit.insert(offset, "pub ");
         edit.set_cursor(offset);
     })
 }
@@ -75,41 +103,49 @@ fn vis_offset(node: &SyntaxNode) -> TextUnit {
         .unwrap_or_else(|| node.text_range().start())
 }
 
+/// Cycles through the visibilities: `pub` becomes `pub(crate)`, `pub(crate)`
+/// becomes `pub` and the restricted ones (`pub(super)`, `pub(in path)`, ...)
+/// become `pub(crate)`.
 fn change_vis(ctx: AssistCtx<impl HirDatabase>, vis: ast::Visibility) -> Option<Assist> {
-    if vis.syntax().text() == "pub" {
-        return ctx.add_assist(AssistId("change_visibility"), "change to pub(crate)", |edit| {
-            edit.target(vis.syntax().text_range());
-            edit.replace(vis.syntax().text_range(), "pub(crate)");
-            edit.set_cursor(vis.syntax().text_range().start())
-        });
-    }
-    if vis.syntax().text() == "pub(crate)" {
-        return ctx.add_assist(AssistId("change_visibility"), "change to pub", |edit| {
-            edit.target(vis.syntax().text_range());
-            edit.replace(vis.syntax().text_range(), "pub");
-            edit.set_cursor(vis.syntax().text_range().start());
-        });
-    }
-    None
+    let (label, new_vis) = match vis.kind() {
+        ast::VisibilityKind::PubCrate => ("change to pub", "pub"),
+        _ => ("change to pub(crate)", "pub(crate)"),
+    };
+    ctx.add_assist(AssistId("change_visibility"), label, |edit| {
+        edit.target(vis.syntax().text_range());
+        edit.replace(vis.syntax().text_range(), new_vis);
+        edit.set_cursor(vis.syntax().text_range().start());
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::helpers::{check_assist, check_assist_target};
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn change_visibility_adds_pub_to_items() {
+        check_assist(change_visibility, "<|>fn foo() {}", "<|>pub fn foo() {}");
+        check_assist(change_visibility, "f<|>n foo() {}", "<|>pub fn foo() {}");
+        check_assist(change_visibility, "<|>struct Foo {}", "<|>pub struct Foo {}");
+        check_assist(change_visibility, "<|>mod foo {}", "<|>pub mod foo {}");
+        check_assist(change_visibility, "<|>trait Foo {}", "<|>pub trait Foo {}");
+        check_assist(change_visibility, "m<|>od {}", "<|>pub mod {}");
+        check_assist(change_visibility, "unsafe f<|>n foo() {}", "<|>pub unsafe fn foo() {}");
+        check_assist(change_visibility, "<|>const FOO: u32 = 0;", "<|>pub const FOO: u32 = 0;");
+        check_assist(change_visibility, "<|>static FOO: u32 = 0;", "<|>pub static FOO: u32 = 0;");
+        check_assist(change_visibility, "<|>type Foo = u32;", "<|>pub type Foo = u32;");
+    }
 
     #[test]
-    fn change_visibility_adds_pub_crate_to_items() {
-        check_assist(change_visibility, "<|>fn foo() {}", "<|>pub(crate) fn foo() {}");
-        check_assist(change_visibility, "f<|>n foo() {}", "<|>pub(crate) fn foo() {}");
-        check_assist(change_visibility, "<|>struct Foo {}", "<|>pub(crate) struct Foo {}");
-        check_assist(change_visibility, "<|>mod foo {}", "<|>pub(crate) mod foo {}");
-        check_assist(change_visibility, "<|>trait Foo {}", "<|>pub(crate) trait Foo {}");
-        check_assist(change_visibility, "m<|>od {}", "<|>pub(crate) mod {}");
+    fn change_visibility_goes_before_fn_modifiers() {
+        check_assist(change_visibility, "<|>async fn foo() {}", "<|>pub async fn foo() {}");
+        check_assist(change_visibility, "<|>unsafe fn foo() {}", "<|>pub unsafe fn foo() {}");
+        check_assist(change_visibility, "<|>const fn foo() {}", "<|>pub const fn foo() {}");
         check_assist(
             change_visibility,
-            "unsafe f<|>n foo() {}",
-            "<|>pub(crate) unsafe fn foo() {}",
+            "const unsafe f<|>n foo() {}",
+            "<|>pub const unsafe fn foo() {}",
         );
     }
 
@@ -118,10 +154,26 @@ mod tests {
         check_assist(
             change_visibility,
             "struct S { <|>field: u32 }",
-            "struct S { <|>pub(crate) field: u32 }",
+            "struct S { <|>pub field: u32 }",
         )
     }
 
+    #[test]
+    fn change_visibility_works_with_tuple_fields() {
+        check_assist(change_visibility, "struct S(<|>u32);", "struct S(<|>pub u32);");
+        check_assist(change_visibility, "struct S(u32, <|>u32);", "struct S(u32, <|>pub u32);");
+    }
+
+    #[test]
+    fn change_visibility_not_applicable_to_enum_variant_fields() {
+        check_assist_not_applicable(change_visibility, "enum E { A(<|>u32) }");
+    }
+
+    #[test]
+    fn change_visibility_not_applicable_to_impls() {
+        check_assist_not_applicable(change_visibility, "<|>unsafe impl Send for S {}");
+    }
+
     #[test]
     fn change_visibility_pub_to_pub_crate() {
         check_assist(change_visibility, "<|>pub fn foo() {}", "<|>pub(crate) fn foo() {}")
@@ -132,6 +184,17 @@ mod tests {
         check_assist(change_visibility, "<|>pub(crate) fn foo() {}", "<|>pub fn foo() {}")
     }
 
+    #[test]
+    fn change_visibility_restricted_to_pub_crate() {
+        check_assist(change_visibility, "<|>pub(super) fn foo() {}", "<|>pub(crate) fn foo() {}");
+        check_assist(change_visibility, "<|>pub(self) fn foo() {}", "<|>pub(crate) fn foo() {}");
+        check_assist(
+            change_visibility,
+            "<|>pub(in crate::foo) fn foo() {}",
+            "<|>pub(crate) fn foo() {}",
+        );
+    }
+
     #[test]
     fn change_visibility_handles_comment_attrs() {
         check_assist(
@@ -150,7 +213,7 @@ mod tests {
             // comments
 
             #[derive(Debug)]
-            <|>pub(crate) struct Foo;
+            <|>pub struct Foo;
             ",
         )
     }
@@ -160,5 +223,6 @@ mod tests {
         check_assist_target(change_visibility, "<|>fn foo() {}", "fn");
         check_assist_target(change_visibility, "pub(crate)<|> fn foo() {}", "pub(crate)");
         check_assist_target(change_visibility, "struct S { <|>field: u32 }", "field");
+        check_assist_target(change_visibility, "struct S(<|>u32);", "u32");
     }
 }
@@ -0,0 +1,349 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, LoopBodyOwner},
+    SyntaxKind::{self, WHITESPACE},
+    SyntaxNode, TextRange, T,
+};
+
+use crate::{assists::invert_if::negate_boolean_expression, Assist, AssistCtx, AssistId};
+
+// Assist: convert_while_to_loop
+//
+// Converts a `while` loop to a `loop` which breaks when the condition doesn't
+// hold anymore.
+//
+// ```
+// fn main() {
+//     <|>while cond {
+//         foo();
+//     }
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     loop {
+//         if !cond {
+//             break;
+//         }
+//         foo();
+//     }
+// }
+// ```
+pub(crate) fn convert_while_to_loop(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let while_expr = ctx.find_node_at_offset::<ast::WhileExpr>()?;
+    let body = while_expr.loop_body()?;
+    if ctx.frange.range.start() >= body.syntax().text_range().start() {
+        return None;
+    }
+    let cond = while_expr.condition()?;
+    // `while let` would need a `match`
+    if cond.pat().is_some() {
+        return None;
+    }
+    let cond = cond.expr()?;
+    let head = loop_head_range(while_expr.syntax(), body.syntax(), T![while])?;
+
+    ctx.add_assist(AssistId("convert_while_to_loop"), "convert while to loop", |edit| {
+        let indent = IndentLevel::from_node(while_expr.syntax());
+        let inner = indent_str(indent.0 + 1);
+        let guard = format!(
+            "\n{0}if {1} {{\n{0}    break;\n{0}}}",
+            inner,
+            negate_boolean_expression(&cond).syntax()
+        );
+        let stmts = block_contents(body.syntax());
+        let contents = if stmts.trim().is_empty() {
+            format!("{}\n{}", guard, indent_str(indent.0))
+        } else if !stmts.starts_with('\n') {
+            // the body was on a single line, the guard puts it on its own
+            format!("{}\n{}{}\n{}", guard, inner, stmts.trim(), indent_str(indent.0))
+        } else {
+            format!("{}{}", guard, stmts)
+        };
+
+        edit.target(head);
+        edit.replace(
+            TextRange::from_to(head.start(), body.syntax().text_range().end()),
+            format!("loop {{{}}}", contents),
+        );
+        edit.set_cursor(head.start());
+    })
+}
+
+// Assist: convert_loop_to_while
+//
+// Converts a `loop` starting with a conditional `break` to a `while` loop.
+//
+// ```
+// fn main() {
+//     <|>loop {
+//         if !cond {
+//             break;
+//         }
+//         foo();
+//     }
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     while cond {
+//         foo();
+//     }
+// }
+// ```
+pub(crate) fn convert_loop_to_while(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let loop_expr = ctx.find_node_at_offset::<ast::LoopExpr>()?;
+    let body = loop_expr.loop_body()?;
+    if ctx.frange.range.start() >= body.syntax().text_range().start() {
+        return None;
+    }
+    let block = body.block()?;
+    let guard = leading_break(&block)?;
+    let cond = guard.condition()?;
+    if cond.pat().is_some() {
+        return None;
+    }
+    let cond = cond.expr()?;
+    // a `while` can't produce a value
+    if body.syntax().descendants().filter_map(ast::BreakExpr::cast).any(|it| it.expr().is_some()) {
+        return None;
+    }
+    let head = loop_head_range(loop_expr.syntax(), body.syntax(), T![loop])?;
+
+    ctx.add_assist(AssistId("convert_loop_to_while"), "convert loop to while", |edit| {
+        // the statement holding the `if` spans up to its `;`, if any
+        let guard_end = guard
+            .syntax()
+            .ancestors()
+            .take_while(|it| it != block.syntax())
+            .last()
+            .map_or(guard.syntax().text_range().end(), |it| it.text_range().end());
+        let block_text = block.syntax().to_string();
+        let rest = &block_text[(guard_end - block.syntax().text_range().start()).to_usize()..];
+        let rest = if rest.trim() == "}" { "}" } else { rest };
+
+        edit.target(head);
+        edit.replace(
+            TextRange::from_to(head.start(), body.syntax().text_range().end()),
+            format!("while {} {{{}", negate_boolean_expression(&cond).syntax(), rest),
+        );
+        edit.set_cursor(head.start());
+    })
+}
+
+/// The `if cond { break; }` starting the block, without `else` or other
+/// statements in the `if`.
+fn leading_break(block: &ast::Block) -> Option<ast::IfExpr> {
+    // comments before the `if` would be lost
+    let first = block
+        .syntax()
+        .children_with_tokens()
+        .skip(1)
+        .find(|it| it.kind() != WHITESPACE)?
+        .into_node()?;
+    let if_expr = match ast::ExprStmt::cast(first.clone()) {
+        Some(stmt) => match stmt.expr()? {
+            ast::Expr::IfExpr(it) => it,
+            _ => return None,
+        },
+        None => ast::IfExpr::cast(first)?,
+    };
+    if if_expr.else_branch().is_some() {
+        return None;
+    }
+    let then_block = if_expr.then_branch()?.block()?;
+    let break_expr = match (then_block.statements().next(), then_block.expr()) {
+        (None, Some(ast::Expr::BreakExpr(it))) => it,
+        (Some(ast::Stmt::ExprStmt(stmt)), None) if then_block.statements().count() == 1 => {
+            match stmt.expr()? {
+                ast::Expr::BreakExpr(it) => it,
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    // a labeled `break` may leave an outer loop
+    if break_expr.syntax().text() != "break" {
+        return None;
+    }
+    Some(if_expr)
+}
+
+/// The range from the `while` or `loop` keyword up to the body of the loop,
+/// which leaves a label of the loop alone.
+fn loop_head_range(
+    loop_expr: &SyntaxNode,
+    body: &SyntaxNode,
+    keyword: SyntaxKind,
+) -> Option<TextRange> {
+    let keyword = loop_expr
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .find(|it| it.kind() == keyword)?;
+    Some(TextRange::from_to(keyword.text_range().start(), body.text_range().start()))
+}
+
+/// The text of a block between its braces.
+fn block_contents(block: &SyntaxNode) -> String {
+    let text = block.to_string();
+    let text = text.trim_start_matches('{').trim_end_matches('}');
+    text.to_string()
+}
+
+fn indent_str(level: u8) -> String {
+    "    ".repeat(level as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn convert_while_to_loop_negates_condition() {
+        check_assist(
+            convert_while_to_loop,
+            "
+fn f() {
+    'outer: wh<|>ile x.is_some() {
+        foo();
+    }
+}",
+            "
+fn f() {
+    'outer: <|>loop {
+        if x.is_none() {
+            break;
+        }
+        foo();
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn convert_while_to_loop_with_inline_body() {
+        check_assist(
+            convert_while_to_loop,
+            "fn f() { <|>while a && b { foo() } }",
+            "fn f() { <|>loop {
+    if !(a && b) {
+        break;
+    }
+    foo()
+} }",
+        );
+        check_assist(
+            convert_while_to_loop,
+            "fn f() { <|>while x != 0 {} }",
+            "fn f() { <|>loop {
+    if x == 0 {
+        break;
+    }
+} }",
+        );
+    }
+
+    #[test]
+    fn convert_while_to_loop_not_applicable() {
+        check_assist_not_applicable(
+            convert_while_to_loop,
+            "fn f() { <|>while let Some(x) = it.next() { foo(x); } }",
+        );
+        check_assist_not_applicable(convert_while_to_loop, "fn f() { while cond { foo(<|>); } }");
+    }
+
+    #[test]
+    fn convert_loop_to_while_negates_condition() {
+        check_assist(
+            convert_loop_to_while,
+            "
+fn f() {
+    lo<|>op {
+        if !cond {
+            break;
+        }
+        foo();
+        bar();
+    }
+}",
+            "
+fn f() {
+    <|>while cond {
+        foo();
+        bar();
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn convert_loop_to_while_without_statements() {
+        check_assist(
+            convert_loop_to_while,
+            "fn f() { <|>loop { if x == 1 { break } } }",
+            "fn f() { <|>while x != 1 {} }",
+        );
+    }
+
+    #[test]
+    fn convert_while_to_loop_and_back() {
+        let before = "
+fn f() {
+    <|>while x.is_ok() {
+        foo();
+    }
+}";
+        let after = "
+fn f() {
+    <|>loop {
+        if x.is_err() {
+            break;
+        }
+        foo();
+    }
+}";
+        check_assist(convert_while_to_loop, before, after);
+        check_assist(convert_loop_to_while, after, before);
+    }
+
+    #[test]
+    fn convert_loop_to_while_not_applicable() {
+        // the loop doesn't start with the condition
+        check_assist_not_applicable(
+            convert_loop_to_while,
+            "fn f() { <|>loop { foo(); if !cond { break; } } }",
+        );
+        // the `if` does more than breaking
+        check_assist_not_applicable(
+            convert_loop_to_while,
+            "fn f() { <|>loop { if !cond { foo(); break; } bar(); } }",
+        );
+        check_assist_not_applicable(
+            convert_loop_to_while,
+            "fn f() { <|>loop { if !cond { break; } else { foo(); } } }",
+        );
+        // the loop produces a value, or the `break` leaves an outer loop
+        check_assist_not_applicable(
+            convert_loop_to_while,
+            "fn f() { let x = <|>loop { if !cond { break; } break 92; }; }",
+        );
+        check_assist_not_applicable(
+            convert_loop_to_while,
+            "fn f() { 'a: loop { <|>loop { if !cond { break 'a; } } } }",
+        );
+    }
+
+    #[test]
+    fn convert_loops_target() {
+        check_assist_target(convert_while_to_loop, "fn f() { 'a: wh<|>ile c {} }", "while c ");
+        check_assist_target(
+            convert_loop_to_while,
+            "fn f() { <|>loop { if c { break; } } }",
+            "loop ",
+        );
+    }
+}
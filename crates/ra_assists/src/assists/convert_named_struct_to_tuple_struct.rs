@@ -0,0 +1,517 @@
+use hir::{db::HirDatabase, FromSource, InFile, SourceAnalyzer};
+use ra_db::FileId;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, StructKind, TypeAscriptionOwner, TypeParamsOwner},
+    SyntaxNode, TextRange, TextUnit, T,
+};
+
+use crate::{
+    assists::extract_struct_from_enum_variant::{find_name_refs, path_parent},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: convert_named_struct_to_tuple_struct
+//
+// Converts a struct with named fields to a tuple struct, and its literals,
+// patterns and field accesses to positional ones.
+//
+// ```
+// struct <|>Point { x: f32, y: f32 }
+//
+// fn norm(p: Point) -> f32 {
+//     let Point { x, y } = p;
+//     (x * x + y * y).sqrt()
+// }
+// ```
+// ->
+// ```
+// struct Point(f32, f32);
+//
+// fn norm(p: Point) -> f32 {
+//     let Point(x, y) = p;
+//     (x * x + y * y).sqrt()
+// }
+// ```
+pub(crate) fn convert_named_struct_to_tuple_struct(
+    ctx: AssistCtx<impl HirDatabase>,
+) -> Option<Assist> {
+    let struct_def = ctx.find_node_at_offset::<ast::StructDef>()?;
+    let field_list = match struct_def.kind() {
+        StructKind::Record(it) => it,
+        _ => return None,
+    };
+    if ctx.frange.range.start() > field_list.syntax().text_range().start() {
+        return None;
+    }
+    // the where clause would have to move after the fields
+    if struct_def.where_clause().is_some() {
+        return None;
+    }
+    let fields: Vec<_> = field_list
+        .fields()
+        .map(|field| {
+            let prefix = TextRange::from_to(
+                field.name()?.syntax().text_range().start(),
+                field.ascribed_type()?.syntax().text_range().start(),
+            );
+            Some((field.syntax().text_range(), Some(prefix)))
+        })
+        .collect::<Option<_>>()?;
+    let name_end = match struct_def.type_param_list() {
+        Some(it) => it.syntax().text_range().end(),
+        None => struct_def.name()?.syntax().text_range().end(),
+    };
+    let def_edits = positional_edits(name_end, field_list.syntax(), &fields, ");")?;
+
+    let db = ctx.db;
+    let file_id = ctx.frange.file_id;
+    let strukt = hir::Struct::from_source(db, InFile::new(file_id.into(), struct_def.clone()))?;
+
+    ctx.add_assist(
+        AssistId("convert_named_struct_to_tuple_struct"),
+        "convert to tuple struct",
+        |edit| {
+            edit.target(struct_def.syntax().text_range());
+            for (range, text) in def_edits {
+                edit.replace(range, text);
+            }
+            for (usage_file_id, edits) in usage_edits(db, strukt) {
+                let builder = if usage_file_id == file_id {
+                    edit.text_edit_builder()
+                } else {
+                    edit.other_file_edit_builder(usage_file_id)
+                };
+                for (range, text) in edits {
+                    builder.replace(range, text);
+                }
+            }
+        },
+    )
+}
+
+/// The edits of the literals, patterns and field accesses of `strukt`, by file.
+/// The literals and patterns naming a field which doesn't exist are left as is.
+fn usage_edits(
+    db: &impl HirDatabase,
+    strukt: hir::Struct,
+) -> Vec<(FileId, Vec<(TextRange, String)>)> {
+    let field_names: Vec<String> =
+        strukt.fields(db).into_iter().map(|it| it.name(db).to_string()).collect();
+    // literals and patterns end with the name of the struct or with `Self`,
+    // field accesses with the name of the field
+    let mut names = vec![strukt.name(db).to_string(), "Self".to_string()];
+    for name in field_names.iter() {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+
+    let mut res: Vec<(FileId, Vec<(TextRange, String)>)> = Vec::new();
+    for name in names {
+        for (file_id, name_refs) in find_name_refs(db, strukt.module(db), &name) {
+            let edits = name_refs
+                .iter()
+                .filter_map(|it| usage_edit(db, file_id, strukt, &field_names, it))
+                .flatten();
+            match res.iter_mut().find(|(it, _)| *it == file_id) {
+                Some((_, file_edits)) => file_edits.extend(edits),
+                None => res.push((file_id, edits.collect())),
+            }
+        }
+    }
+    res
+}
+
+/// The edits of the literal, pattern or field access of `strukt` ending with
+/// `name_ref`, if it is one.
+fn usage_edit(
+    db: &impl HirDatabase,
+    file_id: FileId,
+    strukt: hir::Struct,
+    field_names: &[String],
+    name_ref: &ast::NameRef,
+) -> Option<Vec<(TextRange, String)>> {
+    let index_of = |name: &str| field_names.iter().position(|it| it == name);
+    let is_struct = |def: Option<hir::VariantDef>, path: Option<ast::Path>| {
+        def == Some(hir::VariantDef::Struct(strukt))
+            || path.map_or(false, |it| is_self_path_of(db, file_id, &it, strukt))
+    };
+
+    if let Some(field_expr) = name_ref.syntax().parent().and_then(ast::FieldExpr::cast) {
+        let node = field_expr.syntax();
+        let analyzer = SourceAnalyzer::new(db, InFile::new(file_id.into(), node), None);
+        let field = analyzer.resolve_field(&field_expr)?;
+        if field.parent_def(db) != hir::VariantDef::Struct(strukt) {
+            return None;
+        }
+        let index = index_of(&field.name(db).to_string())?;
+        return Some(vec![(name_ref.syntax().text_range(), index.to_string())]);
+    }
+
+    let node = path_parent(name_ref)?;
+    if let Some(lit) = ast::RecordLit::cast(node.clone()) {
+        let analyzer = SourceAnalyzer::new(db, InFile::new(file_id.into(), &node), None);
+        if !is_struct(analyzer.resolve_record_literal(&lit), lit.path()) {
+            return None;
+        }
+        let (path, list) = (lit.path()?, lit.record_field_list()?);
+        let mut fields = Vec::new();
+        for field in list.fields() {
+            let name_ref = field.name_ref()?;
+            let value_start = field.expr().map(|it| it.syntax().text_range().start());
+            fields.push(NamedField {
+                index: index_of(name_ref.text())?,
+                range: field.syntax().text_range(),
+                name: name_ref.syntax().text_range(),
+                prefix: value_start
+                    .map(|it| TextRange::from_to(name_ref.syntax().text_range().start(), it)),
+            });
+        }
+        let positional = list.spread().is_none() && is_complete(&fields, field_names.len());
+        field_list_edits(path.syntax(), list.syntax(), &fields, positional)
+    } else if let Some(pat) = ast::RecordPat::cast(node.clone()) {
+        let analyzer = SourceAnalyzer::new(db, InFile::new(file_id.into(), &node), None);
+        if !is_struct(analyzer.resolve_record_pattern(&pat), pat.path()) {
+            return None;
+        }
+        let (path, list) = (pat.path()?, pat.record_field_pat_list()?);
+        let mut fields = Vec::new();
+        for child in list.syntax().children() {
+            let field = if let Some(field) = ast::RecordFieldPat::cast(child.clone()) {
+                let name = field.name()?;
+                NamedField {
+                    index: index_of(name.text())?,
+                    range: child.text_range(),
+                    name: name.syntax().text_range(),
+                    prefix: Some(TextRange::from_to(
+                        name.syntax().text_range().start(),
+                        field.pat()?.syntax().text_range().start(),
+                    )),
+                }
+            } else {
+                // `ref mut x` binds the field of the same name
+                let name = ast::BindPat::cast(child.clone())?.name()?;
+                NamedField {
+                    index: index_of(name.text())?,
+                    range: child.text_range(),
+                    name: child.text_range(),
+                    prefix: None,
+                }
+            };
+            fields.push(field);
+        }
+        let has_rest = list.syntax().children_with_tokens().any(|it| it.kind() == T![..]);
+        let positional = !has_rest && is_complete(&fields, field_names.len());
+        field_list_edits(path.syntax(), list.syntax(), &fields, positional)
+    } else {
+        None
+    }
+}
+
+/// Whether the path is `Self` in an impl of `strukt`, which the inference
+/// doesn't resolve in literals and patterns.
+fn is_self_path_of(
+    db: &impl HirDatabase,
+    file_id: FileId,
+    path: &ast::Path,
+    strukt: hir::Struct,
+) -> bool {
+    let is_self = path.qualifier().is_none()
+        && path.segment().and_then(|it| it.name_ref()).map_or(false, |it| it.text() == "Self");
+    if !is_self {
+        return false;
+    }
+    let impl_block = match path.syntax().ancestors().find_map(ast::ImplBlock::cast) {
+        Some(it) => it,
+        None => return false,
+    };
+    let impl_block = hir::ImplBlock::from_source(db, InFile::new(file_id.into(), impl_block));
+    impl_block.and_then(|it| it.target_ty(db).as_adt()) == Some(hir::Adt::Struct(strukt))
+}
+
+/// A field of a literal or a pattern, given by its name.
+struct NamedField {
+    index: usize,
+    range: TextRange,
+    name: TextRange,
+    /// The `name: ` before the value, if this isn't a shorthand.
+    prefix: Option<TextRange>,
+}
+
+/// Whether the fields are all the fields of the struct, in their order.
+fn is_complete(fields: &[NamedField], field_count: usize) -> bool {
+    fields.len() == field_count && fields.iter().enumerate().all(|(idx, it)| it.index == idx)
+}
+
+/// The edits of a literal or a pattern, which becomes positional if it has all
+/// the fields in their order. The fields are named by their index otherwise,
+/// like `S { 1: b, ..s }`.
+fn field_list_edits(
+    path: &SyntaxNode,
+    list: &SyntaxNode,
+    fields: &[NamedField],
+    positional: bool,
+) -> Option<Vec<(TextRange, String)>> {
+    if positional {
+        let fields: Vec<_> = fields.iter().map(|it| (it.range, it.prefix)).collect();
+        return positional_edits(path.text_range().end(), list, &fields, ")");
+    }
+    let edits = fields
+        .iter()
+        .map(|field| match field.prefix {
+            Some(_) => (field.name, field.index.to_string()),
+            None => {
+                (TextRange::offset_len(field.name.start(), 0.into()), format!("{}: ", field.index))
+            }
+        })
+        .collect();
+    Some(edits)
+}
+
+/// The edits turning the fields between braces into fields between parentheses
+/// closed by `close`, without the `name: ` prefixes. The space around the
+/// fields is only kept if they are on several lines.
+fn positional_edits(
+    start: TextUnit,
+    list: &SyntaxNode,
+    fields: &[(TextRange, Option<TextRange>)],
+    close: &str,
+) -> Option<Vec<(TextRange, String)>> {
+    let l_curly = list.first_token().filter(|it| it.kind() == T!['{'])?;
+    let r_curly = list.last_token().filter(|it| it.kind() == T!['}'])?;
+    let mut res = Vec::new();
+    match (fields.first(), fields.last()) {
+        (Some((first, _)), Some((last, _))) if !list.text().contains_char('\n') => {
+            res.push((TextRange::from_to(start, first.start()), "(".to_string()));
+            res.push((
+                TextRange::from_to(last.end(), r_curly.text_range().end()),
+                close.to_string(),
+            ));
+        }
+        (Some(_), Some(_)) => {
+            res.push((TextRange::from_to(start, l_curly.text_range().end()), "(".to_string()));
+            res.push((r_curly.text_range(), close.to_string()));
+        }
+        _ => {
+            res.push((TextRange::from_to(start, r_curly.text_range().end()), format!("({}", close)))
+        }
+    }
+    res.extend(fields.iter().filter_map(|(_, prefix)| *prefix).map(|it| (it, String::new())));
+    Some(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use ra_db::{fixture::WithFixture, FileRange, SourceDatabaseExt};
+    use test_utils::assert_eq_text;
+
+    use super::*;
+    use crate::{
+        helpers::{check_assist, check_assist_not_applicable, check_assist_target},
+        test_db::TestDB,
+    };
+
+    #[test]
+    fn convert_struct_definition() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            "struct <|>S<T> { a: u32, pub b: T }",
+            "struct <|>S<T>(u32, pub T);",
+        );
+        check_assist(convert_named_struct_to_tuple_struct, "struct <|>S {}", "struct <|>S();");
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+pub struct <|>S {
+    /// The first
+    pub a: u32,
+    b: String,
+}"#,
+            r#"
+pub struct <|>S(
+    /// The first
+    pub u32,
+    String,
+);"#,
+        );
+    }
+
+    #[test]
+    fn convert_struct_literals_and_patterns() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct <|>S { a: u32, b: u32 }
+impl S {
+    fn new(a: u32) -> S {
+        S { a, b: a + 1 }
+    }
+    fn sum(&self) -> u32 {
+        let S { a, b: ref other } = self;
+        a + other
+    }
+}"#,
+            r#"
+struct <|>S(u32, u32);
+impl S {
+    fn new(a: u32) -> S {
+        S(a, a + 1)
+    }
+    fn sum(&self) -> u32 {
+        let S(a, ref other) = self;
+        a + other
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn convert_struct_literals_and_patterns_out_of_order() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct <|>S { a: u32, b: u32 }
+fn f(s: S) -> S {
+    let S { b, .. } = s;
+    S { b: 1, a: b }
+}
+fn g(s: S) -> S {
+    S { a: 0, ..s }
+}"#,
+            r#"
+struct <|>S(u32, u32);
+fn f(s: S) -> S {
+    let S { 1: b, .. } = s;
+    S { 1: 1, 0: b }
+}
+fn g(s: S) -> S {
+    S { 0: 0, ..s }
+}"#,
+        );
+    }
+
+    #[test]
+    fn convert_struct_field_accesses() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct <|>S { a: u32, b: u32 }
+struct T { a: u32 }
+fn f(s: S, t: T) -> S {
+    S { a: s.b + t.a, b: s.a }
+}"#,
+            r#"
+struct <|>S(u32, u32);
+struct T { a: u32 }
+fn f(s: S, t: T) -> S {
+    S(s.1 + t.a, s.0)
+}"#,
+        );
+    }
+
+    #[test]
+    fn convert_struct_updates_self_literals_and_patterns() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            r#"
+struct <|>S { a: u32, b: u32 }
+struct T { a: u32 }
+impl S {
+    fn new(a: u32) -> Self {
+        Self { a, b: 0 }
+    }
+    fn sum(self) -> u32 {
+        let Self { a, b } = self;
+        a + b
+    }
+}
+impl T {
+    fn new() -> Self {
+        Self { a: 0 }
+    }
+}"#,
+            r#"
+struct <|>S(u32, u32);
+struct T { a: u32 }
+impl S {
+    fn new(a: u32) -> Self {
+        Self(a, 0)
+    }
+    fn sum(self) -> u32 {
+        let Self(a, b) = self;
+        a + b
+    }
+}
+impl T {
+    fn new() -> Self {
+        Self { a: 0 }
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn convert_struct_updates_usages_in_other_files() {
+        let fixture = r#"
+//- /main.rs
+mod a;
+mod b;
+//- /a.rs
+pub struct <|>S { pub x: u32 }
+//- /b.rs
+use crate::a::S;
+fn f(s: S) -> S {
+    S { x: s.x }
+}
+"#;
+        let (db, position) = TestDB::with_position(fixture);
+        let frange = FileRange {
+            file_id: position.file_id,
+            range: TextRange::offset_len(position.offset, 0.into()),
+        };
+        let action =
+            match AssistCtx::with_ctx(&db, frange, true, convert_named_struct_to_tuple_struct) {
+                Some(Assist::Resolved { action, .. }) => action,
+                _ => panic!("code action is not applicable"),
+            };
+        assert_eq!(action.other_file_edits.len(), 1);
+        let (file_id, edit) = &action.other_file_edits[0];
+        let actual = edit.apply(&db.file_text(*file_id));
+        assert_eq_text!(
+            r#"use crate::a::S;
+fn f(s: S) -> S {
+    S(s.0)
+}
+"#,
+            &actual
+        );
+    }
+
+    #[test]
+    fn convert_struct_not_applicable() {
+        check_assist_not_applicable(convert_named_struct_to_tuple_struct, "struct <|>S(u32);");
+        check_assist_not_applicable(convert_named_struct_to_tuple_struct, "struct S { <|>a: u32 }");
+        check_assist_not_applicable(
+            convert_named_struct_to_tuple_struct,
+            "struct <|>S<T> where T: Copy { a: T }",
+        );
+    }
+
+    #[test]
+    fn convert_struct_leaves_literals_with_unknown_fields() {
+        check_assist(
+            convert_named_struct_to_tuple_struct,
+            "struct <|>S { a: u32 } fn f() -> (S, S) { (S { a: 1, c: 2 }, S { a: 3 }) }",
+            "struct <|>S(u32); fn f() -> (S, S) { (S { a: 1, c: 2 }, S(3)) }",
+        );
+    }
+
+    #[test]
+    fn convert_struct_target() {
+        check_assist_target(
+            convert_named_struct_to_tuple_struct,
+            "struct <|>S { a: u32 } fn f() {}",
+            "struct S { a: u32 }",
+        );
+    }
+}
@@ -0,0 +1,297 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, make, AstNode, ModuleItemOwner, NameOwner, VisibilityOwner},
+    NodeOrToken, SmolStr,
+    SyntaxKind::WHITESPACE,
+    SyntaxNode, TextRange,
+};
+use ra_text_edit::TextEditBuilder;
+
+use crate::{assists::add_import::auto_import_text_edit, Assist, AssistCtx, AssistId};
+
+// Assist: extract_import_to_top
+//
+// Moves a `use` of a function body to the imports of the enclosing module.
+//
+// ```
+// mod foo { pub struct Bar; }
+//
+// fn main() {
+//     use foo::Bar<|>;
+//     let bar = Bar;
+// }
+// ```
+// ->
+// ```
+// use foo::Bar;
+//
+// mod foo { pub struct Bar; }
+//
+// fn main() {
+//     let bar = Bar;
+// }
+// ```
+pub(crate) fn extract_import_to_top(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let use_item = ctx.find_node_at_offset::<ast::UseItem>()?;
+    // the imports of modules are already at the top
+    ast::Block::cast(use_item.syntax().parent()?)?;
+    if use_item.visibility().is_some() {
+        return None;
+    }
+    let tree = use_item.use_tree()?;
+    if tree.use_tree_list().is_some() || tree.has_star() || tree.alias().is_some() {
+        return None;
+    }
+    let path = tree.path()?;
+    let segments = path_segments(&path)?;
+    let container = use_item.syntax().ancestors().find_map(|node| {
+        if let Some(module) = ast::Module::cast(node.clone()) {
+            return module.item_list().map(|it| it.syntax().clone());
+        }
+        ast::SourceFile::cast(node).map(|it| it.syntax().clone())
+    })?;
+
+    // The import has to mean the same at the top of the module: it can't
+    // refer to the items of the function, and its name can't be already
+    // taken there, that would change the meaning of the rest of the module.
+    if is_local_item(&use_item, &container, &segments[0]) {
+        return None;
+    }
+    let analyzer = ctx.source_analyzer(&container, None);
+    let resolution = analyzer.resolve_path(ctx.db, &path)?;
+    let name_ref = path.segment()?.name_ref()?;
+    match analyzer.resolve_path(ctx.db, &make::path_from_name_ref(name_ref)) {
+        Some(existing) if existing != resolution => return None,
+        _ => (),
+    }
+
+    let delete_range = match use_item.syntax().prev_sibling_or_token() {
+        Some(NodeOrToken::Token(ws)) if ws.kind() == WHITESPACE => {
+            TextRange::from_to(ws.text_range().start(), use_item.syntax().text_range().end())
+        }
+        _ => use_item.syntax().text_range(),
+    };
+
+    ctx.add_assist(AssistId("extract_import_to_top"), "move import to the top", |edit| {
+        edit.target(use_item.syntax().text_range());
+        let mut import_edit = TextEditBuilder::default();
+        auto_import_text_edit(use_item.syntax(), use_item.syntax(), &segments, &mut import_edit);
+        let import_edit = import_edit.finish();
+        // the import is added above the function, which shifts the cursor
+        if let Some(cursor) = import_edit.apply_to_offset(delete_range.start()) {
+            edit.set_cursor(cursor);
+        }
+        for atom in import_edit.as_atoms() {
+            edit.replace(atom.delete, atom.insert.clone());
+        }
+        edit.delete(delete_range);
+    })
+}
+
+/// The segments of a use path, as written.
+fn path_segments(path: &ast::Path) -> Option<Vec<SmolStr>> {
+    let mut segments = Vec::new();
+    let mut path = Some(path.clone());
+    while let Some(it) = path {
+        let segment = match it.segment()?.kind()? {
+            ast::PathSegmentKind::Name(name_ref) => name_ref.text().clone(),
+            ast::PathSegmentKind::SelfKw => "self".into(),
+            ast::PathSegmentKind::SuperKw => "super".into(),
+            ast::PathSegmentKind::CrateKw => "crate".into(),
+            ast::PathSegmentKind::Type { .. } => return None,
+        };
+        segments.push(segment);
+        path = it.qualifier();
+    }
+    segments.reverse();
+    Some(segments)
+}
+
+/// Whether `name` is an item declared in one of the blocks around the import,
+/// which can't be seen from the module.
+fn is_local_item(use_item: &ast::UseItem, container: &SyntaxNode, name: &str) -> bool {
+    use_item
+        .syntax()
+        .ancestors()
+        .take_while(|it| it != container)
+        .filter_map(ast::Block::cast)
+        .flat_map(|block| block.items())
+        .filter(|item| item.syntax() != use_item.syntax())
+        .any(|item| item_name(&item).map_or(false, |it| it.as_str() == name))
+}
+
+fn item_name(item: &ast::ModuleItem) -> Option<SmolStr> {
+    let name = match item {
+        ast::ModuleItem::StructDef(it) => it.name(),
+        ast::ModuleItem::UnionDef(it) => it.name(),
+        ast::ModuleItem::EnumDef(it) => it.name(),
+        ast::ModuleItem::FnDef(it) => it.name(),
+        ast::ModuleItem::TraitDef(it) => it.name(),
+        ast::ModuleItem::TypeAliasDef(it) => it.name(),
+        ast::ModuleItem::ConstDef(it) => it.name(),
+        ast::ModuleItem::StaticDef(it) => it.name(),
+        ast::ModuleItem::Module(it) => it.name(),
+        ast::ModuleItem::UseItem(it) => {
+            let tree = it.use_tree()?;
+            return match tree.alias() {
+                Some(alias) => alias.name().map(|it| it.text().clone()),
+                None => tree.path()?.segment()?.name_ref().map(|it| it.text().clone()),
+            };
+        }
+        _ => None,
+    };
+    name.map(|it| it.text().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn extract_import_to_new_use() {
+        check_assist(
+            extract_import_to_top,
+            "
+mod foo { pub struct Bar; }
+
+fn main() {
+    let x = 1;
+    use foo::Bar<|>;
+}
+",
+            "
+use foo::Bar;
+
+mod foo { pub struct Bar; }
+
+fn main() {
+    let x = 1;<|>
+}
+",
+        );
+    }
+
+    #[test]
+    fn extract_import_merges_with_existing_imports() {
+        check_assist(
+            extract_import_to_top,
+            "
+use foo::Baz;
+
+mod foo { pub struct Bar; pub struct Baz; }
+
+fn main() {
+    use foo::<|>Bar;
+    let bar = Bar;
+}
+",
+            "
+use foo::{Bar, Baz};
+
+mod foo { pub struct Bar; pub struct Baz; }
+
+fn main() {<|>
+    let bar = Bar;
+}
+",
+        );
+    }
+
+    #[test]
+    fn extract_import_to_the_enclosing_module() {
+        check_assist(
+            extract_import_to_top,
+            "
+mod m {
+    struct S;
+
+    fn f() {
+        use super::foo<|>::Bar;
+    }
+}
+mod foo { pub struct Bar; }
+",
+            "
+mod m {
+    use super::foo::Bar;
+
+    struct S;
+
+    fn f() {<|>
+    }
+}
+mod foo { pub struct Bar; }
+",
+        );
+    }
+
+    #[test]
+    fn extract_import_not_applicable_if_meaning_changes() {
+        // the path starts with an item of the function
+        check_assist_not_applicable(
+            extract_import_to_top,
+            "
+fn main() {
+    mod foo { pub struct Bar; }
+    use foo::Bar<|>;
+}
+mod foo { pub struct Bar; }
+",
+        );
+        // the name is already taken in the module
+        check_assist_not_applicable(
+            extract_import_to_top,
+            "
+mod foo { pub struct Bar; }
+struct Bar;
+
+fn main() {
+    use foo::Bar<|>;
+}
+",
+        );
+        // the path doesn't resolve
+        check_assist_not_applicable(
+            extract_import_to_top,
+            "
+fn main() {
+    use foo::Bar<|>;
+}
+",
+        );
+    }
+
+    #[test]
+    fn extract_import_not_applicable_outside_of_functions() {
+        check_assist_not_applicable(
+            extract_import_to_top,
+            "
+mod foo { pub struct Bar; }
+use foo::Bar<|>;
+",
+        );
+        check_assist_not_applicable(
+            extract_import_to_top,
+            "
+mod foo { pub struct Bar; pub struct Baz; }
+fn main() {
+    use foo::{Bar, Baz<|>};
+}
+",
+        );
+    }
+
+    #[test]
+    fn extract_import_target() {
+        check_assist_target(
+            extract_import_to_top,
+            "
+mod foo { pub struct Bar; }
+fn main() { use foo::Bar<|>; }
+",
+            "use foo::Bar;",
+        );
+    }
+}
@@ -0,0 +1,407 @@
+use hir::{db::HirDatabase, FromSource, InFile, Module, ModuleSource, SourceAnalyzer};
+use ra_db::FileId;
+use ra_syntax::{
+    algo::find_node_at_offset,
+    ast::{self, edit::IndentLevel, AstNode, NameOwner, TypeParamsOwner, VisibilityOwner},
+    SyntaxNode, TextRange, TextUnit,
+};
+use ra_text_edit::TextEditBuilder;
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: extract_struct_from_enum_variant
+//
+// Extracts the fields of an enum variant into a new struct, which the variant
+// holds instead.
+//
+// ```
+// enum A { <|>One { a: u32, b: u32 } }
+// ```
+// ->
+// ```
+// struct One { a: u32, b: u32 }
+//
+// enum A { One(One) }
+// ```
+pub(crate) fn extract_struct_from_enum_variant(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let variant = ctx.find_node_at_offset::<ast::EnumVariant>()?;
+    let field_list = match variant.kind() {
+        ast::StructKind::Record(it) => it,
+        _ => return None,
+    };
+    if ctx.frange.range.start() >= field_list.syntax().text_range().start() {
+        return None;
+    }
+    let name = variant.name()?;
+    let enum_def = variant.parent_enum();
+    // The fields could use the generic parameters of the enum
+    if enum_def.type_param_list().is_some() {
+        return None;
+    }
+
+    let file_id = ctx.frange.file_id;
+    let variant_hir =
+        hir::EnumVariant::from_source(ctx.db, InFile::new(file_id.into(), variant.clone()))?;
+    let enum_module = variant_hir.module(ctx.db);
+    // The struct would conflict with the items of the same name, including the
+    // variant itself when it's imported
+    let struct_name = variant_hir.name(ctx.db);
+    if enum_module.scope(ctx.db, None).iter().any(|(name, _)| *name == struct_name) {
+        return None;
+    }
+
+    let db = ctx.db;
+    ctx.add_assist(
+        AssistId("extract_struct_from_enum_variant"),
+        "extract struct from enum variant",
+        |edit| {
+            edit.target(variant.syntax().text_range());
+
+            let vis = enum_def.visibility().map(|it| format!("{} ", it.syntax()));
+            let vis = vis.as_ref().map_or("", |it| it.as_str());
+            let indent = IndentLevel::from_node(enum_def.syntax());
+            let struct_def = format!(
+                "{}struct {} {}\n\n{:indent$}",
+                vis,
+                name.syntax(),
+                struct_field_list(field_list.clone(), vis),
+                "",
+                indent = indent.0 as usize * 4
+            );
+            edit.insert(enum_def.syntax().text_range().start(), struct_def);
+            edit.replace(
+                TextRange::from_to(
+                    name.syntax().text_range().end(),
+                    field_list.syntax().text_range().end(),
+                ),
+                format!("({})", name.syntax()),
+            );
+
+            let name = struct_name.to_string();
+            for (usage_file_id, name_refs) in find_name_refs(db, enum_module, &name) {
+                let builder = if usage_file_id == file_id {
+                    edit.text_edit_builder()
+                } else {
+                    edit.other_file_edit_builder(usage_file_id)
+                };
+                update_usages(db, usage_file_id, &name_refs, variant_hir, enum_module, builder);
+            }
+        },
+    )
+}
+
+/// The field list of the variant, one level less indented, with the visibility
+/// of the enum added to the fields.
+fn struct_field_list(field_list: ast::RecordFieldDefList, vis: &str) -> String {
+    let field_list = IndentLevel(1).decrease_indent(field_list);
+    let mut res = field_list.syntax().to_string();
+    if vis.is_empty() {
+        return res;
+    }
+    let start = field_list.syntax().text_range().start();
+    let mut offsets: Vec<TextUnit> = field_list
+        .fields()
+        .filter_map(|it| it.name())
+        .map(|it| it.syntax().text_range().start() - start)
+        .collect();
+    offsets.reverse();
+    for offset in offsets {
+        res.insert_str(offset.to_usize(), vis);
+    }
+    res
+}
+
+/// Wraps the fields of the record literals and patterns of the variant into the
+/// new struct, like `E::V(V { a, b })`. The literals and patterns are the ones
+/// among the parents of the paths ending with `name_refs`.
+fn update_usages(
+    db: &impl HirDatabase,
+    file_id: FileId,
+    name_refs: &[ast::NameRef],
+    variant: hir::EnumVariant,
+    enum_module: Module,
+    builder: &mut TextEditBuilder,
+) {
+    let is_variant = |def: Option<hir::VariantDef>| match def {
+        Some(hir::VariantDef::EnumVariant(it)) => it == variant,
+        _ => false,
+    };
+    for node in name_refs.iter().filter_map(path_parent) {
+        let (path, fields_start) = if let Some(lit) = ast::RecordLit::cast(node.clone()) {
+            let analyzer = SourceAnalyzer::new(db, InFile::new(file_id.into(), &node), None);
+            if !is_variant(analyzer.resolve_record_literal(&lit)) {
+                continue;
+            }
+            match (lit.path(), lit.record_field_list()) {
+                (Some(path), Some(fields)) => (path, fields.syntax().text_range().start()),
+                _ => continue,
+            }
+        } else if let Some(pat) = ast::RecordPat::cast(node.clone()) {
+            let analyzer = SourceAnalyzer::new(db, InFile::new(file_id.into(), &node), None);
+            if !is_variant(analyzer.resolve_record_pattern(&pat)) {
+                continue;
+            }
+            match (pat.path(), pat.record_field_pat_list()) {
+                (Some(path), Some(fields)) => (path, fields.syntax().text_range().start()),
+                _ => continue,
+            }
+        } else {
+            continue;
+        };
+
+        let module_src = ModuleSource::from_child_node(db, InFile::new(file_id.into(), &node));
+        let module = Module::from_definition(db, InFile::new(file_id.into(), module_src));
+        let struct_path = struct_path(db, variant, enum_module, module);
+        builder.replace(
+            TextRange::from_to(path.syntax().text_range().end(), fields_start),
+            format!("({} ", struct_path),
+        );
+        builder.insert(node.text_range().end(), ")".to_string());
+    }
+}
+
+/// The path to the new struct from `module`: the struct is defined next to the
+/// enum.
+fn struct_path(
+    db: &impl HirDatabase,
+    variant: hir::EnumVariant,
+    enum_module: Module,
+    module: Option<Module>,
+) -> String {
+    let name = variant.name(db).to_string();
+    if module == Some(enum_module) {
+        return name;
+    }
+    let mut segments = vec!["crate".to_string()];
+    let mut modules = enum_module.path_to_root(db);
+    modules.reverse();
+    segments.extend(modules.into_iter().filter_map(|it| it.name(db)).map(|it| it.to_string()));
+    segments.push(name);
+    segments.join("::")
+}
+
+/// The parent of the path whose last segment is `name_ref`.
+pub(crate) fn path_parent(name_ref: &ast::NameRef) -> Option<SyntaxNode> {
+    let path = name_ref.syntax().parent().and_then(|it| it.parent()).and_then(ast::Path::cast)?;
+    path.syntax().parent()
+}
+
+/// The name references with the text `name` in the files of the crate of
+/// `module`. As in the reference search, only the files in which the text
+/// occurs are parsed, and the callers only resolve the references found.
+pub(crate) fn find_name_refs(
+    db: &impl HirDatabase,
+    module: Module,
+    name: &str,
+) -> Vec<(FileId, Vec<ast::NameRef>)> {
+    let mut res = Vec::new();
+    for file_id in crate_files(db, module) {
+        let text = ra_db::FileLoader::file_text(db, file_id);
+        if !text.contains(name) {
+            continue;
+        }
+        let file = db.parse(file_id).tree();
+        let name_refs: Vec<ast::NameRef> = text
+            .match_indices(name)
+            .filter_map(|(idx, _)| {
+                let offset = TextUnit::from_usize(idx);
+                find_node_at_offset::<ast::NameRef>(file.syntax(), offset)
+                    .filter(|it| it.syntax().text_range().start() == offset)
+            })
+            .filter(|it| it.text().as_str() == name)
+            .collect();
+        if !name_refs.is_empty() {
+            res.push((file_id, name_refs));
+        }
+    }
+    res
+}
+
+fn crate_files(db: &impl HirDatabase, module: Module) -> Vec<FileId> {
+    let mut res = Vec::new();
+    let mut stack = vec![module.crate_root(db)];
+    while let Some(module) = stack.pop() {
+        let file_id = module.definition_source(db).file_id.original_file(db);
+        if !res.contains(&file_id) {
+            res.push(file_id);
+        }
+        stack.extend(module.children(db));
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use ra_db::{fixture::WithFixture, FileRange, SourceDatabaseExt};
+    use test_utils::assert_eq_text;
+
+    use super::*;
+    use crate::{
+        helpers::{check_assist, check_assist_in_fixture, check_assist_not_applicable},
+        test_db::TestDB,
+    };
+
+    #[test]
+    fn extract_struct_several_fields() {
+        check_assist(
+            extract_struct_from_enum_variant,
+            "enum A { <|>One { foo: u32, bar: u32 } }",
+            "struct One { foo: u32, bar: u32 }
+
+enum A { <|>One(One) }",
+        );
+    }
+
+    #[test]
+    fn extract_struct_multiline_and_pub() {
+        check_assist(
+            extract_struct_from_enum_variant,
+            r#"
+mod m {
+    pub enum A {
+        <|>One {
+            /// The foo
+            foo: u32,
+        },
+        Two,
+    }
+}"#,
+            r#"
+mod m {
+    pub struct One {
+        /// The foo
+        pub foo: u32,
+    }
+
+    pub enum A {
+        <|>One(One),
+        Two,
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn extract_struct_updates_literals_and_patterns() {
+        check_assist(
+            extract_struct_from_enum_variant,
+            r#"
+enum A { <|>One { foo: u32 }, Two }
+fn f(foo: u32) -> A {
+    let a = A::One { foo };
+    match a {
+        A::One { foo, .. } => A::One { foo: foo + 1 },
+        A::Two => A::Two,
+    }
+}"#,
+            r#"
+struct One { foo: u32 }
+
+enum A { <|>One(One), Two }
+fn f(foo: u32) -> A {
+    let a = A::One(One { foo });
+    match a {
+        A::One(One { foo, .. }) => A::One(One { foo: foo + 1 }),
+        A::Two => A::Two,
+    }
+}"#,
+        );
+    }
+
+    #[test]
+    fn extract_struct_only_updates_usages_of_the_variant() {
+        check_assist(
+            extract_struct_from_enum_variant,
+            r#"
+enum A { <|>One { foo: u32 } }
+enum B { One { foo: u32 } }
+fn f(foo: u32) -> (A, B) {
+    (A::One { foo }, B::One { foo })
+}"#,
+            r#"
+struct One { foo: u32 }
+
+enum A { <|>One(One) }
+enum B { One { foo: u32 } }
+fn f(foo: u32) -> (A, B) {
+    (A::One(One { foo }), B::One { foo })
+}"#,
+        );
+    }
+
+    #[test]
+    fn extract_struct_updates_usages_in_other_files() {
+        let fixture = r#"
+//- /main.rs
+mod a;
+mod b;
+//- /a.rs
+pub enum A { <|>One { foo: u32 } }
+//- /b.rs
+use crate::a::A;
+fn f() -> A {
+    A::One { foo: 92 }
+}
+"#;
+        check_assist_in_fixture(
+            extract_struct_from_enum_variant,
+            fixture,
+            r#"pub struct One { pub foo: u32 }
+
+pub enum A { One(One) }
+"#,
+        );
+
+        let (db, position) = TestDB::with_position(fixture);
+        let frange = FileRange {
+            file_id: position.file_id,
+            range: TextRange::offset_len(position.offset, 0.into()),
+        };
+        let action = match AssistCtx::with_ctx(&db, frange, true, extract_struct_from_enum_variant)
+        {
+            Some(Assist::Resolved { action, .. }) => action,
+            _ => panic!("code action is not applicable"),
+        };
+        assert_eq!(action.other_file_edits.len(), 1);
+        let (file_id, edit) = &action.other_file_edits[0];
+        let actual = edit.apply(&db.file_text(*file_id));
+        assert_eq_text!(
+            r#"use crate::a::A;
+fn f() -> A {
+    A::One(crate::a::One { foo: 92 })
+}
+"#,
+            &actual
+        );
+    }
+
+    #[test]
+    fn extract_struct_not_applicable_to_tuple_and_unit_variants() {
+        check_assist_not_applicable(extract_struct_from_enum_variant, "enum A { <|>One(u32) }");
+        check_assist_not_applicable(extract_struct_from_enum_variant, "enum A { <|>One }");
+    }
+
+    #[test]
+    fn extract_struct_not_applicable_in_fields() {
+        check_assist_not_applicable(
+            extract_struct_from_enum_variant,
+            "enum A { One { <|>foo: u32 } }",
+        );
+    }
+
+    #[test]
+    fn extract_struct_not_applicable_with_conflicting_name() {
+        check_assist_not_applicable(
+            extract_struct_from_enum_variant,
+            "struct One; enum A { <|>One { foo: u32 } }",
+        );
+    }
+
+    #[test]
+    fn extract_struct_not_applicable_to_generic_enums() {
+        check_assist_not_applicable(
+            extract_struct_from_enum_variant,
+            "enum A<T> { <|>One { foo: T } }",
+        );
+    }
+}
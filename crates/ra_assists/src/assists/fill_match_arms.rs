@@ -2,7 +2,7 @@
 
 use std::iter;
 
-use hir::{db::HirDatabase, Adt, HasSource};
+use hir::{db::HirDatabase, Adt, HasAttrs, HasSource};
 use ra_syntax::ast::{self, edit::IndentLevel, make, AstNode, NameOwner};
 
 use crate::{Assist, AssistCtx, AssistId};
@@ -46,7 +46,7 @@ pub(crate) fn fill_match_arms(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist
     };
 
     let expr = match_expr.expr()?;
-    let enum_def = {
+    let (enum_def, needs_wildcard) = {
         let analyzer = ctx.source_analyzer(expr.syntax(), None);
         resolve_enum_def(ctx.db, &analyzer, &expr)?
     };
@@ -57,8 +57,10 @@ pub(crate) fn fill_match_arms(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist
 
         let new_arm_list = {
             let variants = variant_list.variants();
+            let wildcard = if needs_wildcard { Some(make::placeholder_pat().into()) } else { None };
             let arms = variants
                 .filter_map(build_pat)
+                .chain(wildcard)
                 .map(|pat| make::match_arm(iter::once(pat), make::expr_unit()));
             indent_level.increase_indent(make::match_arm_list(arms))
         };
@@ -76,15 +78,21 @@ fn is_trivial(arm: &ast::MatchArm) -> bool {
     })
 }
 
-fn resolve_enum_def(
+/// Returns the matched enum, and whether a wildcard arm is needed because the
+/// enum is `#[non_exhaustive]` and defined in another crate.
+pub(crate) fn resolve_enum_def(
     db: &impl HirDatabase,
     analyzer: &hir::SourceAnalyzer,
     expr: &ast::Expr,
-) -> Option<ast::EnumDef> {
+) -> Option<(ast::EnumDef, bool)> {
     let expr_ty = analyzer.type_of(db, &expr)?;
 
     let res = expr_ty.autoderef(db).find_map(|ty| match ty.as_adt() {
-        Some(Adt::Enum(e)) => Some(e.source(db).value),
+        Some(Adt::Enum(e)) => {
+            let needs_wildcard =
+                e.attrs(db).by_key("non_exhaustive").exists() && e.krate(db) != Some(ty.krate());
+            Some((e.source(db).value, needs_wildcard))
+        }
         _ => None,
     });
     res
@@ -114,7 +122,7 @@ fn build_pat(var: ast::EnumVariant) -> Option<ast::Pat> {
 
 #[cfg(test)]
 mod tests {
-    use crate::helpers::{check_assist, check_assist_target};
+    use crate::helpers::{check_assist, check_assist_in_fixture, check_assist_target};
 
     use super::fill_match_arms;
 
@@ -252,4 +260,60 @@ mod tests {
             "#,
         );
     }
+
+    #[test]
+    fn fill_match_arms_adds_wildcard_for_foreign_non_exhaustive_enum() {
+        check_assist_in_fixture(
+            fill_match_arms,
+            r#"
+//- /main.rs crate:main deps:dep
+fn main() {
+    match dep::E::X {
+        <|>
+    }
+}
+
+//- /dep.rs crate:dep
+#[non_exhaustive]
+pub enum E { X, Y }
+"#,
+            r#"fn main() {
+    match <|>dep::E::X {
+        E::X => (),
+        E::Y => (),
+        _ => (),
+    }
+}
+
+"#,
+        );
+    }
+
+    #[test]
+    fn fill_match_arms_ignores_local_non_exhaustive_enum() {
+        check_assist(
+            fill_match_arms,
+            r#"
+            #[non_exhaustive]
+            enum E { X, Y }
+
+            fn main() {
+                match E::X {
+                    <|>
+                }
+            }
+            "#,
+            r#"
+            #[non_exhaustive]
+            enum E { X, Y }
+
+            fn main() {
+                match <|>E::X {
+                    E::X => (),
+                    E::Y => (),
+                }
+            }
+            "#,
+        );
+    }
 }
@@ -1,5 +1,5 @@
 use hir::db::HirDatabase;
-use ra_syntax::{algo::non_trivia_sibling, Direction, T};
+use ra_syntax::{algo::non_trivia_sibling, Direction, SyntaxKind::LIFETIME_PARAM, T};
 
 use crate::{Assist, AssistCtx, AssistId};
 
@@ -28,6 +28,10 @@ pub(crate) fn flip_comma(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
     if next.kind().is_punct() {
         return None;
     }
+    // The lifetime parameters come before the type parameters
+    if (prev.kind() == LIFETIME_PARAM) != (next.kind() == LIFETIME_PARAM) {
+        return None;
+    }
 
     ctx.add_assist(AssistId("flip_comma"), "flip comma", |edit| {
         edit.target(comma.text_range());
@@ -40,7 +44,7 @@ pub(crate) fn flip_comma(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
 mod tests {
     use super::*;
 
-    use crate::helpers::{check_assist, check_assist_target};
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
 
     #[test]
     fn flip_comma_works_for_function_parameters() {
@@ -51,6 +55,34 @@ mod tests {
         )
     }
 
+    #[test]
+    fn flip_comma_works_for_generic_parameters() {
+        check_assist(
+            flip_comma,
+            "struct S<'a, 'b, T: Clone,<|> U = u8>(&'a T, &'b U);",
+            "struct S<'a, 'b, U = u8,<|> T: Clone>(&'a T, &'b U);",
+        );
+        check_assist(
+            flip_comma,
+            "fn foo<T, U>() where T: Copy,<|> U: Into<T> {}",
+            "fn foo<T, U>() where U: Into<T>,<|> T: Copy {}",
+        );
+    }
+
+    #[test]
+    fn flip_comma_keeps_attributes_of_fields() {
+        check_assist(
+            flip_comma,
+            "struct S { #[doc(hidden)] a: u8,<|> pub b: u32 }",
+            "struct S { pub b: u32,<|> #[doc(hidden)] a: u8 }",
+        );
+    }
+
+    #[test]
+    fn flip_comma_not_applicable_between_lifetime_and_type_parameters() {
+        check_assist_not_applicable(flip_comma, "struct S<'a,<|> T>(&'a T);");
+    }
+
     #[test]
     fn flip_comma_target() {
         check_assist_target(flip_comma, "fn foo(x: i32,<|> y: Result<(), ()>) {}", ",")
@@ -0,0 +1,390 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, AstNode, LoopBodyOwner},
+    SyntaxKind::{AWAIT_EXPR, BREAK_EXPR, CONTINUE_EXPR, RETURN_EXPR, TRY_EXPR},
+    SyntaxNode, T,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: convert_for_loop_to_iterator_chain
+//
+// Converts a `for` loop which pushes the items of an iterable into a vector to
+// an iterator chain.
+//
+// ```
+// fn main() {
+//     let mut out = Vec::new();
+//     <|>for x in xs {
+//         out.push(x * 2);
+//     }
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let mut out = Vec::new();
+//     out.extend(xs.into_iter().map(|x| x * 2));
+// }
+// ```
+pub(crate) fn convert_for_loop_to_iterator_chain(
+    ctx: AssistCtx<impl HirDatabase>,
+) -> Option<Assist> {
+    let for_expr = ctx.find_node_at_offset::<ast::ForExpr>()?;
+    // only offer the assist on the head of the loop, not inside of its body
+    let body = for_expr.loop_body()?;
+    if ctx.frange.range.start() >= body.syntax().text_range().start() {
+        return None;
+    }
+    let pat = for_expr.pat()?;
+    let iterable = for_expr.iterable()?;
+    if body.syntax().descendants().any(|it| is_control_flow_or_assignment(&it)) {
+        return None;
+    }
+
+    let (cond, push) = match single_expr(body.block()?)? {
+        ast::Expr::IfExpr(if_expr) => {
+            if if_expr.else_branch().is_some() {
+                return None;
+            }
+            let cond = if_expr.condition()?;
+            if cond.pat().is_some() {
+                return None;
+            }
+            let push = match single_expr(if_expr.then_branch()?.block()?)? {
+                ast::Expr::MethodCallExpr(it) => it,
+                _ => return None,
+            };
+            (Some(cond.expr()?), push)
+        }
+        ast::Expr::MethodCallExpr(it) => (None, it),
+        _ => return None,
+    };
+    let (out, item) = push_call(&push)?;
+    if mentions(cond.as_ref().map(|it| it.syntax()), &out) || mentions(Some(item.syntax()), &out) {
+        return None;
+    }
+
+    let mut chain = String::new();
+    if let Some(cond) = &cond {
+        // `filter` gets a reference to the item, which can only be
+        // dereferenced by the pattern if the items are `Copy`
+        let name = match &pat {
+            ast::Pat::BindPat(it) if !it.is_mutable() && !it.is_ref() && it.pat().is_none() => {
+                it.name()?
+            }
+            _ => return None,
+        };
+        if !has_copy_items(&iterable) {
+            return None;
+        }
+        chain.push_str(&format!(".filter(|&{}| {})", name.syntax(), cond.syntax()));
+    }
+    let is_identity = item.syntax().to_string() == pat.syntax().to_string();
+    if !is_identity {
+        chain.push_str(&format!(".map(|{}| {})", pat.syntax(), item.syntax()));
+    }
+    let chain = if chain.is_empty() {
+        iterable.syntax().to_string()
+    } else {
+        format!("{}{}", iterator(&iterable), chain)
+    };
+
+    ctx.add_assist(
+        AssistId("convert_for_loop_to_iterator_chain"),
+        "convert for loop to iterator chain",
+        |edit| {
+            let range = for_expr.syntax().text_range();
+            let has_semicolon =
+                for_expr.syntax().next_sibling_or_token().map_or(false, |it| it.kind() == T![;]);
+            let semicolon = if has_semicolon { "" } else { ";" };
+            edit.target(range);
+            edit.replace(range, format!("{}.extend({}){}", out.syntax(), chain, semicolon));
+            edit.set_cursor(range.start());
+        },
+    )
+}
+
+/// The only expression of a block, like `foo()` in `{ foo(); }`.
+fn single_expr(block: ast::Block) -> Option<ast::Expr> {
+    let mut statements = block.statements();
+    match (statements.next(), block.expr()) {
+        (None, Some(expr)) => Some(expr),
+        (Some(ast::Stmt::ExprStmt(stmt)), None) if statements.next().is_none() => stmt.expr(),
+        _ => None,
+    }
+}
+
+/// Splits `out.push(item)` into the vector and the pushed item.
+fn push_call(call: &ast::MethodCallExpr) -> Option<(ast::PathExpr, ast::Expr)> {
+    if call.name_ref()?.text() != "push" {
+        return None;
+    }
+    let out = match call.expr()? {
+        ast::Expr::PathExpr(it) => it,
+        _ => return None,
+    };
+    let mut args = call.arg_list()?.args();
+    let item = args.next()?;
+    if args.next().is_some() {
+        return None;
+    }
+    Some((out, item))
+}
+
+/// The loop body may only accumulate items, so anything leaving the loop or
+/// assigning to places is rejected.
+fn is_control_flow_or_assignment(node: &SyntaxNode) -> bool {
+    match node.kind() {
+        BREAK_EXPR | CONTINUE_EXPR | RETURN_EXPR | TRY_EXPR | AWAIT_EXPR => return true,
+        _ => (),
+    }
+    let op = match ast::BinExpr::cast(node.clone()).and_then(|it| it.op_kind()) {
+        Some(it) => it,
+        None => return false,
+    };
+    match op {
+        ast::BinOp::Assignment
+        | ast::BinOp::AddAssign
+        | ast::BinOp::DivAssign
+        | ast::BinOp::MulAssign
+        | ast::BinOp::RemAssign
+        | ast::BinOp::ShrAssign
+        | ast::BinOp::ShlAssign
+        | ast::BinOp::SubAssign
+        | ast::BinOp::BitOrAssign
+        | ast::BinOp::BitAndAssign
+        | ast::BinOp::BitXorAssign => true,
+        _ => false,
+    }
+}
+
+/// Whether `node` mentions the vector, which the closures of the chain can't
+/// borrow while it is being extended.
+fn mentions(node: Option<&SyntaxNode>, out: &ast::PathExpr) -> bool {
+    let node = match node {
+        Some(it) => it,
+        None => return false,
+    };
+    let out = out.syntax().to_string();
+    node.descendants().filter_map(ast::PathExpr::cast).any(|it| it.syntax().to_string() == out)
+}
+
+/// Whether the items of the iterable are known to be `Copy` without looking at
+/// types: shared references, and the integers of ranges.
+fn has_copy_items(iterable: &ast::Expr) -> bool {
+    match iterable {
+        ast::Expr::RefExpr(it) => !it.is_mut(),
+        ast::Expr::RangeExpr(_) => true,
+        ast::Expr::MethodCallExpr(call) => call.name_ref().map_or(false, |it| it.text() == "iter"),
+        _ => false,
+    }
+}
+
+/// The iterator over the items of the iterable, like `xs.iter()` for `&xs`.
+fn iterator(iterable: &ast::Expr) -> String {
+    match iterable {
+        ast::Expr::RefExpr(it) => match it.expr() {
+            Some(inner) => {
+                let method = if it.is_mut() { "iter_mut" } else { "iter" };
+                format!("{}.{}()", receiver(&inner), method)
+            }
+            None => iterable.syntax().to_string(),
+        },
+        // ranges are iterators themselves
+        ast::Expr::RangeExpr(_) => receiver(iterable),
+        ast::Expr::MethodCallExpr(call)
+            if call.name_ref().map_or(false, |it| {
+                it.text() == "iter" || it.text() == "iter_mut" || it.text() == "into_iter"
+            }) =>
+        {
+            iterable.syntax().to_string()
+        }
+        _ => format!("{}.into_iter()", receiver(iterable)),
+    }
+}
+
+/// The text of `expr` as the receiver of a method call, parenthesized if
+/// needed.
+fn receiver(expr: &ast::Expr) -> String {
+    match expr {
+        ast::Expr::PathExpr(_)
+        | ast::Expr::FieldExpr(_)
+        | ast::Expr::MethodCallExpr(_)
+        | ast::Expr::CallExpr(_)
+        | ast::Expr::IndexExpr(_)
+        | ast::Expr::ParenExpr(_)
+        | ast::Expr::ArrayExpr(_)
+        | ast::Expr::MacroCall(_) => expr.syntax().to_string(),
+        _ => format!("({})", expr.syntax()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn convert_push_of_mapped_items() {
+        check_assist(
+            convert_for_loop_to_iterator_chain,
+            "
+fn main() {
+    let mut out = Vec::new();
+    for<|> x in xs {
+        out.push(f(x));
+    }
+}",
+            "
+fn main() {
+    let mut out = Vec::new();
+    <|>out.extend(xs.into_iter().map(|x| f(x)));
+}",
+        );
+    }
+
+    #[test]
+    fn convert_push_of_items_of_reference() {
+        check_assist(
+            convert_for_loop_to_iterator_chain,
+            "
+fn main() {
+    <|>for (a, b) in &self.pairs { out.push(a + b) }
+}",
+            "
+fn main() {
+    <|>out.extend(self.pairs.iter().map(|(a, b)| a + b));
+}",
+        );
+    }
+
+    #[test]
+    fn convert_push_of_unchanged_items() {
+        check_assist(
+            convert_for_loop_to_iterator_chain,
+            "
+fn main() {
+    <|>for x in a.b() {
+        out.push(x);
+    }
+}",
+            "
+fn main() {
+    <|>out.extend(a.b());
+}",
+        );
+    }
+
+    #[test]
+    fn convert_conditional_push() {
+        check_assist(
+            convert_for_loop_to_iterator_chain,
+            "
+fn main() {
+    <|>for i in 0..n {
+        if i % 2 == 0 {
+            out.push(i);
+        }
+    }
+}",
+            "
+fn main() {
+    <|>out.extend((0..n).filter(|&i| i % 2 == 0));
+}",
+        );
+        check_assist(
+            convert_for_loop_to_iterator_chain,
+            "
+fn main() {
+    <|>for s in names.iter() {
+        if !s.is_empty() {
+            out.push(s.len());
+        }
+    }
+}",
+            "
+fn main() {
+    <|>out.extend(names.iter().filter(|&s| !s.is_empty()).map(|s| s.len()));
+}",
+        );
+    }
+
+    #[test]
+    fn convert_for_loop_to_iterator_chain_target() {
+        check_assist_target(
+            convert_for_loop_to_iterator_chain,
+            "
+fn main() {
+    <|>for x in xs { out.push(x) }
+}",
+            "for x in xs { out.push(x) }",
+        );
+    }
+
+    #[test]
+    fn convert_for_loop_not_applicable_to_other_bodies() {
+        check_assist_not_applicable(
+            convert_for_loop_to_iterator_chain,
+            "
+fn main() {
+    <|>for x in xs {
+        if x == 0 { break; }
+        out.push(x);
+    }
+}",
+        );
+        check_assist_not_applicable(
+            convert_for_loop_to_iterator_chain,
+            "
+fn main() {
+    <|>for x in xs {
+        count += 1;
+        out.push(x);
+    }
+}",
+        );
+        check_assist_not_applicable(
+            convert_for_loop_to_iterator_chain,
+            "
+fn main() {
+    <|>for x in xs {
+        out.push(f(x)?);
+    }
+}",
+        );
+        check_assist_not_applicable(
+            convert_for_loop_to_iterator_chain,
+            "
+fn main() {
+    <|>for x in xs {
+        out.push(out.len() + x);
+    }
+}",
+        );
+        check_assist_not_applicable(
+            convert_for_loop_to_iterator_chain,
+            "
+fn main() {
+    for x in xs {
+        out.push(<|>x);
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn convert_for_loop_not_applicable_to_filters_of_owned_items() {
+        check_assist_not_applicable(
+            convert_for_loop_to_iterator_chain,
+            "
+fn main() {
+    <|>for s in strings {
+        if !s.is_empty() {
+            out.push(s);
+        }
+    }
+}",
+        );
+    }
+}
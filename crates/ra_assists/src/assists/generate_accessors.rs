@@ -0,0 +1,209 @@
+use hir::db::HirDatabase;
+use ra_syntax::ast::{self, AstNode, NameOwner, TypeAscriptionOwner};
+
+use crate::{
+    assists::add_new::{find_struct_impl, insert_method},
+    Assist, AssistCtx, AssistId,
+};
+
+// Assist: generate_getter
+//
+// Generates a getter for the field under the cursor. Fields of a `Copy` type
+// are returned by value, the others by reference.
+//
+// ```
+// struct Person {
+//     name<|>: String,
+// }
+// ```
+// ->
+// ```
+// struct Person {
+//     name: String,
+// }
+//
+// impl Person {
+//     pub fn name(&self) -> &String { &self.name }
+// }
+//
+// ```
+pub(crate) fn generate_getter(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let (strukt, field) = struct_field_at_offset(&ctx)?;
+    let field_name = field.name()?;
+    let field_ty = field.ascribed_type()?;
+    let impl_block = find_struct_impl(&ctx, &strukt, field_name.text())?;
+
+    let analyzer = ctx.source_analyzer(strukt.syntax(), None);
+    let is_copy = match analyzer.resolve_type(ctx.db, &field_ty) {
+        Some(ty) => !ty.contains_unknown() && analyzer.impls_copy(ctx.db, ty),
+        None => false,
+    };
+
+    ctx.add_assist(AssistId("generate_getter"), "generate getter", |edit| {
+        edit.target(field.syntax().text_range());
+        let method = if is_copy {
+            format!("pub fn {0}(&self) -> {1} {{ self.{0} }}", field_name.text(), field_ty.syntax())
+        } else {
+            format!(
+                "pub fn {0}(&self) -> &{1} {{ &self.{0} }}",
+                field_name.text(),
+                field_ty.syntax()
+            )
+        };
+        insert_method(edit, &strukt, impl_block, &method);
+    })
+}
+
+// Assist: generate_setter
+//
+// Generates a setter for the field under the cursor.
+//
+// ```
+// struct Person {
+//     name<|>: String,
+// }
+// ```
+// ->
+// ```
+// struct Person {
+//     name: String,
+// }
+//
+// impl Person {
+//     pub fn set_name(&mut self, name: String) { self.name = name; }
+// }
+//
+// ```
+pub(crate) fn generate_setter(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let (strukt, field) = struct_field_at_offset(&ctx)?;
+    let field_name = field.name()?;
+    let field_ty = field.ascribed_type()?;
+    let setter_name = format!("set_{}", field_name.text());
+    let impl_block = find_struct_impl(&ctx, &strukt, &setter_name)?;
+
+    ctx.add_assist(AssistId("generate_setter"), "generate setter", |edit| {
+        edit.target(field.syntax().text_range());
+        let method = format!(
+            "pub fn {0}(&mut self, {1}: {2}) {{ self.{1} = {1}; }}",
+            setter_name,
+            field_name.text(),
+            field_ty.syntax()
+        );
+        insert_method(edit, &strukt, impl_block, &method);
+    })
+}
+
+/// The named field under the cursor, with its struct. The fields of enum
+/// variants have no accessors.
+fn struct_field_at_offset(
+    ctx: &AssistCtx<impl HirDatabase>,
+) -> Option<(ast::StructDef, ast::RecordFieldDef)> {
+    let field = ctx.find_node_at_offset::<ast::RecordFieldDef>()?;
+    let strukt = ast::StructDef::cast(field.syntax().parent()?.parent()?)?;
+    Some((strukt, field))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::{
+        check_assist, check_assist_in_fixture, check_assist_not_applicable, check_assist_target,
+    };
+
+    #[test]
+    fn generate_getter_in_new_impl() {
+        check_assist(
+            generate_getter,
+            "struct S<T> { data<|>: Vec<T> }",
+            "struct S<T> { data: Vec<T> }
+
+impl<T> S<T> {
+    pub fn data(&self) -> &Vec<T> { &self.data }<|>
+}
+",
+        );
+    }
+
+    #[test]
+    fn generate_getter_in_existing_impl() {
+        check_assist(
+            generate_getter,
+            "struct S { a: u8, b<|>: String }
+
+impl S {
+    fn new() -> Self { unimplemented!() }
+}",
+            "struct S { a: u8, b: String }
+
+impl S {
+    pub fn b(&self) -> &String { &self.b }<|>
+    fn new() -> Self { unimplemented!() }
+}",
+        );
+    }
+
+    #[test]
+    fn generate_getter_returns_copy_types_by_value() {
+        check_assist_in_fixture(
+            generate_getter,
+            r#"
+//- /main.rs crate:main deps:std
+struct S { count<|>: u32 }
+
+//- /std/lib.rs crate:std
+pub mod marker {
+    #[lang = "copy"]
+    pub trait Copy {}
+    impl Copy for u32 {}
+}
+"#,
+            "struct S { count: u32 }
+
+impl S {
+    pub fn count(&self) -> u32 { self.count }<|>
+}
+
+
+",
+        );
+    }
+
+    #[test]
+    fn generate_setter_in_new_impl() {
+        check_assist(
+            generate_setter,
+            "struct S { data<|>: Vec<u8> }",
+            "struct S { data: Vec<u8> }
+
+impl S {
+    pub fn set_data(&mut self, data: Vec<u8>) { self.data = data; }<|>
+}
+",
+        );
+    }
+
+    #[test]
+    fn accessors_not_applicable_if_method_exists() {
+        let code = "struct S { data<|>: u8 }
+
+impl S {
+    fn data(&self) -> u8 { self.data }
+    fn set_data(&mut self, data: u8) { self.data = data; }
+}";
+        check_assist_not_applicable(generate_getter, code);
+        check_assist_not_applicable(generate_setter, code);
+    }
+
+    #[test]
+    fn accessors_not_applicable_outside_of_struct_fields() {
+        check_assist_not_applicable(generate_getter, "struct S(u8<|>);");
+        check_assist_not_applicable(generate_getter, "enum E { V { data<|>: u8 } }");
+        check_assist_not_applicable(generate_setter, "struct <|>S { data: u8 }");
+    }
+
+    #[test]
+    fn generate_getter_target() {
+        check_assist_target(generate_getter, "struct S { a: u8, b<|>: String }", "b: String");
+    }
+}
@@ -0,0 +1,190 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner, SelfParamKind, TypeParamsOwner},
+    SyntaxKind::{FN_POINTER_TYPE, PARAM_LIST},
+    SyntaxNode, SyntaxToken, TextUnit,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: introduce_named_lifetime
+//
+// Replaces the elided lifetime of a function signature with a named one.
+//
+// ```
+// fn first<|>(s: &str) -> &str {
+//     &s[..1]
+// }
+// ```
+// ->
+// ```
+// fn first<'a>(s: &'a str) -> &'a str {
+//     &s[..1]
+// }
+// ```
+pub(crate) fn introduce_named_lifetime(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    if let Some(body) = fn_def.body() {
+        if body.syntax().text_range().contains(ctx.frange.range.start()) {
+            return None;
+        }
+    }
+    let param_list = fn_def.param_list()?;
+
+    let self_amp = param_list
+        .self_param()
+        .filter(|it| it.kind() != SelfParamKind::Owned && it.lifetime_token().is_none())
+        .and_then(|it| it.amp_token());
+    let mut inputs: Vec<SyntaxToken> = self_amp.iter().cloned().collect();
+    inputs.extend(elided_references(param_list.syntax()));
+    let outputs = match fn_def.ret_type() {
+        Some(ret_type) => elided_references(ret_type.syntax()),
+        None => Vec::new(),
+    };
+
+    let has_named_input = param_list
+        .syntax()
+        .descendants()
+        .filter_map(ast::ReferenceType::cast)
+        .any(|it| it.lifetime_token().is_some());
+
+    // Follow the lifetime elision rules: the output lifetime comes from
+    // `&self`, or else from the only input lifetime.
+    let input = match (self_amp, inputs.as_slice()) {
+        (Some(self_amp), _) if !outputs.is_empty() => self_amp,
+        (_, [input]) if outputs.is_empty() || !has_named_input => input.clone(),
+        _ => return None,
+    };
+
+    let lifetime = fresh_lifetime_name(&fn_def)?;
+    ctx.add_assist(AssistId("introduce_named_lifetime"), "introduce named lifetime", |edit| {
+        match fn_def.type_param_list() {
+            Some(type_params) => {
+                let l_angle = type_params.syntax().first_token().unwrap();
+                let sep = if type_params.syntax().text_range().len() > TextUnit::from(2) {
+                    ", "
+                } else {
+                    ""
+                };
+                edit.insert(l_angle.text_range().end(), format!("{}{}", lifetime, sep));
+            }
+            None => {
+                if let Some(name) = fn_def.name() {
+                    edit.insert(name.syntax().text_range().end(), format!("<{}>", lifetime));
+                }
+            }
+        }
+        for amp in std::iter::once(&input).chain(outputs.iter()) {
+            edit.insert(amp.text_range().end(), format!("{} ", lifetime));
+        }
+        edit.target(fn_def.syntax().text_range());
+    })
+}
+
+/// The `&` tokens of the references with an elided lifetime in `node`, except
+/// for the ones in nested function signatures, which have their own elision
+/// scope.
+fn elided_references(node: &SyntaxNode) -> Vec<SyntaxToken> {
+    node.descendants()
+        .filter_map(ast::ReferenceType::cast)
+        .filter(|it| it.lifetime_token().is_none())
+        .filter(|it| {
+            !it.syntax()
+                .ancestors()
+                .skip(1)
+                .take_while(|it| it != node)
+                .any(|it| it.kind() == FN_POINTER_TYPE || it.kind() == PARAM_LIST)
+        })
+        .filter_map(|it| it.amp_token())
+        .collect()
+}
+
+fn fresh_lifetime_name(fn_def: &ast::FnDef) -> Option<String> {
+    let used: Vec<String> = fn_def
+        .type_param_list()
+        .into_iter()
+        .flat_map(|it| it.lifetime_params())
+        .filter_map(|it| it.lifetime_token())
+        .map(|it| it.text().to_string())
+        .collect();
+    (b'a'..=b'z').map(|c| format!("'{}", c as char)).find(|it| !used.contains(it))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn introduce_lifetime_for_single_input() {
+        check_assist(
+            introduce_named_lifetime,
+            "fn f<|>(x: &str) -> &str {}",
+            "fn f<|><'a>(x: &'a str) -> &'a str {}",
+        );
+    }
+
+    #[test]
+    fn introduce_lifetime_without_output_reference() {
+        check_assist(
+            introduce_named_lifetime,
+            "fn f<|>(x: &mut Vec<u8>) {}",
+            "fn f<|><'a>(x: &'a mut Vec<u8>) {}",
+        );
+    }
+
+    #[test]
+    fn introduce_lifetime_with_existing_generics() {
+        check_assist(
+            introduce_named_lifetime,
+            "fn f<|><'a, T>(x: &'a T, y: &[T]) {}",
+            "fn f<|><'b, 'a, T>(x: &'a T, y: &'b [T]) {}",
+        );
+    }
+
+    #[test]
+    fn introduce_lifetime_from_self() {
+        check_assist(
+            introduce_named_lifetime,
+            "
+struct S;
+impl S {
+    fn get<|>(&self, key: &str) -> &u32 {}
+}",
+            "
+struct S;
+impl S {
+    fn get<|><'a>(&'a self, key: &str) -> &'a u32 {}
+}",
+        );
+    }
+
+    #[test]
+    fn introduce_lifetime_ignores_nested_signatures() {
+        check_assist(
+            introduce_named_lifetime,
+            "fn f<|>(x: &str, g: fn(&str) -> &str) {}",
+            "fn f<|><'a>(x: &'a str, g: fn(&str) -> &str) {}",
+        );
+    }
+
+    #[test]
+    fn introduce_lifetime_target() {
+        check_assist_target(introduce_named_lifetime, "fn f<|>(x: &str) {}", "fn f(x: &str) {}");
+    }
+
+    #[test]
+    fn introduce_lifetime_not_applicable_to_ambiguous_signatures() {
+        check_assist_not_applicable(
+            introduce_named_lifetime,
+            "fn f<|>(x: &str, y: &str) -> &str {}",
+        );
+        check_assist_not_applicable(introduce_named_lifetime, "fn f<|>(x: &str, y: &str) {}");
+        check_assist_not_applicable(
+            introduce_named_lifetime,
+            "fn f<|><'a>(x: &'a str, y: &str) -> &str {}",
+        );
+        check_assist_not_applicable(introduce_named_lifetime, "fn f<|>(x: u32) -> u32 {}");
+        check_assist_not_applicable(introduce_named_lifetime, "fn f(x: &str) { <|> }");
+    }
+}
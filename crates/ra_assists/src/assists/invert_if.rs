@@ -1,15 +1,15 @@
 use hir::db::HirDatabase;
-use ra_syntax::ast::{self, AstNode};
+use ra_syntax::ast::{self, make, ArgListOwner, AstNode};
 use ra_syntax::T;
 
 use crate::{Assist, AssistCtx, AssistId};
 
 // Assist: invert_if
 //
-// Apply invert_if
-// This transforms if expressions of the form `if !x {A} else {B}` into `if x {B} else {A}`
-// This also works with `!=`. This assist can only be applied with the cursor
-// on `if`.
+// Swaps the branches of an `if` expression and negates its condition.
+// `!x` becomes `x`, `x == y` becomes `x != y` and `x.is_some()` becomes
+// `x.is_none()`, other conditions are wrapped in `!(...)`. This assist can
+// only be applied with the cursor on `if`.
 //
 // ```
 // fn main() {
@@ -32,11 +32,16 @@ pub(crate) fn invert_if(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
         return None;
     }
 
-    let cond = expr.condition()?.expr()?;
+    let cond = expr.condition()?;
+    // `if let` has no negation
+    if cond.pat().is_some() {
+        return None;
+    }
+    let cond = cond.expr()?;
     let then_node = expr.then_branch()?.syntax().clone();
 
     if let ast::ElseBranch::Block(else_block) = expr.else_branch()? {
-        let flip_cond = invert_boolean_expression(&cond)?;
+        let flip_cond = negate_boolean_expression(&cond);
         let cond_range = cond.syntax().text_range();
         let else_node = else_block.syntax();
         let else_range = else_node.text_range();
@@ -52,20 +57,59 @@ pub(crate) fn invert_if(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
     None
 }
 
+/// The inverse of `expr` if it is known without adding a negation, like `x`
+/// for `!x` or `x.is_none()` for `x.is_some()`.
 pub(crate) fn invert_boolean_expression(expr: &ast::Expr) -> Option<ast::Expr> {
     match expr {
         ast::Expr::BinExpr(bin) => match bin.op_kind()? {
             ast::BinOp::NegatedEqualityTest => bin.replace_op(T![==]).map(|it| it.into()),
+            ast::BinOp::EqualityTest => bin.replace_op(T![!=]).map(|it| it.into()),
             _ => None,
         },
         ast::Expr::PrefixExpr(pe) => match pe.op_kind()? {
             ast::PrefixOp::Not => pe.expr(),
             _ => None,
         },
+        ast::Expr::MethodCallExpr(call) => {
+            if call.type_arg_list().is_some() {
+                return None;
+            }
+            let method = inverse_method(call.name_ref()?.text().as_str())?;
+            Some(make::expr_method_call(call.expr()?, method, call.arg_list()?))
+        }
         _ => None,
     }
 }
 
+/// Negates `expr`, with a `!` if its inverse isn't known.
+pub(crate) fn negate_boolean_expression(expr: &ast::Expr) -> ast::Expr {
+    if let Some(inverted) = invert_boolean_expression(expr) {
+        return inverted;
+    }
+    match expr {
+        ast::Expr::PathExpr(_)
+        | ast::Expr::CallExpr(_)
+        | ast::Expr::MethodCallExpr(_)
+        | ast::Expr::FieldExpr(_)
+        | ast::Expr::IndexExpr(_)
+        | ast::Expr::ParenExpr(_)
+        | ast::Expr::MacroCall(_)
+        | ast::Expr::Literal(_) => make::expr_not(expr.clone()),
+        _ => make::expr_not(make::expr_paren(expr.clone())),
+    }
+}
+
+fn inverse_method(name: &str) -> Option<&'static str> {
+    let inverse = match name {
+        "is_some" => "is_none",
+        "is_none" => "is_some",
+        "is_ok" => "is_err",
+        "is_err" => "is_ok",
+        _ => return None,
+    };
+    Some(inverse)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -96,7 +140,87 @@ mod tests {
     }
 
     #[test]
-    fn invert_if_doesnt_apply_without_negated() {
-        check_assist_not_applicable(invert_if, "fn f() { i<|>f cond { 3 * 2 } else { 1 } }")
+    fn invert_if_add_inequality() {
+        check_assist(
+            invert_if,
+            "fn f() { i<|>f x == 3 { 1 } else { 3 + 2 } }",
+            "fn f() { i<|>f x != 3 { 3 + 2 } else { 1 } }",
+        )
+    }
+
+    #[test]
+    fn invert_if_swap_inverse_methods() {
+        check_assist(
+            invert_if,
+            "fn f() { <|>if x.is_some() { 1 } else { 2 } }",
+            "fn f() { <|>if x.is_none() { 2 } else { 1 } }",
+        );
+        check_assist(
+            invert_if,
+            "fn f() { <|>if r.is_err() { 1 } else { 2 } }",
+            "fn f() { <|>if r.is_ok() { 2 } else { 1 } }",
+        );
+        check_assist(
+            invert_if,
+            "fn f() { <|>if x.is_none() { 1 } else { 2 } }",
+            "fn f() { <|>if x.is_some() { 2 } else { 1 } }",
+        );
+        check_assist(
+            invert_if,
+            "fn f() { <|>if r.is_ok() { 1 } else { 2 } }",
+            "fn f() { <|>if r.is_err() { 2 } else { 1 } }",
+        )
+    }
+
+    #[test]
+    fn invert_if_add_not_to_methods_without_inverse() {
+        check_assist(
+            invert_if,
+            "fn f() { <|>if x.is_some::<u32>() { 1 } else { 2 } }",
+            "fn f() { <|>if !x.is_some::<u32>() { 2 } else { 1 } }",
+        );
+        check_assist(
+            invert_if,
+            "fn f() { <|>if x.is_some_and(y) { 1 } else { 2 } }",
+            "fn f() { <|>if !x.is_some_and(y) { 2 } else { 1 } }",
+        )
+    }
+
+    #[test]
+    fn invert_if_add_not() {
+        check_assist(
+            invert_if,
+            "fn f() { i<|>f cond { 3 * 2 } else { 1 } }",
+            "fn f() { i<|>f !cond { 1 } else { 3 * 2 } }",
+        );
+        check_assist(
+            invert_if,
+            "fn f() { i<|>f v.is_empty() { 3 * 2 } else { 1 } }",
+            "fn f() { i<|>f !v.is_empty() { 1 } else { 3 * 2 } }",
+        )
+    }
+
+    #[test]
+    fn invert_if_add_not_with_parens() {
+        check_assist(
+            invert_if,
+            "fn f() { i<|>f x < 3 { 1 } else { 2 } }",
+            "fn f() { i<|>f !(x < 3) { 2 } else { 1 } }",
+        );
+        check_assist(
+            invert_if,
+            "fn f() { i<|>f a && b { 1 } else { 2 } }",
+            "fn f() { i<|>f !(a && b) { 2 } else { 1 } }",
+        )
+    }
+
+    #[test]
+    fn invert_if_doesnt_apply_to_if_let() {
+        check_assist_not_applicable(invert_if, "fn f() { i<|>f let Some(x) = y { 1 } else { 2 } }")
+    }
+
+    #[test]
+    fn invert_if_doesnt_apply_without_else() {
+        check_assist_not_applicable(invert_if, "fn f() { i<|>f cond { 3 * 2 } }")
     }
 }
@@ -0,0 +1,195 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, AstNode},
+    SyntaxKind::{self, ABI, WHITESPACE},
+    SyntaxToken, TextRange, T,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: make_function_async
+//
+// Adds the `async` modifier to a function.
+//
+// ```
+// pub unsafe fn <|>fetch(url: &str) -> Vec<u8> {
+//     download(url)
+// }
+// ```
+// ->
+// ```
+// pub async unsafe fn fetch(url: &str) -> Vec<u8> {
+//     download(url)
+// }
+// ```
+pub(crate) fn make_function_async(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let fn_def = fn_header_at_offset(&ctx)?;
+    if modifier(&fn_def, T![async]).is_some() || modifier(&fn_def, T![const]).is_some() {
+        return None;
+    }
+    // trait methods can't be async
+    let container = fn_def.syntax().parent().and_then(|it| it.parent());
+    if let Some(container) = container {
+        if ast::TraitDef::can_cast(container.kind())
+            || ast::ImplBlock::cast(container).map_or(false, |it| it.target_trait().is_some())
+        {
+            return None;
+        }
+    }
+    // `async` goes after the visibility and before `unsafe` and `extern`
+    let offset = fn_def
+        .syntax()
+        .children_with_tokens()
+        .find(|it| it.kind() == T![unsafe] || it.kind() == ABI || it.kind() == T![fn])?
+        .text_range()
+        .start();
+
+    ctx.add_assist(AssistId("make_function_async"), "make function async", |edit| {
+        edit.target(fn_header_range(&fn_def));
+        edit.insert(offset, "async ");
+    })
+}
+
+// Assist: make_function_sync
+//
+// Removes the `async` modifier of a function which doesn't `.await`.
+//
+// ```
+// async fn <|>answer() -> u32 {
+//     42
+// }
+// ```
+// ->
+// ```
+// fn answer() -> u32 {
+//     42
+// }
+// ```
+pub(crate) fn make_function_sync(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let fn_def = fn_header_at_offset(&ctx)?;
+    let async_kw = modifier(&fn_def, T![async])?;
+    if awaits_in_body(&fn_def) {
+        return None;
+    }
+    let delete_range = match async_kw.next_token() {
+        Some(ws) if ws.kind() == WHITESPACE => {
+            TextRange::from_to(async_kw.text_range().start(), ws.text_range().end())
+        }
+        _ => async_kw.text_range(),
+    };
+
+    ctx.add_assist(AssistId("make_function_sync"), "make function sync", |edit| {
+        edit.target(fn_header_range(&fn_def));
+        edit.delete(delete_range);
+    })
+}
+
+/// The function with a body whose header is under the cursor.
+fn fn_header_at_offset(ctx: &AssistCtx<impl HirDatabase>) -> Option<ast::FnDef> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let body = fn_def.body()?;
+    if ctx.frange.range.start() >= body.syntax().text_range().start() {
+        return None;
+    }
+    Some(fn_def)
+}
+
+fn modifier(fn_def: &ast::FnDef, kind: SyntaxKind) -> Option<SyntaxToken> {
+    fn_def
+        .syntax()
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .find(|it| it.kind() == kind)
+}
+
+/// The range up to the body of the function, leaving out its attributes and
+/// doc comments.
+fn fn_header_range(fn_def: &ast::FnDef) -> TextRange {
+    let syntax = fn_def.syntax();
+    let start = syntax
+        .children_with_tokens()
+        .find(|it| {
+            ast::Visibility::can_cast(it.kind()) || it.kind() == ABI || it.kind().is_keyword()
+        })
+        .map_or(syntax.text_range().start(), |it| it.text_range().start());
+    let end =
+        fn_def.body().map_or(syntax.text_range().end(), |it| it.syntax().text_range().start());
+    TextRange::from_to(start, end)
+}
+
+/// Whether the body has an `.await` outside of its `async` blocks and closures.
+fn awaits_in_body(fn_def: &ast::FnDef) -> bool {
+    fn_def.syntax().descendants().filter(|it| ast::AwaitExpr::can_cast(it.kind())).any(
+        |await_expr| {
+            !await_expr.ancestors().take_while(|it| it != fn_def.syntax()).any(|it| {
+                (ast::BlockExpr::can_cast(it.kind()) || ast::LambdaExpr::can_cast(it.kind()))
+                    && it.first_token().map_or(false, |it| it.kind() == T![async])
+            })
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn make_function_async_places_modifier() {
+        check_assist(make_function_async, "fn f<|>() {}", "async fn f<|>() {}");
+        check_assist(
+            make_function_async,
+            "/// Docs\n#[inline]\npub(crate) fn <|>f() {}",
+            "/// Docs\n#[inline]\npub(crate) async fn <|>f() {}",
+        );
+        check_assist(
+            make_function_async,
+            "pub unsafe extern \"C\" fn <|>f() {}",
+            "pub async unsafe extern \"C\" fn <|>f() {}",
+        );
+        check_assist(
+            make_function_async,
+            "extern \"C\" fn <|>f() {}",
+            "async extern \"C\" fn <|>f() {}",
+        );
+    }
+
+    #[test]
+    fn make_function_async_not_applicable() {
+        check_assist_not_applicable(make_function_async, "async fn f<|>() {}");
+        check_assist_not_applicable(make_function_async, "const fn f<|>() {}");
+        check_assist_not_applicable(make_function_async, "fn f() { foo(<|>); }");
+        check_assist_not_applicable(make_function_async, "trait T { fn f<|>() {} }");
+        check_assist_not_applicable(
+            make_function_async,
+            "struct S; impl Tr for S { fn f<|>() {} }",
+        );
+    }
+
+    #[test]
+    fn make_function_sync_removes_modifier() {
+        check_assist(
+            make_function_sync,
+            "pub async unsafe fn <|>f() -> u8 { 1 }",
+            "pub unsafe fn <|>f() -> u8 { 1 }",
+        );
+        check_assist(
+            make_function_sync,
+            "async fn <|>f() { let fut = async { g().await }; }",
+            "fn <|>f() { let fut = async { g().await }; }",
+        );
+    }
+
+    #[test]
+    fn make_function_sync_not_applicable() {
+        check_assist_not_applicable(make_function_sync, "fn f<|>() {}");
+        check_assist_not_applicable(make_function_sync, "async fn <|>f() { g().await; }");
+    }
+
+    #[test]
+    fn make_function_async_target() {
+        check_assist_target(make_function_async, "#[test]\nfn f<|>() -> u8 { 1 }", "fn f() -> u8 ");
+        check_assist_target(make_function_sync, "pub async fn f<|>() {}", "pub async fn f() ");
+    }
+}
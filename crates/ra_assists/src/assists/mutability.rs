@@ -0,0 +1,207 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner},
+    Direction, SyntaxNode, SyntaxToken, TextRange, TextUnit, T,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: add_mut
+//
+// Makes a reference or a binding mutable.
+//
+// ```
+// fn main() {
+//     let <|>x = 92;
+//     let y = &x;
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let mut x = 92;
+//     let y = &x;
+// }
+// ```
+pub(crate) fn add_mut(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let place = MutPlace::at_offset(&ctx)?;
+    if place.mut_token.is_some() {
+        return None;
+    }
+    // `&mut x` is a mutable reference pattern, not a reference to a mutable
+    // binding
+    if let Some(parent) = place.node.parent().and_then(ast::RefPat::cast) {
+        if ast::BindPat::cast(place.node.clone()).is_some() && !parent.is_mut() {
+            return None;
+        }
+    }
+    ctx.add_assist(AssistId("add_mut"), "add `mut`", |edit| {
+        edit.target(place.node.text_range());
+        edit.insert(place.mut_offset, "mut ");
+        edit.set_cursor(place.mut_offset + TextUnit::of_str("mut "));
+    })
+}
+
+// Assist: remove_mut
+//
+// Makes a reference or a binding immutable.
+//
+// ```
+// fn main() {
+//     let mut x = 92;
+//     let y = &<|>mut x;
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let mut x = 92;
+//     let y = &x;
+// }
+// ```
+pub(crate) fn remove_mut(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let place = MutPlace::at_offset(&ctx)?;
+    let mut_token = place.mut_token?;
+    // the whitespace between `mut` and what follows goes with it
+    let end = mut_token
+        .siblings_with_tokens(Direction::Next)
+        .skip(1)
+        .find(|it| !it.kind().is_trivia())
+        .map_or(mut_token.text_range().end(), |it| it.text_range().start());
+    ctx.add_assist(AssistId("remove_mut"), "remove `mut`", |edit| {
+        edit.target(place.node.text_range());
+        edit.delete(TextRange::from_to(mut_token.text_range().start(), end));
+        edit.set_cursor(mut_token.text_range().start());
+    })
+}
+
+/// A reference expression, type or pattern, or a binding, with the cursor
+/// before what it refers to or binds.
+struct MutPlace {
+    node: SyntaxNode,
+    mut_token: Option<SyntaxToken>,
+    /// Where the `mut` keyword goes: after the `&` and the lifetime of
+    /// references, and after `ref` for bindings.
+    mut_offset: TextUnit,
+}
+
+impl MutPlace {
+    fn at_offset(ctx: &AssistCtx<impl HirDatabase>) -> Option<MutPlace> {
+        let offset = ctx.frange.range.start();
+        ctx.token_at_offset().find_map(|token| MutPlace::for_token(token, offset))
+    }
+
+    fn for_token(token: SyntaxToken, offset: TextUnit) -> Option<MutPlace> {
+        let (node, header_end, mut_offset) = token.parent().ancestors().find_map(|node| {
+            if let Some(it) = ast::RefExpr::cast(node.clone()) {
+                let start = it.expr()?.syntax().text_range().start();
+                return Some((node, start, start));
+            }
+            if let Some(it) = ast::ReferenceType::cast(node.clone()) {
+                let start = it.type_ref()?.syntax().text_range().start();
+                return Some((node, start, start));
+            }
+            if let Some(it) = ast::RefPat::cast(node.clone()) {
+                let start = it.pat()?.syntax().text_range().start();
+                return Some((node, start, start));
+            }
+            if let Some(it) = ast::BindPat::cast(node.clone()) {
+                let name = it.name()?.syntax().text_range();
+                return Some((node, name.end(), name.start()));
+            }
+            None
+        })?;
+        if offset > header_end {
+            return None;
+        }
+        let mut_token = node
+            .children_with_tokens()
+            .filter_map(|it| it.into_token())
+            .find(|it| it.kind() == T![mut]);
+        Some(MutPlace { node, mut_token, mut_offset })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_mut_to_binding() {
+        check_assist(add_mut, "fn f() { let <|>x = 92; }", "fn f() { let mut <|>x = 92; }");
+        check_assist(add_mut, "fn f(<|>x: u32) {}", "fn f(mut <|>x: u32) {}");
+    }
+
+    #[test]
+    fn add_mut_to_ref_binding() {
+        check_assist(
+            add_mut,
+            "fn f(o: Option<u32>) { if let Some(ref <|>x) = o {} }",
+            "fn f(o: Option<u32>) { if let Some(ref mut <|>x) = o {} }",
+        );
+    }
+
+    #[test]
+    fn add_mut_to_reference_expr() {
+        check_assist(add_mut, "fn f() { let y = <|>&x; }", "fn f() { let y = &mut <|>x; }");
+        check_assist(add_mut, "fn f() { let y = &&<|>x; }", "fn f() { let y = &&mut <|>x; }");
+    }
+
+    #[test]
+    fn add_mut_to_reference_type_with_lifetime() {
+        check_assist(add_mut, "fn f<'a>(x: <|>&'a u32) {}", "fn f<'a>(x: &'a mut <|>u32) {}");
+    }
+
+    #[test]
+    fn add_mut_to_reference_pattern() {
+        check_assist(
+            add_mut,
+            "fn f(y: &mut u32) { let <|>&x = y; }",
+            "fn f(y: &mut u32) { let &mut <|>x = y; }",
+        );
+    }
+
+    #[test]
+    fn add_mut_target() {
+        check_assist_target(add_mut, "fn f() { let y = <|>&x.foo; }", "&x.foo");
+    }
+
+    #[test]
+    fn add_mut_not_applicable() {
+        check_assist_not_applicable(add_mut, "fn f() { let mut <|>x = 92; }");
+        check_assist_not_applicable(add_mut, "fn f() { let y = &mut <|>x; }");
+        // the cursor is in the referenced expression
+        check_assist_not_applicable(add_mut, "fn f() { let y = &x.<|>foo; }");
+        // `&mut x` would be a mutable reference pattern
+        check_assist_not_applicable(add_mut, "fn f(y: &u32) { let &<|>x = y; }");
+    }
+
+    #[test]
+    fn remove_mut_from_binding() {
+        check_assist(remove_mut, "fn f() { let <|>mut x = 92; }", "fn f() { let <|>x = 92; }");
+        check_assist(
+            remove_mut,
+            "fn f(o: Option<u32>) { if let Some(ref mut <|>x) = o {} }",
+            "fn f(o: Option<u32>) { if let Some(ref <|>x) = o {} }",
+        );
+    }
+
+    #[test]
+    fn remove_mut_from_references() {
+        check_assist(remove_mut, "fn f() { let y = &<|>mut x; }", "fn f() { let y = &<|>x; }");
+        check_assist(remove_mut, "fn f<'a>(x: &'a <|>mut u32) {}", "fn f<'a>(x: &'a <|>u32) {}");
+        check_assist(
+            remove_mut,
+            "fn f(y: &mut u32) { let &<|>mut x = y; }",
+            "fn f(y: &mut u32) { let &<|>x = y; }",
+        );
+    }
+
+    #[test]
+    fn remove_mut_not_applicable() {
+        check_assist_not_applicable(remove_mut, "fn f() { let <|>x = 92; }");
+        check_assist_not_applicable(remove_mut, "fn f() { let y = <|>&x; }");
+        check_assist_not_applicable(remove_mut, "fn f() { let y = &mut x.<|>foo; }");
+    }
+}
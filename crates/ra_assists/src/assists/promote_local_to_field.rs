@@ -0,0 +1,318 @@
+use hir::{db::HirDatabase, HasSource, HirDisplay};
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode, BinOp, NameOwner, StructKind, TypeParamsOwner},
+    match_ast, Direction,
+    SyntaxKind::COMMA,
+    TextUnit,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: promote_local_to_field
+//
+// Turns a local of a method into a field of the struct, which the method
+// assigns instead.
+//
+// ```
+// struct Counter {
+//     total: u32,
+// }
+//
+// impl Counter {
+//     fn add(&mut self, x: u32) {
+//         let <|>last = x;
+//         self.total += last;
+//     }
+// }
+// ```
+// ->
+// ```
+// struct Counter {
+//     total: u32,
+//     last: u32,
+// }
+//
+// impl Counter {
+//     fn add(&mut self, x: u32) {
+//         self.last = x;
+//         self.total += self.last;
+//     }
+// }
+// ```
+pub(crate) fn promote_local_to_field(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let let_stmt = ctx.find_node_at_offset::<ast::LetStmt>()?;
+    let bind_pat = match let_stmt.pat()? {
+        ast::Pat::BindPat(it) if !it.is_ref() && it.pat().is_none() => it,
+        _ => return None,
+    };
+    let name = bind_pat.name()?;
+    let initializer = let_stmt.initializer()?;
+
+    // the local has to be in a `&mut self` method, outside of closures
+    let fn_def = let_stmt.syntax().ancestors().find_map(|node| {
+        if ast::LambdaExpr::can_cast(node.kind()) {
+            return Some(None);
+        }
+        ast::FnDef::cast(node).map(Some)
+    })??;
+    if fn_def.param_list()?.self_param()?.kind() != ast::SelfParamKind::MutRef {
+        return None;
+    }
+    // the type of the local can't depend on the generics of the method
+    if fn_def.type_param_list().is_some() {
+        return None;
+    }
+    let impl_block =
+        fn_def.syntax().parent().and_then(|it| it.parent()).and_then(ast::ImplBlock::cast)?;
+    if impl_block.target_trait().is_some() {
+        return None;
+    }
+    let target = match impl_block.target_type()? {
+        ast::TypeRef::PathType(it) => it.path()?,
+        _ => return None,
+    };
+
+    let analyzer = ctx.source_analyzer(impl_block.syntax(), None);
+    let strukt = match analyzer.resolve_path(ctx.db, &target)? {
+        hir::PathResolution::Def(hir::ModuleDef::Adt(hir::Adt::Struct(it))) => it.source(ctx.db),
+        _ => return None,
+    };
+    if strukt.file_id != ctx.frange.file_id.into() {
+        return None;
+    }
+    let field_list = match strukt.value.kind() {
+        StructKind::Record(it) => it,
+        _ => return None,
+    };
+    if field_list.fields().any(|it| it.name().map_or(false, |it| it.text() == name.text())) {
+        return None;
+    }
+
+    let analyzer = ctx.source_analyzer(bind_pat.syntax(), None);
+    let ty = analyzer.type_of_pat(ctx.db, &bind_pat.clone().into())?;
+    if ty.contains_unknown() {
+        return None;
+    }
+    // builtin types are taken as `Copy`, and references as reborrowed
+    let is_copy = ty.as_adt().is_none() || analyzer.impls_copy(ctx.db, ty.clone());
+    let refs = analyzer.find_all_refs(&bind_pat);
+    let body_tail = fn_def.body().and_then(|it| it.block()).and_then(|it| it.expr());
+    for desc in &refs {
+        let usage =
+            ctx.covering_node_for_range(desc.range).ancestors().find_map(ast::PathExpr::cast)?;
+        // the value can't be moved out of the struct
+        let parent = usage.syntax().parent();
+        if parent.map_or(false, |it| ast::ReturnExpr::can_cast(it.kind()))
+            || body_tail.as_ref().map_or(false, |it| it.syntax() == usage.syntax())
+            || (!is_copy && moves_value(&usage))
+        {
+            return None;
+        }
+    }
+
+    ctx.add_assist(AssistId("promote_local_to_field"), "promote local to field", |edit| {
+        let field = format!("{}: {}", name.text(), ty.display(ctx.db));
+        let (offset, text) = field_insertion(&field_list, &field);
+        edit.insert(offset, text);
+
+        edit.target(let_stmt.syntax().text_range());
+        edit.replace(
+            let_stmt.syntax().text_range(),
+            format!("self.{} = {};", name.text(), initializer.syntax()),
+        );
+        for desc in &refs {
+            edit.replace(desc.range, format!("self.{}", name.text()));
+        }
+        edit.set_cursor(let_stmt.syntax().text_range().start());
+    })
+}
+
+/// Whether the usage moves the value of the local, unless it is `Copy`.
+fn moves_value(usage: &ast::PathExpr) -> bool {
+    let parent = match usage.syntax().parent() {
+        Some(it) => it,
+        None => return true,
+    };
+    let is_usage = |expr: Option<ast::Expr>| expr.map_or(false, |it| it.syntax() == usage.syntax());
+    match_ast! {
+        match parent {
+            ast::RefExpr(_it) => { false },
+            ast::FieldExpr(_it) => { false },
+            ast::MethodCallExpr(it) => { !is_usage(it.expr()) },
+            ast::IndexExpr(it) => { !is_usage(it.base()) },
+            ast::BinExpr(it) => {
+                match it.op_kind() {
+                    Some(BinOp::EqualityTest)
+                    | Some(BinOp::NegatedEqualityTest)
+                    | Some(BinOp::LesserEqualTest)
+                    | Some(BinOp::GreaterEqualTest)
+                    | Some(BinOp::LesserTest)
+                    | Some(BinOp::GreaterTest) => false,
+                    Some(BinOp::Assignment)
+                    | Some(BinOp::AddAssign)
+                    | Some(BinOp::DivAssign)
+                    | Some(BinOp::MulAssign)
+                    | Some(BinOp::RemAssign)
+                    | Some(BinOp::ShrAssign)
+                    | Some(BinOp::ShlAssign)
+                    | Some(BinOp::SubAssign)
+                    | Some(BinOp::BitOrAssign)
+                    | Some(BinOp::BitAndAssign)
+                    | Some(BinOp::BitXorAssign) => !is_usage(it.lhs()),
+                    _ => true,
+                }
+            },
+            _ => true,
+        }
+    }
+}
+
+/// Where and how to add `field` at the end of the fields, on a new line if the
+/// fields are on several lines.
+fn field_insertion(field_list: &ast::RecordFieldDefList, field: &str) -> (TextUnit, String) {
+    let last_field = match field_list.fields().last() {
+        Some(it) => it,
+        None => {
+            let range = field_list.syntax().text_range();
+            return (range.start() + TextUnit::of_char('{'), format!(" {} ", field));
+        }
+    };
+    let comma =
+        last_field.syntax().siblings_with_tokens(Direction::Next).find(|it| it.kind() == COMMA);
+    if field_list.syntax().text().contains_char('\n') {
+        let indent = "    ".repeat(IndentLevel::from_node(last_field.syntax()).0 as usize);
+        match comma {
+            Some(comma) => (comma.text_range().end(), format!("\n{}{},", indent, field)),
+            None => (last_field.syntax().text_range().end(), format!(",\n{}{}", indent, field)),
+        }
+    } else {
+        match comma {
+            Some(comma) => (comma.text_range().end(), format!(" {},", field)),
+            None => (last_field.syntax().text_range().end(), format!(", {}", field)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn promote_local_to_new_field() {
+        check_assist(
+            promote_local_to_field,
+            "
+struct S {
+    a: u8,
+}
+
+impl S {
+    fn f(&mut self) {
+        let <|>count = 1u32;
+        foo(count, count + 1);
+    }
+}",
+            "
+struct S {
+    a: u8,
+    count: u32,
+}
+
+impl S {
+    fn f(&mut self) {
+        <|>self.count = 1u32;
+        foo(self.count, self.count + 1);
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn promote_local_to_field_of_inline_struct() {
+        check_assist(
+            promote_local_to_field,
+            "
+struct S { a: u8 }
+impl S { fn f(&mut self) { let x<|> = (true, 'c'); } }",
+            "
+struct S { a: u8, x: (bool, char) }
+impl S { fn f(&mut self) { <|>self.x = (true, 'c'); } }",
+        );
+        check_assist(
+            promote_local_to_field,
+            "
+struct S {}
+impl S { fn f(&mut self) { let x<|> = 1u8; } }",
+            "
+struct S { x: u8 }
+impl S { fn f(&mut self) { <|>self.x = 1u8; } }",
+        );
+    }
+
+    #[test]
+    fn promote_local_to_field_not_applicable_outside_of_mut_methods() {
+        check_assist_not_applicable(
+            promote_local_to_field,
+            "
+struct S { a: u8 }
+impl S { fn f(&self) { let x<|> = 1u8; } }",
+        );
+        check_assist_not_applicable(
+            promote_local_to_field,
+            "
+struct S { a: u8 }
+fn f(s: &mut S) { let x<|> = 1u8; }",
+        );
+        check_assist_not_applicable(
+            promote_local_to_field,
+            "
+struct S(u8);
+impl S { fn f(&mut self) { let x<|> = 1u8; } }",
+        );
+    }
+
+    #[test]
+    fn promote_local_to_field_not_applicable_if_local_escapes() {
+        check_assist_not_applicable(
+            promote_local_to_field,
+            "
+struct S { a: u8 }
+impl S { fn f(&mut self) -> u8 { let x<|> = 1u8; x } }",
+        );
+        check_assist_not_applicable(
+            promote_local_to_field,
+            "
+struct S { a: u8 }
+impl S { fn f(&mut self) { let c = || { let x<|> = 1u8; }; } }",
+        );
+        // the value is moved, and isn't `Copy`
+        check_assist_not_applicable(
+            promote_local_to_field,
+            "
+struct S { a: u8 }
+struct V;
+fn consume(v: V) {}
+impl S { fn f(&mut self) { let x<|> = V; consume(x); } }",
+        );
+        // the field already exists
+        check_assist_not_applicable(
+            promote_local_to_field,
+            "
+struct S { a: u8 }
+impl S { fn f(&mut self) { let a<|> = 1u8; } }",
+        );
+    }
+
+    #[test]
+    fn promote_local_to_field_target() {
+        check_assist_target(
+            promote_local_to_field,
+            "
+struct S { a: u8 }
+impl S { fn f(&mut self) { let x<|> = 1u8; } }",
+            "let x = 1u8;",
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, AstNode, BinOp},
+    SyntaxKind::{
+        ARG_LIST, ARRAY_EXPR, BLOCK, BREAK_EXPR, CONDITION, CONST_DEF, EXPR_STMT, LAMBDA_EXPR,
+        LET_STMT, MATCH_ARM, MATCH_GUARD, PAREN_EXPR, RECORD_FIELD, RETURN_EXPR, STATIC_DEF,
+        TUPLE_EXPR,
+    },
+    SyntaxNode, T,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: remove_parentheses
+//
+// Removes parentheses which are redundant given the precedence of the
+// surrounding expression.
+//
+// ```
+// fn main() {
+//     let x = a + <|>(b * c);
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     let x = a + b * c;
+// }
+// ```
+pub(crate) fn remove_parentheses(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let paren = ctx.find_node_at_offset::<ast::ParenExpr>()?;
+    let inner = paren.expr()?;
+    let l_paren = paren.syntax().first_token().filter(|it| it.kind() == T!['('])?;
+    let r_paren = paren.syntax().last_token().filter(|it| it.kind() == T![')'])?;
+    let parent = paren.syntax().parent()?;
+    if !is_redundant(&paren, &inner, &parent) {
+        return None;
+    }
+    // `{ 1 } - 1;` is a block followed by `-1`
+    if starts_statement(paren.syntax()) && starts_with_block_like(inner.syntax()) {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("remove_parentheses"), "remove redundant parentheses", |edit| {
+        edit.target(paren.syntax().text_range());
+        edit.delete(l_paren.text_range());
+        edit.delete(r_paren.text_range());
+    })
+}
+
+/// Whether the meaning of `parent` stays the same with `inner` in place of
+/// the parenthesized expression.
+fn is_redundant(paren: &ast::ParenExpr, inner: &ast::Expr, parent: &SyntaxNode) -> bool {
+    let precedence = expr_precedence(inner);
+    let is_first_child = parent.first_child().as_ref() == Some(paren.syntax());
+    let parent_expr = match ast::Expr::cast(parent.clone()) {
+        Some(it) => it,
+        // the parenthesized expression is the whole of a statement, an
+        // argument, an element or a field
+        None => {
+            return match parent.kind() {
+                // `if (S {}) {}` needs its parentheses
+                CONDITION => !contains_record_lit(inner),
+                LET_STMT | EXPR_STMT | BLOCK | ARG_LIST | MATCH_ARM | MATCH_GUARD
+                | RECORD_FIELD | CONST_DEF | STATIC_DEF => true,
+                _ => false,
+            };
+        }
+    };
+    match parent_expr {
+        ast::Expr::BinExpr(bin) => {
+            let op = match bin.op_kind() {
+                Some(it) => it,
+                None => return false,
+            };
+            // `a as u8 < b` starts a generic argument list
+            if let ast::Expr::CastExpr(_) = inner {
+                if is_first_child && (op == BinOp::LesserTest || op == BinOp::LeftShift) {
+                    return false;
+                }
+            }
+            let parent_precedence = bin_op_precedence(op);
+            if precedence != parent_precedence {
+                return precedence > parent_precedence;
+            }
+            // the operators of the same precedence are left-associative, apart
+            // from the assignments, and the comparisons can't be chained
+            match op {
+                BinOp::EqualityTest
+                | BinOp::NegatedEqualityTest
+                | BinOp::LesserEqualTest
+                | BinOp::GreaterEqualTest
+                | BinOp::LesserTest
+                | BinOp::GreaterTest => false,
+                _ if parent_precedence == ASSIGNMENT => !is_first_child,
+                _ => is_first_child,
+            }
+        }
+        ast::Expr::PrefixExpr(_) | ast::Expr::RefExpr(_) | ast::Expr::BoxExpr(_) => {
+            precedence >= UNARY
+        }
+        ast::Expr::CastExpr(_) => precedence >= UNARY,
+        ast::Expr::RangeExpr(_) => precedence > RANGE,
+        // `(s.f)()` calls a field, `s.f()` a method
+        ast::Expr::CallExpr(_) if is_first_child => {
+            precedence == POSTFIX && !ast::FieldExpr::can_cast(inner.syntax().kind())
+        }
+        ast::Expr::IndexExpr(_) if !is_first_child => true,
+        ast::Expr::MethodCallExpr(_)
+        | ast::Expr::FieldExpr(_)
+        | ast::Expr::TryExpr(_)
+        | ast::Expr::AwaitExpr(_)
+        | ast::Expr::IndexExpr(_) => precedence == POSTFIX,
+        ast::Expr::MatchExpr(_) | ast::Expr::ForExpr(_) => !contains_record_lit(inner),
+        _ => match parent.kind() {
+            PAREN_EXPR | TUPLE_EXPR | ARRAY_EXPR | RETURN_EXPR | BREAK_EXPR | LAMBDA_EXPR => true,
+            _ => false,
+        },
+    }
+}
+
+const ASSIGNMENT: u8 = 1;
+const RANGE: u8 = 2;
+const UNARY: u8 = 13;
+const POSTFIX: u8 = 14;
+
+/// How tightly an expression binds its operands, the expressions which only
+/// fit where any expression goes have the lowest precedence.
+fn expr_precedence(expr: &ast::Expr) -> u8 {
+    match expr {
+        ast::Expr::LambdaExpr(_) | ast::Expr::ReturnExpr(_) | ast::Expr::BreakExpr(_) => 0,
+        ast::Expr::RangeExpr(_) => RANGE,
+        ast::Expr::BinExpr(it) => it.op_kind().map_or(0, bin_op_precedence),
+        ast::Expr::CastExpr(_) => 12,
+        ast::Expr::PrefixExpr(_) | ast::Expr::RefExpr(_) | ast::Expr::BoxExpr(_) => UNARY,
+        _ => POSTFIX,
+    }
+}
+
+fn bin_op_precedence(op: BinOp) -> u8 {
+    match op {
+        BinOp::Multiplication | BinOp::Division | BinOp::Remainder => 11,
+        BinOp::Addition | BinOp::Subtraction => 10,
+        BinOp::LeftShift | BinOp::RightShift => 9,
+        BinOp::BitwiseAnd => 8,
+        BinOp::BitwiseXor => 7,
+        BinOp::BitwiseOr => 6,
+        BinOp::EqualityTest
+        | BinOp::NegatedEqualityTest
+        | BinOp::LesserEqualTest
+        | BinOp::GreaterEqualTest
+        | BinOp::LesserTest
+        | BinOp::GreaterTest => 5,
+        BinOp::BooleanAnd => 4,
+        BinOp::BooleanOr => 3,
+        _ => ASSIGNMENT,
+    }
+}
+
+/// Whether `node` is the leftmost part of a statement, a tail expression or
+/// the body of a match arm, where a block-like expression ends the expression.
+fn starts_statement(node: &SyntaxNode) -> bool {
+    let start = node.text_range().start();
+    for ancestor in node.ancestors().skip(1) {
+        let is_leftmost = ancestor.text_range().start() == start;
+        match ancestor.kind() {
+            EXPR_STMT => return is_leftmost,
+            // the expression reached from `node` is the tail or the body
+            BLOCK | MATCH_ARM => return true,
+            _ if is_leftmost && ast::Expr::can_cast(ancestor.kind()) => (),
+            _ => return false,
+        }
+    }
+    false
+}
+
+fn starts_with_block_like(node: &SyntaxNode) -> bool {
+    let start = node.text_range().start();
+    let mut node = node.clone();
+    loop {
+        let is_block_like = match ast::Expr::cast(node.clone()) {
+            Some(ast::Expr::BlockExpr(_))
+            | Some(ast::Expr::IfExpr(_))
+            | Some(ast::Expr::MatchExpr(_))
+            | Some(ast::Expr::LoopExpr(_))
+            | Some(ast::Expr::WhileExpr(_))
+            | Some(ast::Expr::ForExpr(_))
+            | Some(ast::Expr::TryBlockExpr(_)) => true,
+            Some(ast::Expr::MacroCall(it)) => it
+                .token_tree()
+                .and_then(|it| it.syntax().first_token())
+                .map_or(false, |it| it.kind() == T!['{']),
+            _ => false,
+        };
+        if is_block_like {
+            return true;
+        }
+        node = match node.first_child() {
+            Some(it) if it.text_range().start() == start && ast::Expr::can_cast(it.kind()) => it,
+            _ => return false,
+        };
+    }
+}
+
+/// Whether a struct literal of `expr` would end up in the position of the
+/// condition of an `if`, or the expression of a `match` or a `for`.
+fn contains_record_lit(expr: &ast::Expr) -> bool {
+    expr.syntax().descendants().any(|it| ast::RecordLit::can_cast(it.kind()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn remove_parentheses_of_tighter_operation() {
+        check_assist(
+            remove_parentheses,
+            "fn f() { let x = (a * b) + <|>(-c); }",
+            "fn f() { let x = (a * b) + <|>-c; }",
+        );
+        check_assist(
+            remove_parentheses,
+            "fn f() { let x = (a - b<|>) - c; }",
+            "fn f() { let x = a - b<|> - c; }",
+        );
+        check_assist(remove_parentheses, "fn f() { a = (b <|>= c); }", "fn f() { a = b <|>= c; }");
+    }
+
+    #[test]
+    fn remove_parentheses_of_whole_expression() {
+        check_assist(remove_parentheses, "fn f() { (a + <|>b); }", "fn f() { a + <|>b; }");
+        check_assist(
+            remove_parentheses,
+            "fn f() { foo((<|>a..b), [(x)]) }",
+            "fn f() { foo(<|>a..b, [(x)]) }",
+        );
+        check_assist(
+            remove_parentheses,
+            "fn f() -> u8 { let c = || (<|>x.y()); (1 + 1) }",
+            "fn f() -> u8 { let c = || <|>x.y(); (1 + 1) }",
+        );
+        check_assist(remove_parentheses, "fn f() { (<|>x).foo() }", "fn f() { <|>x.foo() }");
+    }
+
+    #[test]
+    fn remove_parentheses_not_applicable_if_meaning_changes() {
+        check_assist_not_applicable(remove_parentheses, "fn f() { let x = (a <|>+ b) * c; }");
+        check_assist_not_applicable(remove_parentheses, "fn f() { let x = a - (b <|>- c); }");
+        check_assist_not_applicable(remove_parentheses, "fn f() { let x = (<|>-1).abs(); }");
+        check_assist_not_applicable(remove_parentheses, "fn f() { let x = (a <|>< b) == c; }");
+        check_assist_not_applicable(remove_parentheses, "fn f() { (s.f<|>)(); }");
+    }
+
+    #[test]
+    fn remove_parentheses_not_applicable_if_parsing_changes() {
+        check_assist_not_applicable(remove_parentheses, "fn f() { (a as <|>u8) < b; }");
+        check_assist_not_applicable(remove_parentheses, "fn f() { if (<|>S {} == s) {} }");
+        check_assist_not_applicable(remove_parentheses, "fn f() { ({ <|>1 }) - 1; }");
+        check_assist_not_applicable(remove_parentheses, "fn f() { (match x { _ => 1 }<|>).foo() }");
+    }
+
+    #[test]
+    fn remove_parentheses_target() {
+        check_assist_target(remove_parentheses, "fn f() { let x = a + (b <|>* c); }", "(b * c)");
+    }
+}
@@ -0,0 +1,333 @@
+use hir::{db::HirDatabase, ModuleDef, ScopeDef};
+use ra_syntax::{
+    ast::{self, AstNode, ModuleItemOwner, NameOwner, VisibilityOwner},
+    SyntaxKind::{IDENT, WHITESPACE},
+    TextRange, T,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: remove_unused_import
+//
+// Removes an import which is not used in the file.
+//
+// ```
+// use std::collections::{HashMap, HashSet<|>};
+//
+// fn main() {
+//     let map: HashMap<u32, u32> = HashMap::new();
+// }
+// ```
+// ->
+// ```
+// use std::collections::HashMap;
+//
+// fn main() {
+//     let map: HashMap<u32, u32> = HashMap::new();
+// }
+// ```
+pub(crate) fn remove_unused_import(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let tree = ctx.find_node_at_offset::<ast::UseTree>()?;
+    if tree.use_tree_list().is_some() || tree.has_star() {
+        return None;
+    }
+    let use_item = tree.syntax().ancestors().find_map(ast::UseItem::cast)?;
+    // a `pub use` may be used from other files
+    if use_item.visibility().is_some() {
+        return None;
+    }
+    let name = imported_name(&tree)?;
+    if is_used(&use_item, &name) || is_trait(&ctx, &use_item, &name) {
+        return None;
+    }
+    let (range, replace_with) = removal_edit(tree.clone())?;
+
+    ctx.add_assist(AssistId("remove_unused_import"), "remove unused import", |edit| {
+        edit.target(tree.syntax().text_range());
+        edit.replace(range, replace_with);
+        edit.set_cursor(range.start());
+    })
+}
+
+/// The name a (leaf) use tree brings into scope.
+fn imported_name(tree: &ast::UseTree) -> Option<String> {
+    if let Some(alias) = tree.alias() {
+        // `use foo::Trait as _;` has no name and is only imported for its methods
+        return alias.name().map(|it| it.text().to_string());
+    }
+    let segment = tree.path()?.segment()?;
+    match segment.kind()? {
+        ast::PathSegmentKind::Name(name_ref) => Some(name_ref.text().to_string()),
+        ast::PathSegmentKind::SelfKw => {
+            let list = tree.syntax().parent().and_then(ast::UseTreeList::cast)?;
+            let segment = list.parent_use_tree().path()?.segment()?;
+            segment.name_ref().map(|it| it.text().to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Whether `name` is mentioned anywhere in the file outside of the import
+/// itself. This works on tokens rather than on resolved references, so that
+/// names used in macro calls are taken into account as well.
+fn is_used(use_item: &ast::UseItem, name: &str) -> bool {
+    let root = match use_item.syntax().ancestors().last() {
+        Some(it) => it,
+        None => return true,
+    };
+    let mentioned = root
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| it.kind() == IDENT && it.text() == name)
+        .any(|it| !it.text_range().is_subrange(&use_item.syntax().text_range()));
+    if mentioned {
+        return true;
+    }
+    // child modules declared in other files see private imports through
+    // `use super::*;`, which can't be checked here
+    use_item
+        .syntax()
+        .parent()
+        .and_then(|it| {
+            ast::SourceFile::cast(it.clone())
+                .map(|it| it.items())
+                .or_else(|| ast::ItemList::cast(it).map(|it| it.items()))
+        })
+        .map_or(true, |mut items| {
+            items.any(|it| match it {
+                ast::ModuleItem::Module(m) => m.item_list().is_none(),
+                _ => false,
+            })
+        })
+}
+
+/// Traits are used by method calls without being mentioned by name.
+fn is_trait(ctx: &AssistCtx<impl HirDatabase>, use_item: &ast::UseItem, name: &str) -> bool {
+    let analyzer = ctx.source_analyzer(use_item.syntax(), None);
+    let mut res = false;
+    analyzer.process_all_names(ctx.db, &mut |it, def| {
+        if it.to_string() == name {
+            if let ScopeDef::ModuleDef(ModuleDef::Trait(_)) = def {
+                res = true;
+            }
+        }
+    });
+    res
+}
+
+/// The replacement removing `tree` from its use item: the enclosing `{}`
+/// list is collapsed if only one tree is left in it, and the whole item is
+/// removed once it is empty.
+fn removal_edit(tree: ast::UseTree) -> Option<(TextRange, String)> {
+    let list = match tree.syntax().parent().and_then(ast::UseTreeList::cast) {
+        Some(it) => it,
+        None => {
+            let use_item = tree.syntax().parent().and_then(ast::UseItem::cast)?;
+            let range = use_item.syntax().text_range();
+            let end = match use_item.syntax().next_sibling_or_token() {
+                Some(ws) if ws.kind() == WHITESPACE => ws.text_range().end(),
+                _ => range.end(),
+            };
+            return Some((TextRange::from_to(range.start(), end), String::new()));
+        }
+    };
+    let parent = list.parent_use_tree();
+    let siblings: Vec<ast::UseTree> =
+        list.use_trees().filter(|it| it.syntax() != tree.syntax()).collect();
+    match siblings.as_slice() {
+        [] => removal_edit(parent),
+        [remaining] => {
+            let prefix = parent.path().map(|it| it.syntax().to_string());
+            let is_self = remaining
+                .path()
+                .and_then(|it| it.segment())
+                .and_then(|it| it.kind())
+                .map_or(false, |it| it == ast::PathSegmentKind::SelfKw);
+            let collapsed = match prefix {
+                Some(prefix) if is_self => {
+                    let alias = remaining.alias().map(|it| format!(" {}", it.syntax()));
+                    format!("{}{}", prefix, alias.unwrap_or_default())
+                }
+                Some(prefix) => format!("{}::{}", prefix, remaining.syntax()),
+                None => remaining.syntax().to_string(),
+            };
+            Some((parent.syntax().text_range(), collapsed))
+        }
+        _ => {
+            // remove the tree together with the comma after it, or, for the
+            // last tree, with the comma before it
+            let range = tree.syntax().text_range();
+            let next_comma = tree
+                .syntax()
+                .siblings_with_tokens(ra_syntax::Direction::Next)
+                .skip(1)
+                .find(|it| it.kind() != WHITESPACE)
+                .filter(|it| it.kind() == T![,]);
+            let range = match next_comma {
+                Some(comma) => {
+                    let end = match comma.next_sibling_or_token() {
+                        Some(ws) if ws.kind() == WHITESPACE => ws.text_range().end(),
+                        _ => comma.text_range().end(),
+                    };
+                    TextRange::from_to(range.start(), end)
+                }
+                None => {
+                    let prev = tree
+                        .syntax()
+                        .siblings_with_tokens(ra_syntax::Direction::Prev)
+                        .skip(1)
+                        .find(|it| it.kind() != WHITESPACE)
+                        .filter(|it| it.kind() == T![,]);
+                    match prev {
+                        Some(comma) => TextRange::from_to(comma.text_range().start(), range.end()),
+                        None => range,
+                    }
+                }
+            };
+            Some((range, String::new()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        check_assist, check_assist_not_applicable, check_assist_not_applicable_in_fixture,
+        check_assist_target,
+    };
+
+    #[test]
+    fn remove_whole_use_item() {
+        check_assist(
+            remove_unused_import,
+            "
+use std::fmt::Debug<|>;
+fn main() {}",
+            "
+<|>fn main() {}",
+        );
+    }
+
+    #[test]
+    fn remove_leaf_and_collapse_list() {
+        check_assist(
+            remove_unused_import,
+            "
+use a::{b, c<|>};
+fn main() { b(); }",
+            "
+use <|>a::b;
+fn main() { b(); }",
+        );
+        check_assist(
+            remove_unused_import,
+            "
+use a::{b<|>, c};
+fn main() { c(); }",
+            "
+use <|>a::c;
+fn main() { c(); }",
+        );
+    }
+
+    #[test]
+    fn remove_leaf_from_longer_list() {
+        check_assist(
+            remove_unused_import,
+            "
+use a::{b, c<|>, d};
+fn main() { b(); d(); }",
+            "
+use a::{b, <|>d};
+fn main() { b(); d(); }",
+        );
+        check_assist(
+            remove_unused_import,
+            "
+use a::{b, c, d<|>};
+fn main() { b(); c(); }",
+            "
+use a::{b, c<|>};
+fn main() { b(); c(); }",
+        );
+    }
+
+    #[test]
+    fn remove_leaf_from_nested_tree() {
+        check_assist(
+            remove_unused_import,
+            "
+use a::{b::{c<|>}, d};
+fn main() { d(); }",
+            "
+use <|>a::d;
+fn main() { d(); }",
+        );
+        check_assist(
+            remove_unused_import,
+            "
+use a::{self, b<|>};
+fn main() { a::c(); }",
+            "
+use <|>a;
+fn main() { a::c(); }",
+        );
+    }
+
+    #[test]
+    fn remove_unused_import_target() {
+        check_assist_target(
+            remove_unused_import,
+            "
+use a::{b, c<|>};
+fn main() { b(); }",
+            "c",
+        );
+    }
+
+    #[test]
+    fn remove_unused_import_not_applicable_to_used_imports() {
+        check_assist_not_applicable(
+            remove_unused_import,
+            "
+use a::b<|>;
+fn main() { b(); }",
+        );
+        check_assist_not_applicable(
+            remove_unused_import,
+            "
+use a::b<|>;
+fn main() { println!(\"{}\", b); }",
+        );
+        check_assist_not_applicable(
+            remove_unused_import,
+            "
+use a::{b as c<|>};
+fn main() { c(); }",
+        );
+    }
+
+    #[test]
+    fn remove_unused_import_not_applicable_to_globs_and_reexports() {
+        check_assist_not_applicable(remove_unused_import, "use a::*<|>;");
+        check_assist_not_applicable(remove_unused_import, "pub use a::b<|>;");
+        check_assist_not_applicable(remove_unused_import, "use a::b<|>; mod tests;");
+    }
+
+    #[test]
+    fn remove_unused_import_not_applicable_to_traits() {
+        check_assist_not_applicable_in_fixture(
+            remove_unused_import,
+            "
+//- /main.rs crate:main deps:dep
+use dep::Tr<|>;
+fn main() { ().f(); }
+
+//- /lib.rs crate:dep
+pub trait Tr { fn f(&self) {} }
+impl Tr for () {}
+",
+        );
+    }
+}
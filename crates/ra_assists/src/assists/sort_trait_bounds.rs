@@ -0,0 +1,110 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, AstNode},
+    SyntaxKind::LIFETIME,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: sort_trait_bounds
+//
+// Sorts trait bounds alphabetically, keeping lifetime bounds first.
+//
+// ```
+// fn foo<T: Hash +<|> 'static + Eq + Clone>() { }
+// ```
+// ->
+// ```
+// fn foo<T: 'static + Clone + Eq + Hash>() { }
+// ```
+pub(crate) fn sort_trait_bounds(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let bound_list = ctx.find_node_at_offset::<ast::TypeBoundList>()?;
+    let bounds = bound_list.bounds().collect::<Vec<_>>();
+    if bounds.len() < 2 {
+        return None;
+    }
+
+    let mut sorted = bounds.clone();
+    // the sort is stable, so lifetime bounds keep their relative order
+    sorted.sort_by_cached_key(|it| (!is_lifetime_bound(it), sort_key(it)));
+    if sorted == bounds {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("sort_trait_bounds"), "sort trait bounds", |edit| {
+        edit.target(bound_list.syntax().text_range());
+        // each bound is replaced as a whole, so associated type bindings like
+        // `Iterator<Item = u32>` move together with their trait
+        for (bound, new_bound) in bounds.iter().zip(sorted.iter()) {
+            if bound != new_bound {
+                edit.replace(bound.syntax().text_range(), new_bound.syntax().text().to_string());
+            }
+        }
+    })
+}
+
+fn is_lifetime_bound(bound: &ast::TypeBound) -> bool {
+    bound.syntax().first_token().map_or(false, |it| it.kind() == LIFETIME)
+}
+
+fn sort_key(bound: &ast::TypeBound) -> String {
+    if is_lifetime_bound(bound) {
+        return String::new();
+    }
+    // `?Sized` sorts as `Sized`
+    bound.syntax().text().to_string().trim_start_matches('?').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn sort_trait_bounds_in_generics() {
+        check_assist(
+            sort_trait_bounds,
+            "fn f<T: C +<|> A + B>(t: T) { }",
+            "fn f<T: A +<|> B + C>(t: T) { }",
+        )
+    }
+
+    #[test]
+    fn sort_trait_bounds_in_where_clause() {
+        check_assist(
+            sort_trait_bounds,
+            "struct S<T> where T: Hash + Eq <|>+ Clone { }",
+            "struct S<T> where T: Clone + Eq <|>+ Hash { }",
+        )
+    }
+
+    #[test]
+    fn sort_trait_bounds_keeps_lifetimes_first() {
+        check_assist(
+            sort_trait_bounds,
+            "fn f<T>(t: T) where T: B + 'b +<|> A + 'a { }",
+            "fn f<T>(t: T) where T: 'b + 'a +<|> A + B { }",
+        )
+    }
+
+    #[test]
+    fn sort_trait_bounds_keeps_assoc_type_bindings() {
+        check_assist(
+            sort_trait_bounds,
+            "fn f<T: Iterator<Item = u32> <|>+ ?Sized + Clone>(t: T) { }",
+            "fn f<T: Clone <|>+ Iterator<Item = u32> + ?Sized>(t: T) { }",
+        )
+    }
+
+    #[test]
+    fn sort_trait_bounds_target() {
+        check_assist_target(sort_trait_bounds, "fn f<T: B +<|> A>(t: T) { }", "B + A")
+    }
+
+    #[test]
+    fn sort_trait_bounds_not_applicable() {
+        check_assist_not_applicable(sort_trait_bounds, "fn f<T: <|>A>(t: T) { }");
+        check_assist_not_applicable(sort_trait_bounds, "fn f<T: 'b + A +<|> B>(t: T) { }");
+    }
+}
@@ -40,10 +40,130 @@ pub(crate) fn split_import(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
     })
 }
 
+// Assist: split_import_list
+//
+// Splits the list of an import into one import per item.
+//
+// ```
+// use std::{<|>collections::HashMap, fmt::{self, Debug}};
+// ```
+// ->
+// ```
+// use std::collections::HashMap;
+// use std::fmt::{self, Debug};
+// ```
+pub(crate) fn split_import_list(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let use_item = ctx.find_node_at_offset::<ast::UseItem>()?;
+    let tree = use_item.use_tree()?;
+    let prefix = list_prefix(&tree)?;
+    let imports = tree.use_tree_list()?.use_trees().map(|it| leaf_import(&prefix, &it)).collect();
+    replace_use_item(
+        ctx,
+        AssistId("split_import_list"),
+        "split into separate imports",
+        use_item,
+        imports,
+    )
+}
+
+// Assist: flatten_import
+//
+// Splits a nested import into one import per imported name.
+//
+// ```
+// use std::{<|>collections::HashMap, fmt::{self, Debug}};
+// ```
+// ->
+// ```
+// use std::collections::HashMap;
+// use std::fmt;
+// use std::fmt::Debug;
+// ```
+pub(crate) fn flatten_import(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let use_item = ctx.find_node_at_offset::<ast::UseItem>()?;
+    let tree = use_item.use_tree()?;
+    // without nested lists, this is the same as `split_import_list`
+    if !tree.use_tree_list()?.use_trees().any(|it| it.use_tree_list().is_some()) {
+        return None;
+    }
+    let mut imports = Vec::new();
+    flatten(String::new(), &tree, &mut imports)?;
+    replace_use_item(
+        ctx,
+        AssistId("flatten_import"),
+        "split into one import per name",
+        use_item,
+        imports,
+    )
+}
+
+fn flatten(prefix: String, tree: &ast::UseTree, acc: &mut Vec<String>) -> Option<()> {
+    match tree.use_tree_list() {
+        Some(list) => {
+            let prefix = format!("{}{}", prefix, list_prefix(tree)?);
+            for child in list.use_trees() {
+                flatten(prefix.clone(), &child, acc)?;
+            }
+        }
+        None => acc.push(leaf_import(&prefix, tree)),
+    }
+    Some(())
+}
+
+/// The path before the list of a use tree, with its `::`, like `std::` for
+/// `std::{fmt, io}`.
+fn list_prefix(tree: &ast::UseTree) -> Option<String> {
+    let list = tree.use_tree_list()?;
+    let len = list.syntax().text_range().start() - tree.syntax().text_range().start();
+    Some(tree.syntax().text().slice(..len).to_string())
+}
+
+/// The import of an item of a list, keeping the `self` of `std::fmt::{self}`
+/// only when there is nothing to attach it to.
+fn leaf_import(prefix: &str, tree: &ast::UseTree) -> String {
+    let text = tree.syntax().text().to_string();
+    let is_self = tree.path().map_or(false, |path| {
+        path.qualifier().is_none()
+            && path.segment().and_then(|it| it.kind()) == Some(ast::PathSegmentKind::SelfKw)
+    });
+    if is_self && prefix.ends_with("::") && prefix.len() > 2 {
+        return format!("{}{}", prefix.trim_end_matches("::"), &text["self".len()..]);
+    }
+    format!("{}{}", prefix, text)
+}
+
+fn replace_use_item(
+    ctx: AssistCtx<impl HirDatabase>,
+    id: AssistId,
+    label: &str,
+    use_item: ast::UseItem,
+    imports: Vec<String>,
+) -> Option<Assist> {
+    if imports.is_empty() {
+        return None;
+    }
+    let tree_range = use_item.use_tree()?.syntax().text_range();
+    // attributes, visibility and `use` keyword
+    let head_len = tree_range.start() - use_item.syntax().text_range().start();
+    let head = use_item.syntax().text().slice(..head_len).to_string();
+    let indent =
+        ra_fmt::leading_indent(use_item.syntax()).map_or(String::new(), |it| it.to_string());
+    let text = imports
+        .iter()
+        .map(|it| format!("{}{};", head, it))
+        .collect::<Vec<_>>()
+        .join(&format!("\n{}", indent));
+    ctx.add_assist(id, label, |edit| {
+        edit.target(use_item.syntax().text_range());
+        edit.replace(use_item.syntax().text_range(), text);
+        edit.set_cursor(use_item.syntax().text_range().start());
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::helpers::{check_assist, check_assist_target};
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
 
     #[test]
     fn test_split_import() {
@@ -67,4 +187,52 @@ mod tests {
     fn split_import_target() {
         check_assist_target(split_import, "use crate::<|>db::{RootDatabase, FileSymbol}", "::");
     }
+
+    #[test]
+    fn split_import_list_one_level() {
+        check_assist(
+            split_import_list,
+            "pub(crate) use std::{<|>fmt::{self, Debug}, collections::HashMap as Map, io};",
+            "<|>pub(crate) use std::fmt::{self, Debug};
+pub(crate) use std::collections::HashMap as Map;
+pub(crate) use std::io;",
+        )
+    }
+
+    #[test]
+    fn split_import_list_self_and_indent() {
+        check_assist(
+            split_import_list,
+            "
+mod m {
+    use foo::{self as f, <|>bar};
+}",
+            "
+mod m {
+    <|>use foo as f;
+    use foo::bar;
+}",
+        )
+    }
+
+    #[test]
+    fn flatten_import_nested() {
+        check_assist(
+            flatten_import,
+            "use std::{<|>fmt::{self, Debug}, collections::{hash_map::{HashMap, Entry}, *}};",
+            "<|>use std::fmt;
+use std::fmt::Debug;
+use std::collections::hash_map::HashMap;
+use std::collections::hash_map::Entry;
+use std::collections::*;",
+        )
+    }
+
+    #[test]
+    fn split_import_list_not_applicable() {
+        check_assist_not_applicable(split_import_list, "use std::<|>fmt::Debug;");
+        check_assist_not_applicable(flatten_import, "use std::<|>fmt::Debug;");
+        // nothing to flatten further
+        check_assist_not_applicable(flatten_import, "use std::{<|>fmt, io};");
+    }
 }
@@ -0,0 +1,161 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, AstNode},
+    SyntaxNode, TextRange,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: swap_arguments
+//
+// Swaps the two arguments around the cursor, in a call or a tuple.
+//
+// ```
+// fn main() {
+//     foo(x,<|> y, z);
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     foo(y, x, z);
+// }
+// ```
+pub(crate) fn swap_arguments(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let (_, args) = arguments_at_offset(&ctx)?;
+    let offset = ctx.frange.range.start();
+    // the cursor has to be between the arguments, not inside one
+    let next_idx = args.iter().position(|it| it.syntax().text_range().start() >= offset)?;
+    if next_idx == 0 || args[next_idx - 1].syntax().text_range().end() > offset {
+        return None;
+    }
+    let (prev, next) = (&args[next_idx - 1], &args[next_idx]);
+
+    ctx.add_assist(AssistId("swap_arguments"), "swap arguments", |edit| {
+        edit.target(TextRange::from_to(
+            prev.syntax().text_range().start(),
+            next.syntax().text_range().end(),
+        ));
+        edit.replace(prev.syntax().text_range(), next.syntax().text().to_string());
+        edit.replace(next.syntax().text_range(), prev.syntax().text().to_string());
+    })
+}
+
+// Assist: reverse_arguments
+//
+// Reverses the order of the arguments of a call or of the elements of a tuple.
+//
+// ```
+// fn main() {
+//     foo(x, <|>y, z);
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     foo(z, y, x);
+// }
+// ```
+pub(crate) fn reverse_arguments(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let (list, args) = arguments_at_offset(&ctx)?;
+    // two arguments are swapped by `swap_arguments`
+    if args.len() < 3 {
+        return None;
+    }
+
+    ctx.add_assist(AssistId("reverse_arguments"), "reverse arguments", |edit| {
+        edit.target(list.text_range());
+        // only the arguments move, the commas and comments between them stay
+        for (arg, new_arg) in args.iter().zip(args.iter().rev()) {
+            if arg != new_arg {
+                edit.replace(arg.syntax().text_range(), new_arg.syntax().text().to_string());
+            }
+        }
+        edit.set_cursor(list.text_range().start());
+    })
+}
+
+/// The innermost argument list or tuple around the cursor, with its elements.
+fn arguments_at_offset(ctx: &AssistCtx<impl HirDatabase>) -> Option<(SyntaxNode, Vec<ast::Expr>)> {
+    let token = ctx.token_at_offset().left_biased()?;
+    token.parent().ancestors().find_map(|node| {
+        if let Some(arg_list) = ast::ArgList::cast(node.clone()) {
+            return Some((node, arg_list.args().collect()));
+        }
+        ast::TupleExpr::cast(node.clone()).map(|tuple| (node, tuple.exprs().collect()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn swap_arguments_of_call() {
+        check_assist(
+            swap_arguments,
+            "fn f() { foo(a, /* first */ b<|>, c) }",
+            "fn f() { foo(a, /* first */ c<|>, b) }",
+        );
+        check_assist(
+            swap_arguments,
+            "fn f() { s.foo(bar(1, 2),<|>\n    3) }",
+            "fn f() { s.foo(3,<|>\n    bar(1, 2)) }",
+        );
+    }
+
+    #[test]
+    fn swap_arguments_of_innermost_call() {
+        check_assist(
+            swap_arguments,
+            "fn f() { foo(bar(1,<|> 2), 3) }",
+            "fn f() { foo(bar(2,<|> 1), 3) }",
+        );
+    }
+
+    #[test]
+    fn swap_tuple_elements() {
+        check_assist(
+            swap_arguments,
+            "fn f() { let t = (1, <|>\"a\"); }",
+            "fn f() { let t = (\"a\", <|>1); }",
+        );
+    }
+
+    #[test]
+    fn swap_arguments_not_applicable() {
+        check_assist_not_applicable(swap_arguments, "fn f() { foo(a, b<|>b) }");
+        check_assist_not_applicable(swap_arguments, "fn f() { foo(<|>a, b) }");
+        check_assist_not_applicable(swap_arguments, "fn f() { foo(a, b,<|>) }");
+    }
+
+    #[test]
+    fn swap_arguments_target() {
+        check_assist_target(swap_arguments, "fn f() { foo(a,<|> b, c) }", "a, b");
+    }
+
+    #[test]
+    fn reverse_arguments_of_call() {
+        check_assist(
+            reverse_arguments,
+            "fn f() { foo(a, b /* b */, c<|>c) }",
+            "fn f() { foo<|>(cc, b /* b */, a) }",
+        );
+    }
+
+    #[test]
+    fn reverse_tuple_elements() {
+        check_assist(
+            reverse_arguments,
+            "fn f() { let t = (1, 2, <|>3, 4); }",
+            "fn f() { let t = <|>(4, 3, 2, 1); }",
+        );
+    }
+
+    #[test]
+    fn reverse_arguments_not_applicable_to_two_arguments() {
+        check_assist_not_applicable(reverse_arguments, "fn f() { foo(a, <|>b) }");
+    }
+}
@@ -0,0 +1,121 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, AstNode, AttrsOwner},
+    SyntaxKind::WHITESPACE,
+    TextRange,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: toggle_ignore
+//
+// Adds `#[ignore]` attribute to the test, or removes it if it is already there.
+//
+// ```
+// #[test]
+// fn arithmetics<|>() {
+//     assert_eq!(2 + 2, 5);
+// }
+// ```
+// ->
+// ```
+// #[test]
+// #[ignore]
+// fn arithmetics() {
+//     assert_eq!(2 + 2, 5);
+// }
+// ```
+pub(crate) fn toggle_ignore(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let fn_def = ctx.find_node_at_offset::<ast::FnDef>()?;
+    let test_attr =
+        fn_def.attrs().find(|it| it.as_simple_atom().map_or(false, |it| it == "test"))?;
+    let ignore_attr = fn_def.attrs().find(|it| it.simple_name().map_or(false, |it| it == "ignore"));
+
+    match ignore_attr {
+        Some(ignore_attr) => {
+            ctx.add_assist(AssistId("toggle_ignore"), "re-enable this test", |edit| {
+                let attr_range = ignore_attr.syntax().text_range();
+                // also remove the whitespace separating the attribute from the next item
+                let end = match ignore_attr.syntax().next_sibling_or_token() {
+                    Some(ws) if ws.kind() == WHITESPACE => ws.text_range().end(),
+                    _ => attr_range.end(),
+                };
+                edit.target(attr_range);
+                edit.delete(TextRange::from_to(attr_range.start(), end));
+            })
+        }
+        None => ctx.add_assist(AssistId("toggle_ignore"), "ignore this test", |edit| {
+            // keep the layout used between `#[test]` and the next item
+            let separator = match test_attr.syntax().next_sibling_or_token() {
+                Some(ws) if ws.kind() == WHITESPACE => ws.to_string(),
+                _ => " ".to_string(),
+            };
+            edit.target(test_attr.syntax().text_range());
+            edit.insert(test_attr.syntax().text_range().end(), format!("{}#[ignore]", separator));
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn ignore_test() {
+        check_assist(
+            toggle_ignore,
+            "
+    #[test]
+    fn foo<|>() {}",
+            "
+    #[test]
+    #[ignore]
+    fn foo<|>() {}",
+        );
+    }
+
+    #[test]
+    fn ignore_test_on_one_line() {
+        check_assist(toggle_ignore, "#[test] fn foo<|>() {}", "#[test] #[ignore] fn foo<|>() {}");
+    }
+
+    #[test]
+    fn re_enable_test() {
+        check_assist(
+            toggle_ignore,
+            "
+#[test]
+#[ignore]
+fn foo<|>() {}",
+            "
+#[test]
+fn foo<|>() {}",
+        );
+    }
+
+    #[test]
+    fn re_enable_test_ignored_with_reason() {
+        check_assist(
+            toggle_ignore,
+            r#"
+#[ignore = "slow"]
+#[test]
+fn foo<|>() {}"#,
+            "
+#[test]
+fn foo<|>() {}",
+        );
+    }
+
+    #[test]
+    fn toggle_ignore_target() {
+        check_assist_target(toggle_ignore, "#[test]\nfn foo<|>() {}", "#[test]");
+    }
+
+    #[test]
+    fn toggle_ignore_not_applicable_to_non_tests() {
+        check_assist_not_applicable(toggle_ignore, "fn foo<|>() {}");
+        check_assist_not_applicable(toggle_ignore, "#[test_case]\nfn foo<|>() {}");
+    }
+}
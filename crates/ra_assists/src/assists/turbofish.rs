@@ -0,0 +1,284 @@
+use hir::{db::HirDatabase, AssocItem, Function, GenericDef, ModuleDef, PathResolution};
+use ra_syntax::{
+    ast::{self, AstNode},
+    TextUnit,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: add_turbofish
+//
+// Adds placeholders for the type arguments of a generic function call.
+//
+// ```
+// fn make<T>() -> T { unimplemented!() }
+// fn main() {
+//     let x = make<|>();
+// }
+// ```
+// ->
+// ```
+// fn make<T>() -> T { unimplemented!() }
+// fn main() {
+//     let x = make::<_>();
+// }
+// ```
+pub(crate) fn add_turbofish(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let call = GenericCall::at_offset(&ctx)?;
+    if call.type_arg_list.is_some() {
+        return None;
+    }
+    let num_params = GenericDef::from(call.func).type_params(ctx.db).len();
+    if num_params == 0 {
+        return None;
+    }
+    ctx.add_assist(AssistId("add_turbofish"), "add `::<>`", |edit| {
+        let offset = call.name_ref.syntax().text_range().end();
+        let placeholders = vec!["_"; num_params].join(", ");
+        edit.target(call.name_ref.syntax().text_range());
+        edit.insert(offset, format!("::<{}>", placeholders));
+        edit.set_cursor(offset + TextUnit::of_str("::<"));
+    })
+}
+
+// Assist: remove_turbofish
+//
+// Removes the explicit type arguments of a generic function call, leaving
+// them to type inference.
+//
+// ```
+// fn make<T>() -> T { unimplemented!() }
+// fn main() {
+//     let x: u32 = make<|>::<u32>();
+// }
+// ```
+// ->
+// ```
+// fn make<T>() -> T { unimplemented!() }
+// fn main() {
+//     let x: u32 = make();
+// }
+// ```
+pub(crate) fn remove_turbofish(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let call = GenericCall::at_offset(&ctx)?;
+    let type_arg_list = call.type_arg_list?;
+    ctx.add_assist(AssistId("remove_turbofish"), "remove `::<>`", |edit| {
+        edit.target(type_arg_list.syntax().text_range());
+        edit.delete(type_arg_list.syntax().text_range());
+    })
+}
+
+/// A call of a function or method, with the cursor on the callee's name.
+struct GenericCall {
+    name_ref: ast::NameRef,
+    type_arg_list: Option<ast::TypeArgList>,
+    func: Function,
+}
+
+impl GenericCall {
+    fn at_offset(ctx: &AssistCtx<impl HirDatabase>) -> Option<GenericCall> {
+        let name_ref = ctx.find_node_at_offset::<ast::NameRef>()?;
+        let parent = name_ref.syntax().parent()?;
+        if let Some(call) = ast::MethodCallExpr::cast(parent.clone()) {
+            let analyzer = ctx.source_analyzer(call.syntax(), None);
+            let func = analyzer.resolve_method_call(&call)?;
+            return Some(GenericCall { name_ref, type_arg_list: call.type_arg_list(), func });
+        }
+
+        let segment = ast::PathSegment::cast(parent)?;
+        let path = segment.syntax().parent().and_then(ast::Path::cast)?;
+        // only the last segment has the type arguments of the function
+        if path.syntax().parent().and_then(ast::Path::cast).is_some() {
+            return None;
+        }
+        let path_expr = path.syntax().parent().and_then(ast::PathExpr::cast)?;
+        let call = path_expr.syntax().parent().and_then(ast::CallExpr::cast)?;
+        if call.expr()?.syntax() != path_expr.syntax() {
+            return None;
+        }
+        let analyzer = ctx.source_analyzer(call.syntax(), None);
+        let func = match analyzer.resolve_path(ctx.db, &path)? {
+            PathResolution::Def(ModuleDef::Function(it)) => it,
+            PathResolution::AssocItem(AssocItem::Function(it)) => it,
+            _ => return None,
+        };
+        Some(GenericCall { name_ref, type_arg_list: segment.type_arg_list(), func })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{check_assist, check_assist_not_applicable, check_assist_target};
+
+    #[test]
+    fn add_turbofish_to_function_call() {
+        check_assist(
+            add_turbofish,
+            "
+fn make<T>() -> T {}
+fn main() {
+    make<|>();
+}",
+            "
+fn make<T>() -> T {}
+fn main() {
+    make::<<|>_>();
+}",
+        );
+    }
+
+    #[test]
+    fn add_turbofish_with_several_params() {
+        check_assist(
+            add_turbofish,
+            "
+fn make<A, B>() -> (A, B) {}
+fn main() {
+    make<|>();
+}",
+            "
+fn make<A, B>() -> (A, B) {}
+fn main() {
+    make::<<|>_, _>();
+}",
+        );
+    }
+
+    #[test]
+    fn add_turbofish_to_method_call() {
+        check_assist(
+            add_turbofish,
+            "
+struct S;
+impl S {
+    fn make<T>(&self) -> T {}
+}
+fn main() {
+    S.make<|>();
+}",
+            "
+struct S;
+impl S {
+    fn make<T>(&self) -> T {}
+}
+fn main() {
+    S.make::<<|>_>();
+}",
+        );
+    }
+
+    #[test]
+    fn add_turbofish_to_qualified_call() {
+        check_assist(
+            add_turbofish,
+            "
+mod m {
+    pub fn make<T>() -> T {}
+}
+fn main() {
+    m::make<|>();
+}",
+            "
+mod m {
+    pub fn make<T>() -> T {}
+}
+fn main() {
+    m::make::<<|>_>();
+}",
+        );
+    }
+
+    #[test]
+    fn add_turbofish_target() {
+        check_assist_target(
+            add_turbofish,
+            "
+fn make<T>() -> T {}
+fn main() {
+    make<|>();
+}",
+            "make",
+        );
+    }
+
+    #[test]
+    fn add_turbofish_not_applicable() {
+        check_assist_not_applicable(
+            add_turbofish,
+            "
+fn make() -> u32 {}
+fn main() {
+    make<|>();
+}",
+        );
+        check_assist_not_applicable(
+            add_turbofish,
+            "
+fn make<T>() -> T {}
+fn main() {
+    make<|>::<u32>();
+}",
+        );
+        check_assist_not_applicable(
+            add_turbofish,
+            "
+fn make<T>() -> T {}
+fn main() {
+    let f = make<|>;
+}",
+        );
+    }
+
+    #[test]
+    fn remove_turbofish_from_function_call() {
+        check_assist(
+            remove_turbofish,
+            "
+fn make<T>() -> T {}
+fn main() {
+    let x: u32 = make<|>::<u32>();
+}",
+            "
+fn make<T>() -> T {}
+fn main() {
+    let x: u32 = make<|>();
+}",
+        );
+    }
+
+    #[test]
+    fn remove_turbofish_from_method_call() {
+        check_assist(
+            remove_turbofish,
+            "
+struct S;
+impl S {
+    fn make<T>(&self) -> T {}
+}
+fn main() {
+    S.make<|>::<_>();
+}",
+            "
+struct S;
+impl S {
+    fn make<T>(&self) -> T {}
+}
+fn main() {
+    S.make<|>();
+}",
+        );
+    }
+
+    #[test]
+    fn remove_turbofish_not_applicable_without_type_args() {
+        check_assist_not_applicable(
+            remove_turbofish,
+            "
+fn make<T>() -> T {}
+fn main() {
+    make<|>();
+}",
+        );
+    }
+}
@@ -0,0 +1,280 @@
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, AstNode},
+    Direction, NodeOrToken, SyntaxKind, SyntaxNode, TextRange,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: wrap_in_block
+//
+// Wraps the selected statements (or the statement under the cursor) in a new block.
+//
+// ```
+// fn main() {
+//     <|>let x = 92;
+//     foo(x);<|>
+//     bar();
+// }
+// ```
+// ->
+// ```
+// fn main() {
+//     {
+//         let x = 92;
+//         foo(x);
+//     }
+//     bar();
+// }
+// ```
+pub(crate) fn wrap_in_block(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    if let Some(expr) = selected_expr(&ctx) {
+        return ctx.add_assist(AssistId("wrap_in_block"), "wrap in block", |edit| {
+            edit.target(expr.syntax().text_range());
+            edit.replace(expr.syntax().text_range(), format!("{{ {} }}", expr.syntax().text()));
+            edit.set_cursor(expr.syntax().text_range().start());
+        });
+    }
+
+    let stmts = selected_statements(&ctx)?;
+    let first = stmts.first()?.clone();
+    let last = stmts.last()?.clone();
+    let range = TextRange::from_to(first.text_range().start(), last.text_range().end());
+
+    ctx.add_assist(AssistId("wrap_in_block"), "wrap in block", |edit| {
+        let indent = IndentLevel::from_node(&first);
+        let mut buf = String::from("{\n");
+        buf.push_str(&indent_str(indent.0 + 1));
+        for element in first.siblings_with_tokens(Direction::Next) {
+            match &element {
+                NodeOrToken::Node(node) => buf.push_str(&indent_by_one_level(node)),
+                NodeOrToken::Token(token) => {
+                    buf.push_str(token.text());
+                    if token.kind() == SyntaxKind::WHITESPACE && token.text().contains('\n') {
+                        buf.push_str(&indent_str(1));
+                    }
+                }
+            }
+            if element.as_node() == Some(&last) {
+                break;
+            }
+        }
+        buf.push('\n');
+        buf.push_str(&indent_str(indent.0));
+        buf.push('}');
+
+        edit.target(range);
+        edit.replace(range, buf);
+        edit.set_cursor(range.start());
+    })
+}
+
+/// A non-empty selection of exactly one expression, which is not a statement
+/// on its own, is wrapped inline.
+fn selected_expr(ctx: &AssistCtx<impl HirDatabase>) -> Option<ast::Expr> {
+    let range = ctx.frange.range;
+    if range.is_empty() {
+        return None;
+    }
+    let node = ctx.covering_element().into_node()?;
+    if node.text_range() != range || is_block_child(&node) {
+        return None;
+    }
+    match ast::Expr::cast(node)? {
+        ast::Expr::BlockExpr(_) => None,
+        expr => Some(expr),
+    }
+}
+
+/// The statements of a block touched by the selection, or the innermost
+/// statement containing the cursor.
+fn selected_statements(ctx: &AssistCtx<impl HirDatabase>) -> Option<Vec<SyntaxNode>> {
+    let range = ctx.frange.range;
+    let covering = ctx.covering_element();
+    if let Some(block) = covering.as_node().cloned().and_then(ast::Block::cast) {
+        let stmts: Vec<_> = block
+            .syntax()
+            .children()
+            .filter(|it| is_block_child(it))
+            .filter(|it| {
+                let child = it.text_range();
+                child.start() < range.end() && range.start() < child.end()
+            })
+            .collect();
+        return if stmts.is_empty() { None } else { Some(stmts) };
+    }
+    let stmt = covering.ancestors().find(is_block_child)?;
+    Some(vec![stmt])
+}
+
+fn is_block_child(node: &SyntaxNode) -> bool {
+    let parent_is_block = node.parent().map(|it| ast::Block::can_cast(it.kind())) == Some(true);
+    parent_is_block && (ast::Stmt::can_cast(node.kind()) || ast::Expr::can_cast(node.kind()))
+}
+
+fn indent_by_one_level(node: &SyntaxNode) -> String {
+    let indent = IndentLevel(1);
+    if let Some(stmt) = ast::Stmt::cast(node.clone()) {
+        indent.increase_indent(stmt).syntax().to_string()
+    } else if let Some(expr) = ast::Expr::cast(node.clone()) {
+        indent.increase_indent(expr).syntax().to_string()
+    } else {
+        node.to_string()
+    }
+}
+
+fn indent_str(level: u8) -> String {
+    " ".repeat(level as usize * 4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::{
+        check_assist, check_assist_not_applicable, check_assist_range, check_assist_range_target,
+    };
+
+    #[test]
+    fn wrap_statement_under_cursor() {
+        check_assist(
+            wrap_in_block,
+            "
+fn main() {
+    foo(<|>92);
+    bar();
+}",
+            "
+fn main() {
+    <|>{
+        foo(92);
+    }
+    bar();
+}",
+        );
+    }
+
+    #[test]
+    fn wrap_selected_statements() {
+        check_assist_range(
+            wrap_in_block,
+            "
+fn main() {
+    <|>let x = 92;
+    // comment
+    foo(x);<|>
+    bar();
+}",
+            "
+fn main() {
+    <|>{
+        let x = 92;
+        // comment
+        foo(x);
+    }
+    bar();
+}",
+        );
+    }
+
+    #[test]
+    fn wrap_partially_selected_statements() {
+        check_assist_range(
+            wrap_in_block,
+            "
+fn main() {
+    let x = <|>92;
+    foo<|>(x);
+    bar();
+}",
+            "
+fn main() {
+    <|>{
+        let x = 92;
+        foo(x);
+    }
+    bar();
+}",
+        );
+    }
+
+    #[test]
+    fn wrap_reindents_nested_blocks() {
+        check_assist_range(
+            wrap_in_block,
+            "
+fn main() {
+    if true {
+        <|>foo();
+        if false {
+            bar();
+        }<|>
+    }
+}",
+            "
+fn main() {
+    if true {
+        <|>{
+            foo();
+            if false {
+                bar();
+            }
+        }
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn wrap_tail_expression() {
+        check_assist(
+            wrap_in_block,
+            "
+fn main() -> i32 {
+    foo();
+    <|>92
+}",
+            "
+fn main() -> i32 {
+    foo();
+    <|>{
+        92
+    }
+}",
+        );
+    }
+
+    #[test]
+    fn wrap_selected_expression_inline() {
+        check_assist_range(
+            wrap_in_block,
+            "
+fn main() {
+    foo(<|>1 + 2<|>);
+}",
+            "
+fn main() {
+    foo(<|>{ 1 + 2 });
+}",
+        );
+    }
+
+    #[test]
+    fn wrap_in_block_target() {
+        check_assist_range_target(
+            wrap_in_block,
+            "
+fn main() {
+    <|>foo();
+    bar();<|>
+    baz();
+}",
+            "foo();
+    bar();",
+        );
+    }
+
+    #[test]
+    fn wrap_in_block_not_applicable_outside_of_function() {
+        check_assist_not_applicable(wrap_in_block, "struct <|>Foo;");
+    }
+}
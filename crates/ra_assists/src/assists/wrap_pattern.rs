@@ -0,0 +1,473 @@
+use std::iter::once;
+
+use hir::db::HirDatabase;
+use ra_syntax::{
+    ast::{self, edit::IndentLevel, make, AstNode, NameOwner, TypeAscriptionOwner},
+    SyntaxNode, TextRange,
+};
+
+use crate::{Assist, AssistCtx, AssistId};
+
+// Assist: wrap_pattern_in_some
+//
+// Wraps the binding of a match arm or an `if let` in `Some` or `Ok`,
+// depending on the type of the matched value.
+//
+// ```
+// enum Option<T> { Some(T), None }
+// use Option::{None, Some};
+//
+// fn handle(x: Option<u32>) {
+//     match x {
+//         <|>n => foo(n),
+//         None => bar(),
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Option<T> { Some(T), None }
+// use Option::{None, Some};
+//
+// fn handle(x: Option<u32>) {
+//     match x {
+//         Some(n) => foo(n),
+//         None => bar(),
+//     }
+// }
+// ```
+pub(crate) fn wrap_pattern_in_some(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let bind_pat = ctx.find_node_at_offset::<ast::BindPat>()?;
+    let scrutinee = match_scrutinee(bind_pat.syntax())?;
+    let analyzer = ctx.source_analyzer(bind_pat.syntax(), None);
+    // `None` is a `BindPat` too, the name can't be one of a unit variant or constant
+    let name_ref = make::name_ref(bind_pat.name()?.text());
+    match analyzer.resolve_path(ctx.db, &make::path_from_name_ref(name_ref)) {
+        Some(hir::PathResolution::Def(hir::ModuleDef::EnumVariant(_)))
+        | Some(hir::PathResolution::Def(hir::ModuleDef::Const(_)))
+        | Some(hir::PathResolution::Def(hir::ModuleDef::Static(_)))
+        | Some(hir::PathResolution::Def(hir::ModuleDef::Adt(_))) => return None,
+        _ => (),
+    }
+    let ty = analyzer.type_of(ctx.db, &scrutinee)?;
+    let variant = wrapping_variant(ctx.db, &ty)?;
+
+    ctx.add_assist(
+        AssistId("wrap_pattern_in_some"),
+        format!("wrap pattern in `{}`", variant),
+        |edit| {
+            edit.target(bind_pat.syntax().text_range());
+            let path = make::path_from_name_ref(make::name_ref(variant));
+            let pat = make::tuple_struct_pat(path, once(bind_pat.clone().into()));
+            edit.replace_ast::<ast::Pat>(bind_pat.into(), pat.into());
+        },
+    )
+}
+
+// Assist: unwrap_pattern_in_some
+//
+// Unwraps the `Some` or `Ok` pattern of a match arm or an `if let`, binding
+// the matched value itself.
+//
+// ```
+// enum Option<T> { Some(T), None }
+// use Option::Some;
+//
+// fn handle(x: Option<u32>) {
+//     if let <|>Some(n) = x {
+//         foo(n);
+//     }
+// }
+// ```
+// ->
+// ```
+// enum Option<T> { Some(T), None }
+// use Option::Some;
+//
+// fn handle(x: Option<u32>) {
+//     if let n = x {
+//         foo(n);
+//     }
+// }
+// ```
+pub(crate) fn unwrap_pattern_in_some(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let tuple_pat = ctx.find_node_at_offset::<ast::TupleStructPat>()?;
+    let scrutinee = match_scrutinee(tuple_pat.syntax())?;
+    let mut args = tuple_pat.args();
+    let inner = args.next()?;
+    if args.next().is_some() {
+        return None;
+    }
+    let path = tuple_pat.path()?;
+    let analyzer = ctx.source_analyzer(tuple_pat.syntax(), None);
+    match analyzer.resolve_path(ctx.db, &path) {
+        Some(hir::PathResolution::Def(hir::ModuleDef::EnumVariant(_))) => (),
+        _ => return None,
+    }
+    let ty = analyzer.type_of(ctx.db, &scrutinee)?;
+    let variant = wrapping_variant(ctx.db, &ty)?;
+    let segment = path.segment()?.name_ref()?;
+    if segment.text() != variant {
+        return None;
+    }
+
+    ctx.add_assist(
+        AssistId("unwrap_pattern_in_some"),
+        format!("unwrap pattern in `{}`", variant),
+        |edit| {
+            edit.target(tuple_pat.syntax().text_range());
+            edit.replace_ast::<ast::Pat>(tuple_pat.into(), inner);
+        },
+    )
+}
+
+// Assist: replace_let_with_if_let
+//
+// Replaces a `let` of an `Option` or a `Result` with an `if let`, which
+// takes the rest of the block.
+//
+// ```
+// enum Option<T> { Some(T), None }
+// use Option::Some;
+//
+// fn handle(x: Option<u32>) {
+//     let <|>n = x;
+//     foo(n);
+// }
+// ```
+// ->
+// ```
+// enum Option<T> { Some(T), None }
+// use Option::Some;
+//
+// fn handle(x: Option<u32>) {
+//     if let Some(n) = x {
+//         foo(n);
+//     }
+// }
+// ```
+pub(crate) fn replace_let_with_if_let(ctx: AssistCtx<impl HirDatabase>) -> Option<Assist> {
+    let let_stmt = ctx.find_node_at_offset::<ast::LetStmt>()?;
+    // the type of the binding would change
+    if let_stmt.ascribed_type().is_some() {
+        return None;
+    }
+    let pat = let_stmt.pat()?;
+    let initializer = let_stmt.initializer()?;
+    // `if let Some(x) = S { .. } { .. }` doesn't parse
+    if let ast::Expr::RecordLit(_) = initializer {
+        return None;
+    }
+    let block = ast::Block::cast(let_stmt.syntax().parent()?)?;
+    // the value of the block would be lost without an `else`
+    if block.expr().is_some() {
+        return None;
+    }
+    let analyzer = ctx.source_analyzer(let_stmt.syntax(), None);
+    let ty = analyzer.type_of(ctx.db, &initializer)?;
+    let variant = wrapping_variant(ctx.db, &ty)?;
+
+    // the rest of the block, up to its last statement or expression
+    let rest_end = block.syntax().children().last()?.text_range().end();
+    let rest_range = TextRange::from_to(let_stmt.syntax().text_range().end(), rest_end);
+
+    ctx.add_assist(AssistId("replace_let_with_if_let"), "replace with if let", |edit| {
+        let block_text = block.syntax().to_string();
+        let start = block.syntax().text_range().start();
+        let rest = &block_text
+            [(rest_range.start() - start).to_usize()..(rest_range.end() - start).to_usize()];
+        let rest = rest.trim_start();
+
+        let head = format!("if let {}({}) = {}", variant, pat.syntax(), initializer.syntax());
+        let if_let = if rest.is_empty() {
+            format!("{} {{}}", head)
+        } else {
+            let indent = "    ".repeat(IndentLevel::from_node(let_stmt.syntax()).0 as usize);
+            let body = rest
+                .lines()
+                .map(|line| if line.is_empty() { String::new() } else { format!("    {}", line) })
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("{} {{\n{}{}\n{}}}", head, indent, body.trim_start(), indent)
+        };
+
+        edit.target(let_stmt.syntax().text_range());
+        edit.replace(TextRange::from_to(let_stmt.syntax().text_range().start(), rest_end), if_let);
+        edit.set_cursor(let_stmt.syntax().text_range().start());
+    })
+}
+
+/// The expression matched against the whole pattern of a match arm, an
+/// `if let` or a `while let`.
+fn match_scrutinee(pat: &SyntaxNode) -> Option<ast::Expr> {
+    let parent = pat.parent()?;
+    if let Some(arm) = ast::MatchArm::cast(parent.clone()) {
+        let match_expr = ast::MatchExpr::cast(arm.syntax().parent()?.parent()?)?;
+        return match_expr.expr();
+    }
+    ast::Condition::cast(parent)?.expr()
+}
+
+/// The variant wrapping the successful value of an `Option` or a `Result`.
+/// Like the `Some` and `Ok` of the patterns, the enums are known by their names.
+fn wrapping_variant(db: &impl HirDatabase, ty: &hir::Type) -> Option<&'static str> {
+    let enum_ = match ty.as_adt()? {
+        hir::Adt::Enum(it) => it,
+        _ => return None,
+    };
+    let variant = match enum_.name(db).to_string().as_str() {
+        "Option" => "Some",
+        "Result" => "Ok",
+        _ => return None,
+    };
+    let has_variant = enum_.variants(db).into_iter().any(|it| it.name(db).to_string() == variant);
+    if has_variant {
+        Some(variant)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::helpers::{
+        check_assist_in_fixture, check_assist_not_applicable,
+        check_assist_not_applicable_in_fixture, check_assist_target,
+    };
+
+    const STD: &str = r#"
+//- /std/lib.rs crate:std
+pub mod option {
+    pub enum Option<T> { Some(T), None }
+}
+pub mod result {
+    pub enum Result<T, E> { Ok(T), Err(E) }
+}
+"#;
+
+    fn with_std(code: &str) -> String {
+        format!("//- /main.rs crate:main deps:std\n{}\n{}", code, STD)
+    }
+
+    #[test]
+    fn wrap_match_arm_pattern_in_some() {
+        check_assist_in_fixture(
+            wrap_pattern_in_some,
+            &with_std(
+                "use std::option::Option::{self, None};
+fn f(x: Option<u32>) {
+    match x {
+        None => (),
+        n<|> => foo(n),
+    }
+}",
+            ),
+            "use std::option::Option::{self, None};
+fn f(x: Option<u32>) {
+    match x {
+        None => (),
+        Some(n) => foo(n),
+    }
+}
+
+",
+        );
+    }
+
+    #[test]
+    fn wrap_if_let_pattern_in_ok() {
+        check_assist_in_fixture(
+            wrap_pattern_in_some,
+            &with_std(
+                "use std::result::Result;
+fn f(x: Result<u32, ()>) {
+    if let ref <|>n = x {}
+}",
+            ),
+            "use std::result::Result;
+fn f(x: Result<u32, ()>) {
+    if let Ok(ref n) = x {}
+}
+
+",
+        );
+    }
+
+    #[test]
+    fn wrap_pattern_in_some_not_applicable() {
+        // the value isn't an `Option`
+        check_assist_not_applicable(
+            wrap_pattern_in_some,
+            "fn f(x: u32) { match x { n<|> => (), } }",
+        );
+        // `None` is a variant, not a binding
+        check_assist_not_applicable_in_fixture(
+            wrap_pattern_in_some,
+            &with_std(
+                "use std::option::Option::{self, None};
+fn f(x: Option<u32>) { match x { No<|>ne => (), _ => () } }",
+            ),
+        );
+        // the binding is already in a pattern
+        check_assist_not_applicable_in_fixture(
+            wrap_pattern_in_some,
+            &with_std(
+                "use std::option::Option::{self, Some};
+fn f(x: Option<u32>) { if let Some(n<|>) = x {} }",
+            ),
+        );
+    }
+
+    #[test]
+    fn unwrap_match_arm_pattern_in_some() {
+        check_assist_in_fixture(
+            unwrap_pattern_in_some,
+            &with_std(
+                "use std::option::Option::{self, None, Some};
+fn f(x: Option<u32>) {
+    match x {
+        None => (),
+        <|>Some(n) => foo(n),
+    }
+}",
+            ),
+            "use std::option::Option::{self, None, Some};
+fn f(x: Option<u32>) {
+    match x {
+        None => (),
+        n => foo(n),
+    }
+}
+
+",
+        );
+    }
+
+    #[test]
+    fn unwrap_if_let_pattern_in_ok() {
+        check_assist_in_fixture(
+            unwrap_pattern_in_some,
+            &with_std(
+                "use std::result::Result::{self, Ok};
+fn f(x: Result<(u32, u8), ()>) {
+    if let O<|>k((a, b)) = x {}
+}",
+            ),
+            "use std::result::Result::{self, Ok};
+fn f(x: Result<(u32, u8), ()>) {
+    if let (a, b) = x {}
+}
+
+",
+        );
+    }
+
+    #[test]
+    fn unwrap_pattern_in_some_not_applicable() {
+        // the variant isn't the successful one
+        check_assist_not_applicable_in_fixture(
+            unwrap_pattern_in_some,
+            &with_std(
+                "use std::result::Result::{self, Err};
+fn f(x: Result<u32, u32>) { if let E<|>rr(n) = x {} }",
+            ),
+        );
+        // the pattern is nested
+        check_assist_not_applicable_in_fixture(
+            unwrap_pattern_in_some,
+            &with_std(
+                "use std::option::Option::{self, Some};
+fn f(x: (Option<u32>, u8)) { if let (S<|>ome(n), _) = x {} }",
+            ),
+        );
+    }
+
+    #[test]
+    fn replace_let_with_if_let_takes_rest_of_block() {
+        check_assist_in_fixture(
+            replace_let_with_if_let,
+            &with_std(
+                "use std::option::Option;
+fn f(x: Option<u32>) {
+    let y = 1;
+    let <|>n = x;
+    foo(n);
+
+    bar(y);
+}",
+            ),
+            "use std::option::Option;
+fn f(x: Option<u32>) {
+    let y = 1;
+    <|>if let Some(n) = x {
+        foo(n);
+
+        bar(y);
+    }
+}
+
+",
+        );
+    }
+
+    #[test]
+    fn replace_let_with_if_let_at_end_of_block() {
+        check_assist_in_fixture(
+            replace_let_with_if_let,
+            &with_std(
+                "use std::result::Result;
+fn f(x: Result<(u32, u8), ()>) {
+    foo();
+    let (a, <|>b) = x;
+}",
+            ),
+            "use std::result::Result;
+fn f(x: Result<(u32, u8), ()>) {
+    foo();
+    <|>if let Ok((a, b)) = x {}
+}
+
+",
+        );
+    }
+
+    #[test]
+    fn replace_let_with_if_let_not_applicable() {
+        check_assist_not_applicable(replace_let_with_if_let, "fn f() { let x<|> = 1; }");
+        check_assist_not_applicable_in_fixture(
+            replace_let_with_if_let,
+            &with_std(
+                "use std::option::Option;
+fn f(x: Option<u32>) { let n<|>: Option<u32> = x; }",
+            ),
+        );
+        // the block has a value
+        check_assist_not_applicable_in_fixture(
+            replace_let_with_if_let,
+            &with_std(
+                "use std::option::Option;
+fn f(x: Option<u32>) -> u32 { let n<|> = x; 92 }",
+            ),
+        );
+    }
+
+    #[test]
+    fn wrap_pattern_target() {
+        check_assist_target(
+            wrap_pattern_in_some,
+            "
+enum Result<T, E> { Ok(T), Err(E) }
+fn f(x: Result<u32, ()>) { while let n<|> = x {} }",
+            "n",
+        );
+        check_assist_target(
+            replace_let_with_if_let,
+            "
+enum Option<T> { Some(T), None }
+fn f(x: Option<u32>) { let n<|> = x; foo(n); }",
+            "let n = x;",
+        );
+    }
+}
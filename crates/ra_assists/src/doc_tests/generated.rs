@@ -153,7 +153,7 @@ trait T {
 }
 
 impl T for () {
-    fn foo(&self) { unimplemented!() }
+    fn foo(&self) { todo!() }
 
 }
 "#####,
@@ -175,6 +175,52 @@ fn process(map: HashMap<String, String>) {}
     )
 }
 
+#[test]
+fn doctest_add_missing_match_arms() {
+    check(
+        "add_missing_match_arms",
+        r#####"
+enum Action { Move { distance: u32 }, Jump(u32), Stop }
+
+fn handle(action: Action) {
+    match action {
+        Action::Stop => (),<|>
+    }
+}
+"#####,
+        r#####"
+enum Action { Move { distance: u32 }, Jump(u32), Stop }
+
+fn handle(action: Action) {
+    match action {
+        Action::Stop => (),
+        Action::Move { .. } => todo!(),
+        Action::Jump(_) => todo!(),
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_add_mut() {
+    check(
+        "add_mut",
+        r#####"
+fn main() {
+    let <|>x = 92;
+    let y = &x;
+}
+"#####,
+        r#####"
+fn main() {
+    let mut x = 92;
+    let y = &x;
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_add_new() {
     check(
@@ -197,6 +243,25 @@ impl<T: Clone> Ctx<T> {
     )
 }
 
+#[test]
+fn doctest_add_turbofish() {
+    check(
+        "add_turbofish",
+        r#####"
+fn make<T>() -> T { unimplemented!() }
+fn main() {
+    let x = make<|>();
+}
+"#####,
+        r#####"
+fn make<T>() -> T { unimplemented!() }
+fn main() {
+    let x = make::<_>();
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_apply_demorgan() {
     check(
@@ -222,7 +287,75 @@ fn doctest_change_visibility() {
 <|>fn frobnicate() {}
 "#####,
         r#####"
-pub(crate) fn frobnicate() {}
+pub fn frobnicate() {}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_for_loop_to_iterator_chain() {
+    check(
+        "convert_for_loop_to_iterator_chain",
+        r#####"
+fn main() {
+    let mut out = Vec::new();
+    <|>for x in xs {
+        out.push(x * 2);
+    }
+}
+"#####,
+        r#####"
+fn main() {
+    let mut out = Vec::new();
+    out.extend(xs.into_iter().map(|x| x * 2));
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_loop_to_while() {
+    check(
+        "convert_loop_to_while",
+        r#####"
+fn main() {
+    <|>loop {
+        if !cond {
+            break;
+        }
+        foo();
+    }
+}
+"#####,
+        r#####"
+fn main() {
+    while cond {
+        foo();
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_convert_named_struct_to_tuple_struct() {
+    check(
+        "convert_named_struct_to_tuple_struct",
+        r#####"
+struct <|>Point { x: f32, y: f32 }
+
+fn norm(p: Point) -> f32 {
+    let Point { x, y } = p;
+    (x * x + y * y).sqrt()
+}
+"#####,
+        r#####"
+struct Point(f32, f32);
+
+fn norm(p: Point) -> f32 {
+    let Point(x, y) = p;
+    (x * x + y * y).sqrt()
+}
 "#####,
     )
 }
@@ -251,6 +384,69 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_while_to_loop() {
+    check(
+        "convert_while_to_loop",
+        r#####"
+fn main() {
+    <|>while cond {
+        foo();
+    }
+}
+"#####,
+        r#####"
+fn main() {
+    loop {
+        if !cond {
+            break;
+        }
+        foo();
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_extract_import_to_top() {
+    check(
+        "extract_import_to_top",
+        r#####"
+mod foo { pub struct Bar; }
+
+fn main() {
+    use foo::Bar<|>;
+    let bar = Bar;
+}
+"#####,
+        r#####"
+use foo::Bar;
+
+mod foo { pub struct Bar; }
+
+fn main() {
+    let bar = Bar;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_extract_struct_from_enum_variant() {
+    check(
+        "extract_struct_from_enum_variant",
+        r#####"
+enum A { <|>One { a: u32, b: u32 } }
+"#####,
+        r#####"
+struct One { a: u32, b: u32 }
+
+enum A { One(One) }
+"#####,
+    )
+}
+
 #[test]
 fn doctest_fill_match_arms() {
     check(
@@ -277,6 +473,21 @@ fn handle(action: Action) {
     )
 }
 
+#[test]
+fn doctest_flatten_import() {
+    check(
+        "flatten_import",
+        r#####"
+use std::{<|>collections::HashMap, fmt::{self, Debug}};
+"#####,
+        r#####"
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Debug;
+"#####,
+    )
+}
+
 #[test]
 fn doctest_flip_binexpr() {
     check(
@@ -324,6 +535,50 @@ fn foo<T: Copy + Clone>() { }
     )
 }
 
+#[test]
+fn doctest_generate_getter() {
+    check(
+        "generate_getter",
+        r#####"
+struct Person {
+    name<|>: String,
+}
+"#####,
+        r#####"
+struct Person {
+    name: String,
+}
+
+impl Person {
+    pub fn name(&self) -> &String { &self.name }
+}
+
+"#####,
+    )
+}
+
+#[test]
+fn doctest_generate_setter() {
+    check(
+        "generate_setter",
+        r#####"
+struct Person {
+    name<|>: String,
+}
+"#####,
+        r#####"
+struct Person {
+    name: String,
+}
+
+impl Person {
+    pub fn set_name(&mut self, name: String) { self.name = name; }
+}
+
+"#####,
+    )
+}
+
 #[test]
 fn doctest_inline_local_variable() {
     check(
@@ -342,6 +597,23 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_introduce_named_lifetime() {
+    check(
+        "introduce_named_lifetime",
+        r#####"
+fn first<|>(s: &str) -> &str {
+    &s[..1]
+}
+"#####,
+        r#####"
+fn first<'a>(s: &'a str) -> &'a str {
+    &s[..1]
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_introduce_variable() {
     check(
@@ -377,6 +649,40 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_make_function_async() {
+    check(
+        "make_function_async",
+        r#####"
+pub unsafe fn <|>fetch(url: &str) -> Vec<u8> {
+    download(url)
+}
+"#####,
+        r#####"
+pub async unsafe fn fetch(url: &str) -> Vec<u8> {
+    download(url)
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_make_function_sync() {
+    check(
+        "make_function_sync",
+        r#####"
+async fn <|>answer() -> u32 {
+    42
+}
+"#####,
+        r#####"
+fn answer() -> u32 {
+    42
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_make_raw_string() {
     check(
@@ -508,6 +814,38 @@ fn handle(action: Action) {
     )
 }
 
+#[test]
+fn doctest_promote_local_to_field() {
+    check(
+        "promote_local_to_field",
+        r#####"
+struct Counter {
+    total: u32,
+}
+
+impl Counter {
+    fn add(&mut self, x: u32) {
+        let <|>last = x;
+        self.total += last;
+    }
+}
+"#####,
+        r#####"
+struct Counter {
+    total: u32,
+    last: u32,
+}
+
+impl Counter {
+    fn add(&mut self, x: u32) {
+        self.last = x;
+        self.total += self.last;
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_remove_dbg() {
     check(
@@ -542,6 +880,82 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_remove_mut() {
+    check(
+        "remove_mut",
+        r#####"
+fn main() {
+    let mut x = 92;
+    let y = &<|>mut x;
+}
+"#####,
+        r#####"
+fn main() {
+    let mut x = 92;
+    let y = &x;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_remove_parentheses() {
+    check(
+        "remove_parentheses",
+        r#####"
+fn main() {
+    let x = a + <|>(b * c);
+}
+"#####,
+        r#####"
+fn main() {
+    let x = a + b * c;
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_remove_turbofish() {
+    check(
+        "remove_turbofish",
+        r#####"
+fn make<T>() -> T { unimplemented!() }
+fn main() {
+    let x: u32 = make<|>::<u32>();
+}
+"#####,
+        r#####"
+fn make<T>() -> T { unimplemented!() }
+fn main() {
+    let x: u32 = make();
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_remove_unused_import() {
+    check(
+        "remove_unused_import",
+        r#####"
+use std::collections::{HashMap, HashSet<|>};
+
+fn main() {
+    let map: HashMap<u32, u32> = HashMap::new();
+}
+"#####,
+        r#####"
+use std::collections::HashMap;
+
+fn main() {
+    let map: HashMap<u32, u32> = HashMap::new();
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_replace_if_let_with_match() {
     check(
@@ -570,6 +984,62 @@ fn handle(action: Action) {
     )
 }
 
+#[test]
+fn doctest_replace_let_with_if_let() {
+    check(
+        "replace_let_with_if_let",
+        r#####"
+enum Option<T> { Some(T), None }
+use Option::Some;
+
+fn handle(x: Option<u32>) {
+    let <|>n = x;
+    foo(n);
+}
+"#####,
+        r#####"
+enum Option<T> { Some(T), None }
+use Option::Some;
+
+fn handle(x: Option<u32>) {
+    if let Some(n) = x {
+        foo(n);
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_reverse_arguments() {
+    check(
+        "reverse_arguments",
+        r#####"
+fn main() {
+    foo(x, <|>y, z);
+}
+"#####,
+        r#####"
+fn main() {
+    foo(z, y, x);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_sort_trait_bounds() {
+    check(
+        "sort_trait_bounds",
+        r#####"
+fn foo<T: Hash +<|> 'static + Eq + Clone>() { }
+"#####,
+        r#####"
+fn foo<T: 'static + Clone + Eq + Hash>() { }
+"#####,
+    )
+}
+
 #[test]
 fn doctest_split_import() {
     check(
@@ -582,3 +1052,133 @@ use std::{collections::HashMap};
 "#####,
     )
 }
+
+#[test]
+fn doctest_split_import_list() {
+    check(
+        "split_import_list",
+        r#####"
+use std::{<|>collections::HashMap, fmt::{self, Debug}};
+"#####,
+        r#####"
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+"#####,
+    )
+}
+
+#[test]
+fn doctest_swap_arguments() {
+    check(
+        "swap_arguments",
+        r#####"
+fn main() {
+    foo(x,<|> y, z);
+}
+"#####,
+        r#####"
+fn main() {
+    foo(y, x, z);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_toggle_ignore() {
+    check(
+        "toggle_ignore",
+        r#####"
+#[test]
+fn arithmetics<|>() {
+    assert_eq!(2 + 2, 5);
+}
+"#####,
+        r#####"
+#[test]
+#[ignore]
+fn arithmetics() {
+    assert_eq!(2 + 2, 5);
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_unwrap_pattern_in_some() {
+    check(
+        "unwrap_pattern_in_some",
+        r#####"
+enum Option<T> { Some(T), None }
+use Option::Some;
+
+fn handle(x: Option<u32>) {
+    if let <|>Some(n) = x {
+        foo(n);
+    }
+}
+"#####,
+        r#####"
+enum Option<T> { Some(T), None }
+use Option::Some;
+
+fn handle(x: Option<u32>) {
+    if let n = x {
+        foo(n);
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_wrap_in_block() {
+    check(
+        "wrap_in_block",
+        r#####"
+fn main() {
+    <|>let x = 92;
+    foo(x);<|>
+    bar();
+}
+"#####,
+        r#####"
+fn main() {
+    {
+        let x = 92;
+        foo(x);
+    }
+    bar();
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_wrap_pattern_in_some() {
+    check(
+        "wrap_pattern_in_some",
+        r#####"
+enum Option<T> { Some(T), None }
+use Option::{None, Some};
+
+fn handle(x: Option<u32>) {
+    match x {
+        <|>n => foo(n),
+        None => bar(),
+    }
+}
+"#####,
+        r#####"
+enum Option<T> { Some(T), None }
+use Option::{None, Some};
+
+fn handle(x: Option<u32>) {
+    match x {
+        Some(n) => foo(n),
+        None => bar(),
+    }
+}
+"#####,
+    )
+}
@@ -13,12 +13,24 @@ mod doc_tests;
 mod test_db;
 
 use hir::db::HirDatabase;
-use ra_db::FileRange;
+use ra_db::{FileId, FileRange};
 use ra_syntax::{TextRange, TextUnit};
 use ra_text_edit::TextEdit;
 
 pub(crate) use crate::assist_ctx::{Assist, AssistCtx};
-pub use crate::assists::add_import::auto_import_text_edit;
+pub use crate::assists::{
+    add_import::auto_import_text_edit, add_missing_impl_members::missing_impl_members_stubs,
+    auto_import::ImportsLocator,
+};
+
+/// Tweaks the edits the assists compute.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AssistConfig {
+    /// Fill the bodies of the generated methods with `()` or
+    /// `Default::default()` where the return type allows it, instead of
+    /// `todo!()`.
+    pub fill_default_bodies: bool,
+}
 
 /// Unique identifier of the assist, should not be shown to the user
 /// directly.
@@ -35,6 +47,8 @@ pub struct AssistLabel {
 #[derive(Debug, Clone)]
 pub struct AssistAction {
     pub edit: TextEdit,
+    /// Edits of the files other than the one the assist was requested in.
+    pub other_file_edits: Vec<(FileId, TextEdit)>,
     pub cursor_position: Option<TextUnit>,
     pub target: Option<TextRange>,
 }
@@ -67,27 +81,58 @@ pub fn assists<H>(db: &H, range: FileRange) -> Vec<(AssistLabel, AssistAction)>
 where
     H: HirDatabase + 'static,
 {
-    use std::cmp::Ordering;
+    AssistCtx::with_ctx(db, range, true, |ctx| {
+        let a = assists::all().iter().filter_map(|f| f(ctx.clone())).collect();
+        sorted_by_target(a)
+    })
+}
 
+/// Return all the assists applicable at the given position, including the
+/// `auto_import` one, which needs to look up the names in all the crates.
+///
+/// Assists are returned in the "resolved" state, that is with edit fully
+/// computed.
+pub fn assists_with_imports_locator<H, F>(
+    db: &H,
+    range: FileRange,
+    config: AssistConfig,
+    mut imports_locator: F,
+) -> Vec<(AssistLabel, AssistAction)>
+where
+    H: HirDatabase + 'static,
+    F: ImportsLocator,
+{
     AssistCtx::with_ctx(db, range, true, |ctx| {
-        let mut a = assists::all()
-            .iter()
-            .filter_map(|f| f(ctx.clone()))
-            .map(|a| match a {
-                Assist::Resolved { label, action } => (label, action),
-                Assist::Unresolved { .. } => unreachable!(),
-            })
-            .collect::<Vec<_>>();
-        a.sort_by(|a, b| match (a.1.target, b.1.target) {
-            (Some(a), Some(b)) => a.len().cmp(&b.len()),
-            (Some(_), None) => Ordering::Less,
-            (None, Some(_)) => Ordering::Greater,
-            (None, None) => Ordering::Equal,
-        });
-        a
+        let ctx = ctx.with_config(config);
+        let mut a: Vec<Assist> = assists::all().iter().filter_map(|f| f(ctx.clone())).collect();
+        a.extend(
+            assists::auto_import::auto_import(ctx.clone(), &mut imports_locator)
+                .unwrap_or_default(),
+        );
+        sorted_by_target(a)
     })
 }
 
+/// The assists with the smallest targets, the most specific ones, go first.
+fn sorted_by_target(assists: Vec<Assist>) -> Vec<(AssistLabel, AssistAction)> {
+    use std::cmp::Ordering;
+
+    let mut a = assists
+        .into_iter()
+        .map(|a| match a {
+            Assist::Resolved { label, action } => (label, action),
+            Assist::Unresolved { .. } => unreachable!(),
+        })
+        .collect::<Vec<_>>();
+    a.sort_by(|a, b| match (a.1.target, b.1.target) {
+        (Some(a), Some(b)) => a.len().cmp(&b.len()),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+    a
+}
+
 mod assists {
     use crate::{Assist, AssistCtx};
     use hir::db::HirDatabase;
@@ -104,18 +149,38 @@ mod assists {
     mod flip_trait_bound;
     mod change_visibility;
     mod fill_match_arms;
+    mod generate_accessors;
+    mod add_missing_match_arms;
     mod merge_match_arms;
     mod introduce_variable;
+    mod introduce_named_lifetime;
     mod inline_local_variable;
     mod raw_string;
     mod replace_if_let_with_match;
+    mod sort_trait_bounds;
     mod split_import;
+    mod swap_arguments;
+    mod make_async;
     mod remove_dbg;
+    mod remove_parentheses;
+    mod remove_unused_import;
     pub(crate) mod add_import;
-    mod add_missing_impl_members;
+    pub(crate) mod add_missing_impl_members;
+    pub(crate) mod auto_import;
     mod move_guard;
     mod move_bounds;
     mod early_return;
+    mod wrap_in_block;
+    mod toggle_ignore;
+    mod turbofish;
+    mod for_to_iterator_chain;
+    mod convert_loop;
+    mod promote_local_to_field;
+    mod extract_import_to_top;
+    mod extract_struct_from_enum_variant;
+    mod convert_named_struct_to_tuple_struct;
+    mod mutability;
+    mod wrap_pattern;
 
     pub(crate) fn all<DB: HirDatabase>() -> &'static [fn(AssistCtx<DB>) -> Option<Assist>] {
         &[
@@ -128,14 +193,27 @@ mod assists {
             invert_if::invert_if,
             change_visibility::change_visibility,
             fill_match_arms::fill_match_arms,
+            generate_accessors::generate_getter,
+            generate_accessors::generate_setter,
+            add_missing_match_arms::add_missing_match_arms,
             merge_match_arms::merge_match_arms,
             flip_comma::flip_comma,
             flip_binexpr::flip_binexpr,
             flip_trait_bound::flip_trait_bound,
             introduce_variable::introduce_variable,
+            introduce_named_lifetime::introduce_named_lifetime,
             replace_if_let_with_match::replace_if_let_with_match,
+            sort_trait_bounds::sort_trait_bounds,
             split_import::split_import,
+            split_import::split_import_list,
+            split_import::flatten_import,
+            swap_arguments::swap_arguments,
+            swap_arguments::reverse_arguments,
+            make_async::make_function_async,
+            make_async::make_function_sync,
             remove_dbg::remove_dbg,
+            remove_parentheses::remove_parentheses,
+            remove_unused_import::remove_unused_import,
             add_import::add_import,
             add_missing_impl_members::add_missing_impl_members,
             add_missing_impl_members::add_missing_default_members,
@@ -148,13 +226,29 @@ mod assists {
             raw_string::make_usual_string,
             raw_string::remove_hash,
             early_return::convert_to_guarded_return,
+            wrap_in_block::wrap_in_block,
+            toggle_ignore::toggle_ignore,
+            turbofish::add_turbofish,
+            turbofish::remove_turbofish,
+            for_to_iterator_chain::convert_for_loop_to_iterator_chain,
+            convert_loop::convert_while_to_loop,
+            convert_loop::convert_loop_to_while,
+            promote_local_to_field::promote_local_to_field,
+            extract_import_to_top::extract_import_to_top,
+            extract_struct_from_enum_variant::extract_struct_from_enum_variant,
+            convert_named_struct_to_tuple_struct::convert_named_struct_to_tuple_struct,
+            mutability::add_mut,
+            mutability::remove_mut,
+            wrap_pattern::wrap_pattern_in_some,
+            wrap_pattern::replace_let_with_if_let,
+            wrap_pattern::unwrap_pattern_in_some,
         ]
     }
 }
 
 #[cfg(test)]
 mod helpers {
-    use ra_db::{fixture::WithFixture, FileRange};
+    use ra_db::{fixture::WithFixture, FileRange, SourceDatabaseExt};
     use ra_syntax::TextRange;
     use test_utils::{add_cursor, assert_eq_text, extract_offset, extract_range};
 
@@ -188,6 +282,34 @@ mod helpers {
         assert_eq_text!(after, &actual);
     }
 
+    /// Like `check_assist`, but takes a multi-file fixture with a cursor
+    /// position. `after` is the text of the file with the cursor.
+    pub(crate) fn check_assist_in_fixture(
+        assist: fn(AssistCtx<TestDB>) -> Option<Assist>,
+        fixture: &str,
+        after: &str,
+    ) {
+        let (db, position) = TestDB::with_position(fixture);
+        let before = db.file_text(position.file_id);
+        let frange = FileRange {
+            file_id: position.file_id,
+            range: TextRange::offset_len(position.offset, 0.into()),
+        };
+        let assist =
+            AssistCtx::with_ctx(&db, frange, true, assist).expect("code action is not applicable");
+        let action = match assist {
+            Assist::Unresolved { .. } => unreachable!(),
+            Assist::Resolved { action, .. } => action,
+        };
+
+        let actual = action.edit.apply(&before);
+        let actual = match action.cursor_position {
+            None => actual,
+            Some(off) => add_cursor(&actual, off),
+        };
+        assert_eq_text!(after, &actual);
+    }
+
     pub(crate) fn check_assist_range(
         assist: fn(AssistCtx<TestDB>) -> Option<Assist>,
         before: &str,
@@ -261,6 +383,21 @@ mod helpers {
         assert!(assist.is_none());
     }
 
+    /// Like `check_assist_not_applicable`, but takes a multi-file fixture with
+    /// a cursor position.
+    pub(crate) fn check_assist_not_applicable_in_fixture(
+        assist: fn(AssistCtx<TestDB>) -> Option<Assist>,
+        fixture: &str,
+    ) {
+        let (db, position) = TestDB::with_position(fixture);
+        let frange = FileRange {
+            file_id: position.file_id,
+            range: TextRange::offset_len(position.offset, 0.into()),
+        };
+        let assist = AssistCtx::with_ctx(&db, frange, true, assist);
+        assert!(assist.is_none());
+    }
+
     pub(crate) fn check_assist_range_not_applicable(
         assist: fn(AssistCtx<TestDB>) -> Option<Assist>,
         before: &str,
@@ -291,7 +428,7 @@ mod tests {
         let assists = super::assists(&db, frange);
         let mut assists = assists.iter();
 
-        assert_eq!(assists.next().expect("expected assist").0.label, "make pub(crate)");
+        assert_eq!(assists.next().expect("expected assist").0.label, "make pub");
         assert_eq!(assists.next().expect("expected assist").0.label, "add `#[derive]`");
     }
 
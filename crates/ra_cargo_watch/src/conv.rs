@@ -0,0 +1,481 @@
+//! Converts the diagnostics printed by `cargo check --message-format=json` to
+//! LSP diagnostics.
+//!
+//! A rustc diagnostic is a tree: its children are notes, which become extra
+//! lines of the message or related information, and suggestions, which become
+//! fixes.
+
+use std::{
+    path::{Component, Path, Prefix},
+    str::FromStr,
+};
+
+use cargo_metadata::diagnostic::{
+    Applicability, Diagnostic as RustDiagnostic, DiagnosticLevel, DiagnosticSpan,
+    DiagnosticSpanMacroExpansion,
+};
+use lsp_types::{
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, DiagnosticTag, Location,
+    NumberOrString, Position, Range, Url,
+};
+
+/// A replacement suggested by rustc, like prefixing an unused variable with
+/// `_`.
+#[derive(Clone, Debug)]
+pub struct SuggestedFix {
+    pub title: String,
+    pub location: Location,
+    pub replacement: String,
+    pub applicability: Applicability,
+    /// The diagnostics fixed by the replacement.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl SuggestedFix {
+    pub(crate) fn is_same_edit(&self, other: &SuggestedFix) -> bool {
+        self.title == other.title
+            && self.location == other.location
+            && self.replacement == other.replacement
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct MappedRustDiagnostic {
+    pub(crate) location: Location,
+    pub(crate) diagnostic: Diagnostic,
+    pub(crate) suggested_fixes: Vec<SuggestedFix>,
+}
+
+enum MappedRustChildDiagnostic {
+    Related(DiagnosticRelatedInformation),
+    SuggestedFix(SuggestedFix),
+    MessageLine(String),
+}
+
+fn map_level_to_severity(level: &DiagnosticLevel) -> Option<DiagnosticSeverity> {
+    let severity = match level {
+        DiagnosticLevel::Ice | DiagnosticLevel::Error => DiagnosticSeverity::Error,
+        DiagnosticLevel::Warning => DiagnosticSeverity::Warning,
+        DiagnosticLevel::Note => DiagnosticSeverity::Information,
+        DiagnosticLevel::Help => DiagnosticSeverity::Hint,
+        DiagnosticLevel::Unknown => return None,
+    };
+    Some(severity)
+}
+
+/// Spans in macro expansions have file names like `<println macros>`.
+fn is_from_macro(file_name: &str) -> bool {
+    file_name.starts_with('<') && file_name.ends_with('>')
+}
+
+/// The first call site of the expansion which is in an actual file.
+fn map_macro_span_to_location(
+    span_macro: &DiagnosticSpanMacroExpansion,
+    workspace_root: &Path,
+) -> Option<Location> {
+    if !is_from_macro(&span_macro.span.file_name) {
+        return map_span_to_location(&span_macro.span, workspace_root);
+    }
+    let expansion = span_macro.span.expansion.as_ref()?;
+    map_macro_span_to_location(expansion, workspace_root)
+}
+
+/// Returns `None` if the path of the span can't be turned into an URL.
+fn map_span_to_location(span: &DiagnosticSpan, workspace_root: &Path) -> Option<Location> {
+    if is_from_macro(&span.file_name) {
+        let macro_location = span
+            .expansion
+            .as_ref()
+            .and_then(|expansion| map_macro_span_to_location(expansion, workspace_root));
+        if macro_location.is_some() {
+            return macro_location;
+        }
+    }
+
+    let path = workspace_root.join(&span.file_name);
+    let uri = url_from_path_with_drive_lowercasing(&path).ok()?;
+    // Lines and columns are 1-based
+    let range = Range::new(
+        Position::new(span.line_start as u64 - 1, span.column_start as u64 - 1),
+        Position::new(span.line_end as u64 - 1, span.column_end as u64 - 1),
+    );
+    Some(Location { uri, range })
+}
+
+/// Secondary spans without a label have nothing to say.
+fn map_secondary_span_to_related(
+    span: &DiagnosticSpan,
+    workspace_root: &Path,
+) -> Option<DiagnosticRelatedInformation> {
+    let message = span.label.clone()?;
+    let location = map_span_to_location(span, workspace_root)?;
+    Some(DiagnosticRelatedInformation { location, message })
+}
+
+fn is_unused_or_unnecessary(rd: &RustDiagnostic) -> bool {
+    let code = match &rd.code {
+        Some(it) => it.code.as_str(),
+        None => return false,
+    };
+    [
+        "dead_code",
+        "unknown_lints",
+        "unreachable_code",
+        "unused_attributes",
+        "unused_imports",
+        "unused_macros",
+        "unused_variables",
+    ]
+    .contains(&code)
+}
+
+fn is_deprecated(rd: &RustDiagnostic) -> bool {
+    rd.code.as_ref().map_or(false, |it| it.code == "deprecated")
+}
+
+/// Children without a primary span are extra lines of the message of their
+/// parent, the ones with a suggestion are fixes, and the other ones are
+/// related information.
+fn map_rust_child_diagnostic(
+    rd: &RustDiagnostic,
+    workspace_root: &Path,
+) -> Option<MappedRustChildDiagnostic> {
+    let span = match rd.spans.iter().find(|it| it.is_primary) {
+        Some(it) => it,
+        None => return Some(MappedRustChildDiagnostic::MessageLine(rd.message.clone())),
+    };
+    let location = map_span_to_location(span, workspace_root)?;
+
+    let child = match &span.suggested_replacement {
+        Some(replacement) => {
+            let title = if replacement.is_empty() {
+                rd.message.clone()
+            } else {
+                format!("{}: `{}`", rd.message, replacement)
+            };
+            MappedRustChildDiagnostic::SuggestedFix(SuggestedFix {
+                title,
+                location,
+                replacement: replacement.clone(),
+                applicability: span
+                    .suggestion_applicability
+                    .clone()
+                    .unwrap_or(Applicability::Unspecified),
+                diagnostics: Vec::new(),
+            })
+        }
+        None => MappedRustChildDiagnostic::Related(DiagnosticRelatedInformation {
+            location,
+            message: rd.message.clone(),
+        }),
+    };
+    Some(child)
+}
+
+/// Flattens a rustc diagnostic into a single LSP diagnostic at its primary
+/// span.
+///
+/// Returns `None` for diagnostics without a primary span, like the summary of
+/// the number of errors, and for the ones in files without an URL.
+pub(crate) fn map_rust_diagnostic_to_lsp(
+    rd: &RustDiagnostic,
+    workspace_root: &Path,
+) -> Option<MappedRustDiagnostic> {
+    let primary_span = rd.spans.iter().find(|it| it.is_primary)?;
+    let location = map_span_to_location(primary_span, workspace_root)?;
+
+    // Lints of tools are scoped, like `clippy::needless_return`
+    let mut source = "rustc";
+    let mut code = rd.code.as_ref().map(|it| it.code.as_str());
+    if let Some(code_val) = code {
+        let scoped_code: Vec<&str> = code_val.split("::").collect();
+        if scoped_code.len() == 2 {
+            source = scoped_code[0];
+            code = Some(scoped_code[1]);
+        }
+    }
+
+    let mut related_information: Vec<DiagnosticRelatedInformation> = rd
+        .spans
+        .iter()
+        .filter(|it| !it.is_primary)
+        .filter_map(|it| map_secondary_span_to_related(it, workspace_root))
+        .collect();
+
+    let mut message = rd.message.clone();
+    let mut primary_span_label = primary_span.label.as_ref();
+    let mut suggested_fixes = Vec::new();
+    for child in rd.children.iter() {
+        match map_rust_child_diagnostic(child, workspace_root) {
+            Some(MappedRustChildDiagnostic::Related(related)) => related_information.push(related),
+            Some(MappedRustChildDiagnostic::SuggestedFix(fix)) => suggested_fixes.push(fix),
+            Some(MappedRustChildDiagnostic::MessageLine(line)) => {
+                message.push('\n');
+                message.push_str(&line);
+                // These lines usually repeat the label of the primary span
+                primary_span_label = None;
+            }
+            // children in files without an URL are left out
+            None => (),
+        }
+    }
+    if let Some(label) = primary_span_label {
+        message.push('\n');
+        message.push_str(label);
+    }
+
+    let mut tags = Vec::new();
+    if is_unused_or_unnecessary(rd) {
+        tags.push(DiagnosticTag::Unnecessary);
+    }
+    if is_deprecated(rd) {
+        tags.push(DiagnosticTag::Deprecated);
+    }
+
+    let diagnostic = Diagnostic {
+        range: location.range,
+        severity: map_level_to_severity(&rd.level),
+        code: code.map(|it| NumberOrString::String(it.to_string())),
+        source: Some(source.to_string()),
+        message,
+        related_information: if related_information.is_empty() {
+            None
+        } else {
+            Some(related_information)
+        },
+        tags: if tags.is_empty() { None } else { Some(tags) },
+    };
+
+    Some(MappedRustDiagnostic { location, diagnostic, suggested_fixes })
+}
+
+/// Returns a `Url` object from a given path, will lowercase drive letters if present.
+/// This will only happen when processing windows paths.
+///
+/// When processing non-windows path, this is essentially the same as `Url::from_file_path`.
+pub fn url_from_path_with_drive_lowercasing(
+    path: impl AsRef<Path>,
+) -> Result<Url, Box<dyn std::error::Error + Send + Sync>> {
+    let component_has_windows_drive = path.as_ref().components().any(|comp| {
+        if let Component::Prefix(c) = comp {
+            match c.kind() {
+                Prefix::Disk(_) | Prefix::VerbatimDisk(_) => return true,
+                _ => return false,
+            }
+        }
+        false
+    });
+
+    // VSCode expects drive letters to be lowercased, where rust will uppercase the drive letters.
+    if component_has_windows_drive {
+        let url_original = Url::from_file_path(&path)
+            .map_err(|_| format!("can't convert path to url: {}", path.as_ref().display()))?;
+
+        let drive_partition: Vec<&str> = url_original.as_str().rsplitn(2, ':').collect();
+
+        // There is a drive partition, but we never found a colon.
+        // This should not happen, but in this case we just pass it through.
+        if drive_partition.len() == 1 {
+            return Ok(url_original);
+        }
+
+        let joined = drive_partition[1].to_ascii_lowercase() + ":" + drive_partition[0];
+        let url = Url::from_str(&joined).expect("This came from a valid `Url`");
+
+        Ok(url)
+    } else {
+        Ok(Url::from_file_path(&path)
+            .map_err(|_| format!("can't convert path to url: {}", path.as_ref().display()))?)
+    }
+}
+
+// `Url` is not able to parse windows paths on unix machines.
+#[cfg(target_os = "windows")]
+#[cfg(test)]
+mod path_conversion_windows_tests {
+    use super::url_from_path_with_drive_lowercasing;
+    #[test]
+    fn test_lowercase_drive_letter_with_drive() {
+        let url = url_from_path_with_drive_lowercasing("C:\\Test").unwrap();
+
+        assert_eq!(url.to_string(), "file:///c:/Test");
+    }
+
+    #[test]
+    fn test_drive_without_colon_passthrough() {
+        let url = url_from_path_with_drive_lowercasing(r#"\\localhost\C$\my_dir"#).unwrap();
+
+        assert_eq!(url.to_string(), "file://localhost/C$/my_dir");
+    }
+}
+
+#[cfg(all(test, not(target_os = "windows")))]
+mod tests {
+    use super::*;
+
+    fn span(file_name: &str, line: u64, columns: (u64, u64), is_primary: bool) -> String {
+        format!(
+            r#"{{
+                "file_name": "{}",
+                "byte_start": 0,
+                "byte_end": 0,
+                "line_start": {},
+                "line_end": {},
+                "column_start": {},
+                "column_end": {},
+                "is_primary": {},
+                "text": [],
+                "label": null,
+                "suggested_replacement": null,
+                "suggestion_applicability": null,
+                "expansion": null
+            }}"#,
+            file_name, line, line, columns.0, columns.1, is_primary
+        )
+    }
+
+    fn map(json: &str) -> MappedRustDiagnostic {
+        let rd: RustDiagnostic = serde_json::from_str(json).unwrap();
+        map_rust_diagnostic_to_lsp(&rd, Path::new("/test")).unwrap()
+    }
+
+    #[test]
+    fn maps_unused_variable_warning() {
+        let fix_span = span("src/main.rs", 291, (9, 12), true)
+            .replace(r#""suggested_replacement": null"#, r#""suggested_replacement": "_foo""#)
+            .replace(
+                r#""suggestion_applicability": null"#,
+                r#""suggestion_applicability": "MachineApplicable""#,
+            );
+        let json = format!(
+            r#"{{
+                "message": "unused variable: `foo`",
+                "code": {{ "code": "unused_variables", "explanation": null }},
+                "level": "warning",
+                "spans": [{}],
+                "children": [
+                    {{
+                        "message": "#[warn(unused_variables)] on by default",
+                        "code": null,
+                        "level": "note",
+                        "spans": [],
+                        "children": [],
+                        "rendered": null
+                    }},
+                    {{
+                        "message": "consider prefixing with an underscore",
+                        "code": null,
+                        "level": "help",
+                        "spans": [{}],
+                        "children": [],
+                        "rendered": null
+                    }}
+                ],
+                "rendered": null
+            }}"#,
+            span("src/main.rs", 291, (9, 12), true),
+            fix_span
+        );
+        let MappedRustDiagnostic { location, diagnostic, suggested_fixes } = map(&json);
+
+        assert_eq!(location.uri.as_str(), "file:///test/src/main.rs");
+        assert_eq!(diagnostic.range, Range::new(Position::new(290, 8), Position::new(290, 11)));
+        assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::Warning));
+        assert_eq!(diagnostic.source.as_ref().map(String::as_str), Some("rustc"));
+        assert_eq!(diagnostic.code, Some(NumberOrString::String("unused_variables".to_string())));
+        assert_eq!(
+            diagnostic.message,
+            "unused variable: `foo`\n#[warn(unused_variables)] on by default"
+        );
+        assert_eq!(diagnostic.tags, Some(vec![DiagnosticTag::Unnecessary]));
+        assert_eq!(diagnostic.related_information, None);
+
+        assert_eq!(suggested_fixes.len(), 1);
+        let fix = &suggested_fixes[0];
+        assert_eq!(fix.title, "consider prefixing with an underscore: `_foo`");
+        assert_eq!(fix.replacement, "_foo");
+        assert_eq!(fix.applicability, Applicability::MachineApplicable);
+        assert_eq!(fix.location, location);
+    }
+
+    #[test]
+    fn maps_scoped_lint_and_labelled_spans() {
+        let primary = span("src/lib.rs", 3, (5, 10), true)
+            .replace(r#""label": null"#, r#""label": "expected `u32`""#);
+        let secondary = span("src/lib.rs", 1, (13, 16), false)
+            .replace(r#""label": null"#, r#""label": "expected due to this""#);
+        let json = format!(
+            r#"{{
+                "message": "mismatched types",
+                "code": {{ "code": "clippy::some_lint", "explanation": null }},
+                "level": "error",
+                "spans": [{}, {}, {}],
+                "children": [],
+                "rendered": null
+            }}"#,
+            primary,
+            secondary,
+            span("src/lib.rs", 2, (1, 2), false)
+        );
+        let MappedRustDiagnostic { diagnostic, suggested_fixes, .. } = map(&json);
+
+        assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::Error));
+        assert_eq!(diagnostic.source.as_ref().map(String::as_str), Some("clippy"));
+        assert_eq!(diagnostic.code, Some(NumberOrString::String("some_lint".to_string())));
+        assert_eq!(diagnostic.message, "mismatched types\nexpected `u32`");
+        assert_eq!(diagnostic.tags, None);
+
+        // only the labelled secondary span is kept
+        let related = diagnostic.related_information.unwrap();
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].message, "expected due to this");
+        assert_eq!(related[0].location.range.start, Position::new(0, 12));
+        assert!(suggested_fixes.is_empty());
+    }
+
+    #[test]
+    fn maps_macro_spans_to_call_site() {
+        let call_site = span("src/main.rs", 5, (5, 20), false);
+        let primary = span("<println macros>", 1, (1, 10), true).replace(
+            r#""expansion": null"#,
+            &format!(
+                r#""expansion": {{
+                    "span": {},
+                    "macro_decl_name": "println!",
+                    "def_site_span": null
+                }}"#,
+                call_site
+            ),
+        );
+        let json = format!(
+            r#"{{
+                "message": "error in macro",
+                "code": null,
+                "level": "error",
+                "spans": [{}],
+                "children": [],
+                "rendered": null
+            }}"#,
+            primary
+        );
+        let MappedRustDiagnostic { location, diagnostic, .. } = map(&json);
+
+        assert_eq!(location.uri.as_str(), "file:///test/src/main.rs");
+        assert_eq!(diagnostic.range, Range::new(Position::new(4, 4), Position::new(4, 19)));
+        assert_eq!(diagnostic.code, None);
+    }
+
+    #[test]
+    fn does_not_map_diagnostics_without_primary_span() {
+        let json = r#"{
+            "message": "aborting due to previous error",
+            "code": null,
+            "level": "error",
+            "spans": [],
+            "children": [],
+            "rendered": null
+        }"#;
+        let rd: RustDiagnostic = serde_json::from_str(json).unwrap();
+        assert!(map_rust_diagnostic_to_lsp(&rd, Path::new("/test")).is_none());
+    }
+}
@@ -0,0 +1,319 @@
+//! ra_cargo_watch runs `cargo check` (or a compatible command, like `cargo
+//! clippy`) in a background thread whenever the workspace is saved, and turns
+//! the diagnostics it prints into LSP diagnostics.
+//!
+//! The diagnostics of the last run are kept in a `CheckState` shared with the
+//! server, which is told about the files whose diagnostics changed through a
+//! `CheckTask`.
+
+mod conv;
+
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    sync::Arc,
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use cargo_metadata::Message;
+use crossbeam_channel::{after, never, select, unbounded, Receiver, RecvError, Sender};
+use lsp_types::{Diagnostic, Url};
+use parking_lot::RwLock;
+
+use crate::conv::{map_rust_diagnostic_to_lsp, MappedRustDiagnostic};
+
+pub use crate::conv::{url_from_path_with_drive_lowercasing, SuggestedFix};
+
+/// Saves which happen in quick succession, like the ones of "save all", only
+/// trigger a single run.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+#[derive(Clone, Debug)]
+pub struct CheckOptions {
+    pub enable: bool,
+    /// The cargo subcommand to run, like `check` or `clippy`.
+    pub command: String,
+    /// Extra arguments passed to the subcommand.
+    pub args: Vec<String>,
+    pub all_targets: bool,
+}
+
+/// A file whose diagnostics changed, and which should be republished.
+#[derive(Debug)]
+pub enum CheckTask {
+    Update(Url),
+}
+
+#[derive(Debug)]
+enum CheckCommand {
+    Update,
+}
+
+/// The diagnostics and fixes reported by the last run of the command, by file.
+#[derive(Debug, Default)]
+pub struct CheckState {
+    diagnostic_collection: HashMap<Url, Vec<Diagnostic>>,
+    suggested_fix_collection: HashMap<Url, Vec<SuggestedFix>>,
+}
+
+impl CheckState {
+    pub fn diagnostics_for(&self, uri: &Url) -> &[Diagnostic] {
+        self.diagnostic_collection.get(uri).map_or(&[], |it| it.as_slice())
+    }
+
+    pub fn fixes_for(&self, uri: &Url) -> &[SuggestedFix] {
+        self.suggested_fix_collection.get(uri).map_or(&[], |it| it.as_slice())
+    }
+
+    /// Forgets everything, returning the files which had diagnostics.
+    fn clear(&mut self) -> Vec<Url> {
+        let cleared_files = self.diagnostic_collection.keys().cloned().collect();
+        self.diagnostic_collection.clear();
+        self.suggested_fix_collection.clear();
+        cleared_files
+    }
+
+    /// Returns `false` if the diagnostic was already known: with
+    /// `--all-targets`, files shared by several targets are checked once per
+    /// target.
+    fn add_diagnostic(&mut self, file_uri: Url, diagnostic: Diagnostic) -> bool {
+        let diagnostics = self.diagnostic_collection.entry(file_uri).or_default();
+        if diagnostics.contains(&diagnostic) {
+            return false;
+        }
+        diagnostics.push(diagnostic);
+        true
+    }
+
+    fn add_suggested_fix_for_diagnostic(
+        &mut self,
+        mut suggested_fix: SuggestedFix,
+        diagnostic: &Diagnostic,
+    ) {
+        let file_uri = suggested_fix.location.uri.clone();
+        let fixes = self.suggested_fix_collection.entry(file_uri).or_default();
+        // The same fix may be suggested for several diagnostics
+        if let Some(existing) = fixes.iter_mut().find(|it| it.is_same_edit(&suggested_fix)) {
+            existing.diagnostics.push(diagnostic.clone());
+            return;
+        }
+        suggested_fix.diagnostics.push(diagnostic.clone());
+        fixes.push(suggested_fix);
+    }
+}
+
+/// The handle of the background thread running the command.
+///
+/// The thread checks the workspace once when started, and again after each
+/// `update`, cancelling the run in progress if there is one.
+#[derive(Debug)]
+pub struct CheckWatcher {
+    pub task_recv: Receiver<CheckTask>,
+    pub state: Arc<RwLock<CheckState>>,
+    cmd_send: Option<Sender<CheckCommand>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl CheckWatcher {
+    pub fn new(options: &CheckOptions, workspace_root: PathBuf) -> CheckWatcher {
+        let state = Arc::new(RwLock::new(CheckState::default()));
+        if !options.enable {
+            return CheckWatcher { task_recv: never(), state, cmd_send: None, handle: None };
+        }
+
+        let (task_send, task_recv) = unbounded::<CheckTask>();
+        let (cmd_send, cmd_recv) = unbounded::<CheckCommand>();
+        let handle = thread::spawn({
+            let options = options.clone();
+            let state = Arc::clone(&state);
+            move || {
+                let mut check = CheckWatcherThread::new(options, workspace_root, state);
+                check.run(&task_send, &cmd_recv);
+            }
+        });
+        CheckWatcher { task_recv, state, cmd_send: Some(cmd_send), handle: Some(handle) }
+    }
+
+    /// Schedules a new run of the command.
+    pub fn update(&self) {
+        if let Some(cmd_send) = &self.cmd_send {
+            // The thread is only gone early if it panicked
+            let _ = cmd_send.send(CheckCommand::Update);
+        }
+    }
+}
+
+impl Drop for CheckWatcher {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            // Closing the command channel stops the thread
+            drop(self.cmd_send.take());
+            // Whether it panicked doesn't matter anymore
+            let _ = handle.join();
+        }
+    }
+}
+
+struct CheckWatcherThread {
+    options: CheckOptions,
+    workspace_root: PathBuf,
+    state: Arc<RwLock<CheckState>>,
+    watcher: WatchThread,
+    last_update_req: Option<Instant>,
+}
+
+impl CheckWatcherThread {
+    fn new(
+        options: CheckOptions,
+        workspace_root: PathBuf,
+        state: Arc<RwLock<CheckState>>,
+    ) -> CheckWatcherThread {
+        CheckWatcherThread {
+            options,
+            workspace_root,
+            state,
+            watcher: WatchThread::dummy(),
+            last_update_req: Some(Instant::now()),
+        }
+    }
+
+    fn run(&mut self, task_send: &Sender<CheckTask>, cmd_recv: &Receiver<CheckCommand>) {
+        loop {
+            let debounce = match self.last_update_req {
+                Some(it) => after(DEBOUNCE.checked_sub(it.elapsed()).unwrap_or_default()),
+                None => never(),
+            };
+            select! {
+                recv(cmd_recv) -> cmd => match cmd {
+                    Ok(CheckCommand::Update) => {
+                        // The files changed, so the run in progress is stale
+                        self.watcher = WatchThread::dummy();
+                        self.last_update_req = Some(Instant::now());
+                    }
+                    // The server shut down
+                    Err(RecvError) => break,
+                },
+                recv(self.watcher.message_recv) -> msg => match msg {
+                    Ok(msg) => self.handle_message(msg, task_send),
+                    // The run finished
+                    Err(RecvError) => self.watcher = WatchThread::dummy(),
+                },
+                recv(debounce) -> _ => self.restart(task_send),
+            }
+        }
+    }
+
+    fn restart(&mut self, task_send: &Sender<CheckTask>) {
+        self.last_update_req = None;
+        let cleared_files = self.state.write().clear();
+        for uri in cleared_files {
+            // When the server shut down, the closed command channel stops the
+            // loop soon enough
+            let _ = task_send.send(CheckTask::Update(uri));
+        }
+        self.watcher = WatchThread::new(&self.options, &self.workspace_root);
+    }
+
+    fn handle_message(&mut self, msg: Message, task_send: &Sender<CheckTask>) {
+        let msg = match msg {
+            Message::CompilerMessage(it) => it,
+            _ => return,
+        };
+        let MappedRustDiagnostic { location, diagnostic, suggested_fixes } =
+            match map_rust_diagnostic_to_lsp(&msg.message, &self.workspace_root) {
+                Some(it) => it,
+                None => {
+                    log::debug!("unable to map diagnostic: {:?}", msg.message);
+                    return;
+                }
+            };
+
+        let mut state = self.state.write();
+        if !state.add_diagnostic(location.uri.clone(), diagnostic.clone()) {
+            return;
+        }
+        for fix in suggested_fixes {
+            state.add_suggested_fix_for_diagnostic(fix, &diagnostic);
+        }
+        let _ = task_send.send(CheckTask::Update(location.uri));
+    }
+}
+
+/// A single run of the command.
+///
+/// The standard library can't read the output of a process without blocking,
+/// so the output is parsed on a thread of its own, which sends the messages
+/// back over a channel. Dropping the `WatchThread` kills the process.
+struct WatchThread {
+    message_recv: Receiver<Message>,
+    child: Option<Child>,
+    _reader: Option<jod_thread::JoinHandle<()>>,
+}
+
+impl WatchThread {
+    fn dummy() -> WatchThread {
+        WatchThread { message_recv: never(), child: None, _reader: None }
+    }
+
+    fn new(options: &CheckOptions, workspace_root: &Path) -> WatchThread {
+        let mut args: Vec<String> = vec![
+            options.command.clone(),
+            "--message-format=json".to_string(),
+            "--manifest-path".to_string(),
+            workspace_root.join("Cargo.toml").display().to_string(),
+        ];
+        if options.all_targets {
+            args.push("--all-targets".to_string());
+        }
+        args.extend(options.args.iter().cloned());
+
+        let child = Command::new("cargo")
+            .args(&args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .stdin(Stdio::null())
+            .spawn();
+        let mut child = match child {
+            Ok(it) => it,
+            Err(e) => {
+                log::error!("failed to run `cargo {}`: {}", options.command, e);
+                return WatchThread::dummy();
+            }
+        };
+
+        let stdout = child.stdout.take().unwrap();
+        let (message_send, message_recv) = unbounded();
+        let reader = jod_thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let line = match line {
+                    Ok(it) => it,
+                    Err(_) => break,
+                };
+                let message = match serde_json::from_str::<Message>(&line) {
+                    Ok(it) => it,
+                    Err(e) => {
+                        log::error!("invalid json from cargo: {}", e);
+                        continue;
+                    }
+                };
+                if message_send.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+        WatchThread { message_recv, child: Some(child), _reader: Some(reader) }
+    }
+}
+
+impl Drop for WatchThread {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            // Killing a process which already exited fails, which is fine
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
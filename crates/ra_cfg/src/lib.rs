@@ -53,4 +53,12 @@ impl CfgOptions {
     pub fn insert_features(&mut self, iter: impl IntoIterator<Item = SmolStr>) {
         iter.into_iter().for_each(|feat| self.insert_key_value("feature".into(), feat));
     }
+
+    pub fn atoms(&self) -> impl Iterator<Item = &SmolStr> {
+        self.atoms.iter()
+    }
+
+    pub fn key_values(&self) -> impl Iterator<Item = (&SmolStr, &SmolStr)> {
+        self.key_values.iter().map(|(key, value)| (key, value))
+    }
 }
@@ -90,6 +90,7 @@ struct CrateData {
     cfg_options: CfgOptions,
     env: Env,
     dependencies: Vec<Dependency>,
+    declared_features: Vec<SmolStr>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -128,6 +129,15 @@ impl CrateGraph {
         &self.arena[&crate_id].cfg_options
     }
 
+    /// Features declared by the crate's manifest, including disabled ones.
+    pub fn declared_features(&self, crate_id: CrateId) -> &[SmolStr] {
+        &self.arena[&crate_id].declared_features
+    }
+
+    pub fn set_declared_features(&mut self, crate_id: CrateId, features: Vec<SmolStr>) {
+        self.arena.get_mut(&crate_id).unwrap().declared_features = features;
+    }
+
     pub fn add_dep(
         &mut self,
         from: CrateId,
@@ -214,7 +224,14 @@ impl CrateId {
 
 impl CrateData {
     fn new(file_id: FileId, edition: Edition, cfg_options: CfgOptions, env: Env) -> CrateData {
-        CrateData { file_id, edition, dependencies: Vec::new(), cfg_options, env }
+        CrateData {
+            file_id,
+            edition,
+            dependencies: Vec::new(),
+            cfg_options,
+            env,
+            declared_features: Vec::new(),
+        }
     }
 
     fn add_dep(&mut self, name: SmolStr, crate_id: CrateId) {
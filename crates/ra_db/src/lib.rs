@@ -95,15 +95,48 @@ pub trait SourceDatabase: CheckCanceled + FileLoader + std::fmt::Debug {
     #[salsa::invoke(parse_query)]
     fn parse(&self, file_id: FileId) -> Parse<ast::SourceFile>;
 
+    // Parses the file as code of the given edition. Unlike `parse`, this
+    // doesn't depend on the crate graph, so changes of the graph which keep
+    // the edition of the file don't lead to reparsing it.
+    #[salsa::invoke(parse_with_edition_query)]
+    fn parse_with_edition(
+        &self,
+        file_id: FileId,
+        edition: ra_syntax::Edition,
+    ) -> Parse<ast::SourceFile>;
+
     /// The crate graph.
     #[salsa::input]
     fn crate_graph(&self) -> Arc<CrateGraph>;
 }
 
 fn parse_query(db: &impl SourceDatabase, file_id: FileId) -> Parse<ast::SourceFile> {
+    db.parse_with_edition(file_id, file_edition(db, file_id))
+}
+
+fn parse_with_edition_query(
+    db: &impl SourceDatabase,
+    file_id: FileId,
+    edition: ra_syntax::Edition,
+) -> Parse<ast::SourceFile> {
     let _p = profile("parse_query");
     let text = db.file_text(file_id);
-    SourceFile::parse(&*text)
+    SourceFile::parse_with_edition(&*text, edition)
+}
+
+/// The edition a file should be parsed with.
+///
+/// FIXME: a file might belong to several crates with different editions, we
+/// just pick the first one.
+fn file_edition(db: &impl SourceDatabase, file_id: FileId) -> ra_syntax::Edition {
+    let krate = match db.relevant_crates(file_id).first() {
+        Some(&krate) => krate,
+        None => return ra_syntax::Edition::Edition2018,
+    };
+    match db.crate_graph().edition(krate) {
+        Edition::Edition2015 => ra_syntax::Edition::Edition2015,
+        Edition::Edition2018 => ra_syntax::Edition::Edition2018,
+    }
 }
 
 /// We don't want to give HIR knowledge of source roots, hence we extract these
@@ -1,19 +1,21 @@
 //! FIXME: write short doc here
-use std::sync::Arc;
+use std::{collections::hash_map::Entry, sync::Arc};
 
 use either::Either;
 use hir_def::{
-    adt::VariantData,
+    adt::{StructKind, VariantData},
     builtin_type::BuiltinType,
+    diagnostics::DuplicateDefinition,
     docs::Documentation,
     expr::{BindingAnnotation, Pat, PatId},
+    generics::TypeParamProvenance,
     nameres::ModuleSource,
     per_ns::PerNs,
     resolver::HasResolver,
     type_ref::{Mutability, TypeRef},
-    AdtId, ConstId, DefWithBodyId, EnumId, FunctionId, HasModule, ImplId, LocalEnumVariantId,
-    LocalModuleId, LocalStructFieldId, Lookup, ModuleId, StaticId, StructId, TraitId, TypeAliasId,
-    TypeParamId, UnionId,
+    AdtId, AssocContainerId, ConstId, DefWithBodyId, EnumId, FunctionId, GenericDefId, HasModule,
+    ImplId, LocalEnumVariantId, LocalModuleId, LocalStructFieldId, Lookup, ModuleId, StaticId,
+    StructId, TraitId, TypeAliasId, TypeParamId, UnionId,
 };
 use hir_expand::{
     diagnostics::DiagnosticSink,
@@ -25,11 +27,15 @@ use hir_ty::{
     TraitEnvironment, Ty, TyDefId, TypeCtor, TypeWalk,
 };
 use ra_db::{CrateId, Edition, FileId};
-use ra_syntax::ast;
+use ra_syntax::{
+    ast::{self, NameOwner},
+    AstPtr, SmolStr,
+};
+use rustc_hash::FxHashMap;
 
 use crate::{
     db::{DefDatabase, HirDatabase},
-    CallableDef, HirDisplay, InFile, Name,
+    CallableDef, HasSource, HirDisplay, InFile, Name,
 };
 
 /// hir::Crate describes a single crate. It's the main interface with which
@@ -82,6 +88,10 @@ impl Crate {
         crate_graph.edition(self.id)
     }
 
+    pub fn declared_features(self, db: &impl DefDatabase) -> Vec<SmolStr> {
+        db.crate_graph().declared_features(self.id).to_vec()
+    }
+
     pub fn all(db: &impl DefDatabase) -> Vec<Crate> {
         db.crate_graph().iter().map(|id| Crate { id }).collect()
     }
@@ -118,6 +128,24 @@ impl_froms!(
     BuiltinType
 );
 
+impl ModuleDef {
+    /// The module the definition is in, or `None` for crate roots and builtin
+    /// types.
+    pub fn module(self, db: &impl HirDatabase) -> Option<Module> {
+        match self {
+            ModuleDef::Module(it) => it.parent(db),
+            ModuleDef::Function(it) => Some(it.module(db)),
+            ModuleDef::Adt(it) => Some(it.module(db)),
+            ModuleDef::EnumVariant(it) => Some(it.module(db)),
+            ModuleDef::Const(it) => Some(it.module(db)),
+            ModuleDef::Static(it) => Some(it.module(db)),
+            ModuleDef::Trait(it) => Some(it.module(db)),
+            ModuleDef::TypeAlias(it) => Some(it.module(db)),
+            ModuleDef::BuiltinType(_) => None,
+        }
+    }
+}
+
 pub use hir_def::attr::Attrs;
 
 impl Module {
@@ -179,11 +207,21 @@ impl Module {
         res
     }
 
-    /// Returns a `ModuleScope`: a set of items, visible in this module.
-    pub fn scope(self, db: &impl HirDatabase) -> Vec<(Name, ScopeDef)> {
+    /// Returns a `ModuleScope`: a set of items, visible in this module. If
+    /// `visible_from` is given, only the items which can be accessed from that
+    /// module through a path are returned.
+    pub fn scope(
+        self,
+        db: &impl HirDatabase,
+        visible_from: Option<Module>,
+    ) -> Vec<(Name, ScopeDef)> {
         db.crate_def_map(self.id.krate)[self.id.local_id]
             .scope
             .entries()
+            .filter(|(_, res)| match visible_from {
+                Some(m) => res.visibility.is_visible_from(db, m.id),
+                None => true,
+            })
             .map(|(name, res)| (name.clone(), res.def.into()))
             .collect()
     }
@@ -191,8 +229,10 @@ impl Module {
     pub fn diagnostics(self, db: &impl HirDatabase, sink: &mut DiagnosticSink) {
         db.crate_def_map(self.id.krate).add_diagnostics(db, self.id.local_id, sink);
         for decl in self.declarations(db) {
+            db.check_canceled();
             match decl {
                 crate::ModuleDef::Function(f) => f.diagnostics(db, sink),
+                crate::ModuleDef::Adt(adt) => adt.diagnostics(db, sink),
                 crate::ModuleDef::Module(m) => {
                     // Only add diagnostics from inline modules
                     if let ModuleSource::Module(_) = m.definition_source(db).value {
@@ -204,8 +244,10 @@ impl Module {
         }
 
         for impl_block in self.impl_blocks(db) {
+            impl_block.diagnostics(db, sink);
             for item in impl_block.items(db) {
                 if let AssocItem::Function(f) = item {
+                    db.check_canceled();
                     f.diagnostics(db, sink);
                 }
             }
@@ -352,6 +394,11 @@ impl Enum {
     pub fn ty(self, db: &impl HirDatabase) -> Type {
         Type::from_def(db, self.id.lookup(db).container.module(db).krate, self.id)
     }
+
+    /// The integer type of the discriminants, if given with `#[repr]`.
+    pub fn repr(self, db: &impl DefDatabase) -> Option<BuiltinType> {
+        db.enum_data(self.id).repr.map(BuiltinType::Int)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -380,6 +427,14 @@ impl EnumVariant {
             .collect()
     }
 
+    pub fn kind(self, db: &impl DefDatabase) -> StructKind {
+        self.variant_data(db).kind()
+    }
+
+    pub fn discriminant(self, db: &impl DefDatabase) -> Option<i128> {
+        db.enum_data(self.parent.id).variants[self.id].discriminant
+    }
+
     pub(crate) fn variant_data(self, db: &impl DefDatabase) -> Arc<VariantData> {
         db.enum_data(self.parent.id).variants[self.id].variant_data.clone()
     }
@@ -415,6 +470,10 @@ impl Adt {
     pub fn krate(self, db: &impl HirDatabase) -> Option<Crate> {
         Some(self.module(db).krate())
     }
+
+    pub fn diagnostics(self, db: &impl HirDatabase, sink: &mut DiagnosticSink) {
+        hir_ty::adt_validation::validate_adt(db, self.into(), sink);
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -497,6 +556,11 @@ impl Function {
         db.function_data(self.id).params.clone()
     }
 
+    /// Dumps the lowered body of the function, for debugging purposes.
+    pub fn debug_hir(self, db: &impl HirDatabase) -> String {
+        db.body(self.id.into()).pretty_print()
+    }
+
     pub fn diagnostics(self, db: &impl HirDatabase, sink: &mut DiagnosticSink) {
         let infer = db.infer(self.id.into());
         infer.add_diagnostics(db, self.id, sink);
@@ -619,6 +683,26 @@ impl AssocItem {
             AssocItem::TypeAlias(t) => t.module(db),
         }
     }
+
+    /// The trait or the impl block the item is declared in.
+    pub fn container(self, db: &impl DefDatabase) -> Option<AssocItemContainer> {
+        let container = match self {
+            AssocItem::Function(it) => it.id.lookup(db).container,
+            AssocItem::Const(it) => it.id.lookup(db).container,
+            AssocItem::TypeAlias(it) => it.id.lookup(db).container,
+        };
+        match container {
+            AssocContainerId::TraitId(id) => Some(AssocItemContainer::Trait(id.into())),
+            AssocContainerId::ImplId(id) => Some(AssocItemContainer::ImplBlock(id.into())),
+            AssocContainerId::ContainerId(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AssocItemContainer {
+    Trait(Trait),
+    ImplBlock(ImplBlock),
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
@@ -644,6 +728,22 @@ impl_froms!(
     Const
 );
 
+impl GenericDef {
+    /// The type parameters declared by this definition itself, not including
+    /// the ones of its parent.
+    pub fn type_params(self, db: &impl HirDatabase) -> Vec<TypeParam> {
+        let parent: GenericDefId = self.into();
+        let generics = db.generic_params(parent);
+        generics
+            .types
+            .iter()
+            // `impl Trait` arguments are anonymous, they can't be referred to
+            .filter(|(_, param)| param.provenance != TypeParamProvenance::ArgumentImplTrait)
+            .map(|(local_id, _)| TypeParam { id: TypeParamId { parent, local_id } })
+            .collect()
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Local {
     pub(crate) parent: DefWithBody,
@@ -716,6 +816,11 @@ impl TypeParam {
     pub fn module(self, db: &impl HirDatabase) -> Module {
         self.id.parent.module(db).into()
     }
+
+    pub fn has_default(self, db: &impl HirDatabase) -> bool {
+        let params = db.generic_params(self.id.parent);
+        params.types[self.id.local_id].default.is_some()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -760,6 +865,45 @@ impl ImplBlock {
         db.impl_data(self.id).is_negative
     }
 
+    /// Reports items which are defined more than once in the same namespace,
+    /// methods which don't match their declaration in the trait, and super
+    /// traits of the trait which the type doesn't implement.
+    pub fn diagnostics(&self, db: &impl HirDatabase, sink: &mut DiagnosticSink) {
+        hir_ty::impl_validation::validate_trait_impl(db, self.id, sink);
+        hir_ty::impl_validation::validate_super_traits(db, self.id, sink);
+        let mut values: FxHashMap<Name, InFile<AstPtr<ast::Name>>> = FxHashMap::default();
+        let mut types: FxHashMap<Name, InFile<AstPtr<ast::Name>>> = FxHashMap::default();
+        for item in self.items(db) {
+            let (seen, name, src) = match item {
+                AssocItem::Function(it) => {
+                    (&mut values, it.name(db), it.source(db).map(|it| it.name()))
+                }
+                AssocItem::Const(it) => match it.name(db) {
+                    Some(name) => (&mut values, name, it.source(db).map(|it| it.name())),
+                    None => continue,
+                },
+                AssocItem::TypeAlias(it) => {
+                    (&mut types, it.name(db), it.source(db).map(|it| it.name()))
+                }
+            };
+            let ptr = match &src.value {
+                Some(it) => src.with_value(AstPtr::new(it)),
+                None => continue,
+            };
+            match seen.entry(name) {
+                Entry::Vacant(entry) => {
+                    entry.insert(ptr);
+                }
+                Entry::Occupied(entry) => sink.push(DuplicateDefinition {
+                    file: ptr.file_id,
+                    decl: ptr.value,
+                    first: *entry.get(),
+                    name: entry.key().clone(),
+                }),
+            }
+        }
+    }
+
     pub fn module(&self, db: &impl DefDatabase) -> Module {
         self.id.lookup(db).container.module(db).into()
     }
@@ -823,6 +967,14 @@ impl Type {
         Some(self.ty.value.as_callable()?.0)
     }
 
+    /// Types of the parameters if this is the type of a function or of a tuple
+    /// struct or variant constructor.
+    pub fn callable_params(&self, db: &impl HirDatabase) -> Option<Vec<Type>> {
+        let (def, substs) = self.ty.value.as_callable()?;
+        let sig = db.callable_item_signature(def).subst(substs);
+        Some(sig.params().iter().map(|ty| self.derived(ty.clone())).collect())
+    }
+
     pub fn contains_unknown(&self) -> bool {
         return go(&self.ty.value);
 
@@ -931,6 +1083,11 @@ impl Type {
         Some(adt.into())
     }
 
+    /// The crate of the code this type was computed for.
+    pub fn krate(&self) -> Crate {
+        Crate { id: self.krate }
+    }
+
     // FIXME: provide required accessors such that it becomes implementable from outside.
     pub fn is_equal_for_find_impls(&self, other: &Type) -> bool {
         match (&self.ty.value, &other.ty.value) {
@@ -961,6 +1118,7 @@ impl HirDisplay for Type {
 }
 
 /// For IDE only
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ScopeDef {
     ModuleDef(ModuleDef),
     MacroDef(MacroDef),
@@ -1,4 +1,10 @@
 //! FIXME: write short doc here
-pub use hir_def::diagnostics::UnresolvedModule;
+pub use hir_def::diagnostics::{DuplicateDefinition, UnresolvedModule};
 pub use hir_expand::diagnostics::{AstDiagnostic, Diagnostic, DiagnosticSink};
-pub use hir_ty::diagnostics::{MissingFields, MissingOkInTailExpr, NoSuchField};
+pub use hir_ty::diagnostics::{
+    AmbiguousName, FloatEqComparison, MismatchedVariantPath, MissingFields, MissingOkInTailExpr,
+    MissingSuperTraitImpls, MoveOutOfBorrow, NoOpOperation, NoSuchField, PrivateItem,
+    RecursiveType, RedundantCast, RedundantPatternMatching, ReturnsLocalReference, ShadowedBinding,
+    SignatureMismatch, TraitMethodMismatch, TruncatingCast, UnusedCollection, UnusedMustUse,
+    UnwrapCall,
+};
@@ -219,6 +219,22 @@ impl Module {
         })?;
         Some(Module { id: ModuleId { krate, local_id } })
     }
+
+    /// Returns all the modules defined by `file`, in all the crates it is part
+    /// of. A file can be included in several crates, e.g. by shared modules.
+    pub fn all_from_file(db: &impl DefDatabase, file: FileId) -> Vec<Self> {
+        let _p = profile("Module::all_from_file");
+        db.relevant_crates(file)
+            .iter()
+            .flat_map(|&krate| {
+                let crate_def_map = db.crate_def_map(krate);
+                crate_def_map
+                    .modules_for_file(file)
+                    .map(|local_id| Module { id: ModuleId { krate, local_id } })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
 }
 
 fn analyze_container(db: &impl DefDatabase, src: InFile<&SyntaxNode>) -> DynMap {
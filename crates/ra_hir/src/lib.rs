@@ -36,20 +36,23 @@ mod code_model;
 
 mod has_source;
 mod from_source;
+mod semantics;
 
 pub use crate::{
     code_model::{
-        Adt, AssocItem, AttrDef, Const, Crate, CrateDependency, DefWithBody, Docs, Enum,
-        EnumVariant, FieldSource, Function, GenericDef, HasAttrs, ImplBlock, Local, MacroDef,
-        Module, ModuleDef, ScopeDef, Static, Struct, StructField, Trait, Type, TypeAlias,
+        Adt, AssocItem, AssocItemContainer, AttrDef, Const, Crate, CrateDependency, DefWithBody,
+        Docs, Enum, EnumVariant, FieldSource, Function, GenericDef, HasAttrs, ImplBlock, Local,
+        MacroDef, Module, ModuleDef, ScopeDef, Static, Struct, StructField, Trait, Type, TypeAlias,
         TypeParam, Union, VariantDef,
     },
     from_source::FromSource,
     has_source::HasSource,
+    semantics::{descend_into_macros, original_range},
     source_binder::{PathResolution, ScopeEntryWithSyntax, SourceAnalyzer},
 };
 
 pub use hir_def::{
+    adt::StructKind,
     body::scope::ExprScopes,
     builtin_type::BuiltinType,
     docs::Documentation,
@@ -0,0 +1,118 @@
+//! Maps the syntax nodes produced by macro expansion back to the source code
+//! the user actually wrote.
+
+use hir_expand::{db::AstDatabase, InFile, Origin};
+use ra_db::FileRange;
+use ra_syntax::{ast, AstNode, SyntaxNode, SyntaxToken, TextRange};
+use smallvec::SmallVec;
+
+use crate::{db::HirDatabase, SourceAnalyzer};
+
+/// Finds the tokens a token of a macro call becomes in the expansion of the
+/// call, following nested macro calls down to the innermost ones. This is the
+/// inverse of `original_range`. A macro can repeat its arguments, so a token
+/// may be mapped to several tokens; tokens outside of macro calls, or which
+/// can't be mapped, are returned as is.
+pub fn descend_into_macros(
+    db: &impl HirDatabase,
+    token: InFile<SyntaxToken>,
+) -> SmallVec<[InFile<SyntaxToken>; 1]> {
+    let mut res = SmallVec::new();
+    let mut stack = vec![token];
+    while let Some(token) = stack.pop() {
+        let expanded = descend_once(db, token.as_ref());
+        if expanded.is_empty() {
+            res.push(token);
+        } else {
+            // keep the tokens in the order of the expansion
+            stack.extend(expanded.into_iter().rev());
+        }
+    }
+    res
+}
+
+fn descend_once(db: &impl HirDatabase, token: InFile<&SyntaxToken>) -> Vec<InFile<SyntaxToken>> {
+    let macro_call = match token.value.ancestors().find_map(ast::MacroCall::cast) {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    match macro_call.token_tree() {
+        Some(tt) if token.value.text_range().is_subrange(&tt.syntax().text_range()) => (),
+        _ => return Vec::new(),
+    }
+    let analyzer = SourceAnalyzer::new(db, token.with_value(&token.value.parent()), None);
+    match analyzer.expand(db, token.with_value(&macro_call)) {
+        Some(exp) => exp.map_token_down_all(db, token),
+        None => Vec::new(),
+    }
+}
+
+/// Finds the range in a real file a node comes from. For nodes in macro
+/// expansions, this is the range of the tokens of the macro call the node was
+/// created from, or the range of the whole macro call if the node was created
+/// by the macro itself. Nested macro calls are followed up to the outermost
+/// one.
+pub fn original_range(db: &impl AstDatabase, node: InFile<&SyntaxNode>) -> FileRange {
+    if let Some(range) = original_range_from_call(db, node) {
+        return range;
+    }
+
+    if let Some(call_node) = node.file_id.call_node(db) {
+        return original_range(db, call_node.as_ref());
+    }
+
+    FileRange { file_id: node.file_id.original_file(db), range: node.value.text_range() }
+}
+
+fn original_range_from_call(db: &impl AstDatabase, node: InFile<&SyntaxNode>) -> Option<FileRange> {
+    node.file_id.call_node(db)?;
+
+    // the input node has only one token ?
+    let single = node.value.first_token()? == node.value.last_token()?;
+
+    node.value.descendants().find_map(|it| {
+        let first = it.first_token()?;
+        let last = it.last_token()?;
+
+        if !single && first == last {
+            return None;
+        }
+
+        // Try to map first and last tokens of node, and, if success, return the union range of mapped tokens
+        let first = ascend_to_original_file(db, node.with_value(first))?;
+        let last = ascend_to_original_file(db, node.with_value(last))?;
+
+        if first.file_id != last.file_id {
+            return None;
+        }
+
+        Some(FileRange {
+            file_id: first.file_id.original_file(db),
+            range: union_range(first.value.text_range(), last.value.text_range()),
+        })
+    })
+}
+
+/// Maps the token through all the macro calls it is an argument of. Fails if
+/// at some level the token comes from a macro definition instead.
+fn ascend_to_original_file(
+    db: &impl AstDatabase,
+    mut token: InFile<SyntaxToken>,
+) -> Option<InFile<SyntaxToken>> {
+    while token.file_id.call_node(db).is_some() {
+        let expansion = token.file_id.expansion_info(db)?;
+        let (mapped, origin) = expansion.map_token_up(token.as_ref())?;
+        if origin != Origin::Call {
+            return None;
+        }
+        token = mapped;
+    }
+    Some(token)
+}
+
+// FIXME: Add union method in TextRange
+fn union_range(a: TextRange, b: TextRange) -> TextRange {
+    let start = a.start().min(b.start());
+    let end = a.end().max(b.end());
+    TextRange::from_to(start, end)
+}
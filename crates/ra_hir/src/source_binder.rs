@@ -15,8 +15,9 @@ use hir_def::{
     },
     expr::{ExprId, PatId},
     nameres::ModuleSource,
-    path::path,
+    path::{path, ModPath},
     resolver::{self, resolver_for_scope, HasResolver, Resolver, TypeNs, ValueNs},
+    type_ref::TypeRef,
     AssocItemId, DefWithBodyId,
 };
 use hir_expand::{
@@ -160,6 +161,19 @@ impl Expansion {
         exp_info.map_token_down(token)
     }
 
+    /// Like `map_token_down`, but returns all the tokens the token becomes in
+    /// the expansion.
+    pub fn map_token_down_all(
+        &self,
+        db: &impl HirDatabase,
+        token: InFile<&SyntaxToken>,
+    ) -> Vec<InFile<SyntaxToken>> {
+        match self.file_id().expansion_info(db) {
+            Some(exp_info) => exp_info.map_token_down_all(token),
+            None => Vec::new(),
+        }
+    }
+
     pub fn file_id(&self) -> HirFileId {
         self.macro_call_id.as_file()
     }
@@ -178,7 +192,7 @@ impl SourceAnalyzer {
             let scopes = db.expr_scopes(def.into());
             let scope = match offset {
                 None => scope_for(&scopes, &source_map, node),
-                Some(offset) => scope_for_offset(&scopes, &source_map, node.with_value(offset)),
+                Some(offset) => scope_for_offset(&scopes, &source_map, node, offset),
             };
             let resolver = resolver_for_scope(db, def.into(), scope);
             SourceAnalyzer {
@@ -429,10 +443,42 @@ impl SourceAnalyzer {
     /// Checks that particular type `ty` implements `std::future::Future`.
     /// This function is used in `.await` syntax completion.
     pub fn impls_future(&self, db: &impl HirDatabase, ty: Type) -> bool {
-        let std_future_path = path![std::future::Future];
+        self.impls_known_trait(db, ty, &path![std::future::Future])
+    }
+
+    /// Checks that particular type `ty` implements `std::iter::IntoIterator`.
+    /// This function is used to rank iterator methods in completion.
+    pub fn impls_into_iterator(&self, db: &impl HirDatabase, ty: Type) -> bool {
+        self.impls_known_trait(db, ty, &path![std::iter::IntoIterator])
+    }
+
+    /// Checks that particular type `ty` implements `std::default::Default`.
+    /// This function is used to generate stub bodies in assists.
+    pub fn impls_default(&self, db: &impl HirDatabase, ty: Type) -> bool {
+        self.impls_known_trait(db, ty, &path![std::default::Default])
+    }
+
+    /// Checks that particular type `ty` implements `std::marker::Copy`.
+    /// This function is used to return copyable fields by value in assists.
+    pub fn impls_copy(&self, db: &impl HirDatabase, ty: Type) -> bool {
+        self.impls_known_trait(db, ty, &path![std::marker::Copy])
+    }
+
+    /// Checks that particular type `ty` implements `std::marker::Send`.
+    /// This function is used to show the auto traits of a type on hover.
+    pub fn impls_send(&self, db: &impl HirDatabase, ty: Type) -> bool {
+        self.impls_known_trait(db, ty, &path![std::marker::Send])
+    }
 
-        let std_future_trait = match self.resolver.resolve_known_trait(db, &std_future_path) {
-            Some(it) => it.into(),
+    /// Checks that particular type `ty` implements `std::marker::Sync`.
+    /// This function is used to show the auto traits of a type on hover.
+    pub fn impls_sync(&self, db: &impl HirDatabase, ty: Type) -> bool {
+        self.impls_known_trait(db, ty, &path![std::marker::Sync])
+    }
+
+    fn impls_known_trait(&self, db: &impl HirDatabase, ty: Type, trait_path: &ModPath) -> bool {
+        let trait_ = match self.resolver.resolve_known_trait(db, trait_path) {
+            Some(it) => it,
             _ => return false,
         };
 
@@ -442,7 +488,23 @@ impl SourceAnalyzer {
         };
 
         let canonical_ty = Canonical { value: ty.ty.value, num_vars: 0 };
-        implements_trait(&canonical_ty, db, &self.resolver, krate.into(), std_future_trait)
+        implements_trait(&canonical_ty, db, &self.resolver, krate, trait_)
+    }
+
+    /// Lowers a type written in the source code, resolving names relative to
+    /// the scope of this analyzer.
+    pub fn resolve_type(&self, db: &impl HirDatabase, type_ref: &ast::TypeRef) -> Option<Type> {
+        let type_ref = TypeRef::from_ast(type_ref.clone());
+        let ty = Ty::from_hir(db, &self.resolver, &type_ref);
+        let environment = TraitEnvironment::lower(db, &self.resolver);
+        Some(Type { krate: self.resolver.krate()?, ty: InEnvironment { value: ty, environment } })
+    }
+
+    /// The type a path names, like `Self` in a trait.
+    pub fn resolve_path_as_type(&self, db: &impl HirDatabase, path: &crate::Path) -> Option<Type> {
+        let ty = Ty::from_hir(db, &self.resolver, &TypeRef::Path(path.clone()));
+        let environment = TraitEnvironment::lower(db, &self.resolver);
+        Some(Type { krate: self.resolver.krate()?, ty: InEnvironment { value: ty, environment } })
     }
 
     pub fn expand(
@@ -474,15 +536,17 @@ fn scope_for(
 fn scope_for_offset(
     scopes: &ExprScopes,
     source_map: &BodySourceMap,
-    offset: InFile<TextUnit>,
+    node: InFile<&SyntaxNode>,
+    offset: TextUnit,
 ) -> Option<ScopeId> {
+    let root = node.value.ancestors().last()?;
     scopes
         .scope_by_expr()
         .iter()
         .filter_map(|(id, scope)| {
             let source = source_map.expr_syntax(*id)?;
             // FIXME: correctly handle macro expansion
-            if source.file_id != offset.file_id {
+            if source.file_id != node.file_id {
                 return None;
             }
             let syntax_node_ptr =
@@ -491,13 +555,10 @@ fn scope_for_offset(
         })
         // find containing scope
         .min_by_key(|(ptr, _scope)| {
-            (
-                !(ptr.range().start() <= offset.value && offset.value <= ptr.range().end()),
-                ptr.range().len(),
-            )
+            (!(ptr.range().start() <= offset && offset <= ptr.range().end()), ptr.range().len())
         })
         .map(|(ptr, scope)| {
-            adjust(scopes, source_map, ptr, offset.file_id, offset.value).unwrap_or(*scope)
+            adjust(scopes, source_map, &root, ptr, node.file_id, offset).unwrap_or(*scope)
         })
 }
 
@@ -506,6 +567,7 @@ fn scope_for_offset(
 fn adjust(
     scopes: &ExprScopes,
     source_map: &BodySourceMap,
+    root: &SyntaxNode,
     ptr: SyntaxNodePtr,
     file_id: HirFileId,
     offset: TextUnit,
@@ -524,8 +586,12 @@ fn adjust(
                 source.value.either(|it| it.syntax_node_ptr(), |it| it.syntax_node_ptr());
             Some((syntax_node_ptr, scope))
         })
-        .map(|(ptr, scope)| (ptr.range(), scope))
-        .filter(|(range, _)| range.start() <= offset && range.is_subrange(&r) && *range != r);
+        .filter(|(ptr, _)| {
+            let range = ptr.range();
+            range.start() <= offset && range.is_subrange(&r) && range != r
+        })
+        .filter(|(ptr, _)| is_scope_visible_at(&ptr.to_node(root), r, offset))
+        .map(|(ptr, scope)| (ptr.range(), scope));
 
     child_scopes
         .max_by(|(r1, _), (r2, _)| {
@@ -539,3 +605,15 @@ fn adjust(
         })
         .map(|(_ptr, scope)| *scope)
 }
+
+/// Bindings introduced by an expression the cursor is not in (like the
+/// pattern of a previous match arm or the parameters of a closure) are not
+/// in scope at the cursor, so the scope of `node` is only usable if all
+/// expressions and match arms between it and the `container` contain `offset`.
+fn is_scope_visible_at(node: &SyntaxNode, container: TextRange, offset: TextUnit) -> bool {
+    node.ancestors()
+        .skip(1)
+        .take_while(|it| it.text_range() != container)
+        .filter(|it| ast::Expr::can_cast(it.kind()) || ast::MatchArm::can_cast(it.kind()))
+        .all(|it| it.text_range().contains_inclusive(offset))
+}
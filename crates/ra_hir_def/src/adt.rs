@@ -4,16 +4,25 @@ use std::sync::Arc;
 
 use either::Either;
 use hir_expand::{
+    hygiene::Hygiene,
     name::{AsName, Name},
     InFile,
 };
 use ra_arena::{map::ArenaMap, Arena};
 use ra_prof::profile;
-use ra_syntax::ast::{self, NameOwner, TypeAscriptionOwner};
+use ra_syntax::ast::{self, NameOwner, TypeAscriptionOwner, VisibilityOwner};
 
 use crate::{
-    db::DefDatabase, src::HasChildSource, src::HasSource, trace::Trace, type_ref::TypeRef, EnumId,
-    LocalEnumVariantId, LocalStructFieldId, Lookup, StructId, UnionId, VariantId,
+    attr::Attrs,
+    builtin_type::BuiltinInt,
+    db::DefDatabase,
+    resolver::HasResolver,
+    src::HasChildSource,
+    src::HasSource,
+    trace::Trace,
+    type_ref::TypeRef,
+    visibility::{RawVisibility, Visibility},
+    EnumId, LocalEnumVariantId, LocalStructFieldId, Lookup, StructId, UnionId, VariantId,
 };
 
 /// Note that we use `StructData` for unions as well!
@@ -27,12 +36,18 @@ pub struct StructData {
 pub struct EnumData {
     pub name: Name,
     pub variants: Arena<LocalEnumVariantId, EnumVariantData>,
+    /// The integer type of the discriminants, given by `#[repr(u8)]` and the
+    /// like.
+    pub repr: Option<BuiltinInt>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EnumVariantData {
     pub name: Name,
     pub variant_data: Arc<VariantData>,
+    /// The value of the discriminant, if it's an integer literal or follows a
+    /// variant with a known discriminant.
+    pub discriminant: Option<i128>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -47,24 +62,28 @@ pub enum VariantData {
 pub struct StructFieldData {
     pub name: Name,
     pub type_ref: TypeRef,
+    pub visibility: RawVisibility,
 }
 
 impl StructData {
     pub(crate) fn struct_data_query(db: &impl DefDatabase, id: StructId) -> Arc<StructData> {
         let src = id.lookup(db).source(db);
         let name = src.value.name().map_or_else(Name::missing, |n| n.as_name());
-        let variant_data = VariantData::new(src.value.kind());
+        let hygiene = Hygiene::new(db, src.file_id);
+        let variant_data = VariantData::new(src.value.kind(), &hygiene);
         let variant_data = Arc::new(variant_data);
         Arc::new(StructData { name, variant_data })
     }
     pub(crate) fn union_data_query(db: &impl DefDatabase, id: UnionId) -> Arc<StructData> {
         let src = id.lookup(db).source(db);
         let name = src.value.name().map_or_else(Name::missing, |n| n.as_name());
+        let hygiene = Hygiene::new(db, src.file_id);
         let variant_data = VariantData::new(
             src.value
                 .record_field_def_list()
                 .map(ast::StructKind::Record)
                 .unwrap_or(ast::StructKind::Unit),
+            &hygiene,
         );
         let variant_data = Arc::new(variant_data);
         Arc::new(StructData { name, variant_data })
@@ -76,9 +95,11 @@ impl EnumData {
         let _p = profile("enum_data_query");
         let src = e.lookup(db).source(db);
         let name = src.value.name().map_or_else(Name::missing, |n| n.as_name());
+        let hygiene = Hygiene::new(db, src.file_id);
         let mut trace = Trace::new_for_arena();
-        lower_enum(&mut trace, &src.value);
-        Arc::new(EnumData { name, variants: trace.into_arena() })
+        lower_enum(&mut trace, &src.value, &hygiene);
+        let repr = repr_int(&db.attrs(e.into()));
+        Arc::new(EnumData { name, variants: trace.into_arena(), repr })
     }
 
     pub fn variant(&self, name: &Name) -> Option<LocalEnumVariantId> {
@@ -92,8 +113,9 @@ impl HasChildSource for EnumId {
     type Value = ast::EnumVariant;
     fn child_source(&self, db: &impl DefDatabase) -> InFile<ArenaMap<Self::ChildId, Self::Value>> {
         let src = self.lookup(db).source(db);
+        let hygiene = Hygiene::new(db, src.file_id);
         let mut trace = Trace::new_for_map();
-        lower_enum(&mut trace, &src.value);
+        lower_enum(&mut trace, &src.value, &hygiene);
         src.with_value(trace.into_map())
     }
 }
@@ -101,22 +123,53 @@ impl HasChildSource for EnumId {
 fn lower_enum(
     trace: &mut Trace<LocalEnumVariantId, EnumVariantData, ast::EnumVariant>,
     ast: &ast::EnumDef,
+    hygiene: &Hygiene,
 ) {
+    // without an explicit discriminant, a variant takes the one of the
+    // previous variant plus one, and the first variant takes 0
+    let mut next_discriminant = Some(0);
     for var in ast.variant_list().into_iter().flat_map(|it| it.variants()) {
+        let discriminant = match var.expr() {
+            Some(expr) => int_literal_value(&expr),
+            None => next_discriminant,
+        };
+        next_discriminant = discriminant.and_then(|it| it.checked_add(1));
         trace.alloc(
             || var.clone(),
             || EnumVariantData {
                 name: var.name().map_or_else(Name::missing, |it| it.as_name()),
-                variant_data: Arc::new(VariantData::new(var.kind())),
+                variant_data: Arc::new(VariantData::new(var.kind(), hygiene)),
+                discriminant,
             },
         );
     }
 }
 
+/// The value of an integer literal, possibly negated, like `-1`.
+fn int_literal_value(expr: &ast::Expr) -> Option<i128> {
+    match expr {
+        ast::Expr::Literal(it) => it.int_value().map(i128::from),
+        ast::Expr::PrefixExpr(it) if it.op_kind() == Some(ast::PrefixOp::Neg) => {
+            int_literal_value(&it.expr()?).map(|it| -it)
+        }
+        ast::Expr::ParenExpr(it) => int_literal_value(&it.expr()?),
+        _ => None,
+    }
+}
+
+/// The integer type among the representation hints, like `u8` in
+/// `#[repr(C, u8)]`.
+fn repr_int(attrs: &Attrs) -> Option<BuiltinInt> {
+    attrs.by_key("repr").tt_values().flat_map(|it| it.token_trees.iter()).find_map(|tt| match tt {
+        tt::TokenTree::Leaf(tt::Leaf::Ident(ident)) => BuiltinInt::from_suffix(&ident.text),
+        _ => None,
+    })
+}
+
 impl VariantData {
-    fn new(flavor: ast::StructKind) -> Self {
+    fn new(flavor: ast::StructKind, hygiene: &Hygiene) -> Self {
         let mut trace = Trace::new_for_arena();
-        match lower_struct(&mut trace, &flavor) {
+        match lower_struct(&mut trace, &flavor, hygiene) {
             StructKind::Tuple => VariantData::Tuple(trace.into_arena()),
             StructKind::Record => VariantData::Record(trace.into_arena()),
             StructKind::Unit => VariantData::Unit,
@@ -141,6 +194,46 @@ impl VariantData {
             _ => false,
         }
     }
+
+    pub fn kind(&self) -> StructKind {
+        match self {
+            VariantData::Record(_) => StructKind::Record,
+            VariantData::Tuple(_) => StructKind::Tuple,
+            VariantData::Unit => StructKind::Unit,
+        }
+    }
+}
+
+impl VariantData {
+    pub(crate) fn field_visibilities_query(
+        db: &impl DefDatabase,
+        variant_id: VariantId,
+    ) -> Arc<ArenaMap<LocalStructFieldId, Visibility>> {
+        let variant_data = variant_id.variant_data(db);
+        let resolver = variant_id.resolver(db);
+        let mut res = ArenaMap::default();
+        for (field_id, field_data) in variant_data.fields().iter() {
+            let visibility = match variant_id {
+                // fields of enum variants are always as visible as the enum itself
+                VariantId::EnumVariantId(_) => Visibility::Public,
+                _ => field_data.visibility.resolve(db, &resolver),
+            };
+            res.insert(field_id, visibility);
+        }
+        Arc::new(res)
+    }
+}
+
+impl VariantId {
+    pub fn variant_data(self, db: &impl DefDatabase) -> Arc<VariantData> {
+        match self {
+            VariantId::StructId(it) => db.struct_data(it).variant_data.clone(),
+            VariantId::UnionId(it) => db.union_data(it).variant_data.clone(),
+            VariantId::EnumVariantId(it) => {
+                db.enum_data(it.parent).variants[it.local_id].variant_data.clone()
+            }
+        }
+    }
 }
 
 impl HasChildSource for VariantId {
@@ -162,13 +255,15 @@ impl HasChildSource for VariantId {
                     .unwrap_or(ast::StructKind::Unit)
             }),
         };
+        let hygiene = Hygiene::new(db, src.file_id);
         let mut trace = Trace::new_for_map();
-        lower_struct(&mut trace, &src.value);
+        lower_struct(&mut trace, &src.value, &hygiene);
         src.with_value(trace.into_map())
     }
 }
 
-enum StructKind {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StructKind {
     Tuple,
     Record,
     Unit,
@@ -181,6 +276,7 @@ fn lower_struct(
         Either<ast::TupleFieldDef, ast::RecordFieldDef>,
     >,
     ast: &ast::StructKind,
+    hygiene: &Hygiene,
 ) -> StructKind {
     match ast {
         ast::StructKind::Tuple(fl) => {
@@ -190,6 +286,7 @@ fn lower_struct(
                     || StructFieldData {
                         name: Name::new_tuple_field(i),
                         type_ref: TypeRef::from_ast_opt(fd.type_ref()),
+                        visibility: RawVisibility::from_ast_with_hygiene(fd.visibility(), hygiene),
                     },
                 );
             }
@@ -202,6 +299,7 @@ fn lower_struct(
                     || StructFieldData {
                         name: fd.name().map(|n| n.as_name()).unwrap_or_else(Name::missing),
                         type_ref: TypeRef::from_ast_opt(fd.ascribed_type()),
+                        visibility: RawVisibility::from_ast_with_hygiene(fd.visibility(), hygiene),
                     },
                 );
             }
@@ -5,11 +5,12 @@ use std::{ops, sync::Arc};
 use either::Either;
 use hir_expand::{hygiene::Hygiene, AstId, InFile};
 use mbe::ast_to_token_tree;
+use ra_cfg::CfgOptions;
 use ra_syntax::{
     ast::{self, AstNode, AttrsOwner},
     SmolStr,
 };
-use tt::Subtree;
+use tt::{Leaf, Subtree, TokenTree};
 
 use crate::{
     db::DefDatabase, path::ModPath, src::HasChildSource, src::HasSource, AdtId, AttrDefId, Lookup,
@@ -90,6 +91,18 @@ impl Attrs {
     pub fn by_key(&self, key: &'static str) -> AttrQuery<'_> {
         AttrQuery { attrs: self, key }
     }
+
+    /// Replaces each `#[cfg_attr(pred, attr1, attr2, ..)]` with `attr1`,
+    /// `attr2`, .. if `pred` holds with `cfg_options` and drops it otherwise.
+    /// The predicate is evaluated in the same way as in `#[cfg(pred)]`.
+    pub(crate) fn expand_cfg_attr(&self, cfg_options: &CfgOptions) -> Attrs {
+        if self.iter().all(|attr| attr.cfg_attr_input().is_none()) {
+            return self.clone();
+        }
+        let entries: Vec<Attr> =
+            self.iter().flat_map(|attr| attr.expand_cfg_attr(cfg_options)).collect();
+        Attrs { entries: if entries.is_empty() { None } else { Some(entries.into()) } }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -121,6 +134,61 @@ impl Attr {
 
         Some(Attr { path, input })
     }
+
+    fn cfg_attr_input(&self) -> Option<&Subtree> {
+        if self.path.as_ident()?.to_string() != "cfg_attr" {
+            return None;
+        }
+        match self.input.as_ref()? {
+            AttrInput::TokenTree(it) => Some(it),
+            AttrInput::Literal(_) => None,
+        }
+    }
+
+    fn expand_cfg_attr(&self, cfg_options: &CfgOptions) -> Vec<Attr> {
+        let input = match self.cfg_attr_input() {
+            Some(it) => it,
+            None => return vec![self.clone()],
+        };
+        let mut parts = input.token_trees.split(|tt| match tt {
+            TokenTree::Leaf(Leaf::Punct(punct)) => punct.char == ',',
+            _ => false,
+        });
+        let pred = match parts.next() {
+            Some(it) => Subtree { delimiter: None, token_trees: it.to_vec() },
+            None => return Vec::new(),
+        };
+        if cfg_options.is_cfg_enabled(&pred) == Some(false) {
+            return Vec::new();
+        }
+        parts
+            .filter_map(Attr::from_tts)
+            // `cfg_attr` may be nested
+            .flat_map(|attr| attr.expand_cfg_attr(cfg_options))
+            .collect()
+    }
+
+    /// Parses an attribute from the tokens between the commas of a `cfg_attr`.
+    fn from_tts(tts: &[TokenTree]) -> Option<Attr> {
+        // FIXME: support paths with more than one segment
+        let (ident, rest) = match tts.split_first()? {
+            (TokenTree::Leaf(Leaf::Ident(ident)), rest) => (ident, rest),
+            _ => return None,
+        };
+        let path = ModPath::from_tt_ident(ident);
+        let input = match rest {
+            [] => None,
+            [TokenTree::Subtree(subtree)] => Some(AttrInput::TokenTree(subtree.clone())),
+            [TokenTree::Leaf(Leaf::Punct(eq)), TokenTree::Leaf(Leaf::Literal(lit))]
+                if eq.char == '=' =>
+            {
+                // FIXME: escape? raw string?
+                Some(AttrInput::Literal(lit.text.trim_matches('"').into()))
+            }
+            _ => return None,
+        };
+        Some(Attr { path, input })
+    }
 }
 
 pub struct AttrQuery<'a> {
@@ -3,7 +3,7 @@
 mod lower;
 pub mod scope;
 
-use std::{mem, ops::Index, sync::Arc};
+use std::{fmt::Write, mem, ops::Index, sync::Arc};
 
 use drop_bomb::DropBomb;
 use either::Either;
@@ -208,6 +208,23 @@ impl Body {
     ) -> (Body, BodySourceMap) {
         lower::lower(db, def, expander, params, body)
     }
+
+    /// Dumps all expressions and patterns of the body together with their ids,
+    /// for debugging purposes.
+    pub fn pretty_print(&self) -> String {
+        let mut buf = String::new();
+        writeln!(buf, "params: {:?}", self.params).unwrap();
+        writeln!(buf, "body: {:?}", self.body_expr).unwrap();
+        buf.push_str("\npats:\n");
+        for (id, pat) in self.pats.iter() {
+            writeln!(buf, "    {:?}: {:?}", id, pat).unwrap();
+        }
+        buf.push_str("\nexprs:\n");
+        for (id, expr) in self.exprs.iter() {
+            writeln!(buf, "    {:?}: {:?}", id, expr).unwrap();
+        }
+        buf
+    }
 }
 
 impl Index<ExprId> for Body {
@@ -415,7 +415,7 @@ where
                     LiteralKind::IntNumber { suffix } => {
                         let known_name = suffix.and_then(|it| BuiltinInt::from_suffix(&it));
 
-                        Literal::Int(Default::default(), known_name)
+                        Literal::Int(e.int_value().unwrap_or_default(), known_name)
                     }
                     LiteralKind::FloatNumber { suffix } => {
                         let known_name = suffix.and_then(|it| BuiltinFloat::from_suffix(&it));
@@ -567,8 +567,29 @@ where
                 path.map(Pat::Path).unwrap_or(Pat::Missing)
             }
             ast::Pat::TuplePat(p) => {
-                let args = p.args().map(|p| self.collect_pat(p)).collect();
-                Pat::Tuple(args)
+                let args: Vec<_> = p.args().collect();
+                let ellipsis = args.iter().position(|it| is_rest_pat(it));
+                let args = args
+                    .into_iter()
+                    .filter(|it| !is_rest_pat(it))
+                    .map(|it| self.collect_pat(it))
+                    .collect();
+                Pat::Tuple { args, ellipsis }
+            }
+            ast::Pat::SlicePat(p) => {
+                let mut prefix = Vec::new();
+                let mut rest = None;
+                let mut suffix = Vec::new();
+                for arg in p.args() {
+                    if rest.is_none() && is_rest_pat(&arg) {
+                        rest = Some(self.collect_pat(arg));
+                    } else if rest.is_none() {
+                        prefix.push(self.collect_pat(arg));
+                    } else {
+                        suffix.push(self.collect_pat(arg));
+                    }
+                }
+                Pat::Slice { prefix, rest, suffix }
             }
             ast::Pat::PlaceholderPat(_) => Pat::Wild,
             ast::Pat::RecordPat(p) => {
@@ -600,7 +621,7 @@ where
             ast::Pat::DotDotPat(_) => Pat::Missing,
             ast::Pat::BoxPat(_) => Pat::Missing,
             ast::Pat::LiteralPat(_) => Pat::Missing,
-            ast::Pat::SlicePat(_) | ast::Pat::RangePat(_) => Pat::Missing,
+            ast::Pat::RangePat(_) => Pat::Missing,
         };
         let ptr = AstPtr::new(&pat);
         self.alloc_pat(pattern, Either::Left(ptr))
@@ -615,6 +636,19 @@ where
     }
 }
 
+/// Whether the pattern is the rest of a tuple or slice pattern, `..` or
+/// `rest @ ..`.
+fn is_rest_pat(pat: &ast::Pat) -> bool {
+    match pat {
+        ast::Pat::DotDotPat(_) => true,
+        ast::Pat::BindPat(it) => match it.pat() {
+            Some(ast::Pat::DotDotPat(_)) => true,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
 impl From<ast::BinOp> for BinaryOp {
     fn from(ast_op: ast::BinOp) -> Self {
         match ast_op {
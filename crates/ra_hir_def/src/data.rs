@@ -6,12 +6,16 @@ use hir_expand::{
     name::{name, AsName, Name},
     AstId, InFile,
 };
-use ra_syntax::ast::{self, AstNode, ImplItem, ModuleItemOwner, NameOwner, TypeAscriptionOwner};
+use ra_syntax::ast::{
+    self, AstNode, ImplItem, ModuleItemOwner, NameOwner, TypeAscriptionOwner, VisibilityOwner,
+};
 
 use crate::{
     db::DefDatabase,
+    resolver::HasResolver,
     src::HasSource,
     type_ref::{Mutability, TypeRef},
+    visibility::{RawVisibility, Visibility},
     AssocContainerId, AssocItemId, ConstId, ConstLoc, Expander, FunctionId, FunctionLoc, HasModule,
     ImplId, Intern, Lookup, ModuleId, StaticId, TraitId, TypeAliasId, TypeAliasLoc,
 };
@@ -24,11 +28,13 @@ pub struct FunctionData {
     /// True if the first param is `self`. This is relevant to decide whether this
     /// can be called as a method.
     pub has_self_param: bool,
+    pub visibility: RawVisibility,
 }
 
 impl FunctionData {
     pub(crate) fn fn_data_query(db: &impl DefDatabase, func: FunctionId) -> Arc<FunctionData> {
-        let src = func.lookup(db).source(db);
+        let loc = func.lookup(db);
+        let src = loc.source(db);
         let name = src.value.name().map(|n| n.as_name()).unwrap_or_else(Name::missing);
         let mut params = Vec::new();
         let mut has_self_param = false;
@@ -62,9 +68,23 @@ impl FunctionData {
             TypeRef::unit()
         };
 
-        let sig = FunctionData { name, params, ret_type, has_self_param };
+        let visibility = match loc.container {
+            // trait items and trait impl items are as visible as the trait itself
+            AssocContainerId::TraitId(_) => RawVisibility::Public,
+            AssocContainerId::ImplId(it) if db.impl_data(it).target_trait.is_some() => {
+                RawVisibility::Public
+            }
+            _ => RawVisibility::from_ast(db, src.as_ref().map(|it| it.visibility())),
+        };
+
+        let sig = FunctionData { name, params, ret_type, has_self_param, visibility };
         Arc::new(sig)
     }
+
+    pub(crate) fn function_visibility_query(db: &impl DefDatabase, func: FunctionId) -> Visibility {
+        let resolver = func.resolver(db);
+        db.function_data(func).visibility.resolve(db, &resolver)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -2,11 +2,12 @@
 use std::sync::Arc;
 
 use hir_expand::{db::AstDatabase, HirFileId};
+use ra_arena::map::ArenaMap;
 use ra_db::{salsa, CrateId, SourceDatabase};
 use ra_syntax::SmolStr;
 
 use crate::{
-    adt::{EnumData, StructData},
+    adt::{EnumData, StructData, VariantData},
     attr::Attrs,
     body::{scope::ExprScopes, Body, BodySourceMap},
     data::{ConstData, FunctionData, ImplData, TraitData, TypeAliasData},
@@ -14,9 +15,10 @@ use crate::{
     generics::GenericParams,
     lang_item::{LangItemTarget, LangItems},
     nameres::{raw::RawItems, CrateDefMap},
+    visibility::Visibility,
     AttrDefId, ConstId, ConstLoc, DefWithBodyId, EnumId, EnumLoc, FunctionId, FunctionLoc,
-    GenericDefId, ImplId, ImplLoc, ModuleId, StaticId, StaticLoc, StructId, StructLoc, TraitId,
-    TraitLoc, TypeAliasId, TypeAliasLoc, UnionId, UnionLoc,
+    GenericDefId, ImplId, ImplLoc, LocalStructFieldId, ModuleId, StaticId, StaticLoc, StructId,
+    StructLoc, TraitId, TraitLoc, TypeAliasId, TypeAliasLoc, UnionId, UnionLoc, VariantId,
 };
 
 #[salsa::query_group(InternDatabaseStorage)]
@@ -69,6 +71,12 @@ pub trait DefDatabase: InternDatabase + AstDatabase {
     #[salsa::invoke(FunctionData::fn_data_query)]
     fn function_data(&self, func: FunctionId) -> Arc<FunctionData>;
 
+    #[salsa::invoke(VariantData::field_visibilities_query)]
+    fn field_visibilities(&self, var: VariantId) -> Arc<ArenaMap<LocalStructFieldId, Visibility>>;
+
+    #[salsa::invoke(FunctionData::function_visibility_query)]
+    fn function_visibility(&self, def: FunctionId) -> Visibility;
+
     #[salsa::invoke(ConstData::const_data_query)]
     fn const_data(&self, konst: ConstId) -> Arc<ConstData>;
 
@@ -2,7 +2,7 @@
 
 use std::any::Any;
 
-use hir_expand::diagnostics::Diagnostic;
+use hir_expand::{diagnostics::Diagnostic, name::Name};
 use ra_db::RelativePathBuf;
 use ra_syntax::{ast, AstPtr, SyntaxNodePtr};
 
@@ -26,3 +26,24 @@ impl Diagnostic for UnresolvedModule {
         self
     }
 }
+
+/// An item defined twice in the same namespace of an `impl` block.
+#[derive(Debug)]
+pub struct DuplicateDefinition {
+    pub file: HirFileId,
+    pub decl: AstPtr<ast::Name>,
+    pub first: InFile<AstPtr<ast::Name>>,
+    pub name: Name,
+}
+
+impl Diagnostic for DuplicateDefinition {
+    fn message(&self) -> String {
+        format!("duplicate definitions with name `{}`", self.name)
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.decl.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
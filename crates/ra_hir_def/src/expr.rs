@@ -381,7 +381,11 @@ pub struct RecordFieldPat {
 pub enum Pat {
     Missing,
     Wild,
-    Tuple(Vec<PatId>),
+    Tuple {
+        args: Vec<PatId>,
+        /// The position of the `..`, like 1 for `(a, .., b)`.
+        ellipsis: Option<usize>,
+    },
     Record {
         path: Option<Path>,
         args: Vec<RecordFieldPat>,
@@ -420,7 +424,7 @@ impl Pat {
             Pat::Bind { subpat, .. } => {
                 subpat.iter().copied().for_each(f);
             }
-            Pat::Tuple(args) | Pat::TupleStruct { args, .. } => {
+            Pat::Tuple { args, .. } | Pat::TupleStruct { args, .. } => {
                 args.iter().copied().for_each(f);
             }
             Pat::Ref { pat, .. } => f(*pat),
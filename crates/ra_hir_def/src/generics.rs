@@ -11,7 +11,11 @@ use hir_expand::{
 };
 use ra_arena::{map::ArenaMap, Arena};
 use ra_db::FileId;
-use ra_syntax::ast::{self, NameOwner, TypeBoundsOwner, TypeParamsOwner};
+use ra_syntax::{
+    ast::{self, NameOwner, TypeAscriptionOwner, TypeBoundsOwner, TypeParamsOwner},
+    AstNode,
+    SyntaxKind::{DYN_TRAIT_TYPE, IMPL_TRAIT_TYPE},
+};
 
 use crate::{
     child_by_source::ChildBySource,
@@ -29,6 +33,17 @@ use crate::{
 pub struct TypeParamData {
     pub name: Name,
     pub default: Option<TypeRef>,
+    pub provenance: TypeParamProvenance,
+}
+
+/// Where a generic parameter comes from.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TypeParamProvenance {
+    TypeParamList,
+    TraitSelf,
+    /// An `impl Trait` in the type of a function parameter, which is an
+    /// anonymous type parameter bounded by the trait.
+    ArgumentImplTrait,
 }
 
 /// Data about the generic parameters of a function, struct, impl, etc.
@@ -45,10 +60,18 @@ pub struct GenericParams {
 /// associated type bindings like `Iterator<Item = u32>`.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct WherePredicate {
-    pub type_ref: TypeRef,
+    pub target: WherePredicateTarget,
     pub bound: TypeBound,
 }
 
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum WherePredicateTarget {
+    TypeRef(TypeRef),
+    /// For the bounds of anonymous type parameters, which can't be referred to
+    /// by a path.
+    TypeParam(LocalTypeParamId),
+}
+
 type SourceMap = ArenaMap<LocalTypeParamId, Either<ast::TraitDef, ast::TypeParam>>;
 
 impl GenericParams {
@@ -68,6 +91,7 @@ impl GenericParams {
             GenericDefId::FunctionId(it) => {
                 let src = it.lookup(db).source(db);
                 generics.fill(&mut sm, &src.value);
+                generics.fill_impl_trait_args(&src.value);
                 src.file_id
             }
             GenericDefId::AdtId(AdtId::StructId(it)) => {
@@ -89,8 +113,11 @@ impl GenericParams {
                 let src = it.lookup(db).source(db);
 
                 // traits get the Self type as an implicit first type parameter
-                let self_param_id =
-                    generics.types.alloc(TypeParamData { name: name![Self], default: None });
+                let self_param_id = generics.types.alloc(TypeParamData {
+                    name: name![Self],
+                    default: None,
+                    provenance: TypeParamProvenance::TraitSelf,
+                });
                 sm.insert(self_param_id, Either::Left(src.value.clone()));
                 // add super traits as bounds on Self
                 // i.e., trait Foo: Bar is equivalent to trait Foo where Self: Bar
@@ -130,10 +157,19 @@ impl GenericParams {
     }
 
     fn fill_bounds(&mut self, node: &dyn ast::TypeBoundsOwner, type_ref: TypeRef) {
+        let target = WherePredicateTarget::TypeRef(type_ref);
+        self.fill_bounds_of_target(node, target);
+    }
+
+    fn fill_bounds_of_target(
+        &mut self,
+        node: &dyn ast::TypeBoundsOwner,
+        target: WherePredicateTarget,
+    ) {
         for bound in
             node.type_bound_list().iter().flat_map(|type_bound_list| type_bound_list.bounds())
         {
-            self.add_where_predicate_from_bound(bound, type_ref.clone());
+            self.add_where_predicate_from_bound(bound, target.clone());
         }
     }
 
@@ -142,7 +178,11 @@ impl GenericParams {
             let name = type_param.name().map_or_else(Name::missing, |it| it.as_name());
             // FIXME: Use `Path::from_src`
             let default = type_param.default_type().map(TypeRef::from_ast);
-            let param = TypeParamData { name: name.clone(), default };
+            let param = TypeParamData {
+                name: name.clone(),
+                default,
+                provenance: TypeParamProvenance::TypeParamList,
+            };
             let param_id = self.types.alloc(param);
             sm.insert(param_id, Either::Right(type_param.clone()));
 
@@ -157,24 +197,64 @@ impl GenericParams {
                 Some(type_ref) => type_ref,
                 None => continue,
             };
-            let type_ref = TypeRef::from_ast(type_ref);
+            let target = WherePredicateTarget::TypeRef(TypeRef::from_ast(type_ref));
             for bound in pred.type_bound_list().iter().flat_map(|l| l.bounds()) {
-                self.add_where_predicate_from_bound(bound, type_ref.clone());
+                self.add_where_predicate_from_bound(bound, target.clone());
+            }
+        }
+    }
+
+    /// Adds an anonymous type parameter for each `impl Trait` in the types of
+    /// the parameters, in the order they are written. The ones nested in the
+    /// bounds of other `impl Trait` or `dyn Trait` types are left alone.
+    fn fill_impl_trait_args(&mut self, fn_def: &ast::FnDef) {
+        let param_types = fn_def
+            .param_list()
+            .into_iter()
+            .flat_map(|it| it.params())
+            .filter_map(|it| it.ascribed_type());
+        for type_ref in param_types {
+            let root = type_ref.syntax().text_range();
+            let impl_traits = type_ref.syntax().descendants().filter_map(ast::ImplTraitType::cast);
+            for impl_trait in impl_traits {
+                let is_nested = impl_trait
+                    .syntax()
+                    .ancestors()
+                    .skip(1)
+                    .take_while(|it| it.text_range().is_subrange(&root))
+                    .any(|it| it.kind() == IMPL_TRAIT_TYPE || it.kind() == DYN_TRAIT_TYPE);
+                if is_nested {
+                    continue;
+                }
+                let param = TypeParamData {
+                    name: impl_trait.as_name(),
+                    default: None,
+                    provenance: TypeParamProvenance::ArgumentImplTrait,
+                };
+                let param_id = self.types.alloc(param);
+                self.fill_bounds_of_target(&impl_trait, WherePredicateTarget::TypeParam(param_id));
             }
         }
     }
 
-    fn add_where_predicate_from_bound(&mut self, bound: ast::TypeBound, type_ref: TypeRef) {
+    fn add_where_predicate_from_bound(
+        &mut self,
+        bound: ast::TypeBound,
+        target: WherePredicateTarget,
+    ) {
         if bound.has_question_mark() {
             // FIXME: remove this bound
             return;
         }
         let bound = TypeBound::from_ast(bound);
-        self.where_predicates.push(WherePredicate { type_ref, bound });
+        self.where_predicates.push(WherePredicate { target, bound });
     }
 
     pub fn find_by_name(&self, name: &Name) -> Option<LocalTypeParamId> {
-        self.types.iter().find_map(|(id, p)| if &p.name == name { Some(id) } else { None })
+        self.types
+            .iter()
+            .filter(|(_, p)| p.provenance != TypeParamProvenance::ArgumentImplTrait)
+            .find_map(|(id, p)| if &p.name == name { Some(id) } else { None })
     }
 }
 
@@ -3,9 +3,12 @@
 
 use hir_expand::name::Name;
 use once_cell::sync::Lazy;
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 
-use crate::{per_ns::PerNs, BuiltinType, ImplId, MacroDefId, ModuleDefId, TraitId};
+use crate::{
+    nameres::raw, per_ns::PerNs, visibility::Visibility, BuiltinType, ImplId, LocalModuleId,
+    MacroDefId, ModuleDefId, TraitId,
+};
 
 #[derive(Debug, Default, PartialEq, Eq)]
 pub struct ItemScope {
@@ -24,13 +27,44 @@ pub struct ItemScope {
     // FIXME: Macro shadowing in one module is not properly handled. Non-item place macros will
     // be all resolved to the last one defined if shadowing happens.
     legacy_macros: FxHashMap<Name, MacroDefId>,
+    /// Names which are brought into scope by several glob imports referring to
+    /// different items. Importing such a name is fine, using it is an error.
+    ambiguous_types: FxHashSet<Name>,
+    ambiguous_values: FxHashSet<Name>,
+    ambiguous_macros: FxHashSet<Name>,
+    /// Traits imported with `use Trait as _;`, which are in scope for method
+    /// resolution but have no name.
+    unnamed_trait_imports: FxHashSet<TraitId>,
+}
+
+/// How a resolution is being added to a scope.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum ImportType {
+    /// A definition or an explicit import. These shadow glob imports.
+    Named,
+    /// An item brought into scope by the given glob import.
+    Glob(raw::Import),
+}
+
+/// Tracks, per namespace, which names of a module come from a glob import and
+/// from which one. Only needed while collecting the `CrateDefMap`.
+#[derive(Debug, Default)]
+pub(crate) struct PerNsGlobImports {
+    types: FxHashMap<(LocalModuleId, Name), raw::Import>,
+    values: FxHashMap<(LocalModuleId, Name), raw::Import>,
+    macros: FxHashMap<(LocalModuleId, Name), raw::Import>,
 }
 
 static BUILTIN_SCOPE: Lazy<FxHashMap<Name, Resolution>> = Lazy::new(|| {
     BuiltinType::ALL
         .iter()
         .map(|(name, ty)| {
-            (name.clone(), Resolution { def: PerNs::types(ty.clone().into()), import: false })
+            let res = Resolution {
+                def: PerNs::types(ty.clone().into()),
+                import: false,
+                visibility: Visibility::Public,
+            };
+            (name.clone(), res)
         })
         .collect()
 });
@@ -94,10 +128,13 @@ impl ItemScope {
     }
 
     pub(crate) fn traits<'a>(&'a self) -> impl Iterator<Item = TraitId> + 'a {
-        self.items.values().filter_map(|r| match r.def.take_types() {
-            Some(ModuleDefId::TraitId(t)) => Some(t),
-            _ => None,
-        })
+        self.items
+            .values()
+            .filter_map(|r| match r.def.take_types() {
+                Some(ModuleDefId::TraitId(t)) => Some(t),
+                _ => None,
+            })
+            .chain(self.unnamed_trait_imports.iter().copied())
     }
 
     pub(crate) fn get_legacy_macro(&self, name: &Name) -> Option<MacroDefId> {
@@ -108,30 +145,86 @@ impl ItemScope {
         self.impls.push(imp)
     }
 
+    pub(crate) fn define_unnamed_trait_import(&mut self, tr: TraitId) {
+        self.unnamed_trait_imports.insert(tr);
+    }
+
     pub(crate) fn define_legacy_macro(&mut self, name: Name, mac: MacroDefId) {
         self.legacy_macros.insert(name, mac);
     }
 
-    pub(crate) fn push_res(&mut self, name: Name, res: &Resolution, import: bool) -> bool {
-        let mut changed = false;
-        let existing = self.items.entry(name.clone()).or_default();
+    /// Checks whether a value named `name` is brought into scope by several
+    /// conflicting glob imports.
+    pub fn is_value_ambiguous(&self, name: &Name) -> bool {
+        self.ambiguous_values.contains(name)
+    }
 
-        if existing.def.types.is_none() && res.def.types.is_some() {
-            existing.def.types = res.def.types;
-            existing.import = import || res.import;
-            changed = true;
-        }
-        if existing.def.values.is_none() && res.def.values.is_some() {
-            existing.def.values = res.def.values;
-            existing.import = import || res.import;
-            changed = true;
-        }
-        if existing.def.macros.is_none() && res.def.macros.is_some() {
-            existing.def.macros = res.def.macros;
-            existing.import = import || res.import;
-            changed = true;
+    pub fn is_type_ambiguous(&self, name: &Name) -> bool {
+        self.ambiguous_types.contains(name)
+    }
+
+    /// Adds `res` to the scope of module `lookup.0`, following the shadowing
+    /// rules: the first definition or explicit import wins, and those always
+    /// take precedence over glob imported names.
+    pub(crate) fn push_res(
+        &mut self,
+        glob_imports: &mut PerNsGlobImports,
+        lookup: (LocalModuleId, Name),
+        res: &Resolution,
+        import: bool,
+        import_type: ImportType,
+    ) -> bool {
+        let mut changed = false;
+        let existing = self.items.entry(lookup.1.clone()).or_insert_with(|| Resolution {
+            def: PerNs::none(),
+            import: false,
+            visibility: res.visibility,
+        });
+
+        macro_rules! check_changed {
+            ($field:ident, $ambiguous:ident) => {
+                if let Some(new) = res.def.$field {
+                    let glob = glob_imports.$field.get(&lookup).copied();
+                    match (existing.def.$field, glob, import_type) {
+                        (None, _, _) => {
+                            existing.def.$field = Some(new);
+                            existing.import = import || res.import;
+                            existing.visibility = res.visibility;
+                            if let ImportType::Glob(it) = import_type {
+                                glob_imports.$field.insert(lookup.clone(), it);
+                            }
+                            changed = true;
+                        }
+                        // An explicit name shadows a glob imported one.
+                        (Some(_), Some(_), ImportType::Named) => {
+                            existing.def.$field = Some(new);
+                            existing.import = import || res.import;
+                            existing.visibility = res.visibility;
+                            glob_imports.$field.remove(&lookup);
+                            self.$ambiguous.remove(&lookup.1);
+                            changed = true;
+                        }
+                        (Some(old), Some(source), ImportType::Glob(it)) if old != new => {
+                            if source == it {
+                                // The glob imported module changed its mind,
+                                // because the name got shadowed there.
+                                existing.def.$field = Some(new);
+                                existing.visibility = res.visibility;
+                                changed = true;
+                            } else {
+                                self.$ambiguous.insert(lookup.1.clone());
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            };
         }
 
+        check_changed!(types, ambiguous_types);
+        check_changed!(values, ambiguous_values);
+        check_changed!(macros, ambiguous_macros);
+
         if existing.def.is_none() && res.def.is_none() && !existing.import && res.import {
             existing.import = res.import;
         }
@@ -147,9 +240,14 @@ impl ItemScope {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Resolution {
     /// None for unresolved
     pub def: PerNs,
     pub(crate) import: bool,
+    // FIXME: this should be tracked per namespace, `use foo::bar` may import a
+    // private function and a public module at the same time.
+    /// Who can see this name from the outside, i.e. through a path or a glob
+    /// import.
+    pub visibility: Visibility,
 }
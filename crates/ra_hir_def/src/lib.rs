@@ -16,6 +16,7 @@ pub mod builtin_type;
 pub mod diagnostics;
 pub mod per_ns;
 pub mod item_scope;
+pub mod visibility;
 
 pub mod dyn_map;
 pub mod keys;
@@ -73,6 +73,7 @@ use crate::{
     nameres::{diagnostics::DefDiagnostic, path_resolution::ResolveMode},
     path::ModPath,
     per_ns::PerNs,
+    visibility::{RawVisibility, Visibility},
     AstId, LocalModuleId, ModuleDefId, ModuleId,
 };
 
@@ -225,6 +226,31 @@ impl CrateDefMap {
             self.resolve_path_fp_with_macro(db, ResolveMode::Other, original_module, path, shadow);
         (res.resolved_def, res.segment_index)
     }
+
+    /// Resolves `visibility` relative to `original_module`. Unlike
+    /// `RawVisibility::resolve`, this works while the def map is still being
+    /// collected. Returns `None` if the path doesn't resolve to a module.
+    pub(crate) fn resolve_visibility(
+        &self,
+        db: &impl DefDatabase,
+        original_module: LocalModuleId,
+        visibility: &RawVisibility,
+    ) -> Option<Visibility> {
+        match visibility {
+            RawVisibility::Module(path) => {
+                let (res, remaining) =
+                    self.resolve_path(db, original_module, path, BuiltinShadowMode::Module);
+                if remaining.is_some() {
+                    return None;
+                }
+                match res.take_types()? {
+                    ModuleDefId::ModuleId(m) => Some(Visibility::Module(m)),
+                    _ => None,
+                }
+            }
+            RawVisibility::Public => Some(Visibility::Public),
+        }
+    }
 }
 
 impl ModuleData {
@@ -18,13 +18,14 @@ use test_utils::tested_by;
 use crate::{
     attr::Attrs,
     db::DefDatabase,
-    item_scope::Resolution,
+    item_scope::{ImportType, ItemScope, PerNsGlobImports, Resolution},
     nameres::{
         diagnostics::DefDiagnostic, mod_resolution::ModDir, path_resolution::ReachedFixedPoint,
         raw, BuiltinShadowMode, CrateDefMap, ModuleData, ModuleOrigin, ResolveMode,
     },
-    path::{ModPath, PathKind},
+    path::{ImportAlias, ModPath, PathKind},
     per_ns::PerNs,
+    visibility::{RawVisibility, Visibility},
     AdtId, AstId, ConstLoc, ContainerId, EnumLoc, EnumVariantId, FunctionLoc, ImplLoc, Intern,
     LocalModuleId, ModuleDefId, ModuleId, StaticLoc, StructLoc, TraitLoc, TypeAliasLoc, UnionLoc,
 };
@@ -57,6 +58,7 @@ pub(super) fn collect_defs(db: &impl DefDatabase, mut def_map: CrateDefMap) -> C
         db,
         def_map,
         glob_imports: FxHashMap::default(),
+        from_glob_import: Default::default(),
         unresolved_imports: Vec::new(),
         resolved_imports: Vec::new(),
 
@@ -109,7 +111,8 @@ struct MacroDirective {
 struct DefCollector<'a, DB> {
     db: &'a DB,
     def_map: CrateDefMap,
-    glob_imports: FxHashMap<LocalModuleId, Vec<(LocalModuleId, raw::Import)>>,
+    glob_imports: FxHashMap<LocalModuleId, Vec<(LocalModuleId, raw::Import, Visibility)>>,
+    from_glob_import: PerNsGlobImports,
     unresolved_imports: Vec<ImportDirective>,
     resolved_imports: Vec<ImportDirective>,
     unexpanded_macros: Vec<MacroDirective>,
@@ -217,8 +220,15 @@ where
         if export {
             self.update(
                 self.def_map.root,
-                None,
-                &[(name, Resolution { def: PerNs::macros(macro_), import: false })],
+                &[(
+                    name,
+                    Resolution {
+                        def: PerNs::macros(macro_),
+                        import: false,
+                        visibility: Visibility::Public,
+                    },
+                )],
+                ImportType::Named,
             );
         }
     }
@@ -356,6 +366,7 @@ where
         let import_id = directive.import_id;
         let import = &directive.import;
         let def = directive.status.namespaces();
+        let vis = self.resolve_visibility(module_id, &import.visibility);
 
         if import.is_glob {
             log::debug!("glob import: {:?}", import);
@@ -371,9 +382,9 @@ where
                         let scope = &item_map[m.local_id].scope;
 
                         // Module scoped macros is included
-                        let items = scope.collect_resolutions();
+                        let items = self.visible_resolutions(scope, module_id, vis);
 
-                        self.update(module_id, Some(import_id), &items);
+                        self.update(module_id, &items, ImportType::Glob(import_id));
                     } else {
                         // glob import from same crate => we do an initial
                         // import, and then need to propagate any further
@@ -381,13 +392,13 @@ where
                         let scope = &self.def_map[m.local_id].scope;
 
                         // Module scoped macros is included
-                        let items = scope.collect_resolutions();
+                        let items = self.visible_resolutions(scope, module_id, vis);
 
-                        self.update(module_id, Some(import_id), &items);
+                        self.update(module_id, &items, ImportType::Glob(import_id));
                         // record the glob import in case we add further items
                         let glob = self.glob_imports.entry(m.local_id).or_default();
-                        if !glob.iter().any(|it| *it == (module_id, import_id)) {
-                            glob.push((module_id, import_id));
+                        if !glob.iter().any(|(m, i, _)| (*m, *i) == (module_id, import_id)) {
+                            glob.push((module_id, import_id, vis));
                         }
                     }
                 }
@@ -404,11 +415,12 @@ where
                             let res = Resolution {
                                 def: PerNs::both(variant.into(), variant.into()),
                                 import: true,
+                                visibility: vis,
                             };
                             (name, res)
                         })
                         .collect::<Vec<_>>();
-                    self.update(module_id, Some(import_id), &resolutions);
+                    self.update(module_id, &resolutions, ImportType::Glob(import_id));
                 }
                 Some(d) => {
                     log::debug!("glob import {:?} from non-module/enum {:?}", import, d);
@@ -420,7 +432,20 @@ where
         } else {
             match import.path.segments.last() {
                 Some(last_segment) => {
-                    let name = import.alias.clone().unwrap_or_else(|| last_segment.clone());
+                    let name = match &import.alias {
+                        Some(ImportAlias::Alias(name)) => name.clone(),
+                        Some(ImportAlias::Underscore) => {
+                            // `use Trait as _;` brings the trait in scope for
+                            // method resolution only
+                            if let Some(ModuleDefId::TraitId(it)) = def.take_types() {
+                                self.def_map.modules[module_id]
+                                    .scope
+                                    .define_unnamed_trait_import(it);
+                            }
+                            return;
+                        }
+                        None => last_segment.clone(),
+                    };
                     log::debug!("resolved import {:?} ({:?}) to {:?}", name, import, def);
 
                     // extern crates in the crate root are special-cased to insert entries into the extern prelude: rust-lang/rust#54658
@@ -430,28 +455,51 @@ where
                         }
                     }
 
-                    let resolution = Resolution { def, import: true };
-                    self.update(module_id, Some(import_id), &[(name, resolution)]);
+                    let resolution = Resolution { def, import: true, visibility: vis };
+                    self.update(module_id, &[(name, resolution)], ImportType::Named);
                 }
                 None => tested_by!(bogus_paths),
             }
         }
     }
 
+    /// Resolves the visibility of an item or import in `module_id`, falling
+    /// back to public visibility (i.e. failing open) if the path can't be
+    /// resolved.
+    fn resolve_visibility(&self, module_id: LocalModuleId, vis: &RawVisibility) -> Visibility {
+        self.def_map.resolve_visibility(self.db, module_id, vis).unwrap_or(Visibility::Public)
+    }
+
+    /// The names of `scope` which a glob import in `module_id` brings in, with
+    /// the visibility of the glob import.
+    fn visible_resolutions(
+        &self,
+        scope: &ItemScope,
+        module_id: LocalModuleId,
+        vis: Visibility,
+    ) -> Vec<(Name, Resolution)> {
+        scope
+            .collect_resolutions()
+            .into_iter()
+            .filter(|(_, res)| res.visibility.is_visible_from_def_map(&self.def_map, module_id))
+            .map(|(name, res)| (name, Resolution { visibility: vis, ..res }))
+            .collect()
+    }
+
     fn update(
         &mut self,
         module_id: LocalModuleId,
-        import: Option<raw::Import>,
         resolutions: &[(Name, Resolution)],
+        import_type: ImportType,
     ) {
-        self.update_recursive(module_id, import, resolutions, 0)
+        self.update_recursive(module_id, resolutions, import_type, 0)
     }
 
     fn update_recursive(
         &mut self,
         module_id: LocalModuleId,
-        import: Option<raw::Import>,
         resolutions: &[(Name, Resolution)],
+        import_type: ImportType,
         depth: usize,
     ) {
         if depth > 100 {
@@ -461,7 +509,13 @@ where
         let scope = &mut self.def_map.modules[module_id].scope;
         let mut changed = false;
         for (name, res) in resolutions {
-            changed |= scope.push_res(name.clone(), res, import.is_some());
+            changed |= scope.push_res(
+                &mut self.from_glob_import,
+                (module_id, name.clone()),
+                res,
+                import_type != ImportType::Named,
+                import_type,
+            );
         }
 
         if !changed {
@@ -474,9 +528,21 @@ where
             .flat_map(|v| v.iter())
             .cloned()
             .collect::<Vec<_>>();
-        for (glob_importing_module, glob_import) in glob_imports {
+        for (glob_importing_module, glob_import, vis) in glob_imports {
+            let resolutions = resolutions
+                .iter()
+                .filter(|(_, res)| {
+                    res.visibility.is_visible_from_def_map(&self.def_map, glob_importing_module)
+                })
+                .map(|(name, res)| (name.clone(), Resolution { visibility: vis, ..res.clone() }))
+                .collect::<Vec<_>>();
             // We pass the glob import so that the tracked import in those modules is that glob import
-            self.update_recursive(glob_importing_module, Some(glob_import), resolutions, depth + 1);
+            self.update_recursive(
+                glob_importing_module,
+                &resolutions,
+                ImportType::Glob(glob_import),
+                depth + 1,
+            );
         }
     }
 
@@ -592,26 +658,28 @@ where
             }
         }
 
+        let cfg_options = self.def_collector.cfg_options;
+        let attrs: Vec<Attrs> =
+            items.iter().map(|item| item.attrs.expand_cfg_attr(cfg_options)).collect();
+
         // This should be processed eagerly instead of deferred to resolving.
         // `#[macro_use] extern crate` is hoisted to imports macros before collecting
         // any other items.
-        for item in items {
-            if self.is_cfg_enabled(&item.attrs) {
+        for (item, attrs) in items.iter().zip(&attrs) {
+            if self.is_cfg_enabled(attrs) {
                 if let raw::RawItemKind::Import(import_id) = item.kind {
                     let import = self.raw_items[import_id].clone();
-                    if import.is_extern_crate && import.is_macro_use {
+                    if import.is_extern_crate && attrs.by_key("macro_use").exists() {
                         self.def_collector.import_macros_from_extern_crate(self.module_id, &import);
                     }
                 }
             }
         }
 
-        for item in items {
-            if self.is_cfg_enabled(&item.attrs) {
+        for (item, attrs) in items.iter().zip(&attrs) {
+            if self.is_cfg_enabled(attrs) {
                 match item.kind {
-                    raw::RawItemKind::Module(m) => {
-                        self.collect_module(&self.raw_items[m], &item.attrs)
-                    }
+                    raw::RawItemKind::Module(m) => self.collect_module(&self.raw_items[m], attrs),
                     raw::RawItemKind::Import(import_id) => {
                         self.def_collector.unresolved_imports.push(ImportDirective {
                             module_id: self.module_id,
@@ -620,9 +688,7 @@ where
                             status: PartialResolvedImport::Unresolved,
                         })
                     }
-                    raw::RawItemKind::Def(def) => {
-                        self.define_def(&self.raw_items[def], &item.attrs)
-                    }
+                    raw::RawItemKind::Def(def) => self.define_def(&self.raw_items[def], attrs),
                     raw::RawItemKind::Macro(mac) => self.collect_macro(&self.raw_items[mac]),
                     raw::RawItemKind::Impl(imp) => {
                         let module = ModuleId {
@@ -648,9 +714,13 @@ where
         let is_macro_use = attrs.by_key("macro_use").exists();
         match module {
             // inline module, just recurse
-            raw::ModuleData::Definition { name, items, ast_id } => {
-                let module_id =
-                    self.push_child_module(name.clone(), AstId::new(self.file_id, *ast_id), None);
+            raw::ModuleData::Definition { name, visibility, items, ast_id } => {
+                let module_id = self.push_child_module(
+                    name.clone(),
+                    visibility,
+                    AstId::new(self.file_id, *ast_id),
+                    None,
+                );
 
                 ModCollector {
                     def_collector: &mut *self.def_collector,
@@ -665,7 +735,7 @@ where
                 }
             }
             // out of line module, resolve, parse and recurse
-            raw::ModuleData::Declaration { name, ast_id } => {
+            raw::ModuleData::Declaration { name, visibility, ast_id } => {
                 let ast_id = AstId::new(self.file_id, *ast_id);
                 match self.mod_dir.resolve_declaration(
                     self.def_collector.db,
@@ -674,7 +744,8 @@ where
                     path_attr,
                 ) {
                     Ok((file_id, mod_dir)) => {
-                        let module_id = self.push_child_module(name.clone(), ast_id, Some(file_id));
+                        let module_id =
+                            self.push_child_module(name.clone(), visibility, ast_id, Some(file_id));
                         let raw_items = self.def_collector.db.raw_items(file_id.into());
                         ModCollector {
                             def_collector: &mut *self.def_collector,
@@ -703,9 +774,11 @@ where
     fn push_child_module(
         &mut self,
         name: Name,
+        visibility: &RawVisibility,
         declaration: AstId<ast::Module>,
         definition: Option<FileId>,
     ) -> LocalModuleId {
+        let vis = self.def_collector.resolve_visibility(self.module_id, visibility);
         let modules = &mut self.def_collector.def_map.modules;
         let res = modules.alloc(ModuleData::default());
         modules[res].parent = Some(self.module_id);
@@ -719,8 +792,9 @@ where
                 ModuleId { krate: self.def_collector.def_map.krate, local_id: res }.into(),
             ),
             import: false,
+            visibility: vis,
         };
-        self.def_collector.update(self.module_id, None, &[(name, resolution)]);
+        self.def_collector.update(self.module_id, &[(name, resolution)], ImportType::Named);
         res
     }
 
@@ -733,6 +807,7 @@ where
         self.collect_derives(attrs, def);
 
         let name = def.name.clone();
+        let vis = self.def_collector.resolve_visibility(self.module_id, &def.visibility);
         let container = ContainerId::ModuleId(module);
         let def: PerNs = match def.kind {
             raw::DefKind::Function(ast_id) => {
@@ -790,8 +865,8 @@ where
                 PerNs::types(def.into())
             }
         };
-        let resolution = Resolution { def, import: false };
-        self.def_collector.update(self.module_id, None, &[(name, resolution)])
+        let resolution = Resolution { def, import: false, visibility: vis };
+        self.def_collector.update(self.module_id, &[(name, resolution)], ImportType::Named)
     }
 
     fn collect_derives(&mut self, attrs: &Attrs, def: &raw::DefData) {
@@ -882,8 +957,8 @@ where
         }
     }
 
+    /// Expects `cfg_attr`s in `attrs` to be expanded already.
     fn is_cfg_enabled(&self, attrs: &Attrs) -> bool {
-        // FIXME: handle cfg_attr :-)
         attrs
             .by_key("cfg")
             .tt_values()
@@ -908,6 +983,7 @@ mod tests {
             db,
             def_map,
             glob_imports: FxHashMap::default(),
+            from_glob_import: Default::default(),
             unresolved_imports: Vec::new(),
             resolved_imports: Vec::new(),
             unexpanded_macros: Vec::new(),
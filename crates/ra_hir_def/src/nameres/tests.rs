@@ -104,6 +104,56 @@ fn crate_def_map_smoke_test() {
     "###)
 }
 
+#[test]
+fn extern_block_items() {
+    let map = def_map(
+        r#"
+        //- /lib.rs
+        extern "C" {
+            fn foo(x: i32);
+            static BAR: u32;
+        }
+        mod m {
+            extern { pub fn baz(); }
+        }
+        "#,
+    );
+    assert_snapshot!(map, @r###"
+        ⋮crate
+        ⋮BAR: v
+        ⋮foo: v
+        ⋮m: t
+        ⋮
+        ⋮crate::m
+        ⋮baz: v
+    "###)
+}
+
+#[test]
+fn underscore_and_renamed_imports() {
+    let map = def_map(
+        "
+        //- /lib.rs
+        mod m {
+            pub trait Tr {}
+            pub struct S;
+        }
+        use m::Tr as _;
+        use m::S as T;
+        extern crate self as _;
+        ",
+    );
+    assert_snapshot!(map, @r###"
+        ⋮crate
+        ⋮T: t v
+        ⋮m: t
+        ⋮
+        ⋮crate::m
+        ⋮S: t v
+        ⋮Tr: t
+    "###)
+}
+
 #[test]
 fn bogus_paths() {
     covers!(bogus_paths);
@@ -295,6 +345,90 @@ fn edition_2015_imports() {
     "###);
 }
 
+#[test]
+fn edition_2018_imports() {
+    let map = def_map(
+        "
+        //- /main.rs crate:main deps:other_crate,my-lib edition:2018
+        mod foo;
+        mod my_lib {
+            pub struct Local;
+        }
+
+        //- /foo.rs
+        use other_crate::FromLib;
+        use my_lib::FromMyLib;
+        use crate::my_lib::Local;
+
+        //- /lib.rs crate:other_crate edition:2018
+        pub struct FromLib;
+
+        //- /my_lib.rs crate:my-lib edition:2018
+        pub struct FromMyLib;
+        ",
+    );
+
+    assert_snapshot!(map, @r###"
+        ⋮crate
+        ⋮foo: t
+        ⋮my_lib: t
+        ⋮
+        ⋮crate::foo
+        ⋮FromLib: t v
+        ⋮FromMyLib: t v
+        ⋮Local: t v
+        ⋮
+        ⋮crate::my_lib
+        ⋮Local: t v
+    "###);
+}
+
+#[test]
+fn extern_crate_with_dashes_in_name() {
+    let map = def_map(
+        "
+        //- /main.rs crate:main deps:my-lib
+        mod foo;
+
+        //- /foo.rs
+        use my_lib::FromMyLib;
+
+        //- /lib.rs crate:my-lib
+        pub struct FromMyLib;
+        ",
+    );
+
+    assert_snapshot!(map, @r###"
+        ⋮crate
+        ⋮foo: t
+        ⋮
+        ⋮crate::foo
+        ⋮FromMyLib: t v
+    "###);
+}
+
+#[test]
+fn edition_2015_keywords() {
+    let map = def_map(
+        "
+        //- /main.rs crate:main edition:2015
+        mod async {
+            pub struct dyn;
+        }
+        use async::dyn;
+        ",
+    );
+
+    assert_snapshot!(map, @r###"
+        ⋮crate
+        ⋮async: t
+        ⋮dyn: t v
+        ⋮
+        ⋮crate::async
+        ⋮dyn: t v
+    "###);
+}
+
 #[test]
 fn item_map_using_self() {
     let map = def_map(
@@ -554,6 +688,35 @@ fn cfg_test() {
     "###);
 }
 
+#[test]
+fn cfg_attr_test() {
+    let map = def_map(
+        r#"
+        //- /main.rs crate:main cfg:feature=foo
+        #[cfg_attr(feature = "foo", cfg(test))]
+        pub struct NotTest;
+        #[cfg_attr(feature = "bar", cfg(test))]
+        pub struct Bar;
+        #[cfg_attr(feature = "foo", cfg_attr(not(test), cfg(test)))]
+        pub struct Nested;
+        #[cfg_attr(feature = "foo", path = "baz.rs")]
+        mod foo;
+
+        //- /baz.rs
+        pub struct Baz;
+        "#,
+    );
+
+    assert_snapshot!(map, @r###"
+        ⋮crate
+        ⋮Bar: t v
+        ⋮foo: t
+        ⋮
+        ⋮crate::foo
+        ⋮Baz: t v
+    "###);
+}
+
 #[test]
 fn infer_multiple_namespace() {
     let map = def_map(
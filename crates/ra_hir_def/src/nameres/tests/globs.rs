@@ -73,6 +73,45 @@ fn glob_2() {
     );
 }
 
+#[test]
+fn glob_privacy() {
+    let map = def_map(
+        "
+        //- /lib.rs
+        mod foo;
+        use foo::*;
+
+        //- /foo/mod.rs
+        pub mod bar;
+        pub use self::bar::*;
+        struct PrivateStructFoo;
+
+        //- /foo/bar.rs
+        pub struct Baz;
+        struct PrivateStructBar;
+        pub use super::*;
+        ",
+    );
+    assert_snapshot!(map, @r###"
+   ⋮crate
+   ⋮Baz: t v
+   ⋮bar: t
+   ⋮foo: t
+   ⋮
+   ⋮crate::foo
+   ⋮Baz: t v
+   ⋮PrivateStructFoo: t v
+   ⋮bar: t
+   ⋮
+   ⋮crate::foo::bar
+   ⋮Baz: t v
+   ⋮PrivateStructBar: t v
+   ⋮PrivateStructFoo: t v
+   ⋮bar: t
+    "###
+    );
+}
+
 #[test]
 fn glob_across_crates() {
     covers!(glob_across_crates);
@@ -133,3 +172,90 @@ fn glob_enum_group() {
     "###
     );
 }
+
+#[test]
+fn explicit_import_shadows_glob_import() {
+    let map = def_map(
+        "
+        //- /lib.rs
+        mod foo;
+        use foo::*;
+        use foo::bar::Baz;
+        use self::Baz::Inner;
+
+        //- /foo/mod.rs
+        pub mod bar;
+        pub struct Baz;
+
+        //- /foo/bar.rs
+        pub mod Baz {
+            pub struct Inner;
+        }
+        ",
+    );
+    assert_snapshot!(map, @r###"
+   ⋮crate
+   ⋮Baz: t v
+   ⋮Inner: t v
+   ⋮bar: t
+   ⋮foo: t
+   ⋮
+   ⋮crate::foo
+   ⋮Baz: t v
+   ⋮bar: t
+   ⋮
+   ⋮crate::foo::bar
+   ⋮Baz: t
+   ⋮
+   ⋮crate::foo::bar::Baz
+   ⋮Inner: t v
+    "###
+    );
+}
+
+#[test]
+fn glob_ambiguity_does_not_break_other_names() {
+    let map = compute_crate_def_map(
+        "
+        //- /lib.rs
+        mod foo;
+        use foo::*;
+        use foo::bar::*;
+
+        //- /foo/mod.rs
+        pub mod bar;
+        pub struct Baz;
+        pub struct Foo;
+
+        //- /foo/bar.rs
+        pub struct Baz;
+        pub struct Bar;
+        ",
+    );
+    assert_snapshot!(render_crate_def_map(&map), @r###"
+   ⋮crate
+   ⋮Bar: t v
+   ⋮Baz: t v
+   ⋮Foo: t v
+   ⋮bar: t
+   ⋮foo: t
+   ⋮
+   ⋮crate::foo
+   ⋮Baz: t v
+   ⋮Foo: t v
+   ⋮bar: t
+   ⋮
+   ⋮crate::foo::bar
+   ⋮Bar: t v
+   ⋮Baz: t v
+    "###
+    );
+    let scope = &map[map.root].scope;
+    let is_ambiguous = |name: &str| {
+        let (name, _) = scope.entries().find(|(it, _)| it.to_string() == name).unwrap();
+        scope.is_value_ambiguous(name) && scope.is_type_ambiguous(name)
+    };
+    assert!(is_ambiguous("Baz"));
+    assert!(!is_ambiguous("Foo"));
+    assert!(!is_ambiguous("Bar"));
+}
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use ra_db::SourceDatabaseExt;
+use ra_db::{CrateGraph, Edition, SourceDatabase, SourceDatabaseExt};
 
 use super::*;
 
@@ -131,3 +131,27 @@ fn typing_inside_a_macro_should_not_invalidate_def_map() {
         assert!(!format!("{:?}", events).contains("crate_def_map"), "{:#?}", events)
     }
 }
+
+#[test]
+fn changing_the_crate_graph_should_only_reparse_files_on_edition_changes() {
+    let (mut db, file_id) = TestDB::with_single_file("fn foo() { let async = 1; }");
+    let crate_graph_with_edition = |edition| {
+        let mut crate_graph = CrateGraph::default();
+        crate_graph.add_crate_root(file_id, edition, Default::default(), Default::default());
+        Arc::new(crate_graph)
+    };
+    db.set_crate_graph(crate_graph_with_edition(Edition::Edition2015));
+    assert!(db.parse(file_id).errors().is_empty());
+
+    db.set_crate_graph(crate_graph_with_edition(Edition::Edition2015));
+    let events = db.log_executed(|| {
+        db.parse(file_id);
+    });
+    assert!(!format!("{:?}", events).contains("parse_with_edition"), "{:#?}", events);
+
+    db.set_crate_graph(crate_graph_with_edition(Edition::Edition2018));
+    let events = db.log_executed(|| {
+        assert!(!db.parse(file_id).errors().is_empty());
+    });
+    assert!(format!("{:?}", events).contains("parse_with_edition"), "{:#?}", events);
+}
@@ -102,6 +102,32 @@ fn macro_rules_from_other_crates_are_visible() {
     "###);
 }
 
+#[test]
+fn macro_rules_exported_from_private_modules_are_visible_at_crate_root() {
+    let map = def_map(
+        "
+        //- /main.rs crate:main deps:foo
+        foo::structs!(Foo);
+        use foo::structs;
+        structs!(Bar);
+
+        //- /lib.rs crate:foo
+        mod priv_mod {
+            #[macro_export]
+            macro_rules! structs {
+                ($i:ident) => { struct $i; }
+            }
+        }
+        ",
+    );
+    assert_snapshot!(map, @r###"
+   ⋮crate
+   ⋮Bar: t v
+   ⋮Foo: t v
+   ⋮structs: m
+    "###);
+}
+
 #[test]
 fn macro_rules_export_with_local_inner_macros_are_visible() {
     let map = def_map(
@@ -276,6 +302,30 @@ fn prelude_is_macro_use() {
     "###);
 }
 
+#[test]
+fn macro_use_under_cfg_attr() {
+    let map = def_map(
+        r#"
+        //- /main.rs crate:main deps:foo cfg:feature=foo
+        #[cfg_attr(feature = "foo", macro_use)]
+        extern crate foo;
+
+        structs!(Foo);
+
+        //- /lib.rs crate:foo
+        #[macro_export]
+        macro_rules! structs {
+            ($i:ident) => { struct $i; }
+        }
+        "#,
+    );
+    assert_snapshot!(map, @r###"
+   ⋮crate
+   ⋮Foo: t v
+   ⋮foo: t
+    "###);
+}
+
 #[test]
 fn prelude_cycle() {
     let map = def_map(
@@ -8,7 +8,7 @@ use hir_expand::{
     name::{AsName, Name},
 };
 use ra_db::CrateId;
-use ra_syntax::ast;
+use ra_syntax::ast::{self, NameOwner};
 
 use crate::{type_ref::TypeRef, InFile};
 
@@ -18,6 +18,24 @@ pub struct ModPath {
     pub segments: Vec<Name>,
 }
 
+/// The name under which a use tree or an `extern crate` item imports its item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportAlias {
+    /// `use Trait as _;`: the trait is in scope for its methods, but there is
+    /// no name to refer to it.
+    Underscore,
+    Alias(Name),
+}
+
+impl ImportAlias {
+    pub(crate) fn from_ast(alias: ast::Alias) -> ImportAlias {
+        match alias.name() {
+            Some(name) => ImportAlias::Alias(name.as_name()),
+            None => ImportAlias::Underscore,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum PathKind {
     Plain,
@@ -56,7 +74,7 @@ impl ModPath {
     pub(crate) fn expand_use_item(
         item_src: InFile<ast::UseItem>,
         hygiene: &Hygiene,
-        mut cb: impl FnMut(ModPath, &ast::UseTree, /* is_glob */ bool, Option<Name>),
+        mut cb: impl FnMut(ModPath, &ast::UseTree, /* is_glob */ bool, Option<ImportAlias>),
     ) {
         if let Some(tree) = item_src.value.use_tree() {
             lower::lower_use_tree(None, tree, hygiene, &mut cb);
@@ -246,6 +264,7 @@ pub use hir_expand::name as __name;
 macro_rules! __known_path {
     (std::iter::IntoIterator) => {};
     (std::result::Result) => {};
+    (std::option::Option) => {};
     (std::ops::Range) => {};
     (std::ops::RangeFrom) => {};
     (std::ops::RangeFull) => {};
@@ -254,10 +273,26 @@ macro_rules! __known_path {
     (std::ops::RangeInclusive) => {};
     (std::boxed::Box) => {};
     (std::future::Future) => {};
+    (std::default::Default) => {};
+    (std::marker::Copy) => {};
+    (std::marker::Send) => {};
+    (std::marker::Sync) => {};
     (std::ops::Try) => {};
     (std::ops::Neg) => {};
     (std::ops::Not) => {};
     (std::ops::Index) => {};
+    (std::ops::Add) => {};
+    (std::ops::Sub) => {};
+    (std::ops::Mul) => {};
+    (std::ops::Div) => {};
+    (std::ops::Rem) => {};
+    (std::ops::Shl) => {};
+    (std::ops::Shr) => {};
+    (std::ops::BitXor) => {};
+    (std::ops::BitOr) => {};
+    (std::ops::BitAnd) => {};
+    (std::vec::Vec) => {};
+    (std::string::String) => {};
     ($path:path) => {
         compile_error!("Please register your known path in the path module")
     };
@@ -13,7 +13,7 @@ use crate::{
     builtin_type::BuiltinType,
     db::DefDatabase,
     expr::{ExprId, PatId},
-    generics::GenericParams,
+    generics::{GenericParams, TypeParamProvenance},
     item_scope::BuiltinShadowMode,
     nameres::CrateDefMap,
     path::{ModPath, PathKind},
@@ -325,6 +325,46 @@ impl Resolver {
         }
     }
 
+    /// Checks whether `name`, used as a value, refers to an item which is
+    /// brought into scope by several conflicting glob imports.
+    pub fn is_value_ambiguous(&self, name: &Name) -> bool {
+        for scope in self.scopes.iter().rev() {
+            match scope {
+                Scope::ExprScope(scope) => {
+                    let entries = scope.expr_scopes.entries(scope.scope_id);
+                    if entries.iter().any(|entry| entry.name() == name) {
+                        return false;
+                    }
+                }
+                Scope::ModuleScope(m) => {
+                    return m.crate_def_map[m.module_id].scope.is_value_ambiguous(name)
+                }
+                _ => continue,
+            }
+        }
+        false
+    }
+
+    /// Checks whether `name`, used as a type or as the first segment of a
+    /// path, refers to an item which is brought into scope by several
+    /// conflicting glob imports.
+    pub fn is_type_ambiguous(&self, name: &Name) -> bool {
+        for scope in self.scopes.iter().rev() {
+            match scope {
+                Scope::GenericParams { params, .. } => {
+                    if params.find_by_name(name).is_some() {
+                        return false;
+                    }
+                }
+                Scope::ModuleScope(m) => {
+                    return m.crate_def_map[m.module_id].scope.is_type_ambiguous(name)
+                }
+                _ => continue,
+            }
+        }
+        false
+    }
+
     pub fn resolve_path_as_macro(
         &self,
         db: &impl DefDatabase,
@@ -366,17 +406,19 @@ impl Resolver {
         self.module().map(|t| t.0.krate)
     }
 
+    /// The where predicates in scope, together with the definition they come
+    /// from.
     pub fn where_predicates_in_scope<'a>(
         &'a self,
-    ) -> impl Iterator<Item = &'a crate::generics::WherePredicate> + 'a {
+    ) -> impl Iterator<Item = (&'a crate::generics::WherePredicate, GenericDefId)> + 'a {
         self.scopes
             .iter()
             .rev()
             .filter_map(|scope| match scope {
-                Scope::GenericParams { params, .. } => Some(params),
+                Scope::GenericParams { params, def } => Some((params, *def)),
                 _ => None,
             })
-            .flat_map(|params| params.where_predicates.iter())
+            .flat_map(|(params, def)| params.where_predicates.iter().map(move |it| (it, def)))
     }
 
     pub fn generic_def(&self) -> Option<GenericDefId> {
@@ -431,6 +473,9 @@ impl Scope {
             }
             Scope::GenericParams { params, def } => {
                 for (local_id, param) in params.types.iter() {
+                    if param.provenance == TypeParamProvenance::ArgumentImplTrait {
+                        continue;
+                    }
                     f(
                         param.name.clone(),
                         ScopeDef::GenericParam(TypeParamId { local_id, parent: *def }),
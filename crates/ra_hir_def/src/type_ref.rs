@@ -44,7 +44,7 @@ pub enum TypeRef {
     Path(Path),
     RawPtr(Box<TypeRef>, Mutability),
     Reference(Box<TypeRef>, Mutability),
-    Array(Box<TypeRef> /*, Expr*/),
+    Array(Box<TypeRef>, ArrayLen),
     Slice(Box<TypeRef>),
     /// A fn pointer. Last element of the vector is the return type.
     Fn(Vec<TypeRef>),
@@ -54,6 +54,15 @@ pub enum TypeRef {
     Error,
 }
 
+/// The length of an array type. Only lengths which are literals or paths (to
+/// constants, hopefully) can be evaluated.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ArrayLen {
+    Literal(u64),
+    Path(Path),
+    Unknown,
+}
+
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub enum TypeBound {
     Path(Path),
@@ -80,9 +89,10 @@ impl TypeRef {
                 let mutability = Mutability::from_mutable(inner.is_mut());
                 TypeRef::RawPtr(Box::new(inner_ty), mutability)
             }
-            ast::TypeRef::ArrayType(inner) => {
-                TypeRef::Array(Box::new(TypeRef::from_ast_opt(inner.type_ref())))
-            }
+            ast::TypeRef::ArrayType(inner) => TypeRef::Array(
+                Box::new(TypeRef::from_ast_opt(inner.type_ref())),
+                ArrayLen::from_ast_opt(inner.expr()),
+            ),
             ast::TypeRef::SliceType(inner) => {
                 TypeRef::Slice(Box::new(TypeRef::from_ast_opt(inner.type_ref())))
             }
@@ -160,3 +170,20 @@ impl TypeBound {
         }
     }
 }
+
+impl ArrayLen {
+    fn from_ast_opt(node: Option<ast::Expr>) -> Self {
+        match node {
+            Some(ast::Expr::Literal(lit)) => {
+                lit.int_value().map(ArrayLen::Literal).unwrap_or(ArrayLen::Unknown)
+            }
+            Some(ast::Expr::PathExpr(path)) => path
+                .path()
+                .and_then(Path::from_ast)
+                .map(ArrayLen::Path)
+                .unwrap_or(ArrayLen::Unknown),
+            Some(ast::Expr::ParenExpr(inner)) => ArrayLen::from_ast_opt(inner.expr()),
+            _ => ArrayLen::Unknown,
+        }
+    }
+}
@@ -0,0 +1,126 @@
+//! Defines hir-level representation of visibility (e.g. `pub` and `pub(crate)`).
+
+use std::iter::successors;
+
+use hir_expand::{hygiene::Hygiene, InFile};
+use ra_syntax::ast;
+
+use crate::{
+    db::DefDatabase,
+    nameres::CrateDefMap,
+    path::{ModPath, PathKind},
+    resolver::Resolver,
+    LocalModuleId, ModuleDefId, ModuleId,
+};
+
+/// Visibility of an item, not yet resolved.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RawVisibility {
+    /// `pub(in module)`, `pub(crate)` or `pub(super)`. Also private, which is
+    /// equivalent to `pub(self)`.
+    Module(ModPath),
+    /// `pub`.
+    Public,
+}
+
+impl RawVisibility {
+    fn private() -> RawVisibility {
+        let path = ModPath { kind: PathKind::Super(0), segments: Vec::new() };
+        RawVisibility::Module(path)
+    }
+
+    pub(crate) fn from_ast(
+        db: &impl DefDatabase,
+        node: InFile<Option<ast::Visibility>>,
+    ) -> RawVisibility {
+        Self::from_ast_with_hygiene(node.value, &Hygiene::new(db, node.file_id))
+    }
+
+    pub(crate) fn from_ast_with_hygiene(
+        node: Option<ast::Visibility>,
+        hygiene: &Hygiene,
+    ) -> RawVisibility {
+        let node = match node {
+            None => return RawVisibility::private(),
+            Some(node) => node,
+        };
+        match node.kind() {
+            ast::VisibilityKind::In(path) => {
+                let path = ModPath::from_src(path, hygiene);
+                let path = match path {
+                    None => return RawVisibility::private(),
+                    Some(path) => path,
+                };
+                RawVisibility::Module(path)
+            }
+            ast::VisibilityKind::PubCrate => {
+                let path = ModPath { kind: PathKind::Crate, segments: Vec::new() };
+                RawVisibility::Module(path)
+            }
+            ast::VisibilityKind::PubSuper => {
+                let path = ModPath { kind: PathKind::Super(1), segments: Vec::new() };
+                RawVisibility::Module(path)
+            }
+            ast::VisibilityKind::PubSelf => RawVisibility::private(),
+            ast::VisibilityKind::Pub => RawVisibility::Public,
+        }
+    }
+
+    /// Resolves the visibility relative to the module `resolver` is in.
+    pub fn resolve(&self, db: &impl DefDatabase, resolver: &Resolver) -> Visibility {
+        // we fall back to public visibility (i.e. fail open) if the path can't be resolved
+        let path = match self {
+            RawVisibility::Module(path) => path,
+            RawVisibility::Public => return Visibility::Public,
+        };
+        match resolver.resolve_module_path_in_items(db, path).take_types() {
+            Some(ModuleDefId::ModuleId(m)) => Visibility::Module(m),
+            _ => Visibility::Public,
+        }
+    }
+}
+
+/// Visibility of an item, with the path resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Visibility {
+    /// Visibility is restricted to a certain module.
+    Module(ModuleId),
+    /// Visibility is unrestricted.
+    Public,
+}
+
+impl Visibility {
+    /// Whether an item with this visibility can be accessed from `from_module`.
+    pub fn is_visible_from(self, db: &impl DefDatabase, from_module: ModuleId) -> bool {
+        let to_module = match self {
+            Visibility::Module(m) => m,
+            Visibility::Public => return true,
+        };
+        // if they're not in the same crate, it can't be visible
+        if from_module.krate != to_module.krate {
+            return false;
+        }
+        let def_map = db.crate_def_map(from_module.krate);
+        self.is_visible_from_def_map(&def_map, from_module.local_id)
+    }
+
+    /// Like `is_visible_from`, but checks against a module of `def_map`, which
+    /// may still be under construction.
+    pub(crate) fn is_visible_from_def_map(
+        self,
+        def_map: &CrateDefMap,
+        from_module: LocalModuleId,
+    ) -> bool {
+        let to_module = match self {
+            Visibility::Module(m) => m,
+            Visibility::Public => return true,
+        };
+        // if they're not in the same crate, it can't be visible
+        if def_map.krate != to_module.krate {
+            return false;
+        }
+        // from_module needs to be a descendant of to_module
+        let mut ancestors = successors(Some(from_module), |it| def_map[*it].parent);
+        ancestors.any(|it| it == to_module.local_id)
+    }
+}
@@ -222,16 +222,32 @@ impl ExpansionInfo {
     }
 
     pub fn map_token_down(&self, token: InFile<&SyntaxToken>) -> Option<InFile<SyntaxToken>> {
+        self.map_token_down_all(token).into_iter().next()
+    }
+
+    /// Like `map_token_down`, but returns all the tokens the token becomes in
+    /// the expansion, as macros can repeat their arguments.
+    pub fn map_token_down_all(&self, token: InFile<&SyntaxToken>) -> Vec<InFile<SyntaxToken>> {
         assert_eq!(token.file_id, self.arg.file_id);
-        let range = token.value.text_range().checked_sub(self.arg.value.text_range().start())?;
-        let token_id = self.macro_arg.1.token_by_range(range)?;
+        let range = match token.value.text_range().checked_sub(self.arg.value.text_range().start())
+        {
+            Some(it) => it,
+            None => return Vec::new(),
+        };
+        let token_id = match self.macro_arg.1.token_by_range(range) {
+            Some(it) => it,
+            None => return Vec::new(),
+        };
         let token_id = self.macro_def.0.map_id_down(token_id);
 
-        let range = self.exp_map.range_by_token(token_id)?.by_kind(token.value.kind())?;
-
-        let token = algo::find_covering_element(&self.expanded.value, range).into_token()?;
-
-        Some(self.expanded.with_value(token))
+        self.exp_map
+            .ranges_by_token(token_id)
+            .filter_map(|it| it.by_kind(token.value.kind()))
+            .filter_map(|range| {
+                algo::find_covering_element(&self.expanded.value, range).into_token()
+            })
+            .map(|it| self.expanded.with_value(it))
+            .collect()
     }
 
     pub fn map_token_up(
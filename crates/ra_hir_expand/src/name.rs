@@ -2,7 +2,7 @@
 
 use std::fmt;
 
-use ra_syntax::{ast, SmolStr};
+use ra_syntax::{ast, AstNode, SmolStr};
 
 /// `Name` is a wrapper around string, which is used in hir for both references
 /// and declarations. In theory, names should also carry hygiene info, but we are
@@ -83,6 +83,14 @@ impl AsName for ast::Name {
     }
 }
 
+impl AsName for ast::ImplTraitType {
+    /// `impl Trait` arguments are anonymous type parameters, which are named
+    /// after the text of the type to be displayed.
+    fn as_name(&self) -> Name {
+        Name::new_text(self.syntax().text().to_string().into())
+    }
+}
+
 impl AsName for tt::Ident {
     fn as_name(&self) -> Name {
         Name::resolve(&self.text)
@@ -93,14 +101,27 @@ impl AsName for ast::FieldKind {
     fn as_name(&self) -> Name {
         match self {
             ast::FieldKind::Name(nr) => nr.as_name(),
-            ast::FieldKind::Index(idx) => Name::new_tuple_field(idx.text().parse().unwrap()),
+            ast::FieldKind::Index(idx) => {
+                // The lexer turns the `0.` of `x.0.` into a float, whose dot is
+                // the one of the next field access
+                match idx.text().trim_end_matches('.').parse() {
+                    Ok(idx) => Name::new_tuple_field(idx),
+                    Err(_) => Name::missing(),
+                }
+            }
         }
     }
 }
 
 impl AsName for ra_db::Dependency {
     fn as_name(&self) -> Name {
-        Name::new_text(self.name.clone())
+        // Cargo allows dashes in package names, but the crate is referred to
+        // with underscores in the code
+        if self.name.contains('-') {
+            Name::new_text(self.name.replace('-', "_").into())
+        } else {
+            Name::new_text(self.name.clone())
+        }
     }
 }
 
@@ -142,7 +163,12 @@ pub mod known {
         ops,
         future,
         result,
+        option,
         boxed,
+        default,
+        marker,
+        vec,
+        string,
         // Components of known path (type name)
         IntoIterator,
         Item,
@@ -150,6 +176,7 @@ pub mod known {
         Ok,
         Future,
         Result,
+        Option,
         Output,
         Target,
         Box,
@@ -162,6 +189,23 @@ pub mod known {
         Neg,
         Not,
         Index,
+        Add,
+        Sub,
+        Mul,
+        Div,
+        Rem,
+        Shl,
+        Shr,
+        BitXor,
+        BitOr,
+        BitAnd,
+        Send,
+        Sync,
+        Vec,
+        String,
+        // Known methods
+        unwrap,
+        expect,
         // Builtin macros
         file,
         column,
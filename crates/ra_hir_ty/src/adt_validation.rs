@@ -0,0 +1,90 @@
+//! Checks that ADTs have a finite size: a struct, enum or union can't contain
+//! itself, directly or through the fields of other types, without some
+//! indirection like a reference, a pointer or a `Box` in between.
+
+use hir_def::{src::HasChildSource, AdtId, EnumVariantId, VariantId};
+use hir_expand::diagnostics::DiagnosticSink;
+use ra_syntax::{AstNode, SyntaxNodePtr};
+use rustc_hash::FxHashSet;
+
+use crate::{db::HirDatabase, diagnostics::RecursiveType, Ty, TypeCtor, TypeWalk};
+
+/// How many nested ADTs are followed before giving up, because types like
+/// `struct S<T>(Option<S<(T, T)>>)` would otherwise be expanded forever.
+const MAX_DEPTH: usize = 32;
+
+pub fn validate_adt(db: &impl HirDatabase, adt: AdtId, sink: &mut DiagnosticSink) {
+    for variant in adt_variants(db, adt) {
+        let field_types = db.field_types(variant);
+        let field_id = field_types.iter().find_map(|(field_id, ty)| {
+            let mut visited = FxHashSet::default();
+            if contains_without_indirection(db, ty, adt, &mut visited, 0) {
+                Some(field_id)
+            } else {
+                None
+            }
+        });
+        if let Some(field_id) = field_id {
+            let src = variant.child_source(db);
+            let field = src.value[field_id]
+                .as_ref()
+                .either(|it| SyntaxNodePtr::new(it.syntax()), |it| SyntaxNodePtr::new(it.syntax()));
+            let name = match adt {
+                AdtId::StructId(it) => db.struct_data(it).name.clone(),
+                AdtId::UnionId(it) => db.union_data(it).name.clone(),
+                AdtId::EnumId(it) => db.enum_data(it).name.clone(),
+            };
+            // one field closing the cycle is enough to explain the error
+            sink.push(RecursiveType { file: src.file_id, field, name });
+            return;
+        }
+    }
+}
+
+/// Whether a value of type `ty` stores a value of type `target` inline.
+fn contains_without_indirection(
+    db: &impl HirDatabase,
+    ty: &Ty,
+    target: AdtId,
+    visited: &mut FxHashSet<Ty>,
+    depth: usize,
+) -> bool {
+    let a_ty = match ty {
+        Ty::Apply(it) => it,
+        _ => return false,
+    };
+    match a_ty.ctor {
+        // references, pointers, slices and functions have a known size
+        // whatever their content, `Box` and `Vec` are made of pointers too
+        TypeCtor::Tuple { .. } | TypeCtor::Array { .. } => a_ty
+            .parameters
+            .iter()
+            .any(|it| contains_without_indirection(db, it, target, visited, depth)),
+        TypeCtor::Adt(adt) if adt == target => true,
+        TypeCtor::Adt(adt) => {
+            if depth >= MAX_DEPTH || !visited.insert(ty.clone()) {
+                return false;
+            }
+            adt_variants(db, adt).into_iter().any(|variant| {
+                db.field_types(variant).iter().any(|(_, field_ty)| {
+                    let field_ty = field_ty.clone().subst(&a_ty.parameters);
+                    contains_without_indirection(db, &field_ty, target, visited, depth + 1)
+                })
+            })
+        }
+        _ => false,
+    }
+}
+
+fn adt_variants(db: &impl HirDatabase, adt: AdtId) -> Vec<VariantId> {
+    match adt {
+        AdtId::StructId(it) => vec![it.into()],
+        AdtId::UnionId(it) => vec![VariantId::UnionId(it)],
+        AdtId::EnumId(it) => db
+            .enum_data(it)
+            .variants
+            .iter()
+            .map(|(local_id, _)| EnumVariantId { parent: it, local_id }.into())
+            .collect(),
+    }
+}
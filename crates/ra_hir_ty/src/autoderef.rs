@@ -25,10 +25,20 @@ pub fn autoderef<'a>(
     ty: InEnvironment<Canonical<Ty>>,
 ) -> impl Iterator<Item = Canonical<Ty>> + 'a {
     let InEnvironment { value: ty, environment } = ty;
+    // `Deref` impls may form a cycle, like `A: Deref<Target = B>` and
+    // `B: Deref<Target = A>`, so the chain stops at the first repeated type
+    let mut seen = Vec::new();
     successors(Some(ty), move |ty| {
         deref(db, krate?, InEnvironment { value: ty, environment: environment.clone() })
     })
     .take(AUTODEREF_RECURSION_LIMIT)
+    .take_while(move |ty| {
+        if seen.contains(ty) {
+            return false;
+        }
+        seen.push(ty.clone());
+        true
+    })
 }
 
 pub(crate) fn deref(
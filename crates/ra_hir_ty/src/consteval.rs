@@ -0,0 +1,65 @@
+//! Evaluation of the lengths of array types. Only integer literals and paths
+//! to constants, which are evaluated in turn, are understood: anything else is
+//! an unknown length.
+
+use hir_def::{
+    body::Body,
+    expr::{Expr, ExprId, Literal},
+    path::Path,
+    resolver::{HasResolver, Resolver, ValueNs},
+    type_ref::ArrayLen,
+};
+
+use crate::db::HirDatabase;
+
+/// How many constants defined by other constants are followed, so that cyclic
+/// definitions can't overflow the stack.
+const MAX_DEPTH: usize = 16;
+
+pub(crate) fn eval_array_len(
+    db: &impl HirDatabase,
+    resolver: &Resolver,
+    len: &ArrayLen,
+) -> Option<u64> {
+    match len {
+        ArrayLen::Literal(it) => Some(*it),
+        ArrayLen::Path(path) => eval_path(db, resolver, path, 0),
+        ArrayLen::Unknown => None,
+    }
+}
+
+/// Evaluates a length written in a body, like the one of `[0; N]`.
+pub(crate) fn eval_expr(
+    db: &impl HirDatabase,
+    resolver: &Resolver,
+    body: &Body,
+    expr: ExprId,
+) -> Option<u64> {
+    eval_expr_at_depth(db, resolver, body, expr, 0)
+}
+
+fn eval_expr_at_depth(
+    db: &impl HirDatabase,
+    resolver: &Resolver,
+    body: &Body,
+    expr: ExprId,
+    depth: usize,
+) -> Option<u64> {
+    match &body[expr] {
+        Expr::Literal(Literal::Int(value, _)) => Some(*value),
+        Expr::Path(path) => eval_path(db, resolver, path, depth),
+        _ => None,
+    }
+}
+
+fn eval_path(db: &impl HirDatabase, resolver: &Resolver, path: &Path, depth: usize) -> Option<u64> {
+    if depth >= MAX_DEPTH {
+        return None;
+    }
+    let konst = match resolver.resolve_path_in_value_ns_fully(db, path.mod_path())? {
+        ValueNs::ConstId(it) => it,
+        _ => return None,
+    };
+    let body = db.body(konst.into());
+    eval_expr_at_depth(db, &konst.resolver(db), &body, body.body_expr, depth + 1)
+}
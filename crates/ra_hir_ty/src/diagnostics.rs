@@ -3,7 +3,7 @@
 use std::any::Any;
 
 use hir_expand::{db::AstDatabase, name::Name, HirFileId, InFile};
-use ra_syntax::{ast, AstNode, AstPtr, SyntaxNodePtr};
+use ra_syntax::{ast, AstNode, AstPtr, SyntaxNodePtr, TextRange};
 
 pub use hir_def::diagnostics::UnresolvedModule;
 pub use hir_expand::diagnostics::{AstDiagnostic, Diagnostic, DiagnosticSink};
@@ -89,3 +89,531 @@ impl AstDiagnostic for MissingOkInTailExpr {
         ast::Expr::cast(node).unwrap()
     }
 }
+
+#[derive(Debug)]
+pub struct PrivateItem {
+    pub file: HirFileId,
+    pub name_ref: AstPtr<ast::NameRef>,
+    /// The definition of the inaccessible item, used to offer a fix.
+    pub item: InFile<SyntaxNodePtr>,
+    pub kind: &'static str,
+    pub name: Name,
+}
+
+impl Diagnostic for PrivateItem {
+    fn message(&self) -> String {
+        format!("{} `{}` is private", self.kind, self.name)
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.name_ref.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct AmbiguousName {
+    pub file: HirFileId,
+    pub path: AstPtr<ast::Path>,
+    pub name: Name,
+}
+
+impl Diagnostic for AmbiguousName {
+    fn message(&self) -> String {
+        format!("`{}` is ambiguous: it is brought into scope by several glob imports", self.name)
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.path.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct UnwrapCall {
+    pub file: HirFileId,
+    pub call: AstPtr<ast::MethodCallExpr>,
+    pub method: Name,
+    /// Whether the enclosing function returns the same kind of enum as the
+    /// receiver, so that the call can be replaced with `?`.
+    pub can_use_try: bool,
+}
+
+impl Diagnostic for UnwrapCall {
+    fn message(&self) -> String {
+        format!("call to `.{}()` may panic", self.method)
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.call.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+impl AstDiagnostic for UnwrapCall {
+    type AST = ast::MethodCallExpr;
+
+    fn ast(&self, db: &impl AstDatabase) -> Self::AST {
+        let root = db.parse_or_expand(self.source().file_id).unwrap();
+        let node = self.source().value.to_node(&root);
+        ast::MethodCallExpr::cast(node).unwrap()
+    }
+}
+
+#[derive(Debug)]
+pub struct ReturnsLocalReference {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+    pub name: Name,
+}
+
+impl Diagnostic for ReturnsLocalReference {
+    fn message(&self) -> String {
+        format!("cannot return reference to local variable `{}`", self.name)
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct UnusedMustUse {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::Expr>,
+    /// The name of the `#[must_use]` function if the attribute is on the
+    /// function, or else the name of the type of the discarded value.
+    pub name: Name,
+    pub is_return_value: bool,
+    /// Whether the value is a `Result` or an `Option` of the same kind as the
+    /// return type of the enclosing function, so that `?` can be used.
+    pub can_use_try: bool,
+}
+
+impl Diagnostic for UnusedMustUse {
+    fn message(&self) -> String {
+        if self.is_return_value {
+            format!("unused return value of `{}` that must be used", self.name)
+        } else {
+            format!("unused `{}` that must be used", self.name)
+        }
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+impl AstDiagnostic for UnusedMustUse {
+    type AST = ast::Expr;
+
+    fn ast(&self, db: &impl AstDatabase) -> Self::AST {
+        let root = db.parse_or_expand(self.source().file_id).unwrap();
+        let node = self.source().value.to_node(&root);
+        ast::Expr::cast(node).unwrap()
+    }
+}
+
+#[derive(Debug)]
+pub struct UnusedCollection {
+    pub file: HirFileId,
+    /// The initializer of the `let`, or the expression of the statement.
+    pub expr: AstPtr<ast::Expr>,
+    /// The binding of the `let`, if the collection isn't dropped right away.
+    pub name: Option<Name>,
+    /// The type of the collection, `Vec` or `String`.
+    pub ty: Name,
+    /// Whether creating the collection has no side effects, so that the
+    /// statement can be removed.
+    pub removable: bool,
+}
+
+impl Diagnostic for UnusedCollection {
+    fn message(&self) -> String {
+        match &self.name {
+            Some(name) => format!("`{}` is a `{}` which is never used", name, self.ty),
+            None => format!("this `{}` is dropped right after being created", self.ty),
+        }
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+impl AstDiagnostic for UnusedCollection {
+    type AST = ast::Expr;
+
+    fn ast(&self, db: &impl AstDatabase) -> Self::AST {
+        let root = db.parse_or_expand(self.source().file_id).unwrap();
+        let node = self.source().value.to_node(&root);
+        ast::Expr::cast(node).unwrap()
+    }
+}
+
+#[derive(Debug)]
+pub struct RedundantPatternMatching {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::IfExpr>,
+    /// The method checking the same thing as the pattern, like `is_some`.
+    pub method: &'static str,
+}
+
+impl Diagnostic for RedundantPatternMatching {
+    fn message(&self) -> String {
+        format!("redundant pattern matching, consider using `{}()`", self.method)
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+impl AstDiagnostic for RedundantPatternMatching {
+    type AST = ast::IfExpr;
+
+    fn ast(&self, db: &impl AstDatabase) -> Self::AST {
+        let root = db.parse_or_expand(self.source().file_id).unwrap();
+        let node = self.source().value.to_node(&root);
+        ast::IfExpr::cast(node).unwrap()
+    }
+}
+
+#[derive(Debug)]
+pub struct NoOpOperation {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::BinExpr>,
+    /// Whether this is an assignment of a place to itself, like `x = x`.
+    pub self_assignment: bool,
+}
+
+impl Diagnostic for NoOpOperation {
+    fn message(&self) -> String {
+        if self.self_assignment {
+            "self-assignment has no effect".to_string()
+        } else {
+            "operation has no effect".to_string()
+        }
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+impl AstDiagnostic for NoOpOperation {
+    type AST = ast::BinExpr;
+
+    fn ast(&self, db: &impl AstDatabase) -> Self::AST {
+        let root = db.parse_or_expand(self.source().file_id).unwrap();
+        let node = self.source().value.to_node(&root);
+        ast::BinExpr::cast(node).unwrap()
+    }
+}
+
+#[derive(Debug)]
+pub struct ShadowedBinding {
+    pub file: HirFileId,
+    pub pat: AstPtr<ast::Pat>,
+    pub name: Name,
+}
+
+impl Diagnostic for ShadowedBinding {
+    fn message(&self) -> String {
+        format!("`{}` shadows a binding of the same name in this block", self.name)
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.pat.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct MoveOutOfBorrow {
+    pub file: HirFileId,
+    pub pat: AstPtr<ast::Pat>,
+    pub name: Name,
+    /// The type of the moved value, which doesn't implement `Copy`.
+    pub ty: String,
+}
+
+impl Diagnostic for MoveOutOfBorrow {
+    fn message(&self) -> String {
+        format!(
+            "cannot move `{}` out of a reference, its type `{}` doesn't implement `Copy`",
+            self.name, self.ty
+        )
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.pat.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+impl AstDiagnostic for MoveOutOfBorrow {
+    type AST = ast::BindPat;
+
+    fn ast(&self, db: &impl AstDatabase) -> Self::AST {
+        let root = db.parse_or_expand(self.source().file_id).unwrap();
+        let node = self.source().value.to_node(&root);
+        ast::BindPat::cast(node).unwrap()
+    }
+}
+
+#[derive(Debug)]
+pub struct FloatEqComparison {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::BinExpr>,
+    /// Whether this is a `!=` comparison.
+    pub negated: bool,
+    /// The floating-point type of the operands, `f32` or `f64`.
+    pub ty: &'static str,
+}
+
+impl Diagnostic for FloatEqComparison {
+    fn message(&self) -> String {
+        "strict comparison of floating-point values".to_string()
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+impl AstDiagnostic for FloatEqComparison {
+    type AST = ast::BinExpr;
+
+    fn ast(&self, db: &impl AstDatabase) -> Self::AST {
+        let root = db.parse_or_expand(self.source().file_id).unwrap();
+        let node = self.source().value.to_node(&root);
+        ast::BinExpr::cast(node).unwrap()
+    }
+}
+
+#[derive(Debug)]
+pub struct RedundantCast {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::CastExpr>,
+    pub ty: String,
+}
+
+impl Diagnostic for RedundantCast {
+    fn message(&self) -> String {
+        format!("casting `{}` to the same type is unnecessary", self.ty)
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+impl AstDiagnostic for RedundantCast {
+    type AST = ast::CastExpr;
+
+    fn ast(&self, db: &impl AstDatabase) -> Self::AST {
+        let root = db.parse_or_expand(self.source().file_id).unwrap();
+        let node = self.source().value.to_node(&root);
+        ast::CastExpr::cast(node).unwrap()
+    }
+}
+
+#[derive(Debug)]
+pub struct TruncatingCast {
+    pub file: HirFileId,
+    pub expr: AstPtr<ast::CastExpr>,
+    pub from: String,
+    pub to: String,
+}
+
+impl Diagnostic for TruncatingCast {
+    fn message(&self) -> String {
+        format!(
+            "casting `{}` to `{}` may truncate the value, consider using `try_into`",
+            self.from, self.to
+        )
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.expr.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct TraitMethodMismatch {
+    pub file: HirFileId,
+    pub fn_def: AstPtr<ast::FnDef>,
+    /// The range of the signature, from `fn` to the return type.
+    pub signature_range: TextRange,
+    pub name: Name,
+    pub trait_name: Name,
+    pub mismatch: SignatureMismatch,
+}
+
+#[derive(Debug)]
+pub enum SignatureMismatch {
+    /// Only one of the methods has a `self` parameter.
+    SelfParam {
+        in_impl: bool,
+    },
+    ParamCount {
+        expected: usize,
+        found: usize,
+    },
+    ReturnType {
+        expected: String,
+        found: String,
+    },
+}
+
+impl Diagnostic for TraitMethodMismatch {
+    fn message(&self) -> String {
+        match &self.mismatch {
+            SignatureMismatch::SelfParam { in_impl: true } => format!(
+                "method `{}` has a `self` parameter in the impl, but not in trait `{}`",
+                self.name, self.trait_name
+            ),
+            SignatureMismatch::SelfParam { in_impl: false } => format!(
+                "method `{}` has a `self` parameter in trait `{}`, but not in the impl",
+                self.name, self.trait_name
+            ),
+            SignatureMismatch::ParamCount { expected, found } => format!(
+                "method `{}` has {} parameter{} but the declaration in trait `{}` has {}",
+                self.name,
+                found,
+                if *found == 1 { "" } else { "s" },
+                self.trait_name,
+                expected
+            ),
+            SignatureMismatch::ReturnType { expected, found } => format!(
+                "method `{}` has an incompatible return type for trait `{}`: expected `{}`, found `{}`",
+                self.name, self.trait_name, expected, found
+            ),
+        }
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.fn_def.into() }
+    }
+    fn highlight_range(&self) -> TextRange {
+        self.signature_range
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct MissingSuperTraitImpls {
+    pub file: HirFileId,
+    pub trait_ref: AstPtr<ast::TypeRef>,
+    pub trait_name: Name,
+    pub self_ty: String,
+    /// The super traits which aren't implemented for the type.
+    pub missing: Vec<Name>,
+}
+
+impl Diagnostic for MissingSuperTraitImpls {
+    fn message(&self) -> String {
+        let missing = self.missing.iter().map(|it| format!("`{}`", it)).collect::<Vec<_>>();
+        if let [missing] = missing.as_slice() {
+            format!(
+                "the trait {} is not implemented for `{}`, but is required by `{}`",
+                missing, self.self_ty, self.trait_name
+            )
+        } else {
+            format!(
+                "the traits {} are not implemented for `{}`, but are required by `{}`",
+                missing.join(", "),
+                self.self_ty,
+                self.trait_name
+            )
+        }
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.trait_ref.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct RecursiveType {
+    pub file: HirFileId,
+    /// The field through which the type contains itself.
+    pub field: SyntaxNodePtr,
+    pub name: Name,
+}
+
+impl Diagnostic for RecursiveType {
+    fn message(&self) -> String {
+        format!(
+            "recursive type `{}` has infinite size, insert some indirection like `Box` to make it representable",
+            self.name
+        )
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.field }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct MismatchedVariantPath {
+    pub file: HirFileId,
+    pub path: AstPtr<ast::Path>,
+    pub variant: Name,
+    /// The enum named by the path, which isn't the enum of the matched value
+    /// or which doesn't have the variant.
+    pub found_enum: Name,
+    pub expected_enum: Name,
+    pub variant_exists: bool,
+}
+
+impl Diagnostic for MismatchedVariantPath {
+    fn message(&self) -> String {
+        if self.variant_exists {
+            format!(
+                "expected a variant of `{}`, found `{}::{}`",
+                self.expected_enum, self.found_enum, self.variant
+            )
+        } else if self.found_enum == self.expected_enum {
+            format!("no variant `{}` in `{}`", self.variant, self.found_enum)
+        } else {
+            format!(
+                "no variant `{}` in `{}`, expected a variant of `{}`",
+                self.variant, self.found_enum, self.expected_enum
+            )
+        }
+    }
+    fn source(&self) -> InFile<SyntaxNodePtr> {
+        InFile { file_id: self.file, value: self.path.into() }
+    }
+    fn as_any(&self) -> &(dyn Any + Send + 'static) {
+        self
+    }
+}
@@ -3,19 +3,33 @@
 use std::sync::Arc;
 
 use hir_def::{
-    path::{path, Path},
-    resolver::HasResolver,
-    AdtId, FunctionId,
+    path::{path, ModPath, Path, PathKind},
+    resolver::{resolver_for_expr, HasResolver, Resolver, TypeNs, ValueNs},
+    src::{HasChildSource, HasSource},
+    AdtId, AssocContainerId, AssocItemId, AttrDefId, EnumId, FunctionId, HasModule, Lookup,
+    StructFieldId, StructId, VariantId,
 };
-use hir_expand::{diagnostics::DiagnosticSink, name::Name};
-use ra_syntax::ast;
-use ra_syntax::AstPtr;
+use hir_expand::{
+    diagnostics::DiagnosticSink,
+    hygiene::Hygiene,
+    name::{name, Name},
+    HirFileId, InFile,
+};
+use ra_syntax::{ast, AstNode, AstPtr, SyntaxNodePtr};
 use rustc_hash::FxHashSet;
 
 use crate::{
     db::HirDatabase,
-    diagnostics::{MissingFields, MissingOkInTailExpr},
-    ApplicationTy, InferenceResult, Ty, TypeCtor,
+    diagnostics::{
+        AmbiguousName, FloatEqComparison, MismatchedVariantPath, MissingFields,
+        MissingOkInTailExpr, MoveOutOfBorrow, NoOpOperation, PrivateItem, RedundantCast,
+        RedundantPatternMatching, ReturnsLocalReference, ShadowedBinding, TruncatingCast,
+        UnusedCollection, UnusedMustUse, UnwrapCall,
+    },
+    display::HirDisplay,
+    method_resolution::implements_trait,
+    primitive::{FloatBitness, FloatTy, IntBitness, IntTy, Uncertain},
+    ApplicationTy, CallableDef, Canonical, InferenceResult, Ty, TypeCtor, TypeWalk,
 };
 
 pub use hir_def::{
@@ -47,16 +61,78 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
     pub fn validate_body(&mut self, db: &impl HirDatabase) {
         let body = db.body(self.func.into());
 
-        for e in body.exprs.iter() {
-            if let (id, Expr::RecordLit { path, fields, spread }) = e {
-                self.validate_record_literal(id, path, fields, *spread, db);
+        for (id, expr) in body.exprs.iter() {
+            match expr {
+                Expr::RecordLit { path, fields, spread } => {
+                    if let Some(path) = path {
+                        self.validate_path_ambiguity(id, path, false, db);
+                    }
+                    self.validate_record_literal(id, path, fields, *spread, db);
+                    self.validate_record_literal_privacy(id, fields, db);
+                }
+                Expr::Field { .. } => {
+                    if let Some(field) = self.infer.field_resolution(id) {
+                        self.validate_field_privacy(id, field, db);
+                    }
+                }
+                Expr::MethodCall { receiver, .. } => {
+                    if let Some(func) = self.infer.method_resolution(id) {
+                        self.validate_function_privacy(id, func, db);
+                        self.validate_unwrap_call(id, *receiver, func, db);
+                    }
+                }
+                Expr::Path(path) => {
+                    self.validate_path_ambiguity(id, path, true, db);
+                    self.validate_path_privacy(id, path, db);
+                }
+                Expr::BinaryOp { lhs, rhs, op: Some(BinaryOp::CmpOp(CmpOp::Eq { negated })) } => {
+                    self.validate_float_comparison(id, *lhs, *rhs, *negated, db);
+                }
+                Expr::BinaryOp { lhs, rhs, op: Some(op) } => {
+                    self.validate_no_op(&body, id, *lhs, *rhs, *op, db);
+                }
+                Expr::Match { expr, arms } => {
+                    self.validate_if_let_pattern(&body, id, arms, db);
+                    self.validate_match_arm_variants(&body, *expr, arms, db);
+                }
+                Expr::Cast { expr, .. } => {
+                    self.validate_cast(&body, id, *expr, db);
+                }
+                Expr::Return { expr: Some(expr) } => {
+                    self.validate_results_in_tail_expr(*expr, *expr, db);
+                }
+                Expr::Block { statements, tail } => {
+                    for stmt in statements {
+                        if let Statement::Expr(expr) = stmt {
+                            self.validate_unused_must_use(&body, *expr, db);
+                        }
+                    }
+                    self.validate_shadowed_bindings(&body, statements, db);
+                    self.validate_unused_collections(&body, statements, *tail, db);
+                }
+                _ => {}
+            }
+        }
+
+        for (id, pat) in body.pats.iter() {
+            match pat {
+                Pat::Path(path) | Pat::TupleStruct { path: Some(path), .. } => {
+                    self.validate_pat_path_ambiguity(id, path, true, db)
+                }
+                Pat::Record { path: Some(path), .. } => {
+                    self.validate_pat_path_ambiguity(id, path, false, db)
+                }
+                _ => {}
             }
         }
+        self.validate_type_path_ambiguity(db);
 
         let body_expr = &body[body.body_expr];
         if let Expr::Block { statements: _, tail: Some(t) } = body_expr {
             self.validate_results_in_tail_expr(body.body_expr, *t, db);
         }
+        self.validate_returned_references(&body, db);
+        self.validate_moves_out_of_borrows(&body, db);
     }
 
     fn validate_record_literal(
@@ -112,14 +188,711 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
         }
     }
 
+    fn validate_record_literal_privacy(
+        &mut self,
+        id: ExprId,
+        fields: &[RecordLitField],
+        db: &impl HirDatabase,
+    ) {
+        let (_, source_map) = db.body_with_source_map(self.func.into());
+        for (idx, field) in fields.iter().enumerate() {
+            let field_id = match self.infer.record_field_resolution(field.expr) {
+                Some(it) => it,
+                None => continue,
+            };
+            if self.is_field_visible(field_id, db) {
+                continue;
+            }
+            let field_ptr = source_map.field_syntax(id, idx);
+            let file_id = match source_map.expr_syntax(id) {
+                Some(it) => it.file_id,
+                None => continue,
+            };
+            let root = db.parse_or_expand(file_id).unwrap();
+            if let Some(name_ref) = field_ptr.to_node(&root).name_ref() {
+                self.push_private_field(InFile::new(file_id, &name_ref), field_id, db);
+            }
+        }
+    }
+
+    fn validate_field_privacy(&mut self, id: ExprId, field: StructFieldId, db: &impl HirDatabase) {
+        if self.is_field_visible(field, db) {
+            return;
+        }
+        if let Some((file_id, ast::Expr::FieldExpr(field_expr))) = self.expr_node(id, db) {
+            if let Some(name_ref) = field_expr.name_ref() {
+                self.push_private_field(InFile::new(file_id, &name_ref), field, db);
+            }
+        }
+    }
+
+    fn validate_function_privacy(&mut self, id: ExprId, func: FunctionId, db: &impl HirDatabase) {
+        let module = self.func.lookup(db).module(db);
+        if db.function_visibility(func).is_visible_from(db, module) {
+            return;
+        }
+        let name_ref = match self.expr_node(id, db) {
+            Some((file_id, ast::Expr::MethodCallExpr(call))) => {
+                call.name_ref().map(|it| (file_id, it))
+            }
+            Some((file_id, ast::Expr::PathExpr(path_expr))) => path_expr
+                .path()
+                .and_then(|it| it.segment())
+                .and_then(|it| it.name_ref())
+                .map(|it| (file_id, it)),
+            _ => None,
+        };
+        if let Some((file_id, name_ref)) = name_ref {
+            let src = func.lookup(db).source(db);
+            self.sink.push(PrivateItem {
+                file: file_id,
+                name_ref: AstPtr::new(&name_ref),
+                item: src.map(|it| SyntaxNodePtr::new(it.syntax())),
+                kind: "function",
+                name: db.function_data(func).name.clone(),
+            });
+        }
+    }
+
+    /// Checks for paths of expressions starting with a name which several glob
+    /// imports bring into scope. `value_ns` tells whether a path of a single
+    /// segment names a value, like a function, or a type, like the struct of
+    /// a record literal.
+    fn validate_path_ambiguity(
+        &mut self,
+        id: ExprId,
+        path: &Path,
+        value_ns: bool,
+        db: &impl HirDatabase,
+    ) {
+        let resolver = resolver_for_expr(db, self.func.into(), id);
+        let name = match ambiguous_name(&resolver, path.mod_path(), value_ns) {
+            Some(it) => it.clone(),
+            None => return,
+        };
+        let (file_id, expr) = match self.expr_node(id, db) {
+            Some(it) => it,
+            None => return,
+        };
+        let path = match expr {
+            ast::Expr::PathExpr(it) => it.path(),
+            ast::Expr::RecordLit(it) => it.path(),
+            _ => None,
+        };
+        if let Some(path) = path {
+            self.sink.push(AmbiguousName { file: file_id, path: AstPtr::new(&path), name });
+        }
+    }
+
+    fn validate_pat_path_ambiguity(
+        &mut self,
+        id: PatId,
+        path: &Path,
+        value_ns: bool,
+        db: &impl HirDatabase,
+    ) {
+        let resolver = self.func.resolver(db);
+        let name = match ambiguous_name(&resolver, path.mod_path(), value_ns) {
+            Some(it) => it.clone(),
+            None => return,
+        };
+        if let Some((file_id, path)) = self.pat_path_node(id, db) {
+            self.sink.push(AmbiguousName { file: file_id, path: AstPtr::new(&path), name });
+        }
+    }
+
+    /// Checks the types written in the signature and the body of the function,
+    /// which the body doesn't keep the syntax of.
+    fn validate_type_path_ambiguity(&mut self, db: &impl HirDatabase) {
+        let resolver = self.func.resolver(db);
+        let src = self.func.lookup(db).source(db);
+        let hygiene = Hygiene::new(db, src.file_id);
+        for path_type in src.value.syntax().descendants().filter_map(ast::PathType::cast) {
+            let path = match path_type.path() {
+                Some(it) => it,
+                None => continue,
+            };
+            let name = match ModPath::from_src(path.clone(), &hygiene)
+                .and_then(|it| ambiguous_name(&resolver, &it, false).cloned())
+            {
+                Some(it) => it,
+                None => continue,
+            };
+            self.sink.push(AmbiguousName { file: src.file_id, path: AstPtr::new(&path), name });
+        }
+    }
+
+    fn validate_path_privacy(&mut self, id: ExprId, path: &Path, db: &impl HirDatabase) {
+        let func = match self.infer.assoc_resolutions_for_expr(id) {
+            Some(AssocItemId::FunctionId(it)) => it,
+            Some(_) => return,
+            None => {
+                let resolver = resolver_for_expr(db, self.func.into(), id);
+                match resolver.resolve_path_in_value_ns_fully(db, path.mod_path()) {
+                    Some(ValueNs::FunctionId(it)) => it,
+                    _ => return,
+                }
+            }
+        };
+        self.validate_function_privacy(id, func, db);
+    }
+
+    fn is_field_visible(&self, field: StructFieldId, db: &impl HirDatabase) -> bool {
+        let module = self.func.lookup(db).module(db);
+        db.field_visibilities(field.parent)[field.local_id].is_visible_from(db, module)
+    }
+
+    fn push_private_field(
+        &mut self,
+        name_ref: InFile<&ast::NameRef>,
+        field: StructFieldId,
+        db: &impl HirDatabase,
+    ) {
+        let src = field.parent.child_source(db);
+        let item = src.map(|map| {
+            map[field.local_id]
+                .as_ref()
+                .either(|it| SyntaxNodePtr::new(it.syntax()), |it| SyntaxNodePtr::new(it.syntax()))
+        });
+        let variant_data = field.parent.variant_data(db);
+        self.sink.push(PrivateItem {
+            file: name_ref.file_id,
+            name_ref: AstPtr::new(name_ref.value),
+            item,
+            kind: "field",
+            name: variant_data.fields()[field.local_id].name.clone(),
+        });
+    }
+
+    fn expr_node(&self, id: ExprId, db: &impl HirDatabase) -> Option<(HirFileId, ast::Expr)> {
+        let (_, source_map) = db.body_with_source_map(self.func.into());
+        let source_ptr = source_map.expr_syntax(id)?;
+        let expr = source_ptr.value.left()?;
+        let root = source_ptr.file_syntax(db);
+        Some((source_ptr.file_id, expr.to_node(&root)))
+    }
+
+    fn validate_unwrap_call(
+        &mut self,
+        id: ExprId,
+        receiver: ExprId,
+        method: FunctionId,
+        db: &impl HirDatabase,
+    ) {
+        let method_name = db.function_data(method).name.clone();
+        if method_name != name![unwrap] && method_name != name![expect] {
+            return;
+        }
+
+        let resolver = self.func.resolver(db);
+        let std_option_enum = resolver.resolve_known_enum(db, &path![std::option::Option]);
+        let std_result_enum = resolver.resolve_known_enum(db, &path![std::result::Result]);
+        let receiver_enum = match as_enum(&self.infer[receiver]) {
+            Some(it) if Some(it) == std_option_enum || Some(it) == std_result_enum => it,
+            _ => return,
+        };
+        let sig = db.callable_item_signature(CallableDef::FunctionId(self.func));
+        let can_use_try = as_enum(sig.ret()) == Some(receiver_enum);
+
+        if let Some((file_id, ast::Expr::MethodCallExpr(call))) = self.expr_node(id, db) {
+            self.sink.push(UnwrapCall {
+                file: file_id,
+                call: AstPtr::new(&call),
+                method: method_name,
+                can_use_try,
+            });
+        }
+    }
+
+    fn validate_float_comparison(
+        &mut self,
+        id: ExprId,
+        lhs: ExprId,
+        rhs: ExprId,
+        negated: bool,
+        db: &impl HirDatabase,
+    ) {
+        let ty = match (as_float(&self.infer[lhs]), as_float(&self.infer[rhs])) {
+            (Some(ty), Some(_)) => ty,
+            _ => return,
+        };
+        let ty = match ty {
+            Uncertain::Known(FloatTy { bitness: FloatBitness::X32 }) => "f32",
+            _ => "f64",
+        };
+
+        if let Some((file_id, ast::Expr::BinExpr(expr))) = self.expr_node(id, db) {
+            self.sink.push(FloatEqComparison {
+                file: file_id,
+                expr: AstPtr::new(&expr),
+                negated,
+                ty,
+            });
+        }
+    }
+
+    /// Checks for casts to the type the value already has, and for casts to a
+    /// smaller integer type. The type of unsuffixed literals is chosen by the
+    /// cast, so those are ignored.
+    fn validate_cast(&mut self, body: &Body, id: ExprId, expr: ExprId, db: &impl HirDatabase) {
+        if is_literal(body, expr) {
+            return;
+        }
+        let from = &self.infer[expr];
+        let to = &self.infer[id];
+        let (file_id, cast) = match self.expr_node(id, db) {
+            Some((file_id, ast::Expr::CastExpr(cast))) => (file_id, cast),
+            _ => return,
+        };
+        if from == to && !contains_unknown(from) {
+            self.sink.push(RedundantCast {
+                file: file_id,
+                expr: AstPtr::new(&cast),
+                ty: to.display(db).to_string(),
+            });
+            return;
+        }
+        // enums are cast through the integer type of their discriminants
+        let from_int = as_int(from).or_else(|| enum_repr(db, from));
+        if let (Some(from_int), Some(to_int)) = (from_int, as_int(to)) {
+            match (int_bits(from_int), int_bits(to_int)) {
+                (Some(from_bits), Some(to_bits)) if to_bits < from_bits => {
+                    self.sink.push(TruncatingCast {
+                        file: file_id,
+                        expr: AstPtr::new(&cast),
+                        from: from.display(db).to_string(),
+                        to: to.display(db).to_string(),
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Checks for operations without effect: assignments of a place to
+    /// itself, compound assignments with the identity of their operator, like
+    /// `x += 0`, and logical operations on the same operands, like `x && x`.
+    fn validate_no_op(
+        &mut self,
+        body: &Body,
+        id: ExprId,
+        lhs: ExprId,
+        rhs: ExprId,
+        op: BinaryOp,
+        db: &impl HirDatabase,
+    ) {
+        match op {
+            BinaryOp::Assignment { op: None } | BinaryOp::LogicOp(_) => {
+                if !self.is_same_place(body, id, lhs, rhs, db) {
+                    return;
+                }
+            }
+            BinaryOp::Assignment { op: Some(op) } => {
+                let identity = match op {
+                    ArithOp::Add
+                    | ArithOp::Sub
+                    | ArithOp::BitOr
+                    | ArithOp::BitXor
+                    | ArithOp::Shl
+                    | ArithOp::Shr => 0,
+                    ArithOp::Mul | ArithOp::Div => 1,
+                    ArithOp::Rem | ArithOp::BitAnd => return,
+                };
+                match &body[rhs] {
+                    Expr::Literal(Literal::Int(value, _)) if *value == identity => (),
+                    _ => return,
+                }
+            }
+            BinaryOp::CmpOp(_) | BinaryOp::ArithOp(_) => return,
+        }
+        let self_assignment = op == BinaryOp::Assignment { op: None };
+
+        if let Some((file_id, ast::Expr::BinExpr(expr))) = self.expr_node(id, db) {
+            self.sink.push(NoOpOperation {
+                file: file_id,
+                expr: AstPtr::new(&expr),
+                self_assignment,
+            });
+        }
+    }
+
+    /// Checks for `let`s binding a name which an earlier `let` of the same
+    /// block already binds. Shadowing which uses the previous value, like
+    /// `let x = x.to_string();`, is intentional and not reported.
+    fn validate_shadowed_bindings(
+        &mut self,
+        body: &Body,
+        statements: &[Statement],
+        db: &impl HirDatabase,
+    ) {
+        let (_, source_map) = db.body_with_source_map(self.func.into());
+        let mut bound: Vec<Name> = Vec::new();
+        for stmt in statements {
+            let (pat, initializer) = match stmt {
+                Statement::Let { pat, initializer, .. } => (*pat, *initializer),
+                Statement::Expr(_) => continue,
+            };
+            let mut bindings = Vec::new();
+            collect_bindings(body, pat, &mut bindings);
+            for &(ref name, pat_id) in &bindings {
+                if name.to_string().starts_with('_') || !bound.contains(name) {
+                    continue;
+                }
+                if initializer.map_or(false, |it| mentions_name(body, it, name)) {
+                    continue;
+                }
+                let source = match source_map.pat_syntax(pat_id) {
+                    Some(it) => it,
+                    None => continue,
+                };
+                if let Some(ptr) = source.value.left() {
+                    self.sink.push(ShadowedBinding {
+                        file: source.file_id,
+                        pat: ptr,
+                        name: name.clone(),
+                    });
+                }
+            }
+            bound.extend(bindings.into_iter().map(|(name, _)| name));
+        }
+    }
+
+    /// Checks for `Vec`s and `String`s which are never used after being
+    /// created: bound by a `let` whose name isn't mentioned in the rest of the
+    /// block, or constructed by an expression statement and dropped right away.
+    fn validate_unused_collections(
+        &mut self,
+        body: &Body,
+        statements: &[Statement],
+        tail: Option<ExprId>,
+        db: &impl HirDatabase,
+    ) {
+        let resolver = self.func.resolver(db);
+        let collections = [
+            resolver.resolve_known_struct(db, &path![std::vec::Vec]),
+            resolver.resolve_known_struct(db, &path![std::string::String]),
+        ];
+        for (i, stmt) in statements.iter().enumerate() {
+            let (expr, name) = match stmt {
+                Statement::Let { pat, initializer: Some(initializer), .. } => match &body[*pat] {
+                    Pat::Bind { name, subpat: None, .. } if !name.to_string().starts_with('_') => {
+                        (*initializer, Some(name))
+                    }
+                    _ => continue,
+                },
+                Statement::Let { .. } => continue,
+                Statement::Expr(expr) => (*expr, None),
+            };
+            let collection =
+                match &self.infer[expr] {
+                    Ty::Apply(ApplicationTy {
+                        ctor: TypeCtor::Adt(AdtId::StructId(it)), ..
+                    }) if collections.contains(&Some(*it)) => *it,
+                    _ => continue,
+                };
+            let removable = self.is_constructor_call(body, expr, collection, db);
+            match name {
+                Some(name) => {
+                    let mut rest = statements[i + 1..]
+                        .iter()
+                        .filter_map(|stmt| match stmt {
+                            Statement::Let { initializer, .. } => *initializer,
+                            Statement::Expr(it) => Some(*it),
+                        })
+                        .chain(tail);
+                    if rest.any(|it| mentions_name(body, it, name)) {
+                        continue;
+                    }
+                }
+                // the call may be made for its side effects
+                None if !removable => continue,
+                None => {}
+            }
+            if let Some((file_id, expr)) = self.expr_node(expr, db) {
+                self.sink.push(UnusedCollection {
+                    file: file_id,
+                    expr: AstPtr::new(&expr),
+                    name: name.cloned(),
+                    ty: db.struct_data(collection).name.clone(),
+                    removable,
+                });
+            }
+        }
+    }
+
+    /// Whether `id` calls a function of an impl of the `collection` struct,
+    /// like `Vec::new()` or `String::from("")`, with literal arguments only.
+    fn is_constructor_call(
+        &self,
+        body: &Body,
+        id: ExprId,
+        collection: StructId,
+        db: &impl HirDatabase,
+    ) -> bool {
+        let (func, args) = match &body[id] {
+            Expr::Call { callee, args } => match self.infer[*callee].as_callable() {
+                Some((CallableDef::FunctionId(it), _)) => (it, args),
+                _ => return false,
+            },
+            Expr::MethodCall { receiver, args, .. } if is_literal(body, *receiver) => {
+                match self.infer.method_resolution(id) {
+                    Some(it) => (it, args),
+                    None => return false,
+                }
+            }
+            _ => return false,
+        };
+        let impl_id = match func.lookup(db).container {
+            AssocContainerId::ImplId(it) => it,
+            _ => return false,
+        };
+        let self_ty = db.impl_self_ty(impl_id);
+        let is_collection_impl = match &self_ty {
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::Adt(AdtId::StructId(it)), .. }) => {
+                *it == collection
+            }
+            _ => false,
+        };
+        is_collection_impl && args.iter().all(|&arg| is_literal(body, arg))
+    }
+
+    /// Whether `lhs` and `rhs` are the same place expression, made of the
+    /// same local or static, fields and dereferences. Anything else, like a
+    /// call, may have side effects and is never considered the same.
+    fn is_same_place(
+        &self,
+        body: &Body,
+        id: ExprId,
+        lhs: ExprId,
+        rhs: ExprId,
+        db: &impl HirDatabase,
+    ) -> bool {
+        match (&body[lhs], &body[rhs]) {
+            (Expr::Path(lhs), Expr::Path(rhs)) => {
+                if lhs != rhs {
+                    return false;
+                }
+                let resolver = resolver_for_expr(db, self.func.into(), id);
+                match resolver.resolve_path_in_value_ns_fully(db, lhs.mod_path()) {
+                    Some(ValueNs::LocalBinding(_)) | Some(ValueNs::StaticId(_)) => true,
+                    _ => false,
+                }
+            }
+            (Expr::Field { expr: lhs, name: l_name }, Expr::Field { expr: rhs, name: r_name }) => {
+                l_name == r_name && self.is_same_place(body, id, *lhs, *rhs, db)
+            }
+            (
+                Expr::UnaryOp { expr: lhs, op: UnaryOp::Deref },
+                Expr::UnaryOp { expr: rhs, op: UnaryOp::Deref },
+            ) => self.is_same_place(body, id, *lhs, *rhs, db),
+            _ => false,
+        }
+    }
+
+    /// Checks for `if let` expressions whose pattern only checks the variant
+    /// of an `Option` or `Result`, like `if let Some(_) = x`.
+    fn validate_if_let_pattern(
+        &mut self,
+        body: &Body,
+        id: ExprId,
+        arms: &[MatchArm],
+        db: &impl HirDatabase,
+    ) {
+        // `if let` is desugared to a match with the pattern in the first arm
+        let pat = match arms.first() {
+            Some(arm) if arm.pats.len() == 1 => arm.pats[0],
+            _ => return,
+        };
+        match &body[pat] {
+            Pat::Path(_) => {}
+            Pat::TupleStruct { args, .. }
+                if args.iter().all(|it| match body[*it] {
+                    Pat::Wild => true,
+                    _ => false,
+                }) => {}
+            _ => return,
+        }
+        let variant = match self.infer.variant_resolution_for_pat(pat) {
+            Some(VariantId::EnumVariantId(it)) => it,
+            _ => return,
+        };
+
+        let resolver = self.func.resolver(db);
+        let std_option_enum = resolver.resolve_known_enum(db, &path![std::option::Option]);
+        let std_result_enum = resolver.resolve_known_enum(db, &path![std::result::Result]);
+        let variant_name = db.enum_data(variant.parent).variants[variant.local_id].name.to_string();
+        let method = match variant_name.as_str() {
+            "Some" if Some(variant.parent) == std_option_enum => "is_some",
+            "None" if Some(variant.parent) == std_option_enum => "is_none",
+            "Ok" if Some(variant.parent) == std_result_enum => "is_ok",
+            "Err" if Some(variant.parent) == std_result_enum => "is_err",
+            _ => return,
+        };
+
+        if let Some((file_id, ast::Expr::IfExpr(expr))) = self.expr_node(id, db) {
+            self.sink.push(RedundantPatternMatching {
+                file: file_id,
+                expr: AstPtr::new(&expr),
+                method,
+            });
+        }
+    }
+
+    /// Checks that the variants named by the patterns of the arms belong to
+    /// the enum of the matched value, and that they exist at all.
+    fn validate_match_arm_variants(
+        &mut self,
+        body: &Body,
+        scrutinee: ExprId,
+        arms: &[MatchArm],
+        db: &impl HirDatabase,
+    ) {
+        let mut ty = &self.infer[scrutinee];
+        while let Some((inner, _)) = ty.as_reference() {
+            ty = inner;
+        }
+        let expected = match as_enum(ty) {
+            Some(it) => it,
+            None => return,
+        };
+        let resolver = self.func.resolver(db);
+        for &pat in arms.iter().flat_map(|arm| arm.pats.iter()) {
+            let path = match &body[pat] {
+                Pat::Path(path) => path,
+                Pat::TupleStruct { path: Some(path), .. }
+                | Pat::Record { path: Some(path), .. } => path,
+                _ => continue,
+            };
+            // associated consts like `E::C` aren't variants
+            if self.infer.assoc_resolutions_for_pat(pat).is_some() {
+                continue;
+            }
+            let variant = match self.infer.variant_resolution_for_pat(pat) {
+                Some(it) => Some(it),
+                // the inference doesn't record the variants of unit patterns
+                None => match resolver.resolve_path_in_value_ns_fully(db, path.mod_path()) {
+                    Some(ValueNs::EnumVariantId(it)) => Some(it.into()),
+                    // other values, like consts, are matched by value
+                    Some(_) => continue,
+                    None => None,
+                },
+            };
+            let (found, variant_exists) = match variant {
+                Some(VariantId::EnumVariantId(it)) if it.parent == expected => continue,
+                Some(VariantId::EnumVariantId(it)) => (it.parent, true),
+                Some(_) => continue,
+                // `Result::Some`, the enum exists but not its variant
+                None => {
+                    let qualifier = match path.qualifier() {
+                        Some(it) => it,
+                        None => continue,
+                    };
+                    match resolver.resolve_path_in_type_ns_fully(db, qualifier.mod_path()) {
+                        Some(TypeNs::AdtId(AdtId::EnumId(it))) => (it, false),
+                        _ => continue,
+                    }
+                }
+            };
+            let variant = match path.segments().last() {
+                Some(it) => it.name.clone(),
+                None => continue,
+            };
+            if let Some((file_id, path)) = self.pat_path_node(pat, db) {
+                self.sink.push(MismatchedVariantPath {
+                    file: file_id,
+                    path: AstPtr::new(&path),
+                    variant,
+                    found_enum: db.enum_data(found).name.clone(),
+                    expected_enum: db.enum_data(expected).name.clone(),
+                    variant_exists,
+                });
+            }
+        }
+    }
+
+    fn pat_path_node(&self, pat: PatId, db: &impl HirDatabase) -> Option<(HirFileId, ast::Path)> {
+        let (_, source_map) = db.body_with_source_map(self.func.into());
+        let source_ptr = source_map.pat_syntax(pat)?;
+        let pat = source_ptr.value.left()?;
+        let root = source_ptr.file_syntax(db);
+        let path = match pat.to_node(&root) {
+            ast::Pat::PathPat(it) => it.path(),
+            ast::Pat::TupleStructPat(it) => it.path(),
+            ast::Pat::RecordPat(it) => it.path(),
+            _ => None,
+        }?;
+        Some((source_ptr.file_id, path))
+    }
+
+    /// Checks for calls in statement position whose value is discarded even
+    /// though the function or the returned type is `#[must_use]`. `Result`
+    /// and `Option` are always considered `#[must_use]`.
+    fn validate_unused_must_use(&mut self, body: &Body, id: ExprId, db: &impl HirDatabase) {
+        let func = match &body[id] {
+            Expr::Call { callee, .. } => match self.infer[*callee].as_callable() {
+                Some((CallableDef::FunctionId(it), _)) => it,
+                _ => return,
+            },
+            Expr::MethodCall { .. } => match self.infer.method_resolution(id) {
+                Some(it) => it,
+                None => return,
+            },
+            _ => return,
+        };
+
+        let resolver = self.func.resolver(db);
+        let std_option_enum = resolver.resolve_known_enum(db, &path![std::option::Option]);
+        let std_result_enum = resolver.resolve_known_enum(db, &path![std::result::Result]);
+        let value_enum = match as_enum(&self.infer[id]) {
+            Some(it) if Some(it) == std_option_enum || Some(it) == std_result_enum => Some(it),
+            _ => None,
+        };
+
+        let (name, is_return_value) =
+            if db.attrs(AttrDefId::FunctionId(func)).by_key("must_use").exists() {
+                (db.function_data(func).name.clone(), true)
+            } else {
+                let adt = match &self.infer[id] {
+                    Ty::Apply(ApplicationTy { ctor: TypeCtor::Adt(it), .. }) => *it,
+                    _ => return,
+                };
+                if value_enum.is_none()
+                    && !db.attrs(AttrDefId::AdtId(adt)).by_key("must_use").exists()
+                {
+                    return;
+                }
+                let name = match adt {
+                    AdtId::StructId(it) => db.struct_data(it).name.clone(),
+                    AdtId::UnionId(it) => db.union_data(it).name.clone(),
+                    AdtId::EnumId(it) => db.enum_data(it).name.clone(),
+                };
+                (name, false)
+            };
+
+        let sig = db.callable_item_signature(CallableDef::FunctionId(self.func));
+        let can_use_try = value_enum.is_some() && as_enum(sig.ret()) == value_enum;
+
+        if let Some((file_id, expr)) = self.expr_node(id, db) {
+            self.sink.push(UnusedMustUse {
+                file: file_id,
+                expr: AstPtr::new(&expr),
+                name,
+                is_return_value,
+                can_use_try,
+            });
+        }
+    }
+
+    /// Checks for a returned value `id` which is the `Ok` value of the
+    /// expected `Result`, where the type mismatch is recorded on `mismatch_id`:
+    /// the whole body for its tail, and the value itself for `return`.
     fn validate_results_in_tail_expr(
         &mut self,
-        body_id: ExprId,
+        mismatch_id: ExprId,
         id: ExprId,
         db: &impl HirDatabase,
     ) {
-        // the mismatch will be on the whole block currently
-        let mismatch = match self.infer.type_mismatch_for_expr(body_id) {
+        let mismatch = match self.infer.type_mismatch_for_expr(mismatch_id) {
             Some(m) => m,
             None => return,
         };
@@ -148,4 +921,305 @@ impl<'a, 'b> ExprValidator<'a, 'b> {
             }
         }
     }
+
+    /// A best-effort check for `&local` (or `&local.field`) being returned
+    /// from the function. This is not a borrow checker: anything which can't
+    /// be trivially seen to reference a local, like bindings of reference
+    /// type or parameters, is ignored.
+    fn validate_returned_references(&mut self, body: &Body, db: &impl HirDatabase) {
+        let sig = db.callable_item_signature(CallableDef::FunctionId(self.func));
+        match sig.ret() {
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::Ref(_), .. }) => {}
+            _ => return,
+        }
+
+        let mut returned = Vec::new();
+        if let Expr::Block { tail: Some(tail), .. } = &body[body.body_expr] {
+            returned.push(*tail);
+        }
+        collect_returned_exprs(body, body.body_expr, &mut returned);
+
+        for id in returned {
+            let name = match self.referenced_local(body, id, db) {
+                Some(it) => it,
+                None => continue,
+            };
+            if let Some((file_id, expr)) = self.expr_node(id, db) {
+                self.sink.push(ReturnsLocalReference {
+                    file: file_id,
+                    expr: AstPtr::new(&expr),
+                    name,
+                });
+            }
+        }
+    }
+
+    /// A best-effort check for patterns moving a value which isn't `Copy`
+    /// out of a reference, like `let &Point { data } = point;`. Only the
+    /// patterns which obviously destructure a reference are checked: the ones
+    /// under a `&` pattern, and the ones matching a dereferenced reference,
+    /// like `let Point { data } = *point;`.
+    fn validate_moves_out_of_borrows(&mut self, body: &Body, db: &impl HirDatabase) {
+        let mut borrowed = Vec::new();
+        for (_, pat) in body.pats.iter() {
+            if let Pat::Ref { pat, .. } = pat {
+                borrowed.push(*pat);
+            }
+        }
+        for (_, expr) in body.exprs.iter() {
+            match expr {
+                Expr::Match { expr, arms } if self.is_deref_of_reference(body, *expr) => {
+                    borrowed.extend(arms.iter().flat_map(|arm| arm.pats.iter().copied()));
+                }
+                Expr::Block { statements, .. } => {
+                    for stmt in statements {
+                        if let Statement::Let { pat, initializer: Some(init), .. } = stmt {
+                            if self.is_deref_of_reference(body, *init) {
+                                borrowed.push(*pat);
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        if borrowed.is_empty() {
+            return;
+        }
+
+        let resolver = self.func.resolver(db);
+        let copy_trait = match resolver.resolve_known_trait(db, &path![std::marker::Copy]) {
+            Some(it) => it,
+            None => return,
+        };
+        let krate = self.func.lookup(db).container.module(db).krate;
+        let mut moves = Vec::new();
+        for pat in borrowed {
+            collect_moving_bindings(body, pat, &mut moves);
+        }
+        let (_, source_map) = db.body_with_source_map(self.func.into());
+        let mut reported = FxHashSet::default();
+        for (name, pat) in moves {
+            if !reported.insert(pat) {
+                continue;
+            }
+            // scalars are always `Copy`, and references aren't moved
+            let ty = &self.infer[pat];
+            match ty {
+                Ty::Apply(ApplicationTy { ctor: TypeCtor::Adt(_), .. }) => {}
+                _ => continue,
+            }
+            if contains_unknown(ty) {
+                continue;
+            }
+            let canonical = Canonical { value: ty.clone(), num_vars: 0 };
+            if implements_trait(&canonical, db, &resolver, krate, copy_trait) {
+                continue;
+            }
+            let source = match source_map.pat_syntax(pat) {
+                Some(it) => it,
+                None => continue,
+            };
+            if let Some(ptr) = source.value.left() {
+                self.sink.push(MoveOutOfBorrow {
+                    file: source.file_id,
+                    pat: ptr,
+                    name,
+                    ty: ty.display(db).to_string(),
+                });
+            }
+        }
+    }
+
+    /// Whether the expression is `*e`, with `e` a reference. Dereferencing a
+    /// `Box` doesn't count, its content can be moved out.
+    fn is_deref_of_reference(&self, body: &Body, expr: ExprId) -> bool {
+        match &body[expr] {
+            Expr::UnaryOp { expr, op: UnaryOp::Deref } => match &self.infer[*expr] {
+                Ty::Apply(ApplicationTy { ctor: TypeCtor::Ref(_), .. }) => true,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn referenced_local(&self, body: &Body, id: ExprId, db: &impl HirDatabase) -> Option<Name> {
+        let mut place = match &body[id] {
+            Expr::Ref { expr, .. } => *expr,
+            _ => return None,
+        };
+        // field accesses stay inside of the local, unless they auto-deref
+        while let Expr::Field { expr, .. } = &body[place] {
+            if !is_owned(&self.infer[*expr]) {
+                return None;
+            }
+            place = *expr;
+        }
+        let path = match &body[place] {
+            Expr::Path(it) => it,
+            _ => return None,
+        };
+        let resolver = resolver_for_expr(db, self.func.into(), place);
+        let pat = match resolver.resolve_path_in_value_ns_fully(db, path.mod_path())? {
+            ValueNs::LocalBinding(it) => it,
+            _ => return None,
+        };
+        if !is_owned(&self.infer[place])
+            || body.params.iter().any(|&param| pat_contains(body, param, pat))
+        {
+            return None;
+        }
+        match &body[pat] {
+            Pat::Bind { name, .. } => Some(name.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Collects the operands of all `return` expressions of the function, which
+/// excludes the ones in closures.
+fn collect_returned_exprs(body: &Body, id: ExprId, acc: &mut Vec<ExprId>) {
+    match &body[id] {
+        Expr::Lambda { .. } => return,
+        Expr::Return { expr: Some(expr) } => acc.push(*expr),
+        _ => {}
+    }
+    body[id].walk_child_exprs(|it| collect_returned_exprs(body, it, acc));
+}
+
+fn pat_contains(body: &Body, pat: PatId, needle: PatId) -> bool {
+    if pat == needle {
+        return true;
+    }
+    let mut found = false;
+    body[pat].walk_child_pats(|it| found |= pat_contains(body, it, needle));
+    found
+}
+
+/// Whether a value of this type is stored inline, so that references to it
+/// point into the local holding it.
+fn is_owned(ty: &Ty) -> bool {
+    match ty {
+        Ty::Apply(ApplicationTy { ctor, .. }) => match ctor {
+            TypeCtor::Bool
+            | TypeCtor::Char
+            | TypeCtor::Int(_)
+            | TypeCtor::Float(_)
+            | TypeCtor::Adt(_)
+            | TypeCtor::Array { .. }
+            | TypeCtor::Tuple { .. } => true,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn as_float(ty: &Ty) -> Option<Uncertain<FloatTy>> {
+    match ty {
+        Ty::Apply(ApplicationTy { ctor: TypeCtor::Float(it), .. }) => Some(*it),
+        _ => None,
+    }
+}
+
+fn as_int(ty: &Ty) -> Option<IntTy> {
+    match ty {
+        Ty::Apply(ApplicationTy { ctor: TypeCtor::Int(Uncertain::Known(it)), .. }) => Some(*it),
+        _ => None,
+    }
+}
+
+/// The integer type given to the discriminants of an enum with `#[repr]`.
+fn enum_repr(db: &impl HirDatabase, ty: &Ty) -> Option<IntTy> {
+    match ty {
+        Ty::Apply(ApplicationTy { ctor: TypeCtor::Adt(AdtId::EnumId(it)), .. }) => {
+            db.enum_data(*it).repr.map(IntTy::from)
+        }
+        _ => None,
+    }
+}
+
+/// The size of the integer type, if it doesn't depend on the target.
+fn int_bits(ty: IntTy) -> Option<u32> {
+    match ty.bitness {
+        IntBitness::Xsize => None,
+        IntBitness::X8 => Some(8),
+        IntBitness::X16 => Some(16),
+        IntBitness::X32 => Some(32),
+        IntBitness::X64 => Some(64),
+        IntBitness::X128 => Some(128),
+    }
+}
+
+fn contains_unknown(ty: &Ty) -> bool {
+    let mut res = false;
+    ty.walk(&mut |it| res |= *it == Ty::Unknown);
+    res
+}
+
+/// Whether the expression is a literal, possibly negated, like `-1`.
+fn is_literal(body: &Body, expr: ExprId) -> bool {
+    match &body[expr] {
+        Expr::Literal(_) => true,
+        Expr::UnaryOp { expr, op: UnaryOp::Neg } => is_literal(body, *expr),
+        _ => false,
+    }
+}
+
+fn as_enum(ty: &Ty) -> Option<EnumId> {
+    match ty {
+        Ty::Apply(ApplicationTy { ctor: TypeCtor::Adt(AdtId::EnumId(it)), .. }) => Some(*it),
+        _ => None,
+    }
+}
+
+fn collect_bindings(body: &Body, pat: PatId, acc: &mut Vec<(Name, PatId)>) {
+    if let Pat::Bind { name, .. } = &body[pat] {
+        acc.push((name.clone(), pat));
+    }
+    body[pat].walk_child_pats(|it| collect_bindings(body, it, acc));
+}
+
+/// Collects the bindings of the pattern which move the matched value. The
+/// patterns under a nested `&` pattern are collected on their own.
+fn collect_moving_bindings(body: &Body, pat: PatId, acc: &mut Vec<(Name, PatId)>) {
+    match &body[pat] {
+        Pat::Bind { name, mode: BindingAnnotation::Unannotated, .. } => {
+            acc.push((name.clone(), pat))
+        }
+        Pat::Bind { .. } | Pat::Ref { .. } => {}
+        it => it.walk_child_pats(|it| collect_moving_bindings(body, it, acc)),
+    }
+}
+
+/// Whether `name` is used as a plain path anywhere in the expression `expr`.
+fn mentions_name(body: &Body, expr: ExprId, name: &Name) -> bool {
+    if let Expr::Path(path) = &body[expr] {
+        if path.mod_path().as_ident() == Some(name) {
+            return true;
+        }
+    }
+    let mut res = false;
+    body[expr].walk_child_exprs(|it| res = res || mentions_name(body, it, name));
+    res
+}
+
+/// The first segment of `path`, if it is a name which several glob imports
+/// bring into scope. Names used on their own are looked up among the values
+/// if `value_ns` is set, the first segment of longer paths is always a type or
+/// a module.
+fn ambiguous_name<'a>(resolver: &Resolver, path: &'a ModPath, value_ns: bool) -> Option<&'a Name> {
+    if path.kind != PathKind::Plain {
+        return None;
+    }
+    let first = path.segments.first()?;
+    let ambiguous = if value_ns && path.segments.len() == 1 {
+        resolver.is_value_ambiguous(first)
+    } else {
+        resolver.is_type_ambiguous(first)
+    };
+    if ambiguous {
+        Some(first)
+    } else {
+        None
+    }
 }
@@ -0,0 +1,200 @@
+//! Checks that the methods of a trait impl match their declarations in the
+//! trait. Only structural mismatches are reported: a different number of
+//! parameters, or a return type which can't be the one of the trait whatever
+//! the associated types and lifetimes are. Also checks that the implementing
+//! type implements the super traits of the trait.
+
+use std::sync::Arc;
+
+use hir_def::{
+    lang_item::LangItemTarget, resolver::HasResolver, src::HasSource, AssocItemId, FunctionId,
+    ImplId, Lookup,
+};
+use hir_expand::diagnostics::DiagnosticSink;
+use ra_syntax::{
+    ast::{self, AstNode, NameOwner},
+    AstPtr, TextRange, T,
+};
+
+use crate::{
+    db::HirDatabase,
+    diagnostics::{MissingSuperTraitImpls, SignatureMismatch, TraitMethodMismatch},
+    display::HirDisplay,
+    utils::generics,
+    CallableDef, Canonical, GenericPredicate, InEnvironment, Obligation, Substs, TraitEnvironment,
+    Ty, TypeWalk,
+};
+
+pub fn validate_trait_impl(db: &impl HirDatabase, impl_id: ImplId, sink: &mut DiagnosticSink) {
+    let trait_ref = match db.impl_trait(impl_id) {
+        Some(it) => it,
+        None => return,
+    };
+    let trait_data = db.trait_data(trait_ref.trait_);
+    for item in db.impl_data(impl_id).items.iter() {
+        let func = match item {
+            AssocItemId::FunctionId(it) => *it,
+            _ => continue,
+        };
+        let name = db.function_data(func).name.clone();
+        let trait_func = trait_data.items.iter().find_map(|(item_name, item)| match item {
+            AssocItemId::FunctionId(it) if *item_name == name => Some(*it),
+            _ => None,
+        });
+        let trait_func = match trait_func {
+            Some(it) => it,
+            None => continue,
+        };
+        if let Some(mismatch) = signature_mismatch(db, func, trait_func, &trait_ref.substs) {
+            let src = func.lookup(db).source(db);
+            let range = signature_range(&src.value);
+            sink.push(TraitMethodMismatch {
+                file: src.file_id,
+                fn_def: AstPtr::new(&src.value),
+                signature_range: range,
+                name,
+                trait_name: trait_data.name.clone(),
+                mismatch,
+            });
+        }
+    }
+}
+
+pub fn validate_super_traits(db: &impl HirDatabase, impl_id: ImplId, sink: &mut DiagnosticSink) {
+    let trait_ref = match db.impl_trait(impl_id) {
+        Some(it) => it,
+        None => return,
+    };
+    if db.impl_data(impl_id).is_negative {
+        return;
+    }
+    let krate = impl_id.lookup(db).container.module(db).krate;
+    // Chalk doesn't know which types are sized
+    let sized_trait = match db.lang_item(krate, "sized".into()) {
+        Some(LangItemTarget::TraitId(it)) => Some(it),
+        _ => None,
+    };
+    let env = TraitEnvironment::lower(db, &impl_id.resolver(db));
+    let missing: Vec<_> = db
+        .generic_predicates(trait_ref.trait_.into())
+        .iter()
+        .filter_map(|pred| match pred {
+            GenericPredicate::Implemented(super_trait) => match super_trait.self_ty() {
+                Ty::Param { idx: 0, .. } if Some(super_trait.trait_) != sized_trait => {
+                    Some(super_trait.clone().subst(&trait_ref.substs))
+                }
+                _ => None,
+            },
+            _ => None,
+        })
+        .filter(|super_trait| {
+            // don't guess about types which failed to resolve
+            let mut has_unknown = false;
+            super_trait.walk(&mut |ty| has_unknown |= *ty == Ty::Unknown);
+            !has_unknown
+        })
+        .filter(|super_trait| {
+            let obligation = Obligation::Trait(super_trait.clone());
+            let goal =
+                Canonical { num_vars: 0, value: InEnvironment::new(Arc::clone(&env), obligation) };
+            db.trait_solve(krate, goal).is_none()
+        })
+        .map(|super_trait| db.trait_data(super_trait.trait_).name.clone())
+        .collect();
+    if missing.is_empty() {
+        return;
+    }
+
+    let src = impl_id.lookup(db).source(db);
+    let trait_ref_ast = match src.value.target_trait() {
+        Some(it) => it,
+        None => return,
+    };
+    sink.push(MissingSuperTraitImpls {
+        file: src.file_id,
+        trait_ref: AstPtr::new(&trait_ref_ast),
+        trait_name: db.trait_data(trait_ref.trait_).name.clone(),
+        self_ty: trait_ref.self_ty().display(db).to_string(),
+        missing,
+    });
+}
+
+fn signature_mismatch(
+    db: &impl HirDatabase,
+    func: FunctionId,
+    trait_func: FunctionId,
+    trait_substs: &Substs,
+) -> Option<SignatureMismatch> {
+    let data = db.function_data(func);
+    let trait_data = db.function_data(trait_func);
+    if data.has_self_param != trait_data.has_self_param {
+        return Some(SignatureMismatch::SelfParam { in_impl: data.has_self_param });
+    }
+    if data.params.len() != trait_data.params.len() {
+        return Some(SignatureMismatch::ParamCount {
+            expected: trait_data.params.len(),
+            found: data.params.len(),
+        });
+    }
+
+    // The parameters of the trait method are the ones of the trait, including
+    // `Self`, followed by the ones of the method, which are matched by position
+    // with the ones of the impl method.
+    let impl_generics = generics(db, func.into());
+    let (_, impl_parent_len, own_len) = impl_generics.len_split();
+    let (_, _, trait_own_len) = generics(db, trait_func.into()).len_split();
+    if own_len != trait_own_len {
+        return None;
+    }
+    let own_params = impl_generics
+        .iter()
+        .skip(impl_parent_len)
+        .map(|(idx, param)| Ty::Param { idx, name: param.name.clone() });
+    let substs = Substs(trait_substs.iter().cloned().chain(own_params).collect());
+
+    let sig = db.callable_item_signature(CallableDef::FunctionId(func));
+    let trait_sig = db.callable_item_signature(CallableDef::FunctionId(trait_func));
+    let expected = trait_sig.ret().clone().subst(&substs);
+    let found = sig.ret();
+    if obviously_different(&expected, found) {
+        return Some(SignatureMismatch::ReturnType {
+            expected: expected.display(db).to_string(),
+            found: found.display(db).to_string(),
+        });
+    }
+    None
+}
+
+/// Whether two types differ in a way that can't be explained by projections,
+/// `impl Trait` or types which failed to resolve.
+fn obviously_different(ty1: &Ty, ty2: &Ty) -> bool {
+    match (ty1, ty2) {
+        (Ty::Apply(a_ty1), Ty::Apply(a_ty2)) => {
+            !a_ty1.ctor.unifies_with(a_ty2.ctor)
+                || a_ty1
+                    .parameters
+                    .iter()
+                    .zip(a_ty2.parameters.iter())
+                    .any(|(ty1, ty2)| obviously_different(ty1, ty2))
+        }
+        (Ty::Param { idx: idx1, .. }, Ty::Param { idx: idx2, .. }) => idx1 != idx2,
+        (Ty::Apply(_), Ty::Param { .. }) | (Ty::Param { .. }, Ty::Apply(_)) => true,
+        _ => false,
+    }
+}
+
+/// The range from the `fn` keyword to the end of the return type.
+fn signature_range(fn_def: &ast::FnDef) -> TextRange {
+    let start = fn_def
+        .syntax()
+        .children_with_tokens()
+        .find(|it| it.kind() == T![fn])
+        .map_or(fn_def.syntax().text_range().start(), |it| it.text_range().start());
+    let end = fn_def
+        .ret_type()
+        .map(|it| it.syntax().text_range())
+        .or_else(|| fn_def.param_list().map(|it| it.syntax().text_range()))
+        .or_else(|| fn_def.name().map(|it| it.syntax().text_range()))
+        .map_or(fn_def.syntax().text_range().end(), |it| it.end());
+    TextRange::from_to(start, end)
+}
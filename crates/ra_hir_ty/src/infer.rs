@@ -23,11 +23,11 @@ use rustc_hash::FxHashMap;
 use hir_def::{
     body::Body,
     data::{ConstData, FunctionData},
-    expr::{BindingAnnotation, ExprId, PatId},
+    expr::{ArithOp, BindingAnnotation, ExprId, PatId},
     path::{path, Path},
     resolver::{HasResolver, Resolver, TypeNs},
     type_ref::{Mutability, TypeRef},
-    AdtId, AssocItemId, DefWithBodyId, FunctionId, StructFieldId, TypeAliasId, VariantId,
+    AdtId, AssocItemId, DefWithBodyId, FunctionId, StructFieldId, TraitId, TypeAliasId, VariantId,
 };
 use hir_expand::{diagnostics::DiagnosticSink, name::name};
 use ra_arena::map::ArenaMap;
@@ -67,7 +67,7 @@ pub fn infer_query(db: &impl HirDatabase, def: DefWithBodyId) -> Arc<InferenceRe
 
     match def {
         DefWithBodyId::ConstId(c) => ctx.collect_const(&db.const_data(c)),
-        DefWithBodyId::FunctionId(f) => ctx.collect_fn(&db.function_data(f)),
+        DefWithBodyId::FunctionId(f) => ctx.collect_fn(f, &db.function_data(f)),
         DefWithBodyId::StaticId(s) => ctx.collect_const(&db.static_data(s)),
     }
 
@@ -185,6 +185,14 @@ impl Index<PatId> for InferenceResult {
     }
 }
 
+#[derive(Clone, Debug)]
+struct BreakableContext {
+    /// Whether a `break` targets the loop.
+    may_break: bool,
+    /// The type of the values the loop is broken with.
+    break_ty: Ty,
+}
+
 /// The inference context contains all information needed during type inference.
 #[derive(Clone, Debug)]
 struct InferenceContext<'a, D: HirDatabase> {
@@ -203,6 +211,8 @@ struct InferenceContext<'a, D: HirDatabase> {
     /// closures, but currently this is the only field that will change there,
     /// so it doesn't make sense.
     return_ty: Ty,
+    /// The loops enclosing the expression being inferred, innermost last.
+    breakables: Vec<BreakableContext>,
 
     /// Impls of `CoerceUnsized` used in coercion.
     /// (from_ty_ctor, to_ty_ctor) => coerce_generic_index
@@ -218,6 +228,7 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
             table: unify::InferenceTable::new(),
             obligations: Vec::default(),
             return_ty: Ty::Unknown, // set in collect_fn_signature
+            breakables: Vec::new(),
             trait_env: TraitEnvironment::lower(db, &resolver),
             coerce_unsized_map: Self::init_coerce_unsized_map(db, &resolver),
             db,
@@ -412,13 +423,49 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
     }
 
     fn normalize_projection_ty(&mut self, proj_ty: ProjectionTy) -> Ty {
+        let on_param = is_projection_on_param(&proj_ty);
+        if on_param {
+            if let Some(ty) = self.trait_env.projection_binding(&proj_ty) {
+                return ty.clone();
+            }
+        }
         let var = self.table.new_type_var();
-        let predicate = ProjectionPredicate { projection_ty: proj_ty, ty: var.clone() };
+        let predicate = ProjectionPredicate { projection_ty: proj_ty.clone(), ty: var.clone() };
         let obligation = Obligation::Projection(predicate);
+        if on_param {
+            return self.normalize_projection_ty_on_param(proj_ty, obligation, var);
+        }
         self.obligations.push(obligation);
         var
     }
 
+    /// The trait solver can normalize projections on type parameters like
+    /// `T::Item` in `fn foo<T: Iterator>()` only if an impl applies to all
+    /// types, e.g. a blanket impl. Otherwise we keep them as they are, so that
+    /// they at least stand for the same unknown type everywhere.
+    fn normalize_projection_ty_on_param(
+        &mut self,
+        proj_ty: ProjectionTy,
+        obligation: Obligation,
+        var: Ty,
+    ) -> Ty {
+        let in_env = InEnvironment::new(self.trait_env.clone(), obligation);
+        let canonicalized = self.canonicalizer().canonicalize_obligation(in_env);
+        let solution =
+            self.db.trait_solve(self.resolver.krate().unwrap().into(), canonicalized.value.clone());
+        match solution {
+            Some(Solution::Unique(substs)) => canonicalized.apply_solution(self, substs.0),
+            _ => return Ty::Projection(proj_ty),
+        }
+        match self.table.resolve_ty_as_possible(var) {
+            // the solver's placeholder for the projection
+            Ty::Apply(ApplicationTy { ctor: TypeCtor::AssociatedType(_), .. }) => {
+                Ty::Projection(proj_ty)
+            }
+            ty => ty,
+        }
+    }
+
     fn resolve_variant(&mut self, path: Option<&Path>) -> (Ty, Option<VariantId>) {
         let path = match path {
             Some(path) => path,
@@ -448,10 +495,15 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
         self.return_ty = self.make_ty(&data.type_ref);
     }
 
-    fn collect_fn(&mut self, data: &FunctionData) {
+    fn collect_fn(&mut self, func: FunctionId, data: &FunctionData) {
         let body = Arc::clone(&self.body); // avoid borrow checker problem
-        for (type_ref, pat) in data.params.iter().zip(body.params.iter()) {
-            let ty = self.make_ty(type_ref);
+
+        // the signature has the anonymous type parameters of `impl Trait`
+        // arguments, rather than the `impl Trait` types
+        let sig = self.db.callable_item_signature(func.into());
+        for (ty, pat) in sig.params().iter().zip(body.params.iter()) {
+            let ty = self.insert_type_vars(ty.clone());
+            let ty = self.normalize_associated_types_in(ty);
 
             self.infer_pat(*pat, &ty, BindingMode::default());
         }
@@ -487,6 +539,25 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
         self.db.trait_data(trait_).associated_type_by_name(&name![Output])
     }
 
+    /// The `Output` type of the operator trait of `op`, like `std::ops::Add`
+    /// for `+`.
+    fn resolve_ops_arith_output(&self, op: ArithOp) -> Option<TypeAliasId> {
+        let path = match op {
+            ArithOp::Add => path![std::ops::Add],
+            ArithOp::Sub => path![std::ops::Sub],
+            ArithOp::Mul => path![std::ops::Mul],
+            ArithOp::Div => path![std::ops::Div],
+            ArithOp::Rem => path![std::ops::Rem],
+            ArithOp::Shl => path![std::ops::Shl],
+            ArithOp::Shr => path![std::ops::Shr],
+            ArithOp::BitXor => path![std::ops::BitXor],
+            ArithOp::BitOr => path![std::ops::BitOr],
+            ArithOp::BitAnd => path![std::ops::BitAnd],
+        };
+        let trait_ = self.resolver.resolve_known_trait(self.db, &path)?;
+        self.db.trait_data(trait_).associated_type_by_name(&name![Output])
+    }
+
     fn resolve_future_future_output(&self) -> Option<TypeAliasId> {
         let path = path![std::future::Future];
         let trait_ = self.resolver.resolve_known_trait(self.db, &path)?;
@@ -535,13 +606,33 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
         Some(struct_.into())
     }
 
-    fn resolve_ops_index_output(&self) -> Option<TypeAliasId> {
+    fn resolve_ops_index(&self) -> Option<TraitId> {
         let path = path![std::ops::Index];
-        let trait_ = self.resolver.resolve_known_trait(self.db, &path)?;
+        self.resolver.resolve_known_trait(self.db, &path)
+    }
+
+    fn resolve_ops_index_output(&self) -> Option<TypeAliasId> {
+        let trait_ = self.resolve_ops_index()?;
         self.db.trait_data(trait_).associated_type_by_name(&name![Output])
     }
 }
 
+/// Whether the projection is on a type parameter without inference variables,
+/// e.g. `T::Item`.
+fn is_projection_on_param(proj_ty: &ProjectionTy) -> bool {
+    match proj_ty.parameters.get(0) {
+        Some(Ty::Param { .. }) => {}
+        _ => return false,
+    }
+    let mut has_type_vars = false;
+    proj_ty.walk(&mut |ty| {
+        if let Ty::Infer(_) = ty {
+            has_type_vars = true;
+        }
+    });
+    !has_type_vars
+}
+
 /// The kinds of placeholders we need during type inference. There's separate
 /// values for general types, and for integer and float variables. The latter
 /// two are used for inference of literal values (e.g. `100` could be one of
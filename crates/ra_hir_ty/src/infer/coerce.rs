@@ -200,7 +200,7 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
 
         match (&from_ty, &to_ty) {
             // `[T; N]` -> `[T]`
-            (ty_app!(TypeCtor::Array, st1), ty_app!(TypeCtor::Slice, st2)) => {
+            (ty_app!(TypeCtor::Array { .. }, st1), ty_app!(TypeCtor::Slice, st2)) => {
                 Some(self.unify(&st1[0], &st2[0]))
             }
 
@@ -332,7 +332,9 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
             let derefed_ty = canonicalized.decanonicalize_ty(derefed_ty.value);
             match (&*self.resolve_ty_shallow(&derefed_ty), &*to_ty) {
                 // Stop when constructor matches.
-                (ty_app!(from_ctor, st1), ty_app!(to_ctor, st2)) if from_ctor == to_ctor => {
+                (ty_app!(from_ctor, st1), ty_app!(to_ctor, st2))
+                    if from_ctor.unifies_with(*to_ctor) =>
+                {
                     // It will not recurse to `coerce`.
                     return self.table.unify_substs(st1, st2, 0);
                 }
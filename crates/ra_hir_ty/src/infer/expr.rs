@@ -8,22 +8,24 @@ use hir_def::{
     expr::{Array, BinaryOp, Expr, ExprId, Literal, Statement, UnaryOp},
     path::{GenericArg, GenericArgs},
     resolver::resolver_for_expr,
-    AdtId, AssocContainerId, Lookup, StructFieldId,
+    AdtId, AssocContainerId, Lookup, StructFieldId, TraitId,
 };
 use hir_expand::name::{name, Name};
 use ra_syntax::ast::RangeOp;
 
 use crate::{
-    autoderef,
+    autoderef, consteval,
     db::HirDatabase,
     method_resolution, op,
-    traits::InEnvironment,
+    traits::{FnTrait, InEnvironment},
     utils::{generics, variant_data, Generics},
-    ApplicationTy, CallableDef, InferTy, IntTy, Mutability, Obligation, Substs, TraitRef, Ty,
-    TypeCtor, TypeWalk, Uncertain,
+    ApplicationTy, CallableDef, GenericPredicate, InferTy, IntTy, Mutability, Obligation, Substs,
+    TraitRef, Ty, TypeCtor, TypeWalk, Uncertain,
 };
 
-use super::{BindingMode, Expectation, InferenceContext, InferenceDiagnostic, TypeMismatch};
+use super::{
+    BindingMode, BreakableContext, Expectation, InferenceContext, InferenceDiagnostic, TypeMismatch,
+};
 
 impl<'a, D: HirDatabase> InferenceContext<'a, D> {
     pub(super) fn infer_expr(&mut self, tgt_expr: ExprId, expected: &Expectation) -> Ty {
@@ -82,14 +84,25 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
                 Ty::Unknown
             }
             Expr::Loop { body } => {
+                self.breakables.push(BreakableContext {
+                    may_break: false,
+                    break_ty: self.table.new_type_var(),
+                });
                 self.infer_expr(*body, &Expectation::has_type(Ty::unit()));
-                // FIXME handle break with value
-                Ty::simple(TypeCtor::Never)
+                let ctxt = self.breakables.pop().expect("breakable stack broken");
+                // a loop which is never broken out of diverges
+                if ctxt.may_break {
+                    ctxt.break_ty
+                } else {
+                    Ty::simple(TypeCtor::Never)
+                }
             }
             Expr::While { condition, body } => {
                 // while let is desugared to a match loop, so this is always simple while
                 self.infer_expr(*condition, &Expectation::has_type(Ty::simple(TypeCtor::Bool)));
+                self.breakables.push(BreakableContext { may_break: false, break_ty: Ty::unit() });
                 self.infer_expr(*body, &Expectation::has_type(Ty::unit()));
+                self.breakables.pop().expect("breakable stack broken");
                 Ty::unit()
             }
             Expr::For { iterable, body, pat } => {
@@ -99,7 +112,9 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
                     self.resolve_associated_type(iterable_ty, self.resolve_into_iter_item());
 
                 self.infer_pat(*pat, &pat_ty, BindingMode::default());
+                self.breakables.push(BreakableContext { may_break: false, break_ty: Ty::unit() });
                 self.infer_expr(*body, &Expectation::has_type(Ty::unit()));
+                self.breakables.pop().expect("breakable stack broken");
                 Ty::unit()
             }
             Expr::Lambda { body, args, ret_type, arg_types } => {
@@ -138,17 +153,31 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
                 self.coerce(&closure_ty, &expected.ty);
 
                 let prev_ret_ty = std::mem::replace(&mut self.return_ty, ret_ty.clone());
+                // `break` can't leave the closure
+                let prev_breakables = std::mem::replace(&mut self.breakables, Vec::new());
 
                 self.infer_expr_coerce(*body, &Expectation::has_type(ret_ty));
 
                 self.return_ty = prev_ret_ty;
+                self.breakables = prev_breakables;
 
                 closure_ty
             }
             Expr::Call { callee, args } => {
                 let callee_ty = self.infer_expr(*callee, &Expectation::none());
-                let (param_tys, ret_ty) = match callee_ty.callable_sig(self.db) {
-                    Some(sig) => (sig.params().to_vec(), sig.ret().clone()),
+                let canonicalized = self.canonicalizer().canonicalize_ty(callee_ty.clone());
+                let mut derefs = autoderef::autoderef(
+                    self.db,
+                    self.resolver.krate(),
+                    InEnvironment {
+                        value: canonicalized.value.clone(),
+                        environment: self.trait_env.clone(),
+                    },
+                );
+                let (param_tys, ret_ty) = match derefs
+                    .find_map(|it| self.callable_sig(&canonicalized.decanonicalize_ty(it.value)))
+                {
+                    Some(sig) => sig,
                     None => {
                         // Not callable
                         // FIXME: report an error
@@ -191,9 +220,18 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
             }
             Expr::Continue => Ty::simple(TypeCtor::Never),
             Expr::Break { expr } => {
-                if let Some(expr) = expr {
-                    // FIXME handle break with value
-                    self.infer_expr(*expr, &Expectation::none());
+                let last_ty = match self.breakables.last() {
+                    Some(ctxt) => ctxt.break_ty.clone(),
+                    None => Ty::Unknown,
+                };
+                let val_ty = match expr {
+                    Some(expr) => self.infer_expr(*expr, &Expectation::none()),
+                    None => Ty::unit(),
+                };
+                let merged_ty = self.coerce_merge_branch(&last_ty, &val_ty);
+                if let Some(ctxt) = self.breakables.last_mut() {
+                    ctxt.may_break = true;
+                    ctxt.break_ty = merged_ty;
                 }
                 Ty::simple(TypeCtor::Never)
             }
@@ -384,13 +422,29 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
                         _ => Expectation::none(),
                     };
                     let lhs_ty = self.infer_expr(*lhs, &lhs_expectation);
-                    // FIXME: find implementation of trait corresponding to operation
-                    // symbol and resolve associated `Output` type
-                    let rhs_expectation = op::binary_op_rhs_expectation(*op, lhs_ty);
-                    let rhs_ty = self.infer_expr(*rhs, &Expectation::has_type(rhs_expectation));
-
-                    // FIXME: similar as above, return ty is often associated trait type
-                    op::binary_op_return_ty(*op, rhs_ty)
+                    match op {
+                        BinaryOp::ArithOp(arith_op)
+                            if is_overloaded_operand(&self.resolve_ty_shallow(&lhs_ty)) =>
+                        {
+                            // resolve the `Output` of the operator trait, like std::ops::Add
+                            let rhs_ty = self.infer_expr(*rhs, &Expectation::none());
+                            self.resolve_associated_type_with_params(
+                                lhs_ty,
+                                self.resolve_ops_arith_output(*arith_op),
+                                &[rhs_ty],
+                            )
+                        }
+                        _ => {
+                            // FIXME: find implementation of trait corresponding to operation
+                            // symbol and resolve associated `Output` type
+                            let rhs_expectation = op::binary_op_rhs_expectation(*op, lhs_ty);
+                            let rhs_ty =
+                                self.infer_expr(*rhs, &Expectation::has_type(rhs_expectation));
+
+                            // FIXME: similar as above, return ty is often associated trait type
+                            op::binary_op_return_ty(*op, rhs_ty)
+                        }
+                    }
                 }
                 _ => Ty::Unknown,
             },
@@ -436,6 +490,7 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
                 let base_ty = self.infer_expr_inner(*base, &Expectation::none());
                 let index_ty = self.infer_expr(*index, &Expectation::none());
 
+                let base_ty = self.autoderef_index_base(base_ty, &index_ty);
                 self.resolve_associated_type_with_params(
                     base_ty,
                     self.resolve_ops_index_output(),
@@ -461,17 +516,18 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
             }
             Expr::Array(array) => {
                 let elem_ty = match &expected.ty {
-                    ty_app!(TypeCtor::Array, st) | ty_app!(TypeCtor::Slice, st) => {
+                    ty_app!(TypeCtor::Array { .. }, st) | ty_app!(TypeCtor::Slice, st) => {
                         st.as_single().clone()
                     }
                     _ => self.table.new_type_var(),
                 };
 
-                match array {
+                let len = match array {
                     Array::ElementList(items) => {
                         for expr in items.iter() {
                             self.infer_expr_coerce(*expr, &Expectation::has_type(elem_ty.clone()));
                         }
+                        Some(items.len() as u64)
                     }
                     Array::Repeat { initializer, repeat } => {
                         self.infer_expr_coerce(
@@ -484,10 +540,11 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
                                 IntTy::usize(),
                             )))),
                         );
+                        consteval::eval_expr(self.db, &self.resolver, &self.body, *repeat)
                     }
-                }
+                };
 
-                Ty::apply_one(TypeCtor::Array, elem_ty)
+                Ty::apply_one(TypeCtor::Array { len }, elem_ty)
             }
             Expr::Literal(lit) => match lit {
                 Literal::Bool(..) => Ty::simple(TypeCtor::Bool),
@@ -511,6 +568,40 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
         ty
     }
 
+    /// Like for method calls, the base of an index expression is dereferenced
+    /// until a type implementing `Index<index_ty>` is found, e.g. for
+    /// `&HashMap<K, V>`. Returns the base itself if there's none.
+    fn autoderef_index_base(&mut self, base_ty: Ty, index_ty: &Ty) -> Ty {
+        let (index_trait, krate) = match (self.resolve_ops_index(), self.resolver.krate()) {
+            (Some(index_trait), Some(krate)) => (index_trait, krate),
+            _ => return base_ty,
+        };
+        let canonicalized = self.canonicalizer().canonicalize_ty(base_ty.clone());
+        let derefed_tys: Vec<Ty> = autoderef::autoderef(
+            self.db,
+            Some(krate),
+            InEnvironment {
+                value: canonicalized.value.clone(),
+                environment: self.trait_env.clone(),
+            },
+        )
+        .map(|derefed_ty| canonicalized.decanonicalize_ty(derefed_ty.value))
+        .collect();
+        derefed_tys
+            .into_iter()
+            .find(|derefed_ty| {
+                let trait_ref = TraitRef {
+                    trait_: index_trait,
+                    substs: Substs(vec![derefed_ty.clone(), index_ty.clone()].into()),
+                };
+                let in_env =
+                    InEnvironment::new(self.trait_env.clone(), Obligation::Trait(trait_ref));
+                let canonicalized = self.canonicalizer().canonicalize_obligation(in_env);
+                self.db.trait_solve(krate.into(), canonicalized.value).is_some()
+            })
+            .unwrap_or(base_ty)
+    }
+
     fn infer_block(
         &mut self,
         statements: &[Statement],
@@ -674,6 +765,50 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
         Substs(substs.into())
     }
 
+    /// The parameter and return types of a call of a value of type `ty`: a
+    /// function, a closure, a function pointer or a `dyn Fn*` trait object.
+    fn callable_sig(&self, ty: &Ty) -> Option<(Vec<Ty>, Ty)> {
+        if let Some(sig) = ty.callable_sig(self.db) {
+            return Some((sig.params().to_vec(), sig.ret().clone()));
+        }
+        let predicates = match ty {
+            Ty::Dyn(predicates) => predicates,
+            _ => return None,
+        };
+        let krate = self.resolver.krate()?;
+        let fn_traits: Vec<TraitId> = [FnTrait::FnOnce, FnTrait::FnMut, FnTrait::Fn]
+            .iter()
+            .filter_map(|it| it.get_id(self.db, krate))
+            .collect();
+        let params = predicates.iter().find_map(|pred| match pred {
+            GenericPredicate::Implemented(tr) if fn_traits.contains(&tr.trait_) => {
+                match tr.substs.0.get(1)? {
+                    Ty::Apply(ApplicationTy { ctor: TypeCtor::Tuple { .. }, parameters }) => {
+                        Some(parameters.0.to_vec())
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        })?;
+        let output = FnTrait::FnOnce
+            .get_id(self.db, krate)
+            .and_then(|it| self.db.trait_data(it).associated_type_by_name(&name![Output]));
+        // `dyn Fn(T)` has no `Output` binding, and returns `()`
+        let ret = predicates
+            .iter()
+            .find_map(|pred| match pred {
+                GenericPredicate::Projection(proj)
+                    if Some(proj.projection_ty.associated_ty) == output =>
+                {
+                    Some(proj.ty.clone())
+                }
+                _ => None,
+            })
+            .unwrap_or_else(Ty::unit);
+        Some((params, ret))
+    }
+
     fn register_obligations_for_call(&mut self, callable_ty: &Ty) {
         if let Ty::Apply(a_ty) = callable_ty {
             if let TypeCtor::FnDef(def) = a_ty.ctor {
@@ -703,3 +838,16 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
         }
     }
 }
+
+/// Whether the operand of an arithmetic operator is of a type with an
+/// overloaded operator. The operators of the builtin numbers, and the ones of
+/// the operands we know nothing about yet, are inferred without the traits.
+fn is_overloaded_operand(ty: &Ty) -> bool {
+    match ty {
+        Ty::Apply(ApplicationTy { ctor: TypeCtor::Int(_), .. })
+        | Ty::Apply(ApplicationTy { ctor: TypeCtor::Float(_), .. })
+        | Ty::Infer(_)
+        | Ty::Unknown => false,
+        _ => true,
+    }
+}
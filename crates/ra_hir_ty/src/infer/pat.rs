@@ -82,7 +82,7 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
         let body = Arc::clone(&self.body); // avoid borrow checker problem
 
         let is_non_ref_pat = match &body[pat] {
-            Pat::Tuple(..)
+            Pat::Tuple { .. }
             | Pat::TupleStruct { .. }
             | Pat::Record { .. }
             | Pat::Range { .. }
@@ -112,20 +112,56 @@ impl<'a, D: HirDatabase> InferenceContext<'a, D> {
         let expected = expected;
 
         let ty = match &body[pat] {
-            Pat::Tuple(ref args) => {
+            Pat::Tuple { args, ellipsis } => {
                 let expectations = match expected.as_tuple() {
                     Some(parameters) => &*parameters.0,
                     _ => &[],
                 };
-                let expectations_iter = expectations.iter().chain(repeat(&Ty::Unknown));
+                let (before, after) = args.split_at(ellipsis.unwrap_or(args.len()));
+                // the fields the `..` stands for
+                let n_skipped = match ellipsis {
+                    Some(_) => expectations.len().saturating_sub(args.len()),
+                    None => 0,
+                };
+                let mut expectations_iter = expectations.iter().chain(repeat(&Ty::Unknown));
 
-                let inner_tys = args
-                    .iter()
-                    .zip(expectations_iter)
-                    .map(|(&pat, ty)| self.infer_pat(pat, ty, default_bm))
-                    .collect();
+                let mut inner_tys = Vec::with_capacity(args.len() + n_skipped);
+                for &pat in before {
+                    let ty = expectations_iter.next().unwrap();
+                    inner_tys.push(self.infer_pat(pat, ty, default_bm));
+                }
+                inner_tys.extend(expectations_iter.by_ref().take(n_skipped).cloned());
+                for &pat in after {
+                    let ty = expectations_iter.next().unwrap();
+                    inner_tys.push(self.infer_pat(pat, ty, default_bm));
+                }
 
-                Ty::apply(TypeCtor::Tuple { cardinality: args.len() as u16 }, Substs(inner_tys))
+                Ty::apply(
+                    TypeCtor::Tuple { cardinality: inner_tys.len() as u16 },
+                    Substs(inner_tys.into()),
+                )
+            }
+            Pat::Slice { prefix, rest, suffix } => {
+                let (container, elem_ty) = match expected {
+                    ty_app!(ctor @ TypeCtor::Array { .. }, st)
+                    | ty_app!(ctor @ TypeCtor::Slice, st) => (*ctor, st.as_single().clone()),
+                    _ => (TypeCtor::Slice, Ty::Unknown),
+                };
+                for &pat in prefix.iter().chain(suffix) {
+                    self.infer_pat(pat, &elem_ty, default_bm);
+                }
+                // the rest of an array is an array too, of the remaining length
+                let rest_ctor = match container {
+                    TypeCtor::Array { len } => TypeCtor::Array {
+                        len: len.map(|it| it.saturating_sub((prefix.len() + suffix.len()) as u64)),
+                    },
+                    it => it,
+                };
+                let rest_ty = Ty::apply_one(rest_ctor, elem_ty.clone());
+                if let Some(rest) = rest {
+                    self.infer_pat(*rest, &rest_ty, default_bm);
+                }
+                Ty::apply_one(container, elem_ty)
             }
             Pat::Ref { pat, mutability } => {
                 let expectation = match expected.as_reference() {
@@ -238,7 +238,7 @@ impl InferenceTable {
         let ty1 = self.resolve_ty_shallow(ty1);
         let ty2 = self.resolve_ty_shallow(ty2);
         match (&*ty1, &*ty2) {
-            (Ty::Apply(a_ty1), Ty::Apply(a_ty2)) if a_ty1.ctor == a_ty2.ctor => {
+            (Ty::Apply(a_ty1), Ty::Apply(a_ty2)) if a_ty1.ctor.unifies_with(a_ty2.ctor) => {
                 self.unify_substs(&a_ty1.parameters, &a_ty2.parameters, depth + 1)
             }
             _ => self.unify_inner_trivial(&ty1, &ty2),
@@ -26,12 +26,15 @@ pub mod traits;
 pub mod method_resolution;
 mod op;
 mod lower;
+mod consteval;
 mod infer;
 pub mod display;
 pub(crate) mod utils;
 pub mod db;
 pub mod diagnostics;
 pub mod expr;
+pub mod impl_validation;
+pub mod adt_validation;
 
 #[cfg(test)]
 mod tests;
@@ -90,8 +93,9 @@ pub enum TypeCtor {
     /// The pointee of an array slice.  Written as `[T]`.
     Slice,
 
-    /// An array with the given length. Written as `[T; n]`.
-    Array,
+    /// An array with the given length. Written as `[T; n]`. The length is
+    /// `None` if it could not be evaluated.
+    Array { len: Option<u64> },
 
     /// A raw pointer. Written as `*mut T` or `*const T`
     RawPtr(Mutability),
@@ -160,7 +164,7 @@ impl TypeCtor {
             | TypeCtor::Str
             | TypeCtor::Never => 0,
             TypeCtor::Slice
-            | TypeCtor::Array
+            | TypeCtor::Array { .. }
             | TypeCtor::RawPtr(_)
             | TypeCtor::Ref(_)
             | TypeCtor::Closure { .. } // 1 param representing the signature of the closure
@@ -191,7 +195,7 @@ impl TypeCtor {
             | TypeCtor::Str
             | TypeCtor::Never
             | TypeCtor::Slice
-            | TypeCtor::Array
+            | TypeCtor::Array { .. }
             | TypeCtor::RawPtr(_)
             | TypeCtor::Ref(_)
             | TypeCtor::FnPtr { .. }
@@ -213,7 +217,7 @@ impl TypeCtor {
             | TypeCtor::Str
             | TypeCtor::Never
             | TypeCtor::Slice
-            | TypeCtor::Array
+            | TypeCtor::Array { .. }
             | TypeCtor::RawPtr(_)
             | TypeCtor::Ref(_)
             | TypeCtor::FnPtr { .. }
@@ -224,6 +228,27 @@ impl TypeCtor {
             TypeCtor::AssociatedType(type_alias) => Some(type_alias.into()),
         }
     }
+
+    /// The constructor with the length of arrays erased, for the places which
+    /// can't tell arrays of different lengths apart, like chalk.
+    pub(crate) fn without_array_len(self) -> TypeCtor {
+        match self {
+            TypeCtor::Array { .. } => TypeCtor::Array { len: None },
+            it => it,
+        }
+    }
+
+    /// Whether types with these constructors can be unified. Arrays whose
+    /// length is unknown unify with arrays of any length.
+    pub(crate) fn unifies_with(self, other: TypeCtor) -> bool {
+        match (self, other) {
+            (TypeCtor::Array { len: Some(len1) }, TypeCtor::Array { len: Some(len2) }) => {
+                len1 == len2
+            }
+            (TypeCtor::Array { .. }, TypeCtor::Array { .. }) => true,
+            _ => self == other,
+        }
+    }
 }
 
 /// A nominal type with (maybe 0) type parameters. This might be a primitive
@@ -686,16 +711,18 @@ impl Ty {
         }
     }
 
-    /// If this is an `impl Trait` or `dyn Trait`, returns that trait.
-    pub fn inherent_trait(&self) -> Option<TraitId> {
+    /// If this is an `impl Trait` or `dyn Trait`, returns the traits of its
+    /// bounds, e.g. both traits of `impl Iterator + Clone`.
+    pub fn inherent_traits(&self) -> Vec<TraitId> {
         match self {
-            Ty::Dyn(predicates) | Ty::Opaque(predicates) => {
-                predicates.iter().find_map(|pred| match pred {
+            Ty::Dyn(predicates) | Ty::Opaque(predicates) => predicates
+                .iter()
+                .filter_map(|pred| match pred {
                     GenericPredicate::Implemented(tr) => Some(tr.trait_),
                     _ => None,
                 })
-            }
-            _ => None,
+                .collect(),
+            _ => Vec::new(),
         }
     }
 }
@@ -843,9 +870,12 @@ impl HirDisplay for ApplicationTy {
                 let t = self.parameters.as_single();
                 write!(f, "[{}]", t.display(f.db))?;
             }
-            TypeCtor::Array => {
+            TypeCtor::Array { len } => {
                 let t = self.parameters.as_single();
-                write!(f, "[{};_]", t.display(f.db))?;
+                match len {
+                    Some(len) => write!(f, "[{}; {}]", t.display(f.db), len)?,
+                    None => write!(f, "[{}; _]", t.display(f.db))?,
+                }
             }
             TypeCtor::RawPtr(m) => {
                 let t = self.parameters.as_single();
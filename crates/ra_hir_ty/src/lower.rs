@@ -10,17 +10,19 @@ use std::sync::Arc;
 
 use hir_def::{
     builtin_type::BuiltinType,
-    generics::WherePredicate,
+    generics::{TypeParamProvenance, WherePredicate, WherePredicateTarget},
     path::{GenericArg, Path, PathSegment, PathSegments},
     resolver::{HasResolver, Resolver, TypeNs},
     type_ref::{TypeBound, TypeRef},
-    AdtId, ConstId, EnumId, EnumVariantId, FunctionId, GenericDefId, HasModule, ImplId,
-    LocalStructFieldId, Lookup, StaticId, StructId, TraitId, TypeAliasId, UnionId, VariantId,
+    AdtId, AssocContainerId, ConstId, EnumId, EnumVariantId, FunctionId, GenericDefId, HasModule,
+    ImplId, LocalStructFieldId, Lookup, StaticId, StructId, TraitId, TypeAliasId, TypeParamId,
+    UnionId, VariantId,
 };
 use ra_arena::map::ArenaMap;
 use ra_db::CrateId;
 
 use crate::{
+    consteval,
     db::HirDatabase,
     primitive::{FloatTy, IntTy},
     utils::{
@@ -48,9 +50,10 @@ impl Ty {
                 let inner_ty = Ty::from_hir(db, resolver, inner);
                 Ty::apply_one(TypeCtor::RawPtr(*mutability), inner_ty)
             }
-            TypeRef::Array(inner) => {
+            TypeRef::Array(inner, len) => {
                 let inner_ty = Ty::from_hir(db, resolver, inner);
-                Ty::apply_one(TypeCtor::Array, inner_ty)
+                let len = consteval::eval_array_len(db, resolver, len);
+                Ty::apply_one(TypeCtor::Array { len }, inner_ty)
             }
             TypeRef::Slice(inner) => {
                 let inner_ty = Ty::from_hir(db, resolver, inner);
@@ -242,11 +245,18 @@ impl Ty {
             None => return Ty::Unknown, // this can't actually happen
         };
         let predicates = db.generic_predicates_for_param(def.into(), param_idx);
-        let traits_from_env = predicates.iter().filter_map(|pred| match pred {
-            GenericPredicate::Implemented(tr) if tr.self_ty() == &self_ty => Some(tr.trait_),
-            _ => None,
-        });
-        let traits = traits_from_env.flat_map(|t| all_super_traits(db, t));
+        let mut traits_from_env = predicates
+            .iter()
+            .filter_map(|pred| match pred {
+                GenericPredicate::Implemented(tr) if tr.self_ty() == &self_ty => Some(tr.trait_),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        // `Self::Item` in a trait refers to the trait's own associated types
+        if param_idx == 0 {
+            traits_from_env.extend(containing_trait(db, def));
+        }
+        let traits = traits_from_env.into_iter().flat_map(|t| all_super_traits(db, t));
         for t in traits {
             if let Some(associated_ty) = db.trait_data(t).associated_type_by_name(&segment.name) {
                 let substs =
@@ -434,8 +444,16 @@ impl GenericPredicate {
         db: &'a impl HirDatabase,
         resolver: &'a Resolver,
         where_predicate: &'a WherePredicate,
+        def: GenericDefId,
     ) -> impl Iterator<Item = GenericPredicate> + 'a {
-        let self_ty = Ty::from_hir(db, resolver, &where_predicate.type_ref);
+        let self_ty = match &where_predicate.target {
+            WherePredicateTarget::TypeRef(type_ref) => Ty::from_hir(db, resolver, type_ref),
+            WherePredicateTarget::TypeParam(local_id) => {
+                let param_id = TypeParamId { parent: def, local_id: *local_id };
+                let generics = generics(db, def);
+                Ty::Param { idx: generics.param_idx(param_id), name: generics.param_name(param_id) }
+            }
+        };
         GenericPredicate::from_type_bound(db, resolver, &where_predicate.bound, self_ty)
     }
 
@@ -484,6 +502,22 @@ fn assoc_type_bindings_from_type_bound<'a>(
         })
 }
 
+/// The trait `def` is, or is an item of. Its `Self` is the first type parameter
+/// of `def`.
+fn containing_trait(db: &impl HirDatabase, def: GenericDefId) -> Option<TraitId> {
+    let container = match def {
+        GenericDefId::TraitId(it) => return Some(it),
+        GenericDefId::FunctionId(it) => it.lookup(db).container,
+        GenericDefId::TypeAliasId(it) => it.lookup(db).container,
+        GenericDefId::ConstId(it) => it.lookup(db).container,
+        _ => return None,
+    };
+    match container {
+        AssocContainerId::TraitId(it) => Some(it),
+        _ => None,
+    }
+}
+
 /// Build the signature of a callable item (function, struct or enum variant).
 pub fn callable_item_sig(db: &impl HirDatabase, def: CallableDef) -> FnSig {
     match def {
@@ -528,8 +562,16 @@ pub(crate) fn generic_predicates_for_param_query(
     resolver
         .where_predicates_in_scope()
         // we have to filter out all other predicates *first*, before attempting to lower them
-        .filter(|pred| Ty::from_hir_only_param(db, &resolver, &pred.type_ref) == Some(param_idx))
-        .flat_map(|pred| GenericPredicate::from_where_predicate(db, &resolver, pred))
+        .filter(|(pred, pred_def)| match &pred.target {
+            WherePredicateTarget::TypeRef(type_ref) => {
+                Ty::from_hir_only_param(db, &resolver, type_ref) == Some(param_idx)
+            }
+            WherePredicateTarget::TypeParam(local_id) => {
+                let param_id = TypeParamId { parent: *pred_def, local_id: *local_id };
+                generics(db, *pred_def).param_idx(param_id) == param_idx
+            }
+        })
+        .flat_map(|(pred, def)| GenericPredicate::from_where_predicate(db, &resolver, pred, def))
         .collect()
 }
 
@@ -544,15 +586,37 @@ pub(crate) fn generic_predicates_for_param_recover(
 
 impl TraitEnvironment {
     pub fn lower(db: &impl HirDatabase, resolver: &Resolver) -> Arc<TraitEnvironment> {
-        let predicates = resolver
+        let mut predicates = resolver
             .where_predicates_in_scope()
-            .flat_map(|pred| GenericPredicate::from_where_predicate(db, &resolver, pred))
+            .flat_map(|(pred, def)| {
+                GenericPredicate::from_where_predicate(db, &resolver, pred, def)
+            })
             .collect::<Vec<_>>();
 
+        // in the items of a trait, `Self` implements the trait
+        if let Some(trait_) = resolver.generic_def().and_then(|def| containing_trait(db, def)) {
+            let substs = Substs::identity(&generics(db, trait_.into()));
+            predicates.push(GenericPredicate::Implemented(TraitRef { trait_, substs }));
+        }
+
         Arc::new(TraitEnvironment { predicates })
     }
 }
 
+fn containing_trait(db: &impl HirDatabase, def: GenericDefId) -> Option<TraitId> {
+    let container = match def {
+        GenericDefId::TraitId(it) => return Some(it),
+        GenericDefId::FunctionId(it) => it.lookup(db).container,
+        GenericDefId::TypeAliasId(it) => it.lookup(db).container,
+        GenericDefId::ConstId(it) => it.lookup(db).container,
+        _ => return None,
+    };
+    match container {
+        AssocContainerId::TraitId(it) => Some(it),
+        _ => None,
+    }
+}
+
 /// Resolve the where clause(s) of an item with generics.
 pub(crate) fn generic_predicates_query(
     db: &impl HirDatabase,
@@ -561,7 +625,7 @@ pub(crate) fn generic_predicates_query(
     let resolver = def.resolver(db);
     resolver
         .where_predicates_in_scope()
-        .flat_map(|pred| GenericPredicate::from_where_predicate(db, &resolver, pred))
+        .flat_map(|(pred, def)| GenericPredicate::from_where_predicate(db, &resolver, pred, def))
         .collect()
 }
 
@@ -582,10 +646,64 @@ fn fn_sig_for_fn(db: &impl HirDatabase, def: FunctionId) -> FnSig {
     let data = db.function_data(def);
     let resolver = def.resolver(db);
     let params = data.params.iter().map(|tr| Ty::from_hir(db, &resolver, tr)).collect::<Vec<_>>();
+    let params = replace_impl_trait_args(db, def, params);
     let ret = Ty::from_hir(db, &resolver, &data.ret_type);
     FnSig::from_params_and_return(params, ret)
 }
 
+/// Replaces the `impl Trait` types of the parameters of a function by the
+/// anonymous type parameters they introduce, which come in the same order in
+/// the generics of the function. If the types don't line up, e.g. because a
+/// type alias repeats its argument, the `impl Trait` types are kept.
+fn replace_impl_trait_args(db: &impl HirDatabase, def: FunctionId, params: Vec<Ty>) -> Vec<Ty> {
+    let generics = generics(db, def.into());
+    let impl_trait_params = generics
+        .iter()
+        .filter(|(_, param)| param.provenance == TypeParamProvenance::ArgumentImplTrait)
+        .map(|(idx, param)| Ty::Param { idx, name: param.name.clone() })
+        .collect::<Vec<_>>();
+    if impl_trait_params.is_empty() {
+        return params;
+    }
+
+    let mut count = 0;
+    for ty in params.iter() {
+        map_impl_trait_args(ty.clone(), &mut |ty| {
+            count += 1;
+            ty
+        });
+    }
+    if count != impl_trait_params.len() {
+        return params;
+    }
+    let mut impl_trait_params = impl_trait_params.into_iter();
+    params
+        .into_iter()
+        .map(|ty| map_impl_trait_args(ty, &mut |ty| impl_trait_params.next().unwrap_or(ty)))
+        .collect()
+}
+
+/// Calls `f` on the `impl Trait` types in `ty`, except for the ones nested in
+/// the bounds of other `impl Trait` or `dyn Trait` types.
+fn map_impl_trait_args(ty: Ty, f: &mut impl FnMut(Ty) -> Ty) -> Ty {
+    match ty {
+        Ty::Opaque(_) => f(ty),
+        Ty::Apply(mut a_ty) => {
+            for t in make_mut_slice(&mut a_ty.parameters.0) {
+                *t = map_impl_trait_args(t.clone(), f);
+            }
+            Ty::Apply(a_ty)
+        }
+        Ty::Projection(mut p_ty) => {
+            for t in make_mut_slice(&mut p_ty.parameters.0) {
+                *t = map_impl_trait_args(t.clone(), f);
+            }
+            Ty::Projection(p_ty)
+        }
+        _ => ty,
+    }
+}
+
 /// Build the declared type of a function. This should not need to look at the
 /// function body.
 fn type_for_fn(db: &impl HirDatabase, def: FunctionId) -> Ty {
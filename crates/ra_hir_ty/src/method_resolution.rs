@@ -20,7 +20,8 @@ use crate::{
     db::HirDatabase,
     primitive::{FloatBitness, Uncertain},
     utils::all_super_traits,
-    Canonical, InEnvironment, TraitEnvironment, TraitRef, Ty, TypeCtor, TypeWalk,
+    ApplicationTy, Canonical, GenericPredicate, InEnvironment, TraitEnvironment, TraitRef, Ty,
+    TypeCtor, TypeWalk,
 };
 
 /// This is used as a key for indexing impls.
@@ -35,7 +36,8 @@ impl TyFingerprint {
     /// `impl &S`. Hence, this will return `None` for reference types and such.
     fn for_impl(ty: &Ty) -> Option<TyFingerprint> {
         match ty {
-            Ty::Apply(a_ty) => Some(TyFingerprint::Apply(a_ty.ctor)),
+            // the length of arrays is checked when unifying with the self type
+            Ty::Apply(a_ty) => Some(TyFingerprint::Apply(a_ty.ctor.without_array_len())),
             _ => None,
         }
     }
@@ -204,7 +206,13 @@ pub fn iterate_method_candidates<T>(
             // the methods by autoderef order of *receiver types*, not *self
             // types*.
 
-            let deref_chain: Vec<_> = autoderef::autoderef(db, Some(krate), ty.clone()).collect();
+            let mut deref_chain: Vec<_> =
+                autoderef::autoderef(db, Some(krate), ty.clone()).collect();
+            // After the derefs, rustc also tries to unsize an array into a
+            // slice, which is how `[1, 2, 3].len()` finds the slice methods.
+            if let Some(slice) = deref_chain.last().and_then(unsize_array) {
+                deref_chain.push(slice);
+            }
             for i in 0..deref_chain.len() {
                 if let Some(result) = iterate_method_candidates_with_autoref(
                     &deref_chain[i..],
@@ -225,6 +233,16 @@ pub fn iterate_method_candidates<T>(
     }
 }
 
+fn unsize_array(ty: &Canonical<Ty>) -> Option<Canonical<Ty>> {
+    match &ty.value {
+        Ty::Apply(ApplicationTy { ctor: TypeCtor::Array { .. }, parameters }) => Some(Canonical {
+            num_vars: ty.num_vars,
+            value: Ty::apply_one(TypeCtor::Slice, parameters.as_single().clone()),
+        }),
+        _ => None,
+    }
+}
+
 fn iterate_method_candidates_with_autoref<T>(
     deref_chain: &[Canonical<Ty>],
     db: &impl HirDatabase,
@@ -337,22 +355,26 @@ fn iterate_trait_method_candidates<T>(
     let krate = resolver.krate()?;
     // FIXME: maybe put the trait_env behind a query (need to figure out good input parameters for that)
     let env = TraitEnvironment::lower(db, resolver);
-    // if ty is `impl Trait` or `dyn Trait`, the trait doesn't need to be in scope
-    let inherent_trait = self_ty.value.inherent_trait().into_iter();
+    // if ty is `impl Trait` or `dyn Trait`, the trait and its super traits
+    // don't need to be in scope, and they are known to be implemented
+    let inherent_traits = inherent_super_traits(db, &self_ty.value);
     // if we have `T: Trait` in the param env, the trait doesn't need to be in scope
     let traits_from_env = env
         .trait_predicates_for_self_ty(&self_ty.value)
         .map(|tr| tr.trait_)
         .flat_map(|t| all_super_traits(db, t));
-    let traits =
-        inherent_trait.chain(traits_from_env).chain(resolver.traits_in_scope(db).into_iter());
+    let traits = inherent_traits
+        .iter()
+        .copied()
+        .chain(traits_from_env)
+        .chain(resolver.traits_in_scope(db).into_iter());
     'traits: for t in traits {
         let data = db.trait_data(t);
 
         // we'll be lazy about checking whether the type implements the
         // trait, but if we find out it doesn't, we'll skip the rest of the
         // iteration
-        let mut known_implemented = false;
+        let mut known_implemented = inherent_traits.contains(&t);
         for (_name, item) in data.items.iter() {
             if !is_valid_candidate(db, name, receiver_ty, (*item).into(), self_ty) {
                 continue;
@@ -364,6 +386,9 @@ fn iterate_trait_method_candidates<T>(
                 }
             }
             known_implemented = true;
+            if !self_bounds_are_satisfied(db, &env, krate, t, (*item).into(), self_ty) {
+                continue;
+            }
             if let Some(result) = callback(&self_ty.value, (*item).into()) {
                 return Some(result);
             }
@@ -434,6 +459,64 @@ fn is_valid_candidate(
     }
 }
 
+/// Checks the bounds on `Self` in the where clauses of a trait method, like
+/// `where Self: Sized`, which make the method uncallable on trait objects.
+fn self_bounds_are_satisfied(
+    db: &impl HirDatabase,
+    env: &Arc<TraitEnvironment>,
+    krate: CrateId,
+    trait_: TraitId,
+    item: AssocItemId,
+    self_ty: &Canonical<Ty>,
+) -> bool {
+    let function = match item {
+        AssocItemId::FunctionId(it) => it,
+        _ => return true,
+    };
+    let sized_trait = match db.lang_item(krate, "sized".into()) {
+        Some(LangItemTarget::TraitId(it)) => Some(it),
+        _ => None,
+    };
+    // the trait's own predicates (e.g. super traits) are already implied by
+    // the trait being implemented
+    let trait_predicates = db.generic_predicates(trait_.into());
+    let predicates = db.generic_predicates(function.into());
+    predicates
+        .iter()
+        .filter(|pred| !trait_predicates.contains(pred))
+        .filter_map(|pred| match pred {
+            // `Self` is the first parameter of a trait method
+            GenericPredicate::Implemented(tr) if tr.substs.len() == 1 => match tr.self_ty() {
+                Ty::Param { idx: 0, .. } => Some(tr.trait_),
+                _ => None,
+            },
+            _ => None,
+        })
+        .all(|bound| {
+            if Some(bound) == sized_trait {
+                return is_sized(&self_ty.value);
+            }
+            match self_ty.value {
+                // FIXME: Chalk doesn't implement `dyn/impl Trait` yet, so
+                // assume that these implement the bound
+                Ty::Dyn(_) | Ty::Opaque(_) => true,
+                _ => {
+                    let goal = generic_implements_goal(db, env.clone(), bound, self_ty.clone());
+                    db.trait_solve(krate.into(), goal).is_some()
+                }
+            }
+        })
+}
+
+fn is_sized(ty: &Ty) -> bool {
+    match ty {
+        Ty::Dyn(_) => false,
+        Ty::Apply(ApplicationTy { ctor: TypeCtor::Str, .. })
+        | Ty::Apply(ApplicationTy { ctor: TypeCtor::Slice, .. }) => false,
+        _ => true,
+    }
+}
+
 pub(crate) fn inherent_impl_substs(
     db: &impl HirDatabase,
     impl_id: ImplId,
@@ -469,7 +552,7 @@ pub fn implements_trait(
     krate: CrateId,
     trait_: TraitId,
 ) -> bool {
-    if ty.value.inherent_trait() == Some(trait_) {
+    if inherent_super_traits(db, &ty.value).contains(&trait_) {
         // FIXME this is a bit of a hack, since Chalk should say the same thing
         // anyway, but currently Chalk doesn't implement `dyn/impl Trait` yet
         return true;
@@ -481,6 +564,10 @@ pub fn implements_trait(
     solution.is_some()
 }
 
+fn inherent_super_traits(db: &impl HirDatabase, ty: &Ty) -> Vec<TraitId> {
+    ty.inherent_traits().into_iter().flat_map(|t| all_super_traits(db, t)).collect()
+}
+
 /// This creates Substs for a trait with the given Self type and type variables
 /// for all other parameters, to query Chalk with it.
 fn generic_implements_goal(
@@ -71,42 +71,42 @@ fn test2() {
     [82; 93) '{ loop {} }': T
     [84; 91) 'loop {}': !
     [89; 91) '{}': ()
-    [122; 133) '{ loop {} }': *mut [T;_]
+    [122; 133) '{ loop {} }': *mut [T; 2]
     [124; 131) 'loop {}': !
     [129; 131) '{}': ()
     [160; 173) '{     gen() }': *mut [U]
-    [166; 169) 'gen': fn gen<U>() -> *mut [T;_]
-    [166; 171) 'gen()': *mut [U;_]
+    [166; 169) 'gen': fn gen<U>() -> *mut [T; 2]
+    [166; 171) 'gen()': *mut [U; 2]
     [186; 420) '{     ...rr); }': ()
-    [196; 199) 'arr': &[u8;_]
-    [212; 216) '&[1]': &[u8;_]
-    [213; 216) '[1]': [u8;_]
+    [196; 199) 'arr': &[u8; 1]
+    [212; 216) '&[1]': &[u8; 1]
+    [213; 216) '[1]': [u8; 1]
     [214; 215) '1': u8
     [227; 228) 'a': &[u8]
-    [237; 240) 'arr': &[u8;_]
+    [237; 240) 'arr': &[u8; 1]
     [250; 251) 'b': u8
     [254; 255) 'f': fn f<u8>(&[T]) -> T
     [254; 260) 'f(arr)': u8
-    [256; 259) 'arr': &[u8;_]
+    [256; 259) 'arr': &[u8; 1]
     [270; 271) 'c': &[u8]
     [280; 287) '{ arr }': &[u8]
-    [282; 285) 'arr': &[u8;_]
+    [282; 285) 'arr': &[u8; 1]
     [297; 298) 'd': u8
     [301; 302) 'g': fn g<u8>(S<&[T]>) -> T
     [301; 316) 'g(S { a: arr })': u8
     [303; 315) 'S { a: arr }': S<&[u8]>
-    [310; 313) 'arr': &[u8;_]
-    [326; 327) 'e': [&[u8];_]
-    [341; 346) '[arr]': [&[u8];_]
-    [342; 345) 'arr': &[u8;_]
-    [356; 357) 'f': [&[u8];_]
-    [371; 379) '[arr; 2]': [&[u8];_]
-    [372; 375) 'arr': &[u8;_]
+    [310; 313) 'arr': &[u8; 1]
+    [326; 327) 'e': [&[u8]; 1]
+    [341; 346) '[arr]': [&[u8]; 1]
+    [342; 345) 'arr': &[u8; 1]
+    [356; 357) 'f': [&[u8]; 2]
+    [371; 379) '[arr; 2]': [&[u8]; 2]
+    [372; 375) 'arr': &[u8; 1]
     [377; 378) '2': usize
     [389; 390) 'g': (&[u8], &[u8])
     [407; 417) '(arr, arr)': (&[u8], &[u8])
-    [408; 411) 'arr': &[u8;_]
-    [413; 416) 'arr': &[u8;_]
+    [408; 411) 'arr': &[u8; 1]
+    [413; 416) 'arr': &[u8; 1]
     "###
     );
 }
@@ -122,8 +122,8 @@ fn test() {
         @r###"
     [11; 40) '{     ...[1]; }': ()
     [21; 22) 'x': &[i32]
-    [33; 37) '&[1]': &[i32;_]
-    [34; 37) '[1]': [i32;_]
+    [33; 37) '&[1]': &[i32; 1]
+    [34; 37) '[1]': [i32; 1]
     [35; 36) '1': i32
     "###);
 }
@@ -159,22 +159,22 @@ fn test(a: A<[u8; 2]>, b: B<[u8; 2]>, c: C<[u8; 2]>) {
     [334; 335) 'x': C<[T]>
     [355; 360) '{ x }': C<[T]>
     [357; 358) 'x': C<[T]>
-    [370; 371) 'a': A<[u8;_]>
-    [385; 386) 'b': B<[u8;_]>
-    [400; 401) 'c': C<[u8;_]>
+    [370; 371) 'a': A<[u8; 2]>
+    [385; 386) 'b': B<[u8; 2]>
+    [400; 401) 'c': C<[u8; 2]>
     [415; 481) '{     ...(c); }': ()
     [425; 426) 'd': A<[{unknown}]>
     [429; 433) 'foo1': fn foo1<{unknown}>(A<[T]>) -> A<[T]>
     [429; 436) 'foo1(a)': A<[{unknown}]>
-    [434; 435) 'a': A<[u8;_]>
+    [434; 435) 'a': A<[u8; 2]>
     [446; 447) 'e': B<[u8]>
     [450; 454) 'foo2': fn foo2<u8>(B<[T]>) -> B<[T]>
     [450; 457) 'foo2(b)': B<[u8]>
-    [455; 456) 'b': B<[u8;_]>
+    [455; 456) 'b': B<[u8; 2]>
     [467; 468) 'f': C<[u8]>
     [471; 475) 'foo3': fn foo3<u8>(C<[T]>) -> C<[T]>
     [471; 478) 'foo3(c)': C<[u8]>
-    [476; 477) 'c': C<[u8;_]>
+    [476; 477) 'c': C<[u8; 2]>
     "###
     );
 }
@@ -204,12 +204,12 @@ fn test() {
     [72; 97) '{     ...     }': &[i32]
     [82; 85) 'foo': fn foo<i32>(&[T]) -> &[T]
     [82; 91) 'foo(&[1])': &[i32]
-    [86; 90) '&[1]': &[i32;_]
-    [87; 90) '[1]': [i32;_]
+    [86; 90) '&[1]': &[i32; 1]
+    [87; 90) '[1]': [i32; 1]
     [88; 89) '1': i32
-    [103; 123) '{     ...     }': &[i32;_]
-    [113; 117) '&[1]': &[i32;_]
-    [114; 117) '[1]': [i32;_]
+    [103; 123) '{     ...     }': &[i32; 1]
+    [113; 117) '&[1]': &[i32; 1]
+    [114; 117) '[1]': [i32; 1]
     [115; 116) '1': i32
     "###
     );
@@ -237,15 +237,15 @@ fn test() {
     [60; 61) 'x': &[i32]
     [64; 123) 'if tru...     }': &[i32]
     [67; 71) 'true': bool
-    [72; 92) '{     ...     }': &[i32;_]
-    [82; 86) '&[1]': &[i32;_]
-    [83; 86) '[1]': [i32;_]
+    [72; 92) '{     ...     }': &[i32; 1]
+    [82; 86) '&[1]': &[i32; 1]
+    [83; 86) '[1]': [i32; 1]
     [84; 85) '1': i32
     [98; 123) '{     ...     }': &[i32]
     [108; 111) 'foo': fn foo<i32>(&[T]) -> &[T]
     [108; 117) 'foo(&[1])': &[i32]
-    [112; 116) '&[1]': &[i32;_]
-    [113; 116) '[1]': [i32;_]
+    [112; 116) '&[1]': &[i32; 1]
+    [113; 116) '[1]': [i32; 1]
     [114; 115) '1': i32
     "###
     );
@@ -277,16 +277,16 @@ fn test(i: i32) {
     [88; 89) '2': i32
     [93; 96) 'foo': fn foo<i32>(&[T]) -> &[T]
     [93; 102) 'foo(&[2])': &[i32]
-    [97; 101) '&[2]': &[i32;_]
-    [98; 101) '[2]': [i32;_]
+    [97; 101) '&[2]': &[i32; 1]
+    [98; 101) '[2]': [i32; 1]
     [99; 100) '2': i32
     [112; 113) '1': i32
-    [117; 121) '&[1]': &[i32;_]
-    [118; 121) '[1]': [i32;_]
+    [117; 121) '&[1]': &[i32; 1]
+    [118; 121) '[1]': [i32; 1]
     [119; 120) '1': i32
     [131; 132) '_': i32
-    [136; 140) '&[3]': &[i32;_]
-    [137; 140) '[3]': [i32;_]
+    [136; 140) '&[3]': &[i32; 1]
+    [137; 140) '[3]': [i32; 1]
     [138; 139) '3': i32
     "###
     );
@@ -316,18 +316,18 @@ fn test(i: i32) {
     [70; 147) 'match ...     }': &[i32]
     [76; 77) 'i': i32
     [88; 89) '1': i32
-    [93; 97) '&[1]': &[i32;_]
-    [94; 97) '[1]': [i32;_]
+    [93; 97) '&[1]': &[i32; 1]
+    [94; 97) '[1]': [i32; 1]
     [95; 96) '1': i32
     [107; 108) '2': i32
     [112; 115) 'foo': fn foo<i32>(&[T]) -> &[T]
     [112; 121) 'foo(&[2])': &[i32]
-    [116; 120) '&[2]': &[i32;_]
-    [117; 120) '[2]': [i32;_]
+    [116; 120) '&[2]': &[i32; 1]
+    [117; 120) '[2]': [i32; 1]
     [118; 119) '2': i32
     [131; 132) '_': i32
-    [136; 140) '&[3]': &[i32;_]
-    [137; 140) '[3]': [i32;_]
+    [136; 140) '&[3]': &[i32; 1]
+    [137; 140) '[3]': [i32; 1]
     [138; 139) '3': i32
     "###
     );
@@ -353,7 +353,31 @@ fn test() {
 #[prelude_import]
 use clone::*;
 mod clone {
-    trait Clone {
+    pub trait Clone {
+        fn clone(&self) -> Self;
+    }
+}
+"#,
+    );
+    assert_eq!("S", type_at_pos(&db, pos));
+}
+
+#[test]
+fn infer_derive_clone_under_cfg_attr() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs crate:main deps:std cfg:feature=clone
+#[cfg_attr(feature = "clone", derive(Clone))]
+struct S;
+fn test() {
+    S.clone()<|>;
+}
+
+//- /lib.rs crate:std
+#[prelude_import]
+use clone::*;
+mod clone {
+    pub trait Clone {
         fn clone(&self) -> Self;
     }
 }
@@ -380,7 +404,7 @@ fn test() {
 #[prelude_import]
 use clone::*;
 mod clone {
-    trait Clone {
+    pub trait Clone {
         fn clone(&self) -> Self;
     }
 }
@@ -399,6 +399,27 @@ mod bar_test {
     );
 }
 
+#[test]
+fn infer_trait_method_imported_as_underscore() {
+    let t = type_at(
+        r#"
+//- /main.rs
+struct S;
+mod foo {
+    pub trait Trait1 {
+        fn method(&self) -> u32;
+    }
+    impl Trait1 for super::S {}
+}
+use foo::Trait1 as _;
+fn test() {
+    S.method()<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u32");
+}
+
 #[test]
 fn infer_trait_method_generic_1() {
     // the trait implementation is intentionally incomplete -- it shouldn't matter
@@ -794,6 +815,58 @@ fn test() { (&S).foo()<|>; }
     assert_eq!(t, "u128");
 }
 
+#[test]
+fn method_resolution_autoref_mut() {
+    let t = type_at(
+        r#"
+//- /main.rs
+struct S;
+impl S {
+    fn by_ref(&self) -> u8 { 0 }
+    fn by_mut(&mut self) -> i8 { 0 }
+}
+fn test(mut s: S, r: &mut S, rr: &&mut S) {
+    (s.by_mut(), r.by_mut(), r.by_ref(), rr.by_ref(), (&mut s).by_ref())<|>;
+}
+"#,
+    );
+    assert_eq!(t, "(i8, i8, u8, u8, u8)");
+}
+
+#[test]
+fn method_resolution_autoderef_then_autoref_mut() {
+    let t = type_at(
+        r#"
+//- /main.rs
+#[lang = "deref"]
+trait Deref { type Target; fn deref(&self) -> &Self::Target; }
+struct Wrapper<T>(T);
+impl<T> Deref for Wrapper<T> { type Target = T; }
+struct S;
+impl S { fn by_mut(&mut self) -> i8 { 0 } }
+fn test(w: &mut Wrapper<S>) { w.by_mut()<|>; }
+"#,
+    );
+    assert_eq!(t, "i8");
+}
+
+#[test]
+fn method_resolution_unsizes_arrays() {
+    let t = type_at(
+        r#"
+//- /main.rs
+#[lang = "slice"]
+impl<T> [T] {
+    fn first(&self) -> Option<&T> { loop {} }
+    fn len(&self) -> usize { 0 }
+}
+enum Option<T> { Some(T), None }
+fn test(a: [u8; 3], r: &[u8; 3]) { (a.len(), r.first())<|>; }
+"#,
+    );
+    assert_eq!(t, "(usize, Option<&u8>)");
+}
+
 #[test]
 fn method_resolution_impl_before_trait() {
     let t = type_at(
@@ -929,6 +1002,80 @@ fn test() { S.foo()<|>; }
     assert_eq!(t, "u128");
 }
 
+const TO_STRING_STD: &str = r#"
+//- /std.rs crate:std
+#[prelude_import] use prelude::*;
+mod prelude {
+    pub use crate::string::{String, ToString};
+}
+pub mod fmt {
+    pub trait Display {}
+}
+pub mod string {
+    pub struct String;
+    pub trait ToString {
+        fn to_string(&self) -> String;
+    }
+    impl<T: crate::fmt::Display + ?Sized> ToString for T {}
+}
+"#;
+
+#[test]
+fn method_resolution_blanket_impl_from_other_crate() {
+    let t = type_at(&format!(
+        r#"
+//- /main.rs crate:main deps:std
+use std::fmt::Display;
+struct S;
+impl Display for S {{}}
+fn test() {{ S.to_string()<|>; }}
+{}"#,
+        TO_STRING_STD
+    ));
+    assert_eq!(t, "String");
+}
+
+#[test]
+fn method_resolution_blanket_impl_by_ref() {
+    let t = type_at(&format!(
+        r#"
+//- /main.rs crate:main deps:std
+use std::fmt::Display;
+struct S;
+impl Display for S {{}}
+fn test(s: &S) {{ s.to_string()<|>; }}
+{}"#,
+        TO_STRING_STD
+    ));
+    assert_eq!(t, "String");
+}
+
+#[test]
+fn method_resolution_blanket_impl_for_bounded_param() {
+    let t = type_at(&format!(
+        r#"
+//- /main.rs crate:main deps:std
+use std::fmt::Display;
+fn test<T: Display>(t: T) {{ t.to_string()<|>; }}
+{}"#,
+        TO_STRING_STD
+    ));
+    assert_eq!(t, "String");
+}
+
+#[test]
+fn method_resolution_blanket_impl_not_met_from_other_crate() {
+    let t = type_at(&format!(
+        r#"
+//- /main.rs crate:main deps:std
+struct S;
+fn test() {{ S.to_string()<|>; }}
+{}"#,
+        TO_STRING_STD
+    ));
+    assert_eq!(t, "{unknown}");
+}
+
 #[test]
 fn method_resolution_where_clause_2() {
     let t = type_at(
@@ -963,6 +1110,45 @@ fn test() { S2.into()<|>; }
     assert_eq!(t, "{unknown}");
 }
 
+#[test]
+fn method_resolution_self_sized_on_trait_object() {
+    let t = type_at(
+        r#"
+//- /main.rs
+#[lang = "sized"]
+trait Sized {}
+trait Trait {
+    fn foo(&self) -> u32;
+    fn bar(&self) -> u64 where Self: Sized;
+}
+struct S;
+impl Trait for S {}
+fn test(x: &dyn Trait, y: &S) { (x.foo(), x.bar(), y.bar())<|>; }
+"#,
+    );
+    assert_eq!(t, "(u32, {unknown}, u64)");
+}
+
+#[test]
+fn method_resolution_unsatisfied_self_bound() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Clone {}
+trait Trait {
+    fn foo(&self) -> u64 where Self: Clone;
+}
+struct S1;
+struct S2;
+impl Trait for S1 {}
+impl Trait for S2 {}
+impl Clone for S2 {}
+fn test() { (S1.foo(), S2.foo())<|>; }
+"#,
+    );
+    assert_eq!(t, "({unknown}, u64)");
+}
+
 #[test]
 fn method_resolution_encountering_fn_type() {
     type_at(
@@ -1003,3 +1189,177 @@ fn test() { (S {}).method()<|>; }
     );
     assert_eq!(t, "()");
 }
+
+#[test]
+fn method_resolution_through_generic_type_alias() {
+    let t = type_at(
+        r#"
+//- /main.rs
+struct Wrapper<T> { inner: T }
+impl<T> Wrapper<T> { fn get(&self) -> T { loop {} } }
+type Alias<T> = Wrapper<T>;
+fn test(w: Alias<u32>) { w.get()<|>; }
+"#,
+    );
+    assert_eq!(t, "u32");
+}
+
+#[test]
+fn trait_method_resolution_through_type_alias() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Trait<T> { fn method(&self) -> T; }
+struct S<T>;
+impl<T> Trait<T> for S<T> {}
+type Pair<A, B> = S<(B, A)>;
+fn test(s: Pair<u8, i64>) { s.method()<|>; }
+"#,
+    );
+    assert_eq!(t, "(i64, u8)");
+}
+
+#[test]
+fn associated_function_through_type_alias() {
+    let t = type_at(
+        r#"
+//- /main.rs
+struct Wrapper<T> { inner: T }
+impl<T> Wrapper<T> { fn new(inner: T) -> Self { loop {} } }
+type Alias = Wrapper<u8>;
+fn test() { Alias::new(1)<|>; }
+"#,
+    );
+    assert_eq!(t, "Wrapper<u8>");
+}
+
+#[test]
+fn method_resolution_through_recursive_type_alias() {
+    let t = type_at(
+        r#"
+//- /main.rs
+type Foo = Bar;
+type Bar = Foo;
+struct S;
+impl S { fn method(&self) -> u32 { 0 } }
+fn test(x: Foo) { x.method()<|>; }
+"#,
+    );
+    assert_eq!(t, "{unknown}");
+}
+
+#[test]
+fn method_call_with_turbofish_and_associated_type_in_return() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait FromStr { type Err; }
+struct ParseIntError;
+impl FromStr for u32 { type Err = ParseIntError; }
+struct Str;
+enum Result<T, E> { Ok(T), Err(E) }
+impl Str {
+    fn parse<F: FromStr>(&self) -> Result<F, F::Err> { loop {} }
+}
+fn test(s: Str) { s.parse::<u32>()<|>; }
+"#,
+    );
+    assert_eq!(t, "Result<u32, ParseIntError>");
+}
+
+#[test]
+fn method_call_generic_return_inferred_from_expected_type() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait FromIterator<A> {}
+trait Iterator {
+    type Item;
+    fn collect<B: FromIterator<Self::Item>>(self) -> B;
+}
+struct Vec<T> {}
+impl<T> FromIterator<T> for Vec<T> {}
+struct Counter;
+impl Iterator for Counter { type Item = u32; }
+fn test(c: Counter) {
+    let v: Vec<_> = c.collect();
+    v<|>;
+}
+"#,
+    );
+    assert_eq!(t, "Vec<u32>");
+}
+
+#[test]
+fn method_call_generic_param_inferred_from_argument() {
+    let t = type_at(
+        r#"
+//- /main.rs
+struct S;
+impl S {
+    fn pair<T, U>(&self, t: T, u: U) -> (U, T) { loop {} }
+}
+fn test() { S.pair::<u8, _>(1, 'c')<|>; }
+"#,
+    );
+    assert_eq!(t, "(char, u8)");
+}
+
+#[test]
+fn method_resolution_on_self_in_default_trait_method() {
+    let t = type_at(
+        r#"
+//- /main.rs
+mod m {
+    pub trait Base { fn base(&self) -> u8; }
+    pub trait Tr: Base {
+        fn foo(&self) -> u32;
+        fn bar(&self) { (self.foo(), self.base(), Self::foo(self))<|>; }
+    }
+}
+"#,
+    );
+    assert_eq!(t, "(u32, u8, u32)");
+}
+
+#[test]
+fn binding_type_inferred_from_later_method_call() {
+    let t = type_at(
+        r#"
+//- /main.rs
+struct Vec<T>;
+impl<T> Vec<T> {
+    fn new() -> Self { Vec }
+    fn push(&mut self, t: T) {}
+}
+fn test() {
+    let mut v = Vec::new();
+    v<|>;
+    v.push(1u32);
+}
+"#,
+    );
+    assert_eq!(t, "Vec<u32>");
+}
+
+#[test]
+fn binding_type_inferred_from_later_call_argument() {
+    let t = type_at(
+        r#"
+//- /main.rs
+struct Vec<T>;
+impl<T> Vec<T> {
+    fn new() -> Self { Vec }
+    fn len(&self) -> usize { 0 }
+}
+fn consume(v: Vec<char>) {}
+fn test() {
+    let v = Vec::new();
+    let n = v.len();
+    consume(v);
+    (v, n)<|>;
+}
+"#,
+    );
+    assert_eq!(t, "(Vec<char>, usize)");
+}
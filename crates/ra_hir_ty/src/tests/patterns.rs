@@ -1,4 +1,4 @@
-use super::infer;
+use super::{infer, type_at};
 use insta::assert_snapshot;
 use test_utils::covers;
 
@@ -236,3 +236,67 @@ fn test(a1: A<u32>, o: Option<u64>) {
     "###
     );
 }
+
+#[test]
+fn infer_tuple_pattern_with_ellipsis() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test(t: (u8, u16, u32, u64)) {
+    let (a, .., d) = t;
+    d<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u64");
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test(t: (u8, u16, u32, u64)) {
+    let (.., c, _) = t;
+    c<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u32");
+}
+
+#[test]
+fn infer_slice_pattern() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test(s: &[u32]) {
+    if let [first, .., last] = s {
+        (first, last)<|>;
+    }
+}
+"#,
+    );
+    assert_eq!(t, "(&u32, &u32)");
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test(s: &[(u8, u64)]) {
+    if let [(a, b), rest @ ..] = s {
+        (a, b, rest)<|>;
+    }
+}
+"#,
+    );
+    assert_eq!(t, "(&u8, &u64, &[(u8, u64)])");
+}
+
+#[test]
+fn infer_array_pattern() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test(a: [u32; 4]) {
+    let [x, rest @ .., y] = a;
+    (x, rest, y)<|>;
+}
+"#,
+    );
+    assert_eq!(t, "(u32, [u32; 2], u32)");
+}
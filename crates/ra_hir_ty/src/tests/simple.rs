@@ -27,7 +27,7 @@ mod boxed {
 
 "#,
     );
-    assert_eq!("(Box<i32>, Box<Box<i32>>, Box<&i32>, Box<[i32;_]>)", type_at_pos(&db, pos));
+    assert_eq!("(Box<i32>, Box<Box<i32>>, Box<&i32>, Box<[i32; 1]>)", type_at_pos(&db, pos));
 }
 
 #[test]
@@ -99,6 +99,38 @@ pub mod ops {
     );
 }
 
+#[test]
+fn infer_range_endpoints_are_unified() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs crate:main deps:std
+fn test(n: u8) {
+    let r = 0..=n;
+    let s = 1..2;
+    let t = (r, s);
+    t<|>;
+}
+
+//- /std.rs crate:std
+#[prelude_import] use prelude::*;
+mod prelude {}
+
+pub mod ops {
+    pub struct Range<Idx> {
+        pub start: Idx,
+        pub end: Idx,
+    }
+    pub struct RangeInclusive<Idx> {
+        start: Idx,
+        end: Idx,
+        is_empty: u8,
+    }
+}
+"#,
+    );
+    assert_eq!("(RangeInclusive<u8>, Range<i32>)", type_at_pos(&db, pos));
+}
+
 #[test]
 fn infer_while_let() {
     let (db, pos) = TestDB::with_position(
@@ -118,6 +150,74 @@ fn test() {
     assert_eq!("f32", type_at_pos(&db, pos));
 }
 
+#[test]
+fn infer_loop_break_with_value() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test(c: bool) {
+    let x = loop {
+        if c {
+            break 1u8;
+        }
+        break 2;
+    };
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u8");
+}
+
+#[test]
+fn infer_loop_break_without_value() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test() {
+    let x = loop { break; };
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "()");
+}
+
+#[test]
+fn infer_loop_without_break_diverges() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test() -> u32 {
+    let x = loop {
+        for i in 0..10 {
+            break;
+        }
+    };
+    x<|>
+}
+"#,
+    );
+    assert_eq!(t, "!");
+}
+
+#[test]
+fn infer_break_in_closure_does_not_break_loop() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test() {
+    let x = loop {
+        let f = || loop { break 1u8; };
+        break "";
+    };
+    x<|>;
+}
+"#,
+    );
+    assert_eq!(t, "&str");
+}
+
 #[test]
 fn infer_basics() {
     assert_snapshot!(
@@ -1039,59 +1139,76 @@ fn test(x: &str, y: isize) {
     [9; 10) 'x': &str
     [18; 19) 'y': isize
     [28; 293) '{     ... []; }': ()
-    [38; 39) 'a': [&str;_]
-    [42; 45) '[x]': [&str;_]
+    [38; 39) 'a': [&str; 1]
+    [42; 45) '[x]': [&str; 1]
     [43; 44) 'x': &str
-    [55; 56) 'b': [[&str;_];_]
-    [59; 65) '[a, a]': [[&str;_];_]
-    [60; 61) 'a': [&str;_]
-    [63; 64) 'a': [&str;_]
-    [75; 76) 'c': [[[&str;_];_];_]
-    [79; 85) '[b, b]': [[[&str;_];_];_]
-    [80; 81) 'b': [[&str;_];_]
-    [83; 84) 'b': [[&str;_];_]
-    [96; 97) 'd': [isize;_]
-    [100; 112) '[y, 1, 2, 3]': [isize;_]
+    [55; 56) 'b': [[&str; 1]; 2]
+    [59; 65) '[a, a]': [[&str; 1]; 2]
+    [60; 61) 'a': [&str; 1]
+    [63; 64) 'a': [&str; 1]
+    [75; 76) 'c': [[[&str; 1]; 2]; 2]
+    [79; 85) '[b, b]': [[[&str; 1]; 2]; 2]
+    [80; 81) 'b': [[&str; 1]; 2]
+    [83; 84) 'b': [[&str; 1]; 2]
+    [96; 97) 'd': [isize; 4]
+    [100; 112) '[y, 1, 2, 3]': [isize; 4]
     [101; 102) 'y': isize
     [104; 105) '1': isize
     [107; 108) '2': isize
     [110; 111) '3': isize
-    [122; 123) 'd': [isize;_]
-    [126; 138) '[1, y, 2, 3]': [isize;_]
+    [122; 123) 'd': [isize; 4]
+    [126; 138) '[1, y, 2, 3]': [isize; 4]
     [127; 128) '1': isize
     [130; 131) 'y': isize
     [133; 134) '2': isize
     [136; 137) '3': isize
-    [148; 149) 'e': [isize;_]
-    [152; 155) '[y]': [isize;_]
+    [148; 149) 'e': [isize; 1]
+    [152; 155) '[y]': [isize; 1]
     [153; 154) 'y': isize
-    [165; 166) 'f': [[isize;_];_]
-    [169; 175) '[d, d]': [[isize;_];_]
-    [170; 171) 'd': [isize;_]
-    [173; 174) 'd': [isize;_]
-    [185; 186) 'g': [[isize;_];_]
-    [189; 195) '[e, e]': [[isize;_];_]
-    [190; 191) 'e': [isize;_]
-    [193; 194) 'e': [isize;_]
-    [206; 207) 'h': [i32;_]
-    [210; 216) '[1, 2]': [i32;_]
+    [165; 166) 'f': [[isize; 4]; 2]
+    [169; 175) '[d, d]': [[isize; 4]; 2]
+    [170; 171) 'd': [isize; 4]
+    [173; 174) 'd': [isize; 4]
+    [185; 186) 'g': [[isize; 1]; 2]
+    [189; 195) '[e, e]': [[isize; 1]; 2]
+    [190; 191) 'e': [isize; 1]
+    [193; 194) 'e': [isize; 1]
+    [206; 207) 'h': [i32; 2]
+    [210; 216) '[1, 2]': [i32; 2]
     [211; 212) '1': i32
     [214; 215) '2': i32
-    [226; 227) 'i': [&str;_]
-    [230; 240) '["a", "b"]': [&str;_]
+    [226; 227) 'i': [&str; 2]
+    [230; 240) '["a", "b"]': [&str; 2]
     [231; 234) '"a"': &str
     [236; 239) '"b"': &str
-    [251; 252) 'b': [[&str;_];_]
-    [255; 265) '[a, ["b"]]': [[&str;_];_]
-    [256; 257) 'a': [&str;_]
-    [259; 264) '["b"]': [&str;_]
+    [251; 252) 'b': [[&str; 1]; 2]
+    [255; 265) '[a, ["b"]]': [[&str; 1]; 2]
+    [256; 257) 'a': [&str; 1]
+    [259; 264) '["b"]': [&str; 1]
     [260; 263) '"b"': &str
-    [275; 276) 'x': [u8;_]
-    [288; 290) '[]': [u8;_]
+    [275; 276) 'x': [u8; 0]
+    [288; 290) '[]': [u8; 0]
     "###
     );
 }
 
+#[test]
+fn infer_array_len_from_consts() {
+    let t = type_at(
+        r#"
+//- /main.rs
+const N: usize = 4;
+const M: usize = N;
+mod m { pub const K: usize = 0x10; }
+
+fn test(a: [u8; N], b: [u8; M], c: [u8; m::K], d: [u8; 2 + 2]) {
+    (a, b, c, d, [0; M])<|>;
+}
+"#,
+    );
+    assert_eq!(t, "([u8; 4], [u8; 4], [u8; 16], [u8; _], [i32; 4])");
+}
+
 #[test]
 fn infer_struct_generics() {
     assert_snapshot!(
@@ -1128,6 +1245,67 @@ fn test(a1: A<u32>, i: i32) {
     );
 }
 
+#[test]
+fn infer_phantom_data_field_from_annotation() {
+    let t = type_at(
+        r#"
+//- /main.rs
+struct PhantomData<T>;
+struct Wrapper<T> { _p: PhantomData<T> }
+
+fn test() {
+    let w: Wrapper<u32> = Wrapper { _p: PhantomData<|> };
+}
+"#,
+    );
+    assert_eq!(t, "PhantomData<u32>");
+}
+
+#[test]
+fn infer_phantom_data_field_from_return_type() {
+    let t = type_at(
+        r#"
+//- /main.rs
+struct PhantomData<T>;
+struct Locked;
+struct Unlocked;
+struct Door<S> { width: u32, _state: PhantomData<S> }
+
+impl Door<Locked> {
+    fn unlock(self) -> Door<Unlocked> {
+        Door { width: self.width, _state: PhantomData }
+    }
+}
+
+fn new_door() -> Door<Locked> {
+    Door { width: 1, _state: PhantomData }
+}
+
+fn test() {
+    new_door().unlock()<|>;
+}
+"#,
+    );
+    assert_eq!(t, "Door<Unlocked>");
+}
+
+#[test]
+fn infer_unconstrained_phantom_data_field() {
+    let t = type_at(
+        r#"
+//- /main.rs
+struct PhantomData<T>;
+struct Wrapper<T, U> { value: T, _p: PhantomData<U> }
+
+fn test() {
+    let w = Wrapper { value: 1u8, _p: PhantomData };
+    w<|>;
+}
+"#,
+    );
+    assert_eq!(t, "Wrapper<u8, {unknown}>");
+}
+
 #[test]
 fn infer_tuple_struct_generics() {
     assert_snapshot!(
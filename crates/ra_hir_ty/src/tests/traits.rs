@@ -77,6 +77,106 @@ mod result {
     assert_eq!("i32", type_at_pos(&db, pos));
 }
 
+#[test]
+fn infer_try_with_error_conversion() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs crate:main deps:std
+
+struct ParseError;
+struct AppError;
+impl std::convert::From<ParseError> for AppError {}
+
+fn parse() -> Result<u32, ParseError> { Result::Ok(1) }
+
+fn test() -> Result<u32, AppError> {
+    let v = parse()?;
+    v<|>;
+    Result::Ok(v)
+}
+
+//- /std.rs crate:std
+
+#[prelude_import] use ops::*;
+mod ops {
+    trait Try {
+        type Ok;
+        type Error;
+    }
+}
+
+#[prelude_import] use result::*;
+mod result {
+    enum Result<O, E> {
+        Ok(O),
+        Err(E)
+    }
+
+    impl<O, E> crate::ops::Try for Result<O, E> {
+        type Ok = O;
+        type Error = E;
+    }
+}
+
+pub mod convert {
+    pub trait From<T> {}
+}
+"#,
+    );
+    assert_eq!("u32", type_at_pos(&db, pos));
+}
+
+#[test]
+fn infer_try_with_boxed_error() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs crate:main deps:std
+
+struct ParseError;
+impl std::error::Error for ParseError {}
+
+fn test(r: Result<(u8, char), ParseError>) -> Result<(), Box<dyn std::error::Error>> {
+    let v = r?;
+    v<|>;
+    Result::Ok(())
+}
+
+//- /std.rs crate:std
+
+#[prelude_import] use ops::*;
+mod ops {
+    trait Try {
+        type Ok;
+        type Error;
+    }
+}
+
+#[prelude_import] use result::*;
+mod result {
+    enum Result<O, E> {
+        Ok(O),
+        Err(E)
+    }
+
+    impl<O, E> crate::ops::Try for Result<O, E> {
+        type Ok = O;
+        type Error = E;
+    }
+}
+
+#[prelude_import] use boxed::*;
+mod boxed {
+    pub struct Box<T: ?Sized>(T);
+}
+
+pub mod error {
+    pub trait Error {}
+}
+"#,
+    );
+    assert_eq!("(u8, char)", type_at_pos(&db, pos));
+}
+
 #[test]
 fn infer_for_loop() {
     let (db, pos) = TestDB::with_position(
@@ -118,6 +218,124 @@ mod collections {
     assert_eq!("&str", type_at_pos(&db, pos));
 }
 
+#[test]
+fn infer_for_loop_over_iterator() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs crate:main deps:std
+
+struct Counter;
+impl std::iter::Iterator for Counter {
+    type Item = u32;
+}
+
+fn test() {
+    for x in Counter {
+        x<|>;
+    }
+}
+
+//- /std.rs crate:std
+
+#[prelude_import] use iter::*;
+mod iter {
+    trait Iterator {
+        type Item;
+    }
+    trait IntoIterator {
+        type Item;
+    }
+    impl<I: Iterator> IntoIterator for I {
+        type Item = <I as Iterator>::Item;
+    }
+}
+"#,
+    );
+    assert_eq!("u32", type_at_pos(&db, pos));
+}
+
+#[test]
+fn infer_for_loop_over_range() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs crate:main deps:std
+
+fn test(n: usize) {
+    for i in 0..n {
+        i<|>;
+    }
+}
+
+//- /std.rs crate:std
+
+#[prelude_import] use prelude::*;
+mod prelude {}
+
+pub mod iter {
+    pub trait Iterator {
+        type Item;
+    }
+    pub trait IntoIterator {
+        type Item;
+    }
+    impl<I: Iterator> IntoIterator for I {
+        type Item = <I as Iterator>::Item;
+    }
+}
+
+pub mod ops {
+    pub struct Range<Idx> {
+        pub start: Idx,
+        pub end: Idx,
+    }
+    impl<A> crate::iter::Iterator for Range<A> {
+        type Item = A;
+    }
+}
+"#,
+    );
+    assert_eq!("usize", type_at_pos(&db, pos));
+}
+
+#[test]
+fn infer_for_loop_over_reference() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs crate:main deps:std
+
+use std::collections::Vec;
+
+fn test(v: &Vec<u64>) {
+    for x in v {
+        x<|>;
+    }
+}
+
+//- /std.rs crate:std
+
+#[prelude_import] use iter::*;
+mod iter {
+    trait IntoIterator {
+        type Item;
+    }
+}
+
+mod collections {
+    struct Vec<T> {}
+
+    impl<T> crate::iter::IntoIterator for Vec<T> {
+        type Item=T;
+    }
+
+    impl<'a, T> crate::iter::IntoIterator for &'a Vec<T> {
+        type Item=&'a T;
+    }
+}
+"#,
+    );
+    assert_eq!("&u64", type_at_pos(&db, pos));
+}
+
 #[test]
 fn infer_ops_neg() {
     let (db, pos) = TestDB::with_position(
@@ -150,6 +368,67 @@ mod ops {
     assert_eq!("Foo", type_at_pos(&db, pos));
 }
 
+#[test]
+fn infer_ops_arith() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs crate:main deps:std
+
+struct Vec2;
+struct Scaled;
+
+impl std::ops::Add for Vec2 {
+    type Output = Vec2;
+}
+impl std::ops::Mul<f32> for Vec2 {
+    type Output = Scaled;
+}
+
+fn test(a: Vec2, b: Vec2) {
+    let c = (a + b) * 2.0;
+    c<|>;
+}
+
+//- /std.rs crate:std
+
+#[prelude_import] use ops::*;
+mod ops {
+    pub trait Add<Rhs = Self> {
+        type Output;
+    }
+    pub trait Mul<Rhs = Self> {
+        type Output;
+    }
+}
+"#,
+    );
+    assert_eq!("Scaled", type_at_pos(&db, pos));
+}
+
+#[test]
+fn infer_ops_arith_on_type_params() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs crate:main deps:std
+
+fn test<T: std::ops::Sub<Output = T>>(a: T, b: T) {
+    let c = a - b;
+    c<|>;
+}
+
+//- /std.rs crate:std
+
+#[prelude_import] use ops::*;
+mod ops {
+    pub trait Sub<Rhs = Self> {
+        type Output;
+    }
+}
+"#,
+    );
+    assert_eq!("T", type_at_pos(&db, pos));
+}
+
 #[test]
 fn infer_ops_not() {
     let (db, pos) = TestDB::with_position(
@@ -241,7 +520,6 @@ fn test() {
 
 #[test]
 fn infer_project_associated_type() {
-    // y, z, a don't yet work because of https://github.com/rust-lang/chalk/issues/234
     assert_snapshot!(
         infer(r#"
 trait Iterable {
@@ -260,11 +538,11 @@ fn test<T: Iterable>() {
     [108; 261) '{     ...ter; }': ()
     [118; 119) 'x': u32
     [145; 146) '1': u32
-    [156; 157) 'y': {unknown}
+    [156; 157) 'y': <T as Iterable>::Item
     [183; 192) 'no_matter': {unknown}
-    [202; 203) 'z': {unknown}
+    [202; 203) 'z': <T as Iterable>::Item
     [215; 224) 'no_matter': {unknown}
-    [234; 235) 'a': {unknown}
+    [234; 235) 'a': <T as Iterable>::Item
     [249; 258) 'no_matter': {unknown}
     "###
     );
@@ -325,12 +603,76 @@ fn test<T: Iterable<Item=u32>>() {
 "#),
         @r###"
     [67; 100) '{     ...own; }': ()
-    [77; 78) 'y': {unknown}
+    [77; 78) 'y': u32
     [90; 97) 'unknown': {unknown}
     "###
     );
 }
 
+#[test]
+fn infer_projection_on_param_in_method_call() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Iterator {
+    type Item;
+    fn next(&mut self) -> Option<Self::Item>;
+}
+enum Option<T> { Some(T), None }
+trait Trait2 {
+    fn foo(&self) -> u32;
+}
+fn test<I: Iterator>(mut it: I) where I::Item: Trait2 {
+    let x = it.next();
+    if let Option::Some(item) = x {
+        item.foo()<|>;
+    }
+}
+"#,
+    );
+    assert_eq!(t, "u32");
+}
+
+#[test]
+fn infer_projection_on_param_from_blanket_impl() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Foo {}
+trait Bar {
+    type Assoc;
+    fn bar(&self) -> Self::Assoc;
+}
+impl<T: Foo> Bar for T {
+    type Assoc = u32;
+    fn bar(&self) -> u32 { 0 }
+}
+fn test<T: Foo>(t: T) {
+    t.bar()<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u32");
+}
+
+#[test]
+fn infer_self_projection_in_trait() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Iterator {
+    type Item;
+    fn next(&mut self) -> Option<Self::Item>;
+}
+enum Option<T> { Some(T), None }
+fn test<I: Iterator>(mut it: I) {
+    it.next()<|>;
+}
+"#,
+    );
+    assert_eq!(t, "Option<<I as Iterator>::Item>");
+}
+
 #[test]
 fn infer_const_body() {
     assert_snapshot!(
@@ -418,7 +760,7 @@ fn indexing_arrays() {
         @r###"
     [10; 26) '{ &mut...[2]; }': ()
     [12; 23) '&mut [9][2]': &mut {unknown}
-    [17; 20) '[9]': [i32;_]
+    [17; 20) '[9]': [i32; 1]
     [17; 23) '[9][2]': {unknown}
     [18; 19) '9': i32
     [21; 22) '2': i32
@@ -458,6 +800,38 @@ mod ops {
     assert_eq!("Foo", type_at_pos(&db, pos));
 }
 
+#[test]
+fn infer_ops_index_autoderef() {
+    let (db, pos) = TestDB::with_position(
+        r#"
+//- /main.rs crate:main deps:std
+
+struct HashMap<K, V>;
+struct Key;
+struct Value;
+
+impl<K, V> std::ops::Index<&K> for HashMap<K, V> {
+    type Output = V;
+}
+
+fn test(m: &&HashMap<Key, Value>, k: Key) {
+    let v = m[&k];
+    v<|>;
+}
+
+//- /std.rs crate:std
+
+#[prelude_import] use ops::*;
+mod ops {
+    pub trait Index<Idx> {
+        type Output;
+    }
+}
+"#,
+    );
+    assert_eq!("Value", type_at_pos(&db, pos));
+}
+
 #[test]
 fn deref_trait() {
     let t = type_at(
@@ -487,6 +861,77 @@ fn test(s: Arc<S>) {
     assert_eq!(t, "(S, u128)");
 }
 
+#[test]
+fn deref_trait_through_nested_smart_pointers() {
+    let t = type_at(
+        r#"
+//- /main.rs
+#[lang = "deref"]
+trait Deref {
+    type Target;
+    fn deref(&self) -> &Self::Target;
+}
+
+#[lang = "deref_mut"]
+trait DerefMut: Deref {
+    fn deref_mut(&mut self) -> &mut Self::Target;
+}
+
+struct Rc<T>;
+impl<T> Deref for Rc<T> {
+    type Target = T;
+}
+
+struct Box<T>;
+impl<T> Deref for Box<T> {
+    type Target = T;
+}
+impl<T> DerefMut for Box<T> {}
+
+struct S { field: u32 }
+impl S {
+    fn foo(&self) -> u128 {}
+}
+
+fn test(s: Rc<Box<S>>) {
+    (s.foo(), s.field)<|>;
+}
+"#,
+    );
+    assert_eq!(t, "(u128, u32)");
+}
+
+#[test]
+fn deref_trait_cycle() {
+    let t = type_at(
+        r#"
+//- /main.rs
+#[lang = "deref"]
+trait Deref {
+    type Target;
+    fn deref(&self) -> &Self::Target;
+}
+
+struct A;
+struct B;
+impl Deref for A {
+    type Target = B;
+}
+impl Deref for B {
+    type Target = A;
+}
+impl B {
+    fn foo(&self) -> u128 {}
+}
+
+fn test(a: A) {
+    (a.foo(), a.bar())<|>;
+}
+"#,
+    );
+    assert_eq!(t, "(u128, {unknown})");
+}
+
 #[test]
 fn deref_trait_with_inference_var() {
     let t = type_at(
@@ -837,6 +1282,83 @@ fn test(x: impl Trait<u64>, y: &impl Trait<u64>) {
     );
 }
 
+#[test]
+fn argument_impl_trait_uses_bounds_in_body() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Clone {}
+trait Trait { fn foo(self) -> u128; }
+impl<T> Trait for T where T: Clone {}
+fn test(t: impl Clone) { t.foo()<|>; }
+"#,
+    );
+    assert_eq!(t, "u128");
+}
+
+#[test]
+fn argument_impl_trait_is_inferred_at_call_site() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Trait<T> { fn foo(&self) -> T; }
+struct S;
+impl Trait<u64> for S {}
+fn bar<T>(x: impl Trait<T>) -> T {}
+fn test() { bar(S)<|>; }
+"#,
+    );
+    assert_eq!(t, "u64");
+}
+
+#[test]
+fn return_impl_trait_uses_super_traits_out_of_scope() {
+    let t = type_at(
+        r#"
+//- /main.rs
+mod m {
+    pub trait Base { fn base(&self) -> u8; }
+    pub trait Iter: Base {
+        type Item;
+        fn next(&mut self) -> Self::Item;
+        fn by_ref(&mut self) -> &mut Self;
+    }
+}
+fn numbers() -> impl m::Iter<Item = u32> + Clone {}
+fn test() { (numbers().by_ref().next(), numbers().base())<|>; }
+"#,
+    );
+    assert_eq!(t, "(u32, u8)");
+}
+
+#[test]
+fn impl_trait_in_type_alias() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Future { type Output; fn poll(&self) -> Self::Output; }
+type Fut = impl Future<Output = u32>;
+fn test(fut: Fut) { fut.poll()<|>; }
+"#,
+    );
+    assert_eq!(t, "u32");
+}
+
+#[test]
+fn impl_trait_in_associated_type() {
+    let t = type_at(
+        r#"
+//- /main.rs
+trait Iterator { type Item; fn next(&mut self) -> Self::Item; }
+trait Container { type Iter: Iterator; fn iter(&self) -> Self::Iter; }
+struct S;
+impl Container for S { type Iter = impl Iterator<Item = u8>; }
+fn test(s: S) { s.iter().next()<|>; }
+"#,
+    );
+    assert_eq!(t, "u8");
+}
+
 #[test]
 fn dyn_trait() {
     assert_snapshot!(
@@ -1278,6 +1800,91 @@ fn test() {
     );
 }
 
+#[test]
+fn call_fn_pointer_through_reference() {
+    let t = type_at(
+        r#"
+//- /main.rs
+fn test(f: &fn(u32) -> u64) {
+    f(1)<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u64");
+}
+
+#[test]
+fn call_dyn_fn() {
+    let t = type_at(
+        r#"
+//- /main.rs
+#[lang = "fn_once"]
+trait FnOnce<Args> {
+    type Output;
+}
+#[lang = "fn_mut"]
+trait FnMut<Args>: FnOnce<Args> {}
+#[lang = "fn"]
+trait Fn<Args>: FnMut<Args> {}
+fn test(f: &dyn Fn(u32) -> u64) {
+    f(1)<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u64");
+}
+
+#[test]
+fn call_dyn_fn_without_return_type() {
+    let t = type_at(
+        r#"
+//- /main.rs
+#[lang = "fn_once"]
+trait FnOnce<Args> {
+    type Output;
+}
+#[lang = "fn_mut"]
+trait FnMut<Args>: FnOnce<Args> {}
+#[lang = "fn"]
+trait Fn<Args>: FnMut<Args> {}
+fn test(f: &mut dyn FnMut(u32)) {
+    f(1)<|>;
+}
+"#,
+    );
+    assert_eq!(t, "()");
+}
+
+#[test]
+fn call_boxed_dyn_fn() {
+    let t = type_at(
+        r#"
+//- /main.rs
+#[lang = "fn_once"]
+trait FnOnce<Args> {
+    type Output;
+}
+#[lang = "fn_mut"]
+trait FnMut<Args>: FnOnce<Args> {}
+#[lang = "fn"]
+trait Fn<Args>: FnMut<Args> {}
+#[lang = "deref"]
+trait Deref {
+    type Target;
+    fn deref(&self) -> &Self::Target;
+}
+struct Box<T: ?Sized>;
+impl<T: ?Sized> Deref for Box<T> {
+    type Target = T;
+}
+fn test(f: Box<dyn Fn(u32) -> u64>) {
+    f(1)<|>;
+}
+"#,
+    );
+    assert_eq!(t, "u64");
+}
+
 #[test]
 fn closure_2() {
     assert_snapshot!(
@@ -1579,3 +2186,55 @@ fn test() -> impl Trait<i32> {
     "###
     );
 }
+
+#[test]
+fn auto_trait_implemented_through_fields() {
+    let t = type_at(
+        r#"
+//- /main.rs
+auto trait Send {}
+
+struct S;
+struct NotSend;
+impl !Send for NotSend {}
+struct Wrapper(NotSend);
+struct Generic<T>(T);
+enum E { A(S), B(NotSend) }
+
+trait Foo {
+    fn foo(&self) -> u32;
+}
+impl<T: Send> Foo for T {}
+
+fn test() {
+    (S.foo(), NotSend.foo(), Wrapper(NotSend).foo(), Generic(S).foo(), Generic(NotSend).foo(), E::A(S).foo(), (S, S).foo())<|>;
+}
+"#,
+    );
+    assert_eq!(t, "(u32, {unknown}, {unknown}, u32, {unknown}, {unknown}, u32)");
+}
+
+#[test]
+fn auto_trait_explicit_impl_overrides_fields() {
+    let t = type_at(
+        r#"
+//- /main.rs
+auto trait Sync {}
+
+struct NotSync;
+impl !Sync for NotSync {}
+struct Cell(NotSync);
+unsafe impl Sync for Cell {}
+
+trait Foo {
+    fn foo(&self) -> u32;
+}
+impl<T: Sync> Foo for T {}
+
+fn test() {
+    (Cell(NotSync).foo(), NotSync.foo())<|>;
+}
+"#,
+    );
+    assert_eq!(t, "(u32, {unknown})");
+}
@@ -2,7 +2,9 @@
 use std::sync::{Arc, Mutex};
 
 use chalk_ir::{cast::Cast, family::ChalkIr};
-use hir_def::{expr::ExprId, DefWithBodyId, ImplId, TraitId, TypeAliasId};
+use hir_def::{
+    expr::ExprId, lang_item::LangItemTarget, DefWithBodyId, ImplId, TraitId, TypeAliasId,
+};
 use log::debug;
 use ra_db::{impl_intern_key, salsa, CrateId};
 use ra_prof::profile;
@@ -118,6 +120,18 @@ impl TraitEnvironment {
             _ => None,
         })
     }
+
+    /// Returns the type the given projection is bound to in this trait env.
+    /// E.g. if we are in `foo<T: Iterator<Item = u32>>()`, this will find that
+    /// `T::Item` is `u32`.
+    pub(crate) fn projection_binding(&self, projection_ty: &ProjectionTy) -> Option<&Ty> {
+        self.predicates.iter().find_map(|pred| match pred {
+            GenericPredicate::Projection(pred) if &pred.projection_ty == projection_ty => {
+                Some(&pred.ty)
+            }
+            _ => None,
+        })
+    }
 }
 
 /// Something (usually a goal), along with an environment.
@@ -289,6 +303,13 @@ impl FnTrait {
             FnTrait::Fn => "fn",
         }
     }
+
+    pub(crate) fn get_id(self, db: &impl HirDatabase, krate: CrateId) -> Option<TraitId> {
+        match db.lang_item(krate, self.lang_item_name().into())? {
+            LangItemTarget::TraitId(t) => Some(t),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -10,7 +10,8 @@ use chalk_ir::{
 use chalk_rust_ir::{AssociatedTyDatum, AssociatedTyValue, ImplDatum, StructDatum, TraitDatum};
 
 use hir_def::{
-    AssocContainerId, AssocItemId, GenericDefId, HasModule, ImplId, Lookup, TraitId, TypeAliasId,
+    AdtId, AssocContainerId, AssocItemId, EnumVariantId, GenericDefId, HasModule, ImplId, Lookup,
+    TraitId, TypeAliasId, VariantId,
 };
 use ra_db::{
     salsa::{InternId, InternKey},
@@ -184,7 +185,9 @@ impl ToChalk for TypeCtor {
     type Chalk = chalk_ir::StructId;
 
     fn to_chalk(self, db: &impl HirDatabase) -> chalk_ir::StructId {
-        db.intern_type_ctor(self).into()
+        // FIXME: chalk knows nothing about const generics, so impls for arrays
+        // of a given length apply to arrays of all lengths
+        db.intern_type_ctor(self.without_array_len()).into()
     }
 
     fn from_chalk(db: &impl HirDatabase, struct_id: chalk_ir::StructId) -> TypeCtor {
@@ -514,7 +517,16 @@ where
         struct_id: chalk_ir::StructId,
     ) -> bool {
         debug!("impl_provided_for {:?}, {:?}", auto_trait_id, struct_id);
-        false // FIXME
+        let trait_: TraitId = from_chalk(self.db, auto_trait_id);
+        let type_ctor: TypeCtor = from_chalk(self.db, struct_id);
+        // FIXME: this does not take the generic arguments of the impl's self
+        // type into account, but that is enough for auto traits
+        self.db.impls_for_trait(self.krate, trait_.into()).iter().any(|&impl_id| {
+            match self.db.impl_self_ty(impl_id) {
+                Ty::Apply(a_ty) => a_ty.ctor.without_array_len() == type_ctor,
+                _ => false,
+            }
+        })
     }
     fn type_name(&self, _id: TypeKindId) -> Identifier {
         unimplemented!()
@@ -637,7 +649,7 @@ pub(crate) fn struct_datum_query(
         fundamental: false,
     };
     let struct_datum_bound = chalk_rust_ir::StructDatumBound {
-        fields: Vec::new(), // FIXME add fields (only relevant for auto traits)
+        fields: struct_fields(db, type_ctor, num_params),
         where_clauses,
     };
     let struct_datum =
@@ -645,6 +657,43 @@ pub(crate) fn struct_datum_query(
     Arc::new(struct_datum)
 }
 
+/// The types of the fields of the type, which decide whether it implements an
+/// auto trait (unless there is an explicit impl for it).
+fn struct_fields(
+    db: &impl HirDatabase,
+    type_ctor: TypeCtor,
+    num_params: usize,
+) -> Vec<chalk_ir::Ty<ChalkIr>> {
+    match type_ctor {
+        TypeCtor::Adt(adt) => {
+            let bound_vars = Substs::bound_vars(&generics(db, adt.into()));
+            let variants: Vec<VariantId> = match adt {
+                AdtId::StructId(it) => vec![it.into()],
+                AdtId::UnionId(it) => vec![VariantId::UnionId(it)],
+                AdtId::EnumId(it) => db
+                    .enum_data(it)
+                    .variants
+                    .iter()
+                    .map(|(local_id, _)| EnumVariantId { parent: it, local_id }.into())
+                    .collect(),
+            };
+            variants
+                .into_iter()
+                .flat_map(|variant| {
+                    db.field_types(variant)
+                        .iter()
+                        .map(|(_, ty)| ty.clone().subst(&bound_vars).to_chalk(db))
+                        .collect::<Vec<_>>()
+                })
+                .collect()
+        }
+        TypeCtor::Tuple { .. } | TypeCtor::Array { .. } | TypeCtor::Slice => {
+            (0..num_params).map(|idx| Ty::Bound(idx as u32).to_chalk(db)).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
 pub(crate) fn impl_datum_query(
     db: &impl HirDatabase,
     krate: CrateId,
@@ -0,0 +1,147 @@
+//! Computes the "N implementations" and "N references" annotations shown
+//! above definitions (code lenses in LSP terms).
+
+use ra_db::SourceDatabase;
+use ra_prof::profile;
+use ra_syntax::{SyntaxKind::*, TextRange};
+
+use crate::{
+    db::RootDatabase, display::file_structure, impls::goto_implementation,
+    references::find_all_refs, FileId, FilePosition, FileRange, NavigationTarget,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnnotationConfig {
+    /// Annotate structs, enums, unions and traits with their implementations.
+    pub impls: bool,
+    /// Annotate functions with their references.
+    pub references: bool,
+    /// Compute the data of the annotations right away. Otherwise it is left
+    /// empty, and should be computed with `resolve_annotation` once the
+    /// annotation is actually shown, as searching for implementations and
+    /// references is expensive.
+    pub resolve: bool,
+}
+
+#[derive(Debug)]
+pub struct Annotation {
+    /// The range of the annotated item.
+    pub range: TextRange,
+    pub kind: AnnotationKind,
+}
+
+#[derive(Debug)]
+pub enum AnnotationKind {
+    /// Implementations of the type or trait whose name is at `position`.
+    HasImpls { position: FilePosition, data: Option<Vec<NavigationTarget>> },
+    /// References to the function whose name is at `position`.
+    HasReferences { position: FilePosition, data: Option<Vec<FileRange>> },
+}
+
+pub(crate) fn annotations(
+    db: &RootDatabase,
+    file_id: FileId,
+    config: AnnotationConfig,
+) -> Vec<Annotation> {
+    let _p = profile("annotations");
+    let file = db.parse(file_id).tree();
+    let annotations = file_structure(&file).into_iter().filter_map(|node| {
+        let position = FilePosition { file_id, offset: node.navigation_range.start() };
+        let kind = match node.kind {
+            STRUCT_DEF | ENUM_DEF | UNION_DEF | TRAIT_DEF if config.impls => {
+                AnnotationKind::HasImpls { position, data: None }
+            }
+            FN_DEF if config.references => AnnotationKind::HasReferences { position, data: None },
+            _ => return None,
+        };
+        Some(Annotation { range: node.node_range, kind })
+    });
+    if config.resolve {
+        annotations.map(|it| resolve_annotation(db, it)).collect()
+    } else {
+        annotations.collect()
+    }
+}
+
+pub(crate) fn resolve_annotation(db: &RootDatabase, mut annotation: Annotation) -> Annotation {
+    match &mut annotation.kind {
+        AnnotationKind::HasImpls { position, data } => {
+            *data = goto_implementation(db, *position).map(|it| it.info);
+        }
+        AnnotationKind::HasReferences { position, data } => {
+            *data = find_all_refs(db, *position, None).map(|it| it.info.references().to_vec());
+        }
+    }
+    annotation
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_debug_snapshot;
+
+    use crate::{mock_analysis::single_file, AnnotationConfig, AnnotationKind};
+
+    fn render_annotations(text: &str, config: AnnotationConfig) -> Vec<String> {
+        let (analysis, file_id) = single_file(text);
+        analysis
+            .annotations(file_id, config)
+            .unwrap()
+            .into_iter()
+            .map(|it| {
+                let label = match it.kind {
+                    AnnotationKind::HasImpls { data: Some(data), .. } => {
+                        format!("{} implementations", data.len())
+                    }
+                    AnnotationKind::HasReferences { data: Some(data), .. } => {
+                        format!("{} references", data.len())
+                    }
+                    _ => "unresolved".to_string(),
+                };
+                format!("{:?} {}", it.range, label)
+            })
+            .collect()
+    }
+
+    const TEXT: &str = "struct Foo;
+impl Foo {}
+impl Foo {}
+trait Tr {}
+struct Bar;
+impl Tr for Bar {}
+fn f() {}
+fn main() { f(); f(); }
+";
+
+    #[test]
+    fn test_annotations() {
+        let config = AnnotationConfig { impls: true, references: true, resolve: true };
+        assert_debug_snapshot!(render_annotations(TEXT, config), @r###"
+        [
+            "[0; 11) 2 implementations",
+            "[36; 47) 1 implementations",
+            "[48; 59) 1 implementations",
+            "[79; 88) 2 references",
+            "[89; 112) 0 references",
+        ]
+        "###);
+    }
+
+    #[test]
+    fn test_lazy_annotations() {
+        let config = AnnotationConfig { impls: true, references: false, resolve: false };
+        assert_debug_snapshot!(render_annotations(TEXT, config), @r###"
+        [
+            "[0; 11) unresolved",
+            "[36; 47) unresolved",
+            "[48; 59) unresolved",
+        ]
+        "###);
+
+        let (analysis, file_id) = single_file(TEXT);
+        let annotation = analysis.annotations(file_id, config).unwrap().remove(0);
+        match analysis.resolve_annotation(annotation).unwrap().kind {
+            AnnotationKind::HasImpls { data: Some(data), .. } => assert_eq!(data.len(), 2),
+            kind => panic!("unexpected annotation {:?}", kind),
+        }
+    }
+}
@@ -2,7 +2,9 @@
 
 use ra_db::{FilePosition, FileRange};
 
-use crate::{db::RootDatabase, SourceChange, SourceFileEdit};
+use crate::{db::RootDatabase, imports_locator::ImportsLocatorIde, SourceChange, SourceFileEdit};
+
+use ra_assists::AssistConfig;
 
 pub use ra_assists::AssistId;
 
@@ -13,16 +15,27 @@ pub struct Assist {
 }
 
 pub(crate) fn assists(db: &RootDatabase, frange: FileRange) -> Vec<Assist> {
-    ra_assists::assists(db, frange)
+    let config = assist_config(db);
+    ra_assists::assists_with_imports_locator(db, frange, config, ImportsLocatorIde::new(db))
         .into_iter()
         .map(|(label, action)| {
             let file_id = frange.file_id;
-            let file_edit = SourceFileEdit { file_id, edit: action.edit };
+            let mut file_edits = vec![SourceFileEdit { file_id, edit: action.edit }];
+            file_edits.extend(
+                action
+                    .other_file_edits
+                    .into_iter()
+                    .map(|(file_id, edit)| SourceFileEdit { file_id, edit }),
+            );
             let id = label.id;
-            let change = SourceChange::source_file_edit(label.label, file_edit).with_cursor_opt(
+            let change = SourceChange::source_file_edits(label.label, file_edits).with_cursor_opt(
                 action.cursor_position.map(|offset| FilePosition { offset, file_id }),
             );
             Assist { id, change }
         })
         .collect()
 }
+
+pub(crate) fn assist_config(db: &RootDatabase) -> AssistConfig {
+    AssistConfig { fill_default_bodies: db.feature_flags.get("assists.fill-default-bodies") }
+}
@@ -261,6 +261,7 @@ impl RootDatabase {
         let sweep = SweepStrategy::default().discard_values().sweep_all_revisions();
 
         self.query(ra_db::ParseQuery).sweep(sweep);
+        self.query(ra_db::ParseWithEditionQuery).sweep(sweep);
         self.query(hir::db::ParseMacroQuery).sweep(sweep);
 
         // Macros do take significant space, but less then the syntax trees
@@ -299,6 +300,7 @@ impl RootDatabase {
         }
         sweep_each_query![
             ra_db::ParseQuery
+            ra_db::ParseWithEditionQuery
             ra_db::SourceRootCratesQuery
             hir::db::AstIdMapQuery
             hir::db::ParseMacroQuery
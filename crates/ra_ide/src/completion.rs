@@ -15,6 +15,13 @@ mod complete_path;
 mod complete_scope;
 mod complete_postfix;
 mod complete_macro_in_item_position;
+mod complete_cfg_feature;
+mod complete_derive;
+mod complete_expected_type;
+mod complete_impl_members;
+mod complete_builtin_macro;
+mod complete_format_spec;
+mod complete_macro_args;
 
 use ra_db::SourceDatabase;
 
@@ -28,8 +35,9 @@ use crate::{
     db, FilePosition,
 };
 
+pub(crate) use crate::completion::complete_scope::fixup_completion;
 pub use crate::completion::completion_item::{
-    CompletionItem, CompletionItemKind, InsertTextFormat,
+    CompletionItem, CompletionItemKind, CompletionScore, InsertTextFormat,
 };
 
 /// Main entry point for completion. We run completion as a two-phase process.
@@ -63,6 +71,9 @@ pub(crate) fn completions(db: &db::RootDatabase, position: FilePosition) -> Opti
     complete_fn_param::complete_fn_param(&mut acc, &ctx);
     complete_keyword::complete_expr_keyword(&mut acc, &ctx);
     complete_keyword::complete_use_tree_keyword(&mut acc, &ctx);
+    complete_keyword::complete_maybe_bound(&mut acc, &ctx);
+    complete_keyword::complete_where_keyword(&mut acc, &ctx);
+    complete_keyword::complete_item_keyword(&mut acc, &ctx);
     complete_snippet::complete_expr_snippet(&mut acc, &ctx);
     complete_snippet::complete_item_snippet(&mut acc, &ctx);
     complete_path::complete_path(&mut acc, &ctx);
@@ -73,5 +84,18 @@ pub(crate) fn completions(db: &db::RootDatabase, position: FilePosition) -> Opti
     complete_pattern::complete_pattern(&mut acc, &ctx);
     complete_postfix::complete_postfix(&mut acc, &ctx);
     complete_macro_in_item_position::complete_macro_in_item_position(&mut acc, &ctx);
+    complete_cfg_feature::complete_cfg_feature(&mut acc, &ctx);
+    complete_derive::complete_derive(&mut acc, &ctx);
+    complete_expected_type::complete_expected_type(&mut acc, &ctx);
+    complete_impl_members::complete_impl_members(&mut acc, &ctx);
+    complete_builtin_macro::complete_builtin_macro(&mut acc, &ctx);
+    complete_format_spec::complete_format_spec(&mut acc, &ctx);
+    complete_macro_args::complete_macro_args(&mut acc, &ctx);
+
+    // items starting with `__` are internals, e.g. of macros, which are only
+    // offered when asked for
+    if !ctx.typed_prefix().starts_with('_') {
+        acc.retain(|item| !item.lookup().starts_with("__"));
+    }
     Some(acc)
 }
@@ -0,0 +1,125 @@
+//! Completes the macros of the standard library, like `vec!` or `env!`, which
+//! are not always in scope as ordinary definitions.
+
+use rustc_hash::FxHashSet;
+
+use crate::completion::{
+    CompletionContext, CompletionItem, CompletionItemKind, CompletionKind, Completions,
+};
+
+/// The name of the macro, the snippet inserted for it and the shape of its
+/// arguments.
+const BUILTIN_MACROS: &[(&str, &str, &str)] = &[
+    ("assert", "assert!($0)", "assert!(cond, ...)"),
+    ("assert_eq", "assert_eq!($1, $2)", "assert_eq!(left, right, ...)"),
+    ("assert_ne", "assert_ne!($1, $2)", "assert_ne!(left, right, ...)"),
+    ("cfg", "cfg!($0)", "cfg!(predicate)"),
+    ("column", "column!()", "column!()"),
+    ("compile_error", "compile_error!(\"$0\")", "compile_error!(\"message\")"),
+    ("concat", "concat!($0)", "concat!(literal, ...)"),
+    ("dbg", "dbg!($0)", "dbg!(expr)"),
+    ("env", "env!(\"$0\")", "env!(\"NAME\")"),
+    ("eprint", "eprint!(\"$0\")", "eprint!(\"format\", args...)"),
+    ("eprintln", "eprintln!(\"$0\")", "eprintln!(\"format\", args...)"),
+    ("file", "file!()", "file!()"),
+    ("format", "format!(\"$0\")", "format!(\"format\", args...)"),
+    ("format_args", "format_args!(\"$0\")", "format_args!(\"format\", args...)"),
+    ("include", "include!(\"$0\")", "include!(\"path\")"),
+    ("include_bytes", "include_bytes!(\"$0\")", "include_bytes!(\"path\")"),
+    ("include_str", "include_str!(\"$0\")", "include_str!(\"path\")"),
+    ("line", "line!()", "line!()"),
+    ("module_path", "module_path!()", "module_path!()"),
+    ("option_env", "option_env!(\"$0\")", "option_env!(\"NAME\")"),
+    ("panic", "panic!(\"$0\")", "panic!(\"format\", args...)"),
+    ("print", "print!(\"$0\")", "print!(\"format\", args...)"),
+    ("println", "println!(\"$0\")", "println!(\"format\", args...)"),
+    ("stringify", "stringify!($0)", "stringify!(tokens)"),
+    ("todo", "todo!()", "todo!()"),
+    ("unimplemented", "unimplemented!()", "unimplemented!()"),
+    ("unreachable", "unreachable!()", "unreachable!()"),
+    ("vec", "vec![$0]", "vec![elem, ...]"),
+    ("write", "write!($1, \"$0\")", "write!(dst, \"format\", args...)"),
+    ("writeln", "writeln!($1, \"$0\")", "writeln!(dst, \"format\", args...)"),
+];
+
+pub(super) fn complete_builtin_macro(acc: &mut Completions, ctx: &CompletionContext) {
+    if !ctx.is_trivial_path || ctx.is_path_type || ctx.function_syntax.is_none() {
+        return;
+    }
+
+    // macros which are in scope are completed with their own definition
+    let mut in_scope = FxHashSet::default();
+    ctx.analyzer.process_all_names(ctx.db, &mut |name, res| {
+        if let hir::ScopeDef::MacroDef(_) = res {
+            in_scope.insert(name.to_string());
+        }
+    });
+
+    for &(name, snippet, detail) in BUILTIN_MACROS {
+        if in_scope.contains(name) {
+            continue;
+        }
+        CompletionItem::new(CompletionKind::BuiltinMacro, ctx.source_range(), format!("{}!", name))
+            .kind(CompletionItemKind::Macro)
+            .insert_snippet(snippet)
+            .detail(detail)
+            .add_to(acc);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::completion::{do_completion, CompletionItem, CompletionKind};
+    use insta::assert_debug_snapshot;
+
+    fn do_builtin_macro_completion(code: &str) -> Vec<CompletionItem> {
+        do_completion(code, CompletionKind::BuiltinMacro)
+    }
+
+    #[test]
+    fn completes_builtin_macros_in_expressions() {
+        let completions = do_builtin_macro_completion(r"fn foo() { let x = <|> }");
+        let completions: Vec<_> = completions
+            .into_iter()
+            .filter(|it| it.label() == "env!" || it.label() == "vec!")
+            .collect();
+        assert_debug_snapshot!(completions, @r###"
+        [
+            CompletionItem {
+                label: "env!",
+                source_range: [19; 19),
+                delete: [19; 19),
+                insert: "env!(\"$0\")",
+                kind: Macro,
+                detail: "env!(\"NAME\")",
+            },
+            CompletionItem {
+                label: "vec!",
+                source_range: [19; 19),
+                delete: [19; 19),
+                insert: "vec![$0]",
+                kind: Macro,
+                detail: "vec![elem, ...]",
+            },
+        ]
+        "###);
+    }
+
+    #[test]
+    fn does_not_complete_builtin_macros_shadowed_by_macros_in_scope() {
+        let completions = do_builtin_macro_completion(
+            r"
+            macro_rules! vec { () => {} }
+            fn foo() { <|> }
+            ",
+        );
+        assert!(completions.iter().all(|it| it.label() != "vec!"));
+        assert!(completions.iter().any(|it| it.label() == "env!"));
+    }
+
+    #[test]
+    fn does_not_complete_builtin_macros_outside_of_expressions() {
+        assert_debug_snapshot!(do_builtin_macro_completion(r"fn foo() -> <|> {}"), @"[]");
+        assert_debug_snapshot!(do_builtin_macro_completion(r"fn foo() { ::<|> }"), @"[]");
+    }
+}
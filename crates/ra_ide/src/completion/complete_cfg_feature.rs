@@ -0,0 +1,149 @@
+//! Completes feature names declared in `Cargo.toml` inside `#[cfg(feature = "<|>")]`.
+
+use ra_syntax::{
+    algo::non_trivia_sibling, ast, AstNode, Direction, SyntaxKind::*, SyntaxToken, TextRange,
+    TextUnit,
+};
+
+use crate::completion::{CompletionContext, CompletionItem, CompletionKind, Completions};
+
+pub(super) fn complete_cfg_feature(acc: &mut Completions, ctx: &CompletionContext) {
+    if !is_in_cfg_feature_string(&ctx.token) {
+        return;
+    }
+    let krate = match ctx.module {
+        Some(module) => module.krate(),
+        None => return,
+    };
+    let range = string_contents_range(&ctx.token);
+    for feature in krate.declared_features(ctx.db) {
+        CompletionItem::new(CompletionKind::Magic, range, feature.as_str()).add_to(acc);
+    }
+}
+
+/// Checks for `feature = "<|>"` inside of a `cfg` or `cfg_attr` attribute.
+fn is_in_cfg_feature_string(token: &SyntaxToken) -> bool {
+    if token.kind() != STRING {
+        return false;
+    }
+    let eq = match non_trivia_sibling(token.clone().into(), Direction::Prev) {
+        Some(it) if it.kind() == EQ => it,
+        _ => return false,
+    };
+    match non_trivia_sibling(eq, Direction::Prev).and_then(|it| it.into_token()) {
+        Some(it) if it.kind() == IDENT && it.text().as_str() == "feature" => (),
+        _ => return false,
+    }
+    let attr = match token.parent().ancestors().find_map(ast::Attr::cast) {
+        Some(it) => it,
+        None => return false,
+    };
+    match attr.path() {
+        Some(path) => {
+            let path = path.syntax().text();
+            path == "cfg" || path == "cfg_attr"
+        }
+        None => false,
+    }
+}
+
+/// The range between the quotes of a (possibly unterminated) string literal.
+fn string_contents_range(token: &SyntaxToken) -> TextRange {
+    let range = token.text_range();
+    let quote = TextUnit::of_char('"');
+    let end = if token.text().len() > 1 && token.text().ends_with('"') {
+        range.end() - quote
+    } else {
+        range.end()
+    };
+    TextRange::from_to(range.start() + quote, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use insta::assert_debug_snapshot;
+    use ra_cfg::CfgOptions;
+    use ra_db::Env;
+    use test_utils::extract_offset;
+
+    use crate::{
+        completion::{completions, CompletionItem},
+        AnalysisChange, AnalysisHost, CrateGraph, Edition, FileId, FilePosition, SourceRootId,
+    };
+
+    fn complete_features(code: &str, features: &[&str]) -> Vec<CompletionItem> {
+        let (offset, text) = extract_offset(code);
+        let mut host = AnalysisHost::default();
+        let source_root = SourceRootId(0);
+        let mut change = AnalysisChange::new();
+        change.add_root(source_root, true);
+        let mut crate_graph = CrateGraph::default();
+        let file_id = FileId(1);
+        let crate_id = crate_graph.add_crate_root(
+            file_id,
+            Edition::Edition2018,
+            CfgOptions::default(),
+            Env::default(),
+        );
+        crate_graph.set_declared_features(crate_id, features.iter().map(|&it| it.into()).collect());
+        change.add_file(source_root, file_id, "main.rs".into(), Arc::new(text));
+        change.set_crate_graph(crate_graph);
+        host.apply_change(change);
+
+        let analysis = host.analysis();
+        let completions = completions(&analysis.db, FilePosition { file_id, offset }).unwrap();
+        let items: Vec<CompletionItem> = completions.into();
+        let mut items: Vec<CompletionItem> =
+            items.into_iter().filter(|it| features.contains(&it.label())).collect();
+        items.sort_by_key(|it| it.label().to_string());
+        items
+    }
+
+    #[test]
+    fn completes_declared_features() {
+        assert_debug_snapshot!(
+            complete_features(r#"#[cfg(feature = "<|>")] fn foo() {}"#, &["serde", "std"]),
+            @r###"
+        [
+            CompletionItem {
+                label: "serde",
+                source_range: [17; 17),
+                delete: [17; 17),
+                insert: "serde",
+            },
+            CompletionItem {
+                label: "std",
+                source_range: [17; 17),
+                delete: [17; 17),
+                insert: "std",
+            },
+        ]
+        "###
+        );
+    }
+
+    #[test]
+    fn completes_features_in_nested_predicates() {
+        assert_debug_snapshot!(
+            complete_features(r#"#[cfg(any(test, feature = "s<|>"))] fn foo() {}"#, &["std"]),
+            @r###"
+        [
+            CompletionItem {
+                label: "std",
+                source_range: [27; 28),
+                delete: [27; 28),
+                insert: "std",
+            },
+        ]
+        "###
+        );
+    }
+
+    #[test]
+    fn does_not_complete_features_outside_of_cfg() {
+        assert!(complete_features(r#"fn foo() { let x = "<|>"; }"#, &["std"]).is_empty());
+        assert!(complete_features(r#"#[doc(feature = "<|>")] fn foo() {}"#, &["std"]).is_empty());
+    }
+}
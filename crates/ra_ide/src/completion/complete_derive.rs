@@ -0,0 +1,220 @@
+//! Completes the traits which can be derived inside `#[derive(<|>)]`: the
+//! built-in derives, and the custom derives of the dependencies, which are
+//! imported if needed.
+
+use hir::{HasSource, ModuleDef};
+use ra_syntax::{
+    ast::{self, AttrsOwner},
+    AstNode, SmolStr,
+    SyntaxKind::*,
+    SyntaxToken,
+};
+
+use crate::completion::{
+    complete_scope::build_import_label, CompletionContext, CompletionItem, CompletionItemKind,
+    CompletionKind, Completions,
+};
+
+const BUILTIN_DERIVES: &[&str] =
+    &["Clone", "Copy", "Debug", "Default", "Eq", "Hash", "Ord", "PartialEq", "PartialOrd"];
+
+pub(super) fn complete_derive(acc: &mut Completions, ctx: &CompletionContext) {
+    let token_tree = match derive_token_tree(&ctx.token) {
+        Some(it) => it,
+        None => return,
+    };
+    // the traits which are already derived aren't offered again
+    let existing: Vec<String> = token_tree
+        .syntax()
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| it.kind() == IDENT && it.text_range() != ctx.token.text_range())
+        .map(|it| it.text().to_string())
+        .collect();
+
+    for name in BUILTIN_DERIVES.iter().copied() {
+        if !existing.iter().any(|it| it == name) {
+            CompletionItem::new(CompletionKind::Magic, ctx.source_range(), name)
+                .kind(CompletionItemKind::Trait)
+                .add_to(acc);
+        }
+    }
+
+    let module = match ctx.module {
+        Some(it) => it,
+        None => return,
+    };
+    for dep in module.krate().dependencies(ctx.db) {
+        let root = match dep.krate.root_module(ctx.db) {
+            Some(it) => it,
+            None => continue,
+        };
+        for def in root.declarations(ctx.db) {
+            let name = match def {
+                ModuleDef::Function(it) => match custom_derive_name(&it.source(ctx.db).value) {
+                    Some(it) => it,
+                    None => continue,
+                },
+                _ => continue,
+            };
+            if existing.iter().any(|it| *it == name) {
+                continue;
+            }
+            let item =
+                CompletionItem::new(CompletionKind::Magic, ctx.source_range(), name.as_str())
+                    .kind(CompletionItemKind::Macro);
+            if is_imported(ctx, &name) {
+                item.add_to(acc);
+            } else {
+                // the `use` is added when the item is resolved, see `fixup_completion`
+                let path = vec![SmolStr::new(dep.name.to_string()), name.clone()];
+                item.label(build_import_label(&name, &path))
+                    .insert_text(name.as_str())
+                    .import_to_add(path)
+                    .add_to(acc);
+            }
+        }
+    }
+}
+
+/// The `(..)` token tree of the `#[derive]` attribute around the cursor.
+fn derive_token_tree(token: &SyntaxToken) -> Option<ast::TokenTree> {
+    // after the closing parenthesis, the cursor is out of the list
+    if token.kind() == R_PAREN {
+        return None;
+    }
+    let token_tree = ast::TokenTree::cast(token.parent())?;
+    let attr = ast::Attr::cast(token_tree.syntax().parent()?)?;
+    if attr.path()?.syntax().text() != "derive" {
+        return None;
+    }
+    Some(token_tree)
+}
+
+/// The name of the derive defined by a `#[proc_macro_derive(Name)]` function.
+fn custom_derive_name(fn_def: &ast::FnDef) -> Option<SmolStr> {
+    let attr = fn_def
+        .attrs()
+        .find(|it| it.path().map_or(false, |path| path.syntax().text() == "proc_macro_derive"))?;
+    let name = attr
+        .input()
+        .and_then(|it| match it {
+            ast::AttrInput::TokenTree(it) => Some(it),
+            ast::AttrInput::Literal(_) => None,
+        })?
+        .syntax()
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .find(|it| it.kind() == IDENT)?;
+    Some(name.text().clone())
+}
+
+/// Whether a `use` of the file brings `name` into scope. Derive macros aren't
+/// resolved, so the scope of the module doesn't know about them.
+fn is_imported(ctx: &CompletionContext, name: &str) -> bool {
+    let file = ctx.token.ancestors().last().unwrap_or_else(|| ctx.token.parent());
+    file.descendants().filter_map(ast::UseTree::cast).any(|tree| {
+        if tree.use_tree_list().is_some() {
+            return false;
+        }
+        let imported = match tree.alias().and_then(|it| it.name()) {
+            Some(alias) => alias.text().clone(),
+            None => match tree.path().and_then(|it| it.segment()).and_then(|it| it.name_ref()) {
+                Some(name_ref) => name_ref.text().clone(),
+                None => return false,
+            },
+        };
+        imported == name
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_debug_snapshot;
+
+    use crate::completion::{do_completion, CompletionItem, CompletionItemKind, CompletionKind};
+
+    fn do_derive_completion(code: &str) -> Vec<CompletionItem> {
+        do_completion(code, CompletionKind::Magic)
+    }
+
+    fn labels(code: &str) -> Vec<String> {
+        do_derive_completion(code).into_iter().map(|it| it.label().to_string()).collect()
+    }
+
+    #[test]
+    fn completes_builtin_derives() {
+        assert_eq!(
+            labels("#[derive(<|>)] struct S;"),
+            vec![
+                "Clone",
+                "Copy",
+                "Debug",
+                "Default",
+                "Eq",
+                "Hash",
+                "Ord",
+                "PartialEq",
+                "PartialOrd"
+            ]
+        );
+    }
+
+    #[test]
+    fn does_not_complete_already_derived_traits() {
+        assert_eq!(
+            labels("#[derive(Clone, Debug, Pa<|>)] struct S;"),
+            vec!["Copy", "Default", "Eq", "Hash", "Ord", "PartialEq", "PartialOrd"]
+        );
+    }
+
+    #[test]
+    fn does_not_complete_derives_outside_of_derive() {
+        assert!(labels("#[cfg(<|>)] struct S;").is_empty());
+        assert!(labels("#[derive(Clone)] struct S { f: <|> }").is_empty());
+    }
+
+    #[test]
+    fn completes_custom_derives_of_dependencies() {
+        let completions = do_derive_completion(
+            r"
+            //- /main.rs
+            use serde_derive::Deserialize;
+
+            #[derive(Clone, <|>)]
+            struct S;
+
+            //- /serde_derive/lib.rs
+            #[proc_macro_derive(Serialize, attributes(serde))]
+            pub fn derive_serialize(input: TokenStream) -> TokenStream {}
+            #[proc_macro_derive(Deserialize)]
+            pub fn derive_deserialize(input: TokenStream) -> TokenStream {}
+            pub fn helper() {}
+            ",
+        );
+        let custom: Vec<_> =
+            completions.iter().filter(|it| it.kind() == Some(CompletionItemKind::Macro)).collect();
+        assert_debug_snapshot!(custom, @r###"
+        [
+            CompletionItem {
+                label: "Deserialize",
+                source_range: [48; 48),
+                delete: [48; 48),
+                insert: "Deserialize",
+                kind: Macro,
+            },
+            CompletionItem {
+                label: "Serialize (serde_derive::Serialize)",
+                source_range: [48; 48),
+                delete: [48; 48),
+                insert: "Serialize",
+                kind: Macro,
+                import_to_add: [
+                    "serde_derive",
+                    "Serialize",
+                ],
+            },
+        ]
+        "###);
+    }
+}
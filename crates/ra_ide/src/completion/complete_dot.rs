@@ -216,6 +216,83 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_method_completion_through_autoref_and_unsizing() {
+        let labels = |code| {
+            let mut labels: Vec<_> =
+                do_ref_completion(code).into_iter().map(|it| it.label().to_string()).collect();
+            labels.sort();
+            labels
+        };
+        assert_eq!(
+            labels(
+                r"
+                struct A {}
+                impl A {
+                    fn by_ref(&self) {}
+                    fn by_mut(&mut self) {}
+                    fn by_value(self) {}
+                }
+                fn foo(a: &mut &mut A) {
+                   a.<|>
+                }
+                ",
+            ),
+            vec!["by_mut()", "by_ref()", "by_value()"]
+        );
+        assert_eq!(
+            labels(
+                r#"
+                #[lang = "slice"]
+                impl<T> [T] {
+                    fn len(&self) -> usize { 0 }
+                }
+                fn foo(a: [u8; 4]) {
+                   a.<|>
+                }
+                "#,
+            ),
+            vec!["len()"]
+        );
+    }
+
+    #[test]
+    fn test_method_completion_on_self_in_default_trait_method() {
+        let mut labels: Vec<_> = do_ref_completion(
+            r"
+            trait Base { fn base(&self) {} }
+            trait Tr: Base {
+                fn required(&self);
+                fn provided(&self) {
+                    self.<|>
+                }
+            }
+            ",
+        )
+        .into_iter()
+        .map(|it| it.label().to_string())
+        .collect();
+        labels.sort();
+        assert_eq!(labels, vec!["base()", "provided()", "required()"]);
+    }
+
+    #[test]
+    fn test_method_completion_of_trait_imported_as_underscore() {
+        let completions = do_ref_completion(
+            r"
+            mod m {
+                pub trait Tr { fn the_method(&self) {} }
+                impl Tr for u32 {}
+            }
+            use m::Tr as _;
+            fn foo(a: u32) {
+               a.<|>
+            }
+            ",
+        );
+        assert!(completions.iter().any(|it| it.label() == "the_method()"));
+    }
+
     #[test]
     fn test_method_completion_only_fitting_impls() {
         assert_debug_snapshot!(
@@ -278,6 +355,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_method_completion_on_impl_trait_argument() {
+        assert_debug_snapshot!(
+            do_ref_completion(
+                r"
+            trait Display {}
+            trait ToString { fn to_string(&self) -> u64; }
+            impl<T: Display> ToString for T {}
+            fn foo(x: impl Display) {
+               x.<|>
+            }
+            ",
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "to_string()",
+                source_range: [191; 191),
+                delete: [191; 191),
+                insert: "to_string()$0",
+                kind: Method,
+                lookup: "to_string",
+                detail: "fn to_string(&self) -> u64",
+            },
+        ]
+        "###
+        );
+    }
+
     #[test]
     fn test_trait_method_completion_deduplicated() {
         assert_debug_snapshot!(
@@ -429,6 +535,74 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_nested_tuple_field_completion() {
+        assert_debug_snapshot!(
+        do_ref_completion(
+            r"
+            fn foo() {
+                let t = ((1, true), 2);
+                t.0.<|>
+            }
+            ",
+        ),
+        @r###"
+        [
+            CompletionItem {
+                label: "0",
+                source_range: [84; 84),
+                delete: [84; 84),
+                insert: "0",
+                kind: Field,
+                detail: "i32",
+            },
+            CompletionItem {
+                label: "1",
+                source_range: [84; 84),
+                delete: [84; 84),
+                insert: "1",
+                kind: Field,
+                detail: "bool",
+            },
+        ]
+        "###
+        );
+    }
+
+    #[test]
+    fn test_tuple_struct_field_completion() {
+        assert_debug_snapshot!(
+        do_ref_completion(
+            r"
+            struct S(u32, (i64, bool));
+            fn foo(s: S) {
+                s.<|>
+            }
+            ",
+        ),
+        @r###"
+        [
+            CompletionItem {
+                label: "0",
+                source_range: [86; 86),
+                delete: [86; 86),
+                insert: "0",
+                kind: Field,
+                detail: "u32",
+            },
+            CompletionItem {
+                label: "1",
+                source_range: [86; 86),
+                delete: [86; 86),
+                insert: "1",
+                kind: Field,
+                detail: "(i64, bool)",
+            },
+        ]
+        "###
+        );
+    }
+
     #[test]
     fn test_completion_works_in_consts() {
         assert_debug_snapshot!(
@@ -486,4 +660,76 @@ mod tests {
         "###
         )
     }
+
+    #[test]
+    fn test_iterator_methods_are_scored_in_for_loop_head() {
+        assert_debug_snapshot!(
+        do_ref_completion(
+            r"
+            //- /main.rs
+            struct V {}
+            impl V {
+                fn iter(&self) {}
+                fn len(&self) {}
+            }
+            impl std::iter::IntoIterator for V {}
+            fn foo(v: V) {
+                for x in v.<|> {}
+            }
+
+            //- /std/lib.rs
+            pub mod iter {
+                pub trait IntoIterator {}
+            }
+            ",
+        ),
+        @r###"
+        [
+            CompletionItem {
+                label: "iter()",
+                source_range: [134; 134),
+                delete: [134; 134),
+                insert: "iter()$0",
+                kind: Method,
+                lookup: "iter",
+                detail: "fn iter(&self)",
+                score: ContextMatch,
+            },
+            CompletionItem {
+                label: "len()",
+                source_range: [134; 134),
+                delete: [134; 134),
+                insert: "len()$0",
+                kind: Method,
+                lookup: "len",
+                detail: "fn len(&self)",
+            },
+        ]
+        "###
+        );
+    }
+
+    #[test]
+    fn test_iterator_methods_are_not_scored_outside_of_for_loop_head() {
+        let completions = do_ref_completion(
+            r"
+            //- /main.rs
+            struct V {}
+            impl V {
+                fn iter(&self) {}
+            }
+            impl std::iter::IntoIterator for V {}
+            fn foo(v: V) {
+                let it = v.<|>;
+            }
+
+            //- /std/lib.rs
+            pub mod iter {
+                pub trait IntoIterator {}
+            }
+            ",
+        );
+        assert_eq!(completions.len(), 1);
+        assert!(completions[0].score().is_none());
+    }
 }
@@ -0,0 +1,170 @@
+//! Completes values of the type expected at the cursor, like variants of the
+//! enum a call argument should have.
+
+use hir::Adt;
+
+use crate::completion::{CompletionContext, Completions};
+
+pub(super) fn complete_expected_type(acc: &mut Completions, ctx: &CompletionContext) {
+    if !ctx.is_trivial_path {
+        return;
+    }
+    let expected_type = match &ctx.expected_type {
+        Some(it) => it,
+        None => return,
+    };
+    if let Some(Adt::Enum(e)) = expected_type.as_adt() {
+        for variant in e.variants(ctx.db) {
+            acc.add_qualified_enum_variant(ctx, variant);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_debug_snapshot;
+
+    use crate::completion::{do_completion, CompletionItem, CompletionKind};
+
+    fn do_qualified_completion(code: &str) -> Vec<CompletionItem> {
+        do_completion(code, CompletionKind::Reference)
+            .into_iter()
+            .filter(|it| it.label().contains("::"))
+            .collect()
+    }
+
+    #[test]
+    fn completes_variants_of_expected_fn_argument() {
+        assert_debug_snapshot!(
+            do_qualified_completion(
+                r"
+                enum E { A, B(u32) }
+                fn foo(x: u32, e: E) {}
+                fn main() { foo(92, <|>) }
+                ",
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "E::A",
+                source_range: [114; 114),
+                delete: [114; 114),
+                insert: "E::A",
+                kind: EnumVariant,
+                detail: "()",
+            },
+            CompletionItem {
+                label: "E::B",
+                source_range: [114; 114),
+                delete: [114; 114),
+                insert: "E::B",
+                kind: EnumVariant,
+                detail: "(u32)",
+            },
+        ]
+        "###
+        );
+    }
+
+    #[test]
+    fn completes_variants_of_expected_variant_field() {
+        assert_debug_snapshot!(
+            do_qualified_completion(
+                r"
+                enum E { A }
+                enum F { C(E) }
+                fn main() { F::C(<|>) }
+                ",
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "E::A",
+                source_range: [95; 95),
+                delete: [95; 95),
+                insert: "E::A",
+                kind: EnumVariant,
+                detail: "()",
+            },
+        ]
+        "###
+        );
+    }
+
+    #[test]
+    fn completes_variants_of_returned_value() {
+        assert_debug_snapshot!(
+            do_qualified_completion(
+                r"
+                enum E { A, B(u32) }
+                fn foo() -> E {
+                    return <|>
+                }
+                ",
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "E::A",
+                source_range: [97; 97),
+                delete: [97; 97),
+                insert: "E::A",
+                kind: EnumVariant,
+                detail: "()",
+            },
+            CompletionItem {
+                label: "E::B",
+                source_range: [97; 97),
+                delete: [97; 97),
+                insert: "E::B",
+                kind: EnumVariant,
+                detail: "(u32)",
+            },
+        ]
+        "###
+        );
+    }
+
+    #[test]
+    fn completes_variants_of_break_value() {
+        assert_debug_snapshot!(
+            do_qualified_completion(
+                r"
+                enum E { A }
+                fn main() {
+                    loop {
+                        if true {
+                            break E::A;
+                        }
+                        break <|>
+                    };
+                }
+                ",
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "E::A",
+                source_range: [215; 215),
+                delete: [215; 215),
+                insert: "E::A",
+                kind: EnumVariant,
+                detail: "()",
+            },
+        ]
+        "###
+        );
+    }
+
+    #[test]
+    fn does_not_complete_variants_for_unrelated_argument() {
+        assert!(do_qualified_completion(
+            r"
+            enum E { A }
+            fn foo(x: u32, e: E) {}
+            fn main() { foo(<|>) }
+            ",
+        )
+        .is_empty());
+    }
+}
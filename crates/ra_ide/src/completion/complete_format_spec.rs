@@ -0,0 +1,157 @@
+//! Completes the format specifiers inside the placeholders of the format
+//! string of `format!` and friends, like `{:<|>}`.
+
+use ra_syntax::{ast, AstNode, SyntaxKind::*, SyntaxToken, TextUnit};
+
+use crate::completion::{CompletionContext, CompletionItem, CompletionKind, Completions};
+
+/// The macros whose first string literal argument is a format string.
+const FORMAT_MACROS: &[&str] = &[
+    "format",
+    "format_args",
+    "print",
+    "println",
+    "eprint",
+    "eprintln",
+    "write",
+    "writeln",
+    "panic",
+];
+
+/// The specifier and what it does.
+const FORMAT_SPECS: &[(&str, &str)] = &[
+    ("?", "Debug"),
+    ("#?", "Debug, pretty-printed"),
+    ("x", "LowerHex"),
+    ("X", "UpperHex"),
+    ("#x", "LowerHex, with a `0x` prefix"),
+    ("o", "Octal"),
+    ("b", "Binary"),
+    ("#b", "Binary, with a `0b` prefix"),
+    ("e", "LowerExp"),
+    ("E", "UpperExp"),
+    ("<", "align left, followed by the width"),
+    ("^", "align center, followed by the width"),
+    (">", "align right, followed by the width"),
+    ("+", "always print the sign"),
+    ("0", "pad with zeros, followed by the width"),
+    (".", "precision, like `.2`"),
+    (".*", "precision taken from the argument before the value"),
+];
+
+pub(super) fn complete_format_spec(acc: &mut Completions, ctx: &CompletionContext) {
+    if !is_in_format_spec(&ctx.token, ctx.offset) {
+        return;
+    }
+    for &(spec, detail) in FORMAT_SPECS {
+        CompletionItem::new(CompletionKind::Magic, ctx.source_range(), spec)
+            .detail(detail)
+            .add_to(acc);
+    }
+}
+
+/// Checks that the offset is after the `:` of a placeholder of the format
+/// string of a format macro, like `format!("{:<|>}")`.
+fn is_in_format_spec(token: &SyntaxToken, offset: TextUnit) -> bool {
+    if token.kind() != STRING || !is_format_string(token) {
+        return false;
+    }
+    let start = token.text_range().start() + TextUnit::of_char('"');
+    if offset < start {
+        return false;
+    }
+    let before = &token.text()[(start - token.text_range().start()).to_usize()..]
+        [..(offset - start).to_usize()];
+
+    // whether we are in a placeholder, and after its `:`
+    let mut placeholder: Option<bool> = None;
+    let mut chars = before.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, placeholder) {
+            ('{', None) | ('}', None) if chars.peek() == Some(&c) => {
+                chars.next();
+            }
+            ('{', None) => placeholder = Some(false),
+            ('}', Some(_)) => placeholder = None,
+            (':', Some(false)) => placeholder = Some(true),
+            _ => (),
+        }
+    }
+    placeholder == Some(true)
+}
+
+/// Whether the string is the first string literal in the arguments of a format
+/// macro.
+fn is_format_string(token: &SyntaxToken) -> bool {
+    let token_tree = token.parent();
+    let macro_call = match token_tree.parent().and_then(ast::MacroCall::cast) {
+        Some(it) => it,
+        None => return false,
+    };
+    let name = match macro_call.path().and_then(|it| it.segment()).and_then(|it| it.name_ref()) {
+        Some(it) => it,
+        None => return false,
+    };
+    if !FORMAT_MACROS.contains(&name.text().as_str()) {
+        return false;
+    }
+    let first_string = token_tree
+        .children_with_tokens()
+        .filter_map(|it| it.into_token())
+        .find(|it| it.kind() == STRING);
+    first_string.as_ref() == Some(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::completion::{do_completion, CompletionItem, CompletionKind};
+    use insta::assert_debug_snapshot;
+
+    fn do_format_spec_completion(code: &str) -> Vec<CompletionItem> {
+        do_completion(code, CompletionKind::Magic)
+    }
+
+    #[test]
+    fn completes_format_specs() {
+        let completions = do_format_spec_completion(r#"fn foo(x: u32) { format!("{:<|>}", x); }"#);
+        let completions: Vec<_> = completions.into_iter().filter(|it| it.label() == "#?").collect();
+        assert_debug_snapshot!(completions, @r###"
+        [
+            CompletionItem {
+                label: "#?",
+                source_range: [28; 28),
+                delete: [28; 28),
+                insert: "#?",
+                detail: "Debug, pretty-printed",
+            },
+        ]
+        "###);
+    }
+
+    #[test]
+    fn completes_format_specs_after_names_and_flags() {
+        for code in &[
+            r#"fn foo(x: u32) { println!("x = {x:<|>}", x = x); }"#,
+            r#"fn foo(x: u32) { println!("{} {0:#<|>}", x); }"#,
+            r#"fn foo(w: W, x: u32) { writeln!(w, "{{}} {:<|>}", x); }"#,
+        ] {
+            let completions = do_format_spec_completion(code);
+            assert!(completions.iter().any(|it| it.label() == "?"), "{}", code);
+        }
+    }
+
+    #[test]
+    fn does_not_complete_format_specs_outside_of_placeholders() {
+        for code in &[
+            r#"fn foo(x: u32) { format!("<|>{:?}", x); }"#,
+            r#"fn foo(x: u32) { format!("{<|>}", x); }"#,
+            r#"fn foo(x: u32) { format!("{:?}:<|>", x); }"#,
+            r#"fn foo(x: u32) { format!("{{:<|>}}", x); }"#,
+            r#"fn foo() { let s = "{:<|>}"; }"#,
+            r#"fn foo() { format!("{}", "{:<|>}"); }"#,
+            r#"fn foo() { concat!("{:<|>}"); }"#,
+        ] {
+            assert!(do_format_spec_completion(code).is_empty(), "{}", code);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Completes all the missing members of a trait impl at once.
+
+use ra_assists::missing_impl_members_stubs;
+use ra_syntax::ast::{self, edit::IndentLevel, AstNode};
+
+use crate::{
+    assists::assist_config,
+    completion::{
+        CompletionContext, CompletionItem, CompletionItemKind, CompletionKind, Completions,
+    },
+};
+
+pub(super) fn complete_impl_members(acc: &mut Completions, ctx: &CompletionContext) {
+    if !ctx.is_new_item {
+        return;
+    }
+    let impl_block = match ctx
+        .token
+        .parent()
+        .ancestors()
+        .find_map(ast::ItemList::cast)
+        .and_then(|it| it.syntax().parent())
+        .and_then(ast::ImplBlock::cast)
+    {
+        Some(it) => it,
+        None => return,
+    };
+    let stubs =
+        missing_impl_members_stubs(ctx.db, &ctx.analyzer, &assist_config(ctx.db), &impl_block);
+    if stubs.is_empty() {
+        return;
+    }
+    let level = IndentLevel::from_node(impl_block.syntax()).0 as usize + 1;
+    let separator = format!("\n{}", "    ".repeat(level));
+    CompletionItem::new(CompletionKind::Magic, ctx.source_range(), "impl missing members")
+        .kind(CompletionItemKind::Snippet)
+        .insert_text(stubs.join(&separator))
+        .add_to(acc);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::completion::{do_completion, CompletionItem, CompletionKind};
+    use insta::assert_debug_snapshot;
+
+    fn complete(code: &str) -> Vec<CompletionItem> {
+        do_completion(code, CompletionKind::Magic)
+    }
+
+    #[test]
+    fn completes_missing_members_of_trait_impl() {
+        assert_debug_snapshot!(
+            complete(
+                "
+trait Tr { type Output; fn foo(&self); fn bar(&self) -> u32; fn baz(&self) {} }
+struct S;
+impl Tr for S {
+    fn foo(&self) {}
+    <|>
+}
+"
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "impl missing members",
+                source_range: [132; 132),
+                delete: [132; 132),
+                insert: "type Output;\n    fn bar(&self) -> u32 { todo!() }",
+                kind: Snippet,
+            },
+        ]
+        "###
+        );
+    }
+
+    #[test]
+    fn no_missing_members_completion_outside_of_trait_impls() {
+        assert_debug_snapshot!(
+            complete(
+                "
+struct S;
+impl S {
+    <|>
+}
+"
+            ),
+            @"[]"
+        );
+        assert_debug_snapshot!(
+            complete(
+                "
+trait Tr { fn foo(&self); }
+struct S;
+impl Tr for S {
+    fn foo(&self) {}
+    <|>
+}
+"
+            ),
+            @"[]"
+        );
+    }
+}
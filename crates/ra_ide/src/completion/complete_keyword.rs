@@ -1,14 +1,16 @@
 //! FIXME: write short doc here
 
+use hir::{Adt, ModuleDef, ScopeDef, StructKind};
 use ra_syntax::{
     ast::{self, LoopBodyOwner},
     match_ast, AstNode,
     SyntaxKind::*,
-    SyntaxToken,
+    SyntaxToken, T,
 };
 
 use crate::completion::{
-    CompletionContext, CompletionItem, CompletionItemKind, CompletionKind, Completions,
+    completion_context::enclosing_loop, CompletionContext, CompletionItem, CompletionItemKind,
+    CompletionKind, Completions,
 };
 
 pub(super) fn complete_use_tree_keyword(acc: &mut Completions, ctx: &CompletionContext) {
@@ -41,6 +43,76 @@ pub(super) fn complete_use_tree_keyword(acc: &mut Completions, ctx: &CompletionC
     }
 }
 
+/// Completes the `?Sized` maybe-bound in the bounds of type parameters and
+/// associated types, like `T: <|>`, where it is allowed.
+pub(super) fn complete_maybe_bound(acc: &mut Completions, ctx: &CompletionContext) {
+    let bound = match &ctx.type_bound_syntax {
+        Some(it) if ctx.is_trivial_path => it,
+        _ => return,
+    };
+    // with `T: ?<|>`, `Sized` is completed as a trait
+    if bound.syntax().children_with_tokens().any(|it| it.kind() == T![?]) {
+        return;
+    }
+    let bounded = bound.syntax().parent().and_then(|it| it.parent()).map(|it| it.kind());
+    match bounded {
+        Some(TYPE_PARAM) | Some(WHERE_PRED) | Some(TYPE_ALIAS_DEF) => (),
+        _ => return,
+    }
+    CompletionItem::new(CompletionKind::Keyword, ctx.source_range(), "?Sized")
+        .kind(CompletionItemKind::Keyword)
+        .lookup_by("Sized")
+        .add_to(acc);
+}
+
+/// Completes `where` after the header of an item with generic parameters,
+/// like `fn foo<T>() <|> {}`.
+pub(super) fn complete_where_keyword(acc: &mut Completions, ctx: &CompletionContext) {
+    if ctx.can_add_where_clause {
+        acc.add(keyword(ctx, "where", "where $0"));
+    }
+}
+
+/// Completes the keywords starting an item, like `fn` or `pub`, on a new item
+/// of a module, or of an impl or a trait.
+pub(super) fn complete_item_keyword(acc: &mut Completions, ctx: &CompletionContext) {
+    if !ctx.is_new_item {
+        return;
+    }
+    let container = ctx
+        .token
+        .parent()
+        .ancestors()
+        .find_map(ast::ItemList::cast)
+        .and_then(|it| it.syntax().parent())
+        .map(|it| it.kind());
+    match container {
+        Some(IMPL_BLOCK) | Some(TRAIT_DEF) => {
+            // the items of traits are as public as the trait
+            if container == Some(IMPL_BLOCK) {
+                acc.add(keyword(ctx, "pub", "pub $0"));
+            }
+            acc.add(keyword(ctx, "unsafe", "unsafe $0"));
+            acc.add(keyword(ctx, "const", "const $0"));
+            acc.add(keyword(ctx, "fn", "fn ${1:name}($2) {$0}"));
+            acc.add(keyword(ctx, "type", "type $0"));
+        }
+        _ => {
+            acc.add(keyword(ctx, "pub", "pub $0"));
+            acc.add(keyword(ctx, "async", "async $0"));
+            acc.add(keyword(ctx, "unsafe", "unsafe $0"));
+            acc.add(keyword(ctx, "const", "const $0"));
+            acc.add(keyword(ctx, "fn", "fn ${1:name}($2) {$0}"));
+            acc.add(keyword(ctx, "struct", "struct ${1:Name} {$0}"));
+            acc.add(keyword(ctx, "enum", "enum ${1:Name} {$0}"));
+            acc.add(keyword(ctx, "trait", "trait ${1:Name} {$0}"));
+            acc.add(keyword(ctx, "impl", "impl $1 {$0}"));
+            acc.add(keyword(ctx, "mod", "mod $0"));
+            acc.add(keyword(ctx, "use", "use $0;"));
+        }
+    }
+}
+
 fn keyword(ctx: &CompletionContext, kw: &str, snippet: &str) -> CompletionItem {
     CompletionItem::new(CompletionKind::Keyword, ctx.source_range(), kw)
         .kind(CompletionItemKind::Keyword)
@@ -49,7 +121,7 @@ fn keyword(ctx: &CompletionContext, kw: &str, snippet: &str) -> CompletionItem {
 }
 
 pub(super) fn complete_expr_keyword(acc: &mut Completions, ctx: &CompletionContext) {
-    if !ctx.is_trivial_path {
+    if !ctx.is_trivial_path || ctx.is_path_type {
         return;
     }
 
@@ -74,8 +146,11 @@ pub(super) fn complete_expr_keyword(acc: &mut Completions, ctx: &CompletionConte
             acc.add(keyword(ctx, "continue", "continue"));
             acc.add(keyword(ctx, "break", "break"));
         }
+        let break_ty = enclosing_loop(&ctx.token.parent()).and_then(|it| ctx.loop_value_type(&it));
+        acc.add_all(keywords_with_value(ctx, "break", break_ty));
     }
     acc.add_all(complete_return(ctx, &fn_def, ctx.can_be_stmt));
+    acc.add_all(keywords_with_value(ctx, "return", ctx.fn_return_type(&ctx.token.parent())));
 }
 
 fn is_in_loop_body(leaf: &SyntaxToken) -> bool {
@@ -114,6 +189,58 @@ fn complete_return(
     Some(keyword(ctx, "return", snip))
 }
 
+/// Completes `return` or `break` with each variant of the enum the value
+/// should have, like `return Ok(…)`.
+fn keywords_with_value(
+    ctx: &CompletionContext,
+    kw: &str,
+    ty: Option<hir::Type>,
+) -> Vec<CompletionItem> {
+    let enum_ = match ty.and_then(|it| it.as_adt()) {
+        Some(Adt::Enum(it)) => it,
+        _ => return Vec::new(),
+    };
+    let semi = if ctx.can_be_stmt { ";" } else { "" };
+    enum_
+        .variants(ctx.db)
+        .into_iter()
+        .map(|variant| {
+            let path = variant_path(ctx, variant);
+            let (label, snippet) = match variant.kind(ctx.db) {
+                StructKind::Tuple if !variant.fields(ctx.db).is_empty() => {
+                    (format!("{} {}(…)", kw, path), format!("{} {}($0){}", kw, path, semi))
+                }
+                StructKind::Record => {
+                    (format!("{} {} {{…}}", kw, path), format!("{} {} {{ $0 }}{}", kw, path, semi))
+                }
+                _ => (format!("{} {}", kw, path), format!("{} {}{}", kw, path, semi)),
+            };
+            CompletionItem::new(CompletionKind::Keyword, ctx.source_range(), label)
+                .kind(CompletionItemKind::Keyword)
+                .lookup_by(kw)
+                .insert_snippet(snippet)
+                .build()
+        })
+        .collect()
+}
+
+/// The name of `variant`, qualified by its enum unless the variant itself is
+/// in scope, like `Ok` or `None`.
+fn variant_path(ctx: &CompletionContext, variant: hir::EnumVariant) -> String {
+    let name = variant.name(ctx.db);
+    let mut in_scope = false;
+    ctx.analyzer.process_all_names(ctx.db, &mut |it, def| {
+        if it == name && def == ScopeDef::ModuleDef(ModuleDef::EnumVariant(variant)) {
+            in_scope = true;
+        }
+    });
+    if in_scope {
+        name.to_string()
+    } else {
+        format!("{}::{}", variant.parent_enum(ctx.db).name(ctx.db), name)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::completion::{do_completion, CompletionItem, CompletionKind};
@@ -778,4 +905,281 @@ mod tests {
         "###
         )
     }
+
+    #[test]
+    fn completes_return_with_variants_of_return_type() {
+        assert_debug_snapshot!(
+            do_keyword_completion(
+                r"
+                enum E { A, B(u32), C { x: u32 } }
+                use E::B;
+                fn quux() -> E {
+                    <|>
+                }
+                ",
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "if",
+                source_range: [131; 131),
+                delete: [131; 131),
+                insert: "if $0 {}",
+                kind: Keyword,
+            },
+            CompletionItem {
+                label: "loop",
+                source_range: [131; 131),
+                delete: [131; 131),
+                insert: "loop {$0}",
+                kind: Keyword,
+            },
+            CompletionItem {
+                label: "match",
+                source_range: [131; 131),
+                delete: [131; 131),
+                insert: "match $0 {}",
+                kind: Keyword,
+            },
+            CompletionItem {
+                label: "return",
+                source_range: [131; 131),
+                delete: [131; 131),
+                insert: "return $0;",
+                kind: Keyword,
+            },
+            CompletionItem {
+                label: "return B(…)",
+                source_range: [131; 131),
+                delete: [131; 131),
+                insert: "return B($0);",
+                kind: Keyword,
+                lookup: "return",
+            },
+            CompletionItem {
+                label: "return E::A",
+                source_range: [131; 131),
+                delete: [131; 131),
+                insert: "return E::A;",
+                kind: Keyword,
+                lookup: "return",
+            },
+            CompletionItem {
+                label: "return E::C {…}",
+                source_range: [131; 131),
+                delete: [131; 131),
+                insert: "return E::C { $0 };",
+                kind: Keyword,
+                lookup: "return",
+            },
+            CompletionItem {
+                label: "while",
+                source_range: [131; 131),
+                delete: [131; 131),
+                insert: "while $0 {}",
+                kind: Keyword,
+            },
+        ]
+        "###
+        );
+    }
+
+    #[test]
+    fn completes_break_with_variants_of_loop_type() {
+        assert_debug_snapshot!(
+            do_keyword_completion(
+                r"
+                enum E { A, B }
+                fn quux() {
+                    let e: E = loop {
+                        <|>
+                    };
+                }
+                ",
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "break",
+                source_range: [123; 123),
+                delete: [123; 123),
+                insert: "break;",
+                kind: Keyword,
+            },
+            CompletionItem {
+                label: "break E::A",
+                source_range: [123; 123),
+                delete: [123; 123),
+                insert: "break E::A;",
+                kind: Keyword,
+                lookup: "break",
+            },
+            CompletionItem {
+                label: "break E::B",
+                source_range: [123; 123),
+                delete: [123; 123),
+                insert: "break E::B;",
+                kind: Keyword,
+                lookup: "break",
+            },
+            CompletionItem {
+                label: "continue",
+                source_range: [123; 123),
+                delete: [123; 123),
+                insert: "continue;",
+                kind: Keyword,
+            },
+            CompletionItem {
+                label: "if",
+                source_range: [123; 123),
+                delete: [123; 123),
+                insert: "if $0 {}",
+                kind: Keyword,
+            },
+            CompletionItem {
+                label: "loop",
+                source_range: [123; 123),
+                delete: [123; 123),
+                insert: "loop {$0}",
+                kind: Keyword,
+            },
+            CompletionItem {
+                label: "match",
+                source_range: [123; 123),
+                delete: [123; 123),
+                insert: "match $0 {}",
+                kind: Keyword,
+            },
+            CompletionItem {
+                label: "return",
+                source_range: [123; 123),
+                delete: [123; 123),
+                insert: "return;",
+                kind: Keyword,
+            },
+            CompletionItem {
+                label: "while",
+                source_range: [123; 123),
+                delete: [123; 123),
+                insert: "while $0 {}",
+                kind: Keyword,
+            },
+        ]
+        "###
+        );
+    }
+
+    #[test]
+    fn completes_maybe_sized_in_bounds_of_type_params() {
+        assert_debug_snapshot!(
+            do_keyword_completion(r"fn foo<T: <|>>() {}"),
+            @r###"
+        [
+            CompletionItem {
+                label: "?Sized",
+                source_range: [10; 10),
+                delete: [10; 10),
+                insert: "?Sized",
+                kind: Keyword,
+                lookup: "Sized",
+            },
+        ]
+        "###
+        );
+        let completions = do_keyword_completion(r"fn foo<T>() where T: Clone + <|> {}");
+        assert!(completions.iter().any(|it| it.label() == "?Sized"));
+        let completions = do_keyword_completion(r"trait Tr { type A: <|>; }");
+        assert!(completions.iter().any(|it| it.label() == "?Sized"));
+    }
+
+    #[test]
+    fn does_not_complete_maybe_sized_where_not_allowed() {
+        assert_debug_snapshot!(do_keyword_completion(r"fn foo(x: impl <|>) {}"), @"[]");
+        assert_debug_snapshot!(do_keyword_completion(r"fn foo<T: ?<|>>() {}"), @"[]");
+    }
+
+    #[test]
+    fn completes_where_after_generic_headers() {
+        assert_debug_snapshot!(
+            do_keyword_completion(r"fn foo<T>() <|> {}"),
+            @r###"
+        [
+            CompletionItem {
+                label: "where",
+                source_range: [12; 12),
+                delete: [12; 12),
+                insert: "where $0",
+                kind: Keyword,
+            },
+        ]
+        "###
+        );
+        for code in &[
+            r"fn foo<T>() -> Vec<T> <|>",
+            r"struct S<T> wh<|> { x: T }",
+            r"struct S<T>(T) <|>;",
+            r"impl<T> Tr for S<T> <|> {}",
+            r"trait Tr<T>: Clone <|> {}",
+            r"type A<T> <|> = T;",
+        ] {
+            let completions = do_keyword_completion(code);
+            assert!(completions.iter().any(|it| it.label() == "where"), "{}", code);
+        }
+    }
+
+    fn labels(code: &str) -> Vec<String> {
+        do_keyword_completion(code).into_iter().map(|it| it.label().to_string()).collect()
+    }
+
+    #[test]
+    fn completes_item_keywords() {
+        assert_debug_snapshot!(
+            do_keyword_completion(r"mod m { <|> }").into_iter().find(|it| it.label() == "struct"),
+            @r###"
+        Some(
+            CompletionItem {
+                label: "struct",
+                source_range: [8; 8),
+                delete: [8; 8),
+                insert: "struct ${1:Name} {$0}",
+                kind: Keyword,
+            },
+        )
+        "###
+        );
+        let expected = vec![
+            "async", "const", "enum", "fn", "impl", "mod", "pub", "struct", "trait", "unsafe",
+            "use",
+        ];
+        assert_eq!(labels(r"struct S; <|>"), expected);
+        assert_eq!(labels(r"mod m { fn f() {} <|> }"), expected);
+    }
+
+    #[test]
+    fn completes_assoc_item_keywords() {
+        assert_eq!(labels(r"impl S { <|> }"), vec!["const", "fn", "pub", "type", "unsafe"]);
+        assert_eq!(labels(r"trait T { <|> }"), vec!["const", "fn", "type", "unsafe"]);
+    }
+
+    #[test]
+    fn does_not_complete_item_keywords_outside_of_items() {
+        for code in &[r"fn f() { <|> }", r"fn f() -> <|> {}", r"struct S { f: <|> }"] {
+            assert!(labels(code).iter().all(|it| it != "struct"), "{}", code);
+        }
+    }
+
+    #[test]
+    fn does_not_complete_where_where_not_allowed() {
+        for code in &[
+            r"fn foo() <|> {}",
+            r"fn foo<T>() where T: Clone <|> {}",
+            r"fn foo<T>() -> <|> {}",
+            r"fn foo<T>(<|>) {}",
+            r"fn foo<T>() { <|> }",
+            r"impl<T> Tr for <|> {}",
+        ] {
+            let completions = do_keyword_completion(code);
+            assert!(completions.iter().all(|it| it.label() != "where"), "{}", code);
+        }
+    }
 }
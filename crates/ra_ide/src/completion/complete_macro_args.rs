@@ -0,0 +1,142 @@
+//! Completes the keywords and identifiers which the rules of a `macro_rules!`
+//! macro match literally, in the arguments of a call of the macro.
+
+use hir::HasSource;
+use ra_syntax::{
+    ast::{self, NameOwner},
+    AstNode,
+    SyntaxKind::IDENT,
+    SyntaxToken, T,
+};
+use rustc_hash::FxHashSet;
+
+use crate::{
+    completion::{
+        CompletionContext, CompletionItem, CompletionItemKind, CompletionKind, Completions,
+    },
+    display::macro_rule_matchers,
+};
+
+pub(super) fn complete_macro_args(acc: &mut Completions, ctx: &CompletionContext) {
+    let macro_call = match ctx.token.parent().ancestors().find_map(ast::MacroCall::cast) {
+        Some(it) => it,
+        None => return,
+    };
+    // the cursor has to be after the opening delimiter of the arguments
+    match macro_call.token_tree() {
+        Some(tt) if tt.syntax().text_range().start() < ctx.offset => (),
+        _ => return,
+    }
+    let macro_def = match ctx
+        .analyzer
+        .resolve_macro_call(ctx.db, hir::InFile::new(ctx.analyzer.file_id(), &macro_call))
+    {
+        Some(it) => it,
+        None => return,
+    };
+    let macro_src = macro_def.source(ctx.db).value;
+    let name = match macro_src.name() {
+        Some(it) => it.text().to_string(),
+        None => return,
+    };
+
+    let mut seen = FxHashSet::default();
+    for matcher in macro_rule_matchers(&macro_src) {
+        for token in literal_tokens(&matcher) {
+            if !seen.insert(token.text().to_string()) {
+                continue;
+            }
+            CompletionItem::new(CompletionKind::Keyword, ctx.source_range(), token.text().as_str())
+                .kind(CompletionItemKind::Keyword)
+                .detail(format!("matched by `{}!`", name))
+                .add_to(acc);
+        }
+    }
+}
+
+/// The keywords and identifiers of a matcher which aren't metavariables or
+/// fragment specifiers, like `in` in `($x:ident in $e:expr)`.
+fn literal_tokens(matcher: &ast::TokenTree) -> Vec<SyntaxToken> {
+    let tokens: Vec<SyntaxToken> = matcher
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| !it.kind().is_trivia())
+        .collect();
+    let kind_before = |idx: usize, n: usize| idx.checked_sub(n).map(|it| tokens[it].kind());
+    tokens
+        .iter()
+        .enumerate()
+        .filter(|(_, it)| it.kind() == IDENT || it.kind().is_keyword())
+        .filter(|&(idx, _)| kind_before(idx, 1) != Some(T![$]))
+        .filter(|&(idx, _)| {
+            !(kind_before(idx, 1) == Some(T![:]) && kind_before(idx, 3) == Some(T![$]))
+        })
+        .map(|(_, it)| it.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_debug_snapshot;
+
+    use crate::completion::{do_completion, CompletionItem, CompletionKind};
+
+    fn do_keyword_completion(code: &str) -> Vec<CompletionItem> {
+        do_completion(code, CompletionKind::Keyword)
+    }
+
+    #[test]
+    fn completes_literal_tokens_of_macro_rules() {
+        assert_debug_snapshot!(
+            do_keyword_completion(
+                r"
+                macro_rules! each {
+                    ($x:ident in $e:expr => $body:block) => {};
+                    (where $t:ty: $($bound:ident)+) => {};
+                }
+                fn main() {
+                    each!(x <|>);
+                }
+                ",
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "in",
+                source_range: [234; 234),
+                delete: [234; 234),
+                insert: "in",
+                kind: Keyword,
+                detail: "matched by `each!`",
+            },
+            CompletionItem {
+                label: "where",
+                source_range: [234; 234),
+                delete: [234; 234),
+                insert: "where",
+                kind: Keyword,
+                detail: "matched by `each!`",
+            },
+        ]
+        "###
+        );
+    }
+
+    #[test]
+    fn no_macro_rules_completions_outside_of_arguments() {
+        assert_debug_snapshot!(
+            do_keyword_completion(
+                r"
+                macro_rules! m { (in) => {} }
+                fn main() {
+                    let x = 1;
+                    m!(in);
+                    x<|>
+                }
+                ",
+            ),
+            @"[]"
+        );
+    }
+}
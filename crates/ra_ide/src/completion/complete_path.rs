@@ -13,11 +13,28 @@ pub(super) fn complete_path(acc: &mut Completions, ctx: &CompletionContext) {
     };
     let def = match ctx.analyzer.resolve_hir_path(ctx.db, &path) {
         Some(PathResolution::Def(def)) => def,
+        Some(PathResolution::SelfType(impl_block)) => {
+            let ty = impl_block.target_ty(ctx.db);
+            if let Some(Adt::Enum(e)) = ty.as_adt() {
+                for variant in e.variants(ctx.db) {
+                    acc.add_enum_variant(ctx, variant);
+                }
+            }
+            complete_assoc_items(acc, ctx, &ty);
+            return;
+        }
+        // the items of the bounds of `T::` or of the trait for `Self::`
+        Some(PathResolution::TypeParam(_)) => {
+            if let Some(ty) = ctx.analyzer.resolve_path_as_type(ctx.db, &path) {
+                complete_assoc_items(acc, ctx, &ty);
+            }
+            return;
+        }
         _ => return,
     };
     match def {
         hir::ModuleDef::Module(module) => {
-            let module_scope = module.scope(ctx.db);
+            let module_scope = module.scope(ctx.db, ctx.module);
             for (name, def) in module_scope {
                 if ctx.use_item_syntax.is_some() {
                     if let hir::ScopeDef::ModuleDef(hir::ModuleDef::BuiltinType(..)) = def {
@@ -49,30 +66,7 @@ pub(super) fn complete_path(acc: &mut Completions, ctx: &CompletionContext) {
                 hir::ModuleDef::TypeAlias(a) => a.ty(ctx.db),
                 _ => unreachable!(),
             };
-            ctx.analyzer.iterate_path_candidates(ctx.db, &ty, None, |_ty, item| {
-                match item {
-                    hir::AssocItem::Function(func) => {
-                        if !func.has_self_param(ctx.db) {
-                            acc.add_function(ctx, func);
-                        }
-                    }
-                    hir::AssocItem::Const(ct) => acc.add_const(ctx, ct),
-                    hir::AssocItem::TypeAlias(ty) => acc.add_type_alias(ctx, ty),
-                }
-                None::<()>
-            });
-            // Iterate assoc types separately
-            // FIXME: complete T::AssocType
-            let krate = ctx.module.map(|m| m.krate());
-            if let Some(krate) = krate {
-                ty.iterate_impl_items(ctx.db, krate, |item| {
-                    match item {
-                        hir::AssocItem::Function(_) | hir::AssocItem::Const(_) => {}
-                        hir::AssocItem::TypeAlias(ty) => acc.add_type_alias(ctx, ty),
-                    }
-                    None::<()>
-                });
-            }
+            complete_assoc_items(acc, ctx, &ty);
         }
         hir::ModuleDef::Trait(t) => {
             for item in t.items(ctx.db) {
@@ -91,6 +85,35 @@ pub(super) fn complete_path(acc: &mut Completions, ctx: &CompletionContext) {
     };
 }
 
+/// Completes the associated functions, constants and types of `ty`, for
+/// paths like `Foo::` or `Self::`.
+fn complete_assoc_items(acc: &mut Completions, ctx: &CompletionContext, ty: &hir::Type) {
+    ctx.analyzer.iterate_path_candidates(ctx.db, ty, None, |_ty, item| {
+        match item {
+            hir::AssocItem::Function(func) => {
+                if !func.has_self_param(ctx.db) {
+                    acc.add_function(ctx, func);
+                }
+            }
+            hir::AssocItem::Const(ct) => acc.add_const(ctx, ct),
+            hir::AssocItem::TypeAlias(ty) => acc.add_type_alias(ctx, ty),
+        }
+        None::<()>
+    });
+    // Iterate assoc types separately
+    // FIXME: complete T::AssocType
+    let krate = ctx.module.map(|m| m.krate());
+    if let Some(krate) = krate {
+        ty.iterate_impl_items(ctx.db, krate, |item| {
+            match item {
+                hir::AssocItem::Function(_) | hir::AssocItem::Const(_) => {}
+                hir::AssocItem::TypeAlias(ty) => acc.add_type_alias(ctx, ty),
+            }
+            None::<()>
+        });
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use test_utils::covers;
@@ -174,7 +197,7 @@ mod tests {
                 use self::m::<|>;
 
                 mod m {
-                    struct Bar;
+                    pub struct Bar;
                 }
                 "
             ),
@@ -428,6 +451,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn completes_self_associated_items() {
+        assert_debug_snapshot!(
+            do_reference_completion(
+                "
+                //- /lib.rs
+                enum E { A }
+                impl E {
+                    fn foo() {}
+                    fn bar() { Self::<|> }
+                }
+                "
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "A",
+                source_range: [59; 59),
+                delete: [59; 59),
+                insert: "A",
+                kind: EnumVariant,
+                detail: "()",
+            },
+            CompletionItem {
+                label: "bar()",
+                source_range: [59; 59),
+                delete: [59; 59),
+                insert: "bar()$0",
+                kind: Function,
+                lookup: "bar",
+                detail: "fn bar()",
+            },
+            CompletionItem {
+                label: "foo()",
+                source_range: [59; 59),
+                delete: [59; 59),
+                insert: "foo()$0",
+                kind: Function,
+                lookup: "foo",
+                detail: "fn foo()",
+            },
+        ]
+        "###
+        );
+    }
+
     #[test]
     fn completes_struct_associated_const() {
         assert_debug_snapshot!(
@@ -598,6 +667,82 @@ mod tests {
         );
     }
 
+    #[test]
+    fn completes_only_visible_items_across_crates() {
+        assert_debug_snapshot!(
+            do_reference_completion(
+                "
+                //- /main.rs
+                use foo::bar::<|>;
+
+                //- /foo/lib.rs
+                pub mod bar {
+                    pub struct Public;
+                    pub(crate) struct Crate;
+                    struct Private;
+                    pub use self::inner::Reexported;
+                    mod inner {
+                        pub struct Reexported;
+                    }
+                }
+                "
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "Public",
+                source_range: [14; 14),
+                delete: [14; 14),
+                insert: "Public",
+                kind: Struct,
+            },
+            CompletionItem {
+                label: "Reexported",
+                source_range: [14; 14),
+                delete: [14; 14),
+                insert: "Reexported",
+                kind: Struct,
+            },
+        ]
+        "###
+        );
+    }
+
+    #[test]
+    fn completes_private_items_of_ancestor_modules() {
+        assert_debug_snapshot!(
+            do_reference_completion(
+                "
+                //- /lib.rs
+                mod foo {
+                    struct Private;
+                    pub mod bar {
+                        use super::<|>;
+                    }
+                }
+                "
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "Private",
+                source_range: [67; 67),
+                delete: [67; 67),
+                insert: "Private",
+                kind: Struct,
+            },
+            CompletionItem {
+                label: "bar",
+                source_range: [67; 67),
+                delete: [67; 67),
+                insert: "bar",
+                kind: Module,
+            },
+        ]
+        "###
+        );
+    }
+
     #[test]
     fn completes_trait_associated_method_1() {
         assert_debug_snapshot!(
@@ -703,6 +848,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn completes_self_items_in_default_trait_method() {
+        let mut labels: Vec<_> = do_reference_completion(
+            "
+            //- /lib.rs
+            trait Base { fn make() -> u8; }
+            trait Trait: Base {
+                const C: u32;
+                fn new() -> Self;
+                fn by_ref(&self) { Self::<|> }
+            }
+            ",
+        )
+        .into_iter()
+        .map(|it| it.label().to_string())
+        .collect();
+        labels.sort();
+        assert_eq!(labels, vec!["C", "make()", "new()"]);
+    }
+
     #[test]
     fn completes_type_alias() {
         assert_debug_snapshot!(
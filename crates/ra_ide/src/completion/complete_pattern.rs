@@ -1,14 +1,21 @@
 //! FIXME: write short doc here
 
-use crate::completion::{CompletionContext, Completions};
+use hir::Adt;
+
+use crate::completion::{CompletionContext, CompletionItem, CompletionKind, Completions};
 
 /// Completes constats and paths in patterns.
 pub(super) fn complete_pattern(acc: &mut Completions, ctx: &CompletionContext) {
     if !ctx.is_pat_binding {
         return;
     }
-    // FIXME: ideally, we should look at the type we are matching against and
-    // suggest variants + auto-imports
+    if let Some(Adt::Enum(e)) = ctx.expected_type.as_ref().and_then(|it| it.as_adt()) {
+        for variant in e.variants(ctx.db) {
+            acc.add_enum_variant_pattern(ctx, variant);
+        }
+        CompletionItem::new(CompletionKind::Magic, ctx.source_range(), "_").add_to(acc);
+    }
+    // FIXME: suggest auto-imports
     ctx.analyzer.process_all_names(ctx.db, &mut |name, res| {
         let def = match &res {
             hir::ScopeDef::ModuleDef(def) => def,
@@ -34,6 +41,10 @@ mod tests {
         do_completion(code, CompletionKind::Reference)
     }
 
+    fn complete_variant_patterns(code: &str) -> Vec<CompletionItem> {
+        do_completion(code, CompletionKind::Magic)
+    }
+
     #[test]
     fn completes_enum_variants_and_modules() {
         let completions = complete(
@@ -86,4 +97,80 @@ mod tests {
         ]
         "###);
     }
+
+    #[test]
+    fn completes_variant_patterns_of_scrutinee_type() {
+        let completions = complete_variant_patterns(
+            r"
+            enum E { A, B(u32, bool), C { x: u32 } }
+            fn foo(e: E) {
+                match e {
+                    E::A => (),
+                    <|>
+                }
+            }
+            ",
+        );
+        assert_debug_snapshot!(completions, @r###"
+        [
+            CompletionItem {
+                label: "E::A",
+                source_range: [159; 159),
+                delete: [159; 159),
+                insert: "E::A",
+                kind: EnumVariant,
+            },
+            CompletionItem {
+                label: "E::B(_, _)",
+                source_range: [159; 159),
+                delete: [159; 159),
+                insert: "E::B(${1:_}, ${2:_})",
+                kind: EnumVariant,
+            },
+            CompletionItem {
+                label: "E::C { .. }",
+                source_range: [159; 159),
+                delete: [159; 159),
+                insert: "E::C { $0.. }",
+                kind: EnumVariant,
+            },
+            CompletionItem {
+                label: "_",
+                source_range: [159; 159),
+                delete: [159; 159),
+                insert: "_",
+            },
+        ]
+        "###);
+    }
+
+    #[test]
+    fn does_not_complete_variant_patterns_of_outer_match() {
+        let completions = complete_variant_patterns(
+            r"
+            enum E { A, B(u32) }
+            fn foo(e: E) {
+                match e {
+                    E::B(n) => if let <|> = n {},
+                    _ => (),
+                }
+            }
+            ",
+        );
+        assert!(completions.is_empty());
+    }
+
+    #[test]
+    fn does_not_complete_variant_patterns_for_non_enum_scrutinee() {
+        let completions = complete_variant_patterns(
+            r"
+            fn foo(x: u32) {
+                match x {
+                    <|>
+                }
+            }
+            ",
+        );
+        assert!(completions.is_empty());
+    }
 }
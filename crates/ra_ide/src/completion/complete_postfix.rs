@@ -60,6 +60,16 @@ pub(super) fn complete_postfix(acc: &mut Completions, ctx: &CompletionContext) {
     .add_to(acc);
 
     postfix_snippet(ctx, "dbg", "dbg!(expr)", &format!("dbg!({})", receiver_text)).add_to(acc);
+    // unlike `dbg!`, `println!` doesn't pass the value on, so it is made a
+    // statement of its own when it can be one
+    let semicolon = if ctx.dot_access_can_end_stmt { ";" } else { "" };
+    postfix_snippet(
+        ctx,
+        "print",
+        "println!(\"{:?}\", expr)",
+        &format!("println!(\"{{:?}}\", {}){}", receiver_text, semicolon),
+    )
+    .add_to(acc);
 
     postfix_snippet(ctx, "box", "Box::new(expr)", &format!("Box::new({})", receiver_text))
         .add_to(acc);
@@ -135,6 +145,13 @@ mod tests {
                 insert: "!bar",
                 detail: "!expr",
             },
+            CompletionItem {
+                label: "print",
+                source_range: [89; 89),
+                delete: [85; 89),
+                insert: "println!(\"{:?}\", bar);",
+                detail: "println!(\"{:?}\", expr)",
+            },
             CompletionItem {
                 label: "ref",
                 source_range: [89; 89),
@@ -202,6 +219,13 @@ mod tests {
                 insert: "!bar",
                 detail: "!expr",
             },
+            CompletionItem {
+                label: "print",
+                source_range: [91; 91),
+                delete: [87; 91),
+                insert: "println!(\"{:?}\", bar);",
+                detail: "println!(\"{:?}\", expr)",
+            },
             CompletionItem {
                 label: "ref",
                 source_range: [91; 91),
@@ -221,6 +245,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn postfix_print_ends_statement_only_in_statement_position() {
+        let print_insert = |code: &str| {
+            do_postfix_completion(code)
+                .into_iter()
+                .find(|it| it.label() == "print")
+                .map(|it| it.text_edit().as_atoms()[0].insert.clone())
+        };
+        assert_eq!(
+            print_insert("fn main() { let bar = 1; bar.<|>; }"),
+            Some("println!(\"{:?}\", bar)".to_string())
+        );
+        assert_eq!(
+            print_insert("fn main() { let bar = 1; bar.<|>\n foo(); }"),
+            Some("println!(\"{:?}\", bar);".to_string())
+        );
+        assert_eq!(
+            print_insert("fn main() { let bar = 1; foo(bar.<|>) }"),
+            Some("println!(\"{:?}\", bar)".to_string())
+        );
+    }
+
     #[test]
     fn postfix_completion_works_for_ambiguous_float_literal() {
         assert_debug_snapshot!(
@@ -261,6 +307,13 @@ mod tests {
                 insert: "!42",
                 detail: "!expr",
             },
+            CompletionItem {
+                label: "print",
+                source_range: [52; 52),
+                delete: [49; 52),
+                insert: "println!(\"{:?}\", 42);",
+                detail: "println!(\"{:?}\", expr)",
+            },
             CompletionItem {
                 label: "ref",
                 source_range: [52; 52),
@@ -1,19 +1,37 @@
 //! FIXME: write short doc here
 
+use hir::{ModuleDef, ScopeDef};
 use ra_assists::auto_import_text_edit;
-use ra_syntax::{ast, AstNode, SmolStr};
-use ra_text_edit::TextEditBuilder;
-use rustc_hash::FxHashMap;
+use ra_db::SourceDatabase;
+use ra_syntax::{ast, AstNode, SmolStr, SyntaxKind::IDENT, TextRange};
+use ra_text_edit::{TextEdit, TextEditBuilder};
+use rustc_hash::{FxHashMap, FxHashSet};
 
-use crate::completion::{CompletionContext, CompletionItem, CompletionKind, Completions};
+use crate::{
+    completion::{CompletionContext, CompletionItem, CompletionKind, Completions},
+    db::RootDatabase,
+    FilePosition,
+};
 
 pub(super) fn complete_scope(acc: &mut Completions, ctx: &CompletionContext) {
     if !ctx.is_trivial_path {
         return;
     }
 
+    // the same item can be in scope several times, e.g. when it is imported
+    // explicitly and through the prelude
+    let mut seen = FxHashSet::default();
     ctx.analyzer.process_all_names(ctx.db, &mut |name, res| {
-        acc.add_resolution(ctx, name.to_string(), &res)
+        if ctx.is_path_type && !can_be_type(&res) {
+            return;
+        }
+        let is_trait_position = ctx.is_path_impl_trait || ctx.type_bound_syntax.is_some();
+        if is_trait_position && !can_be_trait(&res) {
+            return;
+        }
+        if seen.insert((name.clone(), res)) {
+            acc.add_resolution(ctx, name.to_string(), &res)
+        }
     });
 
     // auto-import
@@ -22,39 +40,46 @@ pub(super) fn complete_scope(acc: &mut Completions, ctx: &CompletionContext) {
         let import_resolver = ImportResolver::new();
         let import_names = import_resolver.all_names(ctx.token.text());
         import_names.into_iter().for_each(|(name, path)| {
-            let edit = {
-                let mut builder = TextEditBuilder::default();
-                builder.replace(ctx.source_range(), name.to_string());
-                auto_import_text_edit(
-                    &ctx.token.parent(),
-                    &ctx.token.parent(),
-                    &path,
-                    &mut builder,
-                );
-                builder.finish()
-            };
-
-            // Hack: copied this check form conv.rs beacause auto import can produce edits
-            // that invalidate assert in conv_with.
-            if edit
-                .as_atoms()
-                .iter()
-                .filter(|atom| !ctx.source_range().is_subrange(&atom.delete))
-                .all(|atom| ctx.source_range().intersection(&atom.delete).is_none())
-            {
-                CompletionItem::new(
-                    CompletionKind::Reference,
-                    ctx.source_range(),
-                    build_import_label(&name, &path),
-                )
-                .text_edit(edit)
-                .add_to(acc);
-            }
+            // the `use` is added when the item is resolved, see `fixup_completion`
+            CompletionItem::new(
+                CompletionKind::Reference,
+                ctx.source_range(),
+                build_import_label(&name, &path),
+            )
+            .insert_text(name.to_string())
+            .import_to_add(path)
+            .add_to(acc);
         });
     }
 }
 
-fn build_import_label(name: &str, path: &[SmolStr]) -> String {
+/// Computes the `use` for an auto-imported completion item at `position`.
+/// Returns `None` if the `use` would overlap the completed identifier, which
+/// can't be expressed with LSP's additional edits.
+pub(crate) fn fixup_completion(
+    db: &RootDatabase,
+    position: FilePosition,
+    import_to_add: &[SmolStr],
+) -> Option<TextEdit> {
+    let file = db.parse(position.file_id).tree();
+    let token = file.syntax().token_at_offset(position.offset).left_biased()?;
+    let source_range = match token.kind() {
+        IDENT => token.text_range(),
+        _ => TextRange::offset_len(position.offset, 0.into()),
+    };
+    let mut builder = TextEditBuilder::default();
+    auto_import_text_edit(&token.parent(), &token.parent(), import_to_add, &mut builder);
+    let edit = builder.finish();
+    let overlaps = edit.as_atoms().iter().any(|atom| {
+        !source_range.is_subrange(&atom.delete) && source_range.intersection(&atom.delete).is_some()
+    });
+    if overlaps {
+        return None;
+    }
+    Some(edit)
+}
+
+pub(super) fn build_import_label(name: &str, path: &[SmolStr]) -> String {
     let mut buf = String::with_capacity(64);
     buf.push_str(name);
     buf.push_str(" (");
@@ -124,15 +149,120 @@ impl ImportResolver {
     }
 }
 
+/// Values can't appear in type position, so there is no point in offering
+/// them there.
+fn can_be_type(def: &ScopeDef) -> bool {
+    match def {
+        ScopeDef::ModuleDef(ModuleDef::Function(_))
+        | ScopeDef::ModuleDef(ModuleDef::EnumVariant(_))
+        | ScopeDef::ModuleDef(ModuleDef::Const(_))
+        | ScopeDef::ModuleDef(ModuleDef::Static(_))
+        | ScopeDef::Local(_) => false,
+        _ => true,
+    }
+}
+
+/// Only traits, or modules containing them, can be implemented.
+fn can_be_trait(def: &ScopeDef) -> bool {
+    match def {
+        ScopeDef::ModuleDef(ModuleDef::Trait(_)) | ScopeDef::ModuleDef(ModuleDef::Module(_)) => {
+            true
+        }
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::completion::{do_completion, CompletionItem, CompletionKind};
+    use crate::{
+        completion::{do_completion, CompletionItem, CompletionItemKind, CompletionKind},
+        mock_analysis::single_file_with_position,
+    };
     use insta::assert_debug_snapshot;
 
     fn do_reference_completion(code: &str) -> Vec<CompletionItem> {
         do_completion(code, CompletionKind::Reference)
     }
 
+    #[test]
+    fn completes_import_aliases_but_not_underscore_imports() {
+        let completions = do_reference_completion(
+            r"
+            mod m {
+                pub trait Tr {}
+                pub struct Foo;
+            }
+            use m::Tr as _;
+            use m::Foo as Bar;
+            fn foo() { <|> }
+            ",
+        );
+        let labels: Vec<&str> = completions.iter().map(|it| it.label()).collect();
+        assert!(labels.contains(&"Bar"));
+        for name in &["Foo", "Tr", "_"] {
+            assert!(!labels.contains(name), "{} is completed", name);
+        }
+    }
+
+    #[test]
+    fn hides_double_underscore_items_unless_asked_for() {
+        let lookups = |code: &str| -> Vec<String> {
+            do_reference_completion(code).into_iter().map(|it| it.lookup().to_string()).collect()
+        };
+        let all = lookups(
+            r"
+            fn __internal() {}
+            fn _private() {}
+            fn main() { <|> }
+            ",
+        );
+        assert_eq!(all, vec!["_private", "main"]);
+        let underscored = lookups(
+            r"
+            fn __internal() {}
+            fn _private() {}
+            fn main() { __<|> }
+            ",
+        );
+        assert_eq!(underscored, vec!["__internal", "_private", "main"]);
+    }
+
+    #[test]
+    fn completes_auto_imports_without_their_use() {
+        let completions: Vec<_> = do_reference_completion(r"fn main() { Debu<|> }")
+            .into_iter()
+            .filter(|it| it.import_to_add().is_some())
+            .collect();
+        assert_debug_snapshot!(completions, @r###"
+        [
+            CompletionItem {
+                label: "Debug (std::fmt::Debug)",
+                source_range: [12; 16),
+                delete: [12; 16),
+                insert: "Debug",
+                import_to_add: [
+                    "std",
+                    "fmt",
+                    "Debug",
+                ],
+            },
+        ]
+        "###);
+    }
+
+    #[test]
+    fn fixup_completion_adds_the_use() {
+        let (analysis, position) = single_file_with_position(r"fn main() { Debu<|> }");
+        let edit = analysis
+            .fixup_completion(position, &["std".into(), "fmt".into(), "Debug".into()])
+            .unwrap()
+            .unwrap();
+        let text = analysis.file_text(position.file_id).unwrap();
+        let text = edit.apply(&text);
+        assert!(text.starts_with("use std::fmt::Debug;"), "{}", text);
+        assert!(text.ends_with("fn main() { Debu }"), "{}", text);
+    }
+
     #[test]
     fn completes_bindings_from_let() {
         assert_debug_snapshot!(
@@ -177,6 +307,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn completes_bindings_with_inferred_types() {
+        assert_debug_snapshot!(
+            do_reference_completion(
+                r"
+                struct Wrapper<T>(T);
+                fn quux() {
+                    let x = Wrapper(92);
+                    let y = unknown();
+                    <|>
+                }
+                "
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "Wrapper",
+                source_range: [167; 167),
+                delete: [167; 167),
+                insert: "Wrapper",
+                kind: Struct,
+            },
+            CompletionItem {
+                label: "quux()",
+                source_range: [167; 167),
+                delete: [167; 167),
+                insert: "quux()$0",
+                kind: Function,
+                lookup: "quux",
+                detail: "fn quux()",
+            },
+            CompletionItem {
+                label: "x",
+                source_range: [167; 167),
+                delete: [167; 167),
+                insert: "x",
+                kind: Binding,
+                detail: "Wrapper<i32>",
+            },
+            CompletionItem {
+                label: "y",
+                source_range: [167; 167),
+                delete: [167; 167),
+                insert: "y",
+                kind: Binding,
+            },
+        ]
+        "###
+        );
+    }
+
     #[test]
     fn completes_bindings_from_if_let() {
         assert_debug_snapshot!(
@@ -259,6 +440,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn dont_complete_bindings_from_other_match_arms() {
+        assert_debug_snapshot!(
+            do_reference_completion(
+                r"
+                fn quux(e: Option<i32>) {
+                    match e {
+                        Some(x) => x,
+                        None => <|>
+                    }
+                }
+                "
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "e",
+                source_range: [143; 143),
+                delete: [143; 143),
+                insert: "e",
+                kind: Binding,
+            },
+            CompletionItem {
+                label: "quux(…)",
+                source_range: [143; 143),
+                delete: [143; 143),
+                insert: "quux($0)",
+                kind: Function,
+                lookup: "quux",
+                detail: "fn quux(e: Option<i32>)",
+            },
+        ]
+        "###
+        );
+    }
+
+    #[test]
+    fn dont_complete_binding_being_defined() {
+        assert_debug_snapshot!(
+            do_reference_completion(
+                r"
+                fn quux() {
+                    let x = x<|>
+                }
+                "
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "quux()",
+                source_range: [57; 58),
+                delete: [57; 58),
+                insert: "quux()$0",
+                kind: Function,
+                lookup: "quux",
+                detail: "fn quux()",
+            },
+        ]
+        "###
+        );
+    }
+
     #[test]
     fn completes_generic_params() {
         assert_debug_snapshot!(
@@ -483,20 +726,95 @@ mod tests {
                 insert: "Foo",
                 kind: Struct,
             },
+        ]
+        "###
+        );
+    }
+
+    #[test]
+    fn completes_only_types_in_type_position() {
+        assert_debug_snapshot!(
+            do_reference_completion(
+                r"
+                struct Foo;
+                const BAR: u32 = 92;
+                fn quux(x: i32) -> <|> {}
+                "
+            ),
+            @r###"
+        [
             CompletionItem {
-                label: "x()",
-                source_range: [55; 55),
-                delete: [55; 55),
-                insert: "x()$0",
-                kind: Function,
-                lookup: "x",
-                detail: "fn x()",
+                label: "Foo",
+                source_range: [101; 101),
+                delete: [101; 101),
+                insert: "Foo",
+                kind: Struct,
             },
         ]
         "###
         );
     }
 
+    #[test]
+    fn completes_only_traits_in_impl_trait_position() {
+        assert_debug_snapshot!(
+            do_reference_completion(
+                r"
+                trait Foo {}
+                struct S;
+                mod m {}
+                fn quux() {}
+                impl <|> for S {}
+                "
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "Foo",
+                source_range: [131; 131),
+                delete: [131; 131),
+                insert: "Foo",
+                kind: Trait,
+            },
+            CompletionItem {
+                label: "m",
+                source_range: [131; 131),
+                delete: [131; 131),
+                insert: "m",
+                kind: Module,
+            },
+        ]
+        "###
+        );
+    }
+
+    #[test]
+    fn completes_builtin_types_in_type_position() {
+        let (analysis, position) = single_file_with_position(r"struct Foo; fn quux<T>() -> <|> {}");
+        let completions = analysis.completions(position).unwrap().unwrap();
+        let labels: Vec<&str> = completions.iter().map(|it| it.label()).collect();
+        let position_of = |label: &str| labels.iter().position(|&it| it == label).unwrap();
+        // the builtin types come after the items in scope
+        for builtin in &["bool", "char", "str", "i32", "u64", "f64"] {
+            assert!(position_of("T") < position_of(builtin), "{} before T", builtin);
+            assert!(position_of("Foo") < position_of(builtin), "{} before Foo", builtin);
+        }
+
+        let mut builtins: Vec<&str> = completions
+            .iter()
+            .filter(|it| it.kind() == Some(CompletionItemKind::BuiltinType))
+            .map(|it| it.label())
+            .collect();
+        builtins.sort();
+        assert_eq!(
+            builtins,
+            vec![
+                "bool", "char", "f32", "f64", "i128", "i16", "i32", "i64", "i8", "isize", "str",
+                "u128", "u16", "u32", "u64", "u8", "usize",
+            ]
+        );
+    }
+
     #[test]
     fn dont_show_both_completions_for_shadowing() {
         assert_debug_snapshot!(
@@ -561,6 +879,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn completes_self_in_type_position_only_where_in_scope() {
+        let has_self =
+            |code: &str| do_reference_completion(code).iter().any(|it| it.label() == "Self");
+        assert!(has_self(r"struct S; impl S { fn new() -> <|> {} }"));
+        assert!(has_self(r"trait T { fn new() -> <|>; }"));
+        assert!(!has_self(r"struct S; fn new() -> <|> {}"));
+    }
+
+    #[test]
+    fn completes_only_traits_in_bounds() {
+        let completions = do_reference_completion(
+            r"
+            trait Foo {}
+            struct S;
+            fn foo<T: <|>>() {}
+            ",
+        );
+        let labels: Vec<_> = completions.iter().map(|it| it.label()).collect();
+        assert_eq!(labels, vec!["Foo"]);
+    }
+
     #[test]
     fn completes_prelude() {
         assert_debug_snapshot!(
@@ -588,18 +928,48 @@ mod tests {
                 kind: Struct,
             },
             CompletionItem {
-                label: "foo()",
+                label: "std",
                 source_range: [18; 18),
                 delete: [18; 18),
-                insert: "foo()$0",
-                kind: Function,
-                lookup: "foo",
-                detail: "fn foo()",
+                insert: "std",
+                kind: Module,
+            },
+        ]
+        "###
+        );
+    }
+
+    #[test]
+    fn completes_items_imported_several_times_once() {
+        assert_debug_snapshot!(
+            do_reference_completion(
+                "
+                //- /main.rs
+                use std::prelude::Option;
+                fn foo() { let x: <|> }
+
+                //- /std/lib.rs
+                #[prelude_import]
+                use prelude::*;
+
+                pub mod prelude {
+                    pub struct Option;
+                }
+                "
+            ),
+            @r###"
+        [
+            CompletionItem {
+                label: "Option",
+                source_range: [44; 44),
+                delete: [44; 44),
+                insert: "Option",
+                kind: Struct,
             },
             CompletionItem {
                 label: "std",
-                source_range: [18; 18),
-                delete: [18; 18),
+                source_range: [44; 44),
+                delete: [44; 44),
                 insert: "std",
                 kind: Module,
             },
@@ -649,15 +1019,6 @@ mod tests {
                 insert: "core",
                 kind: Module,
             },
-            CompletionItem {
-                label: "foo()",
-                source_range: [18; 18),
-                delete: [18; 18),
-                insert: "foo()$0",
-                kind: Function,
-                lookup: "foo",
-                detail: "fn foo()",
-            },
             CompletionItem {
                 label: "std",
                 source_range: [18; 18),
@@ -821,15 +1182,6 @@ mod tests {
                 kind: Macro,
                 detail: "macro_rules! foo",
             },
-            CompletionItem {
-                label: "main()",
-                source_range: [57; 57),
-                delete: [57; 57),
-                insert: "main()$0",
-                kind: Function,
-                lookup: "main",
-                detail: "fn main()",
-            },
         ]
         "###
         );
@@ -2,9 +2,10 @@
 
 use ra_syntax::{
     algo::{find_covering_element, find_node_at_offset},
-    ast, AstNode, Parse, SourceFile,
+    ast::{self, TypeAscriptionOwner},
+    AstNode, Parse, SourceFile,
     SyntaxKind::*,
-    SyntaxNode, SyntaxToken, TextRange, TextUnit,
+    SyntaxNode, SyntaxToken, TextRange, TextUnit, T,
 };
 use ra_text_edit::AtomTextEdit;
 
@@ -37,13 +38,33 @@ pub(crate) struct CompletionContext<'a> {
     pub(super) can_be_stmt: bool,
     /// Something is typed at the "top" level, in module or impl/trait.
     pub(super) is_new_item: bool,
+    /// Right after the header of an item with generic parameters and without
+    /// a where clause, like `fn foo<T>() <|> {}`.
+    pub(super) can_add_where_clause: bool,
     /// The receiver if this is a field or method access, i.e. writing something.<|>
     pub(super) dot_receiver: Option<ast::Expr>,
+    /// If the receiver ends with a float literal which swallowed the dot, like
+    /// `1.<|>` or `x.0.<|>`.
     pub(super) dot_receiver_is_ambiguous_float_literal: bool,
+    /// If the receiver is the iterable of a `for` loop and implements
+    /// `IntoIterator`, like in `for x in xs.<|>`.
+    pub(super) dot_receiver_is_for_loop_iterable: bool,
+    /// If the field or method access is the last expression of a block, or a
+    /// statement without its `;`, like `x.<|>` on a line of its own.
+    pub(super) dot_access_can_end_stmt: bool,
     /// If this is a call (method or function) in particular, i.e. the () are already there.
     pub(super) is_call: bool,
     pub(super) is_path_type: bool,
+    /// If this is the path of the trait of an impl, like `impl Foo<|> for S`.
+    pub(super) is_path_impl_trait: bool,
+    /// The bound this path is in, like `T: Foo<|>` or `impl Foo<|>`.
+    pub(super) type_bound_syntax: Option<ast::TypeBound>,
     pub(super) has_type_args: bool,
+    /// The type expected at the cursor. Currently only computed for arguments
+    /// of calls, like `foo(<|>)` or `Some(<|>)`, for patterns of match arms,
+    /// like `match x { <|> }`, and for the values of `return <|>` and
+    /// `break <|>`.
+    pub(super) expected_type: Option<hir::Type>,
 }
 
 impl<'a> CompletionContext<'a> {
@@ -82,13 +103,25 @@ impl<'a> CompletionContext<'a> {
             after_if: false,
             can_be_stmt: false,
             is_new_item: false,
+            can_add_where_clause: false,
             dot_receiver: None,
             is_call: false,
             is_path_type: false,
+            is_path_impl_trait: false,
+            type_bound_syntax: None,
             has_type_args: false,
             dot_receiver_is_ambiguous_float_literal: false,
+            dot_receiver_is_for_loop_iterable: false,
+            dot_access_can_end_stmt: false,
+            expected_type: None,
         };
         ctx.fill(&original_parse, position.offset);
+        ctx.can_add_where_clause =
+            is_where_clause_position(&original_parse.tree(), position.offset);
+        ctx.expected_type = ctx
+            .call_argument_type(&original_parse.tree(), position.offset)
+            .or_else(|| ctx.match_arm_pattern_type(&original_parse.tree(), position.offset))
+            .or_else(|| ctx.return_value_type(&original_parse.tree(), position.offset));
         Some(ctx)
     }
 
@@ -101,6 +134,17 @@ impl<'a> CompletionContext<'a> {
         }
     }
 
+    /// The part of the identifier being completed which is already typed.
+    pub(crate) fn typed_prefix(&self) -> &str {
+        match self.token.kind() {
+            IDENT | T![_] => {
+                let len = self.offset - self.token.text_range().start();
+                &self.token.text()[..len.to_usize()]
+            }
+            _ => "",
+        }
+    }
+
     fn fill(&mut self, original_parse: &'a Parse<ast::SourceFile>, offset: TextUnit) {
         // Insert a fake ident to get a valid parse tree. We will use this file
         // to determine context, though the original_file will be used for
@@ -143,6 +187,106 @@ impl<'a> CompletionContext<'a> {
         }
     }
 
+    /// Computes the type of the parameter corresponding to the call argument at
+    /// `offset`.
+    // FIXME: handle method calls
+    fn call_argument_type(
+        &self,
+        original_file: &SourceFile,
+        offset: TextUnit,
+    ) -> Option<hir::Type> {
+        let arg_list = find_node_at_offset::<ast::ArgList>(original_file.syntax(), offset)?;
+        if offset <= arg_list.syntax().text_range().start() {
+            return None;
+        }
+        let call = arg_list.syntax().parent().and_then(ast::CallExpr::cast)?;
+        let callee_ty = self.analyzer.type_of(self.db, &call.expr()?)?;
+        let idx = arg_list
+            .syntax()
+            .children_with_tokens()
+            .filter(|it| it.kind() == COMMA && it.text_range().end() <= offset)
+            .count();
+        callee_ty.callable_params(self.db)?.into_iter().nth(idx)
+    }
+
+    /// Computes the type of the scrutinee of the match whose arm pattern is at
+    /// `offset`.
+    fn match_arm_pattern_type(
+        &self,
+        original_file: &SourceFile,
+        offset: TextUnit,
+    ) -> Option<hir::Type> {
+        if !self.is_pat_binding {
+            return None;
+        }
+        let token = original_file.syntax().token_at_offset(offset).left_biased()?;
+        // the pattern can be nested in an arm in a pattern of its own
+        let arm_list = token.parent().ancestors().find_map(|node| match node.kind() {
+            MATCH_ARM_LIST => Some(Some(node)),
+            MATCH_ARM => Some(node.parent()),
+            CONDITION | LET_STMT | PARAM => Some(None),
+            _ => None,
+        })??;
+        let match_expr = arm_list.parent().and_then(ast::MatchExpr::cast)?;
+        self.analyzer.type_of(self.db, &match_expr.expr()?)
+    }
+
+    /// Computes the type of the value of the `return` or `break` expression
+    /// at `offset`.
+    fn return_value_type(&self, original_file: &SourceFile, offset: TextUnit) -> Option<hir::Type> {
+        let mut token = original_file.syntax().token_at_offset(offset).left_biased()?;
+        if token.kind() == WHITESPACE {
+            token = token.prev_token()?;
+            if token.kind() != T![return] && token.kind() != T![break] {
+                return None;
+            }
+        } else if token.kind() != IDENT {
+            return None;
+        }
+        let expr = token.parent().ancestors().find(|it| match it.kind() {
+            NAME_REF | PATH_SEGMENT | PATH | PATH_EXPR => false,
+            _ => true,
+        })?;
+        match expr.kind() {
+            RETURN_EXPR => self.fn_return_type(&expr),
+            BREAK_EXPR => {
+                let loop_expr = enclosing_loop(&expr)?;
+                self.loop_value_type(&loop_expr)
+            }
+            _ => None,
+        }
+    }
+
+    /// The return type written in the signature of the function containing
+    /// `node`. Closures have no written return type, so this returns `None`
+    /// inside of them.
+    pub(super) fn fn_return_type(&self, node: &SyntaxNode) -> Option<hir::Type> {
+        let fn_def = node
+            .ancestors()
+            .find(|it| it.kind() == FN_DEF || it.kind() == LAMBDA_EXPR)
+            .and_then(ast::FnDef::cast)?;
+        let type_ref = fn_def.ret_type()?.type_ref()?;
+        self.analyzer.resolve_type(self.db, &type_ref)
+    }
+
+    /// The type of the values `loop_expr` breaks with, taken from the other
+    /// `break`s of the loop or from the type of the variable the loop
+    /// initializes.
+    pub(super) fn loop_value_type(&self, loop_expr: &ast::LoopExpr) -> Option<hir::Type> {
+        let from_breaks = loop_expr
+            .syntax()
+            .descendants()
+            .filter_map(ast::BreakExpr::cast)
+            .filter(|it| enclosing_loop(it.syntax()).as_ref() == Some(loop_expr))
+            .filter_map(|it| it.expr())
+            .filter_map(|it| self.analyzer.type_of(self.db, &it))
+            .find(|it| !it.is_unknown());
+        from_breaks.or_else(|| {
+            let let_stmt = loop_expr.syntax().parent().and_then(ast::LetStmt::cast)?;
+            self.analyzer.resolve_type(self.db, &let_stmt.ascribed_type()?)
+        })
+    }
+
     fn classify_name_ref(&mut self, original_file: SourceFile, name_ref: ast::NameRef) {
         self.name_ref_syntax =
             find_node_at_offset(original_file.syntax(), name_ref.syntax().text_range().start());
@@ -189,7 +333,18 @@ impl<'a> CompletionContext<'a> {
                 .and_then(|it| it.syntax().parent().and_then(ast::CallExpr::cast))
                 .is_some();
 
-            self.is_path_type = path.syntax().parent().and_then(ast::PathType::cast).is_some();
+            let path_type = path.syntax().parent().and_then(ast::PathType::cast);
+            self.is_path_type = path_type.is_some();
+            self.type_bound_syntax = path_type
+                .as_ref()
+                .and_then(|it| it.syntax().parent())
+                .and_then(ast::TypeBound::cast);
+            self.is_path_impl_trait = path_type
+                .and_then(|path_type| {
+                    let impl_block = path_type.syntax().parent().and_then(ast::ImplBlock::cast)?;
+                    Some(impl_block.target_trait()?.syntax() == path_type.syntax())
+                })
+                .unwrap_or(false);
             self.has_type_args = segment.type_arg_list().is_some();
 
             if let Some(path) = hir::Path::from_ast(path.clone()) {
@@ -243,17 +398,18 @@ impl<'a> CompletionContext<'a> {
                 .expr()
                 .map(|e| e.syntax().text_range())
                 .and_then(|r| find_node_with_range(original_file.syntax(), r));
-            self.dot_receiver_is_ambiguous_float_literal =
-                if let Some(ast::Expr::Literal(l)) = &self.dot_receiver {
-                    match l.kind() {
-                        ast::LiteralKind::FloatNumber { .. } => l.token().text().ends_with('.'),
-                        _ => false,
-                    }
-                } else {
-                    false
-                }
+            self.dot_receiver_is_ambiguous_float_literal = match &self.dot_receiver {
+                Some(ast::Expr::Literal(l)) => match l.kind() {
+                    ast::LiteralKind::FloatNumber { .. } => l.token().text().ends_with('.'),
+                    _ => false,
+                },
+                Some(ast::Expr::FieldExpr(f)) => f
+                    .index_token()
+                    .map_or(false, |it| it.kind() == FLOAT_NUMBER && it.text().ends_with('.')),
+                _ => false,
+            }
         }
-        if let Some(method_call_expr) = ast::MethodCallExpr::cast(parent) {
+        if let Some(method_call_expr) = ast::MethodCallExpr::cast(parent.clone()) {
             // As above
             self.dot_receiver = method_call_expr
                 .expr()
@@ -261,7 +417,98 @@ impl<'a> CompletionContext<'a> {
                 .and_then(|r| find_node_with_range(original_file.syntax(), r));
             self.is_call = true;
         }
+        if let Some(receiver) = &self.dot_receiver {
+            self.dot_access_can_end_stmt = match parent.parent() {
+                Some(stmt) if ast::ExprStmt::can_cast(stmt.kind()) => {
+                    stmt.last_token().map_or(true, |it| it.kind() != T![;])
+                }
+                Some(block) => ast::Block::cast(block)
+                    .and_then(|it| it.expr())
+                    .map_or(false, |it| it.syntax() == &parent),
+                None => false,
+            };
+            let is_iterable = parent
+                .parent()
+                .and_then(ast::ForExpr::cast)
+                .and_then(|it| it.iterable())
+                .map_or(false, |it| it.syntax() == &parent);
+            self.dot_receiver_is_for_loop_iterable = is_iterable
+                && self
+                    .analyzer
+                    .type_of(self.db, receiver)
+                    .map_or(false, |ty| self.analyzer.impls_into_iterator(self.db, ty));
+        }
+    }
+}
+
+/// The innermost `loop` around `node`, if it is not a `while` or `for` loop,
+/// which can't break with a value.
+pub(super) fn enclosing_loop(node: &SyntaxNode) -> Option<ast::LoopExpr> {
+    node.ancestors()
+        .skip(1)
+        .take_while(|it| it.kind() != FN_DEF && it.kind() != LAMBDA_EXPR)
+        .find(|it| it.kind() == LOOP_EXPR || it.kind() == WHILE_EXPR || it.kind() == FOR_EXPR)
+        .and_then(ast::LoopExpr::cast)
+}
+
+/// Whether `offset` is right after the header of an item with generic
+/// parameters, where a where clause can be started.
+fn is_where_clause_position(original_file: &SourceFile, offset: TextUnit) -> bool {
+    let mut token = match original_file.syntax().token_at_offset(offset).left_biased() {
+        Some(it) => it,
+        None => return false,
+    };
+    // skip the keyword being typed, and the whitespace before it
+    if token.kind() == IDENT {
+        token = match token.prev_token() {
+            Some(it) => it,
+            None => return false,
+        };
+    }
+    while token.kind().is_trivia() {
+        token = match token.prev_token() {
+            Some(it) => it,
+            None => return false,
+        };
+    }
+    // a header can't be complete after `->`, `for`, `:` and the like
+    match token.kind() {
+        IDENT | T![')'] | T![>] | T![']'] => (),
+        _ => return false,
+    }
+
+    let item = token.parent().ancestors().find(|it| match it.kind() {
+        FN_DEF | STRUCT_DEF | ENUM_DEF | UNION_DEF | TRAIT_DEF | IMPL_BLOCK | TYPE_ALIAS_DEF => {
+            true
+        }
+        _ => false,
+    });
+    let item = match item {
+        Some(it) => it,
+        None => return false,
+    };
+    let has_generics = item.children().any(|it| it.kind() == TYPE_PARAM_LIST);
+    let has_where_clause = item.children().any(|it| it.kind() == WHERE_CLAUSE);
+    if !has_generics || has_where_clause {
+        return false;
+    }
+
+    // The header is everything before the body, or before the `;` or `=`
+    let mut header_end = None;
+    for child in item.children_with_tokens() {
+        match child.kind() {
+            BLOCK_EXPR | RECORD_FIELD_DEF_LIST | ENUM_VARIANT_LIST | ITEM_LIST | T![;] | T![=] => {
+                if child.text_range().start() < offset {
+                    return false;
+                }
+                break;
+            }
+            ERROR => (),
+            kind if kind.is_trivia() => (),
+            _ => header_end = Some(child.text_range().end()),
+        }
     }
+    header_end == Some(token.text_range().end())
 }
 
 fn find_node_with_range<N: AstNode>(syntax: &SyntaxNode, range: TextRange) -> Option<N> {
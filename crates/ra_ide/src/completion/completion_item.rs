@@ -3,7 +3,7 @@
 use std::fmt;
 
 use hir::Documentation;
-use ra_syntax::TextRange;
+use ra_syntax::{SmolStr, TextRange};
 use ra_text_edit::TextEdit;
 
 /// `CompletionItem` describes a single completion variant in the editor pop-up.
@@ -47,6 +47,15 @@ pub struct CompletionItem {
 
     /// Whether this item is marked as deprecated
     deprecated: bool,
+
+    /// Whether this item fits the context better than the others, and should
+    /// be shown first.
+    score: Option<CompletionScore>,
+
+    /// The path of the item to import when the completion is applied. The
+    /// `use` is only computed on demand, with `Analysis::fixup_completion`,
+    /// as it is too slow for the many items which need one.
+    import_to_add: Option<Vec<SmolStr>>,
 }
 
 // We use custom debug for CompletionItem to make `insta`'s diffs more readable.
@@ -76,6 +85,12 @@ impl fmt::Debug for CompletionItem {
         if self.deprecated {
             s.field("deprecated", &true);
         }
+        if let Some(score) = &self.score {
+            s.field("score", score);
+        }
+        if let Some(import_to_add) = &self.import_to_add {
+            s.field("import_to_add", import_to_add);
+        }
         s.finish()
     }
 }
@@ -112,6 +127,14 @@ pub(crate) enum CompletionKind {
     Snippet,
     Postfix,
     BuiltinType,
+    BuiltinMacro,
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum CompletionScore {
+    /// The item is what is most often written at the cursor, like `iter` in
+    /// `for x in xs.<|>`.
+    ContextMatch,
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -139,6 +162,8 @@ impl CompletionItem {
             kind: None,
             text_edit: None,
             deprecated: None,
+            score: None,
+            import_to_add: None,
         }
     }
     /// What user sees in pop-up in the UI.
@@ -177,6 +202,14 @@ impl CompletionItem {
     pub fn deprecated(&self) -> bool {
         self.deprecated
     }
+
+    pub fn score(&self) -> Option<CompletionScore> {
+        self.score
+    }
+
+    pub fn import_to_add(&self) -> Option<&[SmolStr]> {
+        self.import_to_add.as_ref().map(|it| it.as_slice())
+    }
 }
 
 /// A helper to make `CompletionItem`s.
@@ -193,6 +226,8 @@ pub(crate) struct Builder {
     kind: Option<CompletionItemKind>,
     text_edit: Option<TextEdit>,
     deprecated: Option<bool>,
+    score: Option<CompletionScore>,
+    import_to_add: Option<Vec<SmolStr>>,
 }
 
 impl Builder {
@@ -221,6 +256,8 @@ impl Builder {
             kind: self.kind,
             completion_kind: self.completion_kind,
             deprecated: self.deprecated.unwrap_or(false),
+            score: self.score,
+            import_to_add: self.import_to_add,
         }
     }
     pub(crate) fn lookup_by(mut self, lookup: impl Into<String>) -> Builder {
@@ -271,6 +308,14 @@ impl Builder {
         self.deprecated = Some(deprecated);
         self
     }
+    pub(crate) fn set_score(mut self, score: Option<CompletionScore>) -> Builder {
+        self.score = score;
+        self
+    }
+    pub(crate) fn import_to_add(mut self, path: Vec<SmolStr>) -> Builder {
+        self.import_to_add = Some(path);
+        self
+    }
 }
 
 impl<'a> Into<CompletionItem> for Builder {
@@ -296,6 +341,9 @@ impl Completions {
     {
         items.into_iter().for_each(|item| self.add(item.into()))
     }
+    pub(crate) fn retain(&mut self, f: impl FnMut(&CompletionItem) -> bool) {
+        self.buf.retain(f)
+    }
 }
 
 impl Into<Vec<CompletionItem>> for Completions {
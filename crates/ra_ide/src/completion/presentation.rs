@@ -1,12 +1,13 @@
 //! This modules takes care of rendering various definitions as completion items.
 
-use hir::{db::HirDatabase, Docs, HasAttrs, HasSource, HirDisplay, ScopeDef, Type};
+use hir::{db::HirDatabase, Docs, HasAttrs, HasSource, HirDisplay, ScopeDef, StructKind, Type};
 use join_to_string::join;
 use ra_syntax::ast::NameOwner;
 use test_utils::tested_by;
 
 use crate::completion::{
-    CompletionContext, CompletionItem, CompletionItemKind, CompletionKind, Completions,
+    CompletionContext, CompletionItem, CompletionItemKind, CompletionKind, CompletionScore,
+    Completions,
 };
 
 use crate::display::{const_label, function_label, macro_label, type_label};
@@ -214,6 +215,7 @@ impl Completions {
                 })
                 .set_documentation(func.docs(ctx.db))
                 .set_deprecated(is_deprecated(func, ctx.db))
+                .set_score(iterator_method_score(ctx, &func_name, has_self_param))
                 .detail(detail);
 
         // Add `<>` for generic types
@@ -266,14 +268,61 @@ impl Completions {
     }
 
     pub(crate) fn add_enum_variant(&mut self, ctx: &CompletionContext, variant: hir::EnumVariant) {
+        let name = variant.name(ctx.db).to_string();
+        self.add_enum_variant_with_label(ctx, variant, name)
+    }
+
+    /// Adds a variant qualified with its enum, like `Option::Some`.
+    pub(crate) fn add_qualified_enum_variant(
+        &mut self,
+        ctx: &CompletionContext,
+        variant: hir::EnumVariant,
+    ) {
+        let label =
+            format!("{}::{}", variant.parent_enum(ctx.db).name(ctx.db), variant.name(ctx.db));
+        self.add_enum_variant_with_label(ctx, variant, label)
+    }
+
+    /// Adds a pattern matching `variant`, with placeholders for its fields.
+    pub(crate) fn add_enum_variant_pattern(
+        &mut self,
+        ctx: &CompletionContext,
+        variant: hir::EnumVariant,
+    ) {
+        let path =
+            format!("{}::{}", variant.parent_enum(ctx.db).name(ctx.db), variant.name(ctx.db));
+        let num_fields = variant.fields(ctx.db).len();
+        let (label, snippet) = match variant.kind(ctx.db) {
+            StructKind::Tuple => {
+                let label = vec!["_"; num_fields].join(", ");
+                let snippet = (1..=num_fields).map(|i| format!("${{{}:_}}", i));
+                let snippet = join(snippet).separator(", ").to_string();
+                (format!("{}({})", path, label), format!("{}({})", path, snippet))
+            }
+            StructKind::Record => (format!("{} {{ .. }}", path), format!("{} {{ $0.. }}", path)),
+            StructKind::Unit => (path.clone(), path),
+        };
+        CompletionItem::new(CompletionKind::Magic, ctx.source_range(), label)
+            .kind(CompletionItemKind::EnumVariant)
+            .set_documentation(variant.docs(ctx.db))
+            .set_deprecated(is_deprecated(variant, ctx.db))
+            .insert_snippet(snippet)
+            .add_to(self);
+    }
+
+    fn add_enum_variant_with_label(
+        &mut self,
+        ctx: &CompletionContext,
+        variant: hir::EnumVariant,
+        label: String,
+    ) {
         let is_deprecated = is_deprecated(variant, ctx.db);
-        let name = variant.name(ctx.db);
         let detail_types = variant.fields(ctx.db).into_iter().map(|field| field.ty(ctx.db));
         let detail = join(detail_types.map(|t| t.display(ctx.db).to_string()))
             .separator(", ")
             .surround_with("(", ")")
             .to_string();
-        CompletionItem::new(CompletionKind::Reference, ctx.source_range(), name.to_string())
+        CompletionItem::new(CompletionKind::Reference, ctx.source_range(), label)
             .kind(CompletionItemKind::EnumVariant)
             .set_documentation(variant.docs(ctx.db))
             .set_deprecated(is_deprecated)
@@ -286,6 +335,22 @@ fn is_deprecated(node: impl HasAttrs, db: &impl HirDatabase) -> bool {
     node.attrs(db).by_key("deprecated").exists()
 }
 
+/// Methods turning a collection into an iterator are the ones usually called
+/// on the iterable of a `for` loop.
+fn iterator_method_score(
+    ctx: &CompletionContext,
+    name: &hir::Name,
+    has_self_param: bool,
+) -> Option<CompletionScore> {
+    if !ctx.dot_receiver_is_for_loop_iterable || !has_self_param {
+        return None;
+    }
+    match name.to_string().as_str() {
+        "iter" | "iter_mut" | "into_iter" => Some(CompletionScore::ContextMatch),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use insta::assert_debug_snapshot;
@@ -1,19 +1,30 @@
 //! FIXME: write short doc here
 
-use std::cell::RefCell;
+use std::{cell::RefCell, iter::successors};
 
-use hir::diagnostics::{AstDiagnostic, Diagnostic as _, DiagnosticSink};
+use hir::{
+    diagnostics::{AstDiagnostic, Diagnostic as _, DiagnosticSink},
+    HasSource,
+};
 use itertools::Itertools;
-use ra_db::{RelativePath, SourceDatabase, SourceDatabaseExt};
+use ra_db::{
+    CheckCanceled, CrateId, Edition, FileLoader, RelativePath, SourceDatabase, SourceDatabaseExt,
+};
 use ra_prof::profile;
 use ra_syntax::{
     algo,
-    ast::{self, make, AstNode},
-    Location, SyntaxNode, TextRange, T,
+    ast::{self, make, AstNode, AttrsOwner, TypeParamsOwner},
+    Location, SmolStr,
+    SyntaxKind::{ATTR, COMMENT, IDENT, PATH_SEGMENT, WHITESPACE},
+    SyntaxNode, TextRange, T,
 };
 use ra_text_edit::{TextEdit, TextEditBuilder};
+use rustc_hash::FxHashSet;
 
-use crate::{db::RootDatabase, Diagnostic, FileId, FileSystemEdit, SourceChange, SourceFileEdit};
+use crate::{
+    db::{LineIndexDatabase, RootDatabase},
+    Diagnostic, FileId, FileSystemEdit, SourceChange, SourceFileEdit,
+};
 
 #[derive(Debug, Copy, Clone)]
 pub enum Severity {
@@ -33,15 +44,35 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
         fix: None,
     }));
 
+    // trait objects are written without `dyn` in the 2015 edition
+    let warn_bare_trait_objects = db
+        .relevant_crates(file_id)
+        .iter()
+        .any(|&krate| db.crate_graph().edition(krate) == Edition::Edition2018);
+    let features = enabled_features(db, file_id);
     for node in parse.tree().syntax().descendants() {
+        // the syntactic checks don't run queries, which would notice on their
+        // own that the diagnostics are stale
+        if ast::ModuleItem::can_cast(node.kind()) {
+            db.check_canceled();
+        }
         check_unnecessary_braces_in_use_statement(&mut res, file_id, &node);
         check_struct_shorthand_initialization(&mut res, file_id, &node);
+        check_needless_return(&mut res, file_id, &node);
+        check_chained_comparison(&mut res, file_id, &node);
+        check_type_arg_count(&mut res, db, file_id, &node);
+        if let Some(features) = &features {
+            check_feature_gate(&mut res, features, &node);
+        }
+        if warn_bare_trait_objects {
+            check_bare_trait_object(&mut res, db, file_id, &node);
+        }
     }
     let res = RefCell::new(res);
     let mut sink = DiagnosticSink::new(|d| {
         res.borrow_mut().push(Diagnostic {
             message: d.message(),
-            range: d.highlight_range(),
+            range: original_range(db, d),
             severity: Severity::Error,
             fix: None,
         })
@@ -82,6 +113,268 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
             fix: Some(fix),
         })
     })
+    .on::<hir::diagnostics::DuplicateDefinition, _>(|d| {
+        let message = match first_definition_line(db, file_id, d) {
+            Some(line) => format!("{}, first defined on line {}", d.message(), line),
+            None => d.message(),
+        };
+        res.borrow_mut().push(Diagnostic {
+            range: original_range(db, d),
+            message,
+            severity: Severity::Error,
+            fix: None,
+        })
+    })
+    .on::<hir::diagnostics::PrivateItem, _>(|d| {
+        res.borrow_mut().push(Diagnostic {
+            range: original_range(db, d),
+            message: d.message(),
+            severity: Severity::Error,
+            fix: make_item_pub_fix(db, d),
+        })
+    })
+    .on::<hir::diagnostics::UnwrapCall, _>(|d| {
+        if !db.feature_flags.get("diagnostics.warn-unwrap") {
+            return;
+        }
+        let call = d.ast(db);
+        // calls in macro expansions are shown on the macro call, the quick fix
+        // only makes sense if the call is in the current file
+        let (range, fix) = if d.file == hir::HirFileId::from(file_id) {
+            let range = match call.syntax().children_with_tokens().find(|it| it.kind() == T![.]) {
+                Some(dot) => {
+                    TextRange::from_to(dot.text_range().start(), call.syntax().text_range().end())
+                }
+                None => call.syntax().text_range(),
+            };
+            let fix = if d.can_use_try {
+                let edit = TextEdit::replace(range, "?".to_string());
+                Some(SourceChange::source_file_edit_from("replace with `?`", file_id, edit))
+            } else {
+                None
+            };
+            (range, fix)
+        } else {
+            (hir::original_range(db, hir::InFile::new(d.file, call.syntax())).range, None)
+        };
+        res.borrow_mut().push(Diagnostic {
+            range,
+            message: d.message(),
+            severity: Severity::WeakWarning,
+            fix,
+        })
+    })
+    .on::<hir::diagnostics::UnusedMustUse, _>(|d| {
+        if !db.feature_flags.get("diagnostics.warn-must-use") {
+            return;
+        }
+        // calls in macro expansions are shown on the macro call, the quick fix
+        // only makes sense if the call is in the current file
+        let (range, fix) = if d.file == hir::HirFileId::from(file_id) {
+            let range = d.highlight_range();
+            let fix = if d.can_use_try {
+                let edit = TextEdit::insert(range.end(), "?".to_string());
+                SourceChange::source_file_edit_from("propagate with `?`", file_id, edit)
+            } else {
+                let edit = TextEdit::insert(range.start(), "let _ = ".to_string());
+                SourceChange::source_file_edit_from("discard with `let _ =`", file_id, edit)
+            };
+            (range, Some(fix))
+        } else {
+            (hir::original_range(db, hir::InFile::new(d.file, d.ast(db).syntax())).range, None)
+        };
+        res.borrow_mut().push(Diagnostic {
+            range,
+            message: d.message(),
+            severity: Severity::WeakWarning,
+            fix,
+        })
+    })
+    .on::<hir::diagnostics::FloatEqComparison, _>(|d| {
+        if !db.feature_flags.get("diagnostics.warn-float-cmp") {
+            return;
+        }
+        let expr = d.ast(db);
+        // comparisons in macro expansions are shown on the macro call, the
+        // quick fix only makes sense if the comparison is in the current file
+        let (range, fix) = if d.file == hir::HirFileId::from(file_id) {
+            let fix = match (expr.lhs(), expr.rhs()) {
+                (Some(lhs), Some(rhs)) => {
+                    let cmp = if d.negated { ">=" } else { "<" };
+                    let replacement = format!(
+                        "({} - {}).abs() {} {}::EPSILON",
+                        parenthesize_unless_atom(&lhs),
+                        parenthesize_unless_atom(&rhs),
+                        cmp,
+                        d.ty
+                    );
+                    let edit = TextEdit::replace(expr.syntax().text_range(), replacement);
+                    Some(SourceChange::source_file_edit_from("compare with epsilon", file_id, edit))
+                }
+                _ => None,
+            };
+            (d.highlight_range(), fix)
+        } else {
+            (hir::original_range(db, hir::InFile::new(d.file, expr.syntax())).range, None)
+        };
+        res.borrow_mut().push(Diagnostic {
+            range,
+            message: d.message(),
+            severity: Severity::WeakWarning,
+            fix,
+        })
+    })
+    .on::<hir::diagnostics::RedundantPatternMatching, _>(|d| {
+        if !db.feature_flags.get("diagnostics.warn-redundant-pattern-matching") {
+            return;
+        }
+        let condition = match d.ast(db).condition() {
+            Some(it) => it,
+            None => return,
+        };
+        // `if let`s in macro expansions are shown on the macro call, the quick
+        // fix only makes sense if the `if let` is in the current file
+        let (range, fix) = if d.file == hir::HirFileId::from(file_id) {
+            let fix = condition.expr().map(|expr| {
+                let replacement = format!("{}.{}()", parenthesize_unless_atom(&expr), d.method);
+                let edit = TextEdit::replace(condition.syntax().text_range(), replacement);
+                let label = format!("use `{}()`", d.method);
+                SourceChange::source_file_edit_from(label, file_id, edit)
+            });
+            (condition.syntax().text_range(), fix)
+        } else {
+            (hir::original_range(db, hir::InFile::new(d.file, condition.syntax())).range, None)
+        };
+        res.borrow_mut().push(Diagnostic {
+            range,
+            message: d.message(),
+            severity: Severity::WeakWarning,
+            fix,
+        })
+    })
+    .on::<hir::diagnostics::ShadowedBinding, _>(|d| {
+        if !db.feature_flags.get("diagnostics.warn-shadowing") {
+            return;
+        }
+        res.borrow_mut().push(Diagnostic {
+            range: original_range(db, d),
+            message: d.message(),
+            severity: Severity::WeakWarning,
+            fix: None,
+        })
+    })
+    .on::<hir::diagnostics::NoOpOperation, _>(|d| {
+        if !db.feature_flags.get("diagnostics.warn-no-op") {
+            return;
+        }
+        // `x && x` is just `x`, while for assignments the fix depends on what
+        // was meant. Operations in macro expansions are shown on the macro
+        // call, without a fix.
+        let fix = if d.file == hir::HirFileId::from(file_id) && !d.self_assignment {
+            let expr = d.ast(db);
+            match (expr.op_kind(), expr.lhs()) {
+                (Some(ast::BinOp::BooleanAnd), Some(lhs))
+                | (Some(ast::BinOp::BooleanOr), Some(lhs)) => {
+                    let edit =
+                        TextEdit::replace(expr.syntax().text_range(), lhs.syntax().to_string());
+                    Some(SourceChange::source_file_edit_from(
+                        "remove duplicate operand",
+                        file_id,
+                        edit,
+                    ))
+                }
+                _ => None,
+            }
+        } else {
+            None
+        };
+        res.borrow_mut().push(Diagnostic {
+            range: original_range(db, d),
+            message: d.message(),
+            severity: Severity::WeakWarning,
+            fix,
+        })
+    })
+    .on::<hir::diagnostics::RedundantCast, _>(|d| {
+        // the quick fix only makes sense if the cast is in the current file
+        let fix = if d.file == hir::HirFileId::from(file_id) {
+            d.ast(db).expr().map(|expr| {
+                let edit = TextEdit::replace(d.highlight_range(), expr.syntax().to_string());
+                SourceChange::source_file_edit_from("remove the cast", file_id, edit)
+            })
+        } else {
+            None
+        };
+        res.borrow_mut().push(Diagnostic {
+            range: original_range(db, d),
+            message: d.message(),
+            severity: Severity::WeakWarning,
+            fix,
+        })
+    })
+    .on::<hir::diagnostics::TruncatingCast, _>(|d| {
+        if !db.feature_flags.get("diagnostics.warn-truncating-cast") {
+            return;
+        }
+        res.borrow_mut().push(Diagnostic {
+            range: original_range(db, d),
+            message: d.message(),
+            severity: Severity::WeakWarning,
+            fix: None,
+        })
+    })
+    .on::<hir::diagnostics::UnusedCollection, _>(|d| {
+        if !db.feature_flags.get("diagnostics.warn-unused-collection") {
+            return;
+        }
+        // the whole statement is highlighted, and removed by the fix
+        let stmt = if d.file == hir::HirFileId::from(file_id) {
+            d.ast(db).syntax().parent().filter(|it| {
+                ast::LetStmt::can_cast(it.kind()) || ast::ExprStmt::can_cast(it.kind())
+            })
+        } else {
+            None
+        };
+        let (range, fix) = match stmt {
+            Some(stmt) => {
+                let fix = if d.removable {
+                    let range = match stmt.prev_sibling_or_token() {
+                        Some(ws) if ws.kind() == WHITESPACE => {
+                            TextRange::from_to(ws.text_range().start(), stmt.text_range().end())
+                        }
+                        _ => stmt.text_range(),
+                    };
+                    let edit = TextEdit::delete(range);
+                    Some(SourceChange::source_file_edit_from("remove the statement", file_id, edit))
+                } else {
+                    None
+                };
+                (stmt.text_range(), fix)
+            }
+            None => (original_range(db, d), None),
+        };
+        res.borrow_mut().push(Diagnostic {
+            range,
+            message: d.message(),
+            severity: Severity::WeakWarning,
+            fix,
+        })
+    })
+    .on::<hir::diagnostics::MoveOutOfBorrow, _>(|d| {
+        let fix = if d.file == hir::HirFileId::from(file_id) {
+            let offset = d.ast(db).syntax().text_range().start();
+            let edit = TextEdit::insert(offset, "ref ".to_string());
+            Some(SourceChange::source_file_edit_from("borrow with `ref`", file_id, edit))
+        } else {
+            None
+        };
+        res.borrow_mut().push(Diagnostic {
+            range: original_range(db, d),
+            message: d.message(),
+            severity: Severity::Error,
+            fix,
+        })
+    })
     .on::<hir::diagnostics::MissingOkInTailExpr, _>(|d| {
         let node = d.ast(db);
         let replacement = format!("Ok({})", node.syntax());
@@ -103,6 +396,90 @@ pub(crate) fn diagnostics(db: &RootDatabase, file_id: FileId) -> Vec<Diagnostic>
     drop(sink);
     res.into_inner()
 }
+
+/// Computes the diagnostics of all the files with modules of `krate`, one
+/// file after the other so that a change in between cancels the rest.
+pub(crate) fn crate_diagnostics(
+    db: &RootDatabase,
+    krate: CrateId,
+) -> Vec<(FileId, Vec<Diagnostic>)> {
+    let _p = profile("crate_diagnostics");
+    let mut files = Vec::new();
+    let mut modules: Vec<_> = hir::Crate::from(krate).root_module(db).into_iter().collect();
+    while let Some(module) = modules.pop() {
+        let src = module.definition_source(db);
+        if let hir::ModuleSource::SourceFile(_) = src.value {
+            let file_id = src.file_id.original_file(db);
+            if !files.contains(&file_id) {
+                files.push(file_id);
+            }
+        }
+        modules.extend(module.children(db));
+    }
+    files
+        .into_iter()
+        .map(|file_id| {
+            db.check_canceled();
+            (file_id, diagnostics(db, file_id))
+        })
+        .collect()
+}
+
+fn make_item_pub_fix(db: &RootDatabase, d: &hir::diagnostics::PrivateItem) -> Option<SourceChange> {
+    // we can't edit the definition if it comes from a macro expansion
+    if d.item.file_id.call_node(db).is_some() {
+        return None;
+    }
+    let item_file_id = d.item.file_id.original_file(db);
+    let item = d.item.value.to_node(db.parse(item_file_id).tree().syntax());
+    let edit = match item.children().find_map(ast::Visibility::cast) {
+        Some(vis) => TextEdit::replace(vis.syntax().text_range(), "pub".to_string()),
+        None => {
+            let offset = item
+                .children_with_tokens()
+                .find(|it| match it.kind() {
+                    WHITESPACE | COMMENT | ATTR => false,
+                    _ => true,
+                })
+                .map(|it| it.text_range().start())
+                .unwrap_or_else(|| item.text_range().start());
+            TextEdit::insert(offset, "pub ".to_string())
+        }
+    };
+    Some(SourceChange::source_file_edit_from(
+        format!("make {} `{}` public", d.kind, d.name),
+        item_file_id,
+        edit,
+    ))
+}
+
+/// The line (starting at 1) of the first of the duplicate definitions, if it
+/// is in the file the diagnostics are computed for.
+fn first_definition_line(
+    db: &RootDatabase,
+    file_id: FileId,
+    d: &hir::diagnostics::DuplicateDefinition,
+) -> Option<u32> {
+    let root = db.parse_or_expand(d.first.file_id)?;
+    let name = d.first.value.to_node(&root);
+    let range = hir::original_range(db, d.first.with_value(name.syntax()));
+    if range.file_id != file_id {
+        return None;
+    }
+    Some(db.line_index(file_id).line_col(range.range.start()).line + 1)
+}
+
+/// The range of the diagnostic in the file the diagnostics are computed for:
+/// diagnostics in macro expansions are shown on the corresponding part of the
+/// macro call.
+fn original_range(db: &RootDatabase, d: &dyn hir::diagnostics::Diagnostic) -> TextRange {
+    if d.source().file_id.call_node(db).is_some() {
+        hir::original_range(db, hir::InFile::new(d.source().file_id, &d.syntax_node(db))).range
+    } else {
+        d.highlight_range()
+    }
+}
+
 fn location_to_range(location: Location) -> TextRange {
     match location {
         Location::Offset(offset) => TextRange::offset_len(offset, 1.into()),
@@ -187,14 +564,351 @@ fn check_struct_shorthand_initialization(
     Some(())
 }
 
+/// Checks for a `return` as the last statement of a function, where the
+/// returned value can be the tail expression instead.
+fn check_needless_return(
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let block = ast::FnDef::cast(node.clone())?.body()?.block()?;
+    let (stmt, return_expr) = match block.expr() {
+        Some(ast::Expr::ReturnExpr(it)) => (it.syntax().clone(), it),
+        Some(_) => return None,
+        None => match block.statements().last()? {
+            ast::Stmt::ExprStmt(stmt) => match stmt.expr()? {
+                ast::Expr::ReturnExpr(it) => (stmt.syntax().clone(), it),
+                _ => return None,
+            },
+            _ => return None,
+        },
+    };
+    // a bare `return;` in a function returning `()` is left alone
+    let value = return_expr.expr()?;
+    let edit = TextEdit::replace(stmt.text_range(), value.syntax().text().to_string());
+    acc.push(Diagnostic {
+        range: stmt.text_range(),
+        message: "Unneeded `return` statement".to_string(),
+        severity: Severity::WeakWarning,
+        fix: Some(SourceChange::source_file_edit(
+            "remove `return`",
+            SourceFileEdit { file_id, edit },
+        )),
+    });
+    Some(())
+}
+
+/// The features enabled with `#![feature(...)]` in the roots of the crates of
+/// the file, `None` if the file doesn't belong to a crate.
+fn enabled_features(db: &RootDatabase, file_id: FileId) -> Option<FxHashSet<SmolStr>> {
+    let crates = db.relevant_crates(file_id);
+    if crates.is_empty() {
+        return None;
+    }
+    let crate_graph = db.crate_graph();
+    let mut features = FxHashSet::default();
+    for &krate in crates.iter() {
+        let root = db.parse(crate_graph.crate_root(krate)).tree();
+        for attr in root.attrs() {
+            match attr.as_simple_call() {
+                Some((name, tt)) if name == "feature" => features.extend(
+                    tt.syntax()
+                        .children_with_tokens()
+                        .filter_map(|it| it.into_token())
+                        .filter(|it| it.kind() == IDENT)
+                        .map(|it| it.text().clone()),
+                ),
+                _ => (),
+            }
+        }
+    }
+    Some(features)
+}
+
+/// Checks for unstable syntax, which the parser accepts in any case, used
+/// without enabling its feature in the crate root.
+fn check_feature_gate(
+    acc: &mut Vec<Diagnostic>,
+    features: &FxHashSet<SmolStr>,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let (feature, syntax) = if ast::TryBlockExpr::can_cast(node.kind()) {
+        ("try_blocks", "`try` blocks")
+    } else if ast::BoxExpr::can_cast(node.kind()) {
+        ("box_syntax", "`box` expressions")
+    } else if ast::BoxPat::can_cast(node.kind()) {
+        ("box_patterns", "`box` patterns")
+    } else {
+        return None;
+    };
+    if features.contains(feature) {
+        return None;
+    }
+    // the keyword starting the expression or pattern
+    let keyword = node.first_token()?;
+    acc.push(Diagnostic {
+        range: keyword.text_range(),
+        message: format!(
+            "{} are unstable, add `#![feature({})]` to the crate root",
+            syntax, feature
+        ),
+        severity: Severity::Error,
+        fix: None,
+    });
+    Some(())
+}
+
+/// Checks for comparisons whose left operand is a comparison, like `a < b < c`,
+/// which compares the `bool` of `a < b` with `c` rather than checking that `b`
+/// is between `a` and `c`.
+fn check_chained_comparison(
+    acc: &mut Vec<Diagnostic>,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let chain = ast::BinExpr::cast(node.clone())?;
+    if !is_comparison(&chain) {
+        return None;
+    }
+    // only the whole chain is reported
+    if let Some(parent) = chain.syntax().parent().and_then(ast::BinExpr::cast) {
+        if is_comparison(&parent) && parent.lhs().map_or(false, |it| it.syntax() == chain.syntax())
+        {
+            return None;
+        }
+    }
+
+    // `a < b < c` is `(a < b) < c`, the operands are collected from the right
+    let mut operands = Vec::new();
+    let mut ops = Vec::new();
+    let mut current = chain.clone();
+    loop {
+        operands.push(current.rhs()?);
+        ops.push(current.op_token()?);
+        match current.lhs()? {
+            ast::Expr::BinExpr(lhs) if is_comparison(&lhs) => current = lhs,
+            lhs => {
+                operands.push(lhs);
+                break;
+            }
+        }
+    }
+    if ops.len() < 2 {
+        return None;
+    }
+    operands.reverse();
+    ops.reverse();
+
+    // the middle operands are evaluated twice by the fix
+    let can_duplicate = operands[1..operands.len() - 1].iter().all(is_simple_operand);
+    let fix = if can_duplicate {
+        let comparisons = ops.iter().enumerate().map(|(i, op)| {
+            format!("{} {} {}", operands[i].syntax(), op.text(), operands[i + 1].syntax())
+        });
+        let edit = TextEdit::replace(chain.syntax().text_range(), comparisons.join(" && "));
+        Some(SourceChange::source_file_edit(
+            "split into comparisons combined with `&&`",
+            SourceFileEdit { file_id, edit },
+        ))
+    } else {
+        None
+    };
+    acc.push(Diagnostic {
+        range: chain.syntax().text_range(),
+        message: "comparison operators cannot be chained, the first comparison gives a `bool`"
+            .to_string(),
+        severity: Severity::Error,
+        fix,
+    });
+    Some(())
+}
+
+fn is_comparison(expr: &ast::BinExpr) -> bool {
+    match expr.op_kind() {
+        Some(ast::BinOp::EqualityTest)
+        | Some(ast::BinOp::NegatedEqualityTest)
+        | Some(ast::BinOp::LesserEqualTest)
+        | Some(ast::BinOp::GreaterEqualTest)
+        | Some(ast::BinOp::LesserTest)
+        | Some(ast::BinOp::GreaterTest) => true,
+        _ => false,
+    }
+}
+
+/// Whether evaluating the operand twice is the same as evaluating it once.
+fn is_simple_operand(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::PathExpr(_) | ast::Expr::Literal(_) => true,
+        _ => false,
+    }
+}
+
+/// The text of `expr`, in parentheses unless it is an atom which can be used
+/// as an operand or a receiver as is.
+fn parenthesize_unless_atom(expr: &ast::Expr) -> String {
+    match expr {
+        ast::Expr::PathExpr(_)
+        | ast::Expr::Literal(_)
+        | ast::Expr::CallExpr(_)
+        | ast::Expr::MethodCallExpr(_)
+        | ast::Expr::FieldExpr(_)
+        | ast::Expr::IndexExpr(_)
+        | ast::Expr::ParenExpr(_) => expr.syntax().to_string(),
+        _ => format!("({})", expr.syntax()),
+    }
+}
+
+/// Checks the number of type and lifetime arguments given to a struct, enum,
+/// union or type alias. Type parameters with a default can be left out, as can
+/// all the lifetimes.
+fn check_type_arg_count(
+    acc: &mut Vec<Diagnostic>,
+    db: &RootDatabase,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let path = ast::PathType::cast(node.clone())?.path()?;
+    let type_arg_list = path.segment()?.type_arg_list()?;
+    let analyzer = hir::SourceAnalyzer::new(db, hir::InFile::new(file_id.into(), node), None);
+    let (def, lifetime_params) = match analyzer.resolve_path(db, &path)? {
+        hir::PathResolution::Def(hir::ModuleDef::Adt(it)) => {
+            let type_param_list = match it {
+                hir::Adt::Struct(it) => it.source(db).value.type_param_list(),
+                hir::Adt::Union(it) => it.source(db).value.type_param_list(),
+                hir::Adt::Enum(it) => it.source(db).value.type_param_list(),
+            };
+            (hir::GenericDef::from(it), type_param_list)
+        }
+        hir::PathResolution::Def(hir::ModuleDef::TypeAlias(it)) => {
+            (it.into(), it.source(db).value.type_param_list())
+        }
+        _ => return None,
+    };
+
+    let type_params = def.type_params(db);
+    let max = type_params.len();
+    let min = type_params.iter().filter(|it| !it.has_default(db)).count();
+    let found = type_arg_list.type_args().count();
+    let expected = if found < min && min < max {
+        Some(format!("at least {}", min))
+    } else if found > max && min < max {
+        Some(format!("at most {}", max))
+    } else if found < min || found > max {
+        Some(max.to_string())
+    } else {
+        None
+    };
+    if let Some(expected) = expected {
+        acc.push(Diagnostic {
+            range: type_arg_list.syntax().text_range(),
+            message: format!(
+                "wrong number of type arguments: expected {}, found {}",
+                expected, found
+            ),
+            severity: Severity::Error,
+            fix: None,
+        });
+    }
+
+    let expected = lifetime_params.map_or(0, |it| it.lifetime_params().count());
+    let found = type_arg_list.lifetime_args().count();
+    if found != 0 && found != expected {
+        acc.push(Diagnostic {
+            range: type_arg_list.syntax().text_range(),
+            message: format!(
+                "wrong number of lifetime arguments: expected {}, found {}",
+                expected, found
+            ),
+            severity: Severity::Error,
+            fix: None,
+        });
+    }
+    Some(())
+}
+
+/// Checks for types naming a trait without `dyn`, like `Box<Display>`.
+fn check_bare_trait_object(
+    acc: &mut Vec<Diagnostic>,
+    db: &RootDatabase,
+    file_id: FileId,
+    node: &SyntaxNode,
+) -> Option<()> {
+    let path_type = ast::PathType::cast(node.clone())?;
+    let path = trait_object_candidate(&path_type)?;
+    let analyzer = hir::SourceAnalyzer::new(db, hir::InFile::new(file_id.into(), node), None);
+    match analyzer.resolve_path(db, &path)? {
+        hir::PathResolution::Def(hir::ModuleDef::Trait(_)) => {}
+        _ => return None,
+    }
+
+    let range = node.text_range();
+    let edit = TextEdit::insert(range.start(), "dyn ".to_string());
+    acc.push(Diagnostic {
+        range,
+        message: "trait objects without an explicit `dyn` are deprecated".to_string(),
+        severity: Severity::WeakWarning,
+        fix: Some(SourceChange::source_file_edit_from("add `dyn`", file_id, edit)),
+    });
+    Some(())
+}
+
+/// The path of a path type which may name a trait, without resolving it, as
+/// resolving every type of the file is expensive.
+fn trait_object_candidate(path_type: &ast::PathType) -> Option<ast::Path> {
+    let node = path_type.syntax();
+    let parent = node.parent()?;
+    // the traits of bounds, of trait impls and of qualified paths like
+    // `<T as Trait>::Assoc` aren't types
+    if ast::TypeBound::can_cast(parent.kind()) || parent.kind() == PATH_SEGMENT {
+        return None;
+    }
+    if let Some(impl_block) = ast::ImplBlock::cast(parent) {
+        if impl_block.target_trait().map_or(false, |it| it.syntax() == node) {
+            return None;
+        }
+    }
+    let path = path_type.path()?;
+    // paths starting with a type, like `<T>::Assoc`, name associated types
+    let first_segment = successors(Some(path.clone()), |it| it.qualifier()).last()?.segment()?;
+    if let Some(ast::PathSegmentKind::Type { .. }) = first_segment.kind() {
+        return None;
+    }
+    // `Self`, builtin types and the type parameters in scope aren't traits
+    let name_ref = path.segment()?.name_ref()?;
+    let name = name_ref.text().as_str();
+    if name == "Self" || hir::BuiltinType::ALL.iter().any(|(it, _)| it.to_string() == name) {
+        return None;
+    }
+    if path.qualifier().is_none() {
+        let is_type_param = node
+            .ancestors()
+            .flat_map(|it| it.children())
+            .filter_map(ast::TypeParamList::cast)
+            .flat_map(|it| it.type_params())
+            .filter_map(|it| it.name())
+            .any(|it| it.text().as_str() == name);
+        if is_type_param {
+            return None;
+        }
+    }
+    Some(path)
+}
+
 #[cfg(test)]
 mod tests {
     use insta::assert_debug_snapshot;
     use join_to_string::join;
+    use ra_cfg::CfgOptions;
+    use ra_db::{CrateGraph, Env};
     use ra_syntax::SourceFile;
     use test_utils::assert_eq_text;
 
-    use crate::mock_analysis::{analysis_and_position, single_file};
+    use std::sync::Arc;
+
+    use crate::{
+        mock_analysis::{analysis_and_position, single_file, MockAnalysis},
+        AnalysisChange, FeatureFlags,
+    };
 
     use super::*;
 
@@ -288,33 +1002,1070 @@ mod tests {
         assert_eq!(diagnostics.len(), 0);
     }
 
-    #[test]
-    fn test_wrap_return_type() {
-        let before = r#"
-            //- /main.rs
-            use std::{string::String, result::Result::{self, Ok, Err}};
+    /// Computes the diagnostics for `/main.rs` of the fixture, with the opt-in
+    /// `.unwrap()` lint enabled.
+    fn diagnostics_with_unwrap_lint(fixture: &str) -> (Arc<String>, Vec<Diagnostic>) {
+        diagnostics_with_lint("diagnostics.warn-unwrap", fixture)
+    }
 
-            fn div(x: i32, y: i32) -> Result<i32, String> {
-                if y == 0 {
-                    return Err("div by zero".into());
-                }
-                x / y<|>
-            }
+    fn diagnostics_with_lint(flag: &str, fixture: &str) -> (Arc<String>, Vec<Diagnostic>) {
+        let mock = MockAnalysis::with_files(fixture);
+        let file_id = mock.id_of("/main.rs");
+        let mut host = mock.analysis_host();
+        let mut feature_flags = FeatureFlags::default();
+        feature_flags.set(flag, true).unwrap();
+        host.db.feature_flags = Arc::new(feature_flags);
+        (host.db.file_text(file_id), diagnostics(&host.db, file_id))
+    }
 
+    const UNWRAP_STD: &str = r#"
             //- /std/lib.rs
-            pub mod string {
-                pub struct String { }
+            pub mod option {
+                pub enum Option<T> { Some(T), None }
+                impl<T> Option<T> {
+                    pub fn unwrap(self) -> T { loop {} }
+                }
             }
             pub mod result {
                 pub enum Result<T, E> { Ok(T), Err(E) }
+                impl<T, E> Result<T, E> {
+                    pub fn expect(self, msg: &str) -> T { loop {} }
+                }
             }
-        "#;
-        let after = r#"
-            use std::{string::String, result::Result::{self, Ok, Err}};
+    "#;
 
-            fn div(x: i32, y: i32) -> Result<i32, String> {
-                if y == 0 {
-                    return Err("div by zero".into());
+    #[test]
+    fn test_unwrap_lint_replaces_with_try() {
+        let fixture = format!(
+            "{}{}",
+            r#"
+            //- /main.rs
+            use std::option::Option;
+
+            fn foo(x: Option<u32>) -> Option<u32> {
+                let y = x.unwrap();
+                x
+            }
+            "#,
+            UNWRAP_STD
+        );
+        let (text, mut diagnostics) = diagnostics_with_unwrap_lint(&fixture);
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = diagnostics.pop().unwrap();
+        assert_eq!(diagnostic.message, "call to `.unwrap()` may panic");
+        assert_eq!(
+            &text[diagnostic.range.start().to_usize()..diagnostic.range.end().to_usize()],
+            ".unwrap()"
+        );
+        let mut fix = diagnostic.fix.unwrap();
+        let edit = fix.source_file_edits.pop().unwrap().edit;
+        assert_eq_text!(&text.replace("x.unwrap()", "x?"), &edit.apply(&text));
+    }
+
+    #[test]
+    fn test_unwrap_lint_without_fix_in_incompatible_fn() {
+        let fixture = format!(
+            "{}{}",
+            r#"
+            //- /main.rs
+            use std::result::Result;
+
+            fn foo(x: Result<u32, ()>) -> u32 {
+                x.expect("boom")
+            }
+            "#,
+            UNWRAP_STD
+        );
+        let (text, diagnostics) = diagnostics_with_unwrap_lint(&fixture);
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.message, "call to `.expect()` may panic");
+        assert_eq!(
+            &text[diagnostic.range.start().to_usize()..diagnostic.range.end().to_usize()],
+            r#".expect("boom")"#
+        );
+        assert!(diagnostic.fix.is_none());
+    }
+
+    #[test]
+    fn test_unwrap_lint_without_fix_in_macro_expansion() {
+        let fixture = format!(
+            "{}{}",
+            r#"
+            //- /main.rs
+            use std::option::Option;
+
+            macro_rules! unwrap_it {
+                ($e:expr) => { $e.unwrap() };
+            }
+
+            fn foo(x: Option<u32>) -> u32 {
+                unwrap_it!(x)
+            }
+            "#,
+            UNWRAP_STD
+        );
+        let (text, diagnostics) = diagnostics_with_unwrap_lint(&fixture);
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.message, "call to `.unwrap()` may panic");
+        let call_start = text.find("unwrap_it!(x)").unwrap();
+        assert!(diagnostic.range.start().to_usize() >= call_start);
+        assert!(diagnostic.range.end().to_usize() <= call_start + "unwrap_it!(x)".len());
+        assert!(diagnostic.fix.is_none());
+    }
+
+    #[test]
+    fn test_unwrap_lint_is_off_by_default() {
+        check_no_diagnostic_for_target_file(&format!(
+            "{}{}",
+            r#"
+            //- /main.rs
+            use std::option::Option;
+
+            fn foo(x: Option<u32>) -> u32 {
+                x.unwrap()<|>
+            }
+            "#,
+            UNWRAP_STD
+        ));
+    }
+
+    const MUST_USE_STD: &str = r#"
+            //- /std/lib.rs
+            pub mod result {
+                pub enum Result<T, E> { Ok(T), Err(E) }
+            }
+    "#;
+
+    fn check_must_use_fix(main: &str, message: &str, discarded: &str, fixed: &str) {
+        let fixture = format!("{}{}", main, MUST_USE_STD);
+        let (text, mut diagnostics) = diagnostics_with_lint("diagnostics.warn-must-use", &fixture);
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = diagnostics.pop().unwrap();
+        assert_eq!(diagnostic.message, message);
+        assert_eq!(
+            &text[diagnostic.range.start().to_usize()..diagnostic.range.end().to_usize()],
+            discarded
+        );
+        let mut fix = diagnostic.fix.unwrap();
+        let edit = fix.source_file_edits.pop().unwrap().edit;
+        assert_eq_text!(&text.replace(&format!("{};", discarded), fixed), &edit.apply(&text));
+    }
+
+    #[test]
+    fn test_must_use_lint_propagates_with_try() {
+        check_must_use_fix(
+            r#"
+            //- /main.rs
+            use std::result::Result;
+
+            fn do_thing() -> Result<(), ()> { loop {} }
+
+            fn foo() -> Result<(), ()> {
+                do_thing();
+                loop {}
+            }
+            "#,
+            "unused `Result` that must be used",
+            "do_thing()",
+            "do_thing()?;",
+        );
+    }
+
+    #[test]
+    fn test_must_use_lint_discards_with_let() {
+        check_must_use_fix(
+            r#"
+            //- /main.rs
+            use std::result::Result;
+
+            fn do_thing() -> Result<(), ()> { loop {} }
+
+            fn main() {
+                do_thing();
+            }
+            "#,
+            "unused `Result` that must be used",
+            "do_thing()",
+            "let _ = do_thing();",
+        );
+    }
+
+    #[test]
+    fn test_must_use_lint_on_functions() {
+        check_must_use_fix(
+            r#"
+            //- /main.rs
+            struct S;
+            impl S {
+                #[must_use]
+                fn answer(&self) -> u32 { 42 }
+            }
+
+            fn main() {
+                S.answer();
+            }
+            "#,
+            "unused return value of `answer` that must be used",
+            "S.answer()",
+            "let _ = S.answer();",
+        );
+    }
+
+    #[test]
+    fn test_must_use_lint_ignores_used_values() {
+        let fixture = format!(
+            "{}{}",
+            r#"
+            //- /main.rs
+            use std::result::Result;
+
+            fn do_thing() -> Result<(), ()> { loop {} }
+            fn other_thing() -> u32 { 92 }
+
+            fn main() {
+                let _ = do_thing();
+                other_thing();
+            }
+            "#,
+            MUST_USE_STD
+        );
+        let (_, diagnostics) = diagnostics_with_lint("diagnostics.warn-must-use", &fixture);
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    }
+
+    #[test]
+    fn test_must_use_lint_without_fix_in_macro_expansion() {
+        let fixture = format!(
+            "{}{}",
+            r#"
+            //- /main.rs
+            use std::result::Result;
+
+            fn do_thing() -> Result<(), ()> { loop {} }
+
+            macro_rules! call {
+                ($f:ident) => { $f() };
+            }
+
+            fn main() {
+                call!(do_thing);
+            }
+            "#,
+            MUST_USE_STD
+        );
+        let (_, diagnostics) = diagnostics_with_lint("diagnostics.warn-must-use", &fixture);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "unused `Result` that must be used");
+        assert!(diagnostics[0].fix.is_none());
+    }
+
+    #[test]
+    fn test_must_use_lint_is_off_by_default() {
+        check_no_diagnostic_for_target_file(&format!(
+            "{}{}",
+            r#"
+            //- /main.rs
+            use std::result::Result;
+
+            fn do_thing() -> Result<(), ()> { loop {} }
+
+            fn main() {
+                do_thing();<|>
+            }
+            "#,
+            MUST_USE_STD
+        ));
+    }
+
+    fn check_float_cmp_fix(main: &str, fixed: &str) {
+        let (text, mut diagnostics) = diagnostics_with_lint("diagnostics.warn-float-cmp", main);
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = diagnostics.pop().unwrap();
+        assert_eq!(diagnostic.message, "strict comparison of floating-point values");
+        let mut fix = diagnostic.fix.unwrap();
+        let edit = fix.source_file_edits.pop().unwrap().edit;
+        let discarded =
+            &text[diagnostic.range.start().to_usize()..diagnostic.range.end().to_usize()];
+        assert_eq_text!(&text.replace(discarded, fixed), &edit.apply(&text));
+    }
+
+    #[test]
+    fn test_float_cmp_lint() {
+        check_float_cmp_fix(
+            r#"
+            //- /main.rs
+            fn foo(x: f64) -> bool {
+                x == 1.0
+            }
+            "#,
+            "(x - 1.0).abs() < f64::EPSILON",
+        );
+        check_float_cmp_fix(
+            r#"
+            //- /main.rs
+            fn foo(x: f32, y: f32) -> bool {
+                x * 2.0 != y
+            }
+            "#,
+            "((x * 2.0) - y).abs() >= f32::EPSILON",
+        );
+        check_float_cmp_fix(
+            r#"
+            //- /main.rs
+            fn foo(a: f64, b: f64, c: f64) -> bool {
+                a == b + c
+            }
+            "#,
+            "(a - (b + c)).abs() < f64::EPSILON",
+        );
+    }
+
+    #[test]
+    fn test_float_cmp_lint_without_fix_in_macro_expansion() {
+        let (_, diagnostics) = diagnostics_with_lint(
+            "diagnostics.warn-float-cmp",
+            r#"
+            //- /main.rs
+            macro_rules! same {
+                ($a:expr, $b:expr) => { $a == $b };
+            }
+
+            fn foo(x: f64) -> bool {
+                same!(x, 1.0)
+            }
+            "#,
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "strict comparison of floating-point values");
+        assert!(diagnostics[0].fix.is_none());
+    }
+
+    #[test]
+    fn test_float_cmp_lint_ignores_other_comparisons() {
+        let (_, diagnostics) = diagnostics_with_lint(
+            "diagnostics.warn-float-cmp",
+            r#"
+            //- /main.rs
+            fn foo(x: f64, n: i32) -> bool {
+                n == 1 && x < 1.0
+            }
+            "#,
+        );
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    }
+
+    #[test]
+    fn test_float_cmp_lint_is_off_by_default() {
+        check_no_diagnostic(
+            r"
+            fn foo(x: f64) -> bool {
+                x == 1.0
+            }
+            ",
+        );
+    }
+
+    #[test]
+    fn test_redundant_cast() {
+        // the lint is on by default, unlike the one for truncating casts
+        let text = r#"
+            fn foo(x: i32, y: u8) -> i64 {
+                let a = (x + 1) as i32;
+                let b = y as u32 + 1 as u32;
+                let c = x as u8;
+                a as i64
+            }
+        "#;
+        let (analysis, file_id) = single_file(text);
+        let mut diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = diagnostics.pop().unwrap();
+        assert_eq!(diagnostic.message, "casting `i32` to the same type is unnecessary");
+        let mut fix = diagnostic.fix.unwrap();
+        let edit = fix.source_file_edits.pop().unwrap().edit;
+        assert_eq_text!(&text.replace("(x + 1) as i32", "(x + 1)"), &edit.apply(text));
+    }
+
+    #[test]
+    fn test_redundant_cast_in_macro_call() {
+        let text = r#"
+            macro_rules! id { ($($tt:tt)*) => { $($tt)* }; }
+            fn foo(x: i32) -> i32 {
+                id!((x + 1) as i32)
+            }
+        "#;
+        let (analysis, file_id) = single_file(text);
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.message, "casting `i32` to the same type is unnecessary");
+        let range = diagnostic.range;
+        assert_eq!(&text[range.start().to_usize()..range.end().to_usize()], "(x + 1) as i32");
+        assert!(diagnostic.fix.is_none());
+    }
+
+    #[test]
+    fn test_truncating_cast_lint_in_macro_call() {
+        let (text, diagnostics) = diagnostics_with_lint(
+            "diagnostics.warn-truncating-cast",
+            r#"
+            //- /main.rs
+            macro_rules! id { ($($tt:tt)*) => { $($tt)* }; }
+            fn foo(x: u32) -> u8 {
+                id!(x as u8)
+            }
+            "#,
+        );
+        assert_eq!(diagnostics.len(), 1);
+        let range = diagnostics[0].range;
+        assert_eq!(&text[range.start().to_usize()..range.end().to_usize()], "x as u8");
+    }
+
+    #[test]
+    fn test_truncating_cast_lint() {
+        let (text, diagnostics) = diagnostics_with_lint(
+            "diagnostics.warn-truncating-cast",
+            r#"
+            //- /main.rs
+            fn foo(x: u32, y: i64, z: usize) {
+                let a = x as u8;
+                let b = y as i32;
+                let c = x as u64;
+                let d = x as i32;
+                let e = z as u8;
+                let f = 300 as u8;
+            }
+            "#,
+        );
+        let ranges: Vec<(&str, &str)> = diagnostics
+            .iter()
+            .map(|d| (&text[d.range.start().to_usize()..d.range.end().to_usize()], &*d.message))
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![
+                (
+                    "x as u8",
+                    "casting `u32` to `u8` may truncate the value, consider using `try_into`"
+                ),
+                (
+                    "y as i32",
+                    "casting `i64` to `i32` may truncate the value, consider using `try_into`"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_truncating_cast_lint_on_enums_with_repr() {
+        let (text, diagnostics) = diagnostics_with_lint(
+            "diagnostics.warn-truncating-cast",
+            r#"
+            //- /main.rs
+            #[repr(u16)]
+            enum Wide { A = 300 }
+            #[repr(u8)]
+            enum Narrow { A }
+            enum Plain { A }
+            fn foo() {
+                let a = Wide::A as u8;
+                let b = Narrow::A as u8;
+                let c = Plain::A as u8;
+            }
+            "#,
+        );
+        let ranges: Vec<(&str, &str)> = diagnostics
+            .iter()
+            .map(|d| (&text[d.range.start().to_usize()..d.range.end().to_usize()], &*d.message))
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![(
+                "Wide::A as u8",
+                "casting `Wide` to `u8` may truncate the value, consider using `try_into`"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_truncating_cast_lint_is_off_by_default() {
+        check_no_diagnostic(
+            r"
+            fn foo(x: u32) -> u8 {
+                x as u8
+            }
+            ",
+        );
+    }
+
+    #[test]
+    fn test_no_op_lint() {
+        let (text, diagnostics) = diagnostics_with_lint(
+            "diagnostics.warn-no-op",
+            r#"
+            //- /main.rs
+            struct S { x: u32 }
+            impl S {
+                fn set(&mut self, x: u32) {
+                    self.x = self.x;
+                    let mut y = x;
+                    y = y;
+                    y += 0;
+                    y *= 1;
+                    y -= 1;
+                    self.x = x;
+                }
+            }
+            "#,
+        );
+        let ranges: Vec<(&str, &str)> = diagnostics
+            .iter()
+            .map(|d| (&text[d.range.start().to_usize()..d.range.end().to_usize()], &*d.message))
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![
+                ("self.x = self.x", "self-assignment has no effect"),
+                ("y = y", "self-assignment has no effect"),
+                ("y += 0", "operation has no effect"),
+                ("y *= 1", "operation has no effect"),
+            ]
+        );
+        assert!(diagnostics.iter().all(|d| d.fix.is_none()));
+    }
+
+    #[test]
+    fn test_no_op_lint_fixes_duplicate_operands() {
+        let (text, mut diagnostics) = diagnostics_with_lint(
+            "diagnostics.warn-no-op",
+            r#"
+            //- /main.rs
+            fn foo(x: bool, y: bool) -> bool {
+                x && x || x && y
+            }
+            "#,
+        );
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = diagnostics.pop().unwrap();
+        assert_eq!(diagnostic.message, "operation has no effect");
+        let mut fix = diagnostic.fix.unwrap();
+        let edit = fix.source_file_edits.pop().unwrap().edit;
+        assert_eq_text!(&text.replace("x && x ||", "x ||"), &edit.apply(&text));
+    }
+
+    #[test]
+    fn test_no_op_lint_in_macro_call() {
+        let (text, diagnostics) = diagnostics_with_lint(
+            "diagnostics.warn-no-op",
+            r#"
+            //- /main.rs
+            macro_rules! id { ($($tt:tt)*) => { $($tt)* }; }
+            fn foo(x: bool) -> bool {
+                id!(x && x)
+            }
+            "#,
+        );
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.message, "operation has no effect");
+        let range = diagnostic.range;
+        assert_eq!(&text[range.start().to_usize()..range.end().to_usize()], "x && x");
+        assert!(diagnostic.fix.is_none());
+    }
+
+    #[test]
+    fn test_no_op_lint_ignores_calls() {
+        let (_, diagnostics) = diagnostics_with_lint(
+            "diagnostics.warn-no-op",
+            r#"
+            //- /main.rs
+            fn next() -> bool { true }
+            fn foo(a: &mut [u32; 2]) -> bool {
+                a[0] = a[0];
+                next() && next()
+            }
+            "#,
+        );
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    }
+
+    #[test]
+    fn test_no_op_lint_is_off_by_default() {
+        check_no_diagnostic(
+            r"
+            fn foo(mut x: u32) {
+                x = x;
+            }
+            ",
+        );
+    }
+
+    const COLLECTIONS_STD: &str = r#"
+            //- /std/lib.rs
+            pub mod vec {
+                pub struct Vec<T> { len: usize }
+                impl<T> Vec<T> {
+                    pub fn new() -> Vec<T> { loop {} }
+                    pub fn with_capacity(capacity: usize) -> Vec<T> { loop {} }
+                    pub fn push(&mut self, value: T) {}
+                }
+            }
+            pub mod string {
+                pub struct String { len: usize }
+                impl String {
+                    pub fn new() -> String { loop {} }
+                }
+            }
+    "#;
+
+    #[test]
+    fn test_unused_collection_lint() {
+        let fixture = format!(
+            "{}{}",
+            r#"
+            //- /main.rs
+            use std::{string::String, vec::Vec};
+
+            fn make() -> Vec<u8> { loop {} }
+
+            fn foo() {
+                let mut unused = Vec::<u8>::with_capacity(16);
+                let mut used = Vec::new();
+                used.push(1);
+                let computed = make();
+                let _ignored = String::new();
+                String::new();
+                make();
+                let s = String::new();
+                consume(s)
+            }
+            "#,
+            COLLECTIONS_STD
+        );
+        let (text, diagnostics) =
+            diagnostics_with_lint("diagnostics.warn-unused-collection", &fixture);
+        let ranges: Vec<(&str, &str, bool)> = diagnostics
+            .iter()
+            .map(|d| {
+                let range = &text[d.range.start().to_usize()..d.range.end().to_usize()];
+                (range, &*d.message, d.fix.is_some())
+            })
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![
+                (
+                    "let mut unused = Vec::<u8>::with_capacity(16);",
+                    "`unused` is a `Vec` which is never used",
+                    true
+                ),
+                ("let computed = make();", "`computed` is a `Vec` which is never used", false),
+                ("String::new();", "this `String` is dropped right after being created", true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unused_collection_lint_removes_the_statement() {
+        let fixture = format!(
+            "{}{}",
+            r#"
+            //- /main.rs
+            use std::vec::Vec;
+
+            fn foo() {
+                let v = Vec::<u32>::new();
+                bar();
+            }
+            "#,
+            COLLECTIONS_STD
+        );
+        let (text, mut diagnostics) =
+            diagnostics_with_lint("diagnostics.warn-unused-collection", &fixture);
+        assert_eq!(diagnostics.len(), 1);
+        let mut fix = diagnostics.pop().unwrap().fix.unwrap();
+        let edit = fix.source_file_edits.pop().unwrap().edit;
+        assert_eq_text!(&text.replace("\n    let v = Vec::<u32>::new();", ""), &edit.apply(&text));
+    }
+
+    #[test]
+    fn test_unused_collection_lint_in_macro_call() {
+        let fixture = format!(
+            "{}{}",
+            r#"
+            //- /main.rs
+            use std::vec::Vec;
+
+            macro_rules! id { ($($tt:tt)*) => { $($tt)* }; }
+
+            fn foo() {
+                let v = id!(Vec::<u32>::new());
+            }
+            "#,
+            COLLECTIONS_STD
+        );
+        let (text, diagnostics) =
+            diagnostics_with_lint("diagnostics.warn-unused-collection", &fixture);
+        assert_eq!(diagnostics.len(), 1);
+        let range = diagnostics[0].range;
+        assert_eq!(&text[range.start().to_usize()..range.end().to_usize()], "Vec::<u32>::new()");
+        assert!(diagnostics[0].fix.is_none());
+    }
+
+    #[test]
+    fn test_unused_collection_lint_is_off_by_default() {
+        check_no_diagnostic_for_target_file(&format!(
+            "{}{}",
+            r#"
+            //- /main.rs
+            use std::vec::Vec;
+
+            fn foo() {
+                let v = Vec::<u32>::new();<|>
+            }
+            "#,
+            COLLECTIONS_STD
+        ));
+    }
+
+    #[test]
+    fn test_shadowing_lint() {
+        let (text, diagnostics) = diagnostics_with_lint(
+            "diagnostics.warn-shadowing",
+            r#"
+            //- /main.rs
+            fn foo(x: u32) {
+                let a = 1;
+                let (b, _c) = (2, 3);
+                let a = 4;
+                let b = b + 1;
+                let _c = 5;
+                {
+                    let a = 6;
+                }
+                let x = 7;
+            }
+            "#,
+        );
+        let ranges: Vec<(&str, &str)> = diagnostics
+            .iter()
+            .map(|d| (&text[d.range.start().to_usize()..d.range.end().to_usize()], &*d.message))
+            .collect();
+        assert_eq!(ranges, vec![("a", "`a` shadows a binding of the same name in this block")]);
+        assert!(diagnostics.iter().all(|d| match d.severity {
+            Severity::WeakWarning => true,
+            Severity::Error => false,
+        }));
+    }
+
+    #[test]
+    fn test_shadowing_lint_in_macro_call() {
+        let (text, diagnostics) = diagnostics_with_lint(
+            "diagnostics.warn-shadowing",
+            r#"
+            //- /main.rs
+            macro_rules! id { ($($tt:tt)*) => { $($tt)* }; }
+            fn foo() -> u32 {
+                id!({ let b = 1; let b = 2; b })
+            }
+            "#,
+        );
+        assert_eq!(diagnostics.len(), 1);
+        let range = diagnostics[0].range;
+        assert_eq!(range.start().to_usize(), text.find("let b = 2").unwrap() + "let ".len());
+        assert_eq!(&text[range.start().to_usize()..range.end().to_usize()], "b");
+    }
+
+    #[test]
+    fn test_shadowing_lint_is_off_by_default() {
+        check_no_diagnostic(
+            r"
+            fn foo() {
+                let a = 1;
+                let a = 2;
+            }
+            ",
+        );
+    }
+
+    fn check_redundant_pattern_matching_fix(main: &str, message: &str, fixed: &str) {
+        let fixture = format!("{}{}", main, UNWRAP_STD);
+        let (text, mut diagnostics) =
+            diagnostics_with_lint("diagnostics.warn-redundant-pattern-matching", &fixture);
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = diagnostics.pop().unwrap();
+        assert_eq!(diagnostic.message, message);
+        let mut fix = diagnostic.fix.unwrap();
+        let edit = fix.source_file_edits.pop().unwrap().edit;
+        let condition =
+            &text[diagnostic.range.start().to_usize()..diagnostic.range.end().to_usize()];
+        assert_eq_text!(&text.replace(condition, fixed), &edit.apply(&text));
+    }
+
+    #[test]
+    fn test_redundant_pattern_matching_lint() {
+        check_redundant_pattern_matching_fix(
+            r#"
+            //- /main.rs
+            use std::option::Option::{self, Some};
+            fn foo(x: Option<u32>) {
+                if let Some(_) = x {}
+            }
+            "#,
+            "redundant pattern matching, consider using `is_some()`",
+            "x.is_some()",
+        );
+        check_redundant_pattern_matching_fix(
+            r#"
+            //- /main.rs
+            use std::option::Option;
+            fn foo(x: Option<u32>) -> bool {
+                if let Option::None = x { true } else { false }
+            }
+            "#,
+            "redundant pattern matching, consider using `is_none()`",
+            "x.is_none()",
+        );
+        check_redundant_pattern_matching_fix(
+            r#"
+            //- /main.rs
+            use std::result::Result::{self, Err};
+            fn foo(x: &Result<u32, ()>) {
+                if let Err(_) = *x {}
+            }
+            "#,
+            "redundant pattern matching, consider using `is_err()`",
+            "(*x).is_err()",
+        );
+    }
+
+    #[test]
+    fn test_redundant_pattern_matching_lint_ignores_bindings() {
+        let fixture = format!(
+            "{}{}",
+            r#"
+            //- /main.rs
+            use std::option::Option::{self, Some};
+            fn foo(x: Option<u32>) -> u32 {
+                if let Some(y) = x { y } else { 0 }
+            }
+            fn bar(x: Option<u32>) {
+                match x {
+                    Some(_) => {}
+                    _ => {}
+                }
+            }
+            "#,
+            UNWRAP_STD
+        );
+        let (_, diagnostics) =
+            diagnostics_with_lint("diagnostics.warn-redundant-pattern-matching", &fixture);
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    }
+
+    #[test]
+    fn test_redundant_pattern_matching_lint_without_fix_in_macro_expansion() {
+        let fixture = format!(
+            "{}{}",
+            r#"
+            //- /main.rs
+            use std::option::Option::{self, Some};
+            macro_rules! when_some {
+                ($e:expr) => { if let Some(_) = $e {} };
+            }
+            fn foo(x: Option<u32>) {
+                when_some!(x);
+            }
+            "#,
+            UNWRAP_STD
+        );
+        let (_, diagnostics) =
+            diagnostics_with_lint("diagnostics.warn-redundant-pattern-matching", &fixture);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "redundant pattern matching, consider using `is_some()`"
+        );
+        assert!(diagnostics[0].fix.is_none());
+    }
+
+    const COPY_STD: &str = r#"
+            //- /std/lib.rs
+            pub mod marker {
+                pub trait Copy {}
+            }
+            pub mod boxed {
+                pub struct Box<T>(T);
+            }
+    "#;
+
+    #[test]
+    fn test_move_out_of_borrow() {
+        let before = format!(
+            r#"
+            //- /main.rs
+            struct Data;
+            struct Point {{ x: i32, data: Data }}
+
+            fn f(point: &Point) {{
+                let &Point {{ x, data<|> }} = point;
+            }}
+            {}"#,
+            COPY_STD
+        );
+        let after = r#"
+            struct Data;
+            struct Point { x: i32, data: Data }
+
+            fn f(point: &Point) {
+                let &Point { x, ref data } = point;
+            }
+        "#;
+        check_apply_diagnostic_fix_from_position(&before, after);
+    }
+
+    #[test]
+    fn test_move_out_of_dereferenced_borrow() {
+        let before = format!(
+            r#"
+            //- /main.rs
+            struct Data;
+            enum E {{ A(Data), B }}
+
+            fn f(e: &E) {{
+                match *e {{
+                    E::A(<|>data) => {{}}
+                    E::B => {{}}
+                }}
+            }}
+            {}"#,
+            COPY_STD
+        );
+        let after = r#"
+            struct Data;
+            enum E { A(Data), B }
+
+            fn f(e: &E) {
+                match *e {
+                    E::A(ref data) => {}
+                    E::B => {}
+                }
+            }
+        "#;
+        check_apply_diagnostic_fix_from_position(&before, after);
+    }
+
+    #[test]
+    fn test_move_out_of_borrow_in_macro_call() {
+        let fixture = format!(
+            r#"
+            //- /main.rs
+            macro_rules! id {{ ($($tt:tt)*) => {{ $($tt)* }}; }}
+            struct Data;
+            struct Point {{ x: i32, data: Data }}
+
+            fn f(point: &Point) {{
+                id!(match point {{ &Point {{ x, data }} => () }})
+            }}
+            {}"#,
+            COPY_STD
+        );
+        let mock = MockAnalysis::with_files(&fixture);
+        let file_id = mock.id_of("/main.rs");
+        let analysis = mock.analysis();
+        let text = analysis.file_text(file_id).unwrap();
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        let range = diagnostics[0].range;
+        assert_eq!(&text[range.start().to_usize()..range.end().to_usize()], "data");
+        assert!(diagnostics[0].fix.is_none());
+    }
+
+    #[test]
+    fn test_move_out_of_borrow_ignores_copy_and_owned_values() {
+        check_no_diagnostic_for_target_file(&format!(
+            r#"
+            //- /main.rs
+            use std::{{boxed::Box, marker::Copy}};
+
+            #[derive(Copy)]
+            struct Id;
+            struct Data;
+            struct Point {{ id: Id, data: Data }}
+
+            fn f(point: &Point, boxed: Box<Point><|>) {{
+                let &Point {{ id, ref data }} = point;
+                let Point {{ id, data }} = point;
+                let Point {{ id, data }} = *boxed;
+            }}
+            {}"#,
+            COPY_STD
+        ));
+    }
+
+    #[test]
+    fn test_wrap_return_type() {
+        let before = r#"
+            //- /main.rs
+            use std::{string::String, result::Result::{self, Ok, Err}};
+
+            fn div(x: i32, y: i32) -> Result<i32, String> {
+                if y == 0 {
+                    return Err("div by zero".into());
+                }
+                x / y<|>
+            }
+
+            //- /std/lib.rs
+            pub mod string {
+                pub struct String { }
+            }
+            pub mod result {
+                pub enum Result<T, E> { Ok(T), Err(E) }
+            }
+        "#;
+        let after = r#"
+            use std::{string::String, result::Result::{self, Ok, Err}};
+
+            fn div(x: i32, y: i32) -> Result<i32, String> {
+                if y == 0 {
+                    return Err("div by zero".into());
+                }
+                Ok(x / y)
+            }
+        "#;
+        check_apply_diagnostic_fix_from_position(before, after);
+    }
+
+    #[test]
+    fn test_wrap_return_type_of_return_expr() {
+        let before = r#"
+            //- /main.rs
+            use std::{string::String, result::Result::{self, Ok, Err}};
+
+            fn div(x: i32, y: i32) -> Result<i32, String> {
+                if y == 1 {
+                    return <|>x;
+                }
+                Ok(x / y)
+            }
+
+            //- /std/lib.rs
+            pub mod string {
+                pub struct String { }
+            }
+            pub mod result {
+                pub enum Result<T, E> { Ok(T), Err(E) }
+            }
+        "#;
+        let after = r#"
+            use std::{string::String, result::Result::{self, Ok, Err}};
+
+            fn div(x: i32, y: i32) -> Result<i32, String> {
+                if y == 1 {
+                    return Ok(x);
                 }
                 Ok(x / y)
             }
@@ -521,6 +2272,314 @@ mod tests {
         check_no_diagnostic(content);
     }
 
+    #[test]
+    fn test_duplicate_impl_items_diagnostic() {
+        let (analysis, file_id) = single_file(
+            r"
+struct S;
+impl S {
+    fn foo() {}
+    fn foo() {}
+    type T = ();
+    const T: u32 = 0;
+}
+",
+        );
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert_debug_snapshot!(diagnostics, @r###"
+        [
+            Diagnostic {
+                message: "duplicate definitions with name `foo`, first defined on line 4",
+                range: [43; 46),
+                fix: None,
+                severity: Error,
+            },
+        ]
+        "###);
+    }
+
+    #[test]
+    fn test_trait_method_mismatch_diagnostic() {
+        let (analysis, file_id) = single_file(
+            r"
+trait Tr {
+    type Item;
+    fn new() -> Self;
+    fn get(&self) -> Wrap<Self::Item>;
+    fn len(&self) -> usize;
+    fn clear(&mut self);
+    fn set(&mut self, i: usize, value: u8);
+}
+struct Wrap<T>(T);
+struct S;
+impl Tr for S {
+    type Item = u8;
+    fn new() -> S { S }
+    fn get(&self) -> Wrap<u8> { loop {} }
+    fn len(&self) -> u32 { 0 }
+    fn clear() {}
+    fn set(&mut self, value: u8) {}
+}
+",
+        );
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert_debug_snapshot!(diagnostics, @r###"
+        [
+            Diagnostic {
+                message: "method `len` has an incompatible return type for trait `Tr`: expected `usize`, found `u32`",
+                range: [322; 342),
+                fix: None,
+                severity: Error,
+            },
+            Diagnostic {
+                message: "method `clear` has a `self` parameter in trait `Tr`, but not in the impl",
+                range: [353; 363),
+                fix: None,
+                severity: Error,
+            },
+            Diagnostic {
+                message: "method `set` has 2 parameters but the declaration in trait `Tr` has 3",
+                range: [371; 399),
+                fix: None,
+                severity: Error,
+            },
+        ]
+        "###);
+    }
+
+    #[test]
+    fn test_missing_super_trait_impls_diagnostic() {
+        let (analysis, file_id) = single_file(
+            r"
+trait Super {}
+trait Other {}
+trait Sub: Super + Other {}
+struct A;
+struct B;
+impl Super for A {}
+impl Sub for A {}
+impl Sub for B {}
+struct W<T>(T);
+impl<T: Super> Super for W<T> {}
+impl<T: Super> Other for W<T> {}
+impl<T: Super> Sub for W<T> {}
+",
+        );
+        let mut diagnostics = analysis.diagnostics(file_id).unwrap();
+        diagnostics.sort_by_key(|it| it.range.start());
+        assert_debug_snapshot!(diagnostics, @r###"
+        [
+            Diagnostic {
+                message: "the trait `Other` is not implemented for `A`, but is required by `Sub`",
+                range: [104; 107),
+                fix: None,
+                severity: Error,
+            },
+            Diagnostic {
+                message: "the traits `Super`, `Other` are not implemented for `B`, but are required by `Sub`",
+                range: [122; 125),
+                fix: None,
+                severity: Error,
+            },
+        ]
+        "###);
+    }
+
+    #[test]
+    fn test_recursive_type_diagnostic() {
+        let (analysis, file_id) = single_file(
+            r"
+struct Box<T>(*const T);
+struct Node { value: u32, next: Node }
+struct List { head: Option<(u32, List)> }
+enum Tree { Leaf, Fork([Wrap<Tree>; 2]) }
+struct Wrap<T>(T);
+struct Boxed { next: Box<Boxed>, prev: &'static Boxed }
+enum Option<T> { None, Some(T) }
+",
+        );
+        let mut diagnostics = analysis.diagnostics(file_id).unwrap();
+        diagnostics.sort_by_key(|it| it.range.start());
+        assert_debug_snapshot!(diagnostics, @r###"
+        [
+            Diagnostic {
+                message: "recursive type `Node` has infinite size, insert some indirection like `Box` to make it representable",
+                range: [52; 62),
+                fix: None,
+                severity: Error,
+            },
+            Diagnostic {
+                message: "recursive type `List` has infinite size, insert some indirection like `Box` to make it representable",
+                range: [79; 104),
+                fix: None,
+                severity: Error,
+            },
+            Diagnostic {
+                message: "recursive type `Tree` has infinite size, insert some indirection like `Box` to make it representable",
+                range: [130; 145),
+                fix: None,
+                severity: Error,
+            },
+        ]
+        "###);
+    }
+
+    #[test]
+    fn test_ambiguous_glob_import_diagnostic() {
+        let (analysis, file_id) = single_file(
+            r"
+mod foo { pub fn bar() {} }
+mod baz { pub fn bar() {} }
+use foo::*;
+use baz::*;
+fn main() { bar(); }
+",
+        );
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert_debug_snapshot!(diagnostics, @r###"
+        [
+            Diagnostic {
+                message: "`bar` is ambiguous: it is brought into scope by several glob imports",
+                range: [93; 96),
+                fix: None,
+                severity: Error,
+            },
+        ]
+        "###);
+    }
+
+    #[test]
+    fn test_ambiguous_glob_import_diagnostic_in_types_patterns_and_qualified_paths() {
+        let code = r"
+mod foo { pub struct Bar; pub struct Pair(pub u32); }
+mod baz { pub struct Bar; pub struct Pair(pub u32); }
+use foo::*;
+use baz::*;
+fn f(p: Pair) -> Option<Bar> { let Pair(x) = p; Bar::new() }
+fn g<Bar>(x: Bar) {}
+";
+        let (analysis, file_id) = single_file(code);
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert!(diagnostics.iter().all(|it| it.message.contains("is ambiguous")));
+        let paths: Vec<&str> = diagnostics
+            .iter()
+            .map(|it| &code[it.range.start().to_usize()..it.range.end().to_usize()])
+            .collect();
+        assert_eq!(paths, vec!["Bar::new", "Pair", "Pair", "Bar"]);
+    }
+
+    #[test]
+    fn test_crate_diagnostics_covers_module_files() {
+        let mock = MockAnalysis::with_files(
+            r"
+            //- /lib.rs
+            mod foo;
+            mod bar {
+                mod baz;
+            }
+            fn f() { let x = ; }
+            //- /foo.rs
+            fn g( {}
+            //- /bar/baz.rs
+            struct S
+            //- /unused.rs
+            fn h( {}
+            ",
+        );
+        let lib = mock.id_of("/lib.rs");
+        let foo = mock.id_of("/foo.rs");
+        let baz = mock.id_of("/bar/baz.rs");
+        let analysis = mock.analysis();
+        let krate = analysis.crate_for(lib).unwrap()[0];
+        let mut diagnostics = analysis.crate_diagnostics(krate).unwrap();
+        diagnostics.sort_by_key(|(file_id, _)| *file_id);
+
+        let files: Vec<_> = diagnostics.iter().map(|(file_id, _)| *file_id).collect();
+        assert_eq!(files, vec![lib, foo, baz]);
+        for (file_id, file_diagnostics) in diagnostics {
+            assert!(!file_diagnostics.is_empty());
+            assert_eq!(
+                format!("{:?}", file_diagnostics),
+                format!("{:?}", analysis.diagnostics(file_id).unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn test_mismatched_variant_path_diagnostic() {
+        let (analysis, file_id) = single_file(
+            r"
+enum Option<T> { None, Some(T) }
+enum Result<T, E> { Ok(T), Err(E) }
+fn f(x: &Option<u32>) {
+    match x {
+        Result::Ok(n) => (),
+        Result::Some { .. } => (),
+        Option::Foo => (),
+        Option::None | Option::Some(_) => (),
+    }
+}
+",
+        );
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert_debug_snapshot!(diagnostics, @r###"
+        [
+            Diagnostic {
+                message: "expected a variant of `Option`, found `Result::Ok`",
+                range: [116; 126),
+                fix: None,
+                severity: Error,
+            },
+            Diagnostic {
+                message: "no variant `Some` in `Result`, expected a variant of `Option`",
+                range: [145; 157),
+                fix: None,
+                severity: Error,
+            },
+            Diagnostic {
+                message: "no variant `Foo` in `Option`",
+                range: [180; 191),
+                fix: None,
+                severity: Error,
+            },
+        ]
+        "###);
+    }
+
+    #[test]
+    fn test_no_mismatched_variant_path_diagnostic_for_consts() {
+        check_no_diagnostic(
+            r"
+            enum E { A, B }
+            impl E { const C: E = E::A; }
+            const D: E = E::B;
+            mod m { pub const F: super::E = super::E::B; }
+            fn f(e: E) {
+                match e {
+                    E::C => (),
+                    D => (),
+                    m::F => (),
+                    _ => (),
+                }
+            }
+            ",
+        );
+    }
+
+    #[test]
+    fn test_explicit_import_resolves_glob_ambiguity() {
+        check_no_diagnostic(
+            r"
+mod foo { pub fn bar() {} }
+mod baz { pub fn bar() {} }
+use foo::*;
+use baz::*;
+use baz::bar;
+fn main() { bar(); }
+",
+        );
+    }
+
     #[test]
     fn test_unresolved_module_diagnostic() {
         let (analysis, file_id) = single_file("mod foo;");
@@ -570,6 +2629,166 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bare_trait_object() {
+        check_apply_diagnostic_fix(
+            "trait Tr {}\nfn f(x: &Tr, y: Vec<u8>) {}",
+            "trait Tr {}\nfn f(x: &dyn Tr, y: Vec<u8>) {}",
+        );
+    }
+
+    #[test]
+    fn test_bare_trait_object_ignores_bounds_and_impls() {
+        check_no_diagnostic(
+            r"
+            trait Tr {}
+            struct S;
+            impl Tr for S {}
+            fn f<T: Tr>(x: &dyn Tr, y: &impl Tr, s: S) where T: Tr {}
+            ",
+        );
+    }
+
+    #[test]
+    fn test_bare_trait_object_ignores_qualified_paths_and_type_params() {
+        check_no_diagnostic(
+            r"
+            trait Tr { type Assoc; }
+            struct S;
+            impl Tr for S { type Assoc = u32; }
+            fn f<T: Tr>(x: <S as Tr>::Assoc, y: <T as Tr>::Assoc, z: T, n: u32) {}
+            ",
+        );
+    }
+
+    #[test]
+    fn test_bare_trait_object_is_allowed_in_edition_2015() {
+        let mut mock = MockAnalysis::new();
+        let file_id = mock.add_file("/main.rs", "trait Tr {}\nfn f(x: &Tr) {}");
+        let mut host = mock.analysis_host();
+        let mut crate_graph = CrateGraph::default();
+        crate_graph.add_crate_root(
+            file_id,
+            Edition::Edition2015,
+            CfgOptions::default(),
+            Env::default(),
+        );
+        let mut change = AnalysisChange::new();
+        change.set_crate_graph(crate_graph);
+        host.apply_change(change);
+        assert!(diagnostics(&host.db, file_id).is_empty());
+    }
+
+    #[test]
+    fn test_feature_gated_syntax() {
+        let (analysis, file_id) = single_file("fn f() { let box x = box 1; let y = try { x }; }");
+        let messages: Vec<String> =
+            analysis.diagnostics(file_id).unwrap().into_iter().map(|it| it.message).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "`box` patterns are unstable, add `#![feature(box_patterns)]` to the crate root",
+                "`box` expressions are unstable, add `#![feature(box_syntax)]` to the crate root",
+                "`try` blocks are unstable, add `#![feature(try_blocks)]` to the crate root",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_feature_gated_syntax_with_features_enabled_in_crate_root() {
+        check_no_diagnostic_for_target_file(
+            r"
+            //- /main.rs
+            #![feature(box_syntax, box_patterns)]
+            #![feature(try_blocks)]
+            mod foo;
+
+            //- /foo.rs
+            fn f() { let box x = box 1; let y = try { x }; }<|>
+            ",
+        );
+    }
+
+    #[test]
+    fn test_check_chained_comparison() {
+        check_apply(
+            "fn f(a: u32, b: u32, c: u32) -> bool { a < b < c }",
+            "fn f(a: u32, b: u32, c: u32) -> bool { a < b && b < c }",
+            check_chained_comparison,
+        );
+        check_apply(
+            "fn f() { if 0 <= i < n <= len {} }",
+            "fn f() { if 0 <= i && i < n && n <= len {} }",
+            check_chained_comparison,
+        );
+        check_not_applicable(
+            r#"
+            fn f(a: u32, b: u32, c: bool) -> bool {
+                let x = (a < b) == c;
+                let y = a < b && b < c;
+                c == (a > b)
+            }
+            "#,
+            check_chained_comparison,
+        );
+    }
+
+    #[test]
+    fn test_chained_comparison_without_fix() {
+        let parse = SourceFile::parse("fn f() { 1 < g() < 3 }");
+        let mut diagnostics = Vec::new();
+        for node in parse.tree().syntax().descendants() {
+            check_chained_comparison(&mut diagnostics, FileId(0), &node);
+        }
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].range, TextRange::from_to(9.into(), 20.into()));
+        assert!(diagnostics[0].fix.is_none());
+    }
+
+    #[test]
+    fn test_check_needless_return() {
+        check_apply(
+            r#"
+fn foo(x: u32) -> u32 {
+    let y = x + 1;
+    return y * 2;
+}
+"#,
+            r#"
+fn foo(x: u32) -> u32 {
+    let y = x + 1;
+    y * 2
+}
+"#,
+            check_needless_return,
+        );
+        check_apply(
+            "fn foo() -> u32 { return 92 }",
+            "fn foo() -> u32 { 92 }",
+            check_needless_return,
+        );
+        check_not_applicable(
+            r#"
+            fn foo(x: u32) -> u32 {
+                if x == 0 {
+                    return 1;
+                }
+                loop {
+                    return x;
+                }
+            }
+            fn bar() {
+                return;
+            }
+            fn baz() -> u32 {
+                let f = || { return 1; };
+                f()
+            }
+            "#,
+            check_needless_return,
+        );
+    }
+
     #[test]
     fn test_check_struct_shorthand_initialization() {
         check_not_applicable(
@@ -649,4 +2868,231 @@ fn main() {
             check_struct_shorthand_initialization,
         );
     }
+
+    #[test]
+    fn test_private_field_access_fix() {
+        let before = r#"
+            mod m {
+                pub struct S { x: u32 }
+                pub fn s() -> S { S { x: 0 } }
+            }
+            fn foo() {
+                let s = m::s();
+                s.x;
+            }
+        "#;
+        let after = r#"
+            mod m {
+                pub struct S { pub x: u32 }
+                pub fn s() -> S { S { x: 0 } }
+            }
+            fn foo() {
+                let s = m::s();
+                s.x;
+            }
+        "#;
+        check_apply_diagnostic_fix(before, after);
+    }
+
+    #[test]
+    fn test_private_function_call_fix() {
+        let before = r#"
+            mod m {
+                pub(self) fn f() {}
+            }
+            fn foo() {
+                m::f();
+            }
+        "#;
+        let after = r#"
+            mod m {
+                pub fn f() {}
+            }
+            fn foo() {
+                m::f();
+            }
+        "#;
+        check_apply_diagnostic_fix(before, after);
+    }
+
+    #[test]
+    fn test_private_item_diagnostic_message() {
+        let (analysis, file_id) = single_file(
+            r"
+            mod m {
+                pub struct S { x: u32 }
+                impl S { fn method(&self) {} }
+            }
+            fn foo(s: m::S) {
+                s.x;
+                s.method();
+            }
+            ",
+        );
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        let messages = diagnostics.iter().map(|d| d.message.as_str()).collect::<Vec<_>>();
+        assert_eq!(messages, vec!["field `x` is private", "function `method` is private"]);
+    }
+
+    #[test]
+    fn test_private_item_diagnostic_in_macro_call() {
+        let text = r"
+            macro_rules! id { ($($tt:tt)*) => { $($tt)* }; }
+            mod m {
+                fn f() {}
+            }
+            fn foo() {
+                id!(m::f());
+            }
+            ";
+        let (analysis, file_id) = single_file(text);
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "function `f` is private");
+        let range = diagnostics[0].range;
+        assert_eq!(&text[range.start().to_usize()..range.end().to_usize()], "f");
+    }
+
+    #[test]
+    fn test_returns_local_reference_diagnostic() {
+        let (analysis, file_id) = single_file(
+            r"
+            struct S { f: i32 }
+            fn local() -> &'static i32 {
+                let x = 0;
+                &x
+            }
+            fn field(c: bool) -> &'static i32 {
+                let s = S { f: 0 };
+                if c {
+                    return &s.f;
+                }
+                loop {}
+            }
+            ",
+        );
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        let messages = diagnostics.iter().map(|d| d.message.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                "cannot return reference to local variable `x`",
+                "cannot return reference to local variable `s`"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diagnostics_in_macro_calls_point_into_the_call() {
+        let text = r"
+            macro_rules! id { ($($tt:tt)*) => { $($tt)* }; }
+            fn local() -> &'static i32 {
+                let x = 0;
+                id!(&x)
+            }
+            ";
+        let (analysis, file_id) = single_file(text);
+        let diagnostics = analysis.diagnostics(file_id).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        let range = diagnostics[0].range;
+        assert_eq!(&text[range.start().to_usize()..range.end().to_usize()], "&x");
+    }
+
+    #[test]
+    fn test_no_returns_local_reference_diagnostic_for_non_locals() {
+        check_no_diagnostic(
+            r"
+            static G: i32 = 0;
+            struct S { f: i32 }
+            fn param(x: i32) -> &'static i32 { &x }
+            fn reference(s: &S) -> &i32 {
+                let r = s;
+                &r.f
+            }
+            fn global() -> &'static i32 { &G }
+            fn closure() -> &'static i32 {
+                let f = || {
+                    let x = 0;
+                    return &x;
+                };
+                &G
+            }
+            ",
+        );
+    }
+
+    #[test]
+    fn test_no_private_item_diagnostic_for_accessible_items() {
+        check_no_diagnostic(
+            r"
+            mod m {
+                pub struct S { x: u32, pub(crate) y: u32 }
+                fn f(s: S) -> u32 { s.x }
+                pub(super) fn g() {}
+                pub mod n {
+                    fn h(s: super::S) -> u32 { s.x }
+                    pub(in crate::m) fn i() {}
+                }
+                fn j() { n::i() }
+            }
+            fn foo(s: m::S) -> u32 {
+                m::g();
+                s.y
+            }
+            ",
+        );
+    }
+
+    #[test]
+    fn test_wrong_type_arg_count() {
+        let (analysis, file_id) = single_file(
+            r"
+            struct Vec<T>(T);
+            struct HashMap<K, V, S = ()>(K, V, S);
+            type Ref<'a, T> = &'a T;
+            fn foo(
+                a: Vec<i32, i32>,
+                b: HashMap<u32>,
+                c: HashMap<u32, u32, (), ()>,
+                d: Ref<'static, 'static, u32>,
+            ) {}
+            ",
+        );
+        let text = analysis.file_text(file_id).unwrap();
+        let diagnostics: Vec<(&str, String)> = analysis
+            .diagnostics(file_id)
+            .unwrap()
+            .into_iter()
+            .map(|d| (&text[d.range.start().to_usize()..d.range.end().to_usize()], d.message))
+            .collect();
+        assert_eq!(
+            diagnostics,
+            vec![
+                ("<i32, i32>", "wrong number of type arguments: expected 1, found 2".to_string()),
+                (
+                    "<u32>",
+                    "wrong number of type arguments: expected at least 2, found 1".to_string()
+                ),
+                (
+                    "<u32, u32, (), ()>",
+                    "wrong number of type arguments: expected at most 3, found 4".to_string()
+                ),
+                (
+                    "<'static, 'static, u32>",
+                    "wrong number of lifetime arguments: expected 1, found 2".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_type_arg_count_diagnostic_for_defaults_and_elided_lifetimes() {
+        check_no_diagnostic(
+            r"
+            struct HashMap<K, V, S = ()>(K, V, S);
+            struct Wrapper<'a, T>(&'a T);
+            fn foo(a: HashMap<u32, u32>, b: HashMap<u32, u32, ()>, c: &Wrapper<u32>) {}
+            ",
+        );
+    }
 }
@@ -9,6 +9,7 @@ mod short_label;
 use ra_syntax::{
     ast::{self, AstNode, AttrsOwner, NameOwner, TypeParamsOwner},
     SyntaxKind::{ATTR, COMMENT},
+    T,
 };
 
 pub use function_signature::FunctionSignature;
@@ -67,6 +68,42 @@ pub(crate) fn macro_label(node: &ast::MacroCall) -> String {
     format!("{}macro_rules! {}", vis, name)
 }
 
+/// The label of a `macro_rules!` definition followed by the matchers of its
+/// rules, which show the syntax the macro expects. The transcribers are elided.
+pub(crate) fn macro_rules_label(node: &ast::MacroCall) -> String {
+    let matchers = macro_rule_matchers(node);
+    if matchers.is_empty() {
+        return macro_label(node);
+    }
+    let rules: String =
+        matchers.iter().map(|it| format!("    {} => {{ ... }};\n", it.syntax())).collect();
+    format!("{} {{\n{}}}", macro_label(node), rules)
+}
+
+/// The matchers of the rules of a `macro_rules!` definition, like `($x:expr)`.
+pub(crate) fn macro_rule_matchers(node: &ast::MacroCall) -> Vec<ast::TokenTree> {
+    let body = match node.token_tree() {
+        Some(it) => it,
+        None => return Vec::new(),
+    };
+    let mut children =
+        body.syntax().children_with_tokens().filter(|it| !it.kind().is_trivia()).peekable();
+    let mut res = Vec::new();
+    while let Some(child) = children.next() {
+        let tt = match child.into_node().and_then(ast::TokenTree::cast) {
+            Some(it) => it,
+            None => continue,
+        };
+        // `=>` is made of two tokens in token trees
+        let is_matcher =
+            children.peek().map_or(false, |it| it.kind() == T![=] || it.kind() == T![=>]);
+        if is_matcher {
+            res.push(tt);
+        }
+    }
+    res
+}
+
 pub(crate) fn rust_code_markup<CODE: AsRef<str>>(val: CODE) -> String {
     rust_code_markup_with_doc::<_, &str>(val, None)
 }
@@ -0,0 +1,272 @@
+//! Resolves intra-doc links, like `[Foo]` or `[method](Foo::method)`, in doc
+//! comments.
+
+use hir::{Adt, AssocItem, InFile, ModuleDef, PathResolution, SourceAnalyzer};
+use ra_db::SourceDatabase;
+use ra_syntax::{
+    ast::{self, AstNode},
+    SyntaxKind::COMMENT,
+    SyntaxToken, TextRange, TextUnit,
+};
+
+use crate::{db::RootDatabase, display::ToNav, FilePosition, NavigationTarget, RangeInfo};
+
+pub(crate) fn doc_link(
+    db: &RootDatabase,
+    position: FilePosition,
+) -> Option<RangeInfo<Vec<NavigationTarget>>> {
+    let file = db.parse(position.file_id).tree();
+    let token = file.syntax().token_at_offset(position.offset).find(|it| it.kind() == COMMENT)?;
+    let comment = ast::Comment::cast(token.clone())?;
+    comment.kind().doc?;
+
+    let (range, target) = link_at_offset(&token, position.offset)?;
+    let target = normalize_target(&target)?;
+    let path = parse_path(&target)?;
+
+    let scope = token.parent();
+    let analyzer = SourceAnalyzer::new(db, InFile::new(position.file_id.into(), &scope), None);
+    let resolution = resolve_path(db, &analyzer, path)?;
+    let nav = match resolution {
+        PathResolution::Def(def) => NavigationTarget::from_def(db, def)?,
+        PathResolution::AssocItem(it) => it.to_nav(db),
+        PathResolution::Macro(it) => it.to_nav(db),
+        PathResolution::TypeParam(it) => it.to_nav(db),
+        PathResolution::SelfType(it) => it.to_nav(db),
+        PathResolution::Local(it) => it.to_nav(db),
+    };
+    Some(RangeInfo::new(range, vec![nav]))
+}
+
+/// Finds the `[label]` or `[label](target)` link containing `offset`, and
+/// returns its range together with the text naming the linked item.
+fn link_at_offset(token: &SyntaxToken, offset: TextUnit) -> Option<(TextRange, String)> {
+    let text = token.text().as_str();
+    let start = token.text_range().start();
+    let offset = (offset - start).to_usize();
+
+    let mut pos = 0;
+    while let Some(open) = text[pos..].find('[').map(|it| it + pos) {
+        let close = match text[open..].find(']') {
+            Some(it) => it + open,
+            None => break,
+        };
+        let label = &text[open + 1..close];
+        let (end, target) = match paren_group(&text[close + 1..]) {
+            Some(len) => (close + 1 + len, &text[close + 2..close + len]),
+            None => (close + 1, label),
+        };
+        if open <= offset && offset < end {
+            let range = TextRange::from_to(
+                start + TextUnit::from_usize(open),
+                start + TextUnit::from_usize(end),
+            );
+            return Some((range, target.to_string()));
+        }
+        pos = end;
+    }
+    None
+}
+
+/// The length of a leading `(...)` group, including the parens.
+fn paren_group(text: &str) -> Option<usize> {
+    if !text.starts_with('(') {
+        return None;
+    }
+    text.find(')').map(|it| it + 1)
+}
+
+/// Turns the text of a link target into a path: rustdoc allows the path to be
+/// wrapped in backticks, to be prefixed by the kind of item (`struct@Foo`) and
+/// to be followed by `()` for functions or `!` for macros.
+fn normalize_target(target: &str) -> Option<String> {
+    let target = target.trim();
+    // links to urls and to anchors are not intra-doc links
+    if target.contains("://") || target.starts_with('#') {
+        return None;
+    }
+    let target = target.trim_matches('`');
+    let target = match target.find('@') {
+        Some(idx) => &target[idx + 1..],
+        None => target,
+    };
+    let target = target.trim_end_matches("()").trim_end_matches('!');
+    let is_path =
+        !target.is_empty() && target.chars().all(|c| c.is_alphanumeric() || c == '_' || c == ':');
+    if !is_path {
+        return None;
+    }
+    Some(target.to_string())
+}
+
+fn parse_path(target: &str) -> Option<ast::Path> {
+    let file = ast::SourceFile::parse(&format!("type T = {};", target)).tree();
+    let path = file.syntax().descendants().find_map(ast::Path::cast)?;
+    // the outermost path is the first one in preorder
+    if path.syntax().to_string() != target {
+        return None;
+    }
+    Some(path)
+}
+
+fn resolve_path(
+    db: &RootDatabase,
+    analyzer: &SourceAnalyzer,
+    path: ast::Path,
+) -> Option<PathResolution> {
+    if let Some(res) =
+        hir::Path::from_ast(path.clone()).and_then(|it| analyzer.resolve_hir_path(db, &it))
+    {
+        return Some(res);
+    }
+
+    // `Type::method` and `Trait::method` name associated items, which are not
+    // resolved by paths in the item scope
+    let name = path.segment()?.name_ref()?.text().to_string();
+    let qualifier = hir::Path::from_ast(path.qualifier()?)?;
+    let item = match analyzer.resolve_hir_path(db, &qualifier)? {
+        PathResolution::Def(ModuleDef::Adt(adt)) => find_impl_item(db, adt, &name)?,
+        PathResolution::Def(ModuleDef::Trait(trait_)) => trait_
+            .items(db)
+            .into_iter()
+            .find(|it| assoc_item_name(db, *it).as_ref() == Some(&name))?,
+        _ => return None,
+    };
+    Some(PathResolution::AssocItem(item))
+}
+
+fn find_impl_item(db: &RootDatabase, adt: Adt, name: &str) -> Option<AssocItem> {
+    let krate = adt.krate(db)?;
+    adt.ty(db).iterate_impl_items(db, krate, |it| {
+        if assoc_item_name(db, it).as_ref().map(String::as_str) == Some(name) {
+            return Some(it);
+        }
+        None
+    })
+}
+
+fn assoc_item_name(db: &RootDatabase, item: AssocItem) -> Option<String> {
+    let name = match item {
+        AssocItem::Function(it) => it.name(db),
+        AssocItem::Const(it) => it.name(db)?,
+        AssocItem::TypeAlias(it) => it.name(db),
+    };
+    Some(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock_analysis::analysis_and_position;
+
+    fn check_doc_link(fixture: &str, expected: &str) {
+        let (analysis, pos) = analysis_and_position(fixture);
+
+        let mut navs = analysis.doc_links(pos).unwrap().unwrap().info;
+        assert_eq!(navs.len(), 1);
+        let nav = navs.pop().unwrap();
+        nav.assert_match(expected);
+    }
+
+    fn check_no_doc_link(fixture: &str) {
+        let (analysis, pos) = analysis_and_position(fixture);
+        assert!(analysis.doc_links(pos).unwrap().is_none());
+    }
+
+    #[test]
+    fn doc_link_to_struct() {
+        check_doc_link(
+            "
+            //- /lib.rs
+            /// Makes a [Foo<|>].
+            fn make() {}
+            struct Foo;
+            ",
+            "Foo STRUCT_DEF FileId(1) [32; 43) [39; 42)",
+        );
+    }
+
+    #[test]
+    fn doc_link_in_backticks() {
+        check_doc_link(
+            "
+            //- /lib.rs
+            /// Makes a [`Foo`<|>].
+            fn make() {}
+            struct Foo;
+            ",
+            "Foo STRUCT_DEF FileId(1) [34; 45) [41; 44)",
+        );
+    }
+
+    #[test]
+    fn doc_link_with_explicit_target() {
+        check_doc_link(
+            "
+            //- /lib.rs
+            //! See [the function](<|>foo::bar()).
+            mod foo {
+                pub fn bar() {}
+            }
+            ",
+            "bar FN_DEF FileId(1) [50; 65) [57; 60)",
+        );
+    }
+
+    #[test]
+    fn doc_link_to_method() {
+        check_doc_link(
+            "
+            //- /lib.rs
+            struct Foo;
+            impl Foo {
+                fn new() -> Foo { Foo }
+            }
+            /// Built with [Foo::new<|>].
+            fn make() {}
+            ",
+            "new FN_DEF FileId(1) [27; 50) [30; 33)",
+        );
+    }
+
+    #[test]
+    fn doc_link_to_trait_method() {
+        check_doc_link(
+            "
+            //- /lib.rs
+            trait Tr {
+                fn f(&self);
+            }
+            /// Calls [`Tr::f`<|>].
+            fn g() {}
+            ",
+            "f FN_DEF FileId(1) [15; 27) [18; 19)",
+        );
+    }
+
+    #[test]
+    fn no_doc_link_outside_of_links_and_doc_comments() {
+        check_no_doc_link(
+            "
+            //- /lib.rs
+            /// Ma<|>kes a [Foo]
+            fn make() {}
+            struct Foo;
+            ",
+        );
+        check_no_doc_link(
+            "
+            //- /lib.rs
+            // Makes a [Foo<|>]
+            fn make() {}
+            struct Foo;
+            ",
+        );
+        check_no_doc_link(
+            "
+            //- /lib.rs
+            /// See [the docs](https://docs.rs<|>)
+            fn make() {}
+            ",
+        );
+    }
+}
@@ -1,73 +1,9 @@
 //! Utilities to work with files, produced by macros.
-use std::iter::successors;
-
-use hir::{InFile, Origin};
+use hir::InFile;
 use ra_db::FileId;
-use ra_syntax::{ast, AstNode, SyntaxNode, SyntaxToken, TextRange};
-
-use crate::{db::RootDatabase, FileRange};
-
-pub(crate) fn original_range(db: &RootDatabase, node: InFile<&SyntaxNode>) -> FileRange {
-    if let Some((range, Origin::Call)) = original_range_and_origin(db, node) {
-        return range;
-    }
-
-    if let Some(expansion) = node.file_id.expansion_info(db) {
-        if let Some(call_node) = expansion.call_node() {
-            return FileRange {
-                file_id: call_node.file_id.original_file(db),
-                range: call_node.value.text_range(),
-            };
-        }
-    }
-
-    FileRange { file_id: node.file_id.original_file(db), range: node.value.text_range() }
-}
-
-fn original_range_and_origin(
-    db: &RootDatabase,
-    node: InFile<&SyntaxNode>,
-) -> Option<(FileRange, Origin)> {
-    let expansion = node.file_id.expansion_info(db)?;
-
-    // the input node has only one token ?
-    let single = node.value.first_token()? == node.value.last_token()?;
+use ra_syntax::SyntaxToken;
 
-    // FIXME: We should handle recurside macro expansions
-    let (range, origin) = node.value.descendants().find_map(|it| {
-        let first = it.first_token()?;
-        let last = it.last_token()?;
-
-        if !single && first == last {
-            return None;
-        }
-
-        // Try to map first and last tokens of node, and, if success, return the union range of mapped tokens
-        let (first, first_origin) = expansion.map_token_up(node.with_value(&first))?;
-        let (last, last_origin) = expansion.map_token_up(node.with_value(&last))?;
-
-        if first.file_id != last.file_id || first_origin != last_origin {
-            return None;
-        }
-
-        // FIXME: Add union method in TextRange
-        Some((
-            first.with_value(union_range(first.value.text_range(), last.value.text_range())),
-            first_origin,
-        ))
-    })?;
-
-    return Some((
-        FileRange { file_id: range.file_id.original_file(db), range: range.value },
-        origin,
-    ));
-
-    fn union_range(a: TextRange, b: TextRange) -> TextRange {
-        let start = a.start().min(b.start());
-        let end = a.end().max(b.end());
-        TextRange::from_to(start, end)
-    }
-}
+use crate::db::RootDatabase;
 
 pub(crate) fn descend_into_macros(
     db: &RootDatabase,
@@ -75,18 +11,37 @@ pub(crate) fn descend_into_macros(
     token: SyntaxToken,
 ) -> InFile<SyntaxToken> {
     let src = InFile::new(file_id.into(), token);
+    hir::descend_into_macros(db, src).into_iter().next().unwrap()
+}
 
-    successors(Some(src), |token| {
-        let macro_call = token.value.ancestors().find_map(ast::MacroCall::cast)?;
-        let tt = macro_call.token_tree()?;
-        if !token.value.text_range().is_subrange(&tt.syntax().text_range()) {
-            return None;
-        }
-        let source_analyzer =
-            hir::SourceAnalyzer::new(db, token.with_value(token.value.parent()).as_ref(), None);
-        let exp = source_analyzer.expand(db, token.with_value(&macro_call))?;
-        exp.map_token_down(db, token.as_ref())
-    })
-    .last()
-    .unwrap()
+#[cfg(test)]
+mod tests {
+    use hir::InFile;
+    use ra_db::SourceDatabase;
+    use ra_syntax::AstNode;
+
+    use crate::mock_analysis::single_file_with_position;
+
+    #[test]
+    fn descend_into_macros_follows_repeated_arguments() {
+        let (analysis, pos) = single_file_with_position(
+            "
+            macro_rules! twice {
+                ($e:expr) => { ($e, $e) }
+            }
+            fn foo() {
+                let x = 92;
+                twice!(<|>x);
+            }
+            ",
+        );
+        let db = &*analysis.db;
+        let file = db.parse(pos.file_id).tree();
+        let token = file.syntax().token_at_offset(pos.offset).right_biased().unwrap();
+        let tokens = hir::descend_into_macros(db, InFile::new(pos.file_id.into(), token));
+        assert_eq!(tokens.len(), 2);
+        assert!(tokens.iter().all(|it| it.file_id.call_node(db).is_some()));
+        assert!(tokens.iter().all(|it| it.value.text().as_str() == "x"));
+        assert_ne!(tokens[0].value.text_range(), tokens[1].value.text_range());
+    }
 }
@@ -11,6 +11,10 @@ use ra_syntax::{
     AstNode, NodeOrToken, SyntaxKind, SyntaxNode, WalkEvent, T,
 };
 
+/// Macro calls nested deeper than this in the expansion are left unexpanded,
+/// so that recursive macros don't expand forever.
+const EXPANSION_DEPTH_LIMIT: usize = 32;
+
 pub struct ExpandedMacro {
     pub name: String,
     pub expansion: String,
@@ -23,7 +27,7 @@ pub(crate) fn expand_macro(db: &RootDatabase, position: FilePosition) -> Option<
     let mac = name_ref.syntax().ancestors().find_map(ast::MacroCall::cast)?;
 
     let source = hir::InFile::new(position.file_id.into(), mac.syntax());
-    let expanded = expand_macro_recur(db, source, source.with_value(&mac))?;
+    let expanded = expand_macro_recur(db, source, source.with_value(&mac), 0)?;
 
     // FIXME:
     // macro expansion may lose all white space information
@@ -36,7 +40,11 @@ fn expand_macro_recur(
     db: &RootDatabase,
     source: hir::InFile<&SyntaxNode>,
     macro_call: hir::InFile<&ast::MacroCall>,
+    depth: usize,
 ) -> Option<SyntaxNode> {
+    if depth >= EXPANSION_DEPTH_LIMIT {
+        return None;
+    }
     let analyzer = hir::SourceAnalyzer::new(db, source, None);
     let expansion = analyzer.expand(db, macro_call)?;
     let macro_file_id = expansion.file_id();
@@ -47,7 +55,7 @@ fn expand_macro_recur(
 
     for child in children.into_iter() {
         let node = hir::InFile::new(macro_file_id, &child);
-        if let Some(new_node) = expand_macro_recur(db, source, node) {
+        if let Some(new_node) = expand_macro_recur(db, source, node, depth + 1) {
             // Replace the whole node if it is root
             // `replace_descendants` will not replace the parent node
             // but `SyntaxNode::descendants include itself
@@ -93,18 +101,30 @@ fn insert_whitespaces(syn: SyntaxNode) -> String {
             k if is_text(k) && is_next(|it| !it.is_punct(), true) => token.text().to_string() + " ",
             L_CURLY if is_next(|it| it != R_CURLY, true) => {
                 indent += 1;
-                let leading_space = if is_last(is_text, false) { " " } else { "" };
-                format!("{}{{\n{}", leading_space, "  ".repeat(indent))
+                let leading_space = if is_last(is_block_start, false) { " " } else { "" };
+                format!("{}{{\n{}", leading_space, "    ".repeat(indent))
             }
+            L_CURLY if is_last(is_block_start, false) => " {".to_string(),
             R_CURLY if is_last(|it| it != L_CURLY, true) => {
                 indent = indent.saturating_sub(1);
-                format!("\n{}}}", "  ".repeat(indent))
+                format!("\n{}}}", "    ".repeat(indent))
             }
-            R_CURLY => format!("}}\n{}", "  ".repeat(indent)),
-            T![;] => format!(";\n{}", "  ".repeat(indent)),
+            R_CURLY => format!("}}\n{}", "    ".repeat(indent)),
+            T![;] => format!(";\n{}", "    ".repeat(indent)),
             T![->] => " -> ".to_string(),
             T![=] => " = ".to_string(),
             T![=>] => " => ".to_string(),
+            // the operators which can't be unary
+            T![+]
+            | T![==]
+            | T![!=]
+            | T![<=]
+            | T![>=]
+            | T![+=]
+            | T![-=]
+            | T![*=]
+            | T![/=]
+            | T![%=] => format!(" {} ", token.text()),
             _ => token.text().to_string(),
         };
 
@@ -116,6 +136,12 @@ fn insert_whitespaces(syn: SyntaxNode) -> String {
     fn is_text(k: SyntaxKind) -> bool {
         k.is_keyword() || k.is_literal() || k == IDENT
     }
+
+    /// Whether a `{` following this token opens a block or item body, which
+    /// is separated from what precedes it.
+    fn is_block_start(k: SyntaxKind) -> bool {
+        is_text(k) || k == T![')'] || k == T![>]
+    }
 }
 
 #[cfg(test)]
@@ -149,7 +175,7 @@ mod tests {
 
         assert_eq!(res.name, "foo");
         assert_snapshot!(res.expansion, @r###"
-fn b(){}
+fn b() {}
 "###);
     }
 
@@ -173,8 +199,8 @@ fn b(){}
         assert_eq!(res.name, "foo");
         assert_snapshot!(res.expansion, @r###"
 fn some_thing() -> u32 {
-  let a = 0;
-  a+10
+    let a = 0;
+    a + 10
 }
 "###);
     }
@@ -211,13 +237,13 @@ fn some_thing() -> u32 {
         assert_eq!(res.name, "match_ast");
         assert_snapshot!(res.expansion, @r###"
 {
-  if let Some(it) = ast::TraitDef::cast(container.clone()){}
-  else if let Some(it) = ast::ImplBlock::cast(container.clone()){}
-  else {
-    {
-      continue
+    if let Some(it) = ast::TraitDef::cast(container.clone()) {}
+    else if let Some(it) = ast::ImplBlock::cast(container.clone()) {}
+    else {
+        {
+            continue
+        }
     }
-  }
 }
 "###);
     }
@@ -289,4 +315,20 @@ fn some_thing() -> u32 {
         assert_eq!(res.name, "foo");
         assert_snapshot!(res.expansion, @r###"0"###);
     }
+
+    #[test]
+    fn macro_expand_stops_at_depth_limit() {
+        let res = check_expand_macro(
+            r#"
+        //- /lib.rs
+        macro_rules! foo {
+            () => { foo!(); }
+        }
+        f<|>oo!();
+        "#,
+        );
+
+        assert_eq!(res.name, "foo");
+        assert_snapshot!(res.expansion, @r###"foo!();"###);
+    }
 }
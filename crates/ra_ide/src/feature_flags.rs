@@ -56,6 +56,17 @@ impl Default for FeatureFlags {
             ("completion.insertion.add-call-parenthesis", true),
             ("completion.enable-postfix", true),
             ("notifications.workspace-loaded", true),
+            ("typing.insert-closing-brace", false),
+            ("typing.reindent-closing-brace", false),
+            ("assists.fill-default-bodies", false),
+            ("diagnostics.warn-unwrap", false),
+            ("diagnostics.warn-must-use", false),
+            ("diagnostics.warn-float-cmp", false),
+            ("diagnostics.warn-redundant-pattern-matching", false),
+            ("diagnostics.warn-no-op", false),
+            ("diagnostics.warn-shadowing", false),
+            ("diagnostics.warn-truncating-cast", false),
+            ("diagnostics.warn-unused-collection", false),
         ])
     }
 }
@@ -11,6 +11,7 @@ use ra_syntax::{
 use crate::{
     db::RootDatabase,
     display::{ShortLabel, ToNav},
+    doc_links,
     expand::descend_into_macros,
     references::{classify_name_ref, NameKind::*},
     FilePosition, NavigationTarget, RangeInfo,
@@ -22,6 +23,9 @@ pub(crate) fn goto_definition(
 ) -> Option<RangeInfo<Vec<NavigationTarget>>> {
     let file = db.parse_or_expand(position.file_id.into())?;
     let original_token = pick_best(file.token_at_offset(position.offset))?;
+    if original_token.kind() == COMMENT {
+        return doc_links::doc_link(db, position);
+    }
     let token = descend_into_macros(db, position.file_id, original_token.clone());
 
     let nav_targets = match_ast! {
@@ -480,6 +484,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn goto_def_for_extern_fns() {
+        check_goto(
+            r#"
+            //- /lib.rs
+            extern "C" {
+                fn foo(x: i32);
+            }
+
+            fn bar() {
+                unsafe { foo<|>(1) };
+            }
+            "#,
+            "foo FN_DEF FileId(1) [17; 32) [20; 23)",
+            "fn foo(x: i32);|foo",
+        );
+    }
+
+    #[test]
+    fn goto_def_for_trait_methods_without_body() {
+        check_goto(
+            "
+            //- /lib.rs
+            trait Foo {
+                fn frobnicate(&self);
+            }
+
+            fn bar<T: Foo>(foo: &T) {
+                foo.frobnicate<|>();
+            }
+            ",
+            "frobnicate FN_DEF FileId(1) [16; 37) [19; 29)",
+            "fn frobnicate(&self);|frobnicate",
+        );
+    }
+
     #[test]
     fn goto_def_for_fields() {
         covers!(goto_def_for_fields);
@@ -928,4 +968,18 @@ mod tests {
             "x: i32|x",
         )
     }
+
+    #[test]
+    fn goto_def_from_doc_link() {
+        check_goto(
+            "
+            //- /lib.rs
+            /// Returns a [`Foo<|>`].
+            fn make() {}
+            struct Foo;
+            ",
+            "Foo STRUCT_DEF FileId(1) [36; 47) [43; 46)",
+            "struct Foo;|Foo",
+        );
+    }
 }
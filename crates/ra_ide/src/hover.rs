@@ -12,7 +12,7 @@ use ra_syntax::{
 
 use crate::{
     db::RootDatabase,
-    display::{macro_label, rust_code_markup, rust_code_markup_with_doc, ShortLabel},
+    display::{macro_rules_label, rust_code_markup, rust_code_markup_with_doc, ShortLabel},
     expand::descend_into_macros,
     references::{classify_name, classify_name_ref, NameKind, NameKind::*},
     FilePosition, FileRange, RangeInfo,
@@ -96,7 +96,7 @@ fn hover_text_from_name_kind(db: &RootDatabase, name_kind: NameKind) -> Option<S
     return match name_kind {
         Macro(it) => {
             let src = it.source(db);
-            hover_text(src.value.doc_comment_text(), Some(macro_label(&src.value)))
+            hover_text(src.value.doc_comment_text(), Some(macro_rules_label(&src.value)))
         }
         Field(it) => {
             let src = it.source(db);
@@ -118,10 +118,24 @@ fn hover_text_from_name_kind(db: &RootDatabase, name_kind: NameKind) -> Option<S
                 _ => None,
             },
             hir::ModuleDef::Function(it) => from_def_source(db, it),
-            hir::ModuleDef::Adt(Adt::Struct(it)) => from_def_source(db, it),
-            hir::ModuleDef::Adt(Adt::Union(it)) => from_def_source(db, it),
-            hir::ModuleDef::Adt(Adt::Enum(it)) => from_def_source(db, it),
-            hir::ModuleDef::EnumVariant(it) => from_def_source(db, it),
+            hir::ModuleDef::Adt(adt) => {
+                let text = match adt {
+                    Adt::Struct(it) => from_def_source(db, it),
+                    Adt::Union(it) => from_def_source(db, it),
+                    Adt::Enum(it) => from_def_source(db, it),
+                }?;
+                match auto_traits(db, adt) {
+                    Some(auto_traits) => Some(format!("{}\n\n{}", text, auto_traits)),
+                    None => Some(text),
+                }
+            }
+            hir::ModuleDef::EnumVariant(it) => {
+                let text = from_def_source(db, it)?;
+                match discriminant(db, it) {
+                    Some(discriminant) => Some(format!("{}\n\n{}", text, discriminant)),
+                    None => Some(text),
+                }
+            }
             hir::ModuleDef::Const(it) => from_def_source(db, it),
             hir::ModuleDef::Static(it) => from_def_source(db, it),
             hir::ModuleDef::Trait(it) => from_def_source(db, it),
@@ -152,20 +166,32 @@ pub(crate) fn hover(db: &RootDatabase, position: FilePosition) -> Option<RangeIn
 
     let mut res = HoverResult::new();
 
-    if let Some((range, name_kind)) = match_ast! {
+    if let Some((node, name_kind)) = match_ast! {
         match (token.value.parent()) {
             ast::NameRef(name_ref) => {
-                classify_name_ref(db, token.with_value(&name_ref)).map(|d| (name_ref.syntax().text_range(), d.kind))
+                classify_name_ref(db, token.with_value(&name_ref)).map(|d| (name_ref.syntax().clone(), d.kind))
             },
             ast::Name(name) => {
-                classify_name(db, token.with_value(&name)).map(|d| (name.syntax().text_range(), d.kind))
+                classify_name(db, token.with_value(&name)).map(|d| (name.syntax().clone(), d.kind))
             },
             _ => None,
         }
     } {
-        res.extend(hover_text_from_name_kind(db, name_kind));
+        let mut notes = Vec::new();
+        if let AssocItem(hir::AssocItem::Function(it)) = &name_kind {
+            if let Some(name_ref) = ast::NameRef::cast(node.clone()) {
+                notes.extend(method_call_target(db, token.with_value(&name_ref), *it));
+                notes.extend(shadowed_trait_methods(db, token.with_value(&name_ref), *it));
+            }
+        }
+        res.extend(hover_text_from_name_kind(db, name_kind).map(|text| {
+            notes.into_iter().fold(text, |text, note| format!("{}\n\n{}", text, note))
+        }));
 
         if !res.is_empty() {
+            // The token might come from a macro expansion, so report the
+            // range of the name in the file the user is hovering over.
+            let range = hir::original_range(db, token.with_value(&node)).range;
             return Some(RangeInfo::new(range, res));
         }
     }
@@ -174,14 +200,118 @@ pub(crate) fn hover(db: &RootDatabase, position: FilePosition) -> Option<RangeIn
         .value
         .ancestors()
         .find(|n| ast::Expr::cast(n.clone()).is_some() || ast::Pat::cast(n.clone()).is_some())?;
-    let frange = FileRange { file_id: position.file_id, range: node.text_range() };
+    let frange = hir::original_range(db, token.with_value(&node));
     res.extend(type_of(db, frange).map(rust_code_markup));
     if res.is_empty() {
         return None;
     }
-    let range = node.text_range();
 
-    Some(RangeInfo::new(range, res))
+    Some(RangeInfo::new(frange.range, res))
+}
+
+/// Lists the auto traits among `Send` and `Sync` implemented by the ADT, like
+/// "auto traits: Send, Sync".
+fn auto_traits(db: &RootDatabase, adt: Adt) -> Option<String> {
+    let node = match adt {
+        Adt::Struct(it) => it.source(db).map(|it| it.syntax().clone()),
+        Adt::Union(it) => it.source(db).map(|it| it.syntax().clone()),
+        Adt::Enum(it) => it.source(db).map(|it| it.syntax().clone()),
+    };
+    let analyzer = hir::SourceAnalyzer::new(db, node.as_ref(), None);
+    let ty = adt.ty(db);
+    let mut auto_traits = Vec::new();
+    if analyzer.impls_send(db, ty.clone()) {
+        auto_traits.push("Send");
+    }
+    if analyzer.impls_sync(db, ty) {
+        auto_traits.push("Sync");
+    }
+    if auto_traits.is_empty() {
+        return None;
+    }
+    Some(format!("auto traits: {}", auto_traits.join(", ")))
+}
+
+/// The value of the discriminant of a variant of a field-less enum, like
+/// "discriminant: 1u8" for an enum with `#[repr(u8)]`.
+fn discriminant(db: &RootDatabase, variant: hir::EnumVariant) -> Option<String> {
+    let enum_ = variant.parent_enum(db);
+    if enum_.variants(db).iter().any(|it| it.kind(db) != hir::StructKind::Unit) {
+        return None;
+    }
+    let value = variant.discriminant(db)?;
+    let suffix = enum_.repr(db).map(|it| it.to_string()).unwrap_or_default();
+    Some(format!("discriminant: {}{}", value, suffix))
+}
+
+/// Tells where the method of a method call comes from, like "resolves to
+/// `<Foo as Bar>::bar`" for a trait method or "method of `Foo`" for an inherent
+/// one.
+fn method_call_target(
+    db: &RootDatabase,
+    name_ref: hir::InFile<&ast::NameRef>,
+    func: hir::Function,
+) -> Option<String> {
+    let call = name_ref.value.syntax().parent().and_then(ast::MethodCallExpr::cast)?;
+    match hir::AssocItem::from(func).container(db)? {
+        hir::AssocItemContainer::Trait(trait_) => {
+            // the receiver is auto-dereferenced until the trait is implemented
+            let analyzer = hir::SourceAnalyzer::new(db, name_ref.map(|it| it.syntax()), None);
+            let receiver_ty = analyzer.type_of(db, &call.expr()?)?;
+            let name = func.name(db);
+            let self_ty =
+                analyzer.iterate_method_candidates(db, &receiver_ty, Some(&name), |ty, it| {
+                    if it == func {
+                        Some(ty.display(db).to_string())
+                    } else {
+                        None
+                    }
+                })?;
+            Some(format!("resolves to `<{} as {}>::{}`", self_ty, trait_.name(db), name))
+        }
+        hir::AssocItemContainer::ImplBlock(it) if it.target_trait(db).is_none() => {
+            Some(format!("method of `{}`", it.target_ty(db).display(db)))
+        }
+        hir::AssocItemContainer::ImplBlock(_) => None,
+    }
+}
+
+/// Notes the trait methods of the same name which are applicable to the
+/// receiver of a method call, but lose to the inherent method it resolves to,
+/// like "shadows the trait method `Foo::foo`, call it as `Foo::foo(..)`".
+fn shadowed_trait_methods(
+    db: &RootDatabase,
+    name_ref: hir::InFile<&ast::NameRef>,
+    func: hir::Function,
+) -> Option<String> {
+    let call = name_ref.value.syntax().parent().and_then(ast::MethodCallExpr::cast)?;
+    match hir::AssocItem::from(func).container(db)? {
+        hir::AssocItemContainer::ImplBlock(it) if it.target_trait(db).is_none() => (),
+        _ => return None,
+    }
+    let analyzer = hir::SourceAnalyzer::new(db, name_ref.map(|it| it.syntax()), None);
+    let receiver_ty = analyzer.type_of(db, &call.expr()?)?;
+    let name = func.name(db);
+    let mut traits = Vec::new();
+    analyzer.iterate_method_candidates(db, &receiver_ty, Some(&name), |_ty, it| {
+        if let Some(hir::AssocItemContainer::Trait(it)) = hir::AssocItem::from(it).container(db) {
+            if !traits.contains(&it) {
+                traits.push(it);
+            }
+        }
+        None::<()>
+    });
+    let paths: Vec<String> =
+        traits.into_iter().map(|it| format!("{}::{}", it.name(db), name)).collect();
+    match paths.as_slice() {
+        [] => None,
+        [path] => Some(format!("shadows the trait method `{0}`, call it as `{0}(..)`", path)),
+        _ => Some(format!(
+            "shadows the trait methods {}, call them as `{}(..)`",
+            paths.iter().map(|it| format!("`{}`", it)).collect::<Vec<_>>().join(", "),
+            paths[0],
+        )),
+    }
 }
 
 fn pick_best(tokens: TokenAtOffset<SyntaxToken>) -> Option<SyntaxToken> {
@@ -474,6 +604,97 @@ The Some variant
         );
     }
 
+    #[test]
+    fn hover_shows_auto_traits_of_adts() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            struct Foo<|> { field: Bar }
+            struct Bar;
+            impl !std::marker::Sync for Bar {}
+
+            //- /std/lib.rs
+            pub mod marker {
+                pub auto trait Send {}
+                pub auto trait Sync {}
+            }
+            "#,
+            &["struct Foo\n```\n\nauto traits: Send"],
+        );
+    }
+
+    #[test]
+    fn hover_shows_discriminants_of_field_less_enums() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            #[repr(u8)]
+            enum E { A = 1, B, C = 10 }
+            fn main() { E::B<|>; }
+            "#,
+            &["B\n```\n\ndiscriminant: 2u8"],
+        );
+        check_hover_result(
+            r#"
+            //- /main.rs
+            enum E { A = -2, B, C }
+            fn main() { E::C<|>; }
+            "#,
+            &["C\n```\n\ndiscriminant: 0"],
+        );
+    }
+
+    #[test]
+    fn hover_shows_the_implementing_type_of_trait_method_calls() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            struct S;
+            trait Tr { fn foo(&self, x: u32) -> u32; }
+            impl Tr for S { fn foo(&self, x: u32) -> u32 { x } }
+            fn main() { let s = &&S; s.fo<|>o(1); }
+            "#,
+            &["fn foo(&self, x: u32) -> u32\n```\n\nresolves to `<S as Tr>::foo`"],
+        );
+        check_hover_result(
+            r#"
+            //- /main.rs
+            struct S;
+            impl S { fn foo(&self) {} }
+            fn main() { S.fo<|>o(); }
+            "#,
+            &["fn foo(&self)\n```\n\nmethod of `S`"],
+        );
+    }
+
+    #[test]
+    fn hover_notes_trait_methods_shadowed_by_inherent_ones() {
+        check_hover_result(
+            r#"
+            //- /main.rs
+            struct S;
+            impl S { fn len(&self) -> u32 { 0 } }
+            trait Len { fn len(&self) -> u32; }
+            impl Len for S { fn len(&self) -> u32 { 1 } }
+            fn main() { S.le<|>n(); }
+            "#,
+            &["fn len(&self) -> u32\n```\n\nmethod of `S`\n\nshadows the trait method `Len::len`, call it as `Len::len(..)`"],
+        );
+        check_hover_result(
+            r#"
+            //- /main.rs
+            struct S;
+            impl S { fn len(&self) -> u32 { 0 } }
+            trait Len { fn len(&self) -> u32; }
+            impl Len for S { fn len(&self) -> u32 { 1 } }
+            fn main() { Len::len(&S); S.le<|>n(); }
+            mod m { pub trait Other { fn len(&self) -> u32; } }
+            impl m::Other for S { fn len(&self) -> u32 { 2 } }
+            "#,
+            &["fn len(&self) -> u32\n```\n\nmethod of `S`\n\nshadows the trait method `Len::len`, call it as `Len::len(..)`"],
+        );
+    }
+
     #[test]
     fn hover_for_local_variable() {
         let (analysis, position) = single_file_with_position("fn func(foo: i32) { fo<|>o; }");
@@ -695,10 +916,36 @@ fn func(foo: i32) { if true { <|>foo; }; }
             ",
         );
         let hover = analysis.hover(position).unwrap().unwrap();
-        assert_eq!(trim_markup_opt(hover.info.first()), Some("macro_rules! foo"));
+        assert_eq!(
+            trim_markup_opt(hover.info.first()),
+            Some("macro_rules! foo {\n    () => { ... };\n}")
+        );
         assert_eq!(hover.info.is_exact(), true);
     }
 
+    #[test]
+    fn test_hover_macro_invocation_shows_rule_matchers() {
+        let (analysis, position) = single_file_with_position(
+            "
+            macro_rules! foo {
+                ($x:expr) => { $x };
+                (in $($t:tt)*) => { () }
+            }
+
+            fn f() {
+                fo<|>o!(1);
+            }
+            ",
+        );
+        let hover = analysis.hover(position).unwrap().unwrap();
+        assert_eq!(
+            trim_markup_opt(hover.info.first()),
+            Some(
+                "macro_rules! foo {\n    ($x:expr) => { ... };\n    (in $($t:tt)*) => { ... };\n}"
+            )
+        );
+    }
+
     #[test]
     fn test_hover_tuple_field() {
         let (analysis, position) = single_file_with_position(
@@ -729,4 +976,50 @@ fn func(foo: i32) { if true { <|>foo; }; }
             &["fn foo()"],
         );
     }
+
+    #[test]
+    fn test_hover_range_in_method_chain() {
+        let (analysis, position) = single_file_with_position(
+            "
+            struct Foo;
+            impl Foo {
+                fn bar(&self) -> Foo { Foo }
+                fn baz(&self) -> u32 { 92 }
+            }
+            fn main() {
+                Foo.ba<|>r().baz();
+            }
+            ",
+        );
+        let hover = analysis.hover(position).unwrap().unwrap();
+        let text = analysis.file_text(position.file_id).unwrap();
+        assert_eq!(&text[hover.range.start().to_usize()..hover.range.end().to_usize()], "bar");
+        assert_eq!(
+            trim_markup_opt(hover.info.first()),
+            Some("fn bar(&self) -> Foo\n```\n\nmethod of `Foo`")
+        );
+    }
+
+    #[test]
+    fn test_hover_range_through_macro() {
+        let (analysis, position) = analysis_and_position(
+            "
+            //- /lib.rs
+            macro_rules! id {
+                ($($tt:tt)*) => { $($tt)* }
+            }
+            fn foo() {}
+            id! {
+                fn bar() {
+                    fo<|>o();
+                }
+            }
+            ",
+        );
+        let hover = analysis.hover(position).unwrap().unwrap();
+        let text = analysis.file_text(position.file_id).unwrap();
+        let range = hover.range.start().to_usize()..hover.range.end().to_usize();
+        assert_eq!(&text[range], "foo");
+        assert!(hover.range.start() <= position.offset && position.offset <= hover.range.end());
+    }
 }
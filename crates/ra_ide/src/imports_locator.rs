@@ -0,0 +1,73 @@
+//! Looks up the definitions to import with the `auto_import` assist in the
+//! symbol index of the whole workspace and its dependencies.
+
+use hir::{FromSource, InFile, Module, ModuleDef};
+use ra_assists::ImportsLocator;
+use ra_db::SourceDatabase;
+use ra_prof::profile;
+use ra_syntax::{ast, match_ast, AstNode};
+
+use crate::{
+    db::RootDatabase,
+    symbol_index::{self, FileSymbol},
+    Query,
+};
+
+pub(crate) struct ImportsLocatorIde<'a> {
+    db: &'a RootDatabase,
+}
+
+impl<'a> ImportsLocatorIde<'a> {
+    pub(crate) fn new(db: &'a RootDatabase) -> Self {
+        Self { db }
+    }
+
+    fn get_name_definition(&mut self, import_candidate: &FileSymbol) -> Option<ModuleDef> {
+        let _p = profile("get_name_definition");
+        let file_id = import_candidate.file_id.into();
+        let parse = self.db.parse(import_candidate.file_id);
+        let candidate_node = import_candidate.ptr.to_node(parse.tree().syntax());
+        let src = |value| InFile { file_id, value };
+        let def = match_ast! {
+            match candidate_node {
+                ast::FnDef(it) => ModuleDef::from(hir::Function::from_source(self.db, src(it))?),
+                ast::StructDef(it) => ModuleDef::from(hir::Struct::from_source(self.db, src(it))?),
+                ast::UnionDef(it) => ModuleDef::from(hir::Union::from_source(self.db, src(it))?),
+                ast::EnumDef(it) => ModuleDef::from(hir::Enum::from_source(self.db, src(it))?),
+                ast::TraitDef(it) => ModuleDef::from(hir::Trait::from_source(self.db, src(it))?),
+                ast::ConstDef(it) => ModuleDef::from(hir::Const::from_source(self.db, src(it))?),
+                ast::StaticDef(it) => ModuleDef::from(hir::Static::from_source(self.db, src(it))?),
+                ast::TypeAliasDef(it) => {
+                    ModuleDef::from(hir::TypeAlias::from_source(self.db, src(it))?)
+                },
+                ast::Module(it) => ModuleDef::from(Module::from_declaration(self.db, src(it))?),
+                _ => return None,
+            }
+        };
+        Some(def)
+    }
+}
+
+impl ImportsLocator for ImportsLocatorIde<'_> {
+    fn find_imports(&mut self, name_to_import: &str) -> Vec<ModuleDef> {
+        let _p = profile("search_for_imports");
+
+        let project_results = {
+            let mut query = Query::new(name_to_import.to_string());
+            query.exact();
+            symbol_index::world_symbols(self.db, query)
+        };
+        let lib_results = {
+            let mut query = Query::new(name_to_import.to_string());
+            query.libs();
+            query.exact();
+            symbol_index::world_symbols(self.db, query)
+        };
+
+        project_results
+            .into_iter()
+            .chain(lib_results.into_iter())
+            .filter_map(|import_candidate| self.get_name_definition(&import_candidate))
+            .collect()
+    }
+}
@@ -22,16 +22,21 @@ mod completion;
 mod runnables;
 mod goto_definition;
 mod goto_type_definition;
+mod doc_links;
 mod extend_selection;
 mod hover;
 mod call_info;
 mod syntax_highlighting;
 mod parent_module;
+mod module_tree;
 mod references;
 mod impls;
 mod assists;
+mod imports_locator;
 mod diagnostics;
 mod syntax_tree;
+mod view_hir;
+mod view_crate_graph;
 mod folding_ranges;
 mod line_index;
 mod line_index_utils;
@@ -43,6 +48,8 @@ mod inlay_hints;
 mod wasm_shims;
 mod expand;
 mod expand_macro;
+mod annotations;
+mod ssr;
 
 #[cfg(test)]
 mod marks;
@@ -56,14 +63,16 @@ use ra_db::{
     salsa::{self, ParallelDatabase},
     CheckCanceled, Env, FileLoader, SourceDatabase,
 };
-use ra_syntax::{SourceFile, TextRange, TextUnit};
+use ra_syntax::{SmolStr, SourceFile, TextRange, TextUnit};
+use ra_text_edit::TextEdit;
 
 use crate::{db::LineIndexDatabase, display::ToNav, symbol_index::FileSymbol};
 
 pub use crate::{
+    annotations::{Annotation, AnnotationConfig, AnnotationKind},
     assists::{Assist, AssistId},
     change::{AnalysisChange, LibraryData},
-    completion::{CompletionItem, CompletionItemKind, InsertTextFormat},
+    completion::{CompletionItem, CompletionItemKind, CompletionScore, InsertTextFormat},
     diagnostics::Severity,
     display::{file_structure, FunctionSignature, NavigationTarget, StructureNode},
     expand_macro::ExpandedMacro,
@@ -73,9 +82,11 @@ pub use crate::{
     inlay_hints::{InlayHint, InlayKind},
     line_index::{LineCol, LineIndex},
     line_index_utils::translate_offset_with_edit,
+    module_tree::ModuleTree,
     references::{ReferenceSearchResult, SearchScope},
     runnables::{Runnable, RunnableKind},
     source_change::{FileSystemEdit, SourceChange, SourceFileEdit},
+    ssr::SsrError,
     syntax_highlighting::HighlightedRange,
 };
 
@@ -298,6 +309,18 @@ impl Analysis {
         self.with_db(|db| syntax_tree::syntax_tree(&db, file_id, text_range))
     }
 
+    /// Returns the lowered hir body of the function at `position` represented
+    /// as `String`, for debug purposes.
+    pub fn view_hir(&self, position: FilePosition) -> Cancelable<String> {
+        self.with_db(|db| view_hir::view_hir(&db, position))
+    }
+
+    /// Returns the crate graph represented as `String`, for debug purposes:
+    /// either as plain text, or in the dot format of graphviz.
+    pub fn view_crate_graph(&self, dot: bool) -> Cancelable<String> {
+        self.with_db(|db| view_crate_graph::view_crate_graph(&db, dot))
+    }
+
     pub fn expand_macro(&self, position: FilePosition) -> Cancelable<Option<ExpandedMacro>> {
         self.with_db(|db| expand_macro::expand_macro(db, position))
     }
@@ -354,6 +377,21 @@ impl Analysis {
         })
     }
 
+    /// Returns the "N implementations" annotations of the types and traits and
+    /// the "N references" annotations of the functions in the file.
+    pub fn annotations(
+        &self,
+        file_id: FileId,
+        config: AnnotationConfig,
+    ) -> Cancelable<Vec<Annotation>> {
+        self.with_db(|db| annotations::annotations(db, file_id, config))
+    }
+
+    /// Computes the data of an annotation returned by `annotations`.
+    pub fn resolve_annotation(&self, annotation: Annotation) -> Cancelable<Annotation> {
+        self.with_db(|db| annotations::resolve_annotation(db, annotation))
+    }
+
     /// Returns the set of folding ranges.
     pub fn folding_ranges(&self, file_id: FileId) -> Cancelable<Vec<Fold>> {
         self.with_db(|db| folding_ranges::folding_ranges(&db.parse(file_id).tree()))
@@ -377,6 +415,15 @@ impl Analysis {
         self.with_db(|db| goto_definition::goto_definition(db, position))
     }
 
+    /// Returns the item an intra-doc link at `position` refers to, together
+    /// with the range of the link.
+    pub fn doc_links(
+        &self,
+        position: FilePosition,
+    ) -> Cancelable<Option<RangeInfo<Vec<NavigationTarget>>>> {
+        self.with_db(|db| doc_links::doc_link(db, position))
+    }
+
     /// Returns the impls from the symbol at `position`.
     pub fn goto_implementation(
         &self,
@@ -402,7 +449,8 @@ impl Analysis {
         self.with_db(|db| references::find_all_refs(db, position, search_scope).map(|it| it.info))
     }
 
-    /// Returns a short text describing element at position.
+    /// Returns a short text describing element at position, together with the
+    /// range of the name or expression it describes.
     pub fn hover(&self, position: FilePosition) -> Cancelable<Option<RangeInfo<HoverResult>>> {
         self.with_db(|db| hover::hover(db, position))
     }
@@ -417,7 +465,21 @@ impl Analysis {
         self.with_db(|db| parent_module::parent_module(db, position))
     }
 
-    /// Returns crates this file belongs too.
+    /// Returns the `mod name;` declaration which brings the file into the
+    /// module tree, `None` for crate roots.
+    pub fn incoming_mod_declaration(
+        &self,
+        file_id: FileId,
+    ) -> Cancelable<Option<NavigationTarget>> {
+        self.with_db(|db| parent_module::incoming_mod_declaration(db, file_id))
+    }
+
+    /// Returns the module trees of the crates this file belongs to.
+    pub fn module_tree(&self, file_id: FileId) -> Cancelable<Vec<ModuleTree>> {
+        self.with_db(|db| module_tree::module_tree(db, file_id))
+    }
+
+    /// Returns all the crates this file belongs to.
     pub fn crate_for(&self, file_id: FileId) -> Cancelable<Vec<CrateId>> {
         self.with_db(|db| parent_module::crate_for(db, file_id))
     }
@@ -439,7 +501,13 @@ impl Analysis {
 
     /// Computes syntax highlighting for the given file.
     pub fn highlight(&self, file_id: FileId) -> Cancelable<Vec<HighlightedRange>> {
-        self.with_db(|db| syntax_highlighting::highlight(db, file_id))
+        self.with_db(|db| syntax_highlighting::highlight(db, file_id, None))
+    }
+
+    /// Computes syntax highlighting for the part of the file in the given
+    /// range, e.g. the viewport of the editor.
+    pub fn semantic_tokens_range(&self, frange: FileRange) -> Cancelable<Vec<HighlightedRange>> {
+        self.with_db(|db| syntax_highlighting::highlight(db, frange.file_id, Some(frange.range)))
     }
 
     /// Computes syntax highlighting for the given file.
@@ -452,6 +520,16 @@ impl Analysis {
         self.with_db(|db| completion::completions(db, position).map(Into::into))
     }
 
+    /// Computes the `use` of the completion item at `position` which imports
+    /// `import_to_add`, which is left out of `completions` to keep them fast.
+    pub fn fixup_completion(
+        &self,
+        position: FilePosition,
+        import_to_add: &[SmolStr],
+    ) -> Cancelable<Option<TextEdit>> {
+        self.with_db(|db| completion::fixup_completion(db, position, import_to_add))
+    }
+
     /// Computes assists (aka code actions aka intentions) for the given
     /// position.
     pub fn assists(&self, frange: FileRange) -> Cancelable<Vec<Assist>> {
@@ -463,6 +541,15 @@ impl Analysis {
         self.with_db(|db| diagnostics::diagnostics(db, file_id))
     }
 
+    /// Computes the diagnostics of all the files of the given crate. A change
+    /// applied in the meantime cancels the computation between two files.
+    pub fn crate_diagnostics(
+        &self,
+        crate_id: CrateId,
+    ) -> Cancelable<Vec<(FileId, Vec<Diagnostic>)>> {
+        self.with_db(|db| diagnostics::crate_diagnostics(db, crate_id))
+    }
+
     /// Computes the type of the expression at the given position.
     pub fn type_of(&self, frange: FileRange) -> Cancelable<Option<String>> {
         self.with_db(|db| hover::type_of(db, frange))
@@ -478,6 +565,26 @@ impl Analysis {
         self.with_db(|db| references::rename(db, position, new_name))
     }
 
+    /// Returns the edit required to update the `mod` declarations and paths
+    /// referring to the modules defined by the files, when these are renamed
+    /// so that the modules get the new names.
+    pub fn will_rename_files(
+        &self,
+        renames: &[(FileId, String)],
+    ) -> Cancelable<Option<SourceChange>> {
+        self.with_db(|db| references::will_rename_files(db, renames))
+    }
+
+    /// Applies the structural search replace rule, like `foo($a) ==>> bar($a)`,
+    /// to all the files of the crates the file belongs to.
+    pub fn ssr_across_files(
+        &self,
+        file_id: FileId,
+        query: &str,
+    ) -> Cancelable<Result<SourceChange, SsrError>> {
+        self.with_db(|db| ssr::ssr_across_files(db, file_id, query))
+    }
+
     /// Performs an operation on that may be Canceled.
     fn with_db<F: FnOnce(&db::RootDatabase) -> T + std::panic::UnwindSafe, T>(
         &self,
@@ -0,0 +1,98 @@
+//! Computes the tree of the modules of a crate, following `mod foo;`
+//! declarations across files, for module explorers.
+
+use ra_db::FileId;
+
+use crate::{db::RootDatabase, display::ToNav, NavigationTarget};
+
+#[derive(Debug)]
+pub struct ModuleTree {
+    /// The name of the module, or `crate` for the crate root.
+    pub name: String,
+    /// The `mod` declaration of the module, or the root file for the crate
+    /// root.
+    pub nav: NavigationTarget,
+    /// Whether the module is declared inline, like `mod foo { ... }`, rather
+    /// than in a file of its own.
+    pub is_inline: bool,
+    pub children: Vec<ModuleTree>,
+}
+
+/// Returns the module trees of all the crates the file is part of.
+pub(crate) fn module_tree(db: &RootDatabase, file_id: FileId) -> Vec<ModuleTree> {
+    let mut roots: Vec<hir::Module> = Vec::new();
+    for module in hir::Module::all_from_file(db, file_id) {
+        let root = module.crate_root(db);
+        if !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+    roots.into_iter().map(|it| build(db, it)).collect()
+}
+
+fn build(db: &RootDatabase, module: hir::Module) -> ModuleTree {
+    let name = match module.name(db) {
+        Some(name) => name.to_string(),
+        None => "crate".to_string(),
+    };
+    let nav = NavigationTarget::from_module_to_decl(db, module);
+    let is_inline = match module.definition_source(db).value {
+        hir::ModuleSource::Module(_) => true,
+        hir::ModuleSource::SourceFile(_) => false,
+    };
+    let mut children: Vec<ModuleTree> = module.children(db).map(|it| build(db, it)).collect();
+    children.sort_by(|a, b| a.name.cmp(&b.name));
+    ModuleTree { name, nav, is_inline, children }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mock_analysis::analysis_and_position, ModuleTree};
+
+    fn render(tree: &ModuleTree, indent: usize, buf: &mut String) {
+        let kind = if tree.is_inline { "inline" } else { "file" };
+        buf.push_str(&format!(
+            "{}{} {} {:?}\n",
+            "  ".repeat(indent),
+            tree.name,
+            kind,
+            tree.nav.file_id()
+        ));
+        for child in &tree.children {
+            render(child, indent + 1, buf);
+        }
+    }
+
+    fn check_module_tree(fixture: &str, expected: &str) {
+        let (analysis, pos) = analysis_and_position(fixture);
+        let trees = analysis.module_tree(pos.file_id).unwrap();
+        let mut actual = String::new();
+        for tree in &trees {
+            render(tree, 0, &mut actual);
+        }
+        test_utils::assert_eq_text!(expected.trim(), actual.trim());
+    }
+
+    #[test]
+    fn module_tree_follows_file_modules() {
+        check_module_tree(
+            "
+            //- /lib.rs
+            mod foo;
+            mod bar {
+                mod baz {}
+            }
+            //- /foo.rs
+            <|>mod qux;
+            //- /foo/qux.rs
+            ",
+            "
+crate file FileId(1)
+  bar inline FileId(1)
+    baz inline FileId(1)
+  foo file FileId(1)
+    qux file FileId(2)
+",
+        );
+    }
+}
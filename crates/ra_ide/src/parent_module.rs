@@ -1,6 +1,6 @@
 //! FIXME: write short doc here
 
-use ra_db::{CrateId, FileId, FilePosition, SourceDatabase};
+use ra_db::{CrateId, FileId, FilePosition};
 
 use crate::{db::RootDatabase, NavigationTarget};
 
@@ -19,18 +19,28 @@ pub(crate) fn parent_module(db: &RootDatabase, position: FilePosition) -> Vec<Na
     vec![nav]
 }
 
-/// Returns `Vec` for the same reason as `parent_module`
+/// Returns the `mod name;` declaration which brings the file into the module
+/// tree, or `None` for crate roots.
+pub(crate) fn incoming_mod_declaration(
+    db: &RootDatabase,
+    file_id: FileId,
+) -> Option<NavigationTarget> {
+    let module = hir::Module::all_from_file(db, file_id)
+        .into_iter()
+        .find(|it| it.declaration_source(db).is_some())?;
+    Some(NavigationTarget::from_module_to_decl(db, module))
+}
+
+/// Returns all the crates the file is part of.
 pub(crate) fn crate_for(db: &RootDatabase, file_id: FileId) -> Vec<CrateId> {
-    let source_file = db.parse(file_id).tree();
-    let src = hir::ModuleSource::SourceFile(source_file);
-    let module =
-        match hir::Module::from_definition(db, hir::InFile { file_id: file_id.into(), value: src })
-        {
-            Some(it) => it,
-            None => return Vec::new(),
-        };
-    let krate = module.krate();
-    vec![krate.into()]
+    let mut res = Vec::new();
+    for module in hir::Module::all_from_file(db, file_id) {
+        let krate: CrateId = module.krate().into();
+        if !res.contains(&krate) {
+            res.push(krate);
+        }
+    }
+    res
 }
 
 #[cfg(test)]
@@ -74,6 +84,26 @@ mod tests {
         nav.assert_match("baz MODULE FileId(1) [32; 44)");
     }
 
+    #[test]
+    fn test_incoming_mod_declaration() {
+        let mock = MockAnalysis::with_files(
+            "
+            //- /lib.rs
+            mod foo;
+            //- /foo.rs
+            mod bar;
+            //- /foo/bar.rs
+            // empty
+            ",
+        );
+        let lib = mock.id_of("/lib.rs");
+        let bar = mock.id_of("/foo/bar.rs");
+        let analysis = mock.analysis();
+        let nav = analysis.incoming_mod_declaration(bar).unwrap().unwrap();
+        nav.assert_match("bar MODULE FileId(2) [0; 8)");
+        assert!(analysis.incoming_mod_declaration(lib).unwrap().is_none());
+    }
+
     #[test]
     fn test_resolve_crate_root() {
         let mock = MockAnalysis::with_files(
@@ -102,4 +132,38 @@ mod tests {
 
         assert_eq!(host.analysis().crate_for(mod_file).unwrap(), vec![crate_id]);
     }
+
+    #[test]
+    fn test_crate_for_file_in_several_crates() {
+        let mock = MockAnalysis::with_files(
+            "
+        //- /bar.rs
+        mod foo;
+        //- /baz.rs
+        mod foo;
+        //- /foo.rs
+        // empty
+    ",
+        );
+        let bar = mock.id_of("/bar.rs");
+        let baz = mock.id_of("/baz.rs");
+        let mod_file = mock.id_of("/foo.rs");
+        let mut host = mock.analysis_host();
+
+        let mut crate_graph = CrateGraph::default();
+        let bar_crate =
+            crate_graph.add_crate_root(bar, Edition2018, CfgOptions::default(), Env::default());
+        let baz_crate =
+            crate_graph.add_crate_root(baz, Edition2018, CfgOptions::default(), Env::default());
+        let mut change = AnalysisChange::new();
+        change.set_crate_graph(crate_graph);
+        host.apply_change(change);
+
+        let mut crates = host.analysis().crate_for(mod_file).unwrap();
+        crates.sort();
+        let mut expected = vec![bar_crate, baz_crate];
+        expected.sort();
+        assert_eq!(crates, expected);
+        assert_eq!(host.analysis().crate_for(bar).unwrap(), vec![bar_crate]);
+    }
 }
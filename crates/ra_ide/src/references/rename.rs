@@ -2,7 +2,11 @@
 
 use hir::ModuleSource;
 use ra_db::{RelativePath, RelativePathBuf, SourceDatabase, SourceDatabaseExt};
-use ra_syntax::{algo::find_node_at_offset, ast, tokenize, AstNode, SyntaxKind, SyntaxNode};
+use ra_syntax::{
+    algo::find_node_at_offset,
+    ast::{self, AttrsOwner, NameOwner},
+    tokenize, AstNode, SyntaxKind, SyntaxNode,
+};
 use ra_text_edit::TextEdit;
 
 use crate::{
@@ -17,10 +21,7 @@ pub(crate) fn rename(
     position: FilePosition,
     new_name: &str,
 ) -> Option<RangeInfo<SourceChange>> {
-    let tokens = tokenize(new_name);
-    if tokens.len() != 1
-        || (tokens[0].kind != SyntaxKind::IDENT && tokens[0].kind != SyntaxKind::UNDERSCORE)
-    {
+    if !is_valid_name(new_name) {
         return None;
     }
 
@@ -36,6 +37,68 @@ pub(crate) fn rename(
     }
 }
 
+/// Computes the edits of the `mod` declarations and paths referring to the
+/// modules defined by the files, when these get renamed so that the modules
+/// get the new names. The files themselves are expected to be moved by the
+/// client.
+pub(crate) fn will_rename_files(
+    db: &RootDatabase,
+    renames: &[(FileId, String)],
+) -> Option<SourceChange> {
+    let edits: Vec<SourceFileEdit> = renames
+        .iter()
+        .filter_map(|(file_id, new_name)| rename_mod_references(db, *file_id, new_name))
+        .flatten()
+        .collect();
+    if edits.is_empty() {
+        return None;
+    }
+    Some(SourceChange::source_file_edits("rename", edits))
+}
+
+fn rename_mod_references(
+    db: &RootDatabase,
+    file_id: FileId,
+    new_name: &str,
+) -> Option<Vec<SourceFileEdit>> {
+    if !is_valid_name(new_name) {
+        return None;
+    }
+    let src = hir::InFile {
+        file_id: file_id.into(),
+        value: ModuleSource::SourceFile(db.parse(file_id).tree()),
+    };
+    let module = hir::Module::from_definition(db, src)?;
+    let decl = module.declaration_source(db)?;
+    // we can't edit declarations coming from macro expansions, and the name
+    // of a module with a `#[path]` doesn't depend on the name of the file
+    if decl.file_id.call_node(db).is_some()
+        || decl.value.attrs().any(|it| it.simple_name().map_or(false, |it| it == "path"))
+    {
+        return None;
+    }
+    let name = decl.value.name()?;
+    let decl_file_id = decl.file_id.original_file(db);
+    let position =
+        FilePosition { file_id: decl_file_id, offset: name.syntax().text_range().start() };
+    let refs = find_all_refs(db, position, None)?.info;
+
+    let mut edits =
+        vec![source_edit_from_file_id_range(decl_file_id, name.syntax().text_range(), new_name)];
+    edits.extend(
+        refs.references()
+            .iter()
+            .map(|it| source_edit_from_file_id_range(it.file_id, it.range, new_name)),
+    );
+    Some(edits)
+}
+
+fn is_valid_name(new_name: &str) -> bool {
+    let tokens = tokenize(new_name);
+    tokens.len() == 1
+        && (tokens[0].kind == SyntaxKind::IDENT || tokens[0].kind == SyntaxKind::UNDERSCORE)
+}
+
 fn find_name_and_module_at_offset(
     syntax: &SyntaxNode,
     position: FilePosition,
@@ -127,7 +190,8 @@ mod tests {
     use test_utils::assert_eq_text;
 
     use crate::{
-        mock_analysis::analysis_and_position, mock_analysis::single_file_with_position, FileId,
+        mock_analysis::analysis_and_position, mock_analysis::single_file_with_position,
+        mock_analysis::MockAnalysis, FileId,
     };
 
     #[test]
@@ -358,6 +422,54 @@ mod tests {
                );
     }
 
+    #[test]
+    fn test_will_rename_files() {
+        let mock = MockAnalysis::with_files(
+            "
+            //- /lib.rs
+            mod foo;
+            use crate::foo::S;
+            fn f() { foo::g(); }
+
+            //- /foo.rs
+            pub struct S;
+            pub fn g() {}
+            ",
+        );
+        let lib_id = mock.id_of("/lib.rs");
+        let foo_id = mock.id_of("/foo.rs");
+        let analysis = mock.analysis();
+        let source_change =
+            analysis.will_rename_files(&[(foo_id, "bar".to_string())]).unwrap().unwrap();
+        let mut text_edit_builder = TextEditBuilder::default();
+        for edit in source_change.source_file_edits {
+            assert_eq!(edit.file_id, lib_id);
+            for atom in edit.edit.as_atoms() {
+                text_edit_builder.replace(atom.delete, atom.insert.clone());
+            }
+        }
+        let text = analysis.file_text(lib_id).unwrap();
+        let result = text_edit_builder.finish().apply(&*text);
+        assert_eq_text!(&text.replace("foo", "bar"), &*result);
+    }
+
+    #[test]
+    fn test_will_rename_files_ignores_path_attribute() {
+        let mock = MockAnalysis::with_files(
+            r#"
+            //- /lib.rs
+            #[path = "foo.rs"]
+            mod baz;
+
+            //- /foo.rs
+            pub struct S;
+            "#,
+        );
+        let foo_id = mock.id_of("/foo.rs");
+        let analysis = mock.analysis();
+        assert!(analysis.will_rename_files(&[(foo_id, "bar".to_string())]).unwrap().is_none());
+    }
+
     fn test_rename(text: &str, new_name: &str, expected: &str) {
         let (analysis, position) = single_file_with_position(text);
         let source_change = analysis.rename(position, new_name).unwrap();
@@ -4,8 +4,8 @@ use hir::InFile;
 use itertools::Itertools;
 use ra_db::SourceDatabase;
 use ra_syntax::{
-    ast::{self, AstNode, AttrsOwner, ModuleItemOwner, NameOwner},
-    match_ast, SyntaxNode, TextRange,
+    ast::{self, AstNode, AttrsOwner, DocCommentsOwner, ModuleItemOwner, NameOwner},
+    match_ast, SyntaxNode, TextRange, T,
 };
 
 use crate::{db::RootDatabase, FileId};
@@ -21,6 +21,7 @@ pub enum RunnableKind {
     Test { name: String },
     TestMod { path: String },
     Bench { name: String },
+    DocTest { path: String },
     Bin,
 }
 
@@ -32,14 +33,14 @@ pub(crate) fn runnables(db: &RootDatabase, file_id: FileId) -> Vec<Runnable> {
 fn runnable(db: &RootDatabase, file_id: FileId, item: SyntaxNode) -> Option<Runnable> {
     match_ast! {
         match item {
-            ast::FnDef(it) => { runnable_fn(it) },
+            ast::FnDef(it) => { runnable_fn(db, file_id, it) },
             ast::Module(it) => { runnable_mod(db, file_id, it) },
-            _ => { None },
+            _ => { runnable_doc_test(db, file_id, item) },
         }
     }
 }
 
-fn runnable_fn(fn_def: ast::FnDef) -> Option<Runnable> {
+fn runnable_fn(db: &RootDatabase, file_id: FileId, fn_def: ast::FnDef) -> Option<Runnable> {
     let name = fn_def.name()?.text().clone();
     let kind = if name == "main" {
         RunnableKind::Bin
@@ -48,7 +49,7 @@ fn runnable_fn(fn_def: ast::FnDef) -> Option<Runnable> {
     } else if fn_def.has_atom_attr("bench") {
         RunnableKind::Bench { name: name.to_string() }
     } else {
-        return None;
+        return runnable_doc_test(db, file_id, fn_def.syntax().clone());
     };
     Some(Runnable { range: fn_def.syntax().text_range(), kind })
 }
@@ -65,7 +66,7 @@ fn runnable_mod(db: &RootDatabase, file_id: FileId, module: ast::Module) -> Opti
     if !has_test_function {
         return None;
     }
-    let range = module.syntax().text_range();
+    let range = header_range(&module);
     let src = hir::ModuleSource::from_child_node(db, InFile::new(file_id.into(), &module.syntax()));
     let module = hir::Module::from_definition(db, InFile::new(file_id.into(), src))?;
 
@@ -73,6 +74,93 @@ fn runnable_mod(db: &RootDatabase, file_id: FileId, module: ast::Module) -> Opti
     Some(Runnable { range, kind: RunnableKind::TestMod { path } })
 }
 
+/// A runnable for the doc-tests of an item, if its doc comment has a code
+/// block which `cargo test --doc` runs.
+fn runnable_doc_test(db: &RootDatabase, file_id: FileId, item: SyntaxNode) -> Option<Runnable> {
+    let (name, docs) = match_ast! {
+        match item {
+            ast::FnDef(it) => { (it.name()?, it.doc_comment_text()?) },
+            ast::StructDef(it) => { (it.name()?, it.doc_comment_text()?) },
+            ast::EnumDef(it) => { (it.name()?, it.doc_comment_text()?) },
+            ast::UnionDef(it) => { (it.name()?, it.doc_comment_text()?) },
+            ast::TraitDef(it) => { (it.name()?, it.doc_comment_text()?) },
+            ast::TypeAliasDef(it) => { (it.name()?, it.doc_comment_text()?) },
+            ast::ConstDef(it) => { (it.name()?, it.doc_comment_text()?) },
+            ast::StaticDef(it) => { (it.name()?, it.doc_comment_text()?) },
+            _ => { return None },
+        }
+    };
+    // the items of function bodies are not documented
+    if item.ancestors().skip(1).any(|it| ast::Block::can_cast(it.kind())) {
+        return None;
+    }
+    if !has_runnable_code_block(&docs) {
+        return None;
+    }
+
+    let src = hir::ModuleSource::from_child_node(db, InFile::new(file_id.into(), &item));
+    let module = hir::Module::from_definition(db, InFile::new(file_id.into(), src))?;
+    let mut path = module
+        .path_to_root(db)
+        .into_iter()
+        .rev()
+        .filter_map(|it| it.name(db))
+        .map(|it| it.to_string())
+        .collect::<Vec<_>>();
+    // rustdoc names associated items after their type or trait
+    let container = item.parent().and_then(|it| it.parent());
+    if let Some(impl_block) = container.clone().and_then(ast::ImplBlock::cast) {
+        path.push(impl_block.target_type()?.syntax().to_string());
+    } else if let Some(trait_def) = container.and_then(ast::TraitDef::cast) {
+        path.push(trait_def.name()?.text().to_string());
+    }
+    path.push(name.text().to_string());
+    Some(Runnable {
+        range: item.text_range(),
+        kind: RunnableKind::DocTest { path: path.join("::") },
+    })
+}
+
+/// Whether one of the fenced code blocks of the docs is a doc-test which is
+/// run, i.e. it is Rust code and isn't marked `ignore` or `no_run`.
+fn has_runnable_code_block(docs: &str) -> bool {
+    let mut in_code_block = false;
+    for line in docs.lines() {
+        let line = line.trim_start();
+        if !line.starts_with("```") {
+            continue;
+        }
+        if in_code_block {
+            in_code_block = false;
+            continue;
+        }
+        in_code_block = true;
+        let mut attrs = line["```".len()..]
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|it| !it.is_empty());
+        let is_run = attrs.all(|attr| match attr {
+            "rust" | "should_panic" | "compile_fail" | "test_harness" | "allow_fail" => true,
+            _ => attr.starts_with("edition"),
+        });
+        if is_run {
+            return true;
+        }
+    }
+    false
+}
+
+/// The range from the `mod` keyword to the end of the name of the module,
+/// leaving out its attributes and items.
+fn header_range(module: &ast::Module) -> TextRange {
+    let syntax = module.syntax();
+    let start = syntax
+        .children_with_tokens()
+        .find(|it| it.kind() == T![mod])
+        .map_or(syntax.text_range().start(), |it| it.text_range().start());
+    let end = module.name().map_or(syntax.text_range().end(), |it| it.syntax().text_range().end());
+    TextRange::from_to(start, end)
+}
+
 #[cfg(test)]
 mod tests {
     use insta::assert_debug_snapshot;
@@ -120,6 +208,63 @@ mod tests {
                 );
     }
 
+    #[test]
+    fn test_runnables_doc_test() {
+        let (analysis, pos) = analysis_and_position(
+            r#"
+        //- /lib.rs
+        <|> //empty
+        fn main() {}
+
+        /// ```
+        /// let x = 5;
+        /// ```
+        fn foo() {}
+
+        /// ```ignore
+        /// let x = 5;
+        /// ```
+        fn bar() {}
+
+        /// ```text
+        /// not rust
+        /// ```
+        struct S;
+
+        struct Data;
+        impl Data {
+            /// ```rust,should_panic
+            /// let x = 5;
+            /// ```
+            fn baz() {}
+        }
+        "#,
+        );
+        let runnables = analysis.runnables(pos.file_id).unwrap();
+        assert_debug_snapshot!(&runnables,
+        @r###"
+        [
+            Runnable {
+                range: [1; 21),
+                kind: Bin,
+            },
+            Runnable {
+                range: [23; 65),
+                kind: DocTest {
+                    path: "foo",
+                },
+            },
+            Runnable {
+                range: [190; 261),
+                kind: DocTest {
+                    path: "Data::baz",
+                },
+            },
+        ]
+        "###
+                );
+    }
+
     #[test]
     fn test_runnables_module() {
         let (analysis, pos) = analysis_and_position(
@@ -137,7 +282,7 @@ mod tests {
         @r###"
         [
             Runnable {
-                range: [1; 59),
+                range: [9; 21),
                 kind: TestMod {
                     path: "test_mod",
                 },
@@ -172,7 +317,7 @@ mod tests {
         @r###"
         [
             Runnable {
-                range: [23; 85),
+                range: [23; 35),
                 kind: TestMod {
                     path: "foo::test_mod",
                 },
@@ -209,7 +354,7 @@ mod tests {
         @r###"
         [
             Runnable {
-                range: [41; 115),
+                range: [41; 53),
                 kind: TestMod {
                     path: "foo::bar::test_mod",
                 },
@@ -225,6 +370,40 @@ mod tests {
                 );
     }
 
+    #[test]
+    fn test_runnables_module_range_excludes_attributes() {
+        let (analysis, pos) = analysis_and_position(
+            r#"
+        //- /lib.rs
+        <|> //empty
+        #[cfg(test)]
+        mod tests {
+            #[test]
+            fn test_foo1() {}
+        }
+        "#,
+        );
+        let runnables = analysis.runnables(pos.file_id).unwrap();
+        assert_debug_snapshot!(&runnables,
+        @r###"
+        [
+            Runnable {
+                range: [22; 31),
+                kind: TestMod {
+                    path: "tests",
+                },
+            },
+            Runnable {
+                range: [38; 67),
+                kind: Test {
+                    name: "test_foo1",
+                },
+            },
+        ]
+        "###
+                );
+    }
+
     #[test]
     fn test_runnables_no_test_function_in_module() {
         let (analysis, pos) = analysis_and_position(
@@ -4,10 +4,13 @@
 //! It can be viewed as a dual for `AnalysisChange`.
 
 use ra_db::RelativePathBuf;
-use ra_text_edit::TextEdit;
+use ra_text_edit::{TextEdit, TextEditBuilder};
 
 use crate::{FileId, FilePosition, SourceRootId, TextUnit};
 
+/// A change which is applied by the client as a whole, in a single undoable
+/// step. The edits of a file are merged into a single `SourceFileEdit`, which
+/// the client applies before the `FileSystemEdit`s.
 #[derive(Debug)]
 pub struct SourceChange {
     pub label: String,
@@ -26,7 +29,7 @@ impl SourceChange {
     ) -> Self {
         SourceChange {
             label: label.into(),
-            source_file_edits,
+            source_file_edits: merge_by_file(source_file_edits),
             file_system_edits,
             cursor_position: None,
         }
@@ -37,7 +40,7 @@ impl SourceChange {
     pub(crate) fn source_file_edits<L: Into<String>>(label: L, edits: Vec<SourceFileEdit>) -> Self {
         SourceChange {
             label: label.into(),
-            source_file_edits: edits,
+            source_file_edits: merge_by_file(edits),
             file_system_edits: vec![],
             cursor_position: None,
         }
@@ -95,6 +98,38 @@ pub struct SourceFileEdit {
     pub edit: TextEdit,
 }
 
+/// Merges the edits of the same file, which clients would otherwise apply one
+/// after the other, each of them to the text changed by the previous ones.
+fn merge_by_file(edits: Vec<SourceFileEdit>) -> Vec<SourceFileEdit> {
+    let mut res: Vec<SourceFileEdit> = Vec::new();
+    for edit in edits {
+        let existing = match res.iter_mut().find(|it| it.file_id == edit.file_id) {
+            Some(it) => it,
+            None => {
+                res.push(edit);
+                continue;
+            }
+        };
+        let mut builder = TextEditBuilder::default();
+        for atom in existing.edit.as_atoms() {
+            builder.replace(atom.delete, atom.insert.clone());
+        }
+        for atom in edit.edit.as_atoms() {
+            // the same range can be found several times, e.g. through macros
+            let is_duplicate = existing
+                .edit
+                .as_atoms()
+                .iter()
+                .any(|it| it.delete == atom.delete && it.insert == atom.insert);
+            if !is_duplicate {
+                builder.replace(atom.delete, atom.insert.clone());
+            }
+        }
+        existing.edit = builder.finish();
+    }
+    res
+}
+
 #[derive(Debug)]
 pub enum FileSystemEdit {
     CreateFile { source_root: SourceRootId, path: RelativePathBuf },
@@ -117,3 +152,34 @@ impl SingleFileChange {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ra_text_edit::TextEdit;
+    use test_utils::assert_eq_text;
+
+    use super::*;
+    use crate::TextRange;
+
+    #[test]
+    fn merges_edits_of_the_same_file() {
+        let file_id = FileId(0);
+        let other_file_id = FileId(1);
+        let edit = |start: u32, end: u32| SourceFileEdit {
+            file_id,
+            edit: TextEdit::replace(TextRange::from_to(start.into(), end.into()), "bar".into()),
+        };
+        let edits = vec![
+            edit(10, 13),
+            SourceFileEdit { file_id: other_file_id, edit: TextEdit::insert(0.into(), "x".into()) },
+            edit(0, 3),
+            edit(10, 13),
+        ];
+        let change = SourceChange::source_file_edits("rename", edits);
+        assert_eq!(change.source_file_edits.len(), 2);
+        assert_eq!(change.source_file_edits[0].file_id, file_id);
+        assert_eq!(change.source_file_edits[1].file_id, other_file_id);
+        let actual = change.source_file_edits[0].edit.apply("foo(); // foo");
+        assert_eq_text!("bar(); // bar", &actual);
+    }
+}
@@ -0,0 +1,507 @@
+//! Structural search and replace: rewrites the expressions of a crate which
+//! match a pattern, like `foo($a, $b) ==>> bar($b, $a)`.
+//!
+//! Placeholders (`$a`) match any expression, and are substituted by the text
+//! of what they matched in the template. A placeholder can be constrained by a
+//! type, like `$v: Vec<_>`, in which case the type of the expression it
+//! matches is inferred and compared with the constraint, where `_` stands for
+//! any type.
+
+use std::fmt;
+
+use hir::{InFile, SourceAnalyzer};
+use ra_db::SourceDatabase;
+use ra_syntax::{ast, AstNode, SmolStr, SourceFile, SyntaxElement, SyntaxKind, SyntaxNode};
+use ra_text_edit::TextEditBuilder;
+use rustc_hash::FxHashMap;
+
+use crate::{db::RootDatabase, FileId, SourceChange, SourceFileEdit};
+
+#[derive(Debug, PartialEq)]
+pub struct SsrError(String);
+
+impl fmt::Display for SsrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Parse error: {}", self.0)
+    }
+}
+
+impl std::error::Error for SsrError {}
+
+/// Placeholders are replaced by identifiers of this prefix in the pattern, so
+/// that it can be parsed as an expression.
+const PLACEHOLDER_PREFIX: &str = "__ssr_placeholder_";
+
+#[derive(Debug)]
+struct SsrRule {
+    pattern: SyntaxNode,
+    /// The type constraints of the placeholders, by identifier.
+    constraints: FxHashMap<SmolStr, Option<String>>,
+    template: String,
+}
+
+/// The expressions bound to the placeholders of a match, by identifier.
+type Bindings = FxHashMap<SmolStr, ast::Expr>;
+
+/// Applies the rule to all the files of the crates `file_id` belongs to.
+pub(crate) fn ssr_across_files(
+    db: &RootDatabase,
+    file_id: FileId,
+    query: &str,
+) -> Result<SourceChange, SsrError> {
+    let rule = parse_rule(query)?;
+    let edits = crate_files(db, file_id)
+        .into_iter()
+        .filter_map(|file_id| replace_in_file(db, file_id, &rule))
+        .collect();
+    Ok(SourceChange::source_file_edits("structural search replace", edits))
+}
+
+fn crate_files(db: &RootDatabase, file_id: FileId) -> Vec<FileId> {
+    let mut res = Vec::new();
+    let mut roots: Vec<hir::Module> = Vec::new();
+    for module in hir::Module::all_from_file(db, file_id) {
+        let root = module.crate_root(db);
+        if !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+    let mut stack = roots;
+    while let Some(module) = stack.pop() {
+        let file_id = module.definition_source(db).file_id.original_file(db);
+        if !res.contains(&file_id) {
+            res.push(file_id);
+        }
+        stack.extend(module.children(db));
+    }
+    res
+}
+
+fn replace_in_file(db: &RootDatabase, file_id: FileId, rule: &SsrRule) -> Option<SourceFileEdit> {
+    let file = db.parse(file_id).tree();
+    let mut builder = TextEditBuilder::default();
+    if !replace_in_node(db, file_id, rule, file.syntax(), &mut builder) {
+        return None;
+    }
+    Some(SourceFileEdit { file_id, edit: builder.finish() })
+}
+
+/// Returns whether there was a match. Matches don't overlap: the outermost
+/// one wins.
+fn replace_in_node(
+    db: &RootDatabase,
+    file_id: FileId,
+    rule: &SsrRule,
+    node: &SyntaxNode,
+    builder: &mut TextEditBuilder,
+) -> bool {
+    if let Some(bindings) = match_rule(db, file_id, rule, node) {
+        builder.replace(node.text_range(), render_template(&rule.template, &bindings));
+        return true;
+    }
+    let mut has_matches = false;
+    for child in node.children() {
+        has_matches |= replace_in_node(db, file_id, rule, &child, builder);
+    }
+    has_matches
+}
+
+fn match_rule(
+    db: &RootDatabase,
+    file_id: FileId,
+    rule: &SsrRule,
+    node: &SyntaxNode,
+) -> Option<Bindings> {
+    let mut bindings = Bindings::default();
+    if !match_node(&rule.pattern, node, &rule.constraints, &mut bindings) {
+        return None;
+    }
+    for (name, expr) in bindings.iter() {
+        let constraint = match &rule.constraints[name] {
+            Some(it) => it,
+            None => continue,
+        };
+        let analyzer = SourceAnalyzer::new(db, InFile::new(file_id.into(), expr.syntax()), None);
+        let ty = analyzer.type_of(db, expr)?;
+        if ty.is_unknown() {
+            return None;
+        }
+        if !type_matches(constraint, &ty.display_truncated(db, None).to_string()) {
+            return None;
+        }
+    }
+    Some(bindings)
+}
+
+/// Matches the syntax trees, ignoring whitespace and comments.
+fn match_node(
+    pattern: &SyntaxNode,
+    code: &SyntaxNode,
+    constraints: &FxHashMap<SmolStr, Option<String>>,
+    bindings: &mut Bindings,
+) -> bool {
+    if let Some(name) = placeholder_name(pattern, constraints) {
+        let expr = match ast::Expr::cast(code.clone()) {
+            Some(it) => it,
+            None => return false,
+        };
+        // a placeholder used twice matches the same expression twice
+        if let Some(bound) = bindings.get(&name) {
+            return bound.syntax().to_string() == expr.syntax().to_string();
+        }
+        bindings.insert(name, expr);
+        return true;
+    }
+    if pattern.kind() != code.kind() {
+        return false;
+    }
+    let mut pattern_children = significant_children(pattern);
+    let mut code_children = significant_children(code);
+    loop {
+        match (pattern_children.next(), code_children.next()) {
+            (None, None) => return true,
+            (Some(SyntaxElement::Node(p)), Some(SyntaxElement::Node(c))) => {
+                if !match_node(&p, &c, constraints, bindings) {
+                    return false;
+                }
+            }
+            (Some(SyntaxElement::Token(p)), Some(SyntaxElement::Token(c))) => {
+                if p.kind() != c.kind() || p.text() != c.text() {
+                    return false;
+                }
+            }
+            _ => return false,
+        }
+    }
+}
+
+fn significant_children(node: &SyntaxNode) -> impl Iterator<Item = SyntaxElement> {
+    node.children_with_tokens()
+        .filter(|it| it.kind() != SyntaxKind::WHITESPACE && it.kind() != SyntaxKind::COMMENT)
+}
+
+fn placeholder_name(
+    node: &SyntaxNode,
+    constraints: &FxHashMap<SmolStr, Option<String>>,
+) -> Option<SmolStr> {
+    let path = ast::PathExpr::cast(node.clone())?.path()?;
+    if path.qualifier().is_some() {
+        return None;
+    }
+    let name_ref = path.segment()?.name_ref()?;
+    let name = name_ref.text().clone();
+    if constraints.contains_key(&name) {
+        Some(name)
+    } else {
+        None
+    }
+}
+
+fn render_template(template: &str, bindings: &Bindings) -> String {
+    let mut res = template.to_string();
+    // longer names first, so that `$ab` isn't rendered as the value of `$a`
+    // followed by `b`
+    let mut names: Vec<&SmolStr> = bindings.keys().collect();
+    names.sort_by_key(|it| std::cmp::Reverse(it.len()));
+    for name in names {
+        res = res.replace(name.as_str(), &bindings[name].syntax().text().to_string());
+    }
+    res
+}
+
+fn parse_rule(query: &str) -> Result<SsrRule, SsrError> {
+    let mut parts = query.split("==>>");
+    let (pattern, template) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(pattern), Some(template), None) => (pattern.trim(), template.trim()),
+        _ => {
+            return Err(SsrError("expected one `==>>` between the pattern and the template".into()))
+        }
+    };
+
+    let mut constraints = FxHashMap::default();
+    let pattern = replace_placeholders(pattern, Some(&mut constraints))?;
+    let template = replace_placeholders(template, None)?;
+    for name in template_placeholders(&template) {
+        if !constraints.contains_key(name.as_str()) {
+            let name = &name[PLACEHOLDER_PREFIX.len()..];
+            return Err(SsrError(format!("`${}` is not a placeholder of the pattern", name)));
+        }
+    }
+
+    let pattern = parse_expr(&pattern)
+        .ok_or_else(|| SsrError(format!("`{}` is not an expression", pattern)))?;
+    Ok(SsrRule { pattern, constraints, template })
+}
+
+fn parse_expr(text: &str) -> Option<SyntaxNode> {
+    let parse = SourceFile::parse(&format!("fn f() {{ {} }}", text));
+    if !parse.errors().is_empty() {
+        return None;
+    }
+    let block = parse.tree().syntax().descendants().find_map(ast::Block::cast)?;
+    if block.statements().next().is_some() {
+        return None;
+    }
+    block.expr().map(|it| it.syntax().clone())
+}
+
+/// Replaces `$name` with placeholder identifiers. In the pattern, a
+/// placeholder may be followed by `: Type`, which is recorded as its
+/// constraint.
+fn replace_placeholders(
+    text: &str,
+    mut constraints: Option<&mut FxHashMap<SmolStr, Option<String>>>,
+) -> Result<String, SsrError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut res = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] != '$' {
+            res.push(chars[i]);
+            i += 1;
+            continue;
+        }
+        i += 1;
+        let start = i;
+        while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+            i += 1;
+        }
+        if i == start {
+            return Err(SsrError("expected a placeholder name after `$`".into()));
+        }
+        let name: SmolStr =
+            format!("{}{}", PLACEHOLDER_PREFIX, chars[start..i].iter().collect::<String>()).into();
+        res.push_str(&name);
+
+        let constraints = match &mut constraints {
+            Some(it) => it,
+            None => continue,
+        };
+        let mut constraint = None;
+        let mut j = i;
+        while j < chars.len() && chars[j].is_whitespace() {
+            j += 1;
+        }
+        // `::` would be a path, not a constraint
+        if j < chars.len() && chars[j] == ':' && chars.get(j + 1) != Some(&':') {
+            let (ty, end) = parse_constraint(&chars, j + 1)?;
+            constraint = Some(ty);
+            i = end;
+        }
+        // a placeholder may be used several times, but constrained once
+        match constraints.get(&name) {
+            Some(Some(_)) if constraint.is_some() => {
+                return Err(SsrError(format!(
+                    "`${}` is constrained twice",
+                    &name[PLACEHOLDER_PREFIX.len()..]
+                )));
+            }
+            Some(Some(_)) => (),
+            _ => {
+                constraints.insert(name, constraint);
+            }
+        }
+    }
+    Ok(res)
+}
+
+/// Reads the type of a constraint starting at `start`, until the first
+/// character which can't continue it outside of brackets. Returns the type,
+/// without whitespace, and the index of the end of the type.
+fn parse_constraint(chars: &[char], start: usize) -> Result<(String, usize), SsrError> {
+    let mut i = start;
+    while i < chars.len() && chars[i].is_whitespace() {
+        i += 1;
+    }
+    let mut ty = String::new();
+    let mut depth = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' if depth > 0 => depth -= 1,
+            _ if depth > 0 => (),
+            c if c.is_alphanumeric() || c == '_' || c == ':' || c == '&' => (),
+            // `&mut T`
+            c if c.is_whitespace() && ty.ends_with("&mut") => {
+                i += 1;
+                continue;
+            }
+            _ => break,
+        }
+        if !c.is_whitespace() {
+            ty.push(c);
+        }
+        i += 1;
+    }
+    if ty.is_empty() || depth != 0 {
+        return Err(SsrError("expected a type after `:`".into()));
+    }
+    Ok((ty, i))
+}
+
+fn template_placeholders(template: &str) -> Vec<String> {
+    let mut res = Vec::new();
+    let mut rest = template;
+    while let Some(idx) = rest.find(PLACEHOLDER_PREFIX) {
+        rest = &rest[idx..];
+        let end = rest
+            .char_indices()
+            .find(|(_, c)| !c.is_alphanumeric() && *c != '_')
+            .map_or(rest.len(), |(idx, _)| idx);
+        res.push(rest[..end].to_string());
+        rest = &rest[end..];
+    }
+    res
+}
+
+/// Whether the displayed type matches the constraint, where `_` matches any
+/// type. Whitespace is ignored.
+fn type_matches(constraint: &str, ty: &str) -> bool {
+    let constraint: Vec<char> = constraint.chars().filter(|it| !it.is_whitespace()).collect();
+    let ty: Vec<char> = ty.chars().filter(|it| !it.is_whitespace()).collect();
+    return go(&constraint, &ty);
+
+    fn go(constraint: &[char], ty: &[char]) -> bool {
+        let (first, rest) = match constraint.split_first() {
+            Some(it) => it,
+            None => return ty.is_empty(),
+        };
+        let is_wildcard =
+            *first == '_' && rest.first().map_or(true, |it| !it.is_alphanumeric() && *it != '_');
+        if !is_wildcard {
+            return ty.first() == Some(first) && go(rest, &ty[1..]);
+        }
+        // `_` matches a whole type: a prefix of balanced brackets which ends
+        // at the same level it started
+        let mut depth = 0;
+        for (i, c) in ty.iter().enumerate() {
+            match c {
+                '<' | '(' | '[' => depth += 1,
+                '>' | ')' | ']' => {
+                    if depth == 0 {
+                        return false;
+                    }
+                    depth -= 1
+                }
+                ',' if depth == 0 => return false,
+                _ => (),
+            }
+            if depth == 0 && go(rest, &ty[i + 1..]) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock_analysis::{analysis_and_position, single_file};
+
+    use super::*;
+
+    fn check(query: &str, before: &str, after: &str) {
+        let (analysis, file_id) = single_file(before);
+        let change = analysis.ssr_across_files(file_id, query).unwrap().unwrap();
+        let text = analysis.file_text(file_id).unwrap();
+        let actual = match change.source_file_edits.first() {
+            Some(edit) => edit.edit.apply(&text),
+            None => text.to_string(),
+        };
+        test_utils::assert_eq_text!(after, &actual);
+    }
+
+    #[test]
+    fn ssr_parses_rules() {
+        let rule = parse_rule("foo($a, $b) ==>> bar($b, $a)").unwrap();
+        assert_eq!(rule.template, "bar(__ssr_placeholder_b, __ssr_placeholder_a)");
+        assert_eq!(rule.constraints.len(), 2);
+
+        let rule = parse_rule("$v: Vec<_>.len() ==>> $v.count()").unwrap();
+        assert_eq!(rule.constraints["__ssr_placeholder_v"], Some("Vec<_>".to_string()));
+        assert_eq!(rule.pattern.to_string(), "__ssr_placeholder_v.len()");
+
+        assert!(parse_rule("foo($a)").is_err());
+        assert!(parse_rule("foo($a) ==>> bar($b)").is_err());
+        assert!(parse_rule("foo($a: u32, $a: i32) ==>> bar($a)").is_err());
+        assert!(parse_rule("let x = 1; ==>> 2").is_err());
+    }
+
+    #[test]
+    fn ssr_type_constraints() {
+        assert!(type_matches("Vec<_>", "Vec<i32>"));
+        assert!(type_matches("Vec<_>", "Vec<(i32, Option<u8>)>"));
+        assert!(type_matches("HashMap<_, String>", "HashMap<u32, String>"));
+        assert!(type_matches("&mut _", "&mut Foo"));
+        assert!(!type_matches("Vec<_>", "Option<i32>"));
+        assert!(!type_matches("Vec<_>", "VecDeque<i32>"));
+        assert!(!type_matches("HashMap<_>", "HashMap<u32, String>"));
+        assert!(!type_matches("_", "Foo<Bar"));
+    }
+
+    #[test]
+    fn ssr_swaps_arguments() {
+        check(
+            "foo($a, $b) ==>> foo($b, $a)",
+            "fn foo(x: u32, y: u32) {} fn main() { foo(1 + 2, bar(3)); foo(4, 5) }",
+            "fn foo(x: u32, y: u32) {} fn main() { foo(bar(3), 1 + 2); foo(5, 4) }",
+        );
+    }
+
+    #[test]
+    fn ssr_ignores_whitespace_and_matches_outermost() {
+        check("foo($a) ==>> bar($a)", "fn main() { foo( foo(1) ) }", "fn main() { bar(foo(1)) }");
+    }
+
+    #[test]
+    fn ssr_repeated_placeholder_matches_same_expression() {
+        check(
+            "$a == $a ==>> true",
+            "fn main() { let x = 1; if x == x {} if x == 2 {} }",
+            "fn main() { let x = 1; if true {} if x == 2 {} }",
+        );
+    }
+
+    #[test]
+    fn ssr_with_type_constraint() {
+        check(
+            "$v: Vec<_>.len() ==>> $v.count()",
+            r#"
+struct Vec<T>(T);
+impl<T> Vec<T> { fn len(&self) -> usize { 0 } }
+struct Set;
+impl Set { fn len(&self) -> usize { 0 } }
+fn main(v: Vec<u32>, s: Set) { v.len(); s.len(); }
+"#,
+            r#"
+struct Vec<T>(T);
+impl<T> Vec<T> { fn len(&self) -> usize { 0 } }
+struct Set;
+impl Set { fn len(&self) -> usize { 0 } }
+fn main(v: Vec<u32>, s: Set) { v.count(); s.len(); }
+"#,
+        );
+    }
+
+    #[test]
+    fn ssr_across_files_of_the_crate() {
+        let (analysis, position) = analysis_and_position(
+            "
+            //- /lib.rs
+            mod foo;
+            fn f() { old(1) }<|>
+            //- /foo.rs
+            fn g() { old(2) }
+            ",
+        );
+        let change = analysis.ssr_across_files(position.file_id, "old($x) ==>> new($x)").unwrap();
+        let mut edits = change.unwrap().source_file_edits;
+        edits.sort_by_key(|it| it.file_id);
+        assert_eq!(edits.len(), 2);
+        for (edit, expected) in edits.iter().zip(&["fn f() { new(1) }", "fn g() { new(2) }"]) {
+            let text = analysis.file_text(edit.file_id).unwrap();
+            assert!(edit.edit.apply(&text).contains(expected));
+        }
+    }
+}
@@ -5,7 +5,10 @@ use rustc_hash::{FxHashMap, FxHashSet};
 use hir::{InFile, Name};
 use ra_db::SourceDatabase;
 use ra_prof::profile;
-use ra_syntax::{ast, AstNode, Direction, SyntaxElement, SyntaxKind, SyntaxKind::*, TextRange, T};
+use ra_syntax::{
+    algo::find_covering_element, ast, AstNode, Direction, NodeOrToken, SyntaxElement, SyntaxKind,
+    SyntaxKind::*, SyntaxNode, TextRange, T,
+};
 
 use crate::{
     db::RootDatabase,
@@ -66,11 +69,30 @@ fn is_control_keyword(kind: SyntaxKind) -> bool {
     }
 }
 
-pub(crate) fn highlight(db: &RootDatabase, file_id: FileId) -> Vec<HighlightedRange> {
+/// Highlights the whole file, or only the elements overlapping `range` if it
+/// is given.
+pub(crate) fn highlight(
+    db: &RootDatabase,
+    file_id: FileId,
+    range: Option<TextRange>,
+) -> Vec<HighlightedRange> {
     let _p = profile("highlight");
     let parse = db.parse(file_id);
     let root = parse.tree().syntax().clone();
 
+    // The shadowing counts of bindings are computed from the start of the
+    // function, so the function overlapping the start of the range is visited
+    // from its first token to keep the binding hashes the same as when
+    // highlighting the whole file.
+    let walk_range = range.map(|range| {
+        let start = find_covering_element(&root, TextRange::offset_len(range.start(), 0.into()))
+            .ancestors()
+            .filter(|it| it.kind() == FN_DEF)
+            .last()
+            .map_or(range.start(), |it| it.text_range().start());
+        TextRange::from_to(start, range.end())
+    });
+
     fn calc_binding_hash(file_id: FileId, name: &Name, shadow_count: u32) -> u64 {
         fn hash<T: std::hash::Hash + std::fmt::Debug>(x: T) -> u64 {
             use std::{collections::hash_map::DefaultHasher, hash::Hasher};
@@ -88,8 +110,19 @@ pub(crate) fn highlight(db: &RootDatabase, file_id: FileId) -> Vec<HighlightedRa
     let mut highlighted: FxHashSet<SyntaxElement> = FxHashSet::default();
     let mut bindings_shadow_count: FxHashMap<Name, u32> = FxHashMap::default();
 
+    let elements: Box<dyn Iterator<Item = SyntaxElement>> = match walk_range {
+        Some(walk_range) => {
+            let walk_root = match find_covering_element(&root, walk_range) {
+                NodeOrToken::Node(it) => it,
+                NodeOrToken::Token(it) => it.parent(),
+            };
+            Box::new(elements_overlapping(walk_root, walk_range).into_iter())
+        }
+        None => Box::new(root.descendants_with_tokens()),
+    };
+
     let mut res = Vec::new();
-    for node in root.descendants_with_tokens() {
+    for node in elements {
         if highlighted.contains(&node) {
             continue;
         }
@@ -177,9 +210,34 @@ pub(crate) fn highlight(db: &RootDatabase, file_id: FileId) -> Vec<HighlightedRa
         };
         res.push(HighlightedRange { range: node.text_range(), tag, binding_hash })
     }
+    if let Some(range) = range {
+        res.retain(|it| overlaps(it.range, range));
+    }
     res
 }
 
+/// The elements of `node` overlapping `range` in preorder, without descending
+/// into the ones outside of it.
+fn elements_overlapping(node: SyntaxNode, range: TextRange) -> Vec<SyntaxElement> {
+    let mut res = Vec::new();
+    let mut stack: Vec<SyntaxElement> = vec![node.into()];
+    while let Some(element) = stack.pop() {
+        if !overlaps(element.text_range(), range) {
+            continue;
+        }
+        if let NodeOrToken::Node(node) = &element {
+            let children: Vec<_> = node.children_with_tokens().collect();
+            stack.extend(children.into_iter().rev());
+        }
+        res.push(element);
+    }
+    res
+}
+
+fn overlaps(a: TextRange, b: TextRange) -> bool {
+    a.start() < b.end() && b.start() < a.end()
+}
+
 pub(crate) fn highlight_as_html(db: &RootDatabase, file_id: FileId, rainbow: bool) -> String {
     let parse = db.parse(file_id);
 
@@ -194,7 +252,7 @@ pub(crate) fn highlight_as_html(db: &RootDatabase, file_id: FileId, rainbow: boo
         )
     }
 
-    let mut ranges = highlight(db, file_id);
+    let mut ranges = highlight(db, file_id, None);
     ranges.sort_by_key(|it| it.range.start());
     // quick non-optimal heuristic to intersect token ranges and highlighted ranges
     let mut frontier = 0;
@@ -301,6 +359,9 @@ pre                 { color: #DCDCCC; background: #3F3F3F; font-size: 22px; padd
 
 #[cfg(test)]
 mod tests {
+    use ra_db::FileRange;
+    use ra_syntax::TextRange;
+
     use crate::mock_analysis::single_file;
     use test_utils::{assert_eq_text, project_dir, read_text};
 
@@ -379,4 +440,39 @@ fn bar() {
         std::fs::write(dst_file, &actual_html).unwrap();
         assert_eq_text!(expected_html, actual_html);
     }
+
+    #[test]
+    fn test_ranged_highlighting() {
+        let (analysis, file_id) = single_file(
+            r#"
+fn foo() {
+    let x = 92;
+    let x = x + 1;
+    let y = x;
+}
+
+fn bar() -> u32 {
+    92
+}
+"#
+            .trim(),
+        );
+        // from the second `let` up to the middle of `bar`
+        let range = TextRange::from_to(31.into(), 70.into());
+        let in_range = analysis
+            .semantic_tokens_range(FileRange { file_id, range })
+            .unwrap()
+            .into_iter()
+            .map(|it| (it.range, it.tag, it.binding_hash))
+            .collect::<Vec<_>>();
+        let expected = analysis
+            .highlight(file_id)
+            .unwrap()
+            .into_iter()
+            .filter(|it| it.range.start() < range.end() && range.start() < it.range.end())
+            .map(|it| (it.range, it.tag, it.binding_hash))
+            .collect::<Vec<_>>();
+        assert!(!in_range.is_empty());
+        assert_eq!(in_range, expected);
+    }
 }
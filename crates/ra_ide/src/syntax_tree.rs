@@ -236,6 +236,22 @@ EXPR_STMT@[16; 58)
         );
     }
 
+    #[test]
+    fn test_syntax_tree_with_range_inside_token() {
+        // the node of the token is dumped, so that its range is visible
+        let (analysis, range) = single_file_with_range(r#"fn f<|>o<|>o() {}"#);
+        let syn = analysis.syntax_tree(range.file_id, Some(range.range)).unwrap();
+
+        assert_eq_text!(
+            syn.trim(),
+            r#"
+NAME@[3; 6)
+  IDENT@[3; 6) "foo"
+"#
+            .trim()
+        );
+    }
+
     #[test]
     fn test_syntax_tree_inside_string() {
         let (analysis, range) = single_file_with_range(
@@ -84,7 +84,7 @@ fn node_indent(file: &SourceFile, token: &SyntaxToken) -> Option<SmolStr> {
     Some(text[pos..].into())
 }
 
-pub(crate) const TRIGGER_CHARS: &str = ".=>";
+pub(crate) const TRIGGER_CHARS: &str = ".=>{}";
 
 pub(crate) fn on_char_typed(
     db: &RootDatabase,
@@ -92,6 +92,12 @@ pub(crate) fn on_char_typed(
     char_typed: char,
 ) -> Option<SourceChange> {
     assert!(TRIGGER_CHARS.contains(char_typed));
+    if char_typed == '{' && !db.feature_flags.get("typing.insert-closing-brace") {
+        return None;
+    }
+    if char_typed == '}' && !db.feature_flags.get("typing.reindent-closing-brace") {
+        return None;
+    }
     let file = &db.parse(position.file_id).tree();
     assert_eq!(file.syntax().text().char_at(position.offset), Some(char_typed));
     let single_file_change = on_char_typed_inner(file, position.offset, char_typed)?;
@@ -108,6 +114,8 @@ fn on_char_typed_inner(
         '.' => on_dot_typed(file, offset),
         '=' => on_eq_typed(file, offset),
         '>' => on_arrow_typed(file, offset),
+        '{' => on_brace_typed(file, offset),
+        '}' => on_closing_brace_typed(file, offset),
         _ => unreachable!(),
     }
 }
@@ -193,6 +201,84 @@ fn on_arrow_typed(file: &SourceFile, offset: TextUnit) -> Option<SingleFileChang
     })
 }
 
+/// Inserts a matching `}` and an indented empty line when `{` is typed at the
+/// end of a line, like in `if cond {`.
+fn on_brace_typed(file: &SourceFile, offset: TextUnit) -> Option<SingleFileChange> {
+    assert_eq!(file.syntax().text().char_at(offset), Some('{'));
+    let brace = file.syntax().token_at_offset(offset).right_biased()?;
+    if brace.kind() != L_CURLY {
+        return None;
+    }
+    match brace.next_token() {
+        Some(ws) if ws.kind() == WHITESPACE && ws.text().contains('\n') => (),
+        _ => return None,
+    }
+    let block = brace.parent().and_then(ast::Block::cast)?;
+
+    // If the braces are balanced, the `{` was typed in front of an existing
+    // `}`, and we don't need to add another one.
+    let depth = file.syntax().descendants_with_tokens().filter_map(|it| it.into_token()).fold(
+        0i32,
+        |depth, token| match token.kind() {
+            L_CURLY => depth + 1,
+            R_CURLY => depth - 1,
+            _ => depth,
+        },
+    );
+    if depth <= 0 {
+        return None;
+    }
+
+    let indent = leading_indent(block.syntax()).unwrap_or_default();
+    let after_brace = offset + TextUnit::of_char('{');
+    let inserted = format!("\n{}    \n{}}}", indent, indent);
+    let cursor_position = after_brace + TextUnit::of_str(&indent) + TextUnit::from(5);
+    Some(SingleFileChange {
+        label: "insert closing brace".to_string(),
+        edit: TextEdit::insert(after_brace, inserted),
+        cursor_position: Some(cursor_position),
+    })
+}
+
+/// Reindents a `}` typed at the start of a line to the indentation of the line
+/// of the matching `{`.
+fn on_closing_brace_typed(file: &SourceFile, offset: TextUnit) -> Option<SingleFileChange> {
+    assert_eq!(file.syntax().text().char_at(offset), Some('}'));
+    let brace = file.syntax().token_at_offset(offset).right_biased()?;
+    if brace.kind() != R_CURLY {
+        return None;
+    }
+    let whitespace = brace.prev_token().and_then(ast::Whitespace::cast)?;
+    let current_indent = {
+        let text = whitespace.text();
+        let newline = text.rfind('\n')?;
+        &text[newline + 1..]
+    };
+    let current_indent_len = TextUnit::of_str(current_indent);
+
+    // The braces of a block or an item list are tokens of the same node
+    let braced = brace.parent();
+    if !braced.children_with_tokens().any(|it| it.kind() == L_CURLY) {
+        return None;
+    }
+    let target_indent = leading_indent(&braced).unwrap_or_default();
+    let target_indent_len = TextUnit::of_str(&target_indent);
+    if current_indent_len == target_indent_len {
+        return None;
+    }
+
+    Some(SingleFileChange {
+        label: "reindent closing brace".to_string(),
+        edit: TextEdit::replace(
+            TextRange::from_to(offset - current_indent_len, offset),
+            target_indent.to_string(),
+        ),
+        cursor_position: Some(
+            offset + target_indent_len - current_indent_len + TextUnit::of_char('}'),
+        ),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use test_utils::{add_cursor, assert_eq_text, extract_offset};
@@ -487,4 +573,108 @@ fn foo() {
     fn adds_space_after_return_type() {
         type_char('>', "fn foo() -<|>{ 92 }", "fn foo() -><|> { 92 }")
     }
+
+    #[test]
+    fn inserts_closing_brace() {
+        type_char(
+            '{',
+            r"
+fn foo() {
+    if true <|>
+    92
+}
+",
+            r"
+fn foo() {
+    if true {
+        <|>
+    }
+    92
+}
+",
+        );
+    }
+
+    #[test]
+    fn does_not_insert_closing_brace_if_balanced() {
+        type_char_noop(
+            '{',
+            r"
+fn foo() <|>
+    92
+}
+",
+        );
+        type_char_noop('{', "fn foo() <|>92 }");
+    }
+
+    #[test]
+    fn reindents_closing_brace() {
+        type_char(
+            '}',
+            r"
+fn foo() {
+    if true {
+        92
+        <|>
+}
+",
+            r"
+fn foo() {
+    if true {
+        92
+    }<|>
+}
+",
+        );
+        type_char(
+            '}',
+            r"
+impl S {
+    fn foo() {}
+    <|>
+",
+            r"
+impl S {
+    fn foo() {}
+}<|>
+",
+        );
+    }
+
+    #[test]
+    fn typing_braces_does_nothing_by_default() {
+        for (char_typed, before) in &[
+            ('{', "fn foo() {\n    if true <|>\n    92\n}\n"),
+            ('}', "fn foo() {\n    if true {\n        92\n        <|>\n}\n"),
+        ] {
+            let (offset, before) = extract_offset(before);
+            let text = TextEdit::insert(offset, char_typed.to_string()).apply(&before);
+            let (analysis, file_id) = single_file(&text);
+            let change = analysis.on_char_typed(FilePosition { offset, file_id }, *char_typed);
+            assert!(change.unwrap().is_none(), "typing `{}` did something", char_typed);
+        }
+    }
+
+    #[test]
+    fn does_not_reindent_closing_brace_after_code() {
+        type_char_noop(
+            '}',
+            r"
+fn foo() {
+    if true { 92 <|>
+}
+",
+        );
+        type_char_noop(
+            '}',
+            r"
+fn foo() {
+    if true {
+        92
+    <|>
+}
+",
+        );
+    }
 }
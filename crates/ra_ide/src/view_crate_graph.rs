@@ -0,0 +1,115 @@
+//! Dumps the crate graph, with the root file, edition, cfg options and
+//! dependencies of each crate, for debugging project models.
+
+use std::fmt::Write;
+
+use itertools::Itertools;
+use ra_db::{CrateGraph, CrateId, SourceDatabaseExt};
+
+use crate::db::RootDatabase;
+
+pub(crate) fn view_crate_graph(db: &RootDatabase, dot: bool) -> String {
+    let crate_graph = db.crate_graph();
+    let mut crates: Vec<CrateId> = crate_graph.iter().collect();
+    crates.sort();
+    if dot {
+        to_dot(db, &crate_graph, &crates)
+    } else {
+        to_text(db, &crate_graph, &crates)
+    }
+}
+
+fn to_text(db: &RootDatabase, crate_graph: &CrateGraph, crates: &[CrateId]) -> String {
+    let mut buf = String::new();
+    for &crate_id in crates {
+        writeln!(buf, "crate {}: {}", crate_id.0, root_path(db, crate_graph, crate_id)).unwrap();
+        writeln!(buf, "    edition: {}", crate_graph.edition(crate_id)).unwrap();
+        let cfg_options = cfg_options(crate_graph, crate_id);
+        if !cfg_options.is_empty() {
+            writeln!(buf, "    cfg: {}", cfg_options).unwrap();
+        }
+        for dep in crate_graph.dependencies(crate_id) {
+            writeln!(buf, "    dep {}: crate {}", dep.name, dep.crate_id().0).unwrap();
+        }
+    }
+    buf
+}
+
+fn to_dot(db: &RootDatabase, crate_graph: &CrateGraph, crates: &[CrateId]) -> String {
+    let mut buf = String::from("digraph crates {\n");
+    for &crate_id in crates {
+        writeln!(
+            buf,
+            "    crate{} [label=\"{}\\nedition {}\\n{}\"];",
+            crate_id.0,
+            root_path(db, crate_graph, crate_id),
+            crate_graph.edition(crate_id),
+            cfg_options(crate_graph, crate_id).replace('"', "\\\"")
+        )
+        .unwrap();
+    }
+    for &crate_id in crates {
+        for dep in crate_graph.dependencies(crate_id) {
+            writeln!(
+                buf,
+                "    crate{} -> crate{} [label={:?}];",
+                crate_id.0,
+                dep.crate_id().0,
+                dep.name.as_str()
+            )
+            .unwrap();
+        }
+    }
+    buf.push_str("}\n");
+    buf
+}
+
+fn root_path(db: &RootDatabase, crate_graph: &CrateGraph, crate_id: CrateId) -> String {
+    let file_id = crate_graph.crate_root(crate_id);
+    db.file_relative_path(file_id).to_string()
+}
+
+/// The enabled cfg options, sorted, like `test, feature="std"`.
+fn cfg_options(crate_graph: &CrateGraph, crate_id: CrateId) -> String {
+    let cfg_options = crate_graph.cfg_options(crate_id);
+    let atoms = cfg_options.atoms().map(|it| it.to_string()).sorted();
+    let key_values =
+        cfg_options.key_values().map(|(key, value)| format!("{}={:?}", key, value)).sorted();
+    atoms.chain(key_values).join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock_analysis::MockAnalysis;
+
+    const FIXTURE: &str = "
+        //- /main.rs
+        fn main() {}
+        //- /foo/lib.rs
+        pub fn foo() {}
+    ";
+
+    #[test]
+    fn dumps_crate_graph_as_text() {
+        let analysis = MockAnalysis::with_files(FIXTURE).analysis();
+        let graph = analysis.view_crate_graph(false).unwrap();
+        test_utils::assert_eq_text!(
+            &graph,
+            "\
+crate 0: main.rs
+    edition: 2018
+    dep foo: crate 1
+crate 1: foo/lib.rs
+    edition: 2018
+"
+        );
+    }
+
+    #[test]
+    fn dumps_crate_graph_as_dot() {
+        let analysis = MockAnalysis::with_files(FIXTURE).analysis();
+        let graph = analysis.view_crate_graph(true).unwrap();
+        assert!(graph.starts_with("digraph crates {\n"), "{}", graph);
+        assert!(graph.contains("    crate0 -> crate1 [label=\"foo\"];\n"), "{}", graph);
+    }
+}
@@ -0,0 +1,46 @@
+//! Dumps the lowered hir body of the function at the cursor, for debugging
+//! rust-analyzer itself.
+
+use hir::FromSource;
+use ra_db::SourceDatabase;
+use ra_syntax::{algo::find_node_at_offset, ast, AstNode};
+
+use crate::{db::RootDatabase, FilePosition};
+
+pub(crate) fn view_hir(db: &RootDatabase, position: FilePosition) -> String {
+    body_hir(db, position).unwrap_or_else(|| "Not inside a function body".to_string())
+}
+
+fn body_hir(db: &RootDatabase, position: FilePosition) -> Option<String> {
+    let parse = db.parse(position.file_id);
+    let fn_def = find_node_at_offset::<ast::FnDef>(parse.tree().syntax(), position.offset)?;
+    let src = hir::InFile { file_id: position.file_id.into(), value: fn_def };
+    let function = hir::Function::from_source(db, src)?;
+    Some(function.debug_hir(db))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mock_analysis::single_file_with_position;
+
+    #[test]
+    fn dumps_function_body() {
+        let (analysis, position) = single_file_with_position(
+            "
+            fn foo(x: u32) -> u32 {
+                let y = x;<|>
+                y
+            }
+            ",
+        );
+        let hir = analysis.view_hir(position).unwrap();
+        assert!(hir.starts_with("params: [PatId(0)]\nbody: ExprId("), "{}", hir);
+        assert!(hir.contains("\npats:\n    PatId(0): Bind {"), "{}", hir);
+    }
+
+    #[test]
+    fn outside_of_function() {
+        let (analysis, position) = single_file_with_position("struct S;<|>");
+        assert_eq!(analysis.view_hir(position).unwrap(), "Not inside a function body");
+    }
+}
@@ -28,7 +28,8 @@ pub(crate) fn runnable_args(
                 spec.push_to(&mut res);
             }
             res.push("--".to_string());
-            res.push(path.to_string());
+            // the trailing `::` keeps `tests` from also matching `tests_foo`
+            res.push(format!("{}::", path));
             res.push("--nocapture".to_string());
         }
         RunnableKind::Bench { name } => {
@@ -40,6 +41,16 @@ pub(crate) fn runnable_args(
             res.push(name.to_string());
             res.push("--nocapture".to_string());
         }
+        RunnableKind::DocTest { path } => {
+            res.push("test".to_string());
+            // doc-tests are only built for the library of the package
+            if let Some(spec) = spec {
+                res.push("--package".to_string());
+                res.push(spec.package);
+            }
+            res.push("--doc".to_string());
+            res.push(path.to_string());
+        }
         RunnableKind::Bin => {
             res.push("run".to_string());
             if let Some(spec) = spec {
@@ -58,24 +69,27 @@ pub struct CargoTargetSpec {
 
 impl CargoTargetSpec {
     pub fn for_file(world: &WorldSnapshot, file_id: FileId) -> Result<Option<CargoTargetSpec>> {
-        let &crate_id = match world.analysis().crate_for(file_id)?.first() {
-            Some(crate_id) => crate_id,
-            None => return Ok(None),
-        };
-        let file_id = world.analysis().crate_root(crate_id)?;
-        let path = world.vfs.read().file2path(ra_vfs::VfsFile(file_id.0));
-        let res = world.workspaces.iter().find_map(|ws| match ws {
-            ProjectWorkspace::Cargo { cargo, .. } => {
-                let tgt = cargo.target_by_root(&path)?;
-                Some(CargoTargetSpec {
-                    package: tgt.package(&cargo).name(&cargo).to_string(),
-                    target: tgt.name(&cargo).to_string(),
-                    target_kind: tgt.kind(&cargo),
-                })
+        // the file may be part of several crates, use the first one which is
+        // a cargo target
+        for crate_id in world.analysis().crate_for(file_id)? {
+            let file_id = world.analysis().crate_root(crate_id)?;
+            let path = world.vfs.read().file2path(ra_vfs::VfsFile(file_id.0));
+            let res = world.workspaces.iter().find_map(|ws| match ws {
+                ProjectWorkspace::Cargo { cargo, .. } => {
+                    let tgt = cargo.target_by_root(&path)?;
+                    Some(CargoTargetSpec {
+                        package: tgt.package(&cargo).name(&cargo).to_string(),
+                        target: tgt.name(&cargo).to_string(),
+                        target_kind: tgt.kind(&cargo),
+                    })
+                }
+                ProjectWorkspace::Json { .. } => None,
+            });
+            if res.is_some() {
+                return Ok(res);
             }
-            ProjectWorkspace::Json { .. } => None,
-        });
-        Ok(res)
+        }
+        Ok(None)
     }
 
     pub fn push_to(self, buf: &mut Vec<String>) {
@@ -41,6 +41,18 @@ pub struct ServerConfig {
 
     /// Cargo feature configurations.
     pub cargo_features: CargoFeatures,
+
+    /// Whether to run `cargo check` on save and report its diagnostics.
+    ///
+    /// Defaults to `false`
+    #[serde(deserialize_with = "nullable_bool_false")]
+    pub cargo_watch_enable: bool,
+    /// The cargo subcommand to run, like `check` or `clippy`.
+    pub cargo_watch_command: String,
+    /// Extra arguments passed to the cargo subcommand.
+    pub cargo_watch_args: Vec<String>,
+    #[serde(deserialize_with = "nullable_bool_true")]
+    pub cargo_watch_all_targets: bool,
 }
 
 impl Default for ServerConfig {
@@ -54,6 +66,10 @@ impl Default for ServerConfig {
             with_sysroot: true,
             feature_flags: FxHashMap::default(),
             cargo_features: Default::default(),
+            cargo_watch_enable: false,
+            cargo_watch_command: "check".to_string(),
+            cargo_watch_args: Vec::new(),
+            cargo_watch_all_targets: true,
         }
     }
 }
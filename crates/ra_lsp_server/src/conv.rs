@@ -7,8 +7,8 @@ use lsp_types::{
     TextDocumentPositionParams, Url, VersionedTextDocumentIdentifier, WorkspaceEdit,
 };
 use ra_ide::{
-    translate_offset_with_edit, CompletionItem, CompletionItemKind, FileId, FilePosition,
-    FileRange, FileSystemEdit, Fold, FoldKind, InsertTextFormat, LineCol, LineIndex,
+    translate_offset_with_edit, CompletionItem, CompletionItemKind, CompletionScore, FileId,
+    FilePosition, FileRange, FileSystemEdit, Fold, FoldKind, InsertTextFormat, LineCol, LineIndex,
     NavigationTarget, RangeInfo, Severity, SourceChange, SourceFileEdit,
 };
 use ra_syntax::{SyntaxKind, TextRange, TextUnit};
@@ -135,6 +135,20 @@ impl ConvWith<(&LineIndex, LineEndings)> for CompletionItem {
             res.tags = Some(vec![lsp_types::CompletionItemTag::Deprecated])
         }
 
+        // items without a sort text are sorted by their label, so all the
+        // items get one with a prefix which moves the scored items to the top,
+        // and the ones starting with an underscore, which are private by
+        // convention, to the bottom
+        let rank = match self.score() {
+            Some(CompletionScore::ContextMatch) => {
+                res.preselect = Some(true);
+                0
+            }
+            None if self.lookup().starts_with('_') => 2,
+            None => 1,
+        };
+        res.sort_text = Some(format!("{}{}", rank, self.label()));
+
         res.insert_text_format = Some(match self.insert_text_format() {
             InsertTextFormat::Snippet => lsp_types::InsertTextFormat::Snippet,
             InsertTextFormat::PlainText => lsp_types::InsertTextFormat::PlainText,
@@ -386,13 +400,16 @@ impl TryConvWith<&WorldSnapshot> for SourceChange {
                 })
             }
         };
+        // All the changes go into a single workspace edit, so that the client
+        // applies them as one step. The text edits come first, as they refer
+        // to files by their path before any of them is moved.
         let mut document_changes: Vec<DocumentChangeOperation> = Vec::new();
-        for resource_op in self.file_system_edits.try_conv_with(world)? {
-            document_changes.push(DocumentChangeOperation::Op(resource_op));
-        }
         for text_document_edit in self.source_file_edits.try_conv_with(world)? {
             document_changes.push(DocumentChangeOperation::Edit(text_document_edit));
         }
+        for resource_op in self.file_system_edits.try_conv_with(world)? {
+            document_changes.push(DocumentChangeOperation::Op(resource_op));
+        }
         let workspace_edit = WorkspaceEdit {
             changes: None,
             document_changes: Some(DocumentChanges::Operations(document_changes)),
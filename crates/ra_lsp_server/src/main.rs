@@ -1,7 +1,7 @@
 //! `ra_lsp_server` binary
 
 use lsp_server::Connection;
-use ra_lsp_server::{show_message, Result, ServerConfig};
+use ra_lsp_server::{req, show_message, Result, ServerConfig};
 use ra_prof;
 
 fn main() -> Result<()> {
@@ -45,6 +45,11 @@ fn run_server() -> Result<()> {
     let server_capabilities = serde_json::to_value(ra_lsp_server::server_capabilities()).unwrap();
 
     let initialize_params = connection.initialize(server_capabilities)?;
+    // not yet part of `lsp_types::ClientCapabilities`
+    let file_operation_caps: req::FileOperationClientCapabilities = initialize_params
+        .pointer("/capabilities/workspace/fileOperations")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default();
     let initialize_params: lsp_types::InitializeParams = serde_json::from_value(initialize_params)?;
 
     let cwd = std::env::current_dir()?;
@@ -77,6 +82,7 @@ fn run_server() -> Result<()> {
     ra_lsp_server::main_loop(
         workspace_roots,
         initialize_params.capabilities,
+        file_operation_caps,
         server_config,
         connection,
     )?;
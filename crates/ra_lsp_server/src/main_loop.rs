@@ -10,6 +10,7 @@ use std::{error::Error, fmt, panic, path::PathBuf, sync::Arc, time::Instant};
 use crossbeam_channel::{select, unbounded, RecvError, Sender};
 use lsp_server::{Connection, ErrorCode, Message, Notification, Request, RequestId, Response};
 use lsp_types::{ClientCapabilities, NumberOrString};
+use ra_cargo_watch::{CheckOptions, CheckTask};
 use ra_ide::{Canceled, FeatureFlags, FileId, LibraryData, SourceRootId};
 use ra_prof::profile;
 use ra_vfs::{VfsTask, Watch};
@@ -54,6 +55,7 @@ impl Error for LspError {}
 pub fn main_loop(
     ws_roots: Vec<PathBuf>,
     client_caps: ClientCapabilities,
+    file_operation_caps: req::FileOperationClientCapabilities,
     config: ServerConfig,
     connection: Connection,
 ) -> Result<()> {
@@ -113,6 +115,40 @@ pub fn main_loop(
             connection.sender.send(request.into()).unwrap();
         }
 
+        let will_rename = file_operation_caps.will_rename.unwrap_or(false)
+            && file_operation_caps.dynamic_registration.unwrap_or(false);
+        if will_rename {
+            // `mod` declarations are updated when a module file (or the
+            // directory of a `mod.rs`) is renamed
+            let registration_options = req::FileOperationRegistrationOptions {
+                filters: vec![
+                    req::FileOperationFilter {
+                        scheme: Some("file".to_string()),
+                        pattern: req::FileOperationPattern {
+                            glob: "**/*.rs".to_string(),
+                            matches: Some("file".to_string()),
+                        },
+                    },
+                    req::FileOperationFilter {
+                        scheme: Some("file".to_string()),
+                        pattern: req::FileOperationPattern {
+                            glob: "**".to_string(),
+                            matches: Some("folder".to_string()),
+                        },
+                    },
+                ],
+            };
+            let registration = req::Registration {
+                id: "will-rename-files".to_string(),
+                method: "workspace/willRenameFiles".to_string(),
+                register_options: Some(serde_json::to_value(registration_options).unwrap()),
+            };
+            let params = req::RegistrationParams { registrations: vec![registration] };
+            let request =
+                request_new::<req::RegisterCapability>(loop_state.next_request_id(), params);
+            connection.sender.send(request.into()).unwrap();
+        }
+
         let options = {
             let text_document_caps = client_caps.text_document.as_ref();
             Options {
@@ -126,6 +162,12 @@ pub fn main_loop(
                     .and_then(|it| it.line_folding_only)
                     .unwrap_or(false),
                 max_inlay_hint_length: config.max_inlay_hint_length,
+                cargo_watch: CheckOptions {
+                    enable: config.cargo_watch_enable,
+                    command: config.cargo_watch_command,
+                    args: config.cargo_watch_args,
+                    all_targets: config.cargo_watch_all_targets,
+                },
             }
         };
 
@@ -176,7 +218,11 @@ pub fn main_loop(
                     Ok(task) => Event::Vfs(task),
                     Err(RecvError) => Err("vfs died")?,
                 },
-                recv(libdata_receiver) -> data => Event::Lib(data.unwrap())
+                recv(libdata_receiver) -> data => Event::Lib(data.unwrap()),
+                recv(world_state.check_watcher.task_recv) -> task => match task {
+                    Ok(task) => Event::CheckWatcher(task),
+                    Err(RecvError) => Err("check watcher died")?,
+                }
             };
             if let Event::Msg(Message::Request(req)) = &event {
                 if connection.handle_shutdown(&req)? {
@@ -222,6 +268,7 @@ enum Event {
     Task(Task),
     Vfs(VfsTask),
     Lib(LibraryData),
+    CheckWatcher(CheckTask),
 }
 
 impl fmt::Debug for Event {
@@ -259,6 +306,7 @@ impl fmt::Debug for Event {
             Event::Task(it) => fmt::Debug::fmt(it, f),
             Event::Vfs(it) => fmt::Debug::fmt(it, f),
             Event::Lib(it) => fmt::Debug::fmt(it, f),
+            Event::CheckWatcher(it) => fmt::Debug::fmt(it, f),
         }
     }
 }
@@ -320,6 +368,7 @@ fn loop_turn(
             world_state.maybe_collect_garbage();
             loop_state.in_flight_libraries -= 1;
         }
+        Event::CheckWatcher(task) => on_check_task(pool, task, world_state, task_sender)?,
         Event::Msg(msg) => match msg {
             Message::Request(req) => on_request(
                 world_state,
@@ -409,6 +458,33 @@ fn on_task(
     }
 }
 
+fn on_check_task(
+    pool: &ThreadPool,
+    task: CheckTask,
+    world_state: &WorldState,
+    task_sender: &Sender<Task>,
+) -> Result<()> {
+    match task {
+        CheckTask::Update(uri) => {
+            // The diagnostics of files outside of the workspace, like the ones
+            // of dependencies, can't be published
+            let world = world_state.snapshot();
+            let file_id = match world.uri_to_file_id(&uri) {
+                Ok(it) => it,
+                Err(_) => return Ok(()),
+            };
+            update_file_notifications_on_threadpool(
+                pool,
+                world,
+                false,
+                task_sender.clone(),
+                vec![file_id],
+            );
+        }
+    }
+    Ok(())
+}
+
 fn on_request(
     world: &mut WorldState,
     pending_requests: &mut PendingRequests,
@@ -439,6 +515,8 @@ fn on_request(
         })?
         .on::<req::AnalyzerStatus>(handlers::handle_analyzer_status)?
         .on::<req::SyntaxTree>(handlers::handle_syntax_tree)?
+        .on::<req::ViewHir>(handlers::handle_view_hir)?
+        .on::<req::ViewCrateGraph>(handlers::handle_view_crate_graph)?
         .on::<req::ExpandMacro>(handlers::handle_expand_macro)?
         .on::<req::OnTypeFormatting>(handlers::handle_on_type_formatting)?
         .on::<req::DocumentSymbolRequest>(handlers::handle_document_symbol)?
@@ -450,6 +528,7 @@ fn on_request(
         .on::<req::Runnables>(handlers::handle_runnables)?
         .on::<req::DecorationsRequest>(handlers::handle_decorations)?
         .on::<req::Completion>(handlers::handle_completion)?
+        .on::<req::ResolveCompletionItem>(handlers::handle_completion_resolve)?
         .on::<req::CodeActionRequest>(handlers::handle_code_action)?
         .on::<req::CodeLensRequest>(handlers::handle_code_lens)?
         .on::<req::CodeLensResolve>(handlers::handle_code_lens_resolve)?
@@ -459,6 +538,7 @@ fn on_request(
         .on::<req::PrepareRenameRequest>(handlers::handle_prepare_rename)?
         .on::<req::Rename>(handlers::handle_rename)?
         .on::<req::References>(handlers::handle_references)?
+        .on::<req::WillRenameFiles>(handlers::handle_will_rename_files)?
         .on::<req::Formatting>(handlers::handle_formatting)?
         .on::<req::DocumentHighlightRequest>(handlers::handle_document_highlight)?
         .on::<req::InlayHints>(handlers::handle_inlay_hints)?
@@ -530,6 +610,13 @@ fn on_notification(
         }
         Err(not) => not,
     };
+    let not = match notification_cast::<req::DidSaveTextDocument>(not) {
+        Ok(_params) => {
+            state.check_watcher.update();
+            return Ok(());
+        }
+        Err(not) => not,
+    };
     let not = match notification_cast::<req::DidChangeConfiguration>(not) {
         Ok(_params) => {
             return Ok(());
@@ -697,11 +784,10 @@ fn update_file_notifications_on_threadpool(
         for file_id in subscriptions {
             if publish_diagnostics {
                 match handlers::publish_diagnostics(&world, file_id) {
-                    Err(e) => {
-                        if !is_canceled(&e) {
-                            log::error!("failed to compute diagnostics: {:?}", e);
-                        }
-                    }
+                    // the world has changed, the notifications for the
+                    // remaining files will be computed again
+                    Err(e) if is_canceled(&e) => return,
+                    Err(e) => log::error!("failed to compute diagnostics: {:?}", e),
                     Ok(params) => {
                         let not = notification_new::<req::PublishDiagnostics>(params);
                         sender.send(Task::Notify(not)).unwrap();
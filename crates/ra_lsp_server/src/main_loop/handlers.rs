@@ -1,7 +1,7 @@
 //! This module is responsible for implementing handlers for Lanuage Server Protocol.
 //! The majority of requests are fulfilled by calling into the `ra_ide` crate.
 
-use std::{fmt::Write as _, io::Write as _};
+use std::{collections::HashMap, fmt::Write as _, io::Write as _};
 
 use lsp_server::ErrorCode;
 use lsp_types::{
@@ -14,7 +14,7 @@ use ra_ide::{
     AssistId, FileId, FilePosition, FileRange, Query, Runnable, RunnableKind, SearchScope,
 };
 use ra_prof::profile;
-use ra_syntax::{AstNode, SyntaxKind, TextRange, TextUnit};
+use ra_syntax::{AstNode, SmolStr, SyntaxKind, TextRange, TextUnit};
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 use serde_json::to_value;
@@ -48,6 +48,25 @@ pub fn handle_syntax_tree(world: WorldSnapshot, params: req::SyntaxTreeParams) -
     Ok(res)
 }
 
+pub fn handle_view_hir(
+    world: WorldSnapshot,
+    params: req::TextDocumentPositionParams,
+) -> Result<String> {
+    let _p = profile("handle_view_hir");
+    let position = params.try_conv_with(&world)?;
+    let res = world.analysis().view_hir(position)?;
+    Ok(res)
+}
+
+pub fn handle_view_crate_graph(
+    world: WorldSnapshot,
+    params: req::ViewCrateGraphParams,
+) -> Result<String> {
+    let _p = profile("handle_view_crate_graph");
+    let res = world.analysis().view_crate_graph(params.dot)?;
+    Ok(res)
+}
+
 pub fn handle_expand_macro(
     world: WorldSnapshot,
     params: req::ExpandMacroParams,
@@ -408,12 +427,58 @@ pub fn handle_completion(
     };
     let line_index = world.analysis().file_line_index(position.file_id)?;
     let line_endings = world.file_line_endings(position.file_id);
-    let items: Vec<CompletionItem> =
-        items.into_iter().map(|item| item.conv_with((&line_index, line_endings))).collect();
+    let text_document_position = &params.text_document_position;
+    let items: Vec<CompletionItem> = items
+        .into_iter()
+        .map(|item| {
+            let import_to_add = item
+                .import_to_add()
+                .map(|path| path.iter().map(|it| it.to_string()).collect::<Vec<_>>());
+            let mut res = item.conv_with((&line_index, line_endings));
+            // the `use` of auto-imported items is computed when they are resolved
+            if let Some(import_to_add) = import_to_add {
+                let data = CompletionResolveData {
+                    position: text_document_position.clone(),
+                    import_to_add,
+                };
+                res.data = Some(to_value(data).unwrap());
+            }
+            res
+        })
+        .collect();
 
     Ok(Some(items.into()))
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompletionResolveData {
+    position: req::TextDocumentPositionParams,
+    import_to_add: Vec<String>,
+}
+
+pub fn handle_completion_resolve(
+    world: WorldSnapshot,
+    mut item: CompletionItem,
+) -> Result<CompletionItem> {
+    let _p = profile("handle_completion_resolve");
+    let data: CompletionResolveData = match item.data.take() {
+        Some(data) => serde_json::from_value(data)?,
+        None => return Ok(item),
+    };
+    let position = data.position.try_conv_with(&world)?;
+    let import_to_add: Vec<SmolStr> = data.import_to_add.iter().map(SmolStr::new).collect();
+    let edit = match world.analysis().fixup_completion(position, &import_to_add)? {
+        Some(it) => it,
+        None => return Ok(item),
+    };
+    let line_index = world.analysis().file_line_index(position.file_id)?;
+    let line_endings = world.file_line_endings(position.file_id);
+    let edits = edit.as_atoms().iter().map(|atom| atom.conv_with((&line_index, line_endings)));
+    item.additional_text_edits.get_or_insert_with(Vec::new).extend(edits);
+    Ok(item)
+}
+
 pub fn handle_folding_range(
     world: WorldSnapshot,
     params: FoldingRangeParams,
@@ -516,6 +581,46 @@ pub fn handle_rename(world: WorldSnapshot, params: RenameParams) -> Result<Optio
     Ok(Some(source_change_req.workspace_edit))
 }
 
+pub fn handle_will_rename_files(
+    world: WorldSnapshot,
+    params: req::RenameFilesParams,
+) -> Result<Option<WorkspaceEdit>> {
+    let _p = profile("handle_will_rename_files");
+    let renames = params
+        .files
+        .into_iter()
+        .filter_map(|it| {
+            let old_path = it.old_uri.to_file_path().ok()?;
+            let new_path = it.new_uri.to_file_path().ok()?;
+            // only renames in place can be expressed by renaming the module
+            if old_path.parent() != new_path.parent() {
+                return None;
+            }
+            let (file_path, new_name) = if old_path.extension() == Some("rs".as_ref()) {
+                // the name of a `mod.rs` module is the name of its directory
+                if old_path.file_stem() == Some("mod".as_ref())
+                    || new_path.extension() != Some("rs".as_ref())
+                {
+                    return None;
+                }
+                (old_path, new_path.file_stem()?)
+            } else {
+                (old_path.join("mod.rs"), new_path.file_name()?)
+            };
+            let new_name = new_name.to_str()?.to_string();
+            let file_id = world.vfs.read().path2file(&file_path)?;
+            Some((FileId(file_id.0), new_name))
+        })
+        .collect::<Vec<_>>();
+
+    let change = match world.analysis().will_rename_files(&renames)? {
+        None => return Ok(None),
+        Some(it) => it,
+    };
+    let source_change_req = change.try_conv_with(&world)?;
+    Ok(Some(source_change_req.workspace_edit))
+}
+
 pub fn handle_references(
     world: WorldSnapshot,
     params: req::ReferenceParams,
@@ -654,6 +759,26 @@ pub fn handle_code_action(
         res.push(action.into());
     }
 
+    for fix in world.check_watcher.read().fixes_for(&params.text_document.uri) {
+        let fix_range = fix.location.range.conv_with(&line_index);
+        if fix_range.intersection(&range).is_none() {
+            continue;
+        }
+
+        let edit = TextEdit::new(fix.location.range, fix.replacement.clone());
+        let mut changes = HashMap::new();
+        changes.insert(fix.location.uri.clone(), vec![edit]);
+        let action = CodeAction {
+            title: fix.title.clone(),
+            kind: Some("quickfix".to_string()),
+            diagnostics: Some(fix.diagnostics.clone()),
+            edit: Some(WorkspaceEdit::new(changes)),
+            command: None,
+            is_preferred: None,
+        };
+        res.push(action.into());
+    }
+
     for assist in assists {
         let title = assist.change.label.clone();
         let edit = assist.change.try_conv_with(&world)?;
@@ -695,6 +820,7 @@ pub fn handle_code_lens(
         let title = match &runnable.kind {
             RunnableKind::Test { .. } | RunnableKind::TestMod { .. } => "▶️Run Test",
             RunnableKind::Bench { .. } => "Run Bench",
+            RunnableKind::DocTest { .. } => "▶️Run Doctest",
             RunnableKind::Bin => "Run",
         }
         .to_string();
@@ -820,7 +946,7 @@ pub fn publish_diagnostics(
     let _p = profile("publish_diagnostics");
     let uri = world.file_id_to_uri(file_id)?;
     let line_index = world.analysis().file_line_index(file_id)?;
-    let diagnostics = world
+    let mut diagnostics: Vec<Diagnostic> = world
         .analysis()
         .diagnostics(file_id)?
         .into_iter()
@@ -834,6 +960,7 @@ pub fn publish_diagnostics(
             tags: None,
         })
         .collect();
+    diagnostics.extend(world.check_watcher.read().diagnostics_for(&uri).iter().cloned());
     Ok(req::PublishDiagnosticsParams { uri, diagnostics, version: None })
 }
 
@@ -857,6 +984,7 @@ fn to_lsp_runnable(
         RunnableKind::Test { name } => format!("test {}", name),
         RunnableKind::TestMod { path } => format!("test-mod {}", path),
         RunnableKind::Bench { name } => format!("bench {}", name),
+        RunnableKind::DocTest { path } => format!("doctest {}", path),
         RunnableKind::Bin => "run binary".to_string(),
     };
     Ok(req::Runnable {
@@ -46,6 +46,30 @@ pub struct SyntaxTreeParams {
     pub range: Option<Range>,
 }
 
+pub enum ViewHir {}
+
+impl Request for ViewHir {
+    type Params = TextDocumentPositionParams;
+    type Result = String;
+    const METHOD: &'static str = "rust-analyzer/viewHir";
+}
+
+pub enum ViewCrateGraph {}
+
+impl Request for ViewCrateGraph {
+    type Params = ViewCrateGraphParams;
+    type Result = String;
+    const METHOD: &'static str = "rust-analyzer/viewCrateGraph";
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewCrateGraphParams {
+    /// Whether to use the dot format of graphviz rather than plain text.
+    #[serde(default)]
+    pub dot: bool,
+}
+
 #[derive(Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ExpandedMacro {
@@ -178,6 +202,58 @@ pub struct SourceChange {
     pub cursor_position: Option<TextDocumentPositionParams>,
 }
 
+pub enum WillRenameFiles {}
+
+impl Request for WillRenameFiles {
+    type Params = RenameFilesParams;
+    type Result = Option<WorkspaceEdit>;
+    const METHOD: &'static str = "workspace/willRenameFiles";
+}
+
+// FIXME: the file operation types below are not yet part of `lsp_types`
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameFilesParams {
+    pub files: Vec<FileRename>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRename {
+    pub old_uri: Url,
+    pub new_uri: Url,
+}
+
+/// The `workspace.fileOperations` client capabilities.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FileOperationClientCapabilities {
+    pub dynamic_registration: Option<bool>,
+    pub will_rename: Option<bool>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FileOperationRegistrationOptions {
+    pub filters: Vec<FileOperationFilter>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FileOperationFilter {
+    pub scheme: Option<String>,
+    pub pattern: FileOperationPattern,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FileOperationPattern {
+    pub glob: String,
+    /// Either `"file"` or `"folder"`, or both when missing.
+    pub matches: Option<String>,
+}
+
 pub enum InlayHints {}
 
 impl Request for InlayHints {
@@ -116,6 +116,7 @@ impl Server {
                         window: None,
                         experimental: None,
                     },
+                    Default::default(),
                     ServerConfig { with_sysroot, ..ServerConfig::default() },
                     connection,
                 )
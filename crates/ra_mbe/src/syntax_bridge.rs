@@ -101,8 +101,16 @@ impl TokenMap {
     }
 
     pub fn range_by_token(&self, token_id: tt::TokenId) -> Option<TokenTextRange> {
-        let &(_, range) = self.entries.iter().find(|(tid, _)| *tid == token_id)?;
-        Some(range)
+        self.ranges_by_token(token_id).next()
+    }
+
+    /// All the ranges of a token, which appears several times in the output of
+    /// a macro that repeats its argument.
+    pub fn ranges_by_token(
+        &self,
+        token_id: tt::TokenId,
+    ) -> impl Iterator<Item = TokenTextRange> + '_ {
+        self.entries.iter().filter(move |(tid, _)| *tid == token_id).map(|&(_, range)| range)
     }
 
     fn insert(&mut self, token_id: tt::TokenId, relative_range: TextRange) {
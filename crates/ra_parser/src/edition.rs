@@ -0,0 +1,76 @@
+//! Edition-dependent handling of keywords.
+//!
+//! The lexer always produces 2018 edition keywords. When parsing 2015 edition
+//! code, `EditionTokenSource` demotes the keywords which were reserved in 2018
+//! back to plain identifiers, so that e.g. `let async = 92;` parses.
+
+use crate::{
+    SyntaxKind::{self, IDENT, LIFETIME},
+    Token, TokenSource, T,
+};
+
+/// The edition of the Rust language the source is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Edition {
+    Edition2015,
+    Edition2018,
+}
+
+impl Default for Edition {
+    fn default() -> Edition {
+        Edition::Edition2018
+    }
+}
+
+pub(crate) struct EditionTokenSource<'t> {
+    inner: &'t mut dyn TokenSource,
+    edition: Edition,
+}
+
+impl<'t> EditionTokenSource<'t> {
+    pub(crate) fn new(inner: &'t mut dyn TokenSource, edition: Edition) -> EditionTokenSource<'t> {
+        EditionTokenSource { inner, edition }
+    }
+
+    fn remap(&self, n: usize, mut token: Token) -> Token {
+        if self.edition == Edition::Edition2015 && self.is_2015_ident(n, token.kind) {
+            token.kind = IDENT;
+        }
+        token
+    }
+
+    fn is_2015_ident(&self, n: usize, kind: SyntaxKind) -> bool {
+        match kind {
+            T![async] | T![await] | T![try] => true,
+            // In 2015, `dyn` is a contextual keyword: it starts a trait object
+            // type only if followed by something which can start a bound.
+            T![dyn] => {
+                let next = self.inner.lookahead_nth(n + 1);
+                match next.kind {
+                    IDENT | LIFETIME | T![?] | T!['('] | T![for] => false,
+                    T![self] | T![super] | T![crate] => false,
+                    _ => true,
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<'t> TokenSource for EditionTokenSource<'t> {
+    fn current(&self) -> Token {
+        self.remap(0, self.inner.current())
+    }
+
+    fn lookahead_nth(&self, n: usize) -> Token {
+        self.remap(n, self.inner.lookahead_nth(n))
+    }
+
+    fn bump(&mut self) {
+        self.inner.bump()
+    }
+
+    fn is_keyword(&self, kw: &str) -> bool {
+        self.inner.is_keyword(kw)
+    }
+}
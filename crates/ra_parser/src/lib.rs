@@ -19,9 +19,11 @@ mod syntax_kind;
 mod event;
 mod parser;
 mod grammar;
+mod edition;
 
 pub(crate) use token_set::TokenSet;
 
+pub use crate::edition::Edition;
 pub use syntax_kind::SyntaxKind;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -80,7 +82,18 @@ where
 
 /// Parse given tokens into the given sink as a rust file.
 pub fn parse(token_source: &mut dyn TokenSource, tree_sink: &mut dyn TreeSink) {
-    parse_from_tokens(token_source, tree_sink, grammar::root);
+    parse_with_edition(token_source, tree_sink, Edition::Edition2018)
+}
+
+/// Parse given tokens into the given sink as a rust file, treating
+/// edition-specific keywords according to `edition`.
+pub fn parse_with_edition(
+    token_source: &mut dyn TokenSource,
+    tree_sink: &mut dyn TreeSink,
+    edition: Edition,
+) {
+    let mut token_source = edition::EditionTokenSource::new(token_source, edition);
+    parse_from_tokens(&mut token_source, tree_sink, grammar::root);
 }
 
 #[derive(Clone, Copy)]
@@ -139,9 +152,15 @@ impl Reparser {
     ///
     /// Tokens must start with `{`, end with `}` and form a valid brace
     /// sequence.
-    pub fn parse(self, token_source: &mut dyn TokenSource, tree_sink: &mut dyn TreeSink) {
+    pub fn parse(
+        self,
+        token_source: &mut dyn TokenSource,
+        tree_sink: &mut dyn TreeSink,
+        edition: Edition,
+    ) {
         let Reparser(r) = self;
-        let mut p = parser::Parser::new(token_source);
+        let mut token_source = edition::EditionTokenSource::new(token_source, edition);
+        let mut p = parser::Parser::new(&mut token_source);
         r(&mut p);
         let events = p.finish();
         event::process(tree_sink, events);
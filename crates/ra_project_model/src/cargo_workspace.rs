@@ -61,6 +61,7 @@ struct PackageData {
     dependencies: Vec<PackageDependency>,
     edition: Edition,
     features: Vec<String>,
+    declared_features: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -119,6 +120,9 @@ impl Package {
     pub fn features(self, ws: &CargoWorkspace) -> &[String] {
         &ws.packages[self].features
     }
+    pub fn declared_features(self, ws: &CargoWorkspace) -> &[String] {
+        &ws.packages[self].declared_features
+    }
     pub fn targets<'a>(self, ws: &'a CargoWorkspace) -> impl Iterator<Item = Target> + 'a {
         ws.packages[self].targets.iter().cloned()
     }
@@ -172,6 +176,12 @@ impl CargoWorkspace {
             meta.current_dir(parent);
         }
         let meta = meta.exec().map_err(|e| format!("cargo metadata failed: {}", e))?;
+        CargoWorkspace::from_metadata(meta)
+    }
+
+    /// Builds the workspace from the output of `cargo metadata`, with all the
+    /// packages of the resolve graph.
+    pub(crate) fn from_metadata(meta: cargo_metadata::Metadata) -> Result<CargoWorkspace> {
         let mut pkg_by_id = FxHashMap::default();
         let mut packages = Arena::default();
         let mut targets = Arena::default();
@@ -182,6 +192,8 @@ impl CargoWorkspace {
             let cargo_metadata::Package { id, edition, name, manifest_path, .. } = meta_pkg;
             let is_member = ws_members.contains(&id);
             let edition = edition.parse::<Edition>()?;
+            let mut declared_features: Vec<String> = meta_pkg.features.keys().cloned().collect();
+            declared_features.sort();
             let pkg = packages.alloc(PackageData {
                 name,
                 manifest: manifest_path,
@@ -190,6 +202,7 @@ impl CargoWorkspace {
                 edition,
                 dependencies: Vec::new(),
                 features: Vec::new(),
+                declared_features,
             });
             let pkg_data = &mut packages[pkg];
             pkg_by_id.insert(id, pkg);
@@ -121,6 +121,11 @@ impl ProjectWorkspace {
         }
     }
 
+    /// Builds the crate graph of the workspace. For Cargo workspaces, this
+    /// has a crate for each target of every package in the resolve graph,
+    /// members and dependencies alike, and dependency edges from all targets
+    /// of a package to the lib targets of the packages it depends on, so path
+    /// dependencies between members are resolved like any other dependency.
     pub fn to_crate_graph(
         &self,
         default_cfg_options: &CfgOptions,
@@ -238,6 +243,10 @@ impl ProjectWorkspace {
                                 cfg_options,
                                 Env::default(),
                             );
+                            crate_graph.set_declared_features(
+                                crate_id,
+                                pkg.declared_features(&cargo).iter().map(Into::into).collect(),
+                            );
                             names.insert(crate_id, pkg.name(&cargo).to_string());
                             if tgt.kind(&cargo) == TargetKind::Lib {
                                 lib_tgt = Some(crate_id);
@@ -401,3 +410,131 @@ pub fn get_rustc_cfg_options() -> CfgOptions {
 
     cfg_options
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `cargo metadata` of a workspace with the members `a` and `b`, where
+    /// `a` has a path dependency on `b`.
+    const METADATA: &str = r#"
+{
+    "packages": [
+        {
+            "name": "a",
+            "version": "0.1.0",
+            "id": "a 0.1.0 (path+file:///ws/a)",
+            "license": null,
+            "license_file": null,
+            "description": null,
+            "source": null,
+            "dependencies": [
+                {
+                    "name": "b",
+                    "source": null,
+                    "req": "*",
+                    "kind": null,
+                    "rename": null,
+                    "optional": false,
+                    "uses_default_features": true,
+                    "features": [],
+                    "target": null
+                }
+            ],
+            "targets": [
+                {
+                    "kind": ["lib"],
+                    "crate_types": ["lib"],
+                    "name": "a",
+                    "src_path": "/ws/a/src/lib.rs",
+                    "edition": "2018"
+                }
+            ],
+            "features": {},
+            "manifest_path": "/ws/a/Cargo.toml",
+            "metadata": null,
+            "authors": [],
+            "categories": [],
+            "keywords": [],
+            "readme": null,
+            "repository": null,
+            "edition": "2018",
+            "links": null
+        },
+        {
+            "name": "b",
+            "version": "0.1.0",
+            "id": "b 0.1.0 (path+file:///ws/b)",
+            "license": null,
+            "license_file": null,
+            "description": null,
+            "source": null,
+            "dependencies": [],
+            "targets": [
+                {
+                    "kind": ["lib"],
+                    "crate_types": ["lib"],
+                    "name": "b",
+                    "src_path": "/ws/b/src/lib.rs",
+                    "edition": "2018"
+                }
+            ],
+            "features": {},
+            "manifest_path": "/ws/b/Cargo.toml",
+            "metadata": null,
+            "authors": [],
+            "categories": [],
+            "keywords": [],
+            "readme": null,
+            "repository": null,
+            "edition": "2018",
+            "links": null
+        }
+    ],
+    "workspace_members": [
+        "a 0.1.0 (path+file:///ws/a)",
+        "b 0.1.0 (path+file:///ws/b)"
+    ],
+    "resolve": {
+        "nodes": [
+            {
+                "id": "a 0.1.0 (path+file:///ws/a)",
+                "dependencies": ["b 0.1.0 (path+file:///ws/b)"],
+                "deps": [{ "name": "b", "pkg": "b 0.1.0 (path+file:///ws/b)" }],
+                "features": []
+            },
+            {
+                "id": "b 0.1.0 (path+file:///ws/b)",
+                "dependencies": [],
+                "deps": [],
+                "features": []
+            }
+        ],
+        "root": null
+    },
+    "target_directory": "/ws/target",
+    "version": 1,
+    "workspace_root": "/ws"
+}
+"#;
+
+    #[test]
+    fn crate_graph_has_all_workspace_members() {
+        let meta: cargo_metadata::Metadata = serde_json::from_str(METADATA).unwrap();
+        let cargo = CargoWorkspace::from_metadata(meta).unwrap();
+        assert!(cargo.packages().all(|pkg| pkg.is_member(&cargo)));
+        let workspace = ProjectWorkspace::Cargo { cargo, sysroot: Sysroot::default() };
+
+        let roots = [Path::new("/ws/a/src/lib.rs"), Path::new("/ws/b/src/lib.rs")];
+        let mut load =
+            |path: &Path| roots.iter().position(|&it| it == path).map(|it| FileId(it as u32));
+        let (crate_graph, _names) = workspace.to_crate_graph(&CfgOptions::default(), &mut load);
+
+        let a = crate_graph.crate_id_for_crate_root(FileId(0)).unwrap();
+        let b = crate_graph.crate_id_for_crate_root(FileId(1)).unwrap();
+        let deps: Vec<_> =
+            crate_graph.dependencies(a).map(|dep| (dep.name.as_str(), dep.crate_id)).collect();
+        assert_eq!(deps, vec![("b", b)]);
+        assert_eq!(crate_graph.dependencies(b).count(), 0);
+    }
+}
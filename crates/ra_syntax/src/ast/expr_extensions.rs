@@ -333,6 +333,26 @@ impl ast::Literal {
             _ => unreachable!(),
         }
     }
+
+    /// The value of an integer literal, if it fits into a `u64`.
+    pub fn int_value(&self) -> Option<u64> {
+        let suffix = match self.kind() {
+            LiteralKind::IntNumber { suffix } => suffix,
+            _ => return None,
+        };
+        let text = self.token().text().replace('_', "");
+        let text = match &suffix {
+            Some(suffix) => &text[..text.len() - suffix.len()],
+            None => &text[..],
+        };
+        let (digits, radix) = match text.get(..2) {
+            Some("0x") => (&text[2..], 16),
+            Some("0o") => (&text[2..], 8),
+            Some("0b") => (&text[2..], 2),
+            _ => (text, 10),
+        };
+        u64::from_str_radix(digits, radix).ok()
+    }
 }
 
 impl ast::BlockExpr {
@@ -362,6 +382,21 @@ fn test_literal_with_attr() {
     assert_eq!(lit.token().text(), r#""Hello""#);
 }
 
+#[test]
+fn test_literal_int_value() {
+    fn int_value(text: &str) -> Option<u64> {
+        let parse = ast::SourceFile::parse(&format!("const _: u64 = {};", text));
+        let lit = parse.tree().syntax().descendants().find_map(ast::Literal::cast).unwrap();
+        lit.int_value()
+    }
+    assert_eq!(int_value("92"), Some(92));
+    assert_eq!(int_value("1_000usize"), Some(1000));
+    assert_eq!(int_value("0x1F"), Some(31));
+    assert_eq!(int_value("0b101_u8"), Some(5));
+    assert_eq!(int_value("1.5"), None);
+    assert_eq!(int_value("\"92\""), None);
+}
+
 impl ast::RecordField {
     pub fn parent_record_lit(&self) -> ast::RecordLit {
         self.syntax().ancestors().find_map(ast::RecordLit::cast).unwrap()
@@ -47,6 +47,12 @@ pub fn expr_unit() -> ast::Expr {
 pub fn expr_unimplemented() -> ast::Expr {
     expr_from_text("unimplemented!()")
 }
+pub fn expr_todo() -> ast::Expr {
+    expr_from_text("todo!()")
+}
+pub fn expr_default() -> ast::Expr {
+    expr_from_text("Default::default()")
+}
 pub fn expr_path(path: ast::Path) -> ast::Expr {
     expr_from_text(&path.syntax().to_string())
 }
@@ -62,6 +68,15 @@ pub fn expr_return() -> ast::Expr {
 pub fn expr_match(expr: ast::Expr, match_arm_list: ast::MatchArmList) -> ast::Expr {
     expr_from_text(&format!("match {} {}", expr.syntax(), match_arm_list.syntax()))
 }
+pub fn expr_not(expr: ast::Expr) -> ast::Expr {
+    expr_from_text(&format!("!{}", expr.syntax()))
+}
+pub fn expr_paren(expr: ast::Expr) -> ast::Expr {
+    expr_from_text(&format!("({})", expr.syntax()))
+}
+pub fn expr_method_call(receiver: ast::Expr, method: &str, arg_list: ast::ArgList) -> ast::Expr {
+    expr_from_text(&format!("{}.{}{}", receiver.syntax(), method, arg_list.syntax()))
+}
 fn expr_from_text(text: &str) -> ast::Expr {
     ast_from_text(&format!("const C: () = {};", text))
 }
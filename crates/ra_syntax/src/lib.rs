@@ -48,7 +48,7 @@ pub use crate::{
         Direction, NodeOrToken, SyntaxElement, SyntaxNode, SyntaxToken, SyntaxTreeBuilder,
     },
 };
-pub use ra_parser::{SyntaxKind, T};
+pub use ra_parser::{Edition, SyntaxKind, T};
 pub use rowan::{SmolStr, SyntaxText, TextRange, TextUnit, TokenAtOffset, WalkEvent};
 
 /// `Parse` is the result of the parsing: a syntax tree and a collection of
@@ -60,18 +60,25 @@ pub use rowan::{SmolStr, SyntaxText, TextRange, TextUnit, TokenAtOffset, WalkEve
 pub struct Parse<T> {
     green: GreenNode,
     errors: Arc<Vec<SyntaxError>>,
+    /// The edition the text was parsed with, reparsing keeps it.
+    edition: Edition,
     _ty: PhantomData<fn() -> T>,
 }
 
 impl<T> Clone for Parse<T> {
     fn clone(&self) -> Parse<T> {
-        Parse { green: self.green.clone(), errors: self.errors.clone(), _ty: PhantomData }
+        Parse {
+            green: self.green.clone(),
+            errors: self.errors.clone(),
+            edition: self.edition,
+            _ty: PhantomData,
+        }
     }
 }
 
 impl<T> Parse<T> {
     fn new(green: GreenNode, errors: Vec<SyntaxError>) -> Parse<T> {
-        Parse { green, errors: Arc::new(errors), _ty: PhantomData }
+        Parse { green, errors: Arc::new(errors), edition: Edition::default(), _ty: PhantomData }
     }
 
     pub fn syntax_node(&self) -> SyntaxNode {
@@ -81,7 +88,7 @@ impl<T> Parse<T> {
 
 impl<T: AstNode> Parse<T> {
     pub fn to_syntax(self) -> Parse<SyntaxNode> {
-        Parse { green: self.green, errors: self.errors, _ty: PhantomData }
+        Parse { green: self.green, errors: self.errors, edition: self.edition, _ty: PhantomData }
     }
 
     pub fn tree(&self) -> T {
@@ -104,7 +111,12 @@ impl<T: AstNode> Parse<T> {
 impl Parse<SyntaxNode> {
     pub fn cast<N: AstNode>(self) -> Option<Parse<N>> {
         if N::cast(self.syntax_node()).is_some() {
-            Some(Parse { green: self.green, errors: self.errors, _ty: PhantomData })
+            Some(Parse {
+                green: self.green,
+                errors: self.errors,
+                edition: self.edition,
+                _ty: PhantomData,
+            })
         } else {
             None
         }
@@ -126,18 +138,18 @@ impl Parse<SourceFile> {
 
     fn incremental_reparse(&self, edit: &AtomTextEdit) -> Option<Parse<SourceFile>> {
         // FIXME: validation errors are not handled here
-        parsing::incremental_reparse(self.tree().syntax(), edit, self.errors.to_vec()).map(
-            |(green_node, errors, _reparsed_range)| Parse {
+        parsing::incremental_reparse(self.tree().syntax(), edit, self.errors.to_vec(), self.edition)
+            .map(|(green_node, errors, _reparsed_range)| Parse {
                 green: green_node,
                 errors: Arc::new(errors),
+                edition: self.edition,
                 _ty: PhantomData,
-            },
-        )
+            })
     }
 
     fn full_reparse(&self, edit: &AtomTextEdit) -> Parse<SourceFile> {
         let text = edit.apply(self.tree().syntax().text().to_string());
-        SourceFile::parse(&text)
+        SourceFile::parse_with_edition(&text, self.edition)
     }
 }
 
@@ -146,7 +158,13 @@ pub use crate::ast::SourceFile;
 
 impl SourceFile {
     pub fn parse(text: &str) -> Parse<SourceFile> {
-        let (green, mut errors) = parsing::parse_text(text);
+        SourceFile::parse_with_edition(text, Edition::Edition2018)
+    }
+
+    /// Parses `text` as a file of the given edition. Keywords introduced in
+    /// later editions (like `async` or `dyn`) are treated as identifiers.
+    pub fn parse_with_edition(text: &str, edition: Edition) -> Parse<SourceFile> {
+        let (green, mut errors) = parsing::parse_text(text, edition);
         let root = SyntaxNode::new_root(green.clone());
 
         if cfg!(debug_assertions) {
@@ -156,7 +174,7 @@ impl SourceFile {
         errors.extend(validation::validate(&root));
 
         assert_eq!(root.kind(), SyntaxKind::SOURCE_FILE);
-        Parse { green, errors: Arc::new(errors), _ty: PhantomData }
+        Parse { green, errors: Arc::new(errors), edition, _ty: PhantomData }
     }
 }
 
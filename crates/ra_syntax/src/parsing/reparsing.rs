@@ -6,7 +6,7 @@
 //!   - otherwise, we search for the nearest `{}` block which contains the edit
 //!     and try to parse only this block.
 
-use ra_parser::Reparser;
+use ra_parser::{Edition, Reparser};
 use ra_text_edit::AtomTextEdit;
 
 use crate::{
@@ -26,12 +26,13 @@ pub(crate) fn incremental_reparse(
     node: &SyntaxNode,
     edit: &AtomTextEdit,
     errors: Vec<SyntaxError>,
+    edition: Edition,
 ) -> Option<(GreenNode, Vec<SyntaxError>, TextRange)> {
     if let Some((green, old_range)) = reparse_token(node, &edit) {
         return Some((green, merge_errors(errors, Vec::new(), old_range, edit), old_range));
     }
 
-    if let Some((green, new_errors, old_range)) = reparse_block(node, &edit) {
+    if let Some((green, new_errors, old_range)) = reparse_block(node, &edit, edition) {
         return Some((green, merge_errors(errors, new_errors, old_range, edit), old_range));
     }
     None
@@ -81,6 +82,7 @@ fn reparse_token<'node>(
 fn reparse_block<'node>(
     root: &'node SyntaxNode,
     edit: &AtomTextEdit,
+    edition: Edition,
 ) -> Option<(GreenNode, Vec<SyntaxError>, TextRange)> {
     let (node, reparser) = find_reparsable_node(root, edit.delete)?;
     let text = get_text_after_edit(node.clone().into(), &edit);
@@ -90,7 +92,7 @@ fn reparse_block<'node>(
     }
     let mut token_source = TextTokenSource::new(&text, &tokens);
     let mut tree_sink = TextTreeSink::new(&text, &tokens);
-    reparser.parse(&mut token_source, &mut tree_sink);
+    reparser.parse(&mut token_source, &mut tree_sink, edition);
     let (green, new_errors) = tree_sink.finish();
     Some((node.replace_with(green), new_errors, node.text_range()))
 }
@@ -176,16 +178,25 @@ mod tests {
     use crate::{AstNode, Parse, SourceFile};
 
     fn do_check(before: &str, replace_with: &str, reparsed_len: u32) {
+        do_check_with_edition(before, replace_with, reparsed_len, Edition::Edition2018)
+    }
+
+    fn do_check_with_edition(
+        before: &str,
+        replace_with: &str,
+        reparsed_len: u32,
+        edition: Edition,
+    ) {
         let (range, before) = extract_range(before);
         let edit = AtomTextEdit::replace(range, replace_with.to_owned());
         let after = edit.apply(before.clone());
 
-        let fully_reparsed = SourceFile::parse(&after);
+        let fully_reparsed = SourceFile::parse_with_edition(&after, edition);
         let incrementally_reparsed: Parse<SourceFile> = {
-            let f = SourceFile::parse(&before);
+            let f = SourceFile::parse_with_edition(&before, edition);
             let edit = AtomTextEdit { delete: range, insert: replace_with.to_string() };
             let (green, new_errors, range) =
-                incremental_reparse(f.tree().syntax(), &edit, f.errors.to_vec()).unwrap();
+                incremental_reparse(f.tree().syntax(), &edit, f.errors.to_vec(), edition).unwrap();
             assert_eq!(range.len(), reparsed_len.into(), "reparsed fragment has wrong length");
             Parse::new(green, new_errors)
         };
@@ -392,4 +403,21 @@ enum Foo {
             4,
         );
     }
+
+    #[test]
+    fn reparse_block_with_edition() {
+        let before = r"
+fn foo() {
+    let x = <|>1<|>;
+}
+";
+        do_check_with_edition(before, "async", 22, Edition::Edition2015);
+
+        let (range, before) = extract_range(before);
+        let edit = AtomTextEdit::replace(range, "async".to_owned());
+        let parse = SourceFile::parse_with_edition(&before, Edition::Edition2015).reparse(&edit);
+        assert!(parse.errors().is_empty());
+        let parse = SourceFile::parse_with_edition(&before, Edition::Edition2018).reparse(&edit);
+        assert!(!parse.errors().is_empty());
+    }
 }
@@ -5,7 +5,7 @@ use std::{
 
 use test_utils::{collect_tests, dir_tests, project_dir, read_text};
 
-use crate::{fuzz, SourceFile};
+use crate::{fuzz, Edition, SourceFile};
 
 #[test]
 fn lexer_tests() {
@@ -27,6 +27,22 @@ fn main() {
     assert!(parse.ok().is_ok());
 }
 
+#[test]
+fn parse_edition_2015_keywords() {
+    let code = r##"
+fn main() {
+    let async = 1;
+    let try = async;
+    let x: Box<dyn Trait> = dyn::new();
+}
+    "##;
+
+    let parse = SourceFile::parse_with_edition(code, Edition::Edition2015);
+    assert!(parse.ok().is_ok());
+    let parse = SourceFile::parse_with_edition(code, Edition::Edition2018);
+    assert!(parse.ok().is_err());
+}
+
 #[test]
 fn parser_tests() {
     dir_tests(&test_data_dir(), &["parser/inline/ok", "parser/ok"], |text, path| {